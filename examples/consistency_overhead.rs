@@ -0,0 +1,66 @@
+// cargo run --release --example consistency_overhead -- <num_nodes> <num_edges>
+// the cost of ConsistencyChecks::ALL in PrimalNetworkSimplex for a few intervals, against ConsistencyChecks::NONE
+use network_algorithms::minimum_cost_flow::consistency::ConsistencyChecks;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use std::time::{Duration, Instant};
+
+const NUM_RUNS: usize = 5;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn gen_index(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+// the fastest of NUM_RUNS
+fn time(mut solve: impl FnMut()) -> Duration {
+    (0..NUM_RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            solve();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let num_nodes = args.get(1).map_or(2_000, |arg| arg.parse().unwrap());
+    let num_edges = args.get(2).map_or(50_000, |arg| arg.parse().unwrap());
+    let mut rng = XorShift(88172645463325252);
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        graph.add_directed_edge(u, v, 0, rng.gen_index(100) as i64, rng.gen_index(100) as i64).unwrap();
+    }
+    // a path through all the nodes keeps the supplies feasible
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 1_000_000, 1_000).unwrap();
+    }
+    for _ in 0..num_nodes {
+        let (u, v, amount) = (rng.gen_index(num_nodes / 2), num_nodes / 2 + rng.gen_index(num_nodes / 2), rng.gen_index(100) as i64);
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+    }
+    println!("{num_nodes} nodes, {num_edges} edges");
+
+    let solve = |solver: &mut PrimalNetworkSimplex<i64>| solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph.clone());
+    let without_checks = time(|| _ = solve(&mut PrimalNetworkSimplex::default()));
+    println!("none: {without_checks:?}");
+    for interval in [10_000, 1_000, 100] {
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_consistency_checks(ConsistencyChecks::ALL, interval);
+        let with_checks = time(|| _ = solve(&mut solver));
+        println!("all every {interval} pivots: {with_checks:?} ({:+.2}%)", (with_checks.as_secs_f64() / without_checks.as_secs_f64() - 1.0) * 100.0);
+    }
+}
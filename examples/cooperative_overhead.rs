@@ -0,0 +1,84 @@
+// cargo run --release --example cooperative_overhead -- <num_nodes> <num_edges>
+// the cost of the work counter: solves without a hook against solves whose hook always continues
+use network_algorithms::cooperative::Control;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use std::time::{Duration, Instant};
+
+const GRANULARITY: u64 = 10_000;
+const NUM_RUNS: usize = 5;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn gen_index(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+// the fastest of NUM_RUNS
+fn time(mut solve: impl FnMut()) -> Duration {
+    (0..NUM_RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            solve();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, without_hook: Duration, with_hook: Duration) {
+    println!("{name}: {without_hook:?} without a hook, {with_hook:?} with a hook ({:+.2}%)", (with_hook.as_secs_f64() / without_hook.as_secs_f64() - 1.0) * 100.0);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let num_nodes = args.get(1).map_or(2_000, |arg| arg.parse().unwrap());
+    let num_edges = args.get(2).map_or(50_000, |arg| arg.parse().unwrap());
+    let mut rng = XorShift(88172645463325252);
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    let mut network = maximum_flow::graph::Graph::default();
+    network.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        let (upper, cost) = (rng.gen_index(100) as i64, rng.gen_index(100) as i64);
+        graph.add_directed_edge(u, v, 0, upper, cost).unwrap();
+        network.add_directed_edge(u, v, upper).unwrap();
+    }
+    // a path through all the nodes keeps the supplies feasible
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 1_000_000, 1_000).unwrap();
+    }
+    for _ in 0..num_nodes {
+        let (u, v, amount) = (rng.gen_index(num_nodes / 2), num_nodes / 2 + rng.gen_index(num_nodes / 2), rng.gen_index(100) as i64);
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+    }
+    println!("{num_nodes} nodes, {num_edges} edges, granularity {GRANULARITY}");
+
+    let solve_primal_network_simplex = |solver: &mut PrimalNetworkSimplex<i64>| solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph.clone());
+    let mut hooked = PrimalNetworkSimplex::default();
+    hooked.set_work_counter(GRANULARITY, Box::new(|_| Control::Continue));
+    let without_hook = time(|| _ = solve_primal_network_simplex(&mut PrimalNetworkSimplex::default()));
+    report("primal network simplex", without_hook, time(|| _ = solve_primal_network_simplex(&mut hooked)));
+
+    let mut hooked = SuccessiveShortestPath::default();
+    hooked.set_work_counter(GRANULARITY, Box::new(|_| Control::Continue));
+    let without_hook = time(|| _ = SuccessiveShortestPath::default().solve(&mut graph.clone()));
+    report("successive shortest path", without_hook, time(|| _ = hooked.solve(&mut graph.clone())));
+
+    let mut hooked = Dinic::default();
+    hooked.set_work_counter(GRANULARITY, Box::new(|_| Control::Continue));
+    let without_hook = time(|| _ = Dinic::default().solve(0, num_nodes - 1, &mut network.clone()));
+    report("dinic", without_hook, time(|| _ = hooked.solve(0, num_nodes - 1, &mut network.clone())));
+}
@@ -0,0 +1,47 @@
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::{ActiveNodeOrder, CostScalingPushRelabel};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::time::Instant;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn gen_index(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let num_nodes = args.get(1).map_or(2_000, |arg| arg.parse().unwrap());
+    let num_edges = args.get(2).map_or(20_000, |arg| arg.parse().unwrap());
+    let mut rng = XorShift(88172645463325252);
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes {
+        let b = rng.gen_index(101) as i64 - 50;
+        graph.add_supply(u, b);
+        graph.add_supply(num_nodes - 1 - u, -b);
+        graph.add_directed_edge(u, (u + 1) % num_nodes, 0, 1_000_000, 10_000).unwrap();
+    }
+    for _ in 0..num_edges {
+        let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        graph.add_directed_edge(from, to, 0, rng.gen_index(100) as i64 + 1, rng.gen_index(1_000) as i64).unwrap();
+    }
+
+    for order in [ActiveNodeOrder::Fifo, ActiveNodeOrder::Lifo, ActiveNodeOrder::HighestPotential] {
+        let mut graph = graph.clone();
+        let mut solver = CostScalingPushRelabel::default();
+        solver.set_active_node_order(order);
+        let start = Instant::now();
+        assert_eq!(solver.solve(&mut graph), Status::Optimal);
+        let elapsed = start.elapsed();
+        let stats = solver.stats();
+        println!("{order:?}: cost {}, {} pushes, {} relabels, {elapsed:?}", graph.minimum_cost(), stats.num_pushes, stats.num_relabels);
+    }
+}
@@ -0,0 +1,51 @@
+// cargo run --release --example mpm_dense -- <max_num_nodes>
+// the time of mpm and dinic on complete graphs of growing size, mpm divided by n^3 stays bounded
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::mpm::Mpm;
+use network_algorithms::maximum_flow::status::Status;
+use std::time::Instant;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn gen_index(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let max_num_nodes = args.get(1).map_or(800, |arg| arg.parse().unwrap());
+    let mut rng = XorShift(88172645463325252);
+
+    let mut num_nodes = 100;
+    while num_nodes <= max_num_nodes {
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for u in 0..num_nodes {
+            for v in 0..num_nodes {
+                if u != v {
+                    graph.add_directed_edge(u, v, rng.gen_index(1_000) as i64).unwrap();
+                }
+            }
+        }
+
+        let mut mpm_graph = graph.clone();
+        let start = Instant::now();
+        assert_eq!(Mpm::default().solve(0, num_nodes - 1, &mut mpm_graph), Status::Optimal);
+        let mpm_elapsed = start.elapsed();
+
+        let start = Instant::now();
+        assert_eq!(Dinic::default().solve(0, num_nodes - 1, &mut graph), Status::Optimal);
+        let dinic_elapsed = start.elapsed();
+
+        assert_eq!(mpm_graph.maximum_flow(0), graph.maximum_flow(0));
+        let per_cube = mpm_elapsed.as_secs_f64() * 1e9 / (num_nodes as f64).powi(3);
+        println!("n = {num_nodes}: mpm {mpm_elapsed:?} ({per_cube:.2} ns / n^3), dinic {dinic_elapsed:?}");
+        num_nodes *= 2;
+    }
+}
@@ -0,0 +1,58 @@
+// cargo run --release --features parallel --example parallel_pivot_scan -- <num_edges> <num_threads>
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BestEligibleArcPivotRule, BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::spanning_tree_structure::{InternalEdge, SpanningTreeStructure};
+use std::time::Instant;
+
+// times full scans on the spanning tree structure of the first pivot, and then stops the solver
+struct Bench {
+    num_threads: usize,
+}
+
+impl PivotRule<i64> for Bench {
+    fn new(_num_edges: usize) -> Self {
+        Self { num_threads: 1 }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<i64>, &SpanningTreeStructure<i64>) -> i64>(&mut self, st: &SpanningTreeStructure<i64>, _calculate_violation: F) -> Option<usize> {
+        // no eligible edge, so that every scan goes over all edges
+        let violation = |edge: &InternalEdge<i64>, _: &SpanningTreeStructure<i64>| -edge.cost;
+        let num_edges = st.num_edges();
+
+        let mut serial = BlockSearchPivotRule::new(num_edges);
+        let mut parallel = BlockSearchPivotRule::new(num_edges);
+        parallel.set_parallel_scan(self.num_threads, 1 << 16);
+        for (name, rule) in [("block search (serial)", &mut serial), ("block search (parallel)", &mut parallel)] {
+            let start = Instant::now();
+            assert_eq!(rule.find_entering_edge_parallel(st, violation), None);
+            println!("{name}: {:?}", start.elapsed());
+        }
+
+        let mut serial = <BestEligibleArcPivotRule<i64> as PivotRule<i64>>::new(num_edges);
+        let mut parallel = <BestEligibleArcPivotRule<i64> as PivotRule<i64>>::new(num_edges);
+        parallel.set_parallel_scan(self.num_threads, 1 << 16);
+        for (name, rule) in [("best eligible arc (serial)", &mut serial), ("best eligible arc (parallel)", &mut parallel)] {
+            let start = Instant::now();
+            assert_eq!(rule.find_entering_edge_parallel(st, violation), None);
+            println!("{name}: {:?}", start.elapsed());
+        }
+
+        None
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let num_edges = args.get(1).map_or(50_000_000, |arg| arg.parse().unwrap());
+    let num_threads = args.get(2).map_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()), |arg| arg.parse().unwrap());
+
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    for _ in 0..num_edges {
+        graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    }
+
+    println!("{num_edges} edges, {num_threads} threads");
+    PrimalNetworkSimplex::default().solve(&mut Bench { num_threads }, &mut graph);
+}
@@ -5,18 +5,18 @@ use network_algorithms::minimum_cost_flow::status::Status;
 
 fn main() {
     let mut graph = Graph::default();
-    graph.add_nodes(4);
+    let nodes = graph.add_nodes(4);
 
     let edges = vec![
-        graph.add_directed_edge(0, 1, 0, 2, 1).unwrap(),
-        graph.add_directed_edge(0, 2, 0, 1, 2).unwrap(),
-        graph.add_directed_edge(1, 2, 0, 1, 1).unwrap(),
-        graph.add_directed_edge(1, 3, 0, 1, 3).unwrap(),
-        graph.add_directed_edge(2, 3, 0, 2, 1).unwrap(),
+        graph.add_directed_edge(nodes[0], nodes[1], 0, 2, 1).unwrap(),
+        graph.add_directed_edge(nodes[0], nodes[2], 0, 1, 2).unwrap(),
+        graph.add_directed_edge(nodes[1], nodes[2], 0, 1, 1).unwrap(),
+        graph.add_directed_edge(nodes[1], nodes[3], 0, 1, 3).unwrap(),
+        graph.add_directed_edge(nodes[2], nodes[3], 0, 2, 1).unwrap(),
     ];
 
-    graph.add_supply(0, 2);
-    graph.add_supply(3, -2);
+    graph.add_supply(nodes[0], 2);
+    graph.add_supply(nodes[3], -2);
 
     let status = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(edges.len()), &mut graph);
 
@@ -0,0 +1,58 @@
+// cargo run --release --example scaled_violation_pivots -- <num_nodes> <num_edges>
+// the number of pivots and the time of the primal network simplex with the scaled violation rule against the best eligible arc rule
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BestEligibleArcPivotRule, PivotRule, ScaledViolationPivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::time::Instant;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn gen_index(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+fn run<Pivot: PivotRule<i64>>(name: &str, graph: &Graph<i64>) {
+    let mut graph = graph.clone();
+    let mut solver = PrimalNetworkSimplex::default();
+    let start = Instant::now();
+    let status = solver.solve(&mut Pivot::new(graph.num_edges()), &mut graph);
+    let elapsed = start.elapsed();
+    assert_eq!(status, Status::Optimal);
+    println!("{name}: {} pivots in {elapsed:?}, cost {}", solver.num_pivots(), graph.minimum_cost());
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let num_nodes = args.get(1).map_or(1_000, |arg| arg.parse().unwrap());
+    let num_edges = args.get(2).map_or(10_000, |arg| arg.parse().unwrap());
+    let mut rng = XorShift(88172645463325252);
+
+    // capacities from 1 to 10^5 on a log scale, so that the violation alone says little about the improvement
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        let magnitude = 10_usize.pow(rng.gen_index(6) as u32);
+        let upper = 1 + rng.gen_index(magnitude) as i64;
+        graph.add_directed_edge(u, v, 0, upper, rng.gen_index(100) as i64).unwrap();
+    }
+    for _ in 0..num_nodes / 10 {
+        let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_index(100_000) as i64);
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+    }
+    // a path through all the nodes keeps the supplies feasible
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 10_000_000, 10_000).unwrap();
+        graph.add_directed_edge(u + 1, u, 0, 10_000_000, 10_000).unwrap();
+    }
+
+    run::<BestEligibleArcPivotRule<i64>>("best eligible arc", &graph);
+    run::<ScaledViolationPivotRule<i64>>("scaled violation", &graph);
+}
@@ -0,0 +1,43 @@
+// cargo run --release --example union_find_scaling -- <max_num_elements>
+// the time per operation of random unions and finds for growing sizes, which stays almost constant
+use network_algorithms::util::union_find::{RollbackUnionFind, UnionFind};
+use std::time::Instant;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn gen_index(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let max_num_elements = args.get(1).map_or(10_000_000, |arg| arg.parse().unwrap());
+    let mut rng = XorShift(88172645463325252);
+
+    let mut n = 100_000;
+    while n <= max_num_elements {
+        // n unions and then n finds
+        let pairs: Vec<(usize, usize)> = (0..2 * n).map(|_| (rng.gen_index(n), rng.gen_index(n))).collect();
+
+        let mut union_find = UnionFind::new(n);
+        let start = Instant::now();
+        pairs[..n].iter().for_each(|&(u, v)| _ = union_find.union(u, v));
+        let num_same = pairs[n..].iter().filter(|&&(u, v)| union_find.same(u, v)).count();
+        let per_operation = start.elapsed().as_nanos() as f64 / (2 * n) as f64;
+
+        let mut rollback = RollbackUnionFind::new(n);
+        let start = Instant::now();
+        pairs[..n].iter().for_each(|&(u, v)| _ = rollback.union(u, v));
+        let num_same_rollback = pairs[n..].iter().filter(|&&(u, v)| rollback.same(u, v)).count();
+        let per_operation_rollback = start.elapsed().as_nanos() as f64 / (2 * n) as f64;
+        assert_eq!(num_same, num_same_rollback);
+
+        println!("{n} elements: {per_operation:.1} ns per operation, {per_operation_rollback:.1} ns with rollback");
+        n *= 10;
+    }
+}
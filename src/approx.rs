@@ -0,0 +1,140 @@
+use num_traits::{Float, Num, NumAssign, One, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+// the absolute tolerance the solvers apply to Approx, see FlowExt::tolerance
+pub const TOLERANCE: f64 = 1e-9;
+
+// a floating-point Flow for SuccessiveShortestPath and PrimalNetworkSimplex. Approx is ordered exactly as the floats are
+// (0.0 and -0.0 are equal, NaN is above everything), so that the order is total and sorting by it cannot panic.
+// the tolerance is applied by the solvers where a rounding error would decide a comparison: a reduced cost of -1e-12 is optimal,
+// a pivot that sends 1e-12 is degenerate and dijkstra sees no negative reduced cost of -1e-12.
+// so the network simplex does not cycle on violations that are only noise. the solvers that need an integer Flow
+// (CostScalingPushRelabel divides the costs) are not supported
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Approx<F>(pub F);
+
+impl<F: Float> Approx<F> {
+    #[inline]
+    pub fn value(self) -> F {
+        self.0
+    }
+}
+
+impl<F> From<F> for Approx<F> {
+    fn from(value: F) -> Self {
+        Approx(value)
+    }
+}
+
+impl<F: Float> PartialEq for Approx<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<F: Float> Eq for Approx<F> {}
+
+impl<F: Float> PartialOrd for Approx<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Float> Ord for Approx<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.0.is_nan(), other.0.is_nan()) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.0.partial_cmp(&other.0).unwrap(),
+        }
+    }
+}
+
+impl<F: Display> Display for Approx<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+macro_rules! impl_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl<F: Float> $trait for Approx<F> {
+            type Output = Approx<F>;
+            fn $method(self, rhs: Approx<F>) -> Approx<F> {
+                Approx(self.0.$method(rhs.0))
+            }
+        }
+
+        impl<F: Float + NumAssign> $assign_trait for Approx<F> {
+            fn $assign_method(&mut self, rhs: Approx<F>) {
+                self.0.$assign_method(rhs.0);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign);
+impl_op!(Sub, sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, MulAssign, mul_assign);
+impl_op!(Div, div, DivAssign, div_assign);
+impl_op!(Rem, rem, RemAssign, rem_assign);
+
+impl<F: Float> Neg for Approx<F> {
+    type Output = Approx<F>;
+    fn neg(self) -> Approx<F> {
+        Approx(-self.0)
+    }
+}
+
+impl<F: Float> Zero for Approx<F> {
+    fn zero() -> Self {
+        Approx(F::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+}
+
+impl<F: Float> One for Approx<F> {
+    fn one() -> Self {
+        Approx(F::one())
+    }
+}
+
+impl<F: Float> Num for Approx<F> {
+    type FromStrRadixErr = F::FromStrRadixErr;
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        F::from_str_radix(str, radix).map(Approx)
+    }
+}
+
+impl<F: Float> ToPrimitive for Approx<F> {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.0.to_f64()
+    }
+}
+
+// value, or zero if it is within tolerance of zero
+#[inline]
+pub(crate) fn snap<Flow>(value: Flow, tolerance: &Flow) -> Flow
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    if -tolerance.clone() <= value && value <= *tolerance {
+        Flow::zero()
+    } else {
+        value
+    }
+}
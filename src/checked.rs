@@ -0,0 +1,140 @@
+use crate::flow_ext::FlowExt;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedNeg, CheckedRem, CheckedSub, Num, NumAssign, One, Zero};
+use std::fmt::{self, Display};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+// an integer Flow whose arithmetic is checked. an overflow gives Checked::Overflow, which every later operation keeps,
+// and the minimum cost flow solvers return Status::Overflow when it reaches the flows, the potentials or the objective.
+// Overflow is greater than every value, so a distance that overflowed is never shorter than one that did not
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Clone, Copy)]
+pub enum Checked<T> {
+    Value(T),
+    Overflow,
+}
+
+impl<T> Checked<T> {
+    #[inline]
+    pub fn value(self) -> Option<T> {
+        match self {
+            Checked::Value(value) => Some(value),
+            Checked::Overflow => None,
+        }
+    }
+
+    #[inline]
+    pub fn is_overflow(&self) -> bool {
+        matches!(self, Checked::Overflow)
+    }
+}
+
+impl<T> From<T> for Checked<T> {
+    fn from(value: T) -> Self {
+        Checked::Value(value)
+    }
+}
+
+impl<T: Default> Default for Checked<T> {
+    fn default() -> Self {
+        Checked::Value(T::default())
+    }
+}
+
+impl<T: Display> Display for Checked<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Checked::Value(value) => value.fmt(f),
+            Checked::Overflow => write!(f, "overflow"),
+        }
+    }
+}
+
+macro_rules! impl_op {
+    ($trait:ident, $method:ident, $checked_trait:ident, $checked_method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl<T: $checked_trait> $trait for Checked<T> {
+            type Output = Checked<T>;
+            fn $method(self, rhs: Checked<T>) -> Checked<T> {
+                match (self, rhs) {
+                    (Checked::Value(lhs), Checked::Value(rhs)) => lhs.$checked_method(&rhs).map_or(Checked::Overflow, Checked::Value),
+                    _ => Checked::Overflow,
+                }
+            }
+        }
+
+        impl<T: $checked_trait + Copy> $assign_trait for Checked<T> {
+            fn $assign_method(&mut self, rhs: Checked<T>) {
+                *self = self.$method(rhs);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, CheckedAdd, checked_add, AddAssign, add_assign);
+impl_op!(Sub, sub, CheckedSub, checked_sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, CheckedMul, checked_mul, MulAssign, mul_assign);
+impl_op!(Div, div, CheckedDiv, checked_div, DivAssign, div_assign);
+impl_op!(Rem, rem, CheckedRem, checked_rem, RemAssign, rem_assign);
+
+impl<T: CheckedNeg> Neg for Checked<T> {
+    type Output = Checked<T>;
+    fn neg(self) -> Checked<T> {
+        match self {
+            Checked::Value(value) => value.checked_neg().map_or(Checked::Overflow, Checked::Value),
+            Checked::Overflow => Checked::Overflow,
+        }
+    }
+}
+
+// Some only when the product is a value, for the solvers that check a product themselves
+impl<T: CheckedMul> CheckedMul for Checked<T> {
+    fn checked_mul(&self, rhs: &Checked<T>) -> Option<Checked<T>> {
+        match (self, rhs) {
+            (Checked::Value(lhs), Checked::Value(rhs)) => lhs.checked_mul(rhs).map(Checked::Value),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Zero + CheckedAdd> Zero for Checked<T> {
+    fn zero() -> Self {
+        Checked::Value(T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, Checked::Value(value) if value.is_zero())
+    }
+}
+
+impl<T: One + CheckedMul + PartialEq> One for Checked<T> {
+    fn one() -> Self {
+        Checked::Value(T::one())
+    }
+}
+
+impl<T> Num for Checked<T>
+where
+    T: Num + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv + CheckedRem,
+{
+    type FromStrRadixErr = T::FromStrRadixErr;
+    // "overflow" is Checked::Overflow, as Display writes it
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        if str == "overflow" {
+            return Ok(Checked::Overflow);
+        }
+        T::from_str_radix(str, radix).map(Checked::Value)
+    }
+}
+
+// lhs + rhs for the cost sums of the solvers (the big-M, the distances and the cycle costs), Err(Status::Overflow) if it overflowed
+#[inline]
+pub(crate) fn add_costs<Flow>(lhs: Flow, rhs: Flow) -> Result<Flow, Status>
+where
+    Flow: NumAssign + FlowExt,
+{
+    let sum = lhs + rhs;
+    if sum.is_overflow() {
+        Err(Status::Overflow)
+    } else {
+        Ok(sum)
+    }
+}
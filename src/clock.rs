@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+// wasm32-unknown-unknown has no std::time::Instant, so time limits take a caller supplied clock
+pub trait Clock {
+    fn now_millis(&self) -> u64;
+
+    // for the timing of a solve, which is often shorter than a millisecond. a clock without a finer resolution keeps the default
+    fn now_nanos(&self) -> u64 {
+        self.now_millis().saturating_mul(1_000_000)
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub struct StdClock {
+    start: std::time::Instant,
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self { start: std::time::Instant::now() }
+    }
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+impl Clock for StdClock {
+    fn now_millis(&self) -> u64 {
+        self.start.elapsed().as_millis() as u64
+    }
+
+    fn now_nanos(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+}
+
+// a StdClock for the crate's own timing, None where there is no StdClock
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn std_clock() -> Option<Arc<dyn Clock + Send + Sync>> {
+    Some(Arc::new(StdClock::default()))
+}
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn std_clock() -> Option<Arc<dyn Clock + Send + Sync>> {
+    None
+}
+
+pub struct TimeLimit {
+    clock: Box<dyn Clock>,
+    limit_millis: u64,
+    deadline: u64,
+}
+
+impl TimeLimit {
+    pub fn new(limit_millis: u64, clock: Box<dyn Clock>) -> Self {
+        Self { clock, limit_millis, deadline: u64::MAX }
+    }
+
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    pub fn with_std_clock(limit_millis: u64) -> Self {
+        Self::new(limit_millis, Box::new(StdClock::default()))
+    }
+
+    // call at the beginning of each solve
+    pub(crate) fn start(&mut self) {
+        self.deadline = self.clock.now_millis().saturating_add(self.limit_millis);
+    }
+
+    #[inline]
+    pub(crate) fn is_over(&self) -> bool {
+        self.clock.now_millis() >= self.deadline
+    }
+}
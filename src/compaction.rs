@@ -0,0 +1,67 @@
+// the ids before and after Graph::compact, for the nodes and the edges
+#[derive(PartialEq, Debug, Clone)]
+pub struct CompactionMap {
+    new_node_ids: Vec<Option<usize>>, // indexed by the old id, None if dropped
+    new_edge_ids: Vec<Option<usize>>,
+    old_node_ids: Vec<Option<usize>>, // indexed by the new id, None if added after the first of composed compactions
+    old_edge_ids: Vec<Option<usize>>,
+}
+
+fn renumber(is_kept: &[bool]) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let old_ids: Vec<Option<usize>> = (0..is_kept.len()).filter(|&id| is_kept[id]).map(Some).collect();
+    let mut new_ids = vec![None; is_kept.len()];
+    for (new_id, &old_id) in old_ids.iter().enumerate() {
+        new_ids[old_id.unwrap()] = Some(new_id);
+    }
+    (new_ids, old_ids)
+}
+
+// the ids of first followed by second, where second may have ids added after first
+fn compose(first: (&[Option<usize>], &[Option<usize>]), second: (&[Option<usize>], &[Option<usize>])) -> (Vec<Option<usize>>, Vec<Option<usize>>) {
+    let ((first_new_ids, first_old_ids), (second_new_ids, second_old_ids)) = (first, second);
+    assert!(second_new_ids.len() >= first_old_ids.len());
+    let new_ids = first_new_ids.iter().map(|id| id.and_then(|id| second_new_ids[id])).collect();
+    let old_ids = second_old_ids.iter().map(|id| id.and_then(|id| first_old_ids.get(id).copied().flatten())).collect();
+    (new_ids, old_ids)
+}
+
+impl CompactionMap {
+    // the kept ids keep their order
+    pub(crate) fn new(is_node_kept: &[bool], is_edge_kept: &[bool]) -> Self {
+        let (new_node_ids, old_node_ids) = renumber(is_node_kept);
+        let (new_edge_ids, old_edge_ids) = renumber(is_edge_kept);
+        Self { new_node_ids, new_edge_ids, old_node_ids, old_edge_ids }
+    }
+
+    // None if u was dropped
+    pub fn translate_node(&self, u: usize) -> Option<usize> {
+        self.new_node_ids[u]
+    }
+
+    pub fn translate_edge(&self, edge_id: usize) -> Option<usize> {
+        self.new_edge_ids[edge_id]
+    }
+
+    // None only for a node added between composed compactions
+    pub fn original_node(&self, u: usize) -> Option<usize> {
+        self.old_node_ids[u]
+    }
+
+    pub fn original_edge(&self, edge_id: usize) -> Option<usize> {
+        self.old_edge_ids[edge_id]
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.new_node_ids.len() == self.old_node_ids.len()
+            && self.new_edge_ids.len() == self.old_edge_ids.len()
+            && self.old_node_ids.iter().all(|u| u.is_some())
+            && self.old_edge_ids.iter().all(|edge_id| edge_id.is_some())
+    }
+
+    // the map of self followed by next, a compaction of the graph compacted by self (with nodes and edges possibly added in between)
+    pub fn then(&self, next: &CompactionMap) -> CompactionMap {
+        let (new_node_ids, old_node_ids) = compose((&self.new_node_ids, &self.old_node_ids), (&next.new_node_ids, &next.old_node_ids));
+        let (new_edge_ids, old_edge_ids) = compose((&self.new_edge_ids, &self.old_edge_ids), (&next.new_edge_ids, &next.old_edge_ids));
+        CompactionMap { new_node_ids, new_edge_ids, old_node_ids, old_edge_ids }
+    }
+}
@@ -0,0 +1,125 @@
+use std::cell::{Cell, RefCell};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// cooperative yielding for executors without threads: the solvers count their units of work
+// (edges scanned by the pivot rules, cycle nodes walked, heap pops, arcs examined) and call the hook every granularity units.
+// Control::Pause makes a session return Status::Paused at the next point it can resume from
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Control {
+    Continue,
+    Pause,
+}
+
+// the argument is the number of units of work since the solve started
+pub type YieldHook = Box<dyn FnMut(u64) -> Control>;
+
+// interior mutability, so that the violation closures of the pivot rules can tick it
+pub struct WorkCounter {
+    count: Cell<u64>,
+    next_check: Cell<u64>, // u64::MAX without a hook, so that tick is a single comparison
+    granularity: u64,
+    hook: RefCell<Option<YieldHook>>,
+    is_paused: Cell<bool>,
+}
+
+impl Default for WorkCounter {
+    fn default() -> Self {
+        Self { count: Cell::new(0), next_check: Cell::new(u64::MAX), granularity: u64::MAX, hook: RefCell::new(None), is_paused: Cell::new(false) }
+    }
+}
+
+impl WorkCounter {
+    pub fn new(granularity: u64, hook: YieldHook) -> Self {
+        assert!(granularity > 0);
+        Self { count: Cell::new(0), next_check: Cell::new(granularity), granularity, hook: RefCell::new(Some(hook)), is_paused: Cell::new(false) }
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count.get()
+    }
+
+    pub(crate) fn is_configured(&self) -> bool {
+        self.hook.borrow().is_some()
+    }
+
+    // call at the beginning of each solve
+    pub(crate) fn start(&self) {
+        self.count.set(0);
+        self.next_check.set(if self.is_configured() { self.granularity } else { u64::MAX });
+        self.is_paused.set(false);
+    }
+
+    // return true if the solver has to stop before the units of work it is about to do.
+    // the count before them is compared, so that at least granularity units are done after each resume
+    #[inline]
+    pub(crate) fn tick(&self, units: u64) -> bool {
+        let count = self.count.get();
+        self.count.set(count + units);
+        count >= self.next_check.get() && self.check(count)
+    }
+
+    #[inline]
+    pub(crate) fn is_paused(&self) -> bool {
+        self.is_paused.get()
+    }
+
+    pub(crate) fn resume(&self) {
+        if self.is_paused.replace(false) {
+            self.next_check.set(self.count.get().saturating_add(self.granularity));
+        }
+    }
+
+    // once paused, every tick returns true without calling the hook until resume
+    #[cold]
+    fn check(&self, count: u64) -> bool {
+        if !self.is_paused.get() {
+            self.next_check.set(count.saturating_add(self.granularity));
+            if let Some(hook) = self.hook.borrow_mut().as_mut() {
+                self.is_paused.set(hook(count) == Control::Pause);
+            }
+        }
+        self.is_paused.get()
+    }
+}
+
+// cancellation from another thread, e.g. a request handler whose client disconnected.
+// the solvers that take it look at it between their units of work and return Status::Cancelled
+#[derive(Default, Clone, Debug)]
+pub struct CancellationToken {
+    is_cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.is_cancelled.load(Ordering::Relaxed)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(is_cancelled: Arc<AtomicBool>) -> Self {
+        Self { is_cancelled }
+    }
+}
+
+// reported to the callback of solve_with_progress, where ControlFlow::Break stops the solve as a cancellation does
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Progress<Flow> {
+    CostScaling { epsilon: Flow, num_phases: usize },                            // after each refine, epsilon is for the costs multiplied by alpha * n
+    SuccessiveShortestPath { remaining_excess: Flow, num_augmentations: usize }, // after each augmentation
+    NetworkSimplex { num_pivots: usize },                                        // every primal_network_simplex::PROGRESS_INTERVAL pivots
+}
+
+pub(crate) fn ignore_progress<Flow>(_: Progress<Flow>) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
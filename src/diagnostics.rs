@@ -0,0 +1,485 @@
+use crate::clock::{Clock, StdClock, TimeLimit};
+use crate::minimum_cost_flow::consistency::ConsistencyChecks;
+use crate::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use crate::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use crate::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::network_simplex_pivot_rules::*;
+use crate::minimum_cost_flow::out_of_kilter::OutOfKilter;
+use crate::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use crate::minimum_cost_flow::primal_dual::PrimalDual;
+use crate::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use crate::minimum_cost_flow::status::Status;
+use crate::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use num_traits::{NumAssign, ToPrimitive};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::ops::Neg;
+use std::path::Path;
+
+// a bundle is a directory of plain text files
+//   manifest.txt:    format version, crate version, solver and options (key = value)
+//   instance.dimacs: the instance in the DIMACS minimum cost flow format
+//   stats.txt:       status, objective, operation count and elapsed time (only written by run_and_bundle)
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+const MANIFEST_FILE: &str = "manifest.txt";
+const INSTANCE_FILE: &str = "instance.dimacs";
+const STATS_FILE: &str = "stats.txt";
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum PivotRuleKind {
+    BestEligibleArc,
+    FirstEligibleArc,
+    BlockSearch,
+    CandidateList,
+    AlteringCandidateList,
+    ApproximateSteepestEdge,
+    Bland,
+    ScaledViolation,
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SolverKind {
+    SuccessiveShortestPath,
+    PrimalDual,
+    OutOfKilter,
+    CycleCanceling,
+    CostScalingPushRelabel,
+    PrimalNetworkSimplex(PivotRuleKind),
+    DualNetworkSimplex(PivotRuleKind),
+    ParametricNetworkSimplex,
+}
+
+// options that do not apply to the solver are ignored
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct SolverOptions {
+    pub scaling_factor: Option<u32>,                            // CostScalingPushRelabel
+    pub time_limit_millis: Option<u64>,                         // PrimalNetworkSimplex
+    pub tree_repair: bool,                                      // SuccessiveShortestPath
+    pub consistency_checks: Option<(ConsistencyChecks, usize)>, // PrimalNetworkSimplex, with the interval in pivots
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Stats {
+    pub status: Status,
+    pub objective: i64,
+    pub num_operations: Option<usize>, // heap operations (SuccessiveShortestPath) or pivots (PrimalNetworkSimplex)
+    pub elapsed_millis: u64,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub crate_version: String,
+    pub solver: SolverKind,
+    pub options: SolverOptions,
+    pub num_nodes: usize,
+    pub num_edges: usize,
+    pub stats: Option<Stats>,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct Divergence {
+    pub field: &'static str,
+    pub recorded: String,
+    pub replayed: String,
+}
+
+#[derive(Debug)]
+pub struct ReplayReport {
+    pub manifest: BundleManifest,
+    pub replayed: Stats,
+    pub divergences: Vec<Divergence>,
+}
+
+impl ReplayReport {
+    pub fn is_clean(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+#[derive(Debug)]
+pub enum BundleError {
+    Io(io::Error),
+    Parse { file: &'static str, line: usize, message: String },
+    UnsupportedVersion(u32),
+}
+
+impl From<io::Error> for BundleError {
+    fn from(error: io::Error) -> Self {
+        BundleError::Io(error)
+    }
+}
+
+// write the instance and the solver configuration to output_dir
+pub fn repro_bundle(solver: SolverKind, options: &SolverOptions, graph: &Graph<i64>, output_dir: &Path) -> io::Result<BundleManifest> {
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        crate_version: env!("CARGO_PKG_VERSION").to_string(),
+        solver,
+        options: options.clone(),
+        num_nodes: graph.num_nodes(),
+        num_edges: graph.num_edges(),
+        stats: None,
+    };
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join(INSTANCE_FILE), write_dimacs(graph))?;
+    fs::write(output_dir.join(MANIFEST_FILE), write_manifest(&manifest))?;
+    Ok(manifest)
+}
+
+// solve graph and write the bundle together with the collected stats
+pub fn run_and_bundle(solver: SolverKind, options: &SolverOptions, graph: &mut Graph<i64>, output_dir: &Path) -> io::Result<(Status, BundleManifest)> {
+    let mut manifest = repro_bundle(solver, options, graph, output_dir)?;
+    let stats = run(solver, options, graph);
+    fs::write(output_dir.join(STATS_FILE), write_stats(&stats))?;
+
+    let status = stats.status;
+    manifest.stats = Some(stats);
+    Ok((status, manifest))
+}
+
+// solve graph, and write the bundle of the instance as given only if a consistency check of options fails
+pub fn run_with_repro_on_violation(solver: SolverKind, options: &SolverOptions, graph: &mut Graph<i64>, output_dir: &Path) -> io::Result<(Status, Option<BundleManifest>)> {
+    let instance = graph.clone();
+    let stats = run(solver, options, graph);
+    if stats.status != Status::InternalInvariant {
+        return Ok((stats.status, None));
+    }
+
+    let mut manifest = repro_bundle(solver, options, &instance, output_dir)?;
+    fs::write(output_dir.join(STATS_FILE), write_stats(&stats))?;
+    let status = stats.status;
+    manifest.stats = Some(stats);
+    Ok((status, Some(manifest)))
+}
+
+// re-run the bundle and compare status, objective and operation count with the recorded ones.
+// the elapsed time is not compared.
+pub fn replay_bundle(path: &Path) -> Result<ReplayReport, BundleError> {
+    let mut manifest = read_manifest(&fs::read_to_string(path.join(MANIFEST_FILE))?)?;
+    let mut graph = read_dimacs(&fs::read_to_string(path.join(INSTANCE_FILE))?)?;
+    if path.join(STATS_FILE).exists() {
+        manifest.stats = Some(read_stats(&fs::read_to_string(path.join(STATS_FILE))?)?);
+    }
+
+    let replayed = run(manifest.solver, &manifest.options, &mut graph);
+    let mut divergences = Vec::new();
+    if graph.num_nodes() != manifest.num_nodes || graph.num_edges() != manifest.num_edges {
+        divergences.push(Divergence { field: "instance", recorded: format!("{} {}", manifest.num_nodes, manifest.num_edges), replayed: format!("{} {}", graph.num_nodes(), graph.num_edges()) });
+    }
+    if let Some(recorded) = manifest.stats.as_ref() {
+        if recorded.status != replayed.status {
+            divergences.push(Divergence { field: "status", recorded: format!("{:?}", recorded.status), replayed: format!("{:?}", replayed.status) });
+        }
+        if recorded.objective != replayed.objective {
+            divergences.push(Divergence { field: "objective", recorded: recorded.objective.to_string(), replayed: replayed.objective.to_string() });
+        }
+        if recorded.num_operations != replayed.num_operations {
+            divergences.push(Divergence { field: "num_operations", recorded: format!("{:?}", recorded.num_operations), replayed: format!("{:?}", replayed.num_operations) });
+        }
+    }
+
+    Ok(ReplayReport { manifest, replayed, divergences })
+}
+
+fn run(solver: SolverKind, options: &SolverOptions, graph: &mut Graph<i64>) -> Stats {
+    let clock = StdClock::default();
+    let (status, num_operations) = match solver {
+        SolverKind::SuccessiveShortestPath => {
+            let mut solver = SuccessiveShortestPath::default();
+            solver.set_tree_repair(options.tree_repair);
+            (solver.solve(graph), Some(solver.num_heap_operations()))
+        }
+        SolverKind::PrimalDual => (PrimalDual::default().solve(graph), None),
+        SolverKind::OutOfKilter => (OutOfKilter::default().solve(graph), None),
+        SolverKind::CycleCanceling => (CycleCanceling::default().solve(graph), None),
+        SolverKind::CostScalingPushRelabel => {
+            let mut solver = match options.scaling_factor {
+                Some(scaling_factor) => CostScalingPushRelabel::new(scaling_factor),
+                None => CostScalingPushRelabel::default(),
+            };
+            (solver.solve(graph), None)
+        }
+        SolverKind::PrimalNetworkSimplex(pivot_rule) => {
+            let mut solver = PrimalNetworkSimplex::default();
+            if let Some(limit_millis) = options.time_limit_millis {
+                solver.set_time_limit(TimeLimit::with_std_clock(limit_millis));
+            }
+            if let Some((checks, interval)) = options.consistency_checks {
+                solver.set_consistency_checks(checks, interval);
+            }
+            let num_edges = graph.num_edges();
+            let status = solver.solve(&mut pivot_rule.build(num_edges), graph);
+            (status, Some(solver.num_pivots()))
+        }
+        SolverKind::DualNetworkSimplex(pivot_rule) => {
+            let mut solver = DualNetworkSimplex::default();
+            let num_edges = graph.num_edges();
+            let status = solver.solve(&mut pivot_rule.build(num_edges), graph);
+            (status, None)
+        }
+        SolverKind::ParametricNetworkSimplex => (ParametricNetworkSimplex::default().solve(graph), None),
+    };
+
+    let objective = if status == Status::Optimal { graph.minimum_cost() } else { 0 };
+    Stats { status, objective, num_operations, elapsed_millis: clock.now_millis() }
+}
+
+impl PivotRuleKind {
+    pub fn build<Flow>(self, num_edges: usize) -> DynPivotRule<Flow>
+    where
+        Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + ToPrimitive,
+    {
+        match self {
+            PivotRuleKind::BestEligibleArc => BestEligibleArcPivotRule::new(num_edges).into(),
+            PivotRuleKind::FirstEligibleArc => FirstEligibleArcPivotRule::new(num_edges).into(),
+            PivotRuleKind::BlockSearch => BlockSearchPivotRule::new(num_edges).into(),
+            PivotRuleKind::CandidateList => CandidateListPivotRule::new(num_edges).into(),
+            PivotRuleKind::AlteringCandidateList => AlteringCandidateListPivotRule::new(num_edges).into(),
+            PivotRuleKind::ApproximateSteepestEdge => ApproximateSteepestEdgePivotRule::new(num_edges).into(),
+            PivotRuleKind::Bland => BlandPivotRule::new(num_edges).into(),
+            PivotRuleKind::ScaledViolation => ScaledViolationPivotRule::new(num_edges).into(),
+        }
+    }
+}
+
+fn pivot_rule_name(pivot_rule: PivotRuleKind) -> &'static str {
+    match pivot_rule {
+        PivotRuleKind::BestEligibleArc => "best_eligible_arc",
+        PivotRuleKind::FirstEligibleArc => "first_eligible_arc",
+        PivotRuleKind::BlockSearch => "block_search",
+        PivotRuleKind::CandidateList => "candidate_list",
+        PivotRuleKind::AlteringCandidateList => "altering_candidate_list",
+        PivotRuleKind::ApproximateSteepestEdge => "approximate_steepest_edge",
+        PivotRuleKind::Bland => "bland",
+        PivotRuleKind::ScaledViolation => "scaled_violation",
+    }
+}
+
+fn parse_pivot_rule(name: &str) -> Option<PivotRuleKind> {
+    [
+        PivotRuleKind::BestEligibleArc,
+        PivotRuleKind::FirstEligibleArc,
+        PivotRuleKind::BlockSearch,
+        PivotRuleKind::CandidateList,
+        PivotRuleKind::AlteringCandidateList,
+        PivotRuleKind::ApproximateSteepestEdge,
+        PivotRuleKind::Bland,
+        PivotRuleKind::ScaledViolation,
+    ]
+    .into_iter()
+    .find(|&pivot_rule| pivot_rule_name(pivot_rule) == name)
+}
+
+fn solver_name(solver: SolverKind) -> &'static str {
+    match solver {
+        SolverKind::SuccessiveShortestPath => "successive_shortest_path",
+        SolverKind::PrimalDual => "primal_dual",
+        SolverKind::OutOfKilter => "out_of_kilter",
+        SolverKind::CycleCanceling => "cycle_canceling",
+        SolverKind::CostScalingPushRelabel => "cost_scaling_push_relabel",
+        SolverKind::PrimalNetworkSimplex(_) => "primal_network_simplex",
+        SolverKind::DualNetworkSimplex(_) => "dual_network_simplex",
+        SolverKind::ParametricNetworkSimplex => "parametric_network_simplex",
+    }
+}
+
+fn parse_status(name: &str) -> Option<Status> {
+    match name {
+        "NotSolved" => Some(Status::NotSolved),
+        "BadInput" => Some(Status::BadInput),
+        "Unbalanced" => Some(Status::Unbalanced),
+        "Infeasible" => Some(Status::Infeasible),
+        "Unbounded" => Some(Status::Unbounded),
+        "Optimal" => Some(Status::Optimal),
+        "TimeLimit" => Some(Status::TimeLimit),
+        "Paused" => Some(Status::Paused),
+        "Cancelled" => Some(Status::Cancelled),
+        "InternalInvariant" => Some(Status::InternalInvariant),
+        "Overflow" => Some(Status::Overflow),
+        _ => None,
+    }
+}
+
+fn write_manifest(manifest: &BundleManifest) -> String {
+    let mut text = String::new();
+    writeln!(text, "format_version = {}", manifest.format_version).unwrap();
+    writeln!(text, "crate_version = {}", manifest.crate_version).unwrap();
+    writeln!(text, "solver = {}", solver_name(manifest.solver)).unwrap();
+    if let SolverKind::PrimalNetworkSimplex(pivot_rule) | SolverKind::DualNetworkSimplex(pivot_rule) = manifest.solver {
+        writeln!(text, "pivot_rule = {}", pivot_rule_name(pivot_rule)).unwrap();
+    }
+    if let Some(scaling_factor) = manifest.options.scaling_factor {
+        writeln!(text, "scaling_factor = {scaling_factor}").unwrap();
+    }
+    if let Some(time_limit_millis) = manifest.options.time_limit_millis {
+        writeln!(text, "time_limit_millis = {time_limit_millis}").unwrap();
+    }
+    writeln!(text, "tree_repair = {}", manifest.options.tree_repair).unwrap();
+    if let Some((checks, interval)) = manifest.options.consistency_checks {
+        writeln!(text, "consistency_checks = {}", checks.bits()).unwrap();
+        writeln!(text, "consistency_check_interval = {interval}").unwrap();
+    }
+    writeln!(text, "num_nodes = {}", manifest.num_nodes).unwrap();
+    writeln!(text, "num_edges = {}", manifest.num_edges).unwrap();
+    text
+}
+
+fn write_stats(stats: &Stats) -> String {
+    let mut text = String::new();
+    writeln!(text, "status = {:?}", stats.status).unwrap();
+    writeln!(text, "objective = {}", stats.objective).unwrap();
+    if let Some(num_operations) = stats.num_operations {
+        writeln!(text, "num_operations = {num_operations}").unwrap();
+    }
+    writeln!(text, "elapsed_millis = {}", stats.elapsed_millis).unwrap();
+    text
+}
+
+// key = value per line, and '#' starts a comment
+fn read_key_values(text: &str, file: &'static str) -> Result<HashMap<String, (usize, String)>, BundleError> {
+    let mut values = HashMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or(BundleError::Parse { file, line: i + 1, message: format!("expected key = value: {line}") })?;
+        values.insert(key.trim().to_string(), (i + 1, value.trim().to_string()));
+    }
+    Ok(values)
+}
+
+fn get_value<T: std::str::FromStr>(values: &HashMap<String, (usize, String)>, file: &'static str, key: &str) -> Result<Option<T>, BundleError> {
+    match values.get(key) {
+        None => Ok(None),
+        Some((line, value)) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| BundleError::Parse { file, line: *line, message: format!("invalid {key}: {value}") }),
+    }
+}
+
+fn get_required_value<T: std::str::FromStr>(values: &HashMap<String, (usize, String)>, file: &'static str, key: &str) -> Result<T, BundleError> {
+    get_value(values, file, key)?.ok_or(BundleError::Parse { file, line: 0, message: format!("missing {key}") })
+}
+
+fn read_manifest(text: &str) -> Result<BundleManifest, BundleError> {
+    let values = read_key_values(text, MANIFEST_FILE)?;
+    let format_version: u32 = get_required_value(&values, MANIFEST_FILE, "format_version")?;
+    if format_version != BUNDLE_FORMAT_VERSION {
+        return Err(BundleError::UnsupportedVersion(format_version));
+    }
+
+    let invalid = |key: &str| {
+        let (line, value) = values[key].clone();
+        BundleError::Parse { file: MANIFEST_FILE, line, message: format!("invalid {key}: {value}") }
+    };
+    let solver_name: String = get_required_value(&values, MANIFEST_FILE, "solver")?;
+    let pivot_rule = match get_value::<String>(&values, MANIFEST_FILE, "pivot_rule")? {
+        Some(name) => Some(parse_pivot_rule(&name).ok_or_else(|| invalid("pivot_rule"))?),
+        None => None,
+    };
+    let solver = match (solver_name.as_str(), pivot_rule) {
+        ("successive_shortest_path", _) => SolverKind::SuccessiveShortestPath,
+        ("primal_dual", _) => SolverKind::PrimalDual,
+        ("out_of_kilter", _) => SolverKind::OutOfKilter,
+        ("cycle_canceling", _) => SolverKind::CycleCanceling,
+        ("cost_scaling_push_relabel", _) => SolverKind::CostScalingPushRelabel,
+        ("primal_network_simplex", Some(pivot_rule)) => SolverKind::PrimalNetworkSimplex(pivot_rule),
+        ("dual_network_simplex", Some(pivot_rule)) => SolverKind::DualNetworkSimplex(pivot_rule),
+        ("parametric_network_simplex", _) => SolverKind::ParametricNetworkSimplex,
+        _ => return Err(invalid("solver")),
+    };
+
+    Ok(BundleManifest {
+        format_version,
+        crate_version: get_required_value(&values, MANIFEST_FILE, "crate_version")?,
+        solver,
+        options: SolverOptions {
+            scaling_factor: get_value(&values, MANIFEST_FILE, "scaling_factor")?,
+            time_limit_millis: get_value(&values, MANIFEST_FILE, "time_limit_millis")?,
+            tree_repair: get_value(&values, MANIFEST_FILE, "tree_repair")?.unwrap_or(false),
+            consistency_checks: match get_value::<u32>(&values, MANIFEST_FILE, "consistency_checks")? {
+                Some(bits) => Some((ConsistencyChecks::from_bits_truncate(bits), get_required_value(&values, MANIFEST_FILE, "consistency_check_interval")?)),
+                None => None,
+            },
+        },
+        num_nodes: get_required_value(&values, MANIFEST_FILE, "num_nodes")?,
+        num_edges: get_required_value(&values, MANIFEST_FILE, "num_edges")?,
+        stats: None,
+    })
+}
+
+fn read_stats(text: &str) -> Result<Stats, BundleError> {
+    let values = read_key_values(text, STATS_FILE)?;
+    let status: String = get_required_value(&values, STATS_FILE, "status")?;
+    let status = parse_status(&status).ok_or_else(|| BundleError::Parse { file: STATS_FILE, line: values["status"].0, message: format!("invalid status: {status}") })?;
+    Ok(Stats {
+        status,
+        objective: get_required_value(&values, STATS_FILE, "objective")?,
+        num_operations: get_value(&values, STATS_FILE, "num_operations")?,
+        elapsed_millis: get_required_value(&values, STATS_FILE, "elapsed_millis")?,
+    })
+}
+
+// p min <nodes> <edges>
+// n <id> <supply>
+// a <from> <to> <lower> <upper> <cost>
+// (node ids are 1-indexed)
+fn write_dimacs(graph: &Graph<i64>) -> String {
+    let mut text = String::new();
+    writeln!(text, "p min {} {}", graph.num_nodes(), graph.num_edges()).unwrap();
+    for (u, &b) in graph.b.iter().enumerate() {
+        if b != 0 {
+            writeln!(text, "n {} {}", u + 1, b).unwrap();
+        }
+    }
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        writeln!(text, "a {} {} {} {} {}", edge.from + 1, edge.to + 1, edge.lower, edge.upper, edge.cost).unwrap();
+    }
+    text
+}
+
+fn read_dimacs(text: &str) -> Result<Graph<i64>, BundleError> {
+    let mut graph = Graph::default();
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: &str| BundleError::Parse { file: INSTANCE_FILE, line: i + 1, message: format!("{message}: {line}") };
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let numbers = |from: usize| {
+            tokens[from..]
+                .iter()
+                .map(|token| token.parse::<i64>())
+                .collect::<Result<Vec<i64>, _>>()
+                .map_err(|_| error("invalid number"))
+        };
+        match tokens.first() {
+            None | Some(&"c") => continue,
+            Some(&"p") if tokens.len() == 4 && tokens[1] == "min" => {
+                graph.add_nodes(numbers(2)?[0] as usize);
+            }
+            Some(&"n") if tokens.len() == 3 => {
+                let v = numbers(1)?;
+                if v[0] < 1 || v[0] as usize > graph.num_nodes() {
+                    return Err(error("node out of range"));
+                }
+                graph.add_supply(v[0] as usize - 1, v[1]);
+            }
+            Some(&"a") if tokens.len() == 6 => {
+                let v = numbers(1)?;
+                if v[0] < 1 || v[1] < 1 {
+                    return Err(error("node out of range"));
+                }
+                graph
+                    .add_directed_edge(v[0] as usize - 1, v[1] as usize - 1, v[2], v[3], v[4])
+                    .map_err(|graph_error| error(&graph_error.to_string()))?;
+            }
+            _ => return Err(error("unknown line")),
+        }
+    }
+    Ok(graph)
+}
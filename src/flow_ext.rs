@@ -0,0 +1,57 @@
+use crate::approx::{Approx, TOLERANCE};
+use crate::checked::Checked;
+use num_traits::{Float, Zero};
+
+// what the minimum cost flow solvers need to know about a Flow beyond its arithmetic.
+// the items default to a Flow whose values are exact and never overflow, so a custom Flow implements it with an empty impl
+pub trait FlowExt {
+    // whether a value can be an overflow (see checked::Checked), so that the solvers check the objective only for those:
+    // for the other types it is not computed during a solve, where an overflow would panic in debug builds
+    const CAN_OVERFLOW: bool = false;
+
+    #[inline]
+    fn is_overflow(&self) -> bool {
+        false
+    }
+
+    // the absolute tolerance of the solvers' comparisons of reduced costs, flows and excesses (see approx::snap).
+    // zero for an exact Flow
+    #[inline]
+    fn tolerance() -> Self
+    where
+        Self: Zero,
+    {
+        Self::zero()
+    }
+}
+
+macro_rules! impl_flow_ext {
+    ($($t:ty),*) => {
+        $(impl FlowExt for $t {})*
+    };
+}
+
+impl_flow_ext!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T> FlowExt for Checked<T> {
+    const CAN_OVERFLOW: bool = true;
+
+    #[inline]
+    fn is_overflow(&self) -> bool {
+        matches!(self, Checked::Overflow)
+    }
+}
+
+// a NaN or an infinity is not an overflow: Approx has no overflow to report
+impl<F: Float> FlowExt for Approx<F> {
+    #[inline]
+    fn tolerance() -> Self {
+        Approx(F::from(TOLERANCE).unwrap())
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl FlowExt for num_bigint::BigInt {}
+
+#[cfg(feature = "rational")]
+impl<T> FlowExt for num_rational::Ratio<T> {}
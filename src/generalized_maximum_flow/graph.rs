@@ -1,8 +1,11 @@
+use crate::graph_error::{check_node, check_nodes, GraphError};
+use crate::ids::{EdgeId, NodeId};
 use num_traits::Float;
 use num_traits::ToPrimitive;
 use std::fmt::Debug;
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge<Flow> {
     pub from: usize,
     pub to: usize,
@@ -12,6 +15,7 @@ pub struct Edge<Flow> {
 }
 
 #[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Graph<Flow> {
     num_nodes: usize,
     num_edges: usize,
@@ -34,43 +38,101 @@ where
         self.num_edges
     }
 
-    pub fn add_node(&mut self) -> usize {
-        self.b.push(Flow::zero());
-        self.excesses.push(Flow::zero());
-        self.num_nodes += 1;
-        self.num_nodes - 1
+    // b of u: positive for a supply and negative for a demand
+    #[inline]
+    pub fn supply(&self, u: impl Into<NodeId>) -> Flow {
+        self.b[u.into().index()]
     }
 
-    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<usize> {
-        self.b.extend(vec![Flow::zero(); num_nodes]);
-        self.excesses.extend(vec![Flow::zero(); num_nodes]);
-        self.num_nodes += num_nodes;
-        ((self.num_nodes - num_nodes)..self.num_nodes).collect()
+    #[inline]
+    pub fn supplies(&self) -> &[Flow] {
+        &self.b
+    }
+
+    pub fn add_node(&mut self) -> NodeId {
+        NodeId(self.add_node_raw())
+    }
+
+    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<NodeId> {
+        self.add_nodes_raw(num_nodes).into_iter().map(NodeId).collect()
     }
 
-    pub fn add_supply(&mut self, u: usize, supply: Flow) {
+    pub fn add_supply(&mut self, u: impl Into<NodeId>, supply: Flow) {
+        let u = u.into().index();
         self.b[u] = self.b[u] + supply;
         self.excesses[u] = self.excesses[u] + supply;
     }
 
-    pub fn add_demand(&mut self, u: usize, demand: Flow) {
+    pub fn add_demand(&mut self, u: impl Into<NodeId>, demand: Flow) {
+        let u = u.into().index();
         self.b[u] = self.b[u] - demand;
         self.excesses[u] = self.excesses[u] - demand;
     }
 
-    // return edge index
-    pub fn add_directed_edge(&mut self, from: usize, to: usize, upper: Flow, gain: Flow) -> Option<usize> {
-        if upper <= Flow::zero() || from >= self.num_nodes || to >= self.num_nodes || gain <= Flow::zero() {
-            return None;
+    // add_supply and add_demand, with GraphError::NodeOutOfRange instead of a panic
+    pub fn try_add_supply(&mut self, u: impl Into<NodeId>, supply: Flow) -> Result<(), GraphError<Flow>> {
+        let u = u.into().index();
+        check_node(u, self.num_nodes)?;
+        self.add_supply(u, supply);
+        Ok(())
+    }
+
+    pub fn try_add_demand(&mut self, u: impl Into<NodeId>, demand: Flow) -> Result<(), GraphError<Flow>> {
+        let u = u.into().index();
+        check_node(u, self.num_nodes)?;
+        self.add_demand(u, demand);
+        Ok(())
+    }
+
+    // sets b of the nodes (the last pair of a node wins), and nothing is changed if a node is out of range
+    pub fn set_supplies<N: Into<NodeId> + Copy>(&mut self, supplies: &[(N, Flow)]) -> Result<(), GraphError<Flow>> {
+        for &(u, _) in supplies.iter() {
+            check_node(u.into().index(), self.num_nodes)?;
+        }
+        for &(u, supply) in supplies.iter() {
+            let u = u.into().index();
+            self.add_supply(u, supply - self.b[u]);
+        }
+        Ok(())
+    }
+
+    // return edge id, or why the edge is rejected
+    pub fn add_directed_edge(&mut self, from: impl Into<NodeId>, to: impl Into<NodeId>, upper: Flow, gain: Flow) -> Result<EdgeId, GraphError<Flow>> {
+        self.add_directed_edge_raw(from.into().index(), to.into().index(), upper, gain).map(EdgeId)
+    }
+
+    // the usize versions of the above, kept for one release
+    pub fn add_node_raw(&mut self) -> usize {
+        self.b.push(Flow::zero());
+        self.excesses.push(Flow::zero());
+        self.num_nodes += 1;
+        self.num_nodes - 1
+    }
+
+    pub fn add_nodes_raw(&mut self, num_nodes: usize) -> Vec<usize> {
+        self.b.extend(vec![Flow::zero(); num_nodes]);
+        self.excesses.extend(vec![Flow::zero(); num_nodes]);
+        self.num_nodes += num_nodes;
+        ((self.num_nodes - num_nodes)..self.num_nodes).collect()
+    }
+
+    pub fn add_directed_edge_raw(&mut self, from: usize, to: usize, upper: Flow, gain: Flow) -> Result<usize, GraphError<Flow>> {
+        check_nodes(from, to, self.num_nodes)?;
+        if upper <= Flow::zero() {
+            return Err(GraphError::NonPositiveCapacity { upper });
+        }
+        if gain <= Flow::zero() {
+            return Err(GraphError::NonPositiveGain { gain });
         }
 
         self.edges.push(Edge { from, to, flow: Flow::zero(), upper, gain });
 
         self.num_edges += 1;
-        Some(self.num_edges - 1)
+        Ok(self.num_edges - 1)
     }
 
-    pub fn get_edge(&self, edge_id: usize) -> Option<Edge<Flow>> {
+    pub fn get_edge(&self, edge_id: impl Into<EdgeId>) -> Option<Edge<Flow>> {
+        let edge_id = edge_id.into().index();
         if edge_id >= self.edges.len() {
             return None;
         }
@@ -78,7 +140,22 @@ where
         Some(Edge { from: edge.from, to: edge.to, flow: edge.flow, upper: edge.upper, gain: edge.gain })
     }
 
-    pub fn maximum_flow(&self, sink: usize) -> Flow {
+    // the edges in the order they were added
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, Edge<Flow>)> + '_ {
+        self.edges.iter().enumerate().map(|(edge_id, edge)| (EdgeId(edge_id), edge.clone()))
+    }
+
+    // the edges carrying a flow, the flow that leaves their from before the gain
+    pub fn edges_with_flow(&self) -> impl Iterator<Item = (EdgeId, Edge<Flow>)> + '_ {
+        self.edges().filter(|(_, edge)| edge.flow != Flow::zero())
+    }
+
+    pub fn flow(&self, edge_id: impl Into<EdgeId>) -> Option<Flow> {
+        self.edges.get(edge_id.into().index()).map(|edge| edge.flow)
+    }
+
+    pub fn maximum_flow(&self, sink: impl Into<NodeId>) -> Flow {
+        let sink = sink.into().index();
         (0..self.num_edges()).fold(Flow::zero(), |flow, edge_index| {
             let e = &self.get_edge(edge_index).unwrap();
             flow + if e.to == sink { e.flow * e.gain } else { Flow::zero() }
@@ -0,0 +1,40 @@
+use std::fmt::{self, Display};
+
+// why a graph rejected an edge or a supply, so that the caller can tell the problems apart
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum GraphError<Flow> {
+    NodeOutOfRange { node: usize, num_nodes: usize },
+    InvalidBounds { lower: Flow, upper: Flow }, // lower > upper
+    NegativeLower { lower: Flow },              // maximum flow only, see add_directed_edge_with_lower
+    NegativeCapacity { upper: Flow },           // maximum flow only
+    NonPositiveCapacity { upper: Flow },        // generalized maximum flow only
+    NonPositiveGain { gain: Flow },             // generalized maximum flow only
+}
+
+impl<Flow: Display> Display for GraphError<Flow> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GraphError::NodeOutOfRange { node, num_nodes } => write!(f, "node {node} is out of range of {num_nodes} nodes"),
+            GraphError::InvalidBounds { lower, upper } => write!(f, "lower {lower} > upper {upper}"),
+            GraphError::NegativeLower { lower } => write!(f, "negative lower {lower}"),
+            GraphError::NegativeCapacity { upper } => write!(f, "negative capacity {upper}"),
+            GraphError::NonPositiveCapacity { upper } => write!(f, "capacity {upper} is not positive"),
+            GraphError::NonPositiveGain { gain } => write!(f, "gain {gain} is not positive"),
+        }
+    }
+}
+
+impl<Flow: Display + fmt::Debug> std::error::Error for GraphError<Flow> {}
+
+// the checks of a node and of the ends of an edge shared by the graphs
+pub(crate) fn check_node<Flow>(node: usize, num_nodes: usize) -> Result<(), GraphError<Flow>> {
+    if node >= num_nodes {
+        return Err(GraphError::NodeOutOfRange { node, num_nodes });
+    }
+    Ok(())
+}
+
+pub(crate) fn check_nodes<Flow>(from: usize, to: usize, num_nodes: usize) -> Result<(), GraphError<Flow>> {
+    check_node(from, num_nodes)?;
+    check_node(to, num_nodes)
+}
@@ -0,0 +1,42 @@
+use std::fmt::{self, Display};
+
+// the ids returned by add_node and add_directed_edge of the graphs, distinct types so that an edge id is not taken for a node id.
+// From<usize> keeps the raw ids working, the id of the i-th node or edge added is i
+macro_rules! define_id {
+    ($($name:ident),*) => {
+        $(
+            #[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default, Clone, Copy)]
+            #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+            pub struct $name(pub usize);
+
+            impl $name {
+                #[inline]
+                pub fn index(self) -> usize {
+                    self.0
+                }
+            }
+
+            impl From<usize> for $name {
+                #[inline]
+                fn from(index: usize) -> Self {
+                    $name(index)
+                }
+            }
+
+            impl From<$name> for usize {
+                #[inline]
+                fn from(id: $name) -> Self {
+                    id.0
+                }
+            }
+
+            impl Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", self.0)
+                }
+            }
+        )*
+    };
+}
+
+define_id!(NodeId, EdgeId);
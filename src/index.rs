@@ -0,0 +1,70 @@
+use std::fmt::{self, Debug, Display};
+
+// the integer type of the node and edge indices in the CSR and the spanning tree structure.
+// u32 halves the memory of the indices of a graph with fewer than 2^32 nodes and 2^31 edges.
+// MAX is kept for the "none" marks (usize::MAX of the parents), so the indices are below it
+pub trait IndexType: Copy + Ord + Debug + Default + 'static {
+    const MAX: usize;
+
+    // panics if index does not fit, which try_build has ruled out
+    fn new(index: usize) -> Self;
+
+    fn index(self) -> usize;
+
+    #[inline]
+    fn none() -> Self {
+        Self::new(Self::MAX)
+    }
+}
+
+macro_rules! impl_index_type {
+    ($($t:ty),*) => {
+        $(
+            impl IndexType for $t {
+                const MAX: usize = <$t>::MAX as usize;
+
+                #[inline]
+                fn new(index: usize) -> Self {
+                    debug_assert!(index <= <Self as IndexType>::MAX);
+                    index as $t
+                }
+
+                #[inline]
+                fn index(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_index_type!(u16, u32, usize);
+
+// the graph has more nodes or edges than the index type can address
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum IndexError {
+    TooManyNodes { num_nodes: usize, max: usize },
+    TooManyEdges { num_edges: usize, max: usize }, // each edge takes two indices, for both directions of the residual edge
+}
+
+impl Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            IndexError::TooManyNodes { num_nodes, max } => write!(f, "{num_nodes} nodes do not fit in an index type of at most {max} nodes"),
+            IndexError::TooManyEdges { num_edges, max } => write!(f, "{num_edges} edges do not fit in an index type of at most {max} edges"),
+        }
+    }
+}
+
+impl std::error::Error for IndexError {}
+
+// every node id and every inside edge id (twice the edges) is below I::MAX
+pub(crate) fn check_capacity<I: IndexType>(num_nodes: usize, num_edges: usize) -> Result<(), IndexError> {
+    if num_nodes > I::MAX {
+        return Err(IndexError::TooManyNodes { num_nodes, max: I::MAX });
+    }
+    if num_edges > I::MAX / 2 {
+        return Err(IndexError::TooManyEdges { num_edges, max: I::MAX / 2 });
+    }
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use crate::maximum_flow;
+use crate::minimum_cost_flow;
+use num_traits::NumAssign;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::ops::Neg;
+
+// node id -> label
+pub type LabelMap = HashMap<usize, String>;
+
+pub struct GraphmlOptions<'a, Flow> {
+    pub labels: Option<&'a LabelMap>,   // the nodes without a label get none
+    pub potentials: Option<&'a [Flow]>, // adds the reduced costs of the edges (minimum cost flow only)
+    pub support_only: bool,             // only the edges with nonzero flow, and all the nodes
+}
+
+impl<Flow> Default for GraphmlOptions<'_, Flow> {
+    fn default() -> Self {
+        Self { labels: None, potentials: None, support_only: false }
+    }
+}
+
+// the values of a node and an edge in the orientation given by the user
+pub struct GraphmlNode<Flow> {
+    pub b: Option<Flow>,
+    pub excess: Flow,
+}
+
+pub struct GraphmlEdge<Flow> {
+    pub from: usize,
+    pub to: usize,
+    pub flow: Flow,
+    pub lower: Flow,
+    pub upper: Flow,
+    pub cost: Option<Flow>,
+}
+
+// a graph write_graphml can export
+pub trait GraphmlSource<Flow> {
+    fn graphml_nodes(&self) -> Vec<GraphmlNode<Flow>>;
+    fn graphml_edges(&self) -> Vec<GraphmlEdge<Flow>>;
+    fn has_costs(&self) -> bool;
+}
+
+impl<Flow> GraphmlSource<Flow> for minimum_cost_flow::graph::Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    fn graphml_nodes(&self) -> Vec<GraphmlNode<Flow>> {
+        (0..self.num_nodes()).map(|u| GraphmlNode { b: Some(self.supply(u)), excess: self.excess(u) }).collect()
+    }
+
+    fn graphml_edges(&self) -> Vec<GraphmlEdge<Flow>> {
+        (0..self.num_edges())
+            .map(|edge_id| self.get_edge(edge_id).unwrap())
+            .map(|edge| GraphmlEdge { from: edge.from, to: edge.to, flow: edge.flow, lower: edge.lower, upper: edge.upper, cost: Some(edge.cost) })
+            .collect()
+    }
+
+    fn has_costs(&self) -> bool {
+        true
+    }
+}
+
+impl<Flow> GraphmlSource<Flow> for maximum_flow::graph::Graph<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    // the excess is the inflow minus the outflow
+    fn graphml_nodes(&self) -> Vec<GraphmlNode<Flow>> {
+        let mut nodes: Vec<GraphmlNode<Flow>> = (0..self.num_nodes()).map(|_| GraphmlNode { b: None, excess: Flow::zero() }).collect();
+        for edge in self.graphml_edges() {
+            nodes[edge.from].excess -= edge.flow;
+            nodes[edge.to].excess += edge.flow;
+        }
+        nodes
+    }
+
+    fn graphml_edges(&self) -> Vec<GraphmlEdge<Flow>> {
+        (0..self.num_edges())
+            .map(|edge_id| (self.get_edge(edge_id).unwrap(), self.get_lower(edge_id).unwrap()))
+            .map(|(edge, lower)| GraphmlEdge { from: edge.from, to: edge.to, flow: edge.flow, lower, upper: edge.upper, cost: None })
+            .collect()
+    }
+
+    fn has_costs(&self) -> bool {
+        false
+    }
+}
+
+// & < > " ' as entities, for the labels given by the user
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// GraphML with the keys declared in a fixed order:
+//   node: b (minimum cost flow), excess, label (with options.labels)
+//   edge: flow, lower, upper, cost (minimum cost flow), saturated, reduced_cost (with options.potentials)
+// the nodes are "n<id>" and the edges "e<id>", with the ids of the graph also in the support-only mode
+pub fn write_graphml<Flow, G>(graph: &G, writer: &mut impl Write, options: &GraphmlOptions<Flow>) -> io::Result<()>
+where
+    Flow: NumAssign + Ord + Copy + Display,
+    G: GraphmlSource<Flow>,
+{
+    let (nodes, edges) = (graph.graphml_nodes(), graph.graphml_edges());
+    let has_reduced_costs = graph.has_costs() && options.potentials.is_some();
+    if let Some(potentials) = options.potentials.filter(|_| has_reduced_costs) {
+        assert_eq!(potentials.len(), nodes.len());
+    }
+
+    let mut keys = Vec::new();
+    if graph.has_costs() {
+        keys.push(("node", "b", "double"));
+    }
+    keys.push(("node", "excess", "double"));
+    if options.labels.is_some() {
+        keys.push(("node", "label", "string"));
+    }
+    keys.extend([("edge", "flow", "double"), ("edge", "lower", "double"), ("edge", "upper", "double")]);
+    if graph.has_costs() {
+        keys.push(("edge", "cost", "double"));
+    }
+    keys.push(("edge", "saturated", "boolean"));
+    if has_reduced_costs {
+        keys.push(("edge", "reduced_cost", "double"));
+    }
+
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+    for (domain, name, attribute_type) in keys {
+        writeln!(writer, r#"  <key id="{name}" for="{domain}" attr.name="{name}" attr.type="{attribute_type}"/>"#)?;
+    }
+    writeln!(writer, r#"  <graph id="G" edgedefault="directed">"#)?;
+
+    for (u, node) in nodes.iter().enumerate() {
+        writeln!(writer, r#"    <node id="n{u}">"#)?;
+        if let Some(b) = node.b {
+            writeln!(writer, r#"      <data key="b">{b}</data>"#)?;
+        }
+        writeln!(writer, r#"      <data key="excess">{}</data>"#, node.excess)?;
+        if let Some(label) = options.labels.and_then(|labels| labels.get(&u)) {
+            writeln!(writer, r#"      <data key="label">{}</data>"#, escape_xml(label))?;
+        }
+        writeln!(writer, "    </node>")?;
+    }
+
+    for (edge_id, edge) in edges.iter().enumerate() {
+        if options.support_only && edge.flow == Flow::zero() {
+            continue;
+        }
+        writeln!(writer, r#"    <edge id="e{edge_id}" source="n{}" target="n{}">"#, edge.from, edge.to)?;
+        writeln!(writer, r#"      <data key="flow">{}</data>"#, edge.flow)?;
+        writeln!(writer, r#"      <data key="lower">{}</data>"#, edge.lower)?;
+        writeln!(writer, r#"      <data key="upper">{}</data>"#, edge.upper)?;
+        if let Some(cost) = edge.cost {
+            writeln!(writer, r#"      <data key="cost">{cost}</data>"#)?;
+        }
+        writeln!(writer, r#"      <data key="saturated">{}</data>"#, edge.flow == edge.upper && edge.upper != Flow::zero())?;
+        if let (Some(cost), Some(potentials)) = (edge.cost, options.potentials.filter(|_| has_reduced_costs)) {
+            // reduced cost: cost - potential(from) + potential(to)
+            writeln!(writer, r#"      <data key="reduced_cost">{}</data>"#, cost + potentials[edge.to] - potentials[edge.from])?;
+        }
+        writeln!(writer, "    </edge>")?;
+    }
+
+    writeln!(writer, "  </graph>")?;
+    writeln!(writer, "</graphml>")?;
+    Ok(())
+}
@@ -1,3 +1,17 @@
+pub mod approx;
+pub mod checked;
+pub mod clock;
+pub mod compaction;
+pub mod cooperative;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+pub mod diagnostics;
+pub mod flow_ext;
 pub mod generalized_maximum_flow;
+pub mod graph_error;
+pub mod ids;
+pub mod index;
+pub mod io;
 pub mod maximum_flow;
+pub mod metrics;
 pub mod minimum_cost_flow;
+pub mod util;
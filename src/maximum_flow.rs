@@ -1,9 +1,26 @@
+pub mod apply;
+pub mod bipartite;
+pub mod boykov_kolmogorov;
 pub mod capacity_scaling;
-mod csr;
+pub mod connectivity;
+pub mod csr;
+pub mod decomposition;
+pub mod decremental;
+pub mod dimacs;
 pub mod dinic;
 pub mod edmonds_karp;
+pub mod estimate;
+pub mod excess_scaling;
+pub mod flow_result;
 pub mod ford_fulkerson;
+pub mod gomory_hu;
 pub mod graph;
+pub mod isap;
+mod lower_bounds;
+pub mod mpm;
+pub mod node_capacity;
 pub mod push_relabel_fifo;
 pub mod shortest_augmenting_path;
+pub mod solver;
 pub mod status;
+pub mod stoer_wagner;
@@ -0,0 +1,86 @@
+use crate::maximum_flow::graph::Graph;
+use num_traits::NumAssign;
+use std::collections::HashMap;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Direction {
+    Forward,  // from -> to, increases the flow
+    Backward, // to -> from, decreases the flow
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ApplyError {
+    EmptyPath,
+    NegativeAmount,
+    EdgeOutOfRange { position: usize, edge_id: usize },
+    Disconnected { position: usize },
+    InsufficientCapacity { position: usize, edge_id: usize },
+}
+
+// send amount along the path
+// nothing is changed if the path is not connected or some edge violates 0 <= flow <= upper
+pub fn apply_path<Flow>(graph: &mut Graph<Flow>, path: &[(usize, Direction)], amount: Flow) -> Result<(), ApplyError>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    check_path(graph, path, amount)?;
+    for &(edge_id, direction) in path.iter() {
+        match direction {
+            Direction::Forward => graph.edges[edge_id].flow += amount,
+            Direction::Backward => graph.edges[edge_id].flow -= amount,
+        }
+    }
+    Ok(())
+}
+
+// cancel amount that was sent along the path
+pub fn unapply_path<Flow>(graph: &mut Graph<Flow>, path: &[(usize, Direction)], amount: Flow) -> Result<(), ApplyError>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let reversed: Vec<(usize, Direction)> = path
+        .iter()
+        .rev()
+        .map(|&(edge_id, direction)| match direction {
+            Direction::Forward => (edge_id, Direction::Backward),
+            Direction::Backward => (edge_id, Direction::Forward),
+        })
+        .collect();
+    apply_path(graph, &reversed, amount)
+}
+
+fn check_path<Flow>(graph: &Graph<Flow>, path: &[(usize, Direction)], amount: Flow) -> Result<(), ApplyError>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    if path.is_empty() {
+        return Err(ApplyError::EmptyPath);
+    }
+    if amount < Flow::zero() {
+        return Err(ApplyError::NegativeAmount);
+    }
+
+    // the flow of an edge that appears more than once is tracked here
+    let mut flows: HashMap<usize, Flow> = HashMap::new();
+    let mut now = usize::MAX;
+    for (position, &(edge_id, direction)) in path.iter().enumerate() {
+        let edge = graph.get_edge(edge_id).ok_or(ApplyError::EdgeOutOfRange { position, edge_id })?;
+        let (from, to) = match direction {
+            Direction::Forward => (edge.from, edge.to),
+            Direction::Backward => (edge.to, edge.from),
+        };
+        if position != 0 && now != from {
+            return Err(ApplyError::Disconnected { position });
+        }
+
+        let flow = flows.entry(edge_id).or_insert(edge.flow);
+        match direction {
+            Direction::Forward if *flow + amount <= edge.upper => *flow += amount,
+            Direction::Backward if *flow >= amount => *flow -= amount,
+            _ => return Err(ApplyError::InsufficientCapacity { position, edge_id }),
+        }
+        now = to;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::maximum_flow::dinic::Dinic;
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::status::Status;
+
+// the pairs (left, right) of a maximum matching, in the order of pairs.
+// unit capacity dinic on source -> left -> right -> sink, which is O(E sqrt(V)) as hopcroft-karp
+pub fn maximum_bipartite_matching(num_left: usize, num_right: usize, pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    assert!(pairs.iter().all(|&(left, right)| left < num_left && right < num_right));
+
+    let mut graph: Graph<i64> = Graph::default();
+    graph.add_nodes(num_left + num_right + 2);
+    let (source, sink) = (num_left + num_right, num_left + num_right + 1);
+
+    // the pairs come first, so that edge i is pairs[i]
+    for &(left, right) in pairs.iter() {
+        graph.add_directed_edge(left, num_left + right, 1).unwrap();
+    }
+    for left in 0..num_left {
+        graph.add_directed_edge(source, left, 1).unwrap();
+    }
+    for right in 0..num_right {
+        graph.add_directed_edge(num_left + right, sink, 1).unwrap();
+    }
+
+    assert_eq!(Dinic::default().solve(source, sink, &mut graph), Status::Optimal);
+    pairs
+        .iter()
+        .enumerate()
+        .filter(|&(edge_id, _)| graph.get_edge(edge_id).unwrap().flow == 1)
+        .map(|(_, &pair)| pair)
+        .collect()
+}
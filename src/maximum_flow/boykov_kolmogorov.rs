@@ -0,0 +1,322 @@
+use crate::ids::NodeId;
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
+use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, AUGMENTATIONS_TOTAL};
+use num_traits::NumAssign;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+enum Tree {
+    #[default]
+    Free,
+    Source,
+    Sink,
+}
+
+// the algorithm of Boykov and Kolmogorov, fast on the grids of computer vision.
+// the search trees from source and from sink are grown until they touch, and are kept after the augmentation:
+// the nodes cut off by the saturated edges are orphans, and adopted by another parent of the same tree or freed
+#[derive(Default)]
+pub struct BoykovKolmogorov<Flow> {
+    csr: CSR<Flow>,
+    tree: Vec<Tree>,
+    parent_edge: Vec<usize>, // the edge from u to its parent, usize::MAX for the roots and the orphans
+    // the depths in the trees, known to be exact if the timestamp is the current time. the trees are kept shallow with them
+    depths: Vec<usize>,
+    timestamps: Vec<usize>,
+    time: usize,
+    is_active: Vec<bool>,
+    active_nodes: VecDeque<usize>,
+    orphans: VecDeque<usize>,
+    num_augmentations: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+}
+
+impl<Flow> BoykovKolmogorov<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "boykov_kolmogorov");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
+        self.csr.build(graph);
+        let n = self.csr.num_nodes;
+        self.tree.clear();
+        self.tree.resize(n, Tree::Free);
+        self.parent_edge.clear();
+        self.parent_edge.resize(n, usize::MAX);
+        self.depths.clear();
+        self.depths.resize(n, 0);
+        self.timestamps.clear();
+        self.timestamps.resize(n, 0);
+        self.time = 0;
+        self.is_active.clear();
+        self.is_active.resize(n, false);
+        self.active_nodes.clear();
+        self.orphans.clear();
+        self.num_augmentations = 0;
+
+        (self.tree[source], self.tree[sink]) = (Tree::Source, Tree::Sink);
+        self.grow_from_roots(source, sink);
+        while let Some(edge_id) = self.grow() {
+            self.augment(source, sink, edge_id);
+            self.adopt(source, sink);
+        }
+
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
+
+    // the final source tree, the source side of the minimum cut closest to the source.
+    // the other nodes are in the sink tree or free, valid after solve returns Status::Optimal
+    pub fn is_source_side(&self, u: usize) -> bool {
+        self.tree[u] == Tree::Source
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    fn activate(&mut self, u: usize) {
+        if !self.is_active[u] {
+            self.is_active[u] = true;
+            self.active_nodes.push_back(u);
+        }
+    }
+
+    // the residual capacity of the edge i from u, toward sink in the source tree and toward source in the sink tree
+    fn tree_capacity(&self, tree: Tree, i: usize) -> Flow {
+        match tree {
+            Tree::Source => self.csr.inside_edge_list[i].residual_capacity(),
+            _ => self.csr.inside_edge_list[self.csr.inside_edge_list[i].rev].residual_capacity(),
+        }
+    }
+
+    // source and sink have the terminal edges of every pixel, and are never active so as not to scan them again and again.
+    // their neighbors start the trees instead, and the edges from source to sink are saturated at once.
+    // the residual capacities of the terminal edges never increase, so the roots are not needed to grow again
+    fn grow_from_roots(&mut self, source: usize, sink: usize) {
+        for (root, tree) in [(source, Tree::Source), (sink, Tree::Sink)] {
+            for i in self.csr.start[root]..self.csr.start[root + 1] {
+                let q = self.csr.inside_edge_list[i].to;
+                if self.tree[q] == Tree::Free && self.tree_capacity(tree, i) > Flow::zero() {
+                    self.tree[q] = tree;
+                    self.set_parent(q, self.csr.inside_edge_list[i].rev, root);
+                    self.activate(q);
+                }
+            }
+        }
+        for i in self.csr.start[source]..self.csr.start[source + 1] {
+            let residual_capacity = self.csr.inside_edge_list[i].residual_capacity();
+            if self.csr.inside_edge_list[i].to == sink && residual_capacity > Flow::zero() {
+                self.csr.push_flow(i, residual_capacity);
+                self.flow_value += residual_capacity;
+                self.num_augmentations += 1;
+            }
+        }
+    }
+
+    // the edge from the source tree to the sink tree, if the trees touch
+    fn grow(&mut self) -> Option<usize> {
+        while let Some(&p) = self.active_nodes.front() {
+            if self.tree[p] != Tree::Free {
+                for i in self.csr.start[p]..self.csr.start[p + 1] {
+                    if self.tree_capacity(self.tree[p], i) == Flow::zero() {
+                        continue;
+                    }
+                    let q = self.csr.inside_edge_list[i].to;
+                    if self.tree[q] == Tree::Free {
+                        self.tree[q] = self.tree[p];
+                        self.set_parent(q, self.csr.inside_edge_list[i].rev, p);
+                        self.activate(q);
+                    } else if self.tree[q] != self.tree[p] {
+                        // p stays active
+                        return Some(if self.tree[p] == Tree::Source { i } else { self.csr.inside_edge_list[i].rev });
+                    } else if self.timestamps[q] <= self.timestamps[p] && self.depths[q] > self.depths[p] + 1 {
+                        // p is a closer parent of q
+                        self.set_parent(q, self.csr.inside_edge_list[i].rev, p);
+                    }
+                }
+            }
+            self.active_nodes.pop_front();
+            self.is_active[p] = false;
+        }
+        None
+    }
+
+    fn set_parent(&mut self, u: usize, parent_edge: usize, parent: usize) {
+        self.parent_edge[u] = parent_edge;
+        self.timestamps[u] = self.timestamps[parent];
+        self.depths[u] = self.depths[parent] + 1;
+    }
+
+    // along source -> ... -> from -> to -> ... -> sink for the edge between the trees
+    fn augment(&mut self, source: usize, sink: usize, edge_id: usize) {
+        let from = self.csr.inside_edge_list[self.csr.inside_edge_list[edge_id].rev].to;
+        let to = self.csr.inside_edge_list[edge_id].to;
+
+        let mut delta = self.csr.inside_edge_list[edge_id].residual_capacity();
+        for (root, tree, start) in [(source, Tree::Source, from), (sink, Tree::Sink, to)] {
+            let mut u = start;
+            while u != root {
+                delta = delta.min(self.tree_capacity(tree, self.csr.inside_edge_list[self.parent_edge[u]].rev));
+                u = self.csr.inside_edge_list[self.parent_edge[u]].to;
+            }
+        }
+
+        self.csr.push_flow(edge_id, delta);
+        self.flow_value += delta;
+        for (root, tree, start) in [(source, Tree::Source, from), (sink, Tree::Sink, to)] {
+            let mut u = start;
+            while u != root {
+                let (parent_edge, parent) = (self.parent_edge[u], self.csr.inside_edge_list[self.parent_edge[u]].to);
+                // the edge from the parent to u in the source tree, from u to the parent in the sink tree
+                let edge_id = if tree == Tree::Source { self.csr.inside_edge_list[parent_edge].rev } else { parent_edge };
+                self.csr.push_flow(edge_id, delta);
+                if self.csr.inside_edge_list[edge_id].residual_capacity() == Flow::zero() {
+                    self.parent_edge[u] = usize::MAX;
+                    self.orphans.push_front(u);
+                }
+                u = parent;
+            }
+        }
+        self.num_augmentations += 1;
+    }
+
+    // the depth of u if it is connected to the root of its tree.
+    // the nodes on the way are marked with the current time
+    fn depth_from_root(&mut self, u: usize, source: usize, sink: usize) -> Option<usize> {
+        let (mut v, mut depth) = (u, 0);
+        while self.timestamps[v] != self.time {
+            if v == source || v == sink {
+                (self.timestamps[v], self.depths[v]) = (self.time, 0);
+                break;
+            }
+            if self.parent_edge[v] == usize::MAX {
+                return None;
+            }
+            v = self.csr.inside_edge_list[self.parent_edge[v]].to;
+            depth += 1;
+        }
+        depth += self.depths[v];
+
+        let (mut v, mut d) = (u, depth);
+        while self.timestamps[v] != self.time {
+            (self.timestamps[v], self.depths[v]) = (self.time, d);
+            v = self.csr.inside_edge_list[self.parent_edge[v]].to;
+            d -= 1;
+        }
+        Some(depth)
+    }
+
+    fn adopt(&mut self, source: usize, sink: usize) {
+        self.time += 1;
+        while let Some(u) = self.orphans.pop_front() {
+            let tree = self.tree[u];
+            // the closest neighbor of the same tree with a residual edge to u, which is not below u
+            let mut best: Option<(usize, usize)> = None;
+            for i in self.csr.start[u]..self.csr.start[u + 1] {
+                let q = self.csr.inside_edge_list[i].to;
+                if self.tree[q] != tree || self.tree_capacity(tree, self.csr.inside_edge_list[i].rev) == Flow::zero() {
+                    continue;
+                }
+                if let Some(depth) = self.depth_from_root(q, source, sink) {
+                    if best.is_none_or(|(best_depth, _)| depth < best_depth) {
+                        best = Some((depth, i));
+                    }
+                }
+            }
+            if let Some((depth, parent_edge)) = best {
+                self.parent_edge[u] = parent_edge;
+                (self.timestamps[u], self.depths[u]) = (self.time, depth + 1);
+                continue;
+            }
+
+            // u is freed, and its children become orphans. the neighbors that can reach u may grow into it again, except the roots
+            for i in self.csr.start[u]..self.csr.start[u + 1] {
+                let q = self.csr.inside_edge_list[i].to;
+                if self.tree[q] != tree {
+                    continue;
+                }
+                if q != source && q != sink && self.tree_capacity(tree, self.csr.inside_edge_list[i].rev) > Flow::zero() {
+                    self.activate(q);
+                }
+                if self.parent_edge[q] == self.csr.inside_edge_list[i].rev {
+                    self.parent_edge[q] = usize::MAX;
+                    self.orphans.push_back(q);
+                }
+            }
+            self.tree[u] = Tree::Free;
+        }
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for BoykovKolmogorov<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "boykov_kolmogorov"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
@@ -1,38 +1,96 @@
+use crate::ids::NodeId;
 use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
 use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
 use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PHASES_TOTAL};
 use num_traits::NumAssign;
-use std::collections::VecDeque;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct CapacityScaling<Flow> {
     csr: CSR<Flow>,
     current_edge: Vec<usize>,
-    que: VecDeque<usize>,
+    base: Option<Flow>,          // delta is divided by base after each scaling phase, 2 if None
+    initial_delta: Option<Flow>, // the largest power of base not more than the maximum capacity if None
+    num_phases: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> CapacityScaling<Flow>
 where
     Flow: NumAssign + Ord + Copy,
 {
-    pub fn solve(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
+    // a larger base takes fewer scaling phases, each of which may augment more times.
+    // the last phase is always delta = 1, so the flow is maximum for any initial_delta >= 1
+    pub fn with_parameters(base: Flow, initial_delta: Option<Flow>) -> Self
+    where
+        Flow: Default,
+    {
+        assert!(base >= Flow::one() + Flow::one());
+        assert!(initial_delta.is_none_or(|delta| delta >= Flow::one()));
+        Self { base: Some(base), initial_delta, ..Self::default() }
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "capacity_scaling");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(PHASES_TOTAL, self.num_phases)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(PHASES_TOTAL, self.num_phases)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
         self.csr.build(graph);
         self.current_edge.resize(self.csr.num_nodes, 0);
-        let two = Flow::one() + Flow::one();
+        self.num_phases = 0;
+        let base = self.base.unwrap_or(Flow::one() + Flow::one());
 
-        let max_capacity = self.csr.inside_edge_list.iter().map(|e| e.upper).max().unwrap();
-        let mut delta = Flow::one();
-        while delta <= max_capacity {
-            delta *= two;
-        }
-        delta /= two;
+        // delta * base is not computed unless it is at most the maximum capacity, so that it does not overflow
+        let max_capacity = self.csr.inside_edge_list.iter().map(|e| e.upper).max().unwrap_or(Flow::zero());
+        let mut delta = match self.initial_delta {
+            Some(delta) => delta,
+            None if max_capacity < Flow::one() => Flow::zero(),
+            None => {
+                let mut delta = Flow::one();
+                while delta <= max_capacity / base {
+                    delta *= base;
+                }
+                delta
+            }
+        };
 
         let upper = self.csr.neighbors(source).fold(Flow::zero(), |sum, e| sum + e.upper);
-        let mut flow = Flow::zero();
         while delta > Flow::zero() {
             // solve maximum flow in lambda-residual network
             loop {
-                self.bfs(source, sink, delta);
+                self.csr.update_distances_with_threshold(source, sink, delta);
+                self.num_phases += 1;
 
                 // no s-t path
                 if self.csr.distances[source] >= self.csr.num_nodes {
@@ -41,68 +99,98 @@ where
 
                 self.current_edge.iter_mut().enumerate().for_each(|(u, e)| *e = self.csr.start[u]);
                 match self.dfs(source, sink, upper, delta) {
-                    Some(delta) => flow += delta,
+                    Some(delta) => self.flow_value += delta,
                     None => break,
                 }
             }
-            delta /= two;
+            delta = if delta > Flow::one() { (delta / base).max(Flow::one()) } else { Flow::zero() };
         }
 
-        // copy
-        for edge_id in 0..graph.num_edges() {
-            let i = self.csr.edge_index_to_inside_edge_index[edge_id];
-            graph.edges[edge_id].flow = self.csr.inside_edge_list[i].flow;
-        }
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
 
-        Status::Optimal
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
     }
 
-    fn bfs(&mut self, source: usize, sink: usize, delta: Flow) {
-        self.que.clear();
-        self.que.push_back(sink);
-        let n = self.csr.num_nodes;
-        self.csr.distances.fill(n);
-        self.csr.distances[sink] = 0;
-
-        while let Some(v) = self.que.pop_front() {
-            for e in self.csr.inside_edge_list[self.csr.start[v]..self.csr.start[v + 1]].iter() {
-                // e.to -> v
-                if self.csr.inside_edge_list[e.rev].residual_capacity() >= delta && self.csr.distances[e.to] == self.csr.num_nodes {
-                    self.csr.distances[e.to] = self.csr.distances[v] + 1;
-                    if e.to != source {
-                        self.que.push_back(e.to);
-                    }
-                }
-            }
-        }
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
     }
 
-    fn dfs(&mut self, u: usize, sink: usize, upper: Flow, delta: Flow) -> Option<Flow> {
-        if u == sink {
-            return Some(upper);
-        }
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
 
-        let mut res = Flow::zero();
-        for i in self.current_edge[u]..self.csr.start[u + 1] {
-            self.current_edge[u] = i;
-            let v = self.csr.inside_edge_list[i].to;
-            let residual_capacity = self.csr.inside_edge_list[i].residual_capacity();
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
 
-            if !self.csr.is_admissible_edge(u, i) || residual_capacity < delta {
-                continue;
-            }
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
 
-            if let Some(d) = self.dfs(v, sink, residual_capacity.min(upper - res), delta) {
-                self.csr.push_flow(i, d);
+    // with an explicit stack as Dinic::dfs
+    fn dfs(&mut self, u: usize, sink: usize, upper: Flow, delta: Flow) -> Option<Flow> {
+        let mut stack = vec![(u, upper, Flow::zero())];
+        let mut returned: Option<Flow> = None;
+        while let Some(&(u, upper, mut res)) = stack.last() {
+            let mut next = self.current_edge[u];
+            if let Some(d) = returned.take() {
+                self.csr.push_flow(next, d);
                 res += d;
+                stack.last_mut().unwrap().2 = res;
                 if res == upper {
-                    return Some(res);
+                    stack.pop();
+                    returned = Some(res);
+                    continue;
+                }
+                next += 1;
+            }
+
+            let admissible_edge = (next..self.csr.start[u + 1]).find(|&i| {
+                self.current_edge[u] = i;
+                self.csr.is_admissible_edge_with_threshold(u, i, delta)
+            });
+            match admissible_edge {
+                Some(i) => {
+                    let v = self.csr.inside_edge_list[i].to;
+                    let bound = self.csr.inside_edge_list[i].residual_capacity().min(upper - res);
+                    if v == sink {
+                        returned = Some(bound);
+                    } else {
+                        stack.push((v, bound, Flow::zero()));
+                    }
+                }
+                None => {
+                    self.current_edge[u] = self.csr.start[u + 1];
+                    self.csr.distances[u] = self.csr.num_nodes;
+                    stack.pop();
+                    returned = Some(res);
                 }
             }
         }
-        self.current_edge[u] = self.csr.start[u + 1];
-        self.csr.distances[u] = self.csr.num_nodes;
 
-        Some(res)
+        returned
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for CapacityScaling<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "capacity_scaling"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
     }
 }
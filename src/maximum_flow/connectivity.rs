@@ -0,0 +1,166 @@
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::status::Status;
+use num_traits::NumAssign;
+use std::collections::VecDeque;
+
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+pub enum Orientation {
+    #[default]
+    Directed,
+    // every edge can be used in both directions up to its capacity
+    Undirected,
+}
+
+// edge connectivity by maximum flows, where the capacities are the weights of the edges.
+// with capacities of 1, it is the number of edge-disjoint paths
+#[derive(Default)]
+pub struct Connectivity<Flow> {
+    orientation: Orientation,
+    csr: CSR<Flow>,
+    num_augmentations: usize,
+}
+
+impl<Flow> Connectivity<Flow>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    pub fn new(orientation: Orientation) -> Self {
+        Self { orientation, csr: CSR::default(), num_augmentations: 0 }
+    }
+
+    // the number of augmenting paths of the last query
+    pub fn num_augmentations(&self) -> usize {
+        self.num_augmentations
+    }
+
+    // the minimum capacity of the edges whose removal disconnects v from u
+    pub fn edge_connectivity(&mut self, u: usize, v: usize, graph: &Graph<Flow>) -> Result<Flow, Status> {
+        if u >= graph.num_nodes() || v >= graph.num_nodes() || u == v {
+            return Err(Status::BadInput);
+        }
+        self.num_augmentations = 0;
+        let network = self.network(graph);
+        Ok(self.maximum_flow(u, v, &network, None))
+    }
+
+    // whether every pair of terminals stays connected after removing edges of capacity less than k.
+    // it suffices to check the pairs with the first terminal, since any cut between two terminals separates one of them from it.
+    // each flow stops at k
+    pub fn is_k_connected(&mut self, terminals: &[usize], k: Flow, graph: &Graph<Flow>) -> Result<bool, Status> {
+        if terminals.iter().any(|&u| u >= graph.num_nodes()) {
+            return Err(Status::BadInput);
+        }
+        self.num_augmentations = 0;
+        let Some(&root) = terminals.first() else {
+            return Ok(true);
+        };
+
+        let network = self.network(graph);
+        for &u in terminals.iter().filter(|&&u| u != root) {
+            if self.maximum_flow(root, u, &network, Some(k)) < k {
+                return Ok(false);
+            }
+            if self.orientation == Orientation::Directed && self.maximum_flow(u, root, &network, Some(k)) < k {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // the minimum edge connectivity over all pairs of nodes by n - 1 flows from node 0 (2(n - 1) if directed).
+    // each flow stops at the minimum found so far
+    pub fn global_edge_connectivity(&mut self, graph: &Graph<Flow>) -> Result<Flow, Status> {
+        if graph.num_nodes() < 2 {
+            return Err(Status::BadInput);
+        }
+        self.num_augmentations = 0;
+
+        let network = self.network(graph);
+        let mut best: Option<Flow> = None;
+        for u in 1..graph.num_nodes() {
+            let mut pairs = vec![(0, u)];
+            if self.orientation == Orientation::Directed {
+                pairs.push((u, 0));
+            }
+            for (source, sink) in pairs {
+                let flow = self.maximum_flow(source, sink, &network, best);
+                best = Some(best.map_or(flow, |best| best.min(flow)));
+            }
+        }
+        Ok(best.unwrap())
+    }
+
+    // an undirected edge becomes a pair of opposite edges
+    fn network(&self, graph: &Graph<Flow>) -> Graph<Flow> {
+        let mut network = graph.clone();
+        if self.orientation == Orientation::Undirected {
+            for edge in graph.edges.iter() {
+                network.push_edge(edge.to, edge.from, edge.upper);
+            }
+        }
+        network.edges.iter_mut().for_each(|edge| edge.flow = Flow::zero());
+        network
+    }
+
+    // shortest augmenting paths until the flow reaches limit
+    fn maximum_flow(&mut self, source: usize, sink: usize, network: &Graph<Flow>, limit: Option<Flow>) -> Flow {
+        self.csr.build(network);
+
+        let mut flow = Flow::zero();
+        let mut prev = vec![usize::MAX; self.csr.num_nodes];
+        while limit.is_none_or(|limit| flow < limit) {
+            // bfs in the residual network
+            prev.fill(usize::MAX);
+            let mut que = VecDeque::from([source]);
+            while let Some(u) = que.pop_front() {
+                for i in self.csr.start[u]..self.csr.start[u + 1] {
+                    let edge = &self.csr.inside_edge_list[i];
+                    if edge.to != source && prev[edge.to] == usize::MAX && edge.residual_capacity() > Flow::zero() {
+                        prev[edge.to] = i;
+                        que.push_back(edge.to);
+                    }
+                }
+            }
+            if prev[sink] == usize::MAX {
+                break;
+            }
+
+            let mut path = Vec::new();
+            let mut v = sink;
+            while v != source {
+                path.push(prev[v]);
+                v = self.csr.inside_edge_list[self.csr.inside_edge_list[prev[v]].rev].to;
+            }
+            let mut delta = path.iter().map(|&i| self.csr.inside_edge_list[i].residual_capacity()).min().unwrap();
+            if let Some(limit) = limit {
+                delta = delta.min(limit - flow);
+            }
+            path.iter().for_each(|&i| self.csr.push_flow(i, delta));
+            flow += delta;
+            self.num_augmentations += 1;
+        }
+        flow
+    }
+}
+
+pub fn edge_connectivity<Flow>(u: usize, v: usize, graph: &Graph<Flow>, orientation: Orientation) -> Result<Flow, Status>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    Connectivity::new(orientation).edge_connectivity(u, v, graph)
+}
+
+pub fn is_k_connected<Flow>(terminals: &[usize], k: Flow, graph: &Graph<Flow>, orientation: Orientation) -> Result<bool, Status>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    Connectivity::new(orientation).is_k_connected(terminals, k, graph)
+}
+
+pub fn global_edge_connectivity<Flow>(graph: &Graph<Flow>, orientation: Orientation) -> Result<Flow, Status>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    Connectivity::new(orientation).global_edge_connectivity(graph)
+}
@@ -1,3 +1,4 @@
+use crate::index::{check_capacity, IndexError, IndexType};
 use crate::maximum_flow::graph::Graph;
 use std::collections::VecDeque;
 use std::fmt::Debug;
@@ -6,46 +7,58 @@ use std::ops::Sub;
 use num_traits::NumAssign;
 
 #[derive(Default, PartialEq, Debug)]
-pub struct InsideEdge<Flow> {
-    pub to: usize,
+pub struct InsideEdge<Flow, I: IndexType = usize> {
+    pub to: I,
     pub flow: Flow,
     pub upper: Flow,
-    pub rev: usize,
+    pub rev: I,
 }
 
-impl<Flow> InsideEdge<Flow>
+impl<Flow, I: IndexType> InsideEdge<Flow, I>
 where
-    Flow: Sub<Output = Flow> + Copy,
+    Flow: Sub<Output = Flow> + Clone,
 {
     pub fn residual_capacity(&self) -> Flow {
-        self.upper - self.flow
+        self.upper.clone() - self.flow.clone()
     }
 }
 
+// CSR<Flow, u32> takes half the memory for the indices, for the graphs that fit, see IndexType
 #[derive(Default)]
-pub struct CSR<Flow> {
+#[allow(clippy::upper_case_acronyms)]
+pub struct CSR<Flow, I: IndexType = usize> {
     pub num_nodes: usize,
     pub num_edges: usize,
-    pub edge_index_to_inside_edge_index: Vec<usize>,
+    pub edge_index_to_inside_edge_index: Vec<I>,
 
-    pub start: Vec<usize>,
-    pub inside_edge_list: Vec<InsideEdge<Flow>>,
+    pub start: Vec<I>,
+    pub inside_edge_list: Vec<InsideEdge<Flow, I>>,
     pub distances: Vec<usize>, // distance from u to sink in residual network
     que: VecDeque<usize>,
 }
 
-impl<Flow> CSR<Flow>
+impl<Flow, I> CSR<Flow, I>
 where
-    Flow: NumAssign + Ord + Copy,
+    Flow: NumAssign + Ord + Clone,
+    I: IndexType,
 {
-    pub fn build(&mut self, graph: &mut Graph<Flow>) {
+    // panics if the graph does not fit in I, which try_build reports instead
+    pub fn build(&mut self, graph: &Graph<Flow>) {
+        self.try_build(graph).unwrap();
+    }
+
+    // Err(IndexError) without building if the nodes or the edges of graph do not fit in I
+    pub fn try_build(&mut self, graph: &Graph<Flow>) -> Result<(), IndexError> {
+        check_capacity::<I>(graph.num_nodes(), graph.num_edges())?;
         self.num_nodes = graph.num_nodes();
         self.num_edges = graph.num_edges();
 
         // initialize
-        self.edge_index_to_inside_edge_index.resize(self.num_edges, usize::MAX);
-        self.start.resize(self.num_nodes + 1, 0);
-        self.inside_edge_list = (0..2 * self.num_edges).map(|_| InsideEdge { to: 0, flow: Flow::zero(), upper: Flow::zero(), rev: 0 }).collect();
+        self.edge_index_to_inside_edge_index = vec![I::none(); self.num_edges];
+        self.start = vec![I::new(0); self.num_nodes + 1];
+        self.inside_edge_list = (0..2 * self.num_edges)
+            .map(|_| InsideEdge { to: I::new(0), flow: Flow::zero(), upper: Flow::zero(), rev: I::new(0) })
+            .collect();
         self.distances.resize(self.num_nodes, self.num_nodes);
 
         let mut degree = vec![0; self.num_nodes];
@@ -55,41 +68,40 @@ where
         }
 
         for i in 1..=self.num_nodes {
-            self.start[i] += self.start[i - 1] + degree[i - 1];
+            self.start[i] = I::new(self.start[i - 1].index() + degree[i - 1]);
         }
 
         let mut counter = vec![0; self.num_nodes];
         for (edge_index, e) in graph.edges.iter().enumerate() {
             let (u, v) = (e.from, e.to);
-            let inside_edge_index_u = self.start[u] + counter[u];
+            let inside_edge_index_u = self.start[u].index() + counter[u];
             counter[u] += 1;
-            let inside_edge_index_v = self.start[v] + counter[v];
-            self.edge_index_to_inside_edge_index[edge_index] = inside_edge_index_u;
+            let inside_edge_index_v = self.start[v].index() + counter[v];
+            self.edge_index_to_inside_edge_index[edge_index] = I::new(inside_edge_index_u);
             counter[v] += 1;
 
-            self.inside_edge_list[inside_edge_index_u] = InsideEdge { to: v, flow: Flow::zero(), upper: e.upper, rev: inside_edge_index_v };
-            self.inside_edge_list[inside_edge_index_v] = InsideEdge { to: u, flow: e.upper, upper: e.upper, rev: inside_edge_index_u };
+            self.inside_edge_list[inside_edge_index_u] = InsideEdge { to: I::new(v), flow: Flow::zero(), upper: e.upper.clone(), rev: I::new(inside_edge_index_v) };
+            self.inside_edge_list[inside_edge_index_v] = InsideEdge { to: I::new(u), flow: e.upper.clone(), upper: e.upper.clone(), rev: I::new(inside_edge_index_u) };
         }
+        Ok(())
     }
 
-    pub fn set_flow(&self, graph: &mut Graph<Flow>) {
-        for edge_id in 0..graph.num_edges() {
-            let i = self.edge_index_to_inside_edge_index[edge_id];
-            graph.edges[edge_id].flow = self.inside_edge_list[i].flow;
-        }
+    // flows indexed by the edge ids of the graph
+    pub fn flows(&self) -> Vec<Flow> {
+        self.edge_index_to_inside_edge_index.iter().map(|&i| self.inside_edge_list[i.index()].flow.clone()).collect()
     }
 
     #[inline]
-    pub fn neighbors(&self, u: usize) -> std::slice::Iter<InsideEdge<Flow>> {
-        self.inside_edge_list[self.start[u]..self.start[u + 1]].iter()
+    pub fn neighbors(&self, u: usize) -> std::slice::Iter<'_, InsideEdge<Flow, I>> {
+        self.inside_edge_list[self.start[u].index()..self.start[u + 1].index()].iter()
     }
 
     #[inline]
     pub fn push_flow(&mut self, inside_edge_index: usize, flow: Flow) {
-        let rev = self.inside_edge_list[inside_edge_index].rev;
+        let rev = self.inside_edge_list[inside_edge_index].rev.index();
 
         // update flow
-        self.inside_edge_list[inside_edge_index].flow += flow;
+        self.inside_edge_list[inside_edge_index].flow += flow.clone();
         self.inside_edge_list[rev].flow -= flow;
     }
 
@@ -97,26 +109,120 @@ where
     // calculate the distance from u to sink in the residual network
     // if such a path does not exist, distance[u] becomes self.num_nodes
     pub fn update_distances(&mut self, source: usize, sink: usize) {
+        self.update_distances_by(source, sink, |residual_capacity| residual_capacity > Flow::zero());
+    }
+
+    // update_distances only along the edges of residual capacity at least delta, for capacity scaling
+    pub fn update_distances_with_threshold(&mut self, source: usize, sink: usize, delta: Flow) {
+        self.update_distances_by(source, sink, |residual_capacity| residual_capacity >= delta);
+    }
+
+    fn update_distances_by(&mut self, source: usize, sink: usize, is_residual: impl Fn(Flow) -> bool) {
         self.que.clear();
         self.que.push_back(sink);
         self.distances.fill(self.num_nodes);
         self.distances[sink] = 0;
 
         while let Some(v) = self.que.pop_front() {
-            for e in self.inside_edge_list[self.start[v]..self.start[v + 1]].iter() {
-                // e.to -> v
-                if e.flow > Flow::zero() && self.distances[e.to] == self.num_nodes {
-                    self.distances[e.to] = self.distances[v] + 1;
-                    if e.to != source {
-                        self.que.push_back(e.to);
+            for e in self.inside_edge_list[self.start[v].index()..self.start[v + 1].index()].iter() {
+                // e.to -> v, of which the residual capacity is e.flow
+                let to = e.to.index();
+                if is_residual(e.flow.clone()) && self.distances[to] == self.num_nodes {
+                    self.distances[to] = self.distances[v] + 1;
+                    if to != source {
+                        self.que.push_back(to);
                     }
                 }
             }
         }
     }
 
+    // nodes reachable from source in the residual network
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        let reachable = self.reachable_nodes(source);
+        (0..self.num_nodes).filter(|&u| reachable[u]).collect()
+    }
+
+    // edges from the source side to the sink side of the minimum cut
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.cut_edges(&self.reachable_nodes(source))
+    }
+
+    // the minimum cut with the fewest nodes on the source side: the nodes reachable from source in the residual network.
+    // return the source side and the edges leaving it
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        let is_source_side = self.reachable_nodes(source);
+        assert!(!is_source_side[sink], "the flow is not maximum");
+        ((0..self.num_nodes).filter(|&u| is_source_side[u]).collect(), self.cut_edges(&is_source_side))
+    }
+
+    // the minimum cut with the fewest nodes on the sink side: the nodes that cannot reach sink in the residual network are on the source side
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        let is_source_side: Vec<bool> = self.reaching_nodes(sink).into_iter().map(|reaches| !reaches).collect();
+        assert!(is_source_side[source], "the flow is not maximum");
+        ((0..self.num_nodes).filter(|&u| is_source_side[u]).collect(), self.cut_edges(&is_source_side))
+    }
+
+    // the minimum cut is unique if and only if the two extreme ones coincide,
+    // i.e. every node is reachable from source or reaches sink in the residual network
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        let (reachable, reaching) = (self.reachable_nodes(source), self.reaching_nodes(sink));
+        (0..self.num_nodes).all(|u| reachable[u] || reaching[u])
+    }
+
+    fn cut_edges(&self, is_source_side: &[bool]) -> Vec<usize> {
+        (0..self.num_edges)
+            .filter(|&edge_id| {
+                let edge = &self.inside_edge_list[self.edge_index_to_inside_edge_index[edge_id].index()];
+                let from = self.inside_edge_list[edge.rev.index()].to;
+                is_source_side[from.index()] && !is_source_side[edge.to.index()]
+            })
+            .collect()
+    }
+
+    // nodes from which sink is reachable in the residual network
+    pub fn reaching_nodes(&self, sink: usize) -> Vec<bool> {
+        let mut visited = vec![false; self.num_nodes];
+        let mut que = VecDeque::from([sink]);
+        visited[sink] = true;
+
+        while let Some(v) = que.pop_front() {
+            for e in self.neighbors(v) {
+                // e.to -> v
+                let to = e.to.index();
+                if !visited[to] && self.inside_edge_list[e.rev.index()].residual_capacity() > Flow::zero() {
+                    visited[to] = true;
+                    que.push_back(to);
+                }
+            }
+        }
+        visited
+    }
+
+    pub fn reachable_nodes(&self, source: usize) -> Vec<bool> {
+        let mut visited = vec![false; self.num_nodes];
+        let mut que = VecDeque::from([source]);
+        visited[source] = true;
+
+        while let Some(u) = que.pop_front() {
+            for e in self.neighbors(u) {
+                let to = e.to.index();
+                if !visited[to] && e.residual_capacity() > Flow::zero() {
+                    visited[to] = true;
+                    que.push_back(to);
+                }
+            }
+        }
+        visited
+    }
+
     #[inline]
     pub fn is_admissible_edge(&self, from: usize, i: usize) -> bool {
-        self.inside_edge_list[i].residual_capacity() > Flow::zero() && self.distances[from] == self.distances[self.inside_edge_list[i].to] + 1
+        self.inside_edge_list[i].residual_capacity() > Flow::zero() && self.distances[from] == self.distances[self.inside_edge_list[i].to.index()] + 1
+    }
+
+    #[inline]
+    pub fn is_admissible_edge_with_threshold(&self, from: usize, i: usize, delta: Flow) -> bool {
+        self.inside_edge_list[i].residual_capacity() >= delta && self.distances[from] == self.distances[self.inside_edge_list[i].to.index()] + 1
     }
 }
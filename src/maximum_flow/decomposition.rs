@@ -0,0 +1,110 @@
+use crate::maximum_flow::graph::Graph;
+use num_traits::NumAssign;
+
+// decomposes the flow of a solved graph into simple paths from source to sink, each with the edge ids in order and the amount.
+// the flow on cycles is canceled first and is not in any path, so the amounts summed per edge are the flow of the edge minus its cycle flow
+// (the solvers leave no flow on cycles around a path, but a flow given by the user may have one).
+// every path empties at least one edge, so there are at most num_edges paths
+pub fn decompose<Flow>(source: usize, sink: usize, graph: &Graph<Flow>) -> Vec<(Vec<usize>, Flow)>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    assert!(source < graph.num_nodes() && sink < graph.num_nodes() && source != sink);
+
+    let mut flows: Vec<Flow> = graph.edges.iter().map(|edge| edge.flow).collect();
+    let mut out_edges = vec![Vec::new(); graph.num_nodes()];
+    for (edge_id, edge) in graph.edges.iter().enumerate().filter(|(_, edge)| edge.flow > Flow::zero()) {
+        out_edges[edge.from].push(edge_id);
+    }
+
+    cancel_cycles(graph, &mut flows, &out_edges);
+
+    // what is left is acyclic and conserved except at source and sink, so a walk from source ends at sink
+    let mut current_edge = vec![0; graph.num_nodes()];
+    let mut next_edge = |u: usize, flows: &[Flow]| {
+        while current_edge[u] < out_edges[u].len() && flows[out_edges[u][current_edge[u]]] == Flow::zero() {
+            current_edge[u] += 1;
+        }
+        out_edges[u].get(current_edge[u]).copied()
+    };
+
+    let mut paths = Vec::new();
+    while let Some(edge_id) = next_edge(source, &flows) {
+        let mut path = vec![edge_id];
+        while graph.edges[*path.last().unwrap()].to != sink {
+            let u = graph.edges[*path.last().unwrap()].to;
+            path.push(next_edge(u, &flows).expect("the flow is not conserved"));
+        }
+
+        let amount = path.iter().map(|&edge_id| flows[edge_id]).min().unwrap();
+        for &edge_id in path.iter() {
+            flows[edge_id] -= amount;
+        }
+        paths.push((path, amount));
+    }
+    paths
+}
+
+// depth-first search on the edges with flow. a back edge closes a cycle, which is canceled by its minimum flow
+fn cancel_cycles<Flow>(graph: &Graph<Flow>, flows: &mut [Flow], out_edges: &[Vec<usize>])
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    const UNVISITED: u8 = 0;
+    const ON_STACK: u8 = 1;
+    const DONE: u8 = 2;
+
+    let num_nodes = graph.num_nodes();
+    let mut state = vec![UNVISITED; num_nodes];
+    let mut position = vec![0; num_nodes]; // the position of a node on the stack
+    let mut current_edge = vec![0; num_nodes];
+
+    for root in 0..num_nodes {
+        if state[root] != UNVISITED {
+            continue;
+        }
+        // stack[i] is entered by path[i - 1]
+        let (mut stack, mut path) = (vec![root], Vec::new());
+        state[root] = ON_STACK;
+        position[root] = 0;
+
+        while let Some(&u) = stack.last() {
+            while current_edge[u] < out_edges[u].len() {
+                let edge_id = out_edges[u][current_edge[u]];
+                if flows[edge_id] > Flow::zero() && state[graph.edges[edge_id].to] != DONE {
+                    break;
+                }
+                current_edge[u] += 1;
+            }
+
+            let Some(&edge_id) = out_edges[u].get(current_edge[u]) else {
+                state[u] = DONE;
+                stack.pop();
+                path.pop();
+                continue;
+            };
+
+            let v = graph.edges[edge_id].to;
+            if state[v] == UNVISITED {
+                state[v] = ON_STACK;
+                position[v] = stack.len();
+                stack.push(v);
+                path.push(edge_id);
+                continue;
+            }
+
+            // v is on the stack
+            path.push(edge_id);
+            let cycle = path.split_off(position[v]);
+            let amount = cycle.iter().map(|&edge_id| flows[edge_id]).min().unwrap();
+            for &edge_id in cycle.iter() {
+                flows[edge_id] -= amount;
+            }
+
+            // back to v, the nodes above it are searched again
+            for w in stack.drain(position[v] + 1..) {
+                state[w] = UNVISITED;
+            }
+        }
+    }
+}
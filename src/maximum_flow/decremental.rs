@@ -0,0 +1,123 @@
+use crate::ids::{EdgeId, NodeId};
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::is_bad_terminals;
+use crate::maximum_flow::status::Status;
+use num_traits::NumAssign;
+use std::collections::VecDeque;
+
+// graph holds a maximum flow from source to sink (written by any solve), and it stays maximum after the upper of edge_id is lowered to new_upper.
+// the flow over new_upper is rerouted from the tail to the head of the edge on the residual network, what cannot be is sent back
+// from the tail to source and from sink to the head, and then the flow is augmented from source to sink again
+pub fn decrease_capacity<Flow>(source: impl Into<NodeId>, sink: impl Into<NodeId>, edge_id: impl Into<EdgeId>, new_upper: Flow, graph: &mut Graph<Flow>) -> Status
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let (source, sink, edge_id) = (source.into().index(), sink.into().index(), edge_id.into().index());
+    let num_nodes = graph.num_nodes();
+    if is_bad_terminals(num_nodes, source, sink) || edge_id >= graph.num_edges() || graph.is_preflow {
+        return Status::BadInput;
+    }
+    if new_upper < graph.lowers[edge_id] || new_upper > graph.edges[edge_id].upper {
+        return Status::BadInput;
+    }
+
+    graph.edges[edge_id].upper = new_upper;
+    let (from, to, flow) = (graph.edges[edge_id].from, graph.edges[edge_id].to, graph.edges[edge_id].flow);
+    if flow <= new_upper {
+        return Status::Optimal;
+    }
+    graph.edges[edge_id].flow = new_upper;
+
+    let mut adjacency = vec![Vec::new(); num_nodes];
+    for (edge_id, edge) in graph.edges.iter().enumerate() {
+        adjacency[edge.from].push(edge_id);
+        adjacency[edge.to].push(edge_id);
+    }
+
+    // from has flow - new_upper in surplus and to is short of it
+    let violation = flow - new_upper;
+    let rerouted = augment(graph, &adjacency, from, to, Some(violation));
+    let remaining = violation - rerouted;
+    if remaining > Flow::zero() {
+        let returned = augment(graph, &adjacency, from, source, Some(remaining));
+        let fetched = augment(graph, &adjacency, sink, to, Some(remaining));
+        debug_assert!(returned == remaining && fetched == remaining);
+        augment(graph, &adjacency, source, sink, None);
+    }
+    Status::Optimal
+}
+
+// send up to limit (unlimited if None) from start to goal along shortest residual paths, and return the amount sent
+fn augment<Flow>(graph: &mut Graph<Flow>, adjacency: &[Vec<usize>], start: usize, goal: usize, limit: Option<Flow>) -> Flow
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let mut sent = Flow::zero();
+    if start == goal {
+        return limit.unwrap_or(sent);
+    }
+
+    let num_nodes = graph.num_nodes();
+    loop {
+        if limit.is_some_and(|limit| sent >= limit) {
+            return sent;
+        }
+
+        // (edge, forward) the node was reached by
+        let mut previous: Vec<Option<(usize, bool)>> = vec![None; num_nodes];
+        let mut visited = vec![false; num_nodes];
+        visited[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(u) = queue.pop_front() {
+            if u == goal {
+                break;
+            }
+            for &edge_id in adjacency[u].iter() {
+                let edge = &graph.edges[edge_id];
+                let (v, forward) = if edge.from == u { (edge.to, true) } else { (edge.from, false) };
+                if visited[v] || residual(graph, edge_id, forward) <= Flow::zero() {
+                    continue;
+                }
+                visited[v] = true;
+                previous[v] = Some((edge_id, forward));
+                queue.push_back(v);
+            }
+        }
+        if !visited[goal] {
+            return sent;
+        }
+
+        let mut delta = limit.map(|limit| limit - sent);
+        let mut v = goal;
+        while let Some((edge_id, forward)) = previous[v] {
+            let r = residual(graph, edge_id, forward);
+            delta = Some(delta.map_or(r, |delta| delta.min(r)));
+            v = if forward { graph.edges[edge_id].from } else { graph.edges[edge_id].to };
+        }
+        let delta = delta.unwrap();
+
+        let mut v = goal;
+        while let Some((edge_id, forward)) = previous[v] {
+            if forward {
+                graph.edges[edge_id].flow += delta;
+                v = graph.edges[edge_id].from;
+            } else {
+                graph.edges[edge_id].flow -= delta;
+                v = graph.edges[edge_id].to;
+            }
+        }
+        sent += delta;
+    }
+}
+
+fn residual<Flow>(graph: &Graph<Flow>, edge_id: usize, forward: bool) -> Flow
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let edge = &graph.edges[edge_id];
+    if forward {
+        edge.upper - edge.flow
+    } else {
+        edge.flow - graph.lowers[edge_id]
+    }
+}
@@ -0,0 +1,103 @@
+use crate::maximum_flow::graph::Graph;
+use num_traits::NumAssign;
+use std::fmt::{Display, Write as _};
+use std::str::FromStr;
+
+// c <comment>
+// p max <nodes> <arcs>
+// n <id> s
+// n <id> t
+// a <from> <to> <capacity>
+// (node ids are 1-indexed)
+#[derive(PartialEq, Debug, Clone)]
+pub enum DimacsError {
+    MissingProblemLine,
+    DuplicateProblemLine { line: usize },
+    UnknownLine { line: usize },
+    InvalidNumber { line: usize },
+    NodeOutOfRange { line: usize },
+    NegativeCapacity { line: usize },
+    DuplicateTerminal { line: usize },
+    MissingSource,
+    MissingSink,
+    ArcCountMismatch { expected: usize, actual: usize },
+}
+
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Ord + Copy + Default + FromStr,
+{
+    // return the graph, the source and the sink
+    pub fn from_dimacs(text: &str) -> Result<(Self, usize, usize), DimacsError> {
+        let mut graph = Graph::default();
+        let mut num_arcs = None;
+        let (mut source, mut sink) = (None, None);
+        for (i, line) in text.lines().enumerate() {
+            let line_number = i + 1;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let node = |token: &str, num_nodes: usize| match token.parse::<usize>() {
+                Ok(id) if 1 <= id && id <= num_nodes => Ok(id - 1),
+                Ok(_) => Err(DimacsError::NodeOutOfRange { line: line_number }),
+                Err(_) => Err(DimacsError::InvalidNumber { line: line_number }),
+            };
+
+            match tokens.as_slice() {
+                [] => continue,
+                [c, ..] if c.starts_with('c') => continue,
+                ["p", "max", num_nodes, num_edges] => {
+                    if num_arcs.is_some() {
+                        return Err(DimacsError::DuplicateProblemLine { line: line_number });
+                    }
+                    let (Ok(num_nodes), Ok(num_edges)) = (num_nodes.parse::<usize>(), num_edges.parse::<usize>()) else {
+                        return Err(DimacsError::InvalidNumber { line: line_number });
+                    };
+                    graph.add_nodes(num_nodes);
+                    num_arcs = Some(num_edges);
+                }
+                ["p", ..] => return Err(DimacsError::UnknownLine { line: line_number }),
+                _ if num_arcs.is_none() => return Err(DimacsError::MissingProblemLine),
+                ["n", id, designation @ ("s" | "t")] => {
+                    let terminal = if *designation == "s" { &mut source } else { &mut sink };
+                    if terminal.is_some() {
+                        return Err(DimacsError::DuplicateTerminal { line: line_number });
+                    }
+                    *terminal = Some(node(id, graph.num_nodes())?);
+                }
+                ["a", from, to, capacity] => {
+                    let (from, to) = (node(from, graph.num_nodes())?, node(to, graph.num_nodes())?);
+                    let capacity = capacity.parse::<Flow>().map_err(|_| DimacsError::InvalidNumber { line: line_number })?;
+                    if capacity < Flow::zero() {
+                        return Err(DimacsError::NegativeCapacity { line: line_number });
+                    }
+                    graph.push_edge(from, to, capacity);
+                }
+                _ => return Err(DimacsError::UnknownLine { line: line_number }),
+            }
+        }
+
+        let expected = num_arcs.ok_or(DimacsError::MissingProblemLine)?;
+        if expected != graph.num_edges() {
+            return Err(DimacsError::ArcCountMismatch { expected, actual: graph.num_edges() });
+        }
+        let source = source.ok_or(DimacsError::MissingSource)?;
+        let sink = sink.ok_or(DimacsError::MissingSink)?;
+        Ok((graph, source, sink))
+    }
+}
+
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Ord + Copy + Display,
+{
+    // the capacities only, the flow is not written
+    pub fn to_dimacs(&self, source: usize, sink: usize) -> String {
+        let mut text = String::new();
+        writeln!(text, "p max {} {}", self.num_nodes(), self.num_edges()).unwrap();
+        writeln!(text, "n {} s", source + 1).unwrap();
+        writeln!(text, "n {} t", sink + 1).unwrap();
+        for edge in self.edges.iter() {
+            writeln!(text, "a {} {} {}", edge.from + 1, edge.to + 1, edge.upper).unwrap();
+        }
+        text
+    }
+}
@@ -1,69 +1,365 @@
+use crate::clock::TimeLimit;
+use crate::cooperative::{CancellationToken, WorkCounter, YieldHook};
+use crate::ids::NodeId;
 use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
 use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
 use crate::maximum_flow::status::Status;
+use crate::metrics::{Histogram, MetricsSink, SolveMetrics, Stats, StatsLevel, PHASES_TOTAL};
 use num_traits::NumAssign;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct Dinic<Flow> {
     pub csr: CSR<Flow>,
     current_edge: Vec<usize>,
+    time_limit: Option<TimeLimit>,
+    cancellation_token: Option<CancellationToken>,
+    num_phases: usize,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+    stats_level: StatsLevel,
+    path_lengths: Histogram,
+    phase_sizes: Histogram,
+    phase_distance: usize,
+    work_counter: WorkCounter,
+    flow: Flow,       // sent so far
+    phase_flow: Flow, // sent in the current phase
+    upper: Flow,      // the capacity out of the source
+    capacity_scaling: bool,
+    scaling_delta: Option<Flow>, // the level graphs only have the edges of residual capacity at least delta with capacity scaling
+    is_in_phase: bool,           // the dfs of a phase is paused
+    has_progressed: bool,        // a path or a dead end found since the dfs started or resumed
+}
+
+// a solve that returns Status::Paused when the hook of the work counter returns Control::Pause, and goes on with resume.
+// the flows are written to the graph when it finishes
+pub struct DinicSession<'a, Flow> {
+    solver: &'a mut Dinic<Flow>,
+    source: usize,
+    sink: usize,
+    graph: &'a mut Graph<Flow>,
+    status: Status,
 }
 
 impl<Flow> Dinic<Flow>
 where
-    Flow: NumAssign + Ord + Copy,
+    Flow: NumAssign + Ord + Clone,
 {
-    pub fn solve(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
+    pub fn set_time_limit(&mut self, time_limit: TimeLimit) {
+        self.time_limit = Some(time_limit);
+    }
+
+    // looked at before each phase, the flow found so far is written to the graph with Status::Cancelled
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation_token = Some(token);
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    pub fn set_stats_level(&mut self, stats_level: StatsLevel) {
+        self.stats_level = stats_level;
+    }
+
+    // the blocking flows are found on the edges of residual capacity at least delta, from the largest power of 2 of the capacities down to 1.
+    // for integer capacities, the value is the same and the phases are fewer if a few edges have huge capacities
+    pub fn set_capacity_scaling(&mut self, enabled: bool) {
+        self.capacity_scaling = enabled;
+    }
+
+    pub fn num_phases(&self) -> usize {
+        self.num_phases
+    }
+
+    // the number of edges of each augmenting path, recorded with StatsLevel::Histograms
+    pub fn path_length_histogram(&self) -> Vec<(u64, u64)> {
+        self.path_lengths.buckets()
+    }
+
+    // the flow pushed in each phase, including the last one that finds no path
+    pub fn phase_size_histogram(&self) -> Vec<(u64, u64)> {
+        self.phase_sizes.buckets()
+    }
+
+    // hook is called every granularity arcs examined by the searches. solve goes on after Control::Pause, which only stops a session
+    pub fn set_work_counter(&mut self, granularity: u64, hook: YieldHook) {
+        self.work_counter = WorkCounter::new(granularity, hook);
+    }
+
+    // the units of work of the last solve, counted while a work counter is set
+    pub fn work_count(&self) -> u64 {
+        self.work_counter.count()
+    }
+
+    // the value of the flow found so far, which is maximum if the solve returned Status::Optimal
+    pub fn flow_value(&self) -> Flow {
+        self.flow.clone()
+    }
+
+    pub fn session<'a>(&'a mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &'a mut Graph<Flow>) -> DinicSession<'a, Flow> {
+        let (source, sink) = (source.into().index(), sink.into().index());
+        let status = match self.start(source, sink, graph) {
+            Ok(()) => Status::Paused,
+            Err(result) => result.status(),
+        };
+        DinicSession { solver: self, source, sink, graph, status }
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "dinic");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(PHASES_TOTAL, self.num_phases)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(PHASES_TOTAL, self.num_phases)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        if let Err(result) = self.start(source, sink, graph) {
+            return result;
+        }
+        loop {
+            match self.run(source, sink) {
+                Status::Paused => self.work_counter.resume(),
+                status => return FlowResult::from_csr(status, &self.csr),
+            }
+        }
+    }
+
+    fn start(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> Result<(), FlowResult<Flow>> {
+        self.flow = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return Err(FlowResult::without_flows(Status::BadInput));
+        }
         self.csr.build(graph);
         self.current_edge.resize(graph.num_nodes(), 0);
+        self.num_phases = 0;
+        self.path_lengths.clear();
+        self.phase_sizes.clear();
+        self.work_counter.start();
+        if let Some(time_limit) = self.time_limit.as_mut() {
+            time_limit.start();
+        }
+
+        self.upper = self.csr.neighbors(source).fold(Flow::zero(), |sum, e| sum + e.upper.clone());
+        (self.flow, self.is_in_phase) = (Flow::zero(), false);
+        self.scaling_delta = None;
+        if self.capacity_scaling {
+            let max_capacity = self.csr.inside_edge_list.iter().map(|e| &e.upper).max().cloned().unwrap_or(Flow::zero());
+            let mut delta = Flow::one();
+            while max_capacity >= delta && max_capacity.clone() - delta.clone() >= delta {
+                delta += delta.clone();
+            }
+            self.scaling_delta = Some(delta);
+        }
+        Ok(())
+    }
+
+    fn run(&mut self, source: usize, sink: usize) -> Status {
+        while self.flow < self.upper {
+            if !self.is_in_phase {
+                // the flow found so far is feasible
+                if self.time_limit.as_ref().is_some_and(|time_limit| time_limit.is_over()) {
+                    return Status::TimeLimit;
+                }
+                if self.cancellation_token.as_ref().is_some_and(|token| token.is_cancelled()) {
+                    return Status::Cancelled;
+                }
+
+                match self.scaling_delta.clone() {
+                    Some(delta) => self.csr.update_distances_with_threshold(source, sink, delta),
+                    None => self.csr.update_distances(source, sink),
+                }
+                self.num_phases += 1;
+                // the bfs examines each arc at most once
+                self.work_counter.tick(self.csr.inside_edge_list.len() as u64);
 
-        let upper = self.csr.neighbors(source).fold(Flow::zero(), |sum, e| sum + e.upper);
-        let mut flow = Flow::zero();
-        while flow < upper {
-            self.csr.update_distances(source, sink);
+                // no s-t path
+                if self.csr.distances[source] >= self.csr.num_nodes {
+                    if self.stats_level == StatsLevel::Histograms {
+                        self.phase_sizes.observe(0);
+                    }
+                    match self.scaling_delta.clone() {
+                        Some(delta) if delta > Flow::one() => {
+                            self.scaling_delta = Some(delta / (Flow::one() + Flow::one()));
+                            continue;
+                        }
+                        _ => break,
+                    }
+                }
 
-            // no s-t path
-            if self.csr.distances[source] >= self.csr.num_nodes {
-                break;
+                self.current_edge.iter_mut().enumerate().for_each(|(u, e)| *e = self.csr.start[u]);
+                self.phase_distance = self.csr.distances[source];
+                (self.phase_flow, self.is_in_phase) = (Flow::zero(), true);
+                if self.work_counter.is_paused() {
+                    return Status::Paused;
+                }
             }
 
-            self.current_edge.iter_mut().enumerate().for_each(|(u, e)| *e = self.csr.start[u]);
-            match self.dfs(source, sink, upper) {
-                Some(delta) => flow += delta,
-                None => break,
+            // a paused dfs keeps the current edges, so the next one goes on from them.
+            // it walks down the arcs of the current edges again, so a pause waits for some progress not to repeat the same walk
+            self.has_progressed = false;
+            let delta = self.dfs(source, sink, self.upper.clone() - self.flow.clone());
+            self.flow += delta.clone();
+            self.phase_flow += delta;
+            if self.is_paused() {
+                return Status::Paused;
+            }
+            self.is_in_phase = false;
+            if self.stats_level == StatsLevel::Histograms {
+                self.phase_sizes.observe_flow(self.phase_flow.clone());
             }
         }
 
-        self.csr.set_flow(graph);
         Status::Optimal
     }
 
-    fn dfs(&mut self, u: usize, sink: usize, upper: Flow) -> Option<Flow> {
-        if u == sink {
-            return Some(upper);
-        }
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
 
-        let mut res = Flow::zero();
-        for i in self.current_edge[u]..self.csr.start[u + 1] {
-            self.current_edge[u] = i;
-            let v = self.csr.inside_edge_list[i].to;
-            let residual_capacity = self.csr.inside_edge_list[i].residual_capacity();
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
 
-            if !self.csr.is_admissible_edge(u, i) {
-                continue;
-            }
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
 
-            if let Some(d) = self.dfs(v, sink, residual_capacity.min(upper - res)) {
-                self.csr.push_flow(i, d);
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    fn is_paused(&self) -> bool {
+        self.work_counter.is_paused() && self.has_progressed
+    }
+
+    // the blocking flow from u, with an explicit stack not to overflow the thread stack on long paths.
+    // a frame is (node, the bound of the flow through it, the flow sent from it so far), and goes down along the current edge of the node
+    fn dfs(&mut self, u: usize, sink: usize, upper: Flow) -> Flow {
+        let mut stack = vec![(u, upper, Flow::zero())];
+        let mut returned: Option<Flow> = None;
+        while let Some((u, upper, res)) = stack.last() {
+            let (u, upper, mut res) = (*u, upper.clone(), res.clone());
+            let mut next = self.current_edge[u];
+            if let Some(d) = returned.take() {
+                self.csr.push_flow(next, d.clone());
                 res += d;
-                if res == upper {
-                    return Some(res);
+                stack.last_mut().unwrap().2 = res.clone();
+                if res == upper || self.is_paused() {
+                    stack.pop();
+                    returned = Some(res);
+                    continue;
+                }
+                next += 1;
+            }
+
+            let mut is_stopped = false;
+            let mut admissible_edge = None;
+            for i in next..self.csr.start[u + 1] {
+                if self.work_counter.tick(1) && self.has_progressed {
+                    is_stopped = true;
+                    break;
+                }
+                self.current_edge[u] = i;
+                let is_admissible = match self.scaling_delta.as_ref() {
+                    Some(delta) => self.csr.is_admissible_edge_with_threshold(u, i, delta.clone()),
+                    None => self.csr.is_admissible_edge(u, i),
+                };
+                if is_admissible {
+                    admissible_edge = Some(i);
+                    break;
+                }
+            }
+
+            match admissible_edge {
+                _ if is_stopped => {
+                    stack.pop();
+                    returned = Some(res);
+                }
+                Some(i) => {
+                    let v = self.csr.inside_edge_list[i].to;
+                    let bound = self.csr.inside_edge_list[i].residual_capacity().min(upper - res);
+                    if v == sink {
+                        // every path of a phase is as long as the distance of source
+                        if self.stats_level == StatsLevel::Histograms {
+                            self.path_lengths.observe(self.phase_distance as u64);
+                        }
+                        self.has_progressed = true;
+                        returned = Some(bound);
+                    } else {
+                        stack.push((v, bound, Flow::zero()));
+                    }
+                }
+                None => {
+                    self.current_edge[u] = self.csr.start[u + 1];
+                    self.csr.distances[u] = self.csr.num_nodes;
+                    self.has_progressed = true;
+                    stack.pop();
+                    returned = Some(res);
                 }
             }
         }
-        self.current_edge[u] = self.csr.start[u + 1];
-        self.csr.distances[u] = self.csr.num_nodes;
 
-        Some(res)
+        returned.unwrap()
+    }
+}
+
+impl<Flow> DinicSession<'_, Flow>
+where
+    Flow: NumAssign + Ord + Clone,
+{
+    // Status::Paused if the hook paused it again, and the final status otherwise
+    pub fn resume(&mut self) -> Status {
+        if self.status != Status::Paused {
+            return self.status;
+        }
+        self.solver.work_counter.resume();
+        let status = self.solver.run(self.source, self.sink);
+        if status != Status::Paused {
+            commit(self.graph, &FlowResult::from_csr(status, &self.solver.csr));
+        }
+        self.status = status;
+        status
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for Dinic<Flow>
+where
+    Flow: NumAssign + Ord + Clone,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "dinic"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
     }
 }
@@ -1,52 +1,123 @@
+use crate::ids::NodeId;
 use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
 use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
 use crate::maximum_flow::status::Status;
+use crate::metrics::{Histogram, MetricsSink, SolveMetrics, Stats, StatsLevel, AUGMENTATIONS_TOTAL};
 use num_traits::NumAssign;
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct EdmondsKarp<Flow> {
     csr: CSR<Flow>,
+    num_augmentations: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+    stats_level: StatsLevel,
+    path_lengths: Histogram,
 }
 
 impl<Flow> EdmondsKarp<Flow>
 where
     Flow: NumAssign + Ord + Copy,
 {
-    pub fn solve(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    pub fn set_stats_level(&mut self, stats_level: StatsLevel) {
+        self.stats_level = stats_level;
+    }
+
+    pub fn num_augmentations(&self) -> usize {
+        self.num_augmentations
+    }
+
+    // the number of edges of each augmenting path, recorded with StatsLevel::Histograms
+    pub fn path_length_histogram(&self) -> Vec<(u64, u64)> {
+        self.path_lengths.buckets()
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // stops as soon as limit units are sent, and returns min(limit, the maximum flow).
+    // the flow sent is written to the graph, and 0 is returned for bad input
+    pub fn solve_with_limit(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, limit: Flow, graph: &mut Graph<Flow>) -> Flow {
+        let result = self.solve_ref_with_limit(source.into().index(), sink.into().index(), Some(limit), graph);
+        commit(graph, &result);
+        self.flow_value
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.solve_ref_with_limit(source.into().index(), sink.into().index(), None, graph)
+    }
+
+    fn solve_ref_with_limit(&mut self, source: usize, sink: usize, limit: Option<Flow>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "edmonds_karp");
+        let result = self.solve_inner(source, sink, limit, graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, limit: Option<Flow>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
         self.csr.build(graph);
         let mut prev = vec![(usize::MAX, usize::MAX); self.csr.num_nodes];
         let mut visited = vec![false; self.csr.num_nodes];
+        self.num_augmentations = 0;
+        self.path_lengths.clear();
 
-        loop {
+        while limit.is_none_or(|limit| self.flow_value < limit) {
             prev.fill((usize::MAX, usize::MAX));
             visited.fill(false);
 
-            // bfs
+            // bfs, each node is queued once since it is marked when queued
             let mut queue = VecDeque::from([source]);
+            visited[source] = true;
             while let Some(u) = queue.pop_front() {
-                visited[u] = true;
-                if u == sink {
-                    break;
-                }
-
                 for edge_id in self.csr.start[u]..self.csr.start[u + 1] {
                     let edge = &self.csr.inside_edge_list[edge_id];
                     if visited[edge.to] || edge.residual_capacity() == Flow::zero() {
                         continue;
                     }
 
+                    visited[edge.to] = true;
                     queue.push_back(edge.to);
                     prev[edge.to] = (u, edge_id);
                 }
+                if visited[sink] {
+                    break;
+                }
             }
 
             if !visited[sink] {
                 break;
             }
 
-            // calculate delta
+            // calculate delta, not beyond the limit
             let mut delta = self.csr.inside_edge_list[prev[sink].1].residual_capacity();
+            if let Some(limit) = limit {
+                delta = delta.min(limit - self.flow_value);
+            }
             let mut v = sink;
             while v != source {
                 let (u, edge_id) = prev[v];
@@ -56,14 +127,61 @@ where
 
             // update flow
             let mut v = sink;
+            let mut path_length = 0;
             while v != source {
                 let (u, edge_id) = prev[v];
                 self.csr.push_flow(edge_id, delta);
                 v = u;
+                path_length += 1;
+            }
+            self.flow_value += delta;
+            self.num_augmentations += 1;
+            if self.stats_level == StatsLevel::Histograms {
+                self.path_lengths.observe(path_length);
             }
         }
 
-        self.csr.set_flow(graph);
-        Status::Optimal
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for EdmondsKarp<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "edmonds_karp"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
     }
 }
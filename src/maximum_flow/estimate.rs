@@ -0,0 +1,305 @@
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::is_bad_terminals;
+use crate::maximum_flow::status::Status;
+use num_traits::{NumAssign, ToPrimitive};
+use std::collections::VecDeque;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Effort {
+    // the source and sink degree cuts, and a few augmenting paths found by dfs. O(n + m)
+    Cheap,
+    // the minimum bfs layer cut, and the blocking flow of the first dinic phase
+    Moderate,
+}
+
+const NUM_CHEAP_PATHS: usize = 8;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Bounds<Flow> {
+    pub lower: Flow,
+    pub upper: Flow,
+    flows: Vec<Flow>,
+    cut: Vec<usize>,
+    num_operations: usize,
+}
+
+impl<Flow> Bounds<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    // a feasible flow whose value is lower
+    pub fn flows(&self) -> &[Flow] {
+        &self.flows
+    }
+
+    // source side of a cut whose capacity is upper
+    pub fn cut(&self) -> &[usize] {
+        &self.cut
+    }
+
+    // the number of scanned edges and nodes
+    pub fn num_operations(&self) -> usize {
+        self.num_operations
+    }
+
+    // checks both witnesses against the graph
+    pub fn verify(&self, source: usize, sink: usize, graph: &Graph<Flow>) -> bool {
+        if self.lower > self.upper || self.flows.len() != graph.num_edges() {
+            return false;
+        }
+
+        let mut excesses = vec![Flow::zero(); graph.num_nodes()];
+        for (edge, &flow) in graph.edges.iter().zip(self.flows.iter()) {
+            if flow < Flow::zero() || flow > edge.upper {
+                return false;
+            }
+            excesses[edge.from] -= flow;
+            excesses[edge.to] += flow;
+        }
+        let is_conserved = (0..graph.num_nodes()).all(|u| u == source || u == sink || excesses[u] == Flow::zero());
+        if !is_conserved || excesses[sink] != self.lower {
+            return false;
+        }
+
+        let mut is_source_side = vec![false; graph.num_nodes()];
+        self.cut.iter().for_each(|&u| is_source_side[u] = true);
+        is_source_side[source] && !is_source_side[sink] && cut_capacity(graph, &is_source_side) == self.upper
+    }
+}
+
+impl<Flow> Bounds<Flow>
+where
+    Flow: NumAssign + Ord + Copy + ToPrimitive,
+{
+    // (upper - lower) / upper, 0 if upper is 0
+    pub fn gap(&self) -> f64 {
+        let (lower, upper) = (self.lower.to_f64().unwrap(), self.upper.to_f64().unwrap());
+        if upper == 0.0 {
+            0.0
+        } else {
+            (upper - lower) / upper
+        }
+    }
+}
+
+// lower <= maximum flow <= upper without solving the problem
+pub fn quick_bounds<Flow>(source: usize, sink: usize, graph: &Graph<Flow>, effort: Effort) -> Result<Bounds<Flow>, Status>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    if is_bad_terminals(graph.num_nodes(), source, sink) {
+        return Err(Status::BadInput);
+    }
+
+    let mut estimator = Estimator { csr: CSR::default(), num_operations: 0 };
+    estimator.csr.build(graph);
+
+    let num_nodes = graph.num_nodes();
+    let mut cuts = vec![vec![source], (0..num_nodes).filter(|&u| u != sink).collect()];
+    let lower = match effort {
+        Effort::Cheap => estimator.augment_paths(source, sink, NUM_CHEAP_PATHS),
+        Effort::Moderate => {
+            // before any flow is pushed, so that the layers are the ones of the original network
+            cuts.push(estimator.minimum_layer_cut(source, sink, graph));
+            estimator.blocking_flow(source, sink)
+        }
+    };
+
+    // the flow is maximum if the sink is not reachable anymore
+    let reachable = estimator.csr.reachable_nodes(source);
+    estimator.num_operations += num_nodes + estimator.csr.inside_edge_list.len();
+    if !reachable[sink] {
+        cuts.push((0..num_nodes).filter(|&u| reachable[u]).collect());
+    }
+
+    let mut best: Option<(Flow, Vec<usize>)> = None;
+    for cut in cuts {
+        let mut is_source_side = vec![false; num_nodes];
+        cut.iter().for_each(|&u| is_source_side[u] = true);
+        let capacity = cut_capacity(graph, &is_source_side);
+        estimator.num_operations += num_nodes + graph.num_edges();
+        if best.as_ref().is_none_or(|(upper, _)| capacity < *upper) {
+            best = Some((capacity, cut));
+        }
+    }
+    let (upper, cut) = best.unwrap();
+
+    let flows = (0..graph.num_edges())
+        .map(|edge_id| estimator.csr.inside_edge_list[estimator.csr.edge_index_to_inside_edge_index[edge_id]].flow)
+        .collect();
+    let bounds = Bounds { lower, upper, flows, cut, num_operations: estimator.num_operations };
+    debug_assert!(bounds.verify(source, sink, graph));
+    Ok(bounds)
+}
+
+fn cut_capacity<Flow>(graph: &Graph<Flow>, is_source_side: &[bool]) -> Flow
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    graph
+        .edges
+        .iter()
+        .filter(|edge| is_source_side[edge.from] && !is_source_side[edge.to])
+        .fold(Flow::zero(), |sum, edge| sum + edge.upper)
+}
+
+struct Estimator<Flow> {
+    csr: CSR<Flow>,
+    num_operations: usize,
+}
+
+impl<Flow> Estimator<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    // at most num_paths augmenting paths, O(num_paths * (n + m))
+    fn augment_paths(&mut self, source: usize, sink: usize, num_paths: usize) -> Flow {
+        let mut flow = Flow::zero();
+        for _ in 0..num_paths {
+            let Some(path) = self.find_path(source, sink) else {
+                break;
+            };
+            let delta = path.iter().map(|&i| self.csr.inside_edge_list[i].residual_capacity()).min().unwrap();
+            path.iter().for_each(|&i| self.csr.push_flow(i, delta));
+            flow += delta;
+        }
+        flow
+    }
+
+    // dfs in the residual network, returns the inside edges of the path
+    fn find_path(&mut self, source: usize, sink: usize) -> Option<Vec<usize>> {
+        let mut visited = vec![false; self.csr.num_nodes];
+        let mut current_edge = self.csr.start.clone();
+        self.num_operations += self.csr.num_nodes;
+
+        let mut path = Vec::new();
+        let mut u = source;
+        visited[source] = true;
+        while u != sink {
+            let mut next = None;
+            while current_edge[u] < self.csr.start[u + 1] {
+                let i = current_edge[u];
+                current_edge[u] += 1;
+                self.num_operations += 1;
+                let edge = &self.csr.inside_edge_list[i];
+                if !visited[edge.to] && edge.residual_capacity() > Flow::zero() {
+                    next = Some(i);
+                    break;
+                }
+            }
+
+            match next {
+                Some(i) => {
+                    path.push(i);
+                    u = self.csr.inside_edge_list[i].to;
+                    visited[u] = true;
+                }
+                // retreat, no path if the source is a dead end
+                None => {
+                    let i = path.pop()?;
+                    u = self.csr.inside_edge_list[self.csr.inside_edge_list[i].rev].to;
+                }
+            }
+        }
+        Some(path)
+    }
+
+    // one phase of dinic
+    fn blocking_flow(&mut self, source: usize, sink: usize) -> Flow {
+        let num_nodes = self.csr.num_nodes;
+        self.csr.update_distances(source, sink);
+        self.num_operations += num_nodes + self.csr.inside_edge_list.len();
+
+        let mut flow = Flow::zero();
+        if self.csr.distances[source] >= num_nodes {
+            return flow;
+        }
+
+        let mut current_edge = self.csr.start.clone();
+        let mut path: Vec<usize> = Vec::new();
+        let mut u = source;
+        loop {
+            if u == sink {
+                let delta = path.iter().map(|&i| self.csr.inside_edge_list[i].residual_capacity()).min().unwrap();
+                path.iter().for_each(|&i| self.csr.push_flow(i, delta));
+                flow += delta;
+                path.clear();
+                u = source;
+                continue;
+            }
+
+            let mut next = None;
+            while current_edge[u] < self.csr.start[u + 1] {
+                let i = current_edge[u];
+                self.num_operations += 1;
+                if self.csr.is_admissible_edge(u, i) {
+                    next = Some(i);
+                    break;
+                }
+                current_edge[u] += 1;
+            }
+
+            match next {
+                Some(i) => {
+                    path.push(i);
+                    u = self.csr.inside_edge_list[i].to;
+                }
+                None => {
+                    if u == source {
+                        break;
+                    }
+                    // dead end
+                    self.csr.distances[u] = num_nodes;
+                    let i = path.pop().unwrap();
+                    u = self.csr.inside_edge_list[self.csr.inside_edge_list[i].rev].to;
+                    current_edge[u] += 1;
+                }
+            }
+        }
+        flow
+    }
+
+    // the cheapest of the cuts {v | distance from source to v <= i} for i < distance to sink
+    fn minimum_layer_cut(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> Vec<usize> {
+        let num_nodes = self.csr.num_nodes;
+        let mut distances = vec![usize::MAX; num_nodes];
+        let mut que = VecDeque::from([source]);
+        distances[source] = 0;
+        while let Some(u) = que.pop_front() {
+            for edge in self.csr.neighbors(u) {
+                self.num_operations += 1;
+                if distances[edge.to] == usize::MAX && edge.residual_capacity() > Flow::zero() {
+                    distances[edge.to] = distances[u] + 1;
+                    que.push_back(edge.to);
+                }
+            }
+        }
+        self.num_operations += num_nodes + graph.num_edges();
+
+        // unreachable sink, a cut of capacity 0
+        if distances[sink] == usize::MAX {
+            return (0..num_nodes).filter(|&u| distances[u] != usize::MAX).collect();
+        }
+
+        // an edge from layer a to layer b crosses the cuts a, a + 1, ..., b - 1
+        let depth = distances[sink];
+        let mut diff = vec![Flow::zero(); depth + 1];
+        for edge in graph.edges.iter() {
+            let (a, b) = (distances[edge.from], distances[edge.to].min(depth));
+            if a < b {
+                diff[a] += edge.upper;
+                diff[b] -= edge.upper;
+            }
+        }
+
+        let (mut capacity, mut best) = (Flow::zero(), (diff[0], 0));
+        for (i, &d) in diff.iter().enumerate().take(depth) {
+            capacity += d;
+            if capacity < best.0 {
+                best = (capacity, i);
+            }
+        }
+        (0..num_nodes).filter(|&u| distances[u] <= best.1).collect()
+    }
+}
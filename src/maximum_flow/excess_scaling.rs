@@ -0,0 +1,217 @@
+use crate::ids::NodeId;
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
+use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PHASES_TOTAL};
+use num_traits::NumAssign;
+use std::sync::Arc;
+
+// the excess scaling push relabel algorithm of Ahuja and Orlin, O(nm + n^2 log U).
+// in the phase of delta, only the nodes with an excess of at least delta / 2 are discharged, the one with the lowest distance first,
+// and a push never makes an excess larger than delta
+#[derive(Default)]
+pub struct ExcessScaling<Flow> {
+    csr: CSR<Flow>,
+    excesses: Vec<Flow>,
+    current_edge: Vec<usize>,
+    buckets: Vec<Vec<usize>>, // the nodes with a large excess by their distances
+    num_phases: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+}
+
+impl<Flow> ExcessScaling<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "excess_scaling");
+        self.stats = Stats::default();
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats.num_phases = self.num_phases;
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(PHASES_TOTAL, self.num_phases)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
+        self.csr.build(graph);
+        self.num_phases = 0;
+        self.pre_process(source, sink);
+
+        // the smallest power of 2 not less than every excess, or the largest one that does not overflow
+        let max_excess = (0..self.csr.num_nodes).filter(|&u| u != source && u != sink).map(|u| self.excesses[u]).max().unwrap_or(Flow::zero());
+        let mut delta = Flow::one();
+        while delta < max_excess && delta <= max_excess - delta {
+            delta += delta;
+        }
+
+        let two = Flow::one() + Flow::one();
+        while delta > Flow::zero() {
+            self.num_phases += 1;
+            self.discharge_large_excesses(source, sink, delta);
+            delta /= two;
+        }
+
+        self.flow_value = self.excesses[sink];
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    // the exact distances to sink as PushRelabelFIFO, and the edges out of source are saturated
+    fn pre_process(&mut self, source: usize, sink: usize) {
+        let n = self.csr.num_nodes;
+        self.excesses.clear();
+        self.excesses.resize(n, Flow::zero());
+        self.current_edge.clear();
+        self.current_edge.extend_from_slice(&self.csr.start[..n]);
+        // the distances are less than 2n, since every node with an excess has a residual path to source
+        self.buckets.iter_mut().for_each(|bucket| bucket.clear());
+        self.buckets.resize(2 * n, Vec::new());
+
+        self.csr.update_distances(source, sink);
+        self.csr.distances[source] = n;
+
+        for inside_edge_index in self.csr.start[source]..self.csr.start[source + 1] {
+            let edge = &self.csr.inside_edge_list[inside_edge_index];
+            let delta = edge.residual_capacity();
+            self.excesses[edge.to] += delta;
+            self.csr.push_flow(inside_edge_index, delta);
+        }
+    }
+
+    // excess >= delta / 2
+    fn is_large(&self, u: usize, delta: Flow) -> bool {
+        self.excesses[u] > Flow::zero() && self.excesses[u] >= delta - self.excesses[u]
+    }
+
+    fn discharge_large_excesses(&mut self, source: usize, sink: usize, delta: Flow) {
+        let mut level = usize::MAX;
+        for u in 0..self.csr.num_nodes {
+            if u != source && u != sink && self.is_large(u, delta) {
+                self.buckets[self.csr.distances[u]].push(u);
+                level = level.min(self.csr.distances[u]);
+            }
+        }
+
+        while level < self.buckets.len() {
+            let Some(u) = self.buckets[level].pop() else {
+                level += 1;
+                continue;
+            };
+
+            match (self.current_edge[u]..self.csr.start[u + 1]).find(|&i| self.csr.is_admissible_edge(u, i)) {
+                Some(i) => {
+                    self.current_edge[u] = i;
+                    let to = self.csr.inside_edge_list[i].to;
+                    let was_large = self.is_large(to, delta);
+                    self.push(u, i, source, sink, delta);
+
+                    // to is one below u, and becomes the lowest node with a large excess
+                    if to != source && to != sink && !was_large && self.is_large(to, delta) {
+                        self.buckets[self.csr.distances[to]].push(to);
+                        level = self.csr.distances[to];
+                    }
+                    if self.is_large(u, delta) {
+                        self.buckets[self.csr.distances[u]].push(u);
+                        level = level.min(self.csr.distances[u]);
+                    }
+                }
+                None => {
+                    self.relabel(u);
+                    self.buckets[self.csr.distances[u]].push(u);
+                }
+            }
+        }
+    }
+
+    // the lowest node with a large excess is chosen, so the excess of to is less than delta / 2 unless to is source or sink,
+    // and at least delta / 2 is sent unless the edge is saturated
+    fn push(&mut self, u: usize, i: usize, source: usize, sink: usize, delta: Flow) {
+        let to = self.csr.inside_edge_list[i].to;
+        let mut flow = self.excesses[u].min(self.csr.inside_edge_list[i].residual_capacity());
+        if to != source && to != sink {
+            flow = flow.min(delta - self.excesses[to]);
+        }
+        self.csr.push_flow(i, flow);
+        self.stats.num_pushes += 1;
+        self.excesses[u] -= flow;
+        self.excesses[to] += flow;
+    }
+
+    fn relabel(&mut self, u: usize) {
+        self.stats.num_relabels += 1;
+        self.csr.distances[u] = self
+            .csr
+            .neighbors(u)
+            .filter(|edge| edge.residual_capacity() > Flow::zero())
+            .map(|edge| self.csr.distances[edge.to] + 1)
+            .min()
+            .unwrap();
+        self.current_edge[u] = self.csr.start[u];
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for ExcessScaling<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "excess_scaling"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
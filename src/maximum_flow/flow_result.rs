@@ -0,0 +1,61 @@
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::status::Status;
+use num_traits::NumAssign;
+
+// the outcome of solve_ref, computed without writing to the graph.
+// flows are indexed by the edge ids returned by add_directed_edge
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlowResult<Flow> {
+    status: Status,
+    flows: Option<Vec<Flow>>, // None if the solver did not compute any flow (e.g. Status::BadInput)
+    is_preflow: bool,         // the flows may leave excesses at nodes other than source and sink
+}
+
+impl<Flow> FlowResult<Flow>
+where
+    Flow: NumAssign + Ord + Clone,
+{
+    pub(crate) fn from_csr(status: Status, csr: &CSR<Flow>) -> Self {
+        Self { status, flows: Some(csr.flows()), is_preflow: false }
+    }
+
+    pub(crate) fn preflow_from_csr(status: Status, csr: &CSR<Flow>) -> Self {
+        Self { status, flows: Some(csr.flows()), is_preflow: true }
+    }
+
+    pub(crate) fn without_flows(status: Status) -> Self {
+        Self { status, flows: None, is_preflow: false }
+    }
+
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    #[inline]
+    pub fn flows(&self) -> Option<&[Flow]> {
+        self.flows.as_deref()
+    }
+
+    #[inline]
+    pub fn is_preflow(&self) -> bool {
+        self.is_preflow
+    }
+}
+
+// write the flows of result to the graph it was computed on
+pub fn commit<Flow>(graph: &mut Graph<Flow>, result: &FlowResult<Flow>)
+where
+    Flow: NumAssign + Ord + Clone,
+{
+    let Some(flows) = result.flows.as_ref() else {
+        return;
+    };
+    assert_eq!(flows.len(), graph.num_edges());
+    for (edge, flow) in graph.edges.iter_mut().zip(flows.iter()) {
+        edge.flow = flow.clone();
+    }
+    graph.is_preflow = result.is_preflow;
+}
@@ -1,52 +1,155 @@
+use crate::ids::NodeId;
 use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
 use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
 use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, AUGMENTATIONS_TOTAL};
 use num_traits::NumAssign;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct FordFulkerson<Flow> {
     csr: CSR<Flow>,
+    // u is visited by the current search if visited[u] == epoch, so that the stamps are not cleared for every search
+    visited: Vec<usize>,
+    epoch: usize,
+    stack: Vec<(usize, usize)>, // (node, the edge being examined) from source
+    num_augmentations: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> FordFulkerson<Flow>
 where
     Flow: NumAssign + Ord + Copy,
 {
-    pub fn solve(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // the depth first searches of the last solve, one more than the augmentations
+    pub fn num_searches(&self) -> usize {
+        self.epoch
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "ford_fulkerson");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
         self.csr.build(graph);
-        let mut visited = vec![false; self.csr.num_nodes];
+        self.visited.clear();
+        self.visited.resize(self.csr.num_nodes, 0);
+        self.epoch = 0;
+        self.num_augmentations = 0;
 
         let upper = self.csr.neighbors(source).fold(Flow::zero(), |sum, e| sum + e.upper);
-        let mut flow = Flow::zero();
-        loop {
-            visited.fill(false);
-            match self.dfs(source, sink, upper, &mut visited) {
-                Some(delta) => flow += delta,
-                None => break,
-            }
+        while let Some(delta) = self.dfs(source, sink, upper) {
+            self.flow_value += delta;
+            self.num_augmentations += 1;
         }
 
-        self.csr.set_flow(graph);
-        Status::Optimal
+        FlowResult::from_csr(Status::Optimal, &self.csr)
     }
 
-    fn dfs(&mut self, u: usize, sink: usize, flow: Flow, visited: &mut Vec<bool>) -> Option<Flow> {
-        if u == sink {
-            return Some(flow);
-        }
-        visited[u] = true;
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    // the edges are examined in the same order as the recursive search, with an explicit stack not to overflow on long paths
+    fn dfs(&mut self, source: usize, sink: usize, flow: Flow) -> Option<Flow> {
+        self.epoch += 1;
+        self.visited[source] = self.epoch;
+        self.stack.clear();
+        self.stack.push((source, self.csr.start[source]));
+
+        while let Some(&(u, edge_id)) = self.stack.last() {
+            if edge_id == self.csr.start[u + 1] {
+                // a dead end
+                self.stack.pop();
+                if let Some(parent) = self.stack.last_mut() {
+                    parent.1 += 1;
+                }
+                continue;
+            }
 
-        for edge_id in self.csr.start[u]..self.csr.start[u + 1] {
             let edge = &self.csr.inside_edge_list[edge_id];
-            if visited[edge.to] || edge.residual_capacity() == Flow::zero() {
+            if self.visited[edge.to] == self.epoch || edge.residual_capacity() == Flow::zero() {
+                self.stack.last_mut().unwrap().1 += 1;
                 continue;
             }
 
-            if let Some(d) = self.dfs(edge.to, sink, flow.min(edge.residual_capacity()), visited) {
-                self.csr.push_flow(edge_id, d);
-                return Some(d);
+            if edge.to == sink {
+                let delta = self.stack.iter().fold(flow, |delta, &(_, edge_id)| delta.min(self.csr.inside_edge_list[edge_id].residual_capacity()));
+                for &(_, edge_id) in self.stack.iter() {
+                    self.csr.push_flow(edge_id, delta);
+                }
+                return Some(delta);
             }
+            self.visited[edge.to] = self.epoch;
+            self.stack.push((edge.to, self.csr.start[edge.to]));
         }
         None
     }
 }
+
+impl<Flow> MaximumFlowSolver<Flow> for FordFulkerson<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "ford_fulkerson"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
@@ -0,0 +1,122 @@
+use crate::maximum_flow::dinic::Dinic;
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::status::Status;
+use num_traits::NumAssign;
+
+// the minimum cuts between all pairs of nodes of an undirected graph.
+// node u hangs from parent[u] through an edge of weight[u], and node 0 is the root
+pub struct GomoryHuTree<Flow> {
+    parent: Vec<usize>,
+    weight: Vec<Flow>,
+}
+
+// every edge (u, v, c) of graph is taken as an undirected edge of capacity c.
+// gusfield's algorithm, n - 1 maximum flows by dinic on the graph itself without contractions
+pub fn gomory_hu<Flow>(graph: &Graph<Flow>) -> GomoryHuTree<Flow>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    let num_nodes = graph.num_nodes();
+    let mut network = Graph::default();
+    network.add_nodes(num_nodes);
+    for edge in graph.edges.iter() {
+        network.push_edge(edge.from, edge.to, edge.upper);
+        network.push_edge(edge.to, edge.from, edge.upper);
+    }
+
+    let (mut parent, mut weight) = (vec![0; num_nodes], vec![Flow::zero(); num_nodes]);
+    let mut dinic = Dinic::default();
+    let mut is_source_side = vec![false; num_nodes];
+    for s in 1..num_nodes {
+        let t = parent[s];
+        assert_eq!(dinic.solve_ref(s, t, &network).status(), Status::Optimal);
+        let value = dinic.min_cut_edges(s).into_iter().fold(Flow::zero(), |sum, edge_id| sum + network.edges[edge_id].upper);
+        is_source_side.fill(false);
+        dinic.minimum_cut(s).into_iter().for_each(|u| is_source_side[u] = true);
+
+        weight[s] = value;
+        for u in 0..num_nodes {
+            if u != s && is_source_side[u] && parent[u] == t {
+                parent[u] = s;
+            }
+        }
+        // s takes the place of t below the parent of t
+        if is_source_side[parent[t]] {
+            (parent[s], parent[t]) = (parent[t], s);
+            (weight[s], weight[t]) = (weight[t], value);
+        }
+    }
+    GomoryHuTree { parent, weight }
+}
+
+impl<Flow> GomoryHuTree<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    pub fn num_nodes(&self) -> usize {
+        self.parent.len()
+    }
+
+    // (u, parent of u, the minimum cut value between them) for each node u but the root
+    pub fn edges(&self) -> Vec<(usize, usize, Flow)> {
+        (1..self.num_nodes()).map(|u| (u, self.parent[u], self.weight[u])).collect()
+    }
+
+    // the minimum total capacity of the edges whose removal disconnects u and v, O(n)
+    pub fn min_cut_value(&self, u: usize, v: usize) -> Result<Flow, Status> {
+        let (x, _) = self.lightest_edge(u, v)?;
+        Ok(self.weight[x])
+    }
+
+    // the side of u of a minimum cut between u and v, O(n)
+    pub fn minimum_cut(&self, u: usize, v: usize) -> Result<Vec<usize>, Status> {
+        let (x, is_on_u_side) = self.lightest_edge(u, v)?;
+
+        // removing the edge from x to its parent leaves the subtree of x
+        let mut children = vec![Vec::new(); self.num_nodes()];
+        for w in 1..self.num_nodes() {
+            children[self.parent[w]].push(w);
+        }
+        let mut is_in_subtree = vec![false; self.num_nodes()];
+        let mut stack = vec![x];
+        while let Some(w) = stack.pop() {
+            is_in_subtree[w] = true;
+            stack.extend(children[w].iter().copied());
+        }
+        Ok((0..self.num_nodes()).filter(|&w| is_in_subtree[w] == is_on_u_side).collect())
+    }
+
+    // the node x whose edge to its parent is the lightest on the tree path between u and v, and whether x is on the side of u
+    fn lightest_edge(&self, u: usize, v: usize) -> Result<(usize, bool), Status> {
+        if u >= self.num_nodes() || v >= self.num_nodes() || u == v {
+            return Err(Status::BadInput);
+        }
+
+        let mut is_ancestor_of_u = vec![false; self.num_nodes()];
+        let mut w = u;
+        while w != 0 {
+            is_ancestor_of_u[w] = true;
+            w = self.parent[w];
+        }
+        is_ancestor_of_u[0] = true;
+
+        // up from v to the lowest common ancestor, then up from u to it
+        let mut best: Option<(usize, bool)> = None;
+        let mut w = v;
+        while !is_ancestor_of_u[w] {
+            if best.is_none_or(|(x, _)| self.weight[w] < self.weight[x]) {
+                best = Some((w, false));
+            }
+            w = self.parent[w];
+        }
+        let lca = w;
+        let mut w = u;
+        while w != lca {
+            if best.is_none_or(|(x, _)| self.weight[w] < self.weight[x]) {
+                best = Some((w, true));
+            }
+            w = self.parent[w];
+        }
+        Ok(best.unwrap())
+    }
+}
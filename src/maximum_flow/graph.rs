@@ -1,8 +1,11 @@
+use crate::compaction::CompactionMap;
+use crate::graph_error::{check_nodes, GraphError};
+use crate::ids::{EdgeId, NodeId};
 use num_traits::NumAssign;
-use std::collections::VecDeque;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display, Write as _};
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge<Flow> {
     pub from: usize,
     pub to: usize,
@@ -10,17 +13,20 @@ pub struct Edge<Flow> {
     pub upper: Flow,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Graph<Flow> {
     num_nodes: usize,
     num_edges: usize,
     pub(crate) edges: Vec<Edge<Flow>>,
     pub(crate) excesses: Vec<Flow>,
+    pub(crate) lowers: Vec<Flow>, // only solve_with_lower_bounds keeps the flows above them
+    pub(crate) is_preflow: bool,  // written by a solve that kept the excesses, see ReturnExcessPolicy
 }
 
 impl<Flow> Graph<Flow>
 where
-    Flow: NumAssign + Ord + Copy,
+    Flow: NumAssign + Ord + Clone,
 {
     #[inline]
     pub fn num_nodes(&self) -> usize {
@@ -32,70 +38,190 @@ where
         self.num_edges
     }
 
-    pub fn add_node(&mut self) -> usize {
+    pub fn add_node(&mut self) -> NodeId {
+        NodeId(self.add_node_raw())
+    }
+
+    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<NodeId> {
+        self.add_nodes_raw(num_nodes).into_iter().map(NodeId).collect()
+    }
+
+    // return edge id, or why the edge is rejected
+    pub fn add_directed_edge(&mut self, from: impl Into<NodeId>, to: impl Into<NodeId>, upper: Flow) -> Result<EdgeId, GraphError<Flow>> {
+        self.add_directed_edge_raw(from.into().index(), to.into().index(), upper).map(EdgeId)
+    }
+
+    // an edge whose flow is at least lower, see MaximumFlowSolver::solve_with_lower_bounds
+    pub fn add_directed_edge_with_lower(&mut self, from: impl Into<NodeId>, to: impl Into<NodeId>, lower: Flow, upper: Flow) -> Result<EdgeId, GraphError<Flow>> {
+        self.add_directed_edge_with_lower_raw(from.into().index(), to.into().index(), lower, upper).map(EdgeId)
+    }
+
+    // the usize versions of the above, kept for one release
+    pub fn add_node_raw(&mut self) -> usize {
         self.excesses.push(Flow::zero());
         self.num_nodes += 1;
         self.num_nodes - 1
     }
 
-    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<usize> {
+    pub fn add_nodes_raw(&mut self, num_nodes: usize) -> Vec<usize> {
         self.excesses.extend(vec![Flow::zero(); num_nodes]);
         self.num_nodes += num_nodes;
         ((self.num_nodes - num_nodes)..self.num_nodes).collect()
     }
 
-    // return edge index
-    pub fn add_directed_edge(&mut self, from: usize, to: usize, upper: Flow) -> Option<usize> {
-        if from >= self.num_nodes || to >= self.num_nodes {
-            return None;
+    pub fn add_directed_edge_raw(&mut self, from: usize, to: usize, upper: Flow) -> Result<usize, GraphError<Flow>> {
+        check_nodes(from, to, self.num_nodes)?;
+        if upper < Flow::zero() {
+            return Err(GraphError::NegativeCapacity { upper });
         }
+        Ok(self.push_edge(from, to, upper))
+    }
 
+    pub fn add_directed_edge_with_lower_raw(&mut self, from: usize, to: usize, lower: Flow, upper: Flow) -> Result<usize, GraphError<Flow>> {
+        if lower < Flow::zero() {
+            return Err(GraphError::NegativeLower { lower });
+        }
+        if lower > upper {
+            return Err(GraphError::InvalidBounds { lower, upper });
+        }
+        let edge_id = self.add_directed_edge_raw(from, to, upper)?;
+        self.lowers[edge_id] = lower;
+        Ok(edge_id)
+    }
+
+    // add_directed_edge without the checks, for the networks the crate builds from valid graphs
+    pub(crate) fn push_edge(&mut self, from: usize, to: usize, upper: Flow) -> usize {
         self.edges.push(Edge { from, to, flow: Flow::zero(), upper });
+        self.lowers.push(Flow::zero());
 
         self.num_edges += 1;
-        Some(self.num_edges - 1)
+        self.num_edges - 1
+    }
+
+    pub fn get_lower(&self, edge_id: impl Into<EdgeId>) -> Option<Flow> {
+        self.lowers.get(edge_id.into().index()).cloned()
     }
 
-    pub fn get_edge(&self, edge_id: usize) -> Option<Edge<Flow>> {
+    pub fn get_edge(&self, edge_id: impl Into<EdgeId>) -> Option<Edge<Flow>> {
+        let edge_id = edge_id.into().index();
         if edge_id >= self.edges.len() {
             return None;
         }
         let edge = &self.edges[edge_id];
-        Some(Edge { from: edge.from, to: edge.to, flow: edge.flow, upper: edge.upper })
+        Some(edge.clone())
+    }
+
+    // the edges in the order they were added
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, Edge<Flow>)> + '_ {
+        self.edges.iter().enumerate().map(|(edge_id, edge)| (EdgeId(edge_id), edge.clone()))
+    }
+
+    // the edges carrying a flow
+    pub fn edges_with_flow(&self) -> impl Iterator<Item = (EdgeId, Edge<Flow>)> + '_ {
+        self.edges().filter(|(_, edge)| edge.flow != Flow::zero())
+    }
+
+    pub fn flow(&self, edge_id: impl Into<EdgeId>) -> Option<Flow> {
+        self.edges.get(edge_id.into().index()).map(|edge| edge.flow.clone())
+    }
+
+    // drops the edges with upper = 0 and then the nodes with no edges, and renumbers the rest in order.
+    // a source or a sink without edges is dropped as well, and its flow is zero anyway
+    pub fn compact(&mut self) -> CompactionMap {
+        let is_edge_kept: Vec<bool> = self.edges.iter().map(|edge| edge.upper != Flow::zero()).collect();
+        let mut is_node_kept = vec![false; self.num_nodes];
+        for edge in self.edges.iter().filter(|edge| edge.upper != Flow::zero()) {
+            (is_node_kept[edge.from], is_node_kept[edge.to]) = (true, true);
+        }
+
+        let map = CompactionMap::new(&is_node_kept, &is_edge_kept);
+        self.edges = self
+            .edges
+            .iter()
+            .filter(|edge| edge.upper != Flow::zero())
+            .map(|edge| Edge { from: map.translate_node(edge.from).unwrap(), to: map.translate_node(edge.to).unwrap(), ..edge.clone() })
+            .collect();
+        self.lowers = self.lowers.iter().zip(is_edge_kept.iter()).filter(|(_, &is_kept)| is_kept).map(|(lower, _)| lower.clone()).collect();
+        self.excesses = self
+            .excesses
+            .iter()
+            .zip(is_node_kept.iter())
+            .filter(|(_, &is_kept)| is_kept)
+            .map(|(excess, _)| excess.clone())
+            .collect();
+        (self.num_nodes, self.num_edges) = (self.excesses.len(), self.edges.len());
+        map
+    }
+
+    // whether the flows are a preflow, that is the last solve left excesses at nodes other than source and sink
+    pub fn is_preflow(&self) -> bool {
+        self.is_preflow
+    }
+
+    // the net flow into sink, the value of a preflow as well as of a flow
+    pub fn flow_value(&self, source: impl Into<NodeId>, sink: impl Into<NodeId>) -> Flow {
+        let (source, sink) = (source.into().index(), sink.into().index());
+        debug_assert_ne!(source, sink);
+        self.edges.iter().filter(|edge| edge.from != edge.to).fold(Flow::zero(), |mut flow, edge| {
+            if edge.to == sink {
+                flow += edge.flow.clone();
+            } else if edge.from == sink {
+                flow -= edge.flow.clone();
+            }
+            flow
+        })
+    }
+
+    // maximum_flow, or None on a preflow whose outflow of source also includes the trapped excesses
+    pub fn checked_maximum_flow(&self, source: impl Into<NodeId>) -> Option<Flow> {
+        (!self.is_preflow).then(|| self.maximum_flow(source))
     }
 
-    pub fn maximum_flow(&self, source: usize) -> Flow {
+    // the net flow out of source, which is the value only if the flow is conserved
+    pub fn maximum_flow(&self, source: impl Into<NodeId>) -> Flow {
+        let source = source.into().index();
         (0..self.num_edges).fold(Flow::zero(), |mut flow, edge_index| {
             let edge = self.get_edge(edge_index).unwrap();
-            if edge.from == source {
-                flow += edge.flow;
-            } else if edge.to == source {
-                flow -= edge.flow;
+            // the lower bound of a loop at source forces flow on it, which does not leave source
+            if edge.from == source && edge.to != source {
+                flow += edge.flow.clone();
+            } else if edge.to == source && edge.from != source {
+                flow -= edge.flow.clone();
             }
             flow
         })
     }
+}
 
-    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
-        let mut cut = Vec::new();
-        let mut visited = vec![false; self.num_nodes];
-        let mut que = VecDeque::from([source]);
+pub struct DotOptions {
+    pub show_zero_flow_edges: bool,
+}
 
-        if self.num_edges == 0 {
-            return vec![source];
-        }
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self { show_zero_flow_edges: true }
+    }
+}
 
-        while let Some(u) = que.pop_front() {
-            cut.push(u);
-            visited[u] = true;
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Ord + Clone + Display,
+{
+    // graphviz dot with the edges labeled "flow/upper". saturated edges are bold
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with_options(&DotOptions::default())
+    }
 
-            // for e in self.neighbors(u) {
-            //     if !visited[e.to] && e.residual_capacity() != Flow::zero() {
-            //         que.push_back(e.to);
-            //     }
-            // }
+    pub fn to_dot_with_options(&self, options: &DotOptions) -> String {
+        let mut dot = String::from("digraph {\n");
+        for u in 0..self.num_nodes {
+            writeln!(dot, "    {u};").unwrap();
         }
-
-        cut
+        for edge in self.edges.iter().filter(|edge| options.show_zero_flow_edges || edge.flow != Flow::zero()) {
+            let style = if edge.flow == edge.upper && edge.upper > Flow::zero() { ", style=bold" } else { "" };
+            writeln!(dot, "    {} -> {} [label=\"{}/{}\"{style}];", edge.from, edge.to, edge.flow, edge.upper).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
     }
 }
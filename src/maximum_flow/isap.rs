@@ -0,0 +1,194 @@
+use crate::ids::NodeId;
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
+use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, AUGMENTATIONS_TOTAL};
+use num_traits::NumAssign;
+use std::sync::Arc;
+
+// the improved shortest augmenting path algorithm, O(n^2 m).
+// unlike ShortestAugmentingPath, the path is advanced from where the last augmentation saturated it instead of from source,
+// and the search stops as soon as a distance below source has no node (gap), since source cannot reach sink any more
+#[derive(Default)]
+pub struct Isap<Flow> {
+    csr: CSR<Flow>,
+    current_edge: Vec<usize>,
+    distance_count: Vec<usize>,
+    path: Vec<usize>, // the edges from source to the current node
+    num_augmentations: usize,
+    num_edge_scans: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+}
+
+impl<Flow> Isap<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // the edges examined by the advances and the retreats of the last solve
+    pub fn num_edge_scans(&self) -> usize {
+        self.num_edge_scans
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "isap");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
+        self.csr.build(graph);
+        let n = self.csr.num_nodes;
+        self.csr.update_distances(source, sink);
+        self.current_edge.clear();
+        self.current_edge.extend_from_slice(&self.csr.start[..n]);
+        self.distance_count.clear();
+        self.distance_count.resize(n + 1, 0);
+        for u in 0..n {
+            self.distance_count[self.csr.distances[u]] += 1;
+        }
+        self.path.clear();
+        self.num_augmentations = 0;
+        self.num_edge_scans = 0;
+
+        let mut u = source;
+        while self.csr.distances[source] < n {
+            if u == sink {
+                u = self.augment(source);
+                continue;
+            }
+
+            // advance
+            let admissible_edge = (self.current_edge[u]..self.csr.start[u + 1]).find(|&i| {
+                self.num_edge_scans += 1;
+                self.csr.is_admissible_edge(u, i)
+            });
+            if let Some(i) = admissible_edge {
+                self.current_edge[u] = i;
+                self.path.push(i);
+                u = self.csr.inside_edge_list[i].to;
+                continue;
+            }
+
+            // retreat
+            if !self.relabel(u, source) {
+                break;
+            }
+            if let Some(i) = self.path.pop() {
+                u = self.csr.inside_edge_list[self.csr.inside_edge_list[i].rev].to;
+            }
+        }
+
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    // along the path, and return the tail of its first saturated edge to continue from
+    fn augment(&mut self, source: usize) -> usize {
+        let delta = self.path.iter().map(|&i| self.csr.inside_edge_list[i].residual_capacity()).min().unwrap();
+        for &i in self.path.iter() {
+            self.csr.push_flow(i, delta);
+        }
+        self.flow_value += delta;
+        self.num_augmentations += 1;
+
+        match self.path.iter().position(|&i| self.csr.inside_edge_list[i].residual_capacity() == Flow::zero()) {
+            Some(k) => {
+                let tail = self.csr.inside_edge_list[self.csr.inside_edge_list[self.path[k]].rev].to;
+                self.path.truncate(k);
+                tail
+            }
+            None => source,
+        }
+    }
+
+    // false if the old distance of u has no node left below source (gap)
+    fn relabel(&mut self, u: usize, source: usize) -> bool {
+        let old_distance = self.csr.distances[u];
+        self.distance_count[old_distance] -= 1;
+        if self.distance_count[old_distance] == 0 && old_distance < self.csr.distances[source] {
+            return false;
+        }
+
+        self.num_edge_scans += self.csr.start[u + 1] - self.csr.start[u];
+        let new_distance = self
+            .csr
+            .neighbors(u)
+            .filter(|edge| edge.residual_capacity() > Flow::zero())
+            .map(|edge| self.csr.distances[edge.to] + 1)
+            .min()
+            .unwrap_or(self.csr.num_nodes)
+            .min(self.csr.num_nodes);
+        self.csr.distances[u] = new_distance;
+        self.distance_count[new_distance] += 1;
+        self.current_edge[u] = self.csr.start[u];
+        true
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for Isap<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "isap"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
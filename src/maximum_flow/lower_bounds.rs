@@ -0,0 +1,76 @@
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
+use crate::maximum_flow::status::Status;
+use num_traits::NumAssign;
+
+// the lowers are sent in advance, and a super source and a super sink settle the excesses they leave with a maximum flow (a feasible circulation),
+// where the flow from source to sink comes back on edges between them (it can be negative when lowers enter source).
+// the maximum flow is then grown from that flow on its residual network
+pub(crate) fn solve<Flow, Solver>(solver: &mut Solver, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status
+where
+    Flow: NumAssign + Ord + Clone + Default,
+    Solver: MaximumFlowSolver<Flow> + ?Sized,
+{
+    let num_nodes = graph.num_nodes();
+    if is_bad_terminals(num_nodes, source, sink) {
+        return Status::BadInput;
+    }
+    if graph.edges.iter().zip(graph.lowers.iter()).any(|(edge, lower)| *lower < Flow::zero() || *lower > edge.upper) {
+        return Status::BadInput;
+    }
+
+    let (super_source, super_sink) = (num_nodes, num_nodes + 1);
+    let mut circulation = Graph::default();
+    circulation.add_nodes(num_nodes + 2);
+    let mut excesses = vec![Flow::zero(); num_nodes];
+    // no more than the capacity out of source (into source) leaves (enters) it
+    let (mut out_of_source, mut into_source) = (Flow::zero(), Flow::zero());
+    for (edge, lower) in graph.edges.iter().zip(graph.lowers.iter()) {
+        circulation.push_edge(edge.from, edge.to, edge.upper.clone() - lower.clone());
+        excesses[edge.from] -= lower.clone();
+        excesses[edge.to] += lower.clone();
+        if edge.from == source && edge.to != source {
+            out_of_source += edge.upper.clone();
+        }
+        if edge.to == source && edge.from != source {
+            into_source += edge.upper.clone();
+        }
+    }
+    circulation.push_edge(sink, source, out_of_source);
+    circulation.push_edge(source, sink, into_source);
+    let mut required = Flow::zero();
+    for (u, excess) in excesses.into_iter().enumerate() {
+        if excess > Flow::zero() {
+            circulation.push_edge(super_source, u, excess.clone());
+            required += excess;
+        } else if excess < Flow::zero() {
+            circulation.push_edge(u, super_sink, Flow::zero() - excess);
+        }
+    }
+    // the reduction needs a conserved flow, which a solver keeping its preflow does not give
+    let status = solver.solve_raw(super_source, super_sink, &mut circulation);
+    if status != Status::Optimal || circulation.is_preflow() {
+        return if status == Status::Optimal { Status::BadInput } else { status };
+    }
+    if circulation.maximum_flow(super_source) != required {
+        return Status::Infeasible;
+    }
+
+    // 2 * edge_id goes along the edge and 2 * edge_id + 1 cancels its flow
+    let mut residual = Graph::default();
+    residual.add_nodes(num_nodes);
+    for (edge_id, (edge, lower)) in graph.edges.iter().zip(graph.lowers.iter()).enumerate() {
+        let flow = circulation.edges[edge_id].flow.clone();
+        residual.push_edge(edge.from, edge.to, edge.upper.clone() - lower.clone() - flow.clone());
+        residual.push_edge(edge.to, edge.from, flow);
+    }
+    let status = solver.solve_raw(source, sink, &mut residual);
+    if status != Status::Optimal || residual.is_preflow() {
+        return if status == Status::Optimal { Status::BadInput } else { status };
+    }
+    for (edge_id, (edge, lower)) in graph.edges.iter_mut().zip(graph.lowers.iter()).enumerate() {
+        edge.flow = lower.clone() + circulation.edges[edge_id].flow.clone() + residual.edges[2 * edge_id].flow.clone() - residual.edges[2 * edge_id + 1].flow.clone();
+    }
+    graph.is_preflow = false;
+    Status::Optimal
+}
@@ -0,0 +1,276 @@
+use crate::ids::NodeId;
+use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
+use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PHASES_TOTAL};
+use num_traits::NumAssign;
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+// the blocking flows of the level graphs by Malhotra, Pramodh Kumar and Maheshwari, O(n^3).
+// the node with the smallest throughput is saturated by pushing its potential to sink and pulling it from source,
+// and the nodes whose potential becomes 0 are deleted from the level graph
+#[derive(Default)]
+pub struct Mpm<Flow> {
+    csr: CSR<Flow>,
+    is_alive: Vec<bool>,
+    in_potentials: Vec<Flow>,
+    out_potentials: Vec<Flow>,
+    excesses: Vec<Flow>,
+    out_edge: Vec<usize>, // the current edges of the pushes toward sink
+    in_edge: Vec<usize>,  // the current edges of the pulls from source, of which the reverse edges are in the level graph
+    que: VecDeque<usize>,
+    num_phases: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+}
+
+impl<Flow> Mpm<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "mpm");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(PHASES_TOTAL, self.num_phases)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(PHASES_TOTAL, self.num_phases)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
+        self.csr.build(graph);
+        let n = self.csr.num_nodes;
+        for values in [&mut self.in_potentials, &mut self.out_potentials, &mut self.excesses] {
+            values.resize(n, Flow::zero());
+        }
+        self.is_alive.resize(n, false);
+        self.out_edge.resize(n, 0);
+        self.in_edge.resize(n, 0);
+        self.num_phases = 0;
+
+        loop {
+            self.csr.update_distances(source, sink);
+            if self.csr.distances[source] >= n {
+                break;
+            }
+            self.num_phases += 1;
+            self.blocking_flow(source, sink);
+        }
+
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    // the edge i from u is in the level graph
+    fn is_level_edge(&self, u: usize, i: usize) -> bool {
+        self.is_alive[u] && self.is_alive[self.csr.inside_edge_list[i].to] && self.csr.is_admissible_edge(u, i)
+    }
+
+    // the throughput of u, source has no inflow and sink has no outflow
+    fn potential(&self, u: usize, source: usize, sink: usize) -> Flow {
+        if u == source {
+            self.out_potentials[u]
+        } else if u == sink {
+            self.in_potentials[u]
+        } else {
+            self.in_potentials[u].min(self.out_potentials[u])
+        }
+    }
+
+    fn blocking_flow(&mut self, source: usize, sink: usize) {
+        let n = self.csr.num_nodes;
+        let length = self.csr.distances[source];
+        for u in 0..n {
+            self.is_alive[u] = self.csr.distances[u] <= length;
+            self.in_potentials[u] = Flow::zero();
+            self.out_potentials[u] = Flow::zero();
+            self.excesses[u] = Flow::zero();
+            self.out_edge[u] = self.csr.start[u];
+            self.in_edge[u] = self.csr.start[u];
+        }
+        for u in 0..n {
+            for i in self.csr.start[u]..self.csr.start[u + 1] {
+                if self.is_level_edge(u, i) {
+                    let residual_capacity = self.csr.inside_edge_list[i].residual_capacity();
+                    self.out_potentials[u] += residual_capacity;
+                    self.in_potentials[self.csr.inside_edge_list[i].to] += residual_capacity;
+                }
+            }
+        }
+
+        loop {
+            // the nodes that are not on a path from source to sink lose their inflow or outflow one after another.
+            // the potential of r becomes 0 too
+            let mut deleted: Vec<usize> = (0..n).filter(|&u| self.is_alive[u] && self.potential(u, source, sink) == Flow::zero()).collect();
+            while let Some(u) = deleted.pop() {
+                if self.is_alive[u] {
+                    self.delete(u, source, sink, &mut deleted);
+                }
+            }
+            if !self.is_alive[source] || !self.is_alive[sink] {
+                return;
+            }
+
+            let r = (0..n).filter(|&u| self.is_alive[u]).min_by_key(|&u| self.potential(u, source, sink)).unwrap();
+            let potential = self.potential(r, source, sink);
+            self.flow_value += potential;
+            if r != sink {
+                self.push_to_sink(r, sink, potential);
+            }
+            if r != source {
+                self.pull_from_source(r, source, potential);
+            }
+        }
+    }
+
+    // the edges of u are removed from the potentials of its neighbors
+    fn delete(&mut self, u: usize, source: usize, sink: usize, deleted: &mut Vec<usize>) {
+        for i in self.csr.start[u]..self.csr.start[u + 1] {
+            let v = self.csr.inside_edge_list[i].to;
+            if self.is_level_edge(u, i) {
+                self.in_potentials[v] -= self.csr.inside_edge_list[i].residual_capacity();
+            } else if self.is_level_edge(v, self.csr.inside_edge_list[i].rev) {
+                self.out_potentials[v] -= self.csr.inside_edge_list[self.csr.inside_edge_list[i].rev].residual_capacity();
+            } else {
+                continue;
+            }
+            if self.potential(v, source, sink) == Flow::zero() {
+                deleted.push(v);
+            }
+        }
+        self.is_alive[u] = false;
+    }
+
+    // every node on the way passes the flow, since its potential is not less than that of r.
+    // the levels are processed in order, sink last
+    fn push_to_sink(&mut self, r: usize, sink: usize, flow: Flow) {
+        self.excesses[r] = flow;
+        self.que.push_back(r);
+        while let Some(u) = self.que.pop_front() {
+            if u == sink {
+                self.excesses[u] = Flow::zero();
+                continue;
+            }
+            while self.excesses[u] > Flow::zero() {
+                let i = self.out_edge[u];
+                if !self.is_level_edge(u, i) {
+                    self.out_edge[u] += 1;
+                    continue;
+                }
+                let v = self.csr.inside_edge_list[i].to;
+                let delta = self.excesses[u].min(self.csr.inside_edge_list[i].residual_capacity());
+                self.move_flow(u, i, delta);
+                if self.excesses[v] == Flow::zero() {
+                    self.que.push_back(v);
+                }
+                self.excesses[u] -= delta;
+                self.excesses[v] += delta;
+                if self.csr.inside_edge_list[i].residual_capacity() == Flow::zero() {
+                    self.out_edge[u] += 1;
+                }
+            }
+        }
+    }
+
+    // the same as push_to_sink on the reverse level graph
+    fn pull_from_source(&mut self, r: usize, source: usize, flow: Flow) {
+        self.excesses[r] = flow;
+        self.que.push_back(r);
+        while let Some(v) = self.que.pop_front() {
+            if v == source {
+                self.excesses[v] = Flow::zero();
+                continue;
+            }
+            while self.excesses[v] > Flow::zero() {
+                let j = self.in_edge[v];
+                let (u, i) = (self.csr.inside_edge_list[j].to, self.csr.inside_edge_list[j].rev);
+                if !self.is_level_edge(u, i) {
+                    self.in_edge[v] += 1;
+                    continue;
+                }
+                let delta = self.excesses[v].min(self.csr.inside_edge_list[i].residual_capacity());
+                self.move_flow(u, i, delta);
+                if self.excesses[u] == Flow::zero() {
+                    self.que.push_back(u);
+                }
+                self.excesses[v] -= delta;
+                self.excesses[u] += delta;
+                if self.csr.inside_edge_list[i].residual_capacity() == Flow::zero() {
+                    self.in_edge[v] += 1;
+                }
+            }
+        }
+    }
+
+    // the flow on the edge i from u, and the potentials of both ends
+    fn move_flow(&mut self, u: usize, i: usize, delta: Flow) {
+        self.csr.push_flow(i, delta);
+        self.out_potentials[u] -= delta;
+        self.in_potentials[self.csr.inside_edge_list[i].to] -= delta;
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for Mpm<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "mpm"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
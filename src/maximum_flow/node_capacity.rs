@@ -0,0 +1,135 @@
+use crate::graph_error::{check_nodes, GraphError};
+use crate::ids::{EdgeId, NodeId};
+use crate::maximum_flow::graph::{Edge, Graph};
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
+use crate::maximum_flow::status::Status;
+use num_traits::NumAssign;
+
+// a maximum flow network whose nodes may have a capacity on the flow passing through them.
+// a capped node u is split into u_in -> u_out joined by a bridge edge of its capacity: the edges into u enter u_in and those out of u leave u_out.
+// the ids of the nodes and edges are those of the user, and the split network is only seen through network
+#[derive(Default, Clone)]
+pub struct NodeCapacityGraph<Flow> {
+    network: Graph<Flow>,
+    node_in: Vec<usize>,
+    node_out: Vec<usize>, // node_in[u] if u has no capacity
+    bridge_edge: Vec<Option<usize>>,
+    edge_ids: Vec<usize>, // the edge of the network of each edge
+}
+
+impl<Flow> NodeCapacityGraph<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    #[inline]
+    pub fn num_nodes(&self) -> usize {
+        self.node_in.len()
+    }
+
+    #[inline]
+    pub fn num_edges(&self) -> usize {
+        self.edge_ids.len()
+    }
+
+    pub fn add_node(&mut self) -> NodeId {
+        NodeId(self.add_node_raw())
+    }
+
+    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<NodeId> {
+        (0..num_nodes).map(|_| self.add_node()).collect()
+    }
+
+    // at most capacity flows through the node, including when it is the source or the sink
+    pub fn add_node_with_capacity(&mut self, capacity: Flow) -> NodeId {
+        let (u_in, u_out) = (self.network.add_node_raw(), self.network.add_node_raw());
+        let bridge_edge = self.network.push_edge(u_in, u_out, capacity);
+        self.node_in.push(u_in);
+        self.node_out.push(u_out);
+        self.bridge_edge.push(Some(bridge_edge));
+        NodeId(self.node_in.len() - 1)
+    }
+
+    pub fn node_capacity(&self, u: impl Into<NodeId>) -> Option<Flow> {
+        self.bridge_edge.get(u.into().index())?.map(|edge_id| self.network.edges[edge_id].upper)
+    }
+
+    // return edge id
+    pub fn add_directed_edge(&mut self, from: impl Into<NodeId>, to: impl Into<NodeId>, upper: Flow) -> Result<EdgeId, GraphError<Flow>> {
+        self.add_directed_edge_raw(from.into().index(), to.into().index(), upper).map(EdgeId)
+    }
+
+    // the usize versions of the above, kept for one release
+    pub fn add_node_raw(&mut self) -> usize {
+        let u = self.network.add_node_raw();
+        self.node_in.push(u);
+        self.node_out.push(u);
+        self.bridge_edge.push(None);
+        self.node_in.len() - 1
+    }
+
+    pub fn add_nodes_raw(&mut self, num_nodes: usize) -> Vec<usize> {
+        (0..num_nodes).map(|_| self.add_node_raw()).collect()
+    }
+
+    pub fn add_directed_edge_raw(&mut self, from: usize, to: usize, upper: Flow) -> Result<usize, GraphError<Flow>> {
+        check_nodes(from, to, self.num_nodes())?;
+        self.edge_ids.push(self.network.add_directed_edge_raw(self.node_out[from], self.node_in[to], upper)?);
+        Ok(self.edge_ids.len() - 1)
+    }
+
+    pub fn get_edge(&self, edge_id: impl Into<EdgeId>) -> Option<Edge<Flow>> {
+        let edge = self.network.get_edge(*self.edge_ids.get(edge_id.into().index())?)?;
+        Some(Edge { from: self.original_node(edge.from), to: self.original_node(edge.to), ..edge })
+    }
+
+    // the split network, whose first edges are not necessarily those of the user
+    pub fn network(&self) -> &Graph<Flow> {
+        &self.network
+    }
+
+    // source sends from its in side and sink receives on its out side, so that their capacities also bound the flow
+    pub fn solve<Solver: MaximumFlowSolver<Flow> + ?Sized>(&mut self, solver: &mut Solver, source: impl Into<NodeId>, sink: impl Into<NodeId>) -> Status {
+        let (source, sink) = (source.into().index(), sink.into().index());
+        if is_bad_terminals(self.num_nodes(), source, sink) {
+            return Status::BadInput;
+        }
+        solver.solve_raw(self.node_in[source], self.node_out[sink], &mut self.network)
+    }
+
+    pub fn maximum_flow(&self, source: impl Into<NodeId>) -> Flow {
+        let source = source.into().index();
+        (0..self.num_edges()).fold(Flow::zero(), |mut flow, edge_id| {
+            let edge = self.get_edge(edge_id).unwrap();
+            if edge.from == source && edge.to != source {
+                flow += edge.flow;
+            } else if edge.to == source && edge.from != source {
+                flow -= edge.flow;
+            }
+            flow
+        })
+    }
+
+    // the flow passing through u: on its bridge edge if it has a capacity, and the larger of its inflow and outflow otherwise
+    pub fn node_flow(&self, u: impl Into<NodeId>) -> Flow {
+        let u = u.into().index();
+        if let Some(edge_id) = self.bridge_edge[u] {
+            return self.network.edges[edge_id].flow;
+        }
+        let (mut inflow, mut outflow) = (Flow::zero(), Flow::zero());
+        for edge_id in 0..self.num_edges() {
+            let edge = self.get_edge(edge_id).unwrap();
+            if edge.to == u {
+                inflow += edge.flow;
+            }
+            if edge.from == u {
+                outflow += edge.flow;
+            }
+        }
+        inflow.max(outflow)
+    }
+
+    fn original_node(&self, v: usize) -> usize {
+        // a node of the network belongs to the user node with the largest in side not after it
+        self.node_in.partition_point(|&u_in| u_in <= v) - 1
+    }
+}
@@ -1,34 +1,186 @@
+use crate::ids::NodeId;
 use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
 use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
 use crate::maximum_flow::status::Status;
+use crate::metrics::{Histogram, MetricsSink, SolveMetrics, Stats, StatsLevel};
 use num_traits::NumAssign;
 use std::collections::VecDeque;
+use std::sync::Arc;
+
+// what becomes of the excesses that cannot reach sink once the preflow is maximum
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+pub enum ReturnExcessPolicy {
+    // they are returned to source, so that get_edge reads a maximum flow
+    #[default]
+    Full,
+    // they are kept, get_edge reads a maximum preflow and the graph is marked with is_preflow.
+    // the flows into sink and flow_value are those of a maximum flow, but maximum_flow(source) and the flow conservation are not
+    SkipKeepPreflow,
+    // only flow_value of the solver is computed, and the flows of the graph are not written
+    ValueOnly,
+}
+
+// the states of the nodes in the search for the cycles of the flow
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+enum Mark {
+    #[default]
+    New,
+    OnStack,
+    Done,
+}
+
+pub const DEFAULT_GLOBAL_UPDATE_FACTOR: f64 = 1.0;
+
+// when the exact distances to sink are recomputed on the residual network (global relabeling)
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum GlobalRelabeling {
+    Off,
+    // after every alpha * n relabels, kept for comparison
+    RelabelCount(usize),
+    // after the pushes and relabels have scanned factor * (n + m) edges since the last one
+    Work(f64),
+}
+
+impl Default for GlobalRelabeling {
+    fn default() -> Self {
+        GlobalRelabeling::Work(DEFAULT_GLOBAL_UPDATE_FACTOR)
+    }
+}
+
+// PushRelabelFIFO::builder().global_update_factor(4.0).gap_relabeling(false).build()
+pub struct PushRelabelFIFOBuilder<Flow> {
+    global_relabeling: GlobalRelabeling,
+    gap_relabeling: bool,
+    return_excess_policy: ReturnExcessPolicy,
+    _maker: std::marker::PhantomData<fn() -> Flow>,
+}
+
+impl<Flow> PushRelabelFIFOBuilder<Flow>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    // see PushRelabelFIFO::with_global_relabel_frequency
+    pub fn global_relabel_frequency(mut self, alpha: usize) -> Self {
+        self.global_relabeling = if alpha == 0 { GlobalRelabeling::Off } else { GlobalRelabeling::RelabelCount(alpha) };
+        self
+    }
+
+    // GlobalRelabeling::Work(factor), and off if factor is 0
+    pub fn global_update_factor(mut self, factor: f64) -> Self {
+        assert!(factor >= 0.0);
+        self.global_relabeling = if factor == 0.0 { GlobalRelabeling::Off } else { GlobalRelabeling::Work(factor) };
+        self
+    }
+
+    // on by default: when a relabel would empty a distance, every node above it is cut off from sink at once
+    pub fn gap_relabeling(mut self, enabled: bool) -> Self {
+        self.gap_relabeling = enabled;
+        self
+    }
+
+    // the final pass that returns the excesses to source, see ReturnExcessPolicy
+    pub fn return_excess_policy(mut self, policy: ReturnExcessPolicy) -> Self {
+        self.return_excess_policy = policy;
+        self
+    }
+
+    pub fn build(self) -> PushRelabelFIFO<Flow> {
+        PushRelabelFIFO { global_relabeling: self.global_relabeling, disable_gap_relabeling: !self.gap_relabeling, return_excess_policy: self.return_excess_policy, ..PushRelabelFIFO::default() }
+    }
+}
 
 #[derive(Default)]
 pub struct PushRelabelFIFO<Flow> {
     csr: CSR<Flow>,
     excesses: Vec<Flow>,
 
-    alpha: usize,
+    global_relabeling: GlobalRelabeling,
+    disable_gap_relabeling: bool,
     relabel_count: usize,
+    work: usize, // the edges scanned by the pushes and relabels since the last global relabeling
     active_nodes: VecDeque<usize>,
     current_edge: Vec<usize>,
     distance_count: Vec<usize>,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+    stats_level: StatsLevel,
+    push_distances: Histogram,
+    return_excess_policy: ReturnExcessPolicy,
+    flow_value: Flow,
+    num_drain_operations: usize,
+    is_forward: Vec<bool>, // the inside edges of the edges of the graph, not their reverse edges
+    marks: Vec<Mark>,
+    drain_stack: Vec<usize>,
+    drain_order: Vec<usize>, // the finishing order of cancel_flow_cycles
 }
 
 impl<Flow> PushRelabelFIFO<Flow>
 where
     Flow: NumAssign + Ord + Copy + Default,
 {
-    pub fn new(&mut self, alpha: usize) -> Self {
-        Self { csr: CSR::default(), excesses: Vec::new(), alpha, relabel_count: 0, active_nodes: VecDeque::new(), current_edge: Vec::new(), distance_count: Vec::new() }
+    // the old trigger of the global relabeling, after every alpha * n relabels (GlobalRelabeling::RelabelCount).
+    // alpha = 0 disables the global relabeling. default triggers it by the work instead, see GlobalRelabeling::Work
+    pub fn with_global_relabel_frequency(alpha: usize) -> Self {
+        Self::builder().global_relabel_frequency(alpha).build()
+    }
+
+    pub fn builder() -> PushRelabelFIFOBuilder<Flow> {
+        PushRelabelFIFOBuilder { global_relabeling: GlobalRelabeling::default(), gap_relabeling: true, return_excess_policy: ReturnExcessPolicy::default(), _maker: std::marker::PhantomData }
+    }
+
+    pub fn set_return_excess_policy(&mut self, policy: ReturnExcessPolicy) {
+        self.return_excess_policy = policy;
+    }
+
+    // the value of the maximum flow of the last solve, under every policy
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    // the arcs examined while returning the excesses to source in the last solve
+    pub fn num_drain_operations(&self) -> usize {
+        self.num_drain_operations
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    pub fn set_stats_level(&mut self, stats_level: StatsLevel) {
+        self.stats_level = stats_level;
+    }
+
+    // the distance label of the node at each push of discharge, recorded with StatsLevel::Histograms
+    pub fn push_distance_histogram(&self) -> Vec<(u64, u64)> {
+        self.push_distances.buckets()
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "push_relabel_fifo");
+        self.stats = Stats::default();
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[]);
+        }
+        result
     }
 
-    pub fn solve(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
-        if source >= graph.num_nodes() || sink >= graph.num_nodes() || source == sink {
-            return Status::BadInput;
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
         }
         self.csr.build(graph);
+        self.push_distances.clear();
 
         self.pre_process(source, sink);
 
@@ -39,23 +191,60 @@ where
             }
             self.discharge(u);
 
-            if self.alpha != 0 && self.relabel_count > self.alpha * self.csr.num_nodes {
-                self.relabel_count = 0;
-                self.csr.update_distances(source, sink);
+            let is_due = match self.global_relabeling {
+                GlobalRelabeling::Off => false,
+                GlobalRelabeling::RelabelCount(alpha) => self.relabel_count > alpha * self.csr.num_nodes,
+                GlobalRelabeling::Work(factor) => self.work as f64 > factor * (self.csr.num_nodes + self.csr.num_edges) as f64,
+            };
+            if is_due {
+                self.global_relabeling(source, sink);
             }
         }
 
-        self.push_flow_excess_back_to_source(source, sink);
+        self.flow_value = self.excesses[sink];
+        self.num_drain_operations = 0;
+        match self.return_excess_policy {
+            ReturnExcessPolicy::Full => {
+                self.push_flow_excess_back_to_source(source, sink);
+                FlowResult::from_csr(Status::Optimal, &self.csr)
+            }
+            ReturnExcessPolicy::SkipKeepPreflow => FlowResult::preflow_from_csr(Status::Optimal, &self.csr),
+            ReturnExcessPolicy::ValueOnly => FlowResult::without_flows(Status::Optimal),
+        }
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
+    }
 
-        self.csr.set_flow(graph);
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
 
-        Status::Optimal
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
     }
 
     fn pre_process(&mut self, source: usize, sink: usize) {
+        // the excesses of source and sink are left from a previous solve
+        self.excesses.clear();
         self.excesses.resize(self.csr.num_nodes, Flow::zero());
         self.current_edge.resize(self.csr.num_nodes, 0);
+        self.distance_count.clear();
         self.distance_count.resize(self.csr.num_nodes + 1, 0);
+        (self.relabel_count, self.work) = (0, 0);
 
         self.csr.update_distances(source, sink);
         self.csr.distances[source] = self.csr.num_nodes;
@@ -83,6 +272,7 @@ where
         // push
         for edge_id in self.current_edge[u]..self.csr.start[u + 1] {
             self.current_edge[u] = edge_id;
+            self.work += 1;
             if self.excesses[u] > Flow::zero() {
                 self.push(u, edge_id);
             }
@@ -94,7 +284,7 @@ where
         self.current_edge[u] = self.csr.start[u];
 
         // relabel
-        if self.distance_count[self.csr.distances[u]] == 1 {
+        if self.distance_count[self.csr.distances[u]] == 1 && !self.disable_gap_relabeling {
             self.gap_relabeling(self.csr.distances[u]);
         } else {
             self.relabel(u);
@@ -110,7 +300,11 @@ where
         let to = self.csr.inside_edge_list[edge_id].to;
         let delta = self.excesses[u].min(self.csr.inside_edge_list[edge_id].residual_capacity());
         if self.csr.is_admissible_edge(u, edge_id) && delta > Flow::zero() {
+            if self.stats_level == StatsLevel::Histograms {
+                self.push_distances.observe(self.csr.distances[u] as u64);
+            }
             self.csr.push_flow(edge_id, delta);
+            self.stats.num_pushes += 1;
             self.excesses[u] -= delta;
             self.excesses[to] += delta;
             if self.excesses[to] == delta {
@@ -119,8 +313,24 @@ where
         }
     }
 
+    // the exact distances to sink on the residual network, and n for the nodes that cannot reach it
+    fn global_relabeling(&mut self, source: usize, sink: usize) {
+        (self.relabel_count, self.work) = (0, 0);
+        self.stats.num_global_relabelings += 1;
+        self.csr.update_distances(source, sink);
+        self.csr.distances[source] = self.csr.num_nodes;
+
+        self.distance_count.fill(0);
+        for u in 0..self.csr.num_nodes {
+            self.distance_count[self.csr.distances[u]] += 1;
+            self.current_edge[u] = self.csr.start[u];
+        }
+    }
+
     fn relabel(&mut self, u: usize) {
         self.relabel_count += 1;
+        self.work += self.csr.start[u + 1] - self.csr.start[u];
+        self.stats.num_relabels += 1;
         self.distance_count[self.csr.distances[u]] -= 1;
 
         let new_distance = self
@@ -141,6 +351,7 @@ where
     // set distance[u] >= k to distance[u] = n
     // O(n)
     fn gap_relabeling(&mut self, k: usize) {
+        self.stats.num_gap_relabelings += 1;
         for u in 0..self.csr.num_nodes {
             if self.csr.distances[u] >= k {
                 self.distance_count[self.csr.distances[u]] -= 1;
@@ -150,41 +361,104 @@ where
         }
     }
 
+    // every node with an excess has a residual path to source, along which the preflow came.
+    // the cycles of the flow are cancelled first, and then the excesses are returned along the incoming flows
+    // in a topological order of the acyclic flow, O(nm) in total
     fn push_flow_excess_back_to_source(&mut self, source: usize, sink: usize) {
-        for u in 0..self.csr.num_nodes {
-            if u == source || u == sink {
-                continue;
+        let n = self.csr.num_nodes;
+        self.is_forward.clear();
+        self.is_forward.resize(self.csr.inside_edge_list.len(), false);
+        for &i in self.csr.edge_index_to_inside_edge_index.iter() {
+            self.is_forward[i] = true;
+        }
+        self.marks.clear();
+        self.marks.resize(n, Mark::New);
+        self.drain_order.clear();
+        self.current_edge[..n].copy_from_slice(&self.csr.start[..n]);
+
+        for root in 0..n {
+            if root != source && root != sink && self.marks[root] == Mark::New {
+                self.cancel_flow_cycles(root, source, sink);
             }
-            while self.excesses[u] > Flow::zero() {
-                let mut visited = vec![false; self.csr.num_nodes];
-                self.current_edge.iter_mut().enumerate().for_each(|(u, e)| *e = self.csr.start[u]);
-                let d = self.dfs(u, source, self.excesses[u], &mut visited);
-                self.excesses[u] -= d;
-                self.excesses[source] += d;
+        }
+
+        // the nodes that u sends flow to are finished before u, so an excess is never passed to a node already drained
+        for k in 0..self.drain_order.len() {
+            let u = self.drain_order[k];
+            for i in self.csr.start[u]..self.csr.start[u + 1] {
+                if self.excesses[u] == Flow::zero() {
+                    break;
+                }
+                self.num_drain_operations += 1;
+                let edge = &self.csr.inside_edge_list[i];
+                let (to, inflow) = (edge.to, self.csr.inside_edge_list[edge.rev].flow);
+                if self.is_forward[i] || inflow == Flow::zero() {
+                    continue;
+                }
+                let delta = self.excesses[u].min(inflow);
+                self.csr.push_flow(i, delta);
+                self.excesses[u] -= delta;
+                self.excesses[to] += delta;
             }
         }
     }
 
-    fn dfs(&mut self, u: usize, source: usize, flow: Flow, visited: &mut Vec<bool>) -> Flow {
-        if u == source {
-            return flow;
-        }
-        visited[u] = true;
+    // a depth first search on the edges with positive flows except those into source and sink.
+    // reaching a node on the stack closes a cycle, which is cancelled, and the search resumes at that node
+    fn cancel_flow_cycles(&mut self, root: usize, source: usize, sink: usize) {
+        self.drain_stack.clear();
+        self.drain_stack.push(root);
+        self.marks[root] = Mark::OnStack;
 
-        for i in self.current_edge[u]..self.csr.start[u + 1] {
-            self.current_edge[u] = i;
-            let to = self.csr.inside_edge_list[i].to;
-            let residual_capacity = self.csr.inside_edge_list[i].residual_capacity();
-            if visited[to] || residual_capacity == Flow::zero() {
+        while let Some(&u) = self.drain_stack.last() {
+            let i = self.current_edge[u];
+            if i == self.csr.start[u + 1] {
+                self.drain_stack.pop();
+                self.marks[u] = Mark::Done;
+                self.drain_order.push(u);
                 continue;
             }
 
-            let delta = self.dfs(to, source, flow.min(residual_capacity), visited);
-            if delta > Flow::zero() {
-                self.csr.push_flow(i, delta);
-                return delta;
+            self.num_drain_operations += 1;
+            let edge = &self.csr.inside_edge_list[i];
+            let to = edge.to;
+            if !self.is_forward[i] || edge.flow == Flow::zero() || to == source || to == sink || self.marks[to] == Mark::Done {
+                self.current_edge[u] += 1;
+                continue;
+            }
+            if self.marks[to] == Mark::New {
+                self.marks[to] = Mark::OnStack;
+                self.drain_stack.push(to);
+                continue;
+            }
+
+            // at least one edge of the cycle loses all its flow, and the flows only decrease
+            let position = self.drain_stack.iter().rposition(|&v| v == to).unwrap();
+            let delta = self.drain_stack[position..].iter().map(|&v| self.csr.inside_edge_list[self.current_edge[v]].flow).min().unwrap();
+            for &v in self.drain_stack[position..].iter() {
+                self.csr.push_flow(self.csr.inside_edge_list[self.current_edge[v]].rev, delta);
+            }
+            self.num_drain_operations += self.drain_stack.len() - position;
+            for v in self.drain_stack.drain(position + 1..) {
+                self.marks[v] = Mark::New;
             }
         }
-        Flow::zero()
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for PushRelabelFIFO<Flow>
+where
+    Flow: NumAssign + Ord + Copy + Default,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "push_relabel_fifo"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
     }
 }
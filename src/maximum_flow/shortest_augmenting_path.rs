@@ -1,61 +1,188 @@
+use crate::ids::NodeId;
 use crate::maximum_flow::csr::CSR;
+use crate::maximum_flow::flow_result::{commit, FlowResult};
 use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::solver::{is_bad_terminals, MaximumFlowSolver};
 use crate::maximum_flow::status::Status;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, AUGMENTATIONS_TOTAL};
 use num_traits::NumAssign;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct ShortestAugmentingPath<Flow> {
     csr: CSR<Flow>,
     pub current_edge: Vec<usize>,
+    distance_count: Vec<usize>,
+    disable_gap_relabeling: bool,
+    num_augmentations: usize,
+    num_edge_scans: usize,
+    flow_value: Flow,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> ShortestAugmentingPath<Flow>
 where
     Flow: NumAssign + Ord + Copy,
 {
-    pub fn solve(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // on by default. without it, the nodes cut off from sink are relabeled one retreat after another until source reaches n
+    pub fn set_gap_relabeling(&mut self, enabled: bool) {
+        self.disable_gap_relabeling = !enabled;
+    }
+
+    // the edges examined by the advances and the retreats of the last solve
+    pub fn num_edge_scans(&self) -> usize {
+        self.num_edge_scans
+    }
+
+    // the value of the flow of the last solve, without scanning the graph as maximum_flow(source) does
+    pub fn flow_value(&self) -> Flow {
+        self.flow_value
+    }
+
+    pub fn solve(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Status {
+        let result = self.solve_ref(source, sink, graph);
+        commit(graph, &result);
+        result.status()
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit
+    pub fn solve_ref(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "shortest_augmenting_path");
+        let result = self.solve_inner(source.into().index(), sink.into().index(), graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_augmentations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, source: usize, sink: usize, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.flow_value = Flow::zero();
+        if is_bad_terminals(graph.num_nodes(), source, sink) {
+            return FlowResult::without_flows(Status::BadInput);
+        }
         self.csr.build(graph);
         self.csr.update_distances(source, sink);
         self.current_edge.resize(self.csr.num_nodes, 0);
+        self.distance_count.clear();
+        self.distance_count.resize(self.csr.num_nodes + 1, 0);
+        for u in 0..self.csr.num_nodes {
+            self.distance_count[self.csr.distances[u]] += 1;
+        }
+        self.num_augmentations = 0;
+        self.num_edge_scans = 0;
 
-        let mut flow = Flow::zero();
         let upper = self.csr.neighbors(source).fold(Flow::zero(), |sum, e| sum + e.upper);
         while self.csr.distances[source] < self.csr.num_nodes {
             self.current_edge.iter_mut().enumerate().for_each(|(u, e)| *e = self.csr.start[u]);
-            if let Some(delta) = self.dfs(source, sink, upper) {
-                flow += delta;
+            if let Some(delta) = self.dfs(source, source, sink, upper) {
+                self.flow_value += delta;
+                self.num_augmentations += 1;
             }
         }
 
-        self.csr.set_flow(graph);
-        Status::Optimal
+        FlowResult::from_csr(Status::Optimal, &self.csr)
+    }
+
+    // source side of a minimum cut, valid after solve returns Status::Optimal
+    pub fn minimum_cut(&self, source: usize) -> Vec<usize> {
+        self.csr.minimum_cut(source)
+    }
+
+    // edges crossing the minimum cut, their capacities sum up to the maximum flow
+    pub fn min_cut_edges(&self, source: usize) -> Vec<usize> {
+        self.csr.min_cut_edges(source)
+    }
+
+    // (source side, crossing edges) of the minimum cut closest to the source, the same as minimum_cut and min_cut_edges
+    pub fn min_cut_source_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_source_minimal(source, sink)
     }
 
-    fn dfs(&mut self, u: usize, sink: usize, upper: Flow) -> Option<Flow> {
+    // (source side, crossing edges) of the minimum cut closest to the sink
+    pub fn min_cut_sink_minimal(&self, source: usize, sink: usize) -> (Vec<usize>, Vec<usize>) {
+        self.csr.min_cut_sink_minimal(source, sink)
+    }
+
+    pub fn cut_is_unique(&self, source: usize, sink: usize) -> bool {
+        self.csr.cut_is_unique(source, sink)
+    }
+
+    fn dfs(&mut self, u: usize, source: usize, sink: usize, upper: Flow) -> Option<Flow> {
         if u == sink {
             return Some(upper);
         }
 
         for i in self.current_edge[u]..self.csr.start[u + 1] {
             self.current_edge[u] = i;
+            self.num_edge_scans += 1;
             let e = &self.csr.inside_edge_list[i];
             if self.csr.is_admissible_edge(u, i) {
                 // advance
-                if let Some(delta) = self.dfs(e.to, sink, upper.min(e.residual_capacity())) {
+                if let Some(delta) = self.dfs(e.to, source, sink, upper.min(e.residual_capacity())) {
                     self.csr.push_flow(i, delta);
                     return Some(delta);
                 }
+                // a gap below u, the search is over
+                if self.csr.distances[u] >= self.csr.num_nodes {
+                    return None;
+                }
             }
         }
 
         // retreat
+        let old_distance = self.csr.distances[u];
+        self.distance_count[old_distance] -= 1;
+        if self.distance_count[old_distance] == 0 && old_distance < self.csr.distances[source] && !self.disable_gap_relabeling {
+            self.gap_relabeling(u, old_distance);
+            return None;
+        }
+
         self.csr.distances[u] = self.csr.num_nodes;
+        self.num_edge_scans += self.csr.start[u + 1] - self.csr.start[u];
         for e in self.csr.inside_edge_list[self.csr.start[u]..self.csr.start[u + 1]].iter() {
             if e.residual_capacity() > Flow::zero() {
                 self.csr.distances[u] = self.csr.distances[u].min(self.csr.distances[e.to] + 1);
             }
         }
+        self.distance_count[self.csr.distances[u]] += 1;
 
         None
     }
+
+    // the level k of u is empty, so the nodes above it cannot reach sink, and source is one of them
+    fn gap_relabeling(&mut self, u: usize, k: usize) {
+        let n = self.csr.num_nodes;
+        self.csr.distances[u] = n;
+        self.distance_count[n] += 1;
+        for v in 0..n {
+            if k < self.csr.distances[v] && self.csr.distances[v] < n {
+                self.distance_count[self.csr.distances[v]] -= 1;
+                self.csr.distances[v] = n;
+                self.distance_count[n] += 1;
+            }
+        }
+    }
+}
+
+impl<Flow> MaximumFlowSolver<Flow> for ShortestAugmentingPath<Flow>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status {
+        self.solve(source, sink, graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "shortest_augmenting_path"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
 }
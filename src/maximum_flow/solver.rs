@@ -0,0 +1,44 @@
+use crate::ids::NodeId;
+use crate::maximum_flow::graph::Graph;
+use crate::maximum_flow::lower_bounds;
+use crate::maximum_flow::status::Status;
+use crate::metrics::Stats;
+use num_traits::NumAssign;
+
+// common interface of the maximum flow algorithms, so that they can be swapped behind a trait object.
+// every implementation returns Status::BadInput when source or sink is out of range or source == sink
+pub trait MaximumFlowSolver<Flow> {
+    fn solve(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status;
+
+    // solve with the usize ids, kept for one release
+    fn solve_raw(&mut self, source: usize, sink: usize, graph: &mut Graph<Flow>) -> Status {
+        self.solve(NodeId(source), NodeId(sink), graph)
+    }
+
+    fn name(&self) -> &'static str;
+
+    fn stats(&self) -> &Stats;
+
+    // the value of the flow in graph after solve
+    fn objective(&self, source: NodeId, graph: &Graph<Flow>) -> Flow
+    where
+        Flow: NumAssign + Ord + Clone,
+    {
+        graph.maximum_flow(source)
+    }
+
+    // a maximum flow whose flow on each edge is within the [lower, upper] of add_directed_edge_with_lower.
+    // Status::Infeasible if no flow meets the lower bounds, and the graph is left as it was
+    fn solve_with_lower_bounds(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> Status
+    where
+        Flow: NumAssign + Ord + Clone + Default,
+    {
+        lower_bounds::solve(self, source.index(), sink.index(), graph)
+    }
+}
+
+// the check of every solver, true if source or sink is out of range (every node is, in the empty graph) or source == sink.
+// a graph without edges is valid, and its maximum flow is 0
+pub(crate) fn is_bad_terminals(num_nodes: usize, source: usize, sink: usize) -> bool {
+    source >= num_nodes || sink >= num_nodes || source == sink
+}
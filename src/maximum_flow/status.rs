@@ -1,6 +1,11 @@
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Status {
     BadInput,
+    Infeasible, // the lower bounds of the edges cannot be met
     NotSolved,
     Optimal,
+    TimeLimit,
+    Paused,    // a session stopped by Control::Pause, resume goes on
+    Cancelled, // the cancellation token was cancelled
 }
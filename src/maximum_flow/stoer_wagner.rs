@@ -0,0 +1,81 @@
+use crate::maximum_flow::graph::Graph;
+use crate::util::union_find::UnionFind;
+use num_traits::NumAssign;
+use std::collections::BinaryHeap;
+
+// the minimum total capacity of the edges whose removal disconnects an undirected graph, and the nodes of one side.
+// every edge (u, v, c) of graph is taken as an undirected edge of capacity c, and the parallel ones add up.
+// a disconnected graph gives 0 and the connected component of node 0.
+// stoer-wagner with a binary heap, O(nm log n)
+pub fn global_min_cut<Flow>(graph: &Graph<Flow>) -> (Flow, Vec<usize>)
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let num_nodes = graph.num_nodes();
+    assert!(num_nodes >= 2, "a cut needs at least 2 nodes");
+
+    let mut adjacency = vec![Vec::new(); num_nodes];
+    let mut union_find = UnionFind::new(num_nodes);
+    for edge in graph.edges.iter().filter(|edge| edge.from != edge.to) {
+        adjacency[edge.from].push((edge.to, edge.upper));
+        adjacency[edge.to].push((edge.from, edge.upper));
+        union_find.union(edge.from, edge.to);
+    }
+
+    if union_find.num_components() > 1 {
+        return (Flow::zero(), (0..num_nodes).filter(|&u| union_find.same(0, u)).collect());
+    }
+
+    // the merged nodes are the sets of a union find, and the adjacency of a root includes those of its set
+    let mut union_find = UnionFind::new(num_nodes);
+    let mut members: Vec<Vec<usize>> = (0..num_nodes).map(|u| vec![u]).collect();
+    let mut active: Vec<usize> = (0..num_nodes).collect();
+    let mut best: Option<Flow> = None;
+    let mut best_side = Vec::new();
+
+    let mut weight = vec![Flow::zero(); num_nodes];
+    let mut is_added = vec![false; num_nodes];
+    while active.len() > 1 {
+        // maximum adjacency order: the next node is the one most tightly connected to those already added
+        for &u in active.iter() {
+            (weight[u], is_added[u]) = (Flow::zero(), false);
+        }
+        let mut heap = BinaryHeap::from([(Flow::zero(), active[0])]);
+        let (mut s, mut t) = (usize::MAX, usize::MAX);
+        let mut num_added = 0;
+        while num_added < active.len() {
+            let (w, u) = heap.pop().unwrap();
+            if is_added[u] || w != weight[u] {
+                continue;
+            }
+            is_added[u] = true;
+            num_added += 1;
+            (s, t) = (t, u);
+            for &(v, capacity) in adjacency[u].iter() {
+                let v = union_find.find(v);
+                if !is_added[v] {
+                    weight[v] += capacity;
+                    heap.push((weight[v], v));
+                }
+            }
+        }
+
+        // the cut of the phase separates t from the rest
+        if best.is_none_or(|value| weight[t] < value) {
+            best = Some(weight[t]);
+            best_side = members[t].clone();
+        }
+
+        // merge s and t into the root of their union
+        union_find.union(s, t);
+        let (root, merged) = if union_find.find(s) == s { (s, t) } else { (t, s) };
+        let adjacency_of_merged = std::mem::take(&mut adjacency[merged]);
+        adjacency[root].extend(adjacency_of_merged);
+        let members_of_merged = std::mem::take(&mut members[merged]);
+        members[root].extend(members_of_merged);
+        active.retain(|&u| u != merged);
+    }
+
+    best_side.sort();
+    (best.unwrap(), best_side)
+}
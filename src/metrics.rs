@@ -0,0 +1,236 @@
+use crate::clock::{std_clock, Clock};
+use num_traits::NumAssign;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+
+// standard metric names, labeled with the algorithm (and the status for SOLVES_TOTAL)
+pub const SOLVES_TOTAL: &str = "solves_total";
+pub const PIVOTS_TOTAL: &str = "pivots_total"; // network simplex variants
+pub const AUGMENTATIONS_TOTAL: &str = "augmentations_total"; // augmenting paths or canceled cycles
+pub const PHASES_TOTAL: &str = "phases_total"; // bfs phases of dinic-like algorithms, scaling phases
+pub const SOLVE_SECONDS: &str = "solve_seconds"; // histogram, to the resolution of the clock (see set_clock and Clock::now_nanos)
+
+// receives the metrics of a solve once at its completion
+pub trait MetricsSink: Send + Sync {
+    fn counter_add(&self, name: &str, value: u64);
+    fn histogram_observe(&self, name: &str, value: f64);
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum MetricValue {
+    Counter(u64),
+    Histogram { count: u64, sum: f64 },
+}
+
+#[derive(Default)]
+pub struct InMemoryMetrics {
+    values: Mutex<BTreeMap<String, MetricValue>>,
+}
+
+impl InMemoryMetrics {
+    // sorted by name
+    pub fn snapshot(&self) -> Vec<(String, MetricValue)> {
+        self.values.lock().unwrap().iter().map(|(name, &value)| (name.clone(), value)).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<MetricValue> {
+        self.values.lock().unwrap().get(name).copied()
+    }
+
+    // 0 if the counter has not been added yet
+    pub fn counter(&self, name: &str) -> u64 {
+        match self.get(name) {
+            Some(MetricValue::Counter(value)) => value,
+            _ => 0,
+        }
+    }
+}
+
+impl MetricsSink for InMemoryMetrics {
+    fn counter_add(&self, name: &str, value: u64) {
+        let mut values = self.values.lock().unwrap();
+        match values.entry(name.to_string()).or_insert(MetricValue::Counter(0)) {
+            MetricValue::Counter(counter) => *counter += value,
+            MetricValue::Histogram { .. } => panic!("{name} is a histogram"),
+        }
+    }
+
+    fn histogram_observe(&self, name: &str, value: f64) {
+        let mut values = self.values.lock().unwrap();
+        match values.entry(name.to_string()).or_insert(MetricValue::Histogram { count: 0, sum: 0.0 }) {
+            MetricValue::Histogram { count, sum } => {
+                *count += 1;
+                *sum += value;
+            }
+            MetricValue::Counter(_) => panic!("{name} is a counter"),
+        }
+    }
+}
+
+// name{key1="value1",key2="value2"}
+pub fn labeled(name: &str, labels: &[(&str, &str)]) -> String {
+    let labels: Vec<String> = labels.iter().map(|(key, value)| format!("{key}=\"{value}\"")).collect();
+    format!("{name}{{{}}}", labels.join(","))
+}
+
+// used by the solvers without their own sink. nothing is recorded until it is set
+static DEFAULT_SINK: RwLock<Option<Arc<dyn MetricsSink>>> = RwLock::new(None);
+
+pub fn set_default_sink(sink: Option<Arc<dyn MetricsSink>>) {
+    *DEFAULT_SINK.write().unwrap() = sink;
+}
+
+pub fn default_sink() -> Option<Arc<dyn MetricsSink>> {
+    DEFAULT_SINK.read().unwrap().clone()
+}
+
+// the clock of SOLVE_SECONDS for all solvers, a StdClock until it is set. None goes back to the StdClock.
+// SOLVE_SECONDS is not recorded without a clock, i.e. on wasm32-unknown-unknown until one is set
+static CLOCK: RwLock<Option<Arc<dyn Clock + Send + Sync>>> = RwLock::new(None);
+static STD_CLOCK: OnceLock<Option<Arc<dyn Clock + Send + Sync>>> = OnceLock::new();
+
+pub fn set_clock(clock: Option<Arc<dyn Clock + Send + Sync>>) {
+    *CLOCK.write().unwrap() = clock;
+}
+
+fn clock() -> Option<Arc<dyn Clock + Send + Sync>> {
+    CLOCK.read().unwrap().clone().or_else(|| STD_CLOCK.get_or_init(std_clock).clone())
+}
+
+// started at the beginning of a solve only if there is a sink
+pub(crate) struct SolveMetrics {
+    sink: Arc<dyn MetricsSink>,
+    algorithm: &'static str,
+    clock: Option<(Arc<dyn Clock + Send + Sync>, u64)>, // and the start in nanoseconds
+}
+
+impl SolveMetrics {
+    pub(crate) fn start(sink: Option<&Arc<dyn MetricsSink>>, algorithm: &'static str) -> Option<Self> {
+        let sink = sink.cloned().or_else(default_sink)?;
+        let clock = clock().map(|clock| {
+            let start = clock.now_nanos();
+            (clock, start)
+        });
+        Some(Self { sink, algorithm, clock })
+    }
+
+    pub(crate) fn finish<Status: Debug>(self, status: Status, counters: &[(&str, usize)]) {
+        let status = format!("{status:?}");
+        self.sink.counter_add(&labeled(SOLVES_TOTAL, &[("algorithm", self.algorithm), ("status", &status)]), 1);
+        for &(name, value) in counters.iter() {
+            self.sink.counter_add(&labeled(name, &[("algorithm", self.algorithm)]), value as u64);
+        }
+        if let Some((clock, start)) = self.clock {
+            let nanos = clock.now_nanos().saturating_sub(start);
+            self.sink.histogram_observe(&labeled(SOLVE_SECONDS, &[("algorithm", self.algorithm)]), nanos as f64 / 1e9);
+        }
+    }
+}
+
+// the counters of the last solve, the first three also added to the sink of the solver. 0 for those it does not count
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+pub struct Stats {
+    pub num_pivots: usize,              // PIVOTS_TOTAL
+    pub num_augmentations: usize,       // AUGMENTATIONS_TOTAL
+    pub num_phases: usize,              // PHASES_TOTAL
+    pub num_degenerate_pivots: usize,   // PrimalNetworkSimplex, the pivots that move no flow
+    pub num_entering_edge_scans: usize, // searches of the pivot rule, the last one finds no entering edge
+    pub num_pushes: usize,              // PushRelabelFIFO, ExcessScaling, CostScalingPushRelabel
+    pub num_relabels: usize,
+    pub num_gap_relabelings: usize,
+    pub num_global_relabelings: usize, // the recomputations of the exact distances after the initial one
+    pub num_dijkstra_runs: usize,      // SuccessiveShortestPath, the repairs of the tree count with set_tree_repair
+}
+
+impl Stats {
+    pub(crate) fn from_counters(counters: &[(&str, usize)]) -> Self {
+        let mut stats = Self::default();
+        for &(name, value) in counters.iter() {
+            match name {
+                PIVOTS_TOTAL => stats.num_pivots = value,
+                AUGMENTATIONS_TOTAL => stats.num_augmentations = value,
+                PHASES_TOTAL => stats.num_phases = value,
+                _ => {}
+            }
+        }
+        stats
+    }
+}
+
+// how much a solver records beyond its solution, set by set_stats_level of each solver.
+// the counters (num_pivots, num_augmentations, ...) are kept at every level, since each is a single addition
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+pub enum StatsLevel {
+    Off,
+    #[default]
+    Counters,
+    // the counters and the distributions of the solver, one array index per event
+    Histograms,
+}
+
+// the upper bounds of the buckets are 1, 2, 4, ..., 2^(NUM_BUCKETS - 2) and u64::MAX.
+// a value v falls in the first bucket with v <= upper bound, so 0 and 1 are in the first one
+pub const NUM_BUCKETS: usize = 33;
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Histogram {
+    counts: [u64; NUM_BUCKETS],
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self { counts: [0; NUM_BUCKETS] }
+    }
+}
+
+impl Histogram {
+    pub fn bucket_upper_bound(bucket: usize) -> u64 {
+        if bucket + 1 < NUM_BUCKETS {
+            1 << bucket
+        } else {
+            u64::MAX
+        }
+    }
+
+    pub fn observe(&mut self, value: u64) {
+        let bucket = if value <= 1 { 0 } else { (u64::BITS - (value - 1).leading_zeros()) as usize };
+        self.counts[bucket.min(NUM_BUCKETS - 1)] += 1;
+    }
+
+    // for a non-negative flow, without converting it to u64
+    pub fn observe_flow<Flow>(&mut self, value: Flow)
+    where
+        Flow: NumAssign + Ord + Clone,
+    {
+        // the smallest bucket with value <= 2^bucket is the number of halvings of value - 1 down to zero
+        let two = Flow::one() + Flow::one();
+        let mut bucket = 0;
+        if value > Flow::one() {
+            let mut rest = value - Flow::one();
+            while rest > Flow::zero() && bucket + 1 < NUM_BUCKETS {
+                rest /= two.clone();
+                bucket += 1;
+            }
+        }
+        self.counts[bucket] += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    // (bucket upper bound, count) of the non-empty buckets in increasing order
+    pub fn buckets(&self) -> Vec<(u64, u64)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|&(_, &count)| count > 0)
+            .map(|(bucket, &count)| (Self::bucket_upper_bound(bucket), count))
+            .collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+    }
+}
@@ -1,13 +1,31 @@
+pub mod analysis;
+pub mod apply;
+pub mod artificial;
+pub mod assignment;
+pub mod basis;
+pub mod bicriteria;
+pub mod circulation;
+pub mod consistency;
+pub mod corridor;
 pub mod cost_scaling_push_relabel;
-mod csr;
+pub mod csr;
 pub mod cycle_canceling;
+pub mod decomposition;
 pub mod dual_network_simplex;
+pub mod exact_amount;
+pub mod fixed_charge;
+pub mod flow_result;
 pub mod graph;
+pub mod isomorphism;
+mod max_flow_min_cost;
 pub mod network_simplex_pivot_rules;
+pub mod node_cost;
 pub mod out_of_kilter;
 pub mod parametric_network_simplex;
 pub mod primal_dual;
 pub mod primal_network_simplex;
+pub mod solution;
+pub mod solver;
 pub mod spanning_tree_structure;
 pub mod status;
 pub mod successive_shortest_path;
@@ -0,0 +1,110 @@
+use crate::flow_ext::FlowExt;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use crate::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Perturbation {
+    Remove,        // the edge carries no flow, its lower bound included
+    HalveCapacity, // upper - lower is halved (rounded down)
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Criticality<Flow> {
+    Delta(Flow), // the optimal cost with the perturbed edge minus the baseline
+    Infeasible,  // the supplies cannot be sent with the perturbed edge
+}
+
+// how much the cost of an edge can decrease (lower_delta <= 0) or increase (upper_delta >= 0) with the optimal basis staying optimal.
+// None is unbounded
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct CostRange<Flow> {
+    pub lower_delta: Option<Flow>,
+    pub upper_delta: Option<Flow>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct CriticalityReport<Flow> {
+    pub baseline: Flow,
+    pub criticalities: Vec<Criticality<Flow>>, // in the order of the candidates
+    pub num_solves: usize,                     // the baseline is not counted
+}
+
+// the optimal cost degradation when each candidate edge alone is perturbed.
+// graph is solved for the baseline and keeps that optimal flow; the perturbations are solved on copies, so graph is not changed otherwise.
+// if the baseline flow of a candidate fits in the perturbed bounds, the delta is zero without solving:
+// the perturbed bounds are within the original ones, so the baseline flow stays feasible and nothing cheaper can become feasible.
+// Err(status) if the baseline is not optimal.
+pub fn edge_criticality<Flow>(graph: &mut Graph<Flow>, candidates: &[usize], perturbation: Perturbation) -> Result<CriticalityReport<Flow>, Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    assert!(candidates.iter().all(|&edge_id| edge_id < graph.num_edges()));
+
+    match solve(graph) {
+        Status::Optimal => {}
+        status => return Err(status),
+    }
+    let baseline = graph.minimum_cost();
+
+    let mut num_solves = 0;
+    let criticalities = candidates
+        .iter()
+        .map(|&edge_id| {
+            let edge = graph.get_edge(edge_id).unwrap();
+            let (lower, upper) = perturbed_bounds(edge.lower, edge.upper, perturbation);
+            if lower <= edge.flow && edge.flow <= upper {
+                return Criticality::Delta(Flow::zero());
+            }
+
+            num_solves += 1;
+            let mut perturbed = perturb(graph, edge_id, lower, upper);
+            match solve(&mut perturbed) {
+                Status::Optimal => Criticality::Delta(perturbed.minimum_cost() - baseline),
+                // the baseline is optimal, so only the perturbation can make it infeasible
+                _ => Criticality::Infeasible,
+            }
+        })
+        .collect();
+
+    Ok(CriticalityReport { baseline, criticalities, num_solves })
+}
+
+fn perturbed_bounds<Flow>(lower: Flow, upper: Flow, perturbation: Perturbation) -> (Flow, Flow)
+where
+    Flow: NumAssign + Copy,
+{
+    match perturbation {
+        Perturbation::Remove => (Flow::zero(), Flow::zero()),
+        Perturbation::HalveCapacity => (lower, lower + (upper - lower) / (Flow::one() + Flow::one())),
+    }
+}
+
+// the edges keep their ids
+fn perturb<Flow>(graph: &Graph<Flow>, perturbed_edge_id: usize, lower: Flow, upper: Flow) -> Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+{
+    let mut perturbed = Graph::default();
+    perturbed.add_nodes(graph.num_nodes());
+    perturbed.set_balance_policy(graph.balance_policy());
+    for u in 0..graph.num_nodes() {
+        perturbed.add_supply(u, graph.b[u]);
+    }
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        let (lower, upper) = if edge_id == perturbed_edge_id { (lower, upper) } else { (edge.lower, edge.upper) };
+        perturbed.push_edge(edge.from, edge.to, lower, upper, edge.cost);
+    }
+    perturbed
+}
+
+fn solve<Flow>(graph: &mut Graph<Flow>) -> Status
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+}
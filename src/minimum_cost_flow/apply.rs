@@ -0,0 +1,91 @@
+use crate::minimum_cost_flow::graph::Graph;
+use num_traits::NumAssign;
+use std::collections::HashMap;
+use std::ops::Neg;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Direction {
+    Forward,  // from -> to, increases the flow
+    Backward, // to -> from, decreases the flow
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ApplyError {
+    EmptyPath,
+    NegativeAmount,
+    EdgeOutOfRange { position: usize, edge_id: usize },
+    Disconnected { position: usize },
+    InsufficientCapacity { position: usize, edge_id: usize },
+}
+
+// send amount along the path (edges are in the orientation given to add_directed_edge)
+// nothing is changed if the path is not connected or some edge violates its bounds
+pub fn apply_path<Flow>(graph: &mut Graph<Flow>, path: &[(usize, Direction)], amount: Flow) -> Result<(), ApplyError>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    let (start, end) = check_path(graph, path, amount)?;
+    for &(edge_id, direction) in path.iter() {
+        let delta = if direction == Direction::Forward { amount } else { -amount };
+        graph.edges[edge_id].flow += delta;
+    }
+    graph.excesses[start] -= amount;
+    graph.excesses[end] += amount;
+    Ok(())
+}
+
+// cancel amount that was sent along the path
+pub fn unapply_path<Flow>(graph: &mut Graph<Flow>, path: &[(usize, Direction)], amount: Flow) -> Result<(), ApplyError>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    let reversed: Vec<(usize, Direction)> = path
+        .iter()
+        .rev()
+        .map(|&(edge_id, direction)| match direction {
+            Direction::Forward => (edge_id, Direction::Backward),
+            Direction::Backward => (edge_id, Direction::Forward),
+        })
+        .collect();
+    apply_path(graph, &reversed, amount)
+}
+
+// return the start and end nodes of the path
+fn check_path<Flow>(graph: &Graph<Flow>, path: &[(usize, Direction)], amount: Flow) -> Result<(usize, usize), ApplyError>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    if path.is_empty() {
+        return Err(ApplyError::EmptyPath);
+    }
+    if amount < Flow::zero() {
+        return Err(ApplyError::NegativeAmount);
+    }
+
+    // the same edge may appear more than once in a walk, so the bounds are checked against the accumulated change
+    let mut changes: HashMap<usize, Flow> = HashMap::new();
+    let (mut start, mut now) = (usize::MAX, usize::MAX);
+    for (position, &(edge_id, direction)) in path.iter().enumerate() {
+        let edge = graph.get_edge(edge_id).ok_or(ApplyError::EdgeOutOfRange { position, edge_id })?;
+        let (from, to, delta) = match direction {
+            Direction::Forward => (edge.from, edge.to, amount),
+            Direction::Backward => (edge.to, edge.from, -amount),
+        };
+
+        if position == 0 {
+            start = from;
+        } else if now != from {
+            return Err(ApplyError::Disconnected { position });
+        }
+        now = to;
+
+        let change = changes.entry(edge_id).or_insert(Flow::zero());
+        *change += delta;
+        let flow = edge.flow + *change;
+        if flow < edge.lower || flow > edge.upper {
+            return Err(ApplyError::InsufficientCapacity { position, edge_id });
+        }
+    }
+
+    Ok((start, now))
+}
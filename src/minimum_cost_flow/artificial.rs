@@ -0,0 +1,70 @@
+use std::fmt::{Display, Formatter};
+
+// what an artificial node added by the solvers to extend the network stands for
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ArtificialNodeRole {
+    SuperSource, // sends the supplies
+    SuperSink,   // receives the demands
+    Root,        // of the initial spanning tree
+}
+
+// what an artificial edge stands for, with the node of the graph it is attached to
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum ArtificialEdgeRole {
+    SupplySlack { node: usize }, // carries the supply of node
+    DemandSlack { node: usize }, // carries the demand of node
+    RootLink { node: usize },    // joins a node without supply nor demand to the root
+}
+
+impl ArtificialEdgeRole {
+    pub fn node(&self) -> usize {
+        match *self {
+            ArtificialEdgeRole::SupplySlack { node } | ArtificialEdgeRole::DemandSlack { node } | ArtificialEdgeRole::RootLink { node } => node,
+        }
+    }
+}
+
+impl Display for ArtificialNodeRole {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ArtificialNodeRole::SuperSource => write!(f, "super source"),
+            ArtificialNodeRole::SuperSink => write!(f, "super sink"),
+            ArtificialNodeRole::Root => write!(f, "root"),
+        }
+    }
+}
+
+impl Display for ArtificialEdgeRole {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            ArtificialEdgeRole::SupplySlack { node } => write!(f, "supply slack of node {node}"),
+            ArtificialEdgeRole::DemandSlack { node } => write!(f, "demand slack of node {node}"),
+            ArtificialEdgeRole::RootLink { node } => write!(f, "root link of node {node}"),
+        }
+    }
+}
+
+// the artificial nodes and edges of an extended network with their roles, in the order they were added
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct ArtificialStructure {
+    pub nodes: Vec<(usize, ArtificialNodeRole)>,
+    pub edges: Vec<(usize, ArtificialEdgeRole)>,
+}
+
+impl ArtificialStructure {
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    pub fn node_role(&self, u: usize) -> Option<ArtificialNodeRole> {
+        self.nodes.iter().find(|&&(v, _)| v == u).map(|&(_, role)| role)
+    }
+
+    pub fn edge_role(&self, edge_id: usize) -> Option<ArtificialEdgeRole> {
+        self.edges.iter().find(|&&(id, _)| id == edge_id).map(|&(_, role)| role)
+    }
+
+    pub fn edge_ids(&self) -> Vec<usize> {
+        self.edges.iter().map(|&(edge_id, _)| edge_id).collect()
+    }
+}
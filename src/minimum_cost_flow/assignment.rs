@@ -0,0 +1,152 @@
+use crate::flow_ext::FlowExt;
+use crate::maximum_flow::bipartite::maximum_bipartite_matching;
+use crate::minimum_cost_flow::cost_scaling_push_relabel::from_count;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use crate::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+// assigns min(rows, columns) rows to distinct columns at the minimum total cost, and returns the cost and the column of each row.
+// None in cost_matrix forbids the assignment, and Err(Status::Infeasible) is returned if the forbidden ones leave too few.
+// source -> row -> column -> sink with unit capacities, and min(rows, columns) units sent from source to sink
+pub fn minimum_cost_assignment<Flow>(cost_matrix: &[Vec<Option<Flow>>]) -> Result<(Flow, Vec<Option<usize>>), Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    let num_rows = cost_matrix.len();
+    let num_columns = cost_matrix.first().map_or(0, |row| row.len());
+    assert!(cost_matrix.iter().all(|row| row.len() == num_columns));
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_rows + num_columns + 2);
+    let (source, sink) = (num_rows + num_columns, num_rows + num_columns + 1);
+
+    let mut assignment_edges = Vec::new();
+    for (row, costs) in cost_matrix.iter().enumerate() {
+        for (column, cost) in costs.iter().enumerate() {
+            if let Some(cost) = *cost {
+                let edge_id = graph.push_edge(row, num_rows + column, Flow::zero(), Flow::one(), cost);
+                assignment_edges.push((edge_id, row, column));
+            }
+        }
+    }
+    let mut num_assignments = Flow::zero();
+    for row in 0..num_rows {
+        graph.push_edge(source, row, Flow::zero(), Flow::one(), Flow::zero());
+        if row < num_columns {
+            num_assignments += Flow::one();
+        }
+    }
+    for column in 0..num_columns {
+        graph.push_edge(num_rows + column, sink, Flow::zero(), Flow::one(), Flow::zero());
+    }
+    graph.add_supply(source, num_assignments);
+    graph.add_demand(sink, num_assignments);
+
+    match PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph) {
+        Status::Optimal => {
+            let mut columns = vec![None; num_rows];
+            for &(edge_id, row, column) in assignment_edges.iter() {
+                if graph.get_edge(edge_id).unwrap().flow == Flow::one() {
+                    columns[row] = Some(column);
+                }
+            }
+            Ok((graph.minimum_cost(), columns))
+        }
+        status => Err(status),
+    }
+}
+
+// the same result as minimum_cost_assignment, by bertsekas' auction with epsilon scaling, which is faster on dense instances.
+// the problem is made square with zero cost rows or columns, and the rows bid for the columns at the benefit -cost * (n + 1),
+// so that the assignment is optimal once every row is assigned with epsilon = 1 (n * epsilon < n + 1)
+pub fn minimum_cost_assignment_auction<Flow>(cost_matrix: &[Vec<Option<Flow>>]) -> Result<(Flow, Vec<Option<usize>>), Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+{
+    let num_rows = cost_matrix.len();
+    let num_columns = cost_matrix.first().map_or(0, |row| row.len());
+    assert!(cost_matrix.iter().all(|row| row.len() == num_columns));
+
+    // the auction does not terminate on infeasible instances, which are found by a maximum matching first
+    let pairs: Vec<(usize, usize)> = (0..num_rows)
+        .flat_map(|row| (0..num_columns).filter(move |&column| cost_matrix[row][column].is_some()).map(move |column| (row, column)))
+        .collect();
+    if maximum_bipartite_matching(num_rows, num_columns, &pairs).len() < num_rows.min(num_columns) {
+        return Err(Status::Infeasible);
+    }
+
+    let n = num_rows.max(num_columns);
+    let scale: Flow = from_count(n + 1);
+    // (column, benefit) of each row
+    let candidates: Vec<Vec<(usize, Flow)>> = (0..n)
+        .map(|row| {
+            let mut candidates: Vec<(usize, Flow)> = Vec::new();
+            if row < num_rows {
+                candidates.extend(cost_matrix[row].iter().enumerate().filter_map(|(column, cost)| cost.map(|cost| (column, -(cost * scale)))));
+                candidates.extend((num_columns..n).map(|column| (column, Flow::zero())));
+            } else {
+                candidates.extend((0..n).map(|column| (column, Flow::zero())));
+            }
+            candidates
+        })
+        .collect();
+
+    let alpha: Flow = from_count(4);
+    let mut epsilon = candidates.iter().flatten().map(|&(_, benefit)| benefit.max(-benefit)).max().unwrap_or(Flow::zero()).max(Flow::one());
+    let mut prices = vec![Flow::zero(); n];
+    let mut owners: Vec<Option<usize>> = vec![None; n];
+    let mut columns: Vec<Option<usize>> = vec![None; n];
+    loop {
+        // the prices are kept between the phases
+        owners.fill(None);
+        columns.fill(None);
+        let mut unassigned: Vec<usize> = (0..n).rev().collect();
+        while let Some(row) = unassigned.pop() {
+            let (mut best, mut best_value, mut second_value) = (n, None, None);
+            for &(column, benefit) in candidates[row].iter() {
+                let value = benefit - prices[column];
+                if best_value.is_none_or(|best_value| value > best_value) {
+                    second_value = best_value;
+                    (best, best_value) = (column, Some(value));
+                } else if second_value.is_none_or(|second_value| value > second_value) {
+                    second_value = Some(value);
+                }
+            }
+
+            // a row with a single column raises its price by epsilon only
+            let best_value = best_value.unwrap();
+            prices[best] += best_value - second_value.unwrap_or(best_value) + epsilon;
+            if let Some(previous) = owners[best].replace(row) {
+                columns[previous] = None;
+                unassigned.push(previous);
+            }
+            columns[row] = Some(best);
+        }
+
+        if epsilon == Flow::one() {
+            break;
+        }
+        epsilon = (epsilon / alpha).max(Flow::one());
+    }
+
+    let mut total_cost = Flow::zero();
+    let columns: Vec<Option<usize>> = columns[..num_rows].iter().map(|&column| column.filter(|&column| column < num_columns)).collect();
+    for (row, &column) in columns.iter().enumerate() {
+        if let Some(column) = column {
+            total_cost += cost_matrix[row][column].unwrap();
+        }
+    }
+    Ok((total_cost, columns))
+}
+
+// the maximum total weight, by minimum_cost_assignment_auction on the negated weights
+pub fn maximum_weight_assignment_auction<Flow>(weight_matrix: &[Vec<Option<Flow>>]) -> Result<(Flow, Vec<Option<usize>>), Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+{
+    let cost_matrix: Vec<Vec<Option<Flow>>> = weight_matrix.iter().map(|weights| weights.iter().map(|weight| weight.map(|weight| -weight)).collect()).collect();
+    minimum_cost_assignment_auction(&cost_matrix).map(|(total_cost, columns)| (-total_cost, columns))
+}
@@ -0,0 +1,157 @@
+use crate::minimum_cost_flow::graph::Graph;
+use crate::util::union_find::UnionFind;
+use num_traits::NumAssign;
+use std::hash::{Hash, Hasher};
+use std::ops::Neg;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum BasisError {
+    TopologyMismatch, // the nodes or the edges of the graph are not those of the bundle
+    InvalidBasis,     // an id out of range, or the tree edges do not make a spanning tree
+    Corrupted,        // the checksum does not match the contents
+}
+
+// the optimal basis of PrimalNetworkSimplex in the ids of the graph, to warm start a solve in another process.
+// the edges in neither list are at the lower bound, and the nodes in artificial_tree_nodes hang from the root of the extended network
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BasisBundle<Flow> {
+    pub fingerprint: u64,
+    pub tree_edge_ids: Vec<usize>,
+    pub artificial_tree_nodes: Vec<usize>,
+    pub upper_edge_ids: Vec<usize>,
+    pub potentials: Vec<Flow>,
+    pub checksum: u64,
+}
+
+// the potentials of the last solve of a graph, for CostScalingPushRelabel and SuccessiveShortestPath
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PotentialBundle<Flow> {
+    pub fingerprint: u64,
+    pub potentials: Vec<Flow>,
+    pub checksum: u64,
+}
+
+// fnv-1a on little endian integers, which unlike the std hasher is the same in every process and on every platform
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes.iter() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+// the number of nodes and the ends of the edges in order
+pub(crate) fn fingerprint(num_nodes: usize, edges: impl Iterator<Item = (usize, usize)>) -> u64 {
+    let mut hasher = Fnv::default();
+    num_nodes.hash(&mut hasher);
+    for (from, to) in edges {
+        (from, to).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+impl<Flow> BasisBundle<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Hash,
+{
+    pub(crate) fn new(fingerprint: u64, tree_edge_ids: Vec<usize>, artificial_tree_nodes: Vec<usize>, upper_edge_ids: Vec<usize>, potentials: Vec<Flow>) -> Self {
+        let mut bundle = Self { fingerprint, tree_edge_ids, artificial_tree_nodes, upper_edge_ids, potentials, checksum: 0 };
+        bundle.checksum = bundle.calculate_checksum();
+        bundle
+    }
+
+    fn calculate_checksum(&self) -> u64 {
+        let mut hasher = Fnv::default();
+        (self.fingerprint, &self.tree_edge_ids, &self.artificial_tree_nodes, &self.upper_edge_ids, &self.potentials).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn validate(&self, graph: &Graph<Flow>) -> Result<(), BasisError> {
+        if self.fingerprint != graph.topology_fingerprint() {
+            return Err(BasisError::TopologyMismatch);
+        }
+
+        let (num_nodes, num_edges) = (graph.num_nodes(), graph.num_edges());
+        let mut is_listed = vec![false; num_edges];
+        for &edge_id in self.tree_edge_ids.iter().chain(self.upper_edge_ids.iter()) {
+            if edge_id >= num_edges || is_listed[edge_id] {
+                return Err(BasisError::InvalidBasis);
+            }
+            is_listed[edge_id] = true;
+        }
+        if self.potentials.len() != num_nodes || self.artificial_tree_nodes.iter().any(|&u| u >= num_nodes) {
+            return Err(BasisError::InvalidBasis);
+        }
+
+        // num_nodes edges without a cycle span the extended network, whose root is num_nodes
+        if self.tree_edge_ids.len() + self.artificial_tree_nodes.len() != num_nodes {
+            return Err(BasisError::InvalidBasis);
+        }
+        let mut union_find = UnionFind::new(num_nodes + 1);
+        let tree_edges = self.tree_edge_ids.iter().map(|&edge_id| graph.get_edge(edge_id).unwrap()).map(|edge| (edge.from, edge.to));
+        for (u, v) in tree_edges.chain(self.artificial_tree_nodes.iter().map(|&u| (u, num_nodes))) {
+            if !union_find.union(u, v) {
+                return Err(BasisError::InvalidBasis);
+            }
+        }
+
+        if self.checksum != self.calculate_checksum() {
+            return Err(BasisError::Corrupted);
+        }
+        Ok(())
+    }
+}
+
+impl<Flow> PotentialBundle<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Hash,
+{
+    pub fn from_graph(graph: &Graph<Flow>) -> Self {
+        let potentials = (0..graph.num_nodes()).map(|u| graph.potential(u)).collect();
+        let mut bundle = Self { fingerprint: graph.topology_fingerprint(), potentials, checksum: 0 };
+        bundle.checksum = bundle.calculate_checksum();
+        bundle
+    }
+
+    fn calculate_checksum(&self) -> u64 {
+        let mut hasher = Fnv::default();
+        (self.fingerprint, &self.potentials).hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn validate(&self, graph: &Graph<Flow>) -> Result<(), BasisError> {
+        if self.fingerprint != graph.topology_fingerprint() {
+            return Err(BasisError::TopologyMismatch);
+        }
+        if self.potentials.len() != graph.num_nodes() {
+            return Err(BasisError::InvalidBasis);
+        }
+        if self.checksum != self.calculate_checksum() {
+            return Err(BasisError::Corrupted);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use crate::flow_ext::FlowExt;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use crate::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::{CheckedAdd, CheckedMul, NumAssign};
+use std::collections::VecDeque;
+use std::ops::Neg;
+
+// the point is optimal for the combined cost c1 + lambda * c2 with lambda in [lambda_lower, lambda_upper].
+// lambda is a fraction (numerator, denominator) and lambda_upper = None means infinity.
+#[derive(PartialEq, Debug, Clone)]
+pub struct FrontierPoint<Flow> {
+    pub objective1: Flow,
+    pub objective2: Flow,
+    pub lambda_lower: (Flow, Flow),
+    pub lambda_upper: Option<(Flow, Flow)>,
+    pub flows: Option<Vec<Flow>>,
+}
+
+#[derive(Debug)]
+pub struct Frontier<Flow> {
+    pub status: Status,
+    pub points: Vec<FrontierPoint<Flow>>, // in increasing order of objective1
+    pub truncated: bool,                  // max_points was reached before all breakpoints were found
+}
+
+struct Point<Flow> {
+    objective1: Flow,
+    objective2: Flow,
+    flows: Vec<Flow>,
+}
+
+// supported efficient points of the bicriteria minimum cost flow problem (the edge costs of graph and cost2).
+// the endpoints are the lexicographic minima, and each segment between two points is refined by solving
+// with the weights given by the segment until no point lies strictly below it.
+// combined costs are computed exactly, and Status::BadInput is returned if they overflow.
+// the objectives of the points are computed exactly too, and Status::Overflow is returned if they overflow.
+// the flow of the first point (the minimum of objective1) is written to graph.
+pub fn efficient_frontier<Flow>(graph: &mut Graph<Flow>, cost2: &[Flow], max_points: usize, with_flows: bool) -> Frontier<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + CheckedAdd + CheckedMul + FlowExt,
+{
+    assert_eq!(cost2.len(), graph.num_edges());
+    assert!(max_points >= 1);
+
+    let cost1: Vec<Flow> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().cost).collect();
+    let (points, truncated) = match solve_frontier(graph, &cost1, cost2, max_points) {
+        Ok(result) => result,
+        Err(status) => return Frontier { status, points: Vec::new(), truncated: false },
+    };
+
+    for (edge_id, &flow) in points[0].flows.iter().enumerate() {
+        graph.set_flow(edge_id, flow);
+    }
+
+    // breakpoint between p and q: p.objective1 + lambda * p.objective2 = q.objective1 + lambda * q.objective2
+    let breakpoints: Vec<(Flow, Flow)> = points.windows(2).map(|w| reduce(w[1].objective1 - w[0].objective1, w[0].objective2 - w[1].objective2)).collect();
+    let points = points
+        .into_iter()
+        .enumerate()
+        .map(|(i, point)| FrontierPoint {
+            objective1: point.objective1,
+            objective2: point.objective2,
+            lambda_lower: if i == 0 { (Flow::zero(), Flow::one()) } else { breakpoints[i - 1] },
+            lambda_upper: breakpoints.get(i).copied(),
+            flows: if with_flows { Some(point.flows) } else { None },
+        })
+        .collect();
+
+    Frontier { status: Status::Optimal, points, truncated }
+}
+
+#[allow(clippy::type_complexity)]
+fn solve_frontier<Flow>(graph: &Graph<Flow>, cost1: &[Flow], cost2: &[Flow], max_points: usize) -> Result<(Vec<Point<Flow>>, bool), Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + CheckedAdd + CheckedMul + FlowExt,
+{
+    let first = evaluate(cost1, cost2, solve_lexicographic(graph, cost1, cost2)?)?;
+    let last = evaluate(cost1, cost2, solve_lexicographic(graph, cost2, cost1)?)?;
+    if first.objective1 == last.objective1 && first.objective2 == last.objective2 {
+        return Ok((vec![first], false));
+    }
+
+    let mut points = vec![first, last];
+    if max_points < 2 {
+        points.truncate(max_points);
+        return Ok((points, true));
+    }
+
+    // segments between points[i] and the next point, identified by the objective1 of the left point
+    let mut segments = VecDeque::from([(points[0].objective1, points[1].objective1)]);
+    while let Some((left, right)) = segments.pop_front() {
+        if points.len() >= max_points {
+            return Ok((points, true));
+        }
+
+        let i = points.iter().position(|point| point.objective1 == left).unwrap();
+        debug_assert!(points[i + 1].objective1 == right);
+        let (w1, w2) = (points[i].objective2 - points[i + 1].objective2, points[i + 1].objective1 - points[i].objective1);
+        let combined: Vec<Flow> = (0..cost1.len())
+            .map(|edge_id| weighted_sum(w1, cost1[edge_id], w2, cost2[edge_id]))
+            .collect::<Option<_>>()
+            .ok_or(Status::BadInput)?;
+        let point = evaluate(cost1, cost2, solve(graph, &combined, None)?)?;
+
+        let line = weighted_sum(w1, points[i].objective1, w2, points[i].objective2).ok_or(Status::BadInput)?;
+        if weighted_sum(w1, point.objective1, w2, point.objective2).ok_or(Status::BadInput)? < line {
+            segments.push_back((left, point.objective1));
+            segments.push_back((point.objective1, right));
+            points.insert(i + 1, point);
+        }
+    }
+
+    Ok((points, false))
+}
+
+// minimize primary, and then secondary among the optimal solutions for primary.
+// the optimal solutions for primary are the flows that satisfy the complementary slackness with its optimal potentials.
+fn solve_lexicographic<Flow>(graph: &Graph<Flow>, primary: &[Flow], secondary: &[Flow]) -> Result<Vec<Flow>, Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    let mut restricted = build(graph, primary, None);
+    match PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(restricted.num_edges()), &mut restricted) {
+        Status::Optimal => {}
+        status => return Err(status),
+    }
+
+    let fixed: Vec<Option<Flow>> = (0..graph.num_edges())
+        .map(|edge_id| {
+            let edge = restricted.get_edge(edge_id).unwrap();
+            let reduced_cost = edge.cost - restricted.potential(edge.from) + restricted.potential(edge.to);
+            if reduced_cost > Flow::zero() {
+                Some(edge.lower)
+            } else if reduced_cost < Flow::zero() {
+                Some(edge.upper)
+            } else {
+                None
+            }
+        })
+        .collect();
+    solve(graph, secondary, Some(&fixed))
+}
+
+fn solve<Flow>(graph: &Graph<Flow>, costs: &[Flow], fixed: Option<&[Option<Flow>]>) -> Result<Vec<Flow>, Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    let mut restricted = build(graph, costs, fixed);
+    match PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(restricted.num_edges()), &mut restricted) {
+        Status::Optimal => Ok((0..restricted.num_edges()).map(|edge_id| restricted.get_edge(edge_id).unwrap().flow).collect()),
+        status => Err(status),
+    }
+}
+
+// copy of graph with the given costs (and the edges fixed to the given flows)
+fn build<Flow>(graph: &Graph<Flow>, costs: &[Flow], fixed: Option<&[Option<Flow>]>) -> Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+{
+    let mut copied = Graph::default();
+    copied.add_nodes(graph.num_nodes());
+    for u in 0..graph.num_nodes() {
+        copied.add_supply(u, graph.b[u]);
+    }
+    for (edge_id, &cost) in costs.iter().enumerate() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        let (lower, upper) = match fixed.and_then(|fixed| fixed[edge_id]) {
+            Some(flow) => (flow, flow),
+            None => (edge.lower, edge.upper),
+        };
+        copied.push_edge(edge.from, edge.to, lower, upper, cost);
+    }
+    copied
+}
+
+// Err(Status::Overflow) if an objective does not fit in Flow
+fn evaluate<Flow>(cost1: &[Flow], cost2: &[Flow], flows: Vec<Flow>) -> Result<Point<Flow>, Status>
+where
+    Flow: NumAssign + Copy + CheckedAdd + CheckedMul,
+{
+    let objective = |costs: &[Flow]| {
+        costs
+            .iter()
+            .zip(flows.iter())
+            .try_fold(Flow::zero(), |sum, (cost, flow)| sum.checked_add(&cost.checked_mul(flow)?))
+            .ok_or(Status::Overflow)
+    };
+    Ok(Point { objective1: objective(cost1)?, objective2: objective(cost2)?, flows })
+}
+
+fn weighted_sum<Flow>(w1: Flow, x1: Flow, w2: Flow, x2: Flow) -> Option<Flow>
+where
+    Flow: CheckedAdd + CheckedMul,
+{
+    w1.checked_mul(&x1)?.checked_add(&w2.checked_mul(&x2)?)
+}
+
+// numerator / denominator in lowest terms (denominator > 0)
+fn reduce<Flow>(numerator: Flow, denominator: Flow) -> (Flow, Flow)
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let (mut a, mut b) = (numerator, denominator);
+    while b != Flow::zero() {
+        (a, b) = (b, a % b);
+    }
+    let g = if a < Flow::zero() { Flow::zero() - a } else { a };
+    (numerator / g, denominator / g)
+}
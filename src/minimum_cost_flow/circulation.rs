@@ -0,0 +1,21 @@
+use crate::flow_ext::FlowExt;
+use crate::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+// a minimum cost circulation: every flow within [lower, upper] of its edge and conserved at every node, without supplies.
+// the graph keeps a negative cost edge at its upper bound, and the cycle canceling sends the excesses this and the lower bounds leave
+// and then cancels the negative residual cycles, so the zero circulation is returned as it is when no cycle improves it.
+// Status::BadInput if a node has a supply, and Status::Infeasible if the lower bounds cannot be met.
+// every edge has a finite upper, so a negative cycle has a bounded capacity and Status::Unbounded is not returned
+pub fn minimum_cost_circulation<Flow>(graph: &mut Graph<Flow>) -> Status
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    if graph.b.iter().any(|&b| b != Flow::zero()) {
+        return Status::BadInput;
+    }
+    CycleCanceling::default().solve(graph)
+}
@@ -0,0 +1,239 @@
+use crate::approx::snap;
+use crate::minimum_cost_flow::spanning_tree_structure::{EdgeState, SpanningTreeStructure};
+use num_traits::NumAssign;
+use std::fmt::{Display, Formatter};
+use std::ops::{BitOr, BitOrAssign, Neg};
+
+// checks of the state of a solve that are cheap enough for release builds, run every interval pivots.
+// a full round costs O(nodes + edges), about one scan of BestEligibleArcPivotRule, so an interval of k adds at most 1/k of such a scan per pivot
+// (examples/consistency_overhead.rs: ALL every 1000 pivots made a solve on 2000 nodes and 50000 edges about 5% slower)
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub struct ConsistencyChecks(u32);
+
+impl ConsistencyChecks {
+    pub const NONE: Self = Self(0);
+    pub const PERIODIC_EXCESS_SUM: Self = Self(1); // the excesses sum to zero, as the supplies of a balanced graph do
+    pub const TREE_STRUCTURE: Self = Self(1 << 1); // the parents, the thread and the numbers of successors of the spanning tree agree
+    pub const REDUCED_COST_SAMPLE: Self = Self(1 << 2); // the tree edges of a random sample of nodes have zero reduced costs
+    pub const FLOW_BOUNDS_FULL: Self = Self(1 << 3); // every flow is within its bounds, and at its bound outside the tree
+    pub const ALL: Self = Self(0b1111);
+
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    // the unknown bits are dropped
+    pub fn from_bits_truncate(bits: u32) -> Self {
+        Self(bits & Self::ALL.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for ConsistencyChecks {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for ConsistencyChecks {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl Display for ConsistencyChecks {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let names = [
+            (Self::PERIODIC_EXCESS_SUM, "PERIODIC_EXCESS_SUM"),
+            (Self::TREE_STRUCTURE, "TREE_STRUCTURE"),
+            (Self::REDUCED_COST_SAMPLE, "REDUCED_COST_SAMPLE"),
+            (Self::FLOW_BOUNDS_FULL, "FLOW_BOUNDS_FULL"),
+        ];
+        let names: Vec<&str> = names.iter().filter(|&&(check, _)| self.contains(check)).map(|&(_, name)| name).collect();
+        if names.is_empty() {
+            write!(f, "NONE")
+        } else {
+            write!(f, "{}", names.join(" | "))
+        }
+    }
+}
+
+// the check that failed and the number of pivots done when it ran, behind Status::InternalInvariant
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct InvariantViolation {
+    pub check: ConsistencyChecks,
+    pub num_pivots: usize,
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(f, "{} failed after {} pivots", self.check, self.num_pivots)
+    }
+}
+
+// a corruption of the state of the solve, to test that the checks catch it
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum InjectedFault {
+    Excess,    // the excess of a node is increased
+    Parent,    // a node gets a wrong parent
+    Potential, // the potential of a node with a tree edge is increased
+    Flow,      // the flow of an edge goes over its upper bound
+}
+
+const NUM_SAMPLED_NODES: usize = 64;
+
+#[derive(Default)]
+pub(crate) struct ConsistencyChecker {
+    checks: ConsistencyChecks,
+    interval: usize,
+    num_rounds: usize,
+    state: u64, // xorshift for the sampled nodes
+    injected_fault: Option<(InjectedFault, usize)>,
+}
+
+impl ConsistencyChecker {
+    pub(crate) fn new(checks: ConsistencyChecks, interval: usize) -> Self {
+        Self { checks, interval: interval.max(1), num_rounds: 0, state: 88172645463325252, injected_fault: None }
+    }
+
+    pub(crate) fn set_injected_fault(&mut self, injected_fault: Option<(InjectedFault, usize)>) {
+        self.injected_fault = injected_fault;
+    }
+
+    pub(crate) fn start(&mut self) {
+        self.num_rounds = 0;
+    }
+
+    // the number of rounds of checks of the last solve
+    pub(crate) fn num_rounds(&self) -> usize {
+        self.num_rounds
+    }
+
+    pub(crate) fn is_due(&self, num_pivots: usize) -> bool {
+        !self.checks.is_empty() && num_pivots.is_multiple_of(self.interval)
+    }
+
+    pub(crate) fn check<Flow>(&mut self, st: &mut SpanningTreeStructure<Flow>, num_pivots: usize) -> Result<(), InvariantViolation>
+    where
+        Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+    {
+        if let Some((fault, _)) = self.injected_fault.filter(|&(_, at)| at == num_pivots) {
+            inject(st, fault);
+        }
+        self.num_rounds += 1;
+
+        let violation = |check| Err(InvariantViolation { check, num_pivots });
+        if self.checks.contains(ConsistencyChecks::PERIODIC_EXCESS_SUM) && snap(st.excesses.iter().fold(Flow::zero(), |sum, excess| sum + excess.clone()), &st.tolerance) != Flow::zero() {
+            return violation(ConsistencyChecks::PERIODIC_EXCESS_SUM);
+        }
+        if self.checks.contains(ConsistencyChecks::TREE_STRUCTURE) && !is_tree_consistent(st) {
+            return violation(ConsistencyChecks::TREE_STRUCTURE);
+        }
+        if self.checks.contains(ConsistencyChecks::REDUCED_COST_SAMPLE) && !self.is_sample_optimal(st) {
+            return violation(ConsistencyChecks::REDUCED_COST_SAMPLE);
+        }
+        if self.checks.contains(ConsistencyChecks::FLOW_BOUNDS_FULL) && !are_flows_at_bounds(st) {
+            return violation(ConsistencyChecks::FLOW_BOUNDS_FULL);
+        }
+        Ok(())
+    }
+
+    // all the nodes if there are at most NUM_SAMPLED_NODES, and NUM_SAMPLED_NODES consecutive ones from a random node otherwise
+    fn is_sample_optimal<Flow>(&mut self, st: &SpanningTreeStructure<Flow>) -> bool
+    where
+        Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+    {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        let start = (self.state % st.num_nodes as u64) as usize;
+        (0..NUM_SAMPLED_NODES.min(st.num_nodes)).map(|i| (start + i) % st.num_nodes).filter(|&u| u != st.root).all(|u| {
+            let edge_id = st.nodes[u].parent_edge_id;
+            edge_id < st.num_edges && snap(st.reduced_cost(&st.edges[edge_id]), &st.tolerance) == Flow::zero()
+        })
+    }
+}
+
+fn is_tree_consistent<Flow>(st: &SpanningTreeStructure<Flow>) -> bool
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    // every node other than the root hangs by a tree edge joining it to its parent
+    for (u, node) in st.nodes.iter().enumerate().take(st.num_nodes) {
+        if u == st.root {
+            if node.parent != usize::MAX {
+                return false;
+            }
+            continue;
+        }
+        let Some(edge) = st.edges.get(node.parent_edge_id) else {
+            return false;
+        };
+        if edge.state != EdgeState::Tree || (edge.from, edge.to) != (u, node.parent) && (edge.from, edge.to) != (node.parent, u) {
+            return false;
+        }
+    }
+
+    // the thread visits every node once, a parent before its children, and back
+    let mut seen = vec![false; st.num_nodes];
+    let mut now = st.root;
+    for _ in 0..st.num_nodes {
+        if seen[now] || st.prev_node_dft[st.next_node_dft[now]] != now {
+            return false;
+        }
+        let parent = st.nodes[now].parent;
+        if parent != usize::MAX && !seen[parent] {
+            return false;
+        }
+        seen[now] = true;
+        now = st.next_node_dft[now];
+    }
+    now == st.root && st.validate_num_successors(st.root)
+}
+
+fn are_flows_at_bounds<Flow>(st: &SpanningTreeStructure<Flow>) -> bool
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    // within the tolerance
+    let tolerance = &st.tolerance;
+    st.edges.iter().all(|edge| {
+        -tolerance.clone() <= edge.flow
+            && edge.flow <= edge.upper.clone() + tolerance.clone()
+            && match edge.state {
+                EdgeState::Lower => snap(edge.flow.clone(), tolerance) == Flow::zero(),
+                EdgeState::Upper => snap(edge.residual_capacity(), tolerance) == Flow::zero(),
+                EdgeState::Tree => true,
+            }
+    })
+}
+
+fn inject<Flow>(st: &mut SpanningTreeStructure<Flow>, fault: InjectedFault)
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    let Some(u) = (0..st.num_nodes).find(|&u| u != st.root) else {
+        return;
+    };
+    match fault {
+        InjectedFault::Excess => st.excesses[u] += Flow::one(),
+        InjectedFault::Parent => {
+            st.nodes[u].parent = if st.nodes[u].parent == st.root {
+                (0..st.num_nodes).find(|&v| v != u && v != st.root).unwrap_or(u)
+            } else {
+                st.root
+            }
+        }
+        InjectedFault::Potential => st.nodes[u].potential += Flow::one(),
+        InjectedFault::Flow => st.edges[0].flow = st.edges[0].upper.clone() + Flow::one(),
+    }
+}
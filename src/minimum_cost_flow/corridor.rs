@@ -0,0 +1,102 @@
+use crate::minimum_cost_flow::graph::Graph;
+use num_traits::{NumAssign, ToPrimitive};
+use std::collections::BTreeMap;
+use std::ops::Neg;
+
+// how the edges are grouped into corridors
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CorridorDirection {
+    #[default]
+    Ordered, // u -> v and v -> u are two corridors
+    Unordered, // one corridor for both, from the smaller node to the larger one
+}
+
+// the edge ids of each corridor by its (from, to)
+pub(crate) type CorridorIndex = BTreeMap<(usize, usize), Vec<usize>>;
+
+// all the parallel edges between a pair of nodes seen as one link, with the edges as given to add_directed_edge
+#[derive(PartialEq, Debug, Clone)]
+pub struct Corridor<Flow> {
+    pub from: usize,
+    pub to: usize,
+    pub edge_ids: Vec<usize>,
+    pub net_flow: Flow,     // from -> to minus to -> from, the same as gross_flow for an ordered corridor
+    pub gross_flow: Flow,   // in both directions
+    pub capacity: Flow,     // the sum of the uppers
+    pub cost: Flow,         // the sum of flow * cost
+    pub is_saturated: bool, // some edge has flow = upper > 0
+}
+
+impl<Flow> Corridor<Flow>
+where
+    Flow: NumAssign + Ord + Copy + ToPrimitive,
+{
+    // the cost per unit of the flow, None without flow
+    pub fn average_cost(&self) -> Option<f64> {
+        if self.gross_flow == Flow::zero() {
+            return None;
+        }
+        Some(self.cost.to_f64().unwrap() / self.gross_flow.to_f64().unwrap())
+    }
+}
+
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    pub fn set_corridor_direction(&mut self, direction: CorridorDirection) {
+        self.corridor_direction = direction;
+        self.corridor_index.take();
+    }
+
+    pub fn corridor_direction(&self) -> CorridorDirection {
+        self.corridor_direction
+    }
+
+    // the corridors in the order of (from, to), with the flows of the last solve
+    pub fn corridors(&self) -> impl Iterator<Item = Corridor<Flow>> + '_ {
+        self.corridor_index().iter().map(|(&(from, to), edge_ids)| self.corridor(from, to, edge_ids))
+    }
+
+    // the corridor from u to v, which is also the one from v to u if the direction is unordered
+    pub fn corridor_between(&self, u: usize, v: usize) -> Option<Corridor<Flow>> {
+        let key = self.corridor_key(u, v);
+        self.corridor_index().get(&key).map(|edge_ids| self.corridor(key.0, key.1, edge_ids))
+    }
+
+    fn corridor_key(&self, u: usize, v: usize) -> (usize, usize) {
+        match self.corridor_direction {
+            CorridorDirection::Ordered => (u, v),
+            CorridorDirection::Unordered => (u.min(v), u.max(v)),
+        }
+    }
+
+    fn corridor_index(&self) -> &CorridorIndex {
+        self.corridor_index.get_or_init(|| {
+            let mut index = CorridorIndex::new();
+            for edge_id in 0..self.num_edges() {
+                let edge = self.get_edge(edge_id).unwrap();
+                index.entry(self.corridor_key(edge.from, edge.to)).or_default().push(edge_id);
+            }
+            index
+        })
+    }
+
+    fn corridor(&self, from: usize, to: usize, edge_ids: &[usize]) -> Corridor<Flow> {
+        let mut corridor = Corridor { from, to, edge_ids: edge_ids.to_vec(), net_flow: Flow::zero(), gross_flow: Flow::zero(), capacity: Flow::zero(), cost: Flow::zero(), is_saturated: false };
+        for edge in edge_ids.iter().map(|&edge_id| self.get_edge(edge_id).unwrap()) {
+            // a loop of an unordered corridor goes both ways, and is counted forward
+            if edge.from == from {
+                corridor.net_flow += edge.flow;
+            } else {
+                corridor.net_flow -= edge.flow;
+            }
+            corridor.gross_flow += edge.flow;
+            corridor.capacity += edge.upper;
+            corridor.cost += edge.flow * edge.cost;
+            corridor.is_saturated |= edge.flow == edge.upper && edge.upper > Flow::zero();
+        }
+        corridor
+    }
+}
@@ -1,64 +1,200 @@
+use crate::cooperative::{ignore_progress, CancellationToken, Progress};
+use crate::flow_ext::FlowExt;
 use crate::maximum_flow::capacity_scaling::CapacityScaling;
 use crate::maximum_flow::graph;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PHASES_TOTAL};
+use crate::minimum_cost_flow::basis::{BasisError, PotentialBundle};
 use crate::minimum_cost_flow::csr::{InsideEdge, CSR};
+use crate::minimum_cost_flow::flow_result::{commit, FlowResult};
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::status::Status;
-use num_traits::{FromPrimitive, NumAssign};
-use std::collections::VecDeque;
-use std::ops::Neg;
+use num_traits::{CheckedMul, NumAssign};
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::{ControlFlow, Neg};
+use std::sync::Arc;
+
+// discharges between two looks at the cancellation token
+const CANCELLATION_INTERVAL: usize = 1024;
+
+// the active node that refine discharges next. every order gives an epsilon-optimal flow,
+// since a push or a relabel keeps the epsilon-optimality whichever node it is applied to
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+pub enum ActiveNodeOrder {
+    Fifo,
+    #[default]
+    Lifo,
+    // the node of the highest potential first, the one whose excess is the furthest from the deficits
+    HighestPotential,
+}
 
 pub struct CostScalingPushRelabel<Flow> {
     csr: CSR<Flow>,
+    active_node_order: ActiveNodeOrder,
     active_nodes: VecDeque<usize>,
+    active_heap: BinaryHeap<(Flow, usize)>, // (potential when pushed, node) with HighestPotential, outdated entries are skipped
     current_edge: Vec<usize>,
     alpha: Flow,
+    imported_potentials: Option<Vec<Flow>>,
+    num_iterations: usize,
+    cancellation_token: Option<CancellationToken>,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> Default for CostScalingPushRelabel<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FromPrimitive + Default,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + CheckedMul + FlowExt,
 {
     fn default() -> Self {
-        Self { csr: CSR::default(), active_nodes: VecDeque::new(), current_edge: Vec::new(), alpha: Flow::from_isize(16).unwrap() }
+        Self {
+            csr: CSR::default(),
+            active_node_order: ActiveNodeOrder::default(),
+            active_nodes: VecDeque::new(),
+            active_heap: BinaryHeap::new(),
+            current_edge: Vec::new(),
+            alpha: from_count(16),
+            imported_potentials: None,
+            num_iterations: 0,
+            cancellation_token: None,
+            metrics_sink: None,
+            stats: Stats::default(),
+        }
     }
 }
 
 #[allow(dead_code)]
 impl<Flow> CostScalingPushRelabel<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FromPrimitive + Default,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + CheckedMul + FlowExt,
 {
     // scaling_factor: it was usually between 8 and 24. default scaling factor is 16
-    pub fn new(scaling_factor: Flow) -> Self {
-        assert!(scaling_factor > Flow::one());
-        Self { csr: CSR::default(), active_nodes: VecDeque::new(), current_edge: Vec::new(), alpha: scaling_factor }
+    pub fn new(scaling_factor: u32) -> Self {
+        assert!(scaling_factor > 1);
+        Self {
+            csr: CSR::default(),
+            active_node_order: ActiveNodeOrder::default(),
+            active_nodes: VecDeque::new(),
+            active_heap: BinaryHeap::new(),
+            current_edge: Vec::new(),
+            alpha: from_count(scaling_factor as usize),
+            imported_potentials: None,
+            num_iterations: 0,
+            cancellation_token: None,
+            metrics_sink: None,
+            stats: Stats::default(),
+        }
+    }
+
+    pub fn set_active_node_order(&mut self, order: ActiveNodeOrder) {
+        self.active_node_order = order;
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // looked at every CANCELLATION_INTERVAL discharges. the pseudo flow of a refine is not feasible,
+    // so the graph is left as it was before the solve with Status::Cancelled
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation_token = Some(token);
+    }
+
+    // the next solve starts from the potentials of bundle. they are taken to be within a unit of cost of optimal ones,
+    // so the scaling starts at epsilon = alpha * n for the scaled costs instead of the largest cost, and fewer phases are run
+    pub fn import_potentials(&mut self, graph: &Graph<Flow>, bundle: &PotentialBundle<Flow>) -> Result<(), BasisError>
+    where
+        Flow: Hash,
+    {
+        bundle.validate(graph)?;
+        self.imported_potentials = Some(bundle.potentials.clone());
+        Ok(())
     }
 
     pub fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve_with_progress(graph, ignore_progress)
+    }
+
+    // progress is called after each refine, and ControlFlow::Break leaves the graph as it was before the solve with Status::Cancelled
+    pub fn solve_with_progress(&mut self, graph: &mut Graph<Flow>, mut progress: impl FnMut(Progress<Flow>) -> ControlFlow<()>) -> Status {
+        graph.solve_balanced(|graph| {
+            let result = self.solve_ref_with_progress(graph, &mut progress);
+            commit(graph, &result);
+            result.status()
+        })
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit.
+    // the balance policy of graph is applied by solve, not here
+    pub fn solve_ref(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.solve_ref_with_progress(graph, &mut ignore_progress)
+    }
+
+    fn solve_ref_with_progress(&mut self, graph: &Graph<Flow>, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "cost_scaling_push_relabel");
+        self.stats = Stats::default();
+        let result = self.solve_inner(graph, progress);
+        self.stats.num_phases = self.num_iterations;
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(PHASES_TOTAL, self.num_iterations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, graph: &Graph<Flow>, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> FlowResult<Flow> {
+        self.num_iterations = 0;
+        let imported_potentials = self.imported_potentials.take().filter(|potentials| potentials.len() == graph.num_nodes());
+
         if graph.is_unbalance() {
-            return Status::Unbalanced;
+            return FlowResult::without_flows(Status::Unbalanced);
         }
         self.csr.build(graph);
 
-        // all edge costs are non-negative
+        // no residual edge has a negative cost, as a negative cost edge starts at its upper bound
         if self.csr.excesses.iter().all(|&excess| excess == Flow::zero()) {
-            return Status::Optimal;
+            return FlowResult::without_flows(Status::Optimal);
         }
 
         if !self.check_feasibility(graph) {
-            return Status::Infeasible;
+            return FlowResult::without_flows(Status::Infeasible);
         }
 
         self.current_edge.resize(self.csr.num_nodes, 0);
-        let gamma = self.csr.inside_edge_list.iter().map(|e| e.cost).max().unwrap_or(Flow::one()); // all edge costs are non-negative
-        let cost_scaling_factor = self.alpha * Flow::from_usize(self.csr.num_nodes).unwrap();
-        let mut epsilon = Flow::one().max(gamma * cost_scaling_factor);
+        // every edge is in the residual network both ways, so this is the largest absolute cost
+        let gamma = self.csr.inside_edge_list.iter().map(|e| e.cost).max().unwrap_or(Flow::one());
+        // costs are premultiplied by alpha * n, so that epsilon = 1 at the last phase means (1 / n)-optimal for the original costs.
+        // max cost * alpha * n must fit in Flow, Status::Overflow if it does not
+        let Some(cost_scaling_factor) = self.alpha.checked_mul(&from_count(self.csr.num_nodes)) else {
+            return FlowResult::without_flows(Status::Overflow);
+        };
+        let Some(max_scaled_cost) = gamma.checked_mul(&cost_scaling_factor) else {
+            return FlowResult::without_flows(Status::Overflow);
+        };
+        let mut epsilon = Flow::one().max(max_scaled_cost);
+        if let Some(potentials) = imported_potentials {
+            let Some(potentials) = potentials.into_iter().map(|potential| potential.checked_mul(&cost_scaling_factor)).collect::<Option<Vec<_>>>() else {
+                return FlowResult::without_flows(Status::Overflow);
+            };
+            self.csr.potentials = potentials;
+            // alpha * alpha * n does not fit when alpha * n is near the maximum, and then epsilon stays at max_scaled_cost
+            if let Some(imported_epsilon) = self.alpha.checked_mul(&cost_scaling_factor) {
+                epsilon = epsilon.min(imported_epsilon);
+            }
+        }
 
         // scale cost
         self.csr.inside_edge_list.iter_mut().for_each(|e| e.cost *= cost_scaling_factor);
         loop {
             epsilon = Flow::one().max(epsilon / self.alpha);
-            self.refine(epsilon);
+            if !self.refine(epsilon) {
+                return FlowResult::without_flows(Status::Cancelled);
+            }
+            self.num_iterations += 1;
+            if progress(Progress::CostScaling { epsilon, num_phases: self.num_iterations }).is_break() {
+                return FlowResult::without_flows(Status::Cancelled);
+            }
             if epsilon == Flow::one() {
                 break;
             }
@@ -66,13 +202,21 @@ where
         // unscale cost
         self.csr.inside_edge_list.iter_mut().for_each(|e| e.cost /= cost_scaling_factor);
 
-        self.csr.set_flow(graph);
+        // the potentials are only 1-optimal for the scaled costs. unscaled, each reduced cost is off by at most a unit,
+        // so the exact potentials are found from them with a few relabels
+        self.csr.potentials.iter_mut().for_each(|potential| *potential /= cost_scaling_factor);
+        let has_no_negative_cycle = self.csr.compute_potentials();
+        debug_assert!(has_no_negative_cycle);
+        FlowResult::from_csr(Status::Optimal, &self.csr, graph.num_nodes(), graph.num_edges())
+    }
 
-        Status::Optimal
+    pub fn solve_with_solution(&mut self, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(graph);
+        Solution::from_status(status, graph, self.num_iterations)
     }
 
-    // make epsilon-optimal flow
-    fn refine(&mut self, epsilon: Flow) {
+    // make epsilon-optimal flow, return false if the token is cancelled
+    fn refine(&mut self, epsilon: Flow) -> bool {
         // make 0-optimal pseudo flow
         for u in 0..self.csr.num_nodes {
             for edge_id in self.csr.start[u]..self.csr.start[u + 1] {
@@ -90,12 +234,52 @@ where
         }
 
         self.current_edge.iter_mut().enumerate().for_each(|(u, e)| *e = self.csr.start[u]);
-        debug_assert_eq!(self.active_nodes.len(), 0);
-        self.active_nodes.extend((0..self.csr.num_nodes).filter(|&u| self.csr.excesses[u] > Flow::zero()));
+        debug_assert!(self.active_nodes.is_empty() && self.active_heap.is_empty());
+        for u in 0..self.csr.num_nodes {
+            if self.csr.excesses[u] > Flow::zero() {
+                self.push_active_node(u);
+            }
+        }
 
         // 0-optimal pseudo flow -> epsilon-optimal feasible flow
-        while let Some(u) = self.active_nodes.pop_back() {
+        let mut num_discharges = 0;
+        while let Some(u) = self.pop_active_node() {
+            if num_discharges % CANCELLATION_INTERVAL == 0 && self.cancellation_token.as_ref().is_some_and(|token| token.is_cancelled()) {
+                self.active_nodes.clear();
+                self.active_heap.clear();
+                return false;
+            }
             self.discharge(u, epsilon);
+            num_discharges += 1;
+        }
+        true
+    }
+
+    fn push_active_node(&mut self, u: usize) {
+        match self.active_node_order {
+            ActiveNodeOrder::Fifo | ActiveNodeOrder::Lifo => self.active_nodes.push_back(u),
+            ActiveNodeOrder::HighestPotential => self.active_heap.push((self.csr.potentials[u], u)),
+        }
+    }
+
+    fn pop_active_node(&mut self) -> Option<usize> {
+        match self.active_node_order {
+            ActiveNodeOrder::Fifo => self.active_nodes.pop_front(),
+            ActiveNodeOrder::Lifo => self.active_nodes.pop_back(),
+            // the potential of a queued node rises when look_ahead relabels it, and it is queued again with the new one
+            ActiveNodeOrder::HighestPotential => {
+                while let Some((potential, u)) = self.active_heap.pop() {
+                    if self.csr.excesses[u] <= Flow::zero() {
+                        continue;
+                    }
+                    if potential != self.csr.potentials[u] {
+                        self.active_heap.push((self.csr.potentials[u], u));
+                        continue;
+                    }
+                    return Some(u);
+                }
+                None
+            }
         }
     }
 
@@ -118,7 +302,9 @@ where
     fn push(&mut self, u: usize, epsilon: Flow) {
         debug_assert!(self.csr.excesses[u] > Flow::zero());
 
-        for edge_id in self.csr.start[u]..self.csr.start[u + 1] {
+        let mut next = self.csr.start[u];
+        while let Some(edge_id) = self.csr.next_residual_edge(next, self.csr.start[u + 1]) {
+            next = edge_id + 1;
             let edge = &self.csr.inside_edge_list[edge_id];
             let to = edge.to;
             if edge.residual_capacity() <= Flow::zero() {
@@ -129,17 +315,16 @@ where
                 continue;
             }
 
-            if !self.look_ahead(to, epsilon) {
-                if !self.is_admissible(u, &self.csr.inside_edge_list[edge_id], epsilon) {
-                    continue;
-                }
+            if !self.look_ahead(to, epsilon) && !self.is_admissible(u, &self.csr.inside_edge_list[edge_id], epsilon) {
+                continue;
             }
 
             let flow = self.csr.inside_edge_list[edge_id].residual_capacity().min(self.csr.excesses[u]);
             self.csr.push_flow(u, edge_id, flow);
 
+            self.stats.num_pushes += 1;
             if self.csr.excesses[to] > Flow::zero() && self.csr.excesses[to] <= flow {
-                self.active_nodes.push_back(to);
+                self.push_active_node(to);
             }
 
             if self.csr.excesses[u] == Flow::zero() {
@@ -153,13 +338,16 @@ where
     }
 
     fn relabel(&mut self, u: usize, epsilon: Flow) {
+        self.stats.num_relabels += 1;
         let guaranteed_new_potential = self.csr.potentials[u] + epsilon;
 
         let mut mini_potential = None;
         let mut previous_mini_potential = None;
         let mut current_edges_for_u = 0;
 
-        for edge_id in self.csr.start[u]..self.csr.start[u + 1] {
+        let mut next = self.csr.start[u];
+        while let Some(edge_id) = self.csr.next_residual_edge(next, self.csr.start[u + 1]) {
+            next = edge_id + 1;
             if self.csr.inside_edge_list[edge_id].residual_capacity() <= Flow::zero() {
                 continue;
             }
@@ -211,7 +399,9 @@ where
         }
 
         // search admissible edge
-        for edge_id in self.current_edge[u]..self.csr.start[u + 1] {
+        let mut next = self.current_edge[u];
+        while let Some(edge_id) = self.csr.next_residual_edge(next, self.csr.start[u + 1]) {
+            next = edge_id + 1;
             let edge = &self.csr.inside_edge_list[edge_id];
             if edge.residual_capacity() <= Flow::zero() {
                 continue;
@@ -230,28 +420,59 @@ where
     fn check_feasibility(&self, graph: &Graph<Flow>) -> bool {
         let mut maximum_flow_graph = graph::Graph::default();
         maximum_flow_graph.add_nodes(graph.num_nodes());
-        let source = maximum_flow_graph.add_node();
-        let sink = maximum_flow_graph.add_node();
+        let source = maximum_flow_graph.add_node_raw();
+        let sink = maximum_flow_graph.add_node_raw();
 
         let mut excesses = graph.b.clone();
         for (edge_id, edge) in graph.edges.iter().enumerate() {
-            let (from, to) = if graph.is_reversed[edge_id] { (edge.to, edge.from) } else { (edge.from, edge.to) };
-            excesses[from] -= graph.lowers[edge_id];
-            excesses[to] += graph.lowers[edge_id];
-            maximum_flow_graph.add_directed_edge(from, to, edge.upper);
+            excesses[edge.from] -= graph.lowers[edge_id];
+            excesses[edge.to] += graph.lowers[edge_id];
+            maximum_flow_graph.push_edge(edge.from, edge.to, edge.upper);
         }
 
         let mut total_excess = Flow::zero();
-        for u in 0..graph.num_nodes() {
-            if excesses[u] > Flow::zero() {
-                maximum_flow_graph.add_directed_edge(source, u, excesses[u]);
-                total_excess += excesses[u];
+        for (u, &excess) in excesses.iter().enumerate() {
+            if excess > Flow::zero() {
+                maximum_flow_graph.push_edge(source, u, excess);
+                total_excess += excess;
             }
-            if excesses[u] < Flow::zero() {
-                maximum_flow_graph.add_directed_edge(u, sink, -excesses[u]);
+            if excess < Flow::zero() {
+                maximum_flow_graph.push_edge(u, sink, -excess);
             }
         }
         CapacityScaling::default().solve(source, sink, &mut maximum_flow_graph);
         maximum_flow_graph.maximum_flow(source) >= total_excess
     }
 }
+
+// n as Flow by doubling, O(log n) additions
+pub(crate) fn from_count<Flow>(n: usize) -> Flow
+where
+    Flow: NumAssign + Copy,
+{
+    let mut value = Flow::zero();
+    for bit in (0..usize::BITS - n.leading_zeros()).rev() {
+        value += value;
+        if (n >> bit) & 1 == 1 {
+            value += Flow::one();
+        }
+    }
+    value
+}
+
+impl<Flow> MinimumCostFlowSolver<Flow> for CostScalingPushRelabel<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + CheckedMul + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "cost_scaling_push_relabel"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
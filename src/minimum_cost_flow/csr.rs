@@ -1,17 +1,21 @@
+use crate::approx::snap;
+use crate::flow_ext::FlowExt;
+use crate::index::{check_capacity, IndexError, IndexType};
 use crate::minimum_cost_flow::graph::Graph;
 use num_traits::NumAssign;
 use std::cmp::Reverse;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, VecDeque};
 use std::fmt::Debug;
 use std::ops::Neg;
 
 #[allow(dead_code)]
-impl<Flow> InsideEdge<Flow>
+impl<Flow, I> InsideEdge<Flow, I>
 where
-    Flow: NumAssign + Ord + Copy,
+    Flow: NumAssign + Ord + Clone,
+    I: IndexType,
 {
     pub fn residual_capacity(&self) -> Flow {
-        self.upper - self.flow
+        self.upper.clone() - self.flow.clone()
     }
 
     pub fn is_feasible(&self) -> bool {
@@ -19,49 +23,109 @@ where
     }
 }
 
+// CSR<Flow, u32> takes half the memory for the indices, for the graphs that fit, see IndexType
 #[derive(Default)]
-pub struct CSR<Flow> {
+#[allow(clippy::upper_case_acronyms)]
+pub struct CSR<Flow, I: IndexType = usize> {
     pub num_nodes: usize,
     pub num_edges: usize,
-    pub edge_index_to_inside_edge_index: Vec<usize>,
+    pub edge_index_to_inside_edge_index: Vec<I>,
 
     pub excesses: Vec<Flow>,
     pub potentials: Vec<Flow>,
+    pub are_potentials_preserved: bool, // set by build_preserving_potentials
+    pub tolerance: Flow,                // of the dijkstra reduced costs, see FlowExt::tolerance
 
-    pub start: Vec<usize>,
-    pub inside_edge_list: Vec<InsideEdge<Flow>>,
+    pub start: Vec<I>,
+    pub inside_edge_list: Vec<InsideEdge<Flow, I>>,
+
+    // bit i is set if the inside edge i has residual capacity, kept by push_flow and move_flow.
+    // the flows must not be changed otherwise, or next_residual_edge skips wrong edges
+    residual_bits: Vec<u64>,
+    pub scan_empty_edges: bool, // next_residual_edge returns every edge, to measure what the skipping saves
 }
 
 #[derive(Default, Debug)]
-pub struct InsideEdge<Flow> {
-    pub to: usize,
+pub struct InsideEdge<Flow, I: IndexType = usize> {
+    pub to: I,
     pub flow: Flow,
     pub upper: Flow,
     pub cost: Flow,
-    pub rev: usize,
+    pub rev: I,
 }
 
 #[allow(dead_code)]
-impl<Flow> CSR<Flow>
+impl<Flow, I> CSR<Flow, I>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+    I: IndexType,
 {
+    // nothing of the previous graph is kept. the potentials are zero.
+    // the builds panic if the graph does not fit in I, which try_build reports instead
     pub fn build(&mut self, graph: &Graph<Flow>) {
-        if graph.num_nodes() == 0 {
-            return;
+        self.build_edges(graph);
+        self.potentials = vec![Flow::zero(); self.num_nodes];
+        self.are_potentials_preserved = false;
+    }
+
+    // build, or Err(IndexError) without building if the nodes or the edges of graph do not fit in I
+    pub fn try_build(&mut self, graph: &Graph<Flow>) -> Result<(), IndexError> {
+        check_capacity::<I>(graph.num_nodes(), graph.num_edges())?;
+        self.build(graph);
+        Ok(())
+    }
+
+    // keeps the potentials of the previous solve if they are still valid for graph,
+    // i.e. the number of nodes is the same and no residual edge has a negative reduced cost.
+    // otherwise the potentials are zero, or computed again if the flow of graph makes zero invalid.
+    // the check is a single pass over the edges, as cheap as the build itself.
+    // return false if the residual graph has a negative cycle, so that no valid potentials exist
+    pub fn build_preserving_potentials(&mut self, graph: &Graph<Flow>) -> bool {
+        let potentials = std::mem::take(&mut self.potentials);
+        self.build_edges(graph);
+
+        self.potentials = potentials;
+        self.are_potentials_preserved = self.potentials.len() == self.num_nodes && self.satisfy_reduced_cost_optimality();
+        if !self.are_potentials_preserved {
+            self.potentials = vec![Flow::zero(); self.num_nodes];
+            if !self.satisfy_reduced_cost_optimality() {
+                return self.compute_potentials();
+            }
         }
+        true
+    }
 
+    // starts from the given potentials, which are made valid by saturating the residual edges of negative reduced cost.
+    // the excesses this leaves are sent by the solver like the supplies
+    pub fn build_with_potentials(&mut self, graph: &Graph<Flow>, potentials: Vec<Flow>) {
+        assert_eq!(potentials.len(), graph.num_nodes());
+        self.build_edges(graph);
+        self.potentials = potentials;
+        for u in 0..self.num_nodes {
+            for edge_id in self.start[u].index()..self.start[u + 1].index() {
+                let edge = &self.inside_edge_list[edge_id];
+                let residual_capacity = edge.residual_capacity();
+                if residual_capacity > Flow::zero() && self.reduced_cost(u, edge) < Flow::zero() {
+                    self.push_flow(u, edge_id, residual_capacity);
+                }
+            }
+        }
+        self.are_potentials_preserved = true;
+    }
+
+    fn build_edges(&mut self, graph: &Graph<Flow>) {
+        check_capacity::<I>(graph.num_nodes(), graph.num_edges()).unwrap();
         self.num_nodes = graph.num_nodes();
         self.num_edges = graph.num_edges();
         self.excesses = graph.excesses.clone();
+        self.tolerance = Flow::tolerance();
 
         // initialize
-        self.edge_index_to_inside_edge_index.resize(self.num_edges, usize::MAX);
-        self.start.resize(self.num_nodes + 1, 0);
+        self.edge_index_to_inside_edge_index = vec![I::none(); self.num_edges];
+        self.start = vec![I::new(0); self.num_nodes + 1];
         self.inside_edge_list = (0..2 * self.num_edges)
-            .map(|_| InsideEdge { to: 0, flow: Flow::zero(), upper: Flow::zero(), cost: Flow::zero(), rev: 0 })
+            .map(|_| InsideEdge { to: I::new(0), flow: Flow::zero(), upper: Flow::zero(), cost: Flow::zero(), rev: I::new(0) })
             .collect();
-        self.potentials.resize(self.num_nodes, Flow::zero());
 
         let mut degree = vec![0; self.num_nodes];
         for edge in graph.edges.iter() {
@@ -70,53 +134,91 @@ where
         }
 
         for i in 1..=self.num_nodes {
-            self.start[i] += self.start[i - 1] + degree[i - 1];
+            self.start[i] = I::new(self.start[i - 1].index() + degree[i - 1]);
         }
 
         let mut counter = vec![0; self.num_nodes];
         for (edge_index, edge) in graph.edges.iter().enumerate() {
             let (u, v) = (edge.from, edge.to);
-            let inside_edge_index_u = self.start[u] + counter[u];
+            let inside_edge_index_u = self.start[u].index() + counter[u];
             counter[u] += 1;
-            let inside_edge_index_v = self.start[v] + counter[v];
-            self.edge_index_to_inside_edge_index[edge_index] = inside_edge_index_u;
+            let inside_edge_index_v = self.start[v].index() + counter[v];
+            self.edge_index_to_inside_edge_index[edge_index] = I::new(inside_edge_index_u);
             counter[v] += 1;
 
             assert_ne!(inside_edge_index_u, inside_edge_index_v);
 
             // u -> v
-            self.inside_edge_list[inside_edge_index_u] = InsideEdge { to: v, flow: edge.flow, upper: edge.upper, cost: edge.cost, rev: inside_edge_index_v };
+            self.inside_edge_list[inside_edge_index_u] = InsideEdge { to: I::new(v), flow: edge.flow.clone(), upper: edge.upper.clone(), cost: edge.cost.clone(), rev: I::new(inside_edge_index_v) };
             // v -> u
-            self.inside_edge_list[inside_edge_index_v] = InsideEdge { to: u, flow: edge.upper - edge.flow, upper: edge.upper, cost: -edge.cost, rev: inside_edge_index_u };
+            self.inside_edge_list[inside_edge_index_v] =
+                InsideEdge { to: I::new(u), flow: edge.upper.clone() - edge.flow.clone(), upper: edge.upper.clone(), cost: -edge.cost.clone(), rev: I::new(inside_edge_index_u) };
 
-            assert!(edge.cost >= Flow::zero());
             assert!(edge.upper >= Flow::zero());
         }
+
+        self.residual_bits.clear();
+        self.residual_bits.resize(self.inside_edge_list.len().div_ceil(64), 0);
+        for edge_id in 0..self.inside_edge_list.len() {
+            self.update_residual_bit(edge_id);
+        }
     }
 
-    pub fn set_flow(&self, graph: &mut Graph<Flow>) {
-        graph.excesses = self.excesses.clone();
-        for edge_id in 0..graph.num_edges() {
-            let i = self.edge_index_to_inside_edge_index[edge_id];
-            graph.edges[edge_id].flow = self.inside_edge_list[i].flow;
+    #[inline]
+    fn update_residual_bit(&mut self, edge_id: usize) {
+        let bit = 1 << (edge_id % 64);
+        if self.inside_edge_list[edge_id].residual_capacity() > Flow::zero() {
+            self.residual_bits[edge_id / 64] |= bit;
+        } else {
+            self.residual_bits[edge_id / 64] &= !bit;
+        }
+    }
+
+    // the first inside edge in edge_id..end with residual capacity, 64 empty edges at a time
+    #[inline]
+    pub fn next_residual_edge(&self, mut edge_id: usize, end: usize) -> Option<usize> {
+        if self.scan_empty_edges {
+            return (edge_id < end).then_some(edge_id);
         }
+        while edge_id < end {
+            let word = self.residual_bits[edge_id / 64] >> (edge_id % 64);
+            if word != 0 {
+                let next = edge_id + word.trailing_zeros() as usize;
+                return (next < end).then_some(next);
+            }
+            edge_id = (edge_id / 64 + 1) * 64;
+        }
+        None
+    }
+
+    #[inline]
+    pub fn flow(&self, edge_id: usize) -> Flow {
+        self.inside_edge_list[self.edge_index_to_inside_edge_index[edge_id].index()].flow.clone()
     }
 
     #[inline]
-    pub fn neighbors(&self, u: usize) -> std::slice::Iter<InsideEdge<Flow>> {
-        self.inside_edge_list[self.start[u]..self.start[u + 1]].iter()
+    pub fn neighbors(&self, u: usize) -> std::slice::Iter<'_, InsideEdge<Flow, I>> {
+        self.inside_edge_list[self.start[u].index()..self.start[u + 1].index()].iter()
     }
 
     #[inline]
     pub fn push_flow(&mut self, u: usize, edge_id: usize, flow: Flow) {
-        let rev = self.inside_edge_list[edge_id].rev;
-        let to = self.inside_edge_list[edge_id].to;
-        self.inside_edge_list[edge_id].flow += flow;
-        self.inside_edge_list[rev].flow -= flow;
-        self.excesses[u] -= flow;
+        let to = self.inside_edge_list[edge_id].to.index();
+        self.move_flow(edge_id, flow.clone());
+        self.excesses[u] -= flow.clone();
         self.excesses[to] += flow;
     }
 
+    // push_flow without the excesses, for the augmentations along paths and cycles
+    #[inline]
+    pub fn move_flow(&mut self, edge_id: usize, flow: Flow) {
+        let rev = self.inside_edge_list[edge_id].rev.index();
+        self.inside_edge_list[edge_id].flow += flow.clone();
+        self.inside_edge_list[rev].flow -= flow;
+        self.update_residual_bit(edge_id);
+        self.update_residual_bit(rev);
+    }
+
     pub fn calculate_distance_from_source(&mut self, source: usize) -> (Vec<Option<Flow>>, Vec<Option<usize>>) {
         let mut prev = vec![None; self.num_nodes];
         let mut bh = BinaryHeap::new();
@@ -132,17 +234,17 @@ where
             }
             visited[u] = true;
 
-            for edge_id in self.start[u]..self.start[u + 1] {
+            for edge_id in self.start[u].index()..self.start[u + 1].index() {
                 let edge = &self.inside_edge_list[edge_id];
                 if edge.residual_capacity() == Flow::zero() {
                     continue;
                 }
 
-                let new_dist = d.0 + self.reduced_cost(u, edge);
-                if dist[edge.to].is_none() || dist[edge.to].unwrap() > new_dist {
-                    dist[edge.to] = Some(new_dist);
-                    prev[edge.to] = Some(edge_id);
-                    bh.push((Reverse(new_dist), edge.to));
+                let (to, new_dist) = (edge.to.index(), d.0.clone() + self.reduced_cost(u, edge));
+                if dist[to].as_ref().is_none_or(|dist| *dist > new_dist) {
+                    dist[to] = Some(new_dist.clone());
+                    prev[to] = Some(edge_id);
+                    bh.push((Reverse(new_dist), to));
                 }
             }
         }
@@ -150,13 +252,55 @@ where
         (dist, prev)
     }
 
+    // potentials under which every residual edge has non-negative reduced cost (bellman-ford from all nodes),
+    // starting from the current potentials: those that are nearly valid are corrected with a few relabels instead of O(nm).
+    // return false if the residual graph has a negative cycle, then the potentials are meaningless
+    pub fn compute_potentials(&mut self) -> bool {
+        let mut dist: Vec<Flow> = self.potentials.iter().map(|potential| -potential.clone()).collect();
+        let mut num_path_edges = vec![0; self.num_nodes];
+        let mut in_queue = vec![true; self.num_nodes];
+        let mut queue: VecDeque<usize> = (0..self.num_nodes).collect();
+        while let Some(u) = queue.pop_front() {
+            in_queue[u] = false;
+            for edge in self.neighbors(u) {
+                let to = edge.to.index();
+                if edge.residual_capacity() > Flow::zero() && dist[u].clone() + edge.cost.clone() < dist[to] {
+                    dist[to] = dist[u].clone() + edge.cost.clone();
+
+                    // a shortest path has at most num_nodes - 1 edges
+                    num_path_edges[to] = num_path_edges[u] + 1;
+                    if num_path_edges[to] >= self.num_nodes {
+                        return false;
+                    }
+                    if !in_queue[to] {
+                        in_queue[to] = true;
+                        queue.push_back(to);
+                    }
+                }
+            }
+        }
+        self.potentials = dist.into_iter().map(|d| -d).collect();
+        true
+    }
+
+    // no residual edge has a negative reduced cost
+    pub fn satisfy_reduced_cost_optimality(&self) -> bool {
+        (0..self.num_nodes).all(|u| self.neighbors(u).all(|edge| edge.residual_capacity() == Flow::zero() || self.reduced_cost(u, edge) >= Flow::zero()))
+    }
+
+    #[inline]
+    pub fn reduced_cost(&self, u: usize, e: &InsideEdge<Flow, I>) -> Flow {
+        e.cost.clone() - self.potentials[u].clone() + self.potentials[e.to.index()].clone()
+    }
+
+    // reduced_cost within the tolerance of zero is zero, so that dijkstra sees no negative one from a rounding error
     #[inline]
-    pub fn reduced_cost(&self, u: usize, e: &InsideEdge<Flow>) -> Flow {
-        e.cost - self.potentials[u] + self.potentials[e.to]
+    pub fn dijkstra_reduced_cost(&self, u: usize, e: &InsideEdge<Flow, I>) -> Flow {
+        snap(self.reduced_cost(u, e), &self.tolerance)
     }
 
     #[inline]
-    pub fn reduced_cost_rev(&self, u: usize, e: &InsideEdge<Flow>) -> Flow {
-        -(e.cost - self.potentials[u] + self.potentials[e.to])
+    pub fn reduced_cost_rev(&self, u: usize, e: &InsideEdge<Flow, I>) -> Flow {
+        -self.reduced_cost(u, e)
     }
 }
@@ -1,24 +1,71 @@
+use crate::checked::add_costs;
+use crate::flow_ext::FlowExt;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, AUGMENTATIONS_TOTAL};
 use crate::minimum_cost_flow::csr::CSR;
+use crate::minimum_cost_flow::flow_result::{commit, FlowResult};
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::ops::Neg;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct CycleCanceling<Flow> {
     csr: CSR<Flow>,
+    num_iterations: usize,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> CycleCanceling<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
 {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
     pub fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
-        let (_source, artificial_nodes, artificial_edges) = graph.construct_extend_network_feasible_solution();
-        self.csr.build(graph);
+        graph.solve_balanced(|graph| {
+            let result = self.solve_ref(graph);
+            commit(graph, &result);
+            result.status()
+        })
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit.
+    // the balance policy of graph is applied by solve, not here
+    pub fn solve_ref(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "cycle_canceling");
+        let result = self.solve_inner(graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_iterations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_iterations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.num_iterations = 0;
+
+        // the artificial sub graph is added to a copy, so that graph is only read
+        let mut network = graph.clone();
+        let (_source, artificial_structure) = match network.construct_extend_network_feasible_solution() {
+            Ok(extended) => extended,
+            Err(status) => return FlowResult::without_flows(status),
+        };
+        self.csr.build(&network);
 
         let mut prev = vec![(usize::MAX, usize::MAX); self.csr.num_nodes];
-        while let Some(start) = self.find_negative_cycle(&mut prev) {
+        let mut dist = vec![Flow::zero(); self.csr.num_nodes];
+        loop {
+            let start = match self.find_negative_cycle(&mut prev, &mut dist) {
+                Ok(Some(start)) => start,
+                Ok(None) => break,
+                Err(status) => return FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges()),
+            };
             let (mut v, idx) = prev[start];
             let mut delta = self.csr.inside_edge_list[idx].residual_capacity();
             let mut cycle = vec![idx];
@@ -31,34 +78,43 @@ where
             assert!(delta > Flow::zero());
 
             for idx in cycle {
-                let rev = self.csr.inside_edge_list[idx].rev;
-                self.csr.inside_edge_list[idx].flow += delta;
-                self.csr.inside_edge_list[rev].flow -= delta;
+                self.csr.move_flow(idx, delta);
             }
+            self.num_iterations += 1;
         }
 
-        self.csr.set_flow(graph);
-
-        let status = if artificial_edges.iter().all(|&edge_id| graph.edges[edge_id].flow == Flow::zero()) {
+        // there is no negative cycle, and the distances of the last search are shortest ones
+        self.csr.potentials = dist.into_iter().map(|d| -d).collect();
+        debug_assert!(self.csr.satisfy_reduced_cost_optimality());
+        let status = if artificial_structure.edges.iter().all(|&(edge_id, _)| self.csr.flow(edge_id) == Flow::zero()) {
             Status::Optimal
         } else {
             Status::Infeasible
         };
-        graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
+        FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges())
+    }
 
-        status
+    pub fn solve_with_solution(&mut self, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(graph);
+        Solution::from_status(status, graph, self.num_iterations)
     }
 
-    fn find_negative_cycle(&self, prev: &mut [(usize, usize)]) -> Option<usize> {
+    // Ok(None) leaves the shortest distances from all nodes in dist.
+    // Err(Status::Overflow) if a path cost does not fit in Flow
+    fn find_negative_cycle(&self, prev: &mut [(usize, usize)], dist: &mut [Flow]) -> Result<Option<usize>, Status> {
         let mut start = usize::MAX;
-        let mut dist = vec![Flow::zero(); self.csr.num_nodes];
+        dist.fill(Flow::zero());
         for _ in 0..self.csr.num_nodes {
             let mut updated = false;
             for u in 0..self.csr.num_nodes {
                 for edge_index in self.csr.start[u]..self.csr.start[u + 1] {
                     let edge = &self.csr.inside_edge_list[edge_index];
-                    if edge.residual_capacity() > Flow::zero() && dist[u] + edge.cost < dist[edge.to] {
-                        dist[edge.to] = dist[u] + edge.cost;
+                    if edge.residual_capacity() == Flow::zero() {
+                        continue;
+                    }
+                    let new_dist = add_costs(dist[u], edge.cost)?;
+                    if new_dist < dist[edge.to] {
+                        dist[edge.to] = new_dist;
                         prev[edge.to] = (u, edge_index);
                         start = u;
                         updated = true;
@@ -66,7 +122,7 @@ where
                 }
             }
             if !updated {
-                return None;
+                return Ok(None);
             }
         }
 
@@ -75,10 +131,27 @@ where
         loop {
             let (u, _idx) = prev[v];
             if visited[u] {
-                return Some(v);
+                return Ok(Some(v));
             }
             visited[u] = true;
             v = u;
         }
     }
 }
+
+impl<Flow> MinimumCostFlowSolver<Flow> for CycleCanceling<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "cycle_canceling"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
@@ -0,0 +1,168 @@
+use crate::minimum_cost_flow::graph::Graph;
+use num_traits::NumAssign;
+use std::collections::VecDeque;
+use std::ops::Neg;
+
+// the edges are listed in the order they are traversed.
+// an edge with a negative flow is traversed from `to` to `from`, and its flow is minus the sum of the amounts of the components it is in
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlowComponent<Flow> {
+    Path { edges: Vec<usize>, amount: Flow }, // from a node that sends more than it receives to a node that receives more than it sends
+    Cycle { edges: Vec<usize>, amount: Flow },
+}
+
+struct Arc<Flow> {
+    to: usize,
+    edge_id: usize,
+    flow: Flow,
+}
+
+// decomposes the flows of graph (in the orientation given by the user, see get_edge) into paths and cycles.
+// the cycles are canceled first, and then every path goes from a node without incoming flow to a node without outgoing flow.
+// every component empties at least one edge, so there are at most num_edges components
+pub fn decompose_flow<Flow>(graph: &Graph<Flow>) -> Vec<FlowComponent<Flow>>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    let num_nodes = graph.num_nodes();
+    let mut arcs: Vec<Arc<Flow>> = Vec::new();
+    let mut out_arcs = vec![Vec::new(); num_nodes];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        let (from, to, flow) = if edge.flow >= Flow::zero() {
+            (edge.from, edge.to, edge.flow)
+        } else {
+            (edge.to, edge.from, -edge.flow)
+        };
+        if flow > Flow::zero() {
+            out_arcs[from].push(arcs.len());
+            arcs.push(Arc { to, edge_id, flow });
+        }
+    }
+
+    let cycles = cancel_cycles(&mut arcs, &out_arcs);
+    let mut components = strip_paths(&mut arcs, &out_arcs);
+    components.extend(cycles);
+    components
+}
+
+// depth-first search on the arcs with flow. a back arc closes a cycle, which is canceled by its minimum flow
+fn cancel_cycles<Flow>(arcs: &mut [Arc<Flow>], out_arcs: &[Vec<usize>]) -> Vec<FlowComponent<Flow>>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    const UNVISITED: u8 = 0;
+    const ON_STACK: u8 = 1;
+    const DONE: u8 = 2;
+
+    let num_nodes = out_arcs.len();
+    let mut state = vec![UNVISITED; num_nodes];
+    let mut position = vec![0; num_nodes]; // the position of a node on the stack
+    let mut current_arc = vec![0; num_nodes];
+    let mut cycles = Vec::new();
+
+    for root in 0..num_nodes {
+        if state[root] != UNVISITED {
+            continue;
+        }
+        // stack[i] is entered by path[i - 1]
+        let (mut stack, mut path) = (vec![root], Vec::new());
+        state[root] = ON_STACK;
+        position[root] = 0;
+
+        while let Some(&u) = stack.last() {
+            while current_arc[u] < out_arcs[u].len() {
+                let arc = &arcs[out_arcs[u][current_arc[u]]];
+                if arc.flow > Flow::zero() && state[arc.to] != DONE {
+                    break;
+                }
+                current_arc[u] += 1;
+            }
+
+            let Some(&arc_id) = out_arcs[u].get(current_arc[u]) else {
+                state[u] = DONE;
+                stack.pop();
+                path.pop();
+                continue;
+            };
+
+            let v = arcs[arc_id].to;
+            if state[v] == UNVISITED {
+                state[v] = ON_STACK;
+                position[v] = stack.len();
+                stack.push(v);
+                path.push(arc_id);
+                continue;
+            }
+
+            // v is on the stack
+            path.push(arc_id);
+            let cycle = path.split_off(position[v]);
+            let amount = cycle.iter().map(|&arc_id| arcs[arc_id].flow).min().unwrap();
+            for &arc_id in cycle.iter() {
+                arcs[arc_id].flow -= amount;
+            }
+            cycles.push(FlowComponent::Cycle { edges: cycle.iter().map(|&arc_id| arcs[arc_id].edge_id).collect(), amount });
+
+            // back to v, the nodes above it are searched again
+            for w in stack.drain(position[v] + 1..) {
+                state[w] = UNVISITED;
+            }
+        }
+    }
+
+    cycles
+}
+
+// the arcs with flow are acyclic. in topological order, a node has no incoming flow left when it is reached,
+// and every path from it goes on until a node without outgoing flow
+fn strip_paths<Flow>(arcs: &mut [Arc<Flow>], out_arcs: &[Vec<usize>]) -> Vec<FlowComponent<Flow>>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    let num_nodes = out_arcs.len();
+    let mut in_degree = vec![0; num_nodes];
+    for arc in arcs.iter().filter(|arc| arc.flow > Flow::zero()) {
+        in_degree[arc.to] += 1;
+    }
+
+    let mut queue: VecDeque<usize> = (0..num_nodes).filter(|&u| in_degree[u] == 0).collect();
+    let mut order = Vec::with_capacity(num_nodes);
+    while let Some(u) = queue.pop_front() {
+        order.push(u);
+        for &arc_id in out_arcs[u].iter().filter(|&&arc_id| arcs[arc_id].flow > Flow::zero()) {
+            let v = arcs[arc_id].to;
+            in_degree[v] -= 1;
+            if in_degree[v] == 0 {
+                queue.push_back(v);
+            }
+        }
+    }
+
+    let mut current_arc = vec![0; num_nodes];
+    let mut next_arc = |u: usize, arcs: &[Arc<Flow>]| {
+        while current_arc[u] < out_arcs[u].len() && arcs[out_arcs[u][current_arc[u]]].flow == Flow::zero() {
+            current_arc[u] += 1;
+        }
+        out_arcs[u].get(current_arc[u]).copied()
+    };
+
+    let mut paths = Vec::new();
+    for &s in order.iter() {
+        while let Some(arc_id) = next_arc(s, arcs) {
+            let mut path = vec![arc_id];
+            while let Some(arc_id) = next_arc(arcs[*path.last().unwrap()].to, arcs) {
+                path.push(arc_id);
+            }
+
+            let amount = path.iter().map(|&arc_id| arcs[arc_id].flow).min().unwrap();
+            for &arc_id in path.iter() {
+                arcs[arc_id].flow -= amount;
+            }
+            paths.push(FlowComponent::Path { edges: path.iter().map(|&arc_id| arcs[arc_id].edge_id).collect(), amount });
+        }
+    }
+
+    paths
+}
@@ -1,53 +1,148 @@
+use crate::flow_ext::FlowExt;
+use crate::ids::EdgeId;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PIVOTS_TOTAL};
 use crate::minimum_cost_flow::graph::Graph;
-use crate::minimum_cost_flow::network_simplex_pivot_rules::PivotRule;
+use crate::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::spanning_tree_structure::{EdgeState, InternalEdge, SpanningTreeStructure};
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::collections::VecDeque;
 use std::ops::Neg;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct DualNetworkSimplex<Flow> {
     st: SpanningTreeStructure<Flow>,
     sink: usize,
+    num_iterations: usize,
+    solved_fingerprint: Option<u64>, // the graph of the last solve, for reoptimize_capacity
+    has_tree: bool,                  // st keeps the final spanning tree of the last solve (there was an s-t path)
+    is_tree_node: Vec<bool>,         // the initial tree only spans the nodes reachable from the super source, and no flow can move to the others
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> DualNetworkSimplex<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
 {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
     pub fn solve<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>) -> Status {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "dual_network_simplex");
+        let status = graph.solve_balanced(|graph| self.solve_inner(pivot, graph));
+        self.stats = Stats::from_counters(&[(PIVOTS_TOTAL, self.num_iterations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(status, &[(PIVOTS_TOTAL, self.num_iterations)]);
+        }
+        status
+    }
+
+    fn solve_inner<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>) -> Status {
+        pivot.reset(graph.num_edges());
+        self.num_iterations = 0;
+        (self.solved_fingerprint, self.has_tree) = (None, false);
+
         if graph.is_unbalance() {
             return Status::Unbalanced;
         }
 
-        let (source, sink, artificial_nodes, artificial_edges) = graph.construct_extend_network_one_supply_one_demand();
+        let (source, sink, artificial_structure) = graph.construct_extend_network_one_supply_one_demand();
         self.st.build(graph);
         (self.st.root, self.sink) = (source, sink);
 
-        if !self.make_initial_spanning_tree_structure() {
-            // there is no s-t path
-            let status = if self.st.satisfy_constraints() { Status::Optimal } else { Status::Infeasible };
-            graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
+        let initial = self.make_initial_spanning_tree_structure();
+        if initial != Ok(true) {
+            // there is no s-t path, or a distance does not fit in Flow
+            let status = match initial {
+                Err(status) => status,
+                _ if self.st.satisfy_constraints() => Status::Optimal,
+                _ => Status::Infeasible,
+            };
+            graph.remove_artificial_sub_graph(&artificial_structure);
+            self.solved_fingerprint = Some(graph.topology_fingerprint());
             return status;
         }
         debug_assert!(self.st.satisfy_optimality_conditions());
 
         self.run(pivot);
+        graph.remove_artificial_sub_graph(&artificial_structure);
+        (self.solved_fingerprint, self.has_tree) = (Some(graph.topology_fingerprint()), true);
+        self.copy_to(graph)
+    }
 
+    fn copy_to(&self, graph: &mut Graph<Flow>) -> Status {
         let status = if self.st.satisfy_constraints() { Status::Optimal } else { Status::Infeasible };
-
-        // copy
-        graph.excesses = self.st.excesses.clone();
+        for u in 0..graph.num_nodes() {
+            (graph.excesses[u], graph.potentials[u]) = (self.st.excesses[u], self.st.nodes[u].potential);
+        }
         for edge_id in 0..graph.num_edges() {
             graph.edges[edge_id].flow = self.st.edges[edge_id].flow;
         }
-        graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
         status
     }
 
+    // the last solve on graph goes on with the upper bound of edge_id (as given to add_directed_edge) changed to new_upper.
+    // the spanning tree stays dual feasible, so only the flows out of their new bounds are repaired by the dual pivots.
+    // the graph must be unchanged since the solve (Status::BadInput otherwise, or if new_upper is below the lower bound)
+    pub fn reoptimize_capacity(&mut self, edge_id: impl Into<EdgeId>, new_upper: Flow, graph: &mut Graph<Flow>) -> Status {
+        let edge_id = edge_id.into().index();
+        if self.solved_fingerprint != Some(graph.topology_fingerprint()) || edge_id >= graph.num_edges() || new_upper < graph.lowers[edge_id] {
+            return Status::BadInput;
+        }
+        self.num_iterations = 0;
+        if !self.has_tree {
+            return self.solve_again(edge_id, new_upper - graph.lowers[edge_id], graph);
+        }
+
+        let upper = new_upper - graph.lowers[edge_id];
+        self.st.edges[edge_id].upper = upper;
+
+        // a non-tree edge goes to the bound its reduced cost asks for (the pivots skip the edges without capacity, so it may not be at it),
+        // and the difference goes around its cycle in the tree
+        let reduced_cost = self.st.reduced_cost(&self.st.edges[edge_id]);
+        let edge = &mut self.st.edges[edge_id];
+        if edge.state != EdgeState::Tree && reduced_cost != Flow::zero() {
+            edge.state = if reduced_cost < Flow::zero() { EdgeState::Upper } else { EdgeState::Lower };
+        }
+        let target = match edge.state {
+            EdgeState::Lower => Flow::zero(),
+            EdgeState::Upper => edge.upper,
+            EdgeState::Tree => edge.flow,
+        };
+        if target != edge.flow {
+            let delta = if edge.state == EdgeState::Upper { edge.flow - target } else { target - edge.flow };
+            if !self.is_tree_node[edge.from] || !self.is_tree_node[edge.to] {
+                return self.solve_again(edge_id, upper, graph);
+            }
+            let apex = self.find_apex(edge_id);
+            self.st.update_flow_in_cycle(edge_id, delta, apex);
+        }
+        graph.edges[edge_id].upper = upper;
+
+        self.run(&mut BlockSearchPivotRule::new(self.st.num_edges));
+        self.stats = Stats::from_counters(&[(PIVOTS_TOTAL, self.num_iterations)]);
+        self.copy_to(graph)
+    }
+
+    // from scratch, with the flows of the graph taken back to the bounds they start at
+    fn solve_again(&mut self, edge_id: usize, upper: Flow, graph: &mut Graph<Flow>) -> Status {
+        graph.edges[edge_id].upper = upper;
+        graph.reset_flows();
+        self.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+    }
+
+    pub fn solve_with_solution<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(pivot, graph);
+        Solution::from_status(status, graph, self.num_iterations)
+    }
+
     fn run<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot) {
-        while let Some(leaving_edge_id) = pivot.find_entering_edge(&self.st, Self::calculate_violation) {
+        while let Some(leaving_edge_id) = pivot.find_entering_edge_parallel(&self.st, Self::calculate_violation) {
             let leaving_edge = &self.st.edges[leaving_edge_id];
             let t2_now_root = if self.st.nodes[leaving_edge.from].parent == leaving_edge.to {
                 leaving_edge.from
@@ -64,6 +159,7 @@ where
                 assert!(self.st.edges[leaving_edge_id].is_lower() || self.st.edges[leaving_edge_id].is_upper());
 
                 self.dual_pivot(leaving_edge_id, entering_edge_id, t2_now_root, t2_new_root);
+                self.num_iterations += 1;
                 debug_assert!(self.st.validate_num_successors(self.st.root));
                 debug_assert!(self.st.satisfy_optimality_conditions());
             } else {
@@ -85,21 +181,23 @@ where
     // T: shortest path
     // L: A \ T
     // U: empty
-    fn make_initial_spanning_tree_structure(&mut self) -> bool {
-        let (distances, prev_edge_id) = self.st.shortest_path(self.st.root);
+    fn make_initial_spanning_tree_structure(&mut self) -> Result<bool, Status> {
+        let (distances, prev_edge_id) = self.st.shortest_path(self.st.root)?;
 
         // there is no s-t path
         if prev_edge_id[self.sink].is_none() {
-            return false;
+            return Ok(false);
         }
 
         // make tree structure
         let mut children = vec![Vec::new(); self.st.num_nodes];
-        for edge_id in prev_edge_id.iter().filter_map(|&edge_id| edge_id) {
+        // a negative cost edge is at its upper bound and leads from its to to its from
+        for (v, edge_id) in prev_edge_id.iter().enumerate().filter_map(|(v, &edge_id)| Some((v, edge_id?))) {
             let edge = &mut self.st.edges[edge_id];
+            let u = edge.opposite_side(v);
             edge.state = EdgeState::Tree;
-            (self.st.nodes[edge.to].parent, self.st.nodes[edge.to].parent_edge_id) = (edge.from, edge_id);
-            children[edge.from].push(edge.to);
+            (self.st.nodes[v].parent, self.st.nodes[v].parent_edge_id) = (u, edge_id);
+            children[u].push(v);
         }
         (self.st.nodes[self.st.root].parent, self.st.nodes[self.st.root].parent_edge_id) = (usize::MAX, usize::MAX);
         self.st.last_descendent_dft = (0..self.st.num_nodes).collect();
@@ -129,6 +227,7 @@ where
             }
         }
         self.st.next_node_dft[prev_node] = self.st.root;
+        self.is_tree_node = seen;
 
         // determine potentials
         for (u, node) in self.st.nodes.iter_mut().enumerate() {
@@ -139,7 +238,7 @@ where
         self.st.update_flow_in_path(self.st.root, self.sink, self.st.excesses[self.st.root]);
         assert!(self.st.excesses[self.st.root] == Flow::zero() && self.st.excesses[self.sink] == Flow::zero());
 
-        true
+        Ok(true)
     }
 
     fn select_entering_edge_id(&self, leaving_edge_id: usize, t2_now_root: usize) -> Option<(usize, usize)> {
@@ -167,7 +266,7 @@ where
         let mut t2_new_root = None;
         let mut mini_delta = Flow::zero();
         for (edge_id, edge) in self.st.edges.iter().enumerate() {
-            if edge.state == EdgeState::Tree || edge.upper == Flow::zero() {
+            if edge.state == EdgeState::Tree || edge.upper == Flow::zero() || !self.is_tree_node[edge.from] || !self.is_tree_node[edge.to] {
                 continue;
             }
 
@@ -240,3 +339,20 @@ where
         u
     }
 }
+
+impl<Flow> MinimumCostFlowSolver<Flow> for DualNetworkSimplex<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "dual_network_simplex"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
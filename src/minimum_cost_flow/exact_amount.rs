@@ -0,0 +1,113 @@
+use crate::ids::NodeId;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::max_flow_min_cost::maximum_flow_value;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::{Deref, DerefMut, Neg};
+
+// a supply of amount at source and a demand of amount at sink while alive, taken back from b when dropped.
+// the excesses are not restored, so the flow sent shows as the excesses of source (negative) and sink afterwards
+pub(crate) struct TemporarySupply<'a, Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    graph: &'a mut Graph<Flow>,
+    source: usize,
+    sink: usize,
+    amount: Flow,
+}
+
+impl<'a, Flow> TemporarySupply<'a, Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    pub(crate) fn new(graph: &'a mut Graph<Flow>, source: usize, sink: usize, amount: Flow) -> Self {
+        graph.add_supply(source, amount.clone());
+        graph.add_demand(sink, amount.clone());
+        Self { graph, source, sink, amount }
+    }
+}
+
+impl<Flow> Deref for TemporarySupply<'_, Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    type Target = Graph<Flow>;
+
+    fn deref(&self) -> &Graph<Flow> {
+        self.graph
+    }
+}
+
+impl<Flow> DerefMut for TemporarySupply<'_, Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    fn deref_mut(&mut self) -> &mut Graph<Flow> {
+        self.graph
+    }
+}
+
+impl<Flow> Drop for TemporarySupply<'_, Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    fn drop(&mut self) {
+        self.graph.add_demand(self.source, self.amount.clone());
+        self.graph.add_supply(self.sink, self.amount.clone());
+    }
+}
+
+fn is_valid<Flow>(source: usize, sink: usize, amount: &Flow, graph: &Graph<Flow>) -> bool
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    source < graph.num_nodes() && sink < graph.num_nodes() && source != sink && *amount >= Flow::zero() && graph.b.iter().all(|b| *b == Flow::zero())
+}
+
+// the cheapest flow of exactly amount from source to sink, in a graph without supplies (Status::BadInput otherwise).
+// the supply and the demand are only set during the solve, so b is zero again afterwards and the flow shows as the excesses of source and sink.
+// Status::Infeasible if the maximum flow is less than amount
+pub fn solve_exact_amount<Flow, Solver>(source: impl Into<NodeId>, sink: impl Into<NodeId>, amount: Flow, graph: &mut Graph<Flow>, solver: &mut Solver) -> Status
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+    Solver: MinimumCostFlowSolver<Flow> + ?Sized,
+{
+    let (source, sink) = (source.into().index(), sink.into().index());
+    if !is_valid(source, sink, &amount, graph) {
+        return Status::BadInput;
+    }
+    solver.solve(&mut TemporarySupply::new(graph, source, sink, amount))
+}
+
+// solve_exact_amount, with the maximum flow from source to sink when the status is Status::Infeasible
+// (None if the lower bounds cannot be met for any amount)
+pub fn solve_exact_amount_reporting_achievable<Flow, Solver>(source: impl Into<NodeId>, sink: impl Into<NodeId>, amount: Flow, graph: &mut Graph<Flow>, solver: &mut Solver) -> (Status, Option<Flow>)
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Default,
+    Solver: MinimumCostFlowSolver<Flow> + ?Sized,
+{
+    let (source, sink) = (source.into().index(), sink.into().index());
+    match solve_exact_amount(source, sink, amount, graph, solver) {
+        Status::Infeasible => (Status::Infeasible, maximum_flow_value(source, sink, graph).ok()),
+        status => (status, None),
+    }
+}
+
+// the cheapest flow of as much as possible up to amount from source to sink, and the amount sent
+pub fn solve_at_most_amount<Flow, Solver>(source: impl Into<NodeId>, sink: impl Into<NodeId>, amount: Flow, graph: &mut Graph<Flow>, solver: &mut Solver) -> (Flow, Status)
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Default,
+    Solver: MinimumCostFlowSolver<Flow> + ?Sized,
+{
+    let (source, sink) = (source.into().index(), sink.into().index());
+    if !is_valid(source, sink, &amount, graph) {
+        return (Flow::zero(), Status::BadInput);
+    }
+    let amount = match maximum_flow_value(source, sink, graph) {
+        Ok(maximum_flow) => amount.min(maximum_flow),
+        Err(status) => return (Flow::zero(), status),
+    };
+    (amount.clone(), solver.solve(&mut TemporarySupply::new(graph, source, sink, amount)))
+}
@@ -0,0 +1,166 @@
+use crate::flow_ext::FlowExt;
+use crate::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+#[derive(Debug)]
+pub struct FixedChargeResult<Flow> {
+    pub status: Status,
+    pub open: Vec<bool>,
+    pub required: Vec<bool>, // edges whose closing made the problem infeasible
+    pub flows: Vec<Flow>,
+    pub flow_cost: Flow,
+    pub fixed_cost: Flow,
+    pub total_cost: Flow,
+    pub iterations: usize, // the number of open sets evaluated
+}
+
+struct Evaluation<Flow> {
+    flows: Vec<Flow>,
+    flow_cost: Flow,
+    fixed_cost: Flow,
+}
+
+impl<Flow> Evaluation<Flow>
+where
+    Flow: NumAssign + Copy,
+{
+    fn total_cost(&self) -> Flow {
+        self.flow_cost + self.fixed_cost
+    }
+}
+
+// add-drop heuristic for the fixed charge minimum cost flow problem.
+// this is a heuristic: the result is only a local optimum for closing or reopening one edge, and is not guaranteed to be optimal.
+//
+// 1. open all edges and solve
+// 2. close the open edge with the largest fixed_cost / flow and solve again while the total cost improves
+//    (if closing the edge makes the problem infeasible, the edge is reopened and marked as required)
+// 3. reopen closed edges one by one while the total cost improves
+//
+// a closed edge has its upper bound set to its lower bound in graph, and every step goes on from the previous solve
+// with DualNetworkSimplex::reoptimize_capacity, so a step costs a few dual pivots instead of a solve from scratch.
+// ties are broken by the smaller edge id, so the result is deterministic.
+// an edge with a positive lower bound or zero fixed cost is never closed.
+// the bounds of graph are restored, and the flows of the best solution found are written to it.
+pub fn add_drop_heuristic<Flow>(graph: &mut Graph<Flow>, fixed_costs: &[Flow], max_iters: usize) -> FixedChargeResult<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    assert_eq!(fixed_costs.len(), graph.num_edges());
+
+    let bounds: Vec<(Flow, Flow)> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).map(|edge| (edge.lower, edge.upper)).collect();
+    let mut open = vec![true; graph.num_edges()];
+    let mut required: Vec<bool> = bounds.iter().map(|&(lower, _)| lower > Flow::zero()).collect();
+    let mut solver = DualNetworkSimplex::default();
+    let mut iterations = 1;
+    let status = solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph);
+    let mut best = match evaluate(status, graph, fixed_costs, &open) {
+        Ok(evaluation) => evaluation,
+        Err(status) => {
+            return FixedChargeResult { status, open, required, flows: Vec::new(), flow_cost: Flow::zero(), fixed_cost: Flow::zero(), total_cost: Flow::zero(), iterations };
+        }
+    };
+    // open or close edge_id, going on from the last solve
+    let mut set_open = |graph: &mut Graph<Flow>, open: &mut [bool], edge_id: usize, is_open: bool| {
+        open[edge_id] = is_open;
+        let (lower, upper) = bounds[edge_id];
+        solver.reoptimize_capacity(edge_id, if is_open { upper } else { lower }, graph)
+    };
+
+    // drop
+    while iterations < max_iters {
+        let Some(edge_id) = select_edge_to_close(fixed_costs, &open, &required, &best.flows) else {
+            break;
+        };
+
+        iterations += 1;
+        let status = set_open(graph, &mut open, edge_id, false);
+        match evaluate(status, graph, fixed_costs, &open) {
+            Ok(evaluation) if evaluation.total_cost() < best.total_cost() => best = evaluation,
+            Ok(_) => {
+                set_open(graph, &mut open, edge_id, true);
+                break;
+            }
+            Err(_) => {
+                // the edge is necessary to satisfy the demands
+                set_open(graph, &mut open, edge_id, true);
+                required[edge_id] = true;
+            }
+        }
+    }
+
+    // add
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for edge_id in 0..graph.num_edges() {
+            if open[edge_id] || iterations >= max_iters {
+                continue;
+            }
+
+            iterations += 1;
+            let status = set_open(graph, &mut open, edge_id, true);
+            match evaluate(status, graph, fixed_costs, &open) {
+                Ok(evaluation) if evaluation.total_cost() < best.total_cost() => {
+                    best = evaluation;
+                    improved = true;
+                }
+                _ => {
+                    set_open(graph, &mut open, edge_id, false);
+                }
+            }
+        }
+    }
+
+    for (edge_id, &(lower, upper)) in bounds.iter().enumerate() {
+        graph.edges[edge_id].upper = upper - lower;
+        graph.set_flow(edge_id, best.flows[edge_id]);
+    }
+
+    FixedChargeResult { status: Status::Optimal, open, required, total_cost: best.total_cost(), flows: best.flows, flow_cost: best.flow_cost, fixed_cost: best.fixed_cost, iterations }
+}
+
+// the open edge with the largest fixed_cost / flow (zero flow is treated as infinity)
+fn select_edge_to_close<Flow>(fixed_costs: &[Flow], open: &[bool], required: &[bool], flows: &[Flow]) -> Option<usize>
+where
+    Flow: NumAssign + Ord + Copy,
+{
+    let mut selected: Option<usize> = None;
+    for edge_id in 0..open.len() {
+        if !open[edge_id] || required[edge_id] || fixed_costs[edge_id] <= Flow::zero() {
+            continue;
+        }
+
+        let is_worse = match selected {
+            None => true,
+            Some(s) => {
+                // fixed_costs[edge_id] / flows[edge_id] > fixed_costs[s] / flows[s]
+                let (lhs, rhs) = (fixed_costs[edge_id] * flows[s], fixed_costs[s] * flows[edge_id]);
+                lhs > rhs || (lhs == rhs && fixed_costs[edge_id] > fixed_costs[s])
+            }
+        };
+        if is_worse {
+            selected = Some(edge_id);
+        }
+    }
+    selected
+}
+
+// the solve of graph with the closed edges at their lower bounds
+fn evaluate<Flow>(status: Status, graph: &Graph<Flow>, fixed_costs: &[Flow], open: &[bool]) -> Result<Evaluation<Flow>, Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+{
+    match status {
+        Status::Optimal => {
+            let flows = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect();
+            let fixed_cost = (0..graph.num_edges()).filter(|&edge_id| open[edge_id]).fold(Flow::zero(), |sum, edge_id| sum + fixed_costs[edge_id]);
+            Ok(Evaluation { flows, flow_cost: graph.minimum_cost(), fixed_cost })
+        }
+        status => Err(status),
+    }
+}
@@ -0,0 +1,60 @@
+use crate::minimum_cost_flow::csr::CSR;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+// the outcome of solve_ref, computed without writing to the graph.
+// unlike Solution, it is kept for every status and holds the flows as the graph stores them
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FlowResult<Flow> {
+    status: Status,
+    values: Option<FlowValues<Flow>>, // None if the solver did not compute any flow (e.g. Status::Unbalanced)
+}
+
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct FlowValues<Flow> {
+    flows: Vec<Flow>,
+    excesses: Vec<Flow>,
+    potentials: Vec<Flow>,
+}
+
+impl<Flow> FlowResult<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    // the artificial nodes and edges come after the first num_nodes nodes and num_edges edges, and are dropped
+    pub(crate) fn from_csr(status: Status, csr: &CSR<Flow>, num_nodes: usize, num_edges: usize) -> Self {
+        let flows = csr.edge_index_to_inside_edge_index[..num_edges].iter().map(|&i| csr.inside_edge_list[i].flow.clone()).collect();
+        let values = FlowValues { flows, excesses: csr.excesses[..num_nodes].to_vec(), potentials: csr.potentials[..num_nodes].to_vec() };
+        Self { status, values: Some(values) }
+    }
+
+    pub(crate) fn without_flows(status: Status) -> Self {
+        Self { status, values: None }
+    }
+
+    #[inline]
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
+
+// write the flows, excesses and potentials of result to the graph it was computed on
+pub fn commit<Flow>(graph: &mut Graph<Flow>, result: &FlowResult<Flow>)
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    let Some(values) = result.values.as_ref() else {
+        return;
+    };
+    assert_eq!(values.flows.len(), graph.num_edges());
+    assert_eq!(values.excesses.len(), graph.num_nodes());
+    graph.excesses.clone_from(&values.excesses);
+    graph.potentials.clone_from(&values.potentials);
+    for (edge, flow) in graph.edges.iter_mut().zip(values.flows.iter()) {
+        edge.flow = flow.clone();
+    }
+}
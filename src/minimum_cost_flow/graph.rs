@@ -1,8 +1,20 @@
-use num_traits::NumAssign;
-use std::fmt::Debug;
+use crate::checked::add_costs;
+use crate::compaction::CompactionMap;
+use crate::flow_ext::FlowExt;
+use crate::graph_error::{check_node, check_nodes, GraphError};
+use crate::ids::{EdgeId, NodeId};
+use crate::minimum_cost_flow::artificial::{ArtificialEdgeRole, ArtificialNodeRole, ArtificialStructure};
+use crate::minimum_cost_flow::basis::fingerprint;
+use crate::minimum_cost_flow::corridor::{CorridorDirection, CorridorIndex};
+use crate::minimum_cost_flow::node_cost::OriginatedFlow;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::{NumAssign, ToPrimitive};
+use std::fmt::{Debug, Display, Write as _};
 use std::ops::Neg;
+use std::sync::OnceLock;
 
 #[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge<Flow> {
     pub from: usize,
     pub to: usize,
@@ -12,7 +24,24 @@ pub struct Edge<Flow> {
     pub cost: Flow,
 }
 
-#[derive(Default)]
+// what the solvers do when the supplies and demands do not sum to zero
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BalancePolicy<Flow> {
+    #[default]
+    Reject, // Status::Unbalanced
+    // the difference d (the sum of the supplies and demands) up to max_imbalance in absolute value is absorbed by a slack node:
+    // - Some(u): the demand of u is increased by d during the solve, and cost_per_unit * |d| is charged
+    // - None: a node is added for the solve with an edge of cost_per_unit from each supply node (d > 0) or to each demand node (d < 0)
+    SlackNode {
+        node: Option<usize>,
+        cost_per_unit: Flow,
+        max_imbalance: Flow,
+    },
+}
+
+#[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Graph<Flow> {
     num_nodes: usize,
     num_edges: usize,
@@ -20,12 +49,19 @@ pub struct Graph<Flow> {
     pub(crate) b: Vec<Flow>,
     pub(crate) lowers: Vec<Flow>,
     pub(crate) excesses: Vec<Flow>,
-    pub(crate) is_reversed: Vec<bool>,
+    pub(crate) potentials: Vec<Flow>,
+    node_costs: Vec<Flow>, // only up to the last node whose cost was set
+    originated_flow: OriginatedFlow,
+    balance_policy: BalancePolicy<Flow>,
+    balance_adjustment: Option<(Flow, Flow)>,
+    pub(crate) corridor_direction: CorridorDirection,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) corridor_index: OnceLock<CorridorIndex>, // built by the first corridor query after the edges change
 }
 
 impl<Flow> Graph<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     #[inline]
     pub fn num_nodes(&self) -> usize {
@@ -37,64 +73,168 @@ where
         self.num_edges
     }
 
-    pub fn add_node(&mut self) -> usize {
+    #[inline]
+    pub fn excess(&self, u: impl Into<NodeId>) -> Flow {
+        self.excesses[u.into().index()].clone()
+    }
+
+    // b of u: positive for a supply and negative for a demand
+    #[inline]
+    pub fn supply(&self, u: impl Into<NodeId>) -> Flow {
+        self.b[u.into().index()].clone()
+    }
+
+    #[inline]
+    pub fn supplies(&self) -> &[Flow] {
+        &self.b
+    }
+
+    // optimal dual values after solve returns Status::Optimal.
+    // reduced cost: cost - potential(from) + potential(to)
+    #[inline]
+    pub fn potential(&self, u: impl Into<NodeId>) -> Flow {
+        self.potentials[u.into().index()].clone()
+    }
+
+    pub fn add_node(&mut self) -> NodeId {
+        NodeId(self.add_node_raw())
+    }
+
+    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<NodeId> {
+        self.add_nodes_raw(num_nodes).into_iter().map(NodeId).collect()
+    }
+
+    pub fn add_supply(&mut self, u: impl Into<NodeId>, supply: Flow) {
+        let u = u.into().index();
+        self.b[u] += supply.clone();
+        self.excesses[u] += supply;
+    }
+
+    pub fn add_demand(&mut self, u: impl Into<NodeId>, demand: Flow) {
+        let u = u.into().index();
+        self.b[u] -= demand.clone();
+        self.excesses[u] -= demand;
+    }
+
+    // add_supply and add_demand, with GraphError::NodeOutOfRange instead of a panic
+    pub fn try_add_supply(&mut self, u: impl Into<NodeId>, supply: Flow) -> Result<(), GraphError<Flow>> {
+        let u = u.into().index();
+        check_node(u, self.num_nodes)?;
+        self.add_supply(u, supply);
+        Ok(())
+    }
+
+    pub fn try_add_demand(&mut self, u: impl Into<NodeId>, demand: Flow) -> Result<(), GraphError<Flow>> {
+        let u = u.into().index();
+        check_node(u, self.num_nodes)?;
+        self.add_demand(u, demand);
+        Ok(())
+    }
+
+    // sets b of the nodes (the last pair of a node wins), and nothing is changed if a node is out of range
+    pub fn set_supplies<N: Into<NodeId> + Copy>(&mut self, supplies: &[(N, Flow)]) -> Result<(), GraphError<Flow>> {
+        for (u, _) in supplies.iter() {
+            check_node((*u).into().index(), self.num_nodes)?;
+        }
+        for (u, supply) in supplies.iter() {
+            let u = (*u).into().index();
+            self.add_supply(u, supply.clone() - self.b[u].clone());
+        }
+        Ok(())
+    }
+
+    // return edge id, or why the edge is rejected
+    // an edge with a negative cost starts at its upper bound, so that no residual edge has a negative cost before a solve
+    pub fn add_directed_edge(&mut self, from: impl Into<NodeId>, to: impl Into<NodeId>, lower: Flow, upper: Flow, cost: Flow) -> Result<EdgeId, GraphError<Flow>> {
+        self.add_directed_edge_raw(from.into().index(), to.into().index(), lower, upper, cost).map(EdgeId)
+    }
+
+    // the usize versions of the above, kept for one release
+    pub fn add_node_raw(&mut self) -> usize {
         self.b.push(Flow::zero());
         self.excesses.push(Flow::zero());
+        self.potentials.push(Flow::zero());
         self.num_nodes += 1;
         self.num_nodes - 1
     }
 
-    pub fn add_nodes(&mut self, num_nodes: usize) -> Vec<usize> {
+    pub fn add_nodes_raw(&mut self, num_nodes: usize) -> Vec<usize> {
         self.b.extend(vec![Flow::zero(); num_nodes]);
         self.excesses.extend(vec![Flow::zero(); num_nodes]);
+        self.potentials.extend(vec![Flow::zero(); num_nodes]);
         self.num_nodes += num_nodes;
         ((self.num_nodes - num_nodes)..self.num_nodes).collect()
     }
 
-    pub fn add_supply(&mut self, u: usize, supply: Flow) {
-        self.b[u] += supply;
-        self.excesses[u] += supply;
-    }
-
-    pub fn add_demand(&mut self, u: usize, demand: Flow) {
-        self.b[u] -= demand;
-        self.excesses[u] -= demand;
-    }
-
-    // return edge index
-    pub fn add_directed_edge(&mut self, from: usize, to: usize, lower: Flow, upper: Flow, cost: Flow) -> Option<usize> {
-        if lower > upper || from >= self.num_nodes || to >= self.num_nodes {
-            return None;
+    pub fn add_directed_edge_raw(&mut self, from: usize, to: usize, lower: Flow, upper: Flow, cost: Flow) -> Result<usize, GraphError<Flow>> {
+        check_nodes(from, to, self.num_nodes)?;
+        if lower > upper {
+            return Err(GraphError::InvalidBounds { lower, upper });
         }
+        Ok(self.push_edge(from, to, lower, upper, cost))
+    }
 
-        if cost >= Flow::zero() {
-            self.edges.push(Edge { from, to, flow: Flow::zero(), lower: Flow::zero(), upper: upper - lower, cost });
-            self.excesses[from] -= lower;
-            self.excesses[to] += lower;
-            self.lowers.push(lower);
-            self.is_reversed.push(false);
+    // add_directed_edge without the checks, for the networks the crate builds from valid graphs
+    pub(crate) fn push_edge(&mut self, from: usize, to: usize, lower: Flow, upper: Flow, cost: Flow) -> usize {
+        let (flow, sent) = if cost < Flow::zero() {
+            (upper.clone() - lower.clone(), upper.clone())
         } else {
-            self.edges.push(Edge { from: to, to: from, flow: Flow::zero(), lower: Flow::zero(), upper: upper - lower, cost: -cost });
-            self.excesses[from] -= upper;
-            self.excesses[to] += upper;
-            self.lowers.push(lower);
-            self.is_reversed.push(true);
-        }
+            (Flow::zero(), lower.clone())
+        };
+        self.edges.push(Edge { from, to, flow, lower: Flow::zero(), upper: upper - lower.clone(), cost });
+        self.excesses[from] -= sent.clone();
+        self.excesses[to] += sent;
+        self.lowers.push(lower);
+        self.corridor_index.take();
 
         self.num_edges += 1;
-        Some(self.num_edges - 1)
+        self.num_edges - 1
     }
 
-    pub fn get_edge(&self, edge_id: usize) -> Option<Edge<Flow>> {
+    pub fn get_edge(&self, edge_id: impl Into<EdgeId>) -> Option<Edge<Flow>> {
+        let edge_id = edge_id.into().index();
         if edge_id >= self.edges.len() {
             return None;
         }
         let edge = &self.edges[edge_id];
-        let lower = self.lowers[edge_id];
-        if self.is_reversed[edge_id] {
-            Some(Edge { from: edge.to, to: edge.from, flow: edge.upper - edge.flow + lower, lower, upper: edge.upper + lower, cost: -edge.cost })
-        } else {
-            Some(Edge { from: edge.from, to: edge.to, flow: edge.flow + lower, lower, upper: edge.upper + lower, cost: edge.cost })
+        let lower = self.lowers[edge_id].clone();
+        let upper = edge.upper.clone() + lower.clone();
+        Some(Edge { from: edge.from, to: edge.to, flow: edge.flow.clone() + lower.clone(), lower, upper, cost: edge.cost.clone() })
+    }
+
+    // the edges in the order they were added, as get_edge returns them
+    pub fn edges(&self) -> impl Iterator<Item = (EdgeId, Edge<Flow>)> + '_ {
+        (0..self.num_edges).map(|edge_id| (EdgeId(edge_id), self.get_edge(edge_id).unwrap()))
+    }
+
+    // the edges carrying a flow
+    pub fn edges_with_flow(&self) -> impl Iterator<Item = (EdgeId, Edge<Flow>)> + '_ {
+        self.edges().filter(|(_, edge)| edge.flow != Flow::zero())
+    }
+
+    // the flow of get_edge, without the rest of the edge
+    pub fn flow(&self, edge_id: impl Into<EdgeId>) -> Option<Flow> {
+        let edge_id = edge_id.into().index();
+        let edge = self.edges.get(edge_id)?;
+        Some(edge.flow.clone() + self.lowers[edge_id].clone())
+    }
+
+    // set the flow (lower bound included) and keep the excesses consistent
+    pub(crate) fn set_flow(&mut self, edge_id: usize, flow: Flow) {
+        let edge = self.get_edge(edge_id).unwrap();
+        let delta = flow - edge.flow;
+        self.edges[edge_id].flow += delta.clone();
+        self.excesses[edge.from] -= delta.clone();
+        self.excesses[edge.to] += delta;
+    }
+
+    // every edge back at the bound add_directed_edge starts it at, with the excesses kept consistent
+    pub(crate) fn reset_flows(&mut self) {
+        for edge in self.edges.iter_mut() {
+            let flow = if edge.cost < Flow::zero() { edge.upper.clone() } else { Flow::zero() };
+            self.excesses[edge.from] += edge.flow.clone() - flow.clone();
+            self.excesses[edge.to] -= edge.flow.clone() - flow.clone();
+            edge.flow = flow;
         }
     }
 
@@ -105,36 +245,238 @@ where
         })
     }
 
+    // cost per unit of flow passing through u, honored by node_cost::solve
+    pub fn set_node_cost(&mut self, u: impl Into<NodeId>, cost: Flow) {
+        let u = u.into().index();
+        assert!(u < self.num_nodes);
+        if self.node_costs.len() <= u {
+            self.node_costs.resize(u + 1, Flow::zero());
+        }
+        self.node_costs[u] = cost;
+    }
+
+    pub fn node_cost(&self, u: impl Into<NodeId>) -> Flow {
+        self.node_costs.get(u.into().index()).cloned().unwrap_or(Flow::zero())
+    }
+
+    pub fn set_originated_flow(&mut self, originated_flow: OriginatedFlow) {
+        self.originated_flow = originated_flow;
+    }
+
+    pub fn originated_flow(&self) -> OriginatedFlow {
+        self.originated_flow
+    }
+
+    // the node cost paid at u by the current flow
+    pub fn node_throughput_cost(&self, u: impl Into<NodeId>) -> Flow {
+        let u = u.into().index();
+        let (mut inflow, mut outflow) = (Flow::zero(), Flow::zero());
+        for edge_id in 0..self.num_edges {
+            let edge = self.get_edge(edge_id).unwrap();
+            if edge.to == u {
+                inflow += edge.flow.clone();
+            }
+            if edge.from == u {
+                outflow += edge.flow;
+            }
+        }
+
+        // the flow ending at u is never charged
+        let throughput = match self.originated_flow {
+            OriginatedFlow::Exempt => inflow.min(outflow),
+            OriginatedFlow::Charged => outflow,
+        };
+        self.node_cost(u) * throughput
+    }
+
+    // a hash of the number of nodes and the ends of the edges, the same in every process
+    pub fn topology_fingerprint(&self) -> u64 {
+        fingerprint(self.num_nodes, (0..self.num_edges).map(|edge_id| self.get_edge(edge_id).unwrap()).map(|edge| (edge.from, edge.to)))
+    }
+
     pub fn is_unbalance(&self) -> bool {
-        self.b.iter().fold(Flow::zero(), |sum, &excess| sum + excess) != Flow::zero()
+        self.imbalance() != Flow::zero()
+    }
+
+    fn imbalance(&self) -> Flow {
+        self.b.iter().fold(Flow::zero(), |sum, excess| sum + excess.clone())
     }
 
-    pub(crate) fn construct_extend_network_one_supply_one_demand(&mut self) -> (usize, usize, Vec<usize>, Vec<usize>) {
+    pub fn set_balance_policy(&mut self, balance_policy: BalancePolicy<Flow>) {
+        self.balance_policy = balance_policy;
+    }
+
+    pub fn balance_policy(&self) -> BalancePolicy<Flow> {
+        self.balance_policy.clone()
+    }
+
+    // (the absorbed difference d, its cost) of the last solve, None if nothing was absorbed.
+    // the cost is not included in minimum_cost, and the unsent supplies or unmet demands are left in the excesses
+    pub fn balance_adjustment(&self) -> Option<(Flow, Flow)> {
+        self.balance_adjustment.clone()
+    }
+
+    fn solve_balanced_unchecked(&mut self, solve: impl FnOnce(&mut Self) -> Status) -> Status {
+        self.balance_adjustment = None;
+        let imbalance = self.imbalance();
+        if imbalance == Flow::zero() {
+            return solve(self);
+        }
+        let BalancePolicy::SlackNode { node, cost_per_unit, max_imbalance } = self.balance_policy.clone() else {
+            return Status::Unbalanced;
+        };
+        let amount = if imbalance > Flow::zero() { imbalance.clone() } else { -imbalance.clone() };
+        if amount > max_imbalance || node.is_some_and(|u| u >= self.num_nodes) {
+            return Status::Unbalanced;
+        }
+
+        if let Some(u) = node {
+            self.add_demand(u, imbalance.clone());
+            let status = solve(self);
+            self.add_supply(u, imbalance.clone());
+            self.balance_adjustment = Some((imbalance, cost_per_unit * amount));
+            return status;
+        }
+
+        let slack = self.add_node_raw();
+        self.add_demand(slack, imbalance.clone());
+        // the slack node receives the surplus of supply, or sends the missing supply
+        let role = if imbalance > Flow::zero() {
+            ArtificialNodeRole::SuperSink
+        } else {
+            ArtificialNodeRole::SuperSource
+        };
+        let mut structure = ArtificialStructure { nodes: vec![(slack, role)], edges: Vec::new() };
+        for u in 0..slack {
+            if imbalance > Flow::zero() && self.b[u] > Flow::zero() {
+                let edge_id = self.push_edge(u, slack, Flow::zero(), self.b[u].clone(), cost_per_unit.clone());
+                structure.edges.push((edge_id, ArtificialEdgeRole::SupplySlack { node: u }));
+            } else if imbalance < Flow::zero() && self.b[u] < Flow::zero() {
+                let edge_id = self.push_edge(slack, u, Flow::zero(), -self.b[u].clone(), cost_per_unit.clone());
+                structure.edges.push((edge_id, ArtificialEdgeRole::DemandSlack { node: u }));
+            }
+        }
+        let status = solve(self);
+
+        // the flow on an artificial edge is supply that stays at u, or demand of u that is not met
+        let mut absorbed_cost = Flow::zero();
+        for &(edge_id, role) in structure.edges.iter() {
+            let flow = self.get_edge(edge_id).unwrap().flow;
+            self.excesses[role.node()] += if imbalance > Flow::zero() { flow.clone() } else { -flow.clone() };
+            absorbed_cost += cost_per_unit.clone() * flow;
+        }
+        self.remove_artificial_sub_graph(&structure);
+        self.balance_adjustment = Some((imbalance, absorbed_cost));
+        status
+    }
+
+    // drops the edges with lower = upper = 0 and then the nodes with b = 0 and no edges (but the slack node of the balance policy),
+    // and renumbers the rest in order. flows, bounds, costs, b, excesses, potentials and node costs are kept
+    pub fn compact(&mut self) -> CompactionMap {
+        let is_edge_kept: Vec<bool> = (0..self.num_edges)
+            .map(|edge_id| self.lowers[edge_id] != Flow::zero() || self.edges[edge_id].upper != Flow::zero())
+            .collect();
+        let mut is_node_kept: Vec<bool> = self.b.iter().map(|b| *b != Flow::zero()).collect();
+        for edge in self.edges.iter().zip(is_edge_kept.iter()).filter(|(_, &is_kept)| is_kept).map(|(edge, _)| edge) {
+            (is_node_kept[edge.from], is_node_kept[edge.to]) = (true, true);
+        }
+        if let BalancePolicy::SlackNode { node: Some(u), .. } = self.balance_policy {
+            if u < self.num_nodes {
+                is_node_kept[u] = true;
+            }
+        }
+
+        let map = CompactionMap::new(&is_node_kept, &is_edge_kept);
+        fn retain<T: Clone>(values: &[T], is_kept: &[bool]) -> Vec<T> {
+            values.iter().zip(is_kept.iter()).filter(|(_, &is_kept)| is_kept).map(|(value, _)| value.clone()).collect()
+        }
+
+        self.edges = self
+            .edges
+            .iter()
+            .zip(is_edge_kept.iter())
+            .filter(|(_, &is_kept)| is_kept)
+            .map(|(edge, _)| Edge { from: map.translate_node(edge.from).unwrap(), to: map.translate_node(edge.to).unwrap(), ..edge.clone() })
+            .collect();
+        self.lowers = retain(&self.lowers, &is_edge_kept);
+        self.b = retain(&self.b, &is_node_kept);
+        self.excesses = retain(&self.excesses, &is_node_kept);
+        self.potentials = retain(&self.potentials, &is_node_kept);
+        self.node_costs = retain(&self.node_costs, &is_node_kept[..self.node_costs.len()]);
+        if let BalancePolicy::SlackNode { node: Some(u), cost_per_unit, max_imbalance } = self.balance_policy.clone() {
+            if u < self.num_nodes {
+                self.balance_policy = BalancePolicy::SlackNode { node: map.translate_node(u), cost_per_unit, max_imbalance };
+            }
+        }
+        (self.num_nodes, self.num_edges) = (self.b.len(), self.edges.len());
+        self.corridor_index.take();
+        map
+    }
+
+    pub(crate) fn construct_extend_network_one_supply_one_demand(&mut self) -> (usize, usize, ArtificialStructure) {
         let mut artificial_edges = Vec::new();
-        let (source, sink) = (self.add_node(), self.add_node());
+        let (source, sink) = (self.add_node_raw(), self.add_node_raw());
         for u in 0..self.num_nodes() {
             if u == source || u == sink {
                 continue;
             }
             if self.excesses[u] > Flow::zero() {
-                artificial_edges.push(self.add_directed_edge(source, u, Flow::zero(), self.excesses[u], Flow::zero()).unwrap());
-                self.excesses[source] = self.excesses[source] + self.excesses[u];
+                let edge_id = self.push_edge(source, u, Flow::zero(), self.excesses[u].clone(), Flow::zero());
+                artificial_edges.push((edge_id, ArtificialEdgeRole::SupplySlack { node: u }));
+                self.excesses[source] = self.excesses[source].clone() + self.excesses[u].clone();
             }
             if self.excesses[u] < Flow::zero() {
-                artificial_edges.push(self.add_directed_edge(u, sink, Flow::zero(), -self.excesses[u], Flow::zero()).unwrap());
-                self.excesses[sink] = self.excesses[sink] + self.excesses[u];
+                let edge_id = self.push_edge(u, sink, Flow::zero(), -self.excesses[u].clone(), Flow::zero());
+                artificial_edges.push((edge_id, ArtificialEdgeRole::DemandSlack { node: u }));
+                self.excesses[sink] = self.excesses[sink].clone() + self.excesses[u].clone();
             }
             self.excesses[u] = Flow::zero();
         }
 
-        (source, sink, vec![source, sink], artificial_edges)
+        let nodes = vec![(source, ArtificialNodeRole::SuperSource), (sink, ArtificialNodeRole::SuperSink)];
+        (source, sink, ArtificialStructure { nodes, edges: artificial_edges })
     }
 
-    pub(crate) fn construct_extend_network_feasible_solution(&mut self) -> (usize, Vec<usize>, Vec<usize>) {
-        let inf_cost = self.edges.iter().map(|e| e.cost).fold(Flow::one(), |acc, cost| acc + cost); // all edge costs are non-negative
+    pub(crate) fn remove_artificial_sub_graph(&mut self, structure: &ArtificialStructure) {
+        let (num_artificial_nodes, num_artificial_edges) = (structure.nodes.len(), structure.edges.len());
+        self.edges.truncate(self.num_edges - num_artificial_edges);
+        self.b.truncate(self.num_nodes - num_artificial_nodes);
+        self.lowers.truncate(self.num_edges - num_artificial_edges);
+        self.excesses.truncate(self.num_nodes - num_artificial_nodes);
+        self.potentials.truncate(self.num_nodes - num_artificial_nodes);
+
+        self.num_nodes -= num_artificial_nodes;
+        self.num_edges -= num_artificial_edges;
+        self.corridor_index.take();
+    }
+}
+
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    // the pre-pass shared by the solvers: the difference is absorbed by the slack node of the policy during solve.
+    // an optimal solve whose objective or potentials overflowed (see checked::Checked) is Status::Overflow, and its flows are left in the graph
+    pub(crate) fn solve_balanced(&mut self, solve: impl FnOnce(&mut Self) -> Status) -> Status {
+        self.solve_balanced_unchecked(|graph| match solve(graph) {
+            Status::Optimal if Flow::CAN_OVERFLOW && (graph.minimum_cost().is_overflow() || graph.potentials.iter().any(FlowExt::is_overflow)) => Status::Overflow,
+            status => status,
+        })
+    }
+
+    // longer than any path: 1 + the sum of the absolute costs
+    pub(crate) fn inf_cost(&self) -> Result<Flow, Status> {
+        self.edges
+            .iter()
+            .map(|e| if e.cost < Flow::zero() { -e.cost.clone() } else { e.cost.clone() })
+            .try_fold(Flow::one(), add_costs)
+    }
+
+    pub(crate) fn construct_extend_network_feasible_solution(&mut self) -> Result<(usize, ArtificialStructure), Status> {
+        let inf_cost = self.inf_cost()?;
 
         // add artificial nodes
-        let root = self.add_node();
+        let root = self.add_node_raw();
 
         // add artificial edges
         let mut artificial_edges = Vec::new();
@@ -143,32 +485,107 @@ where
                 continue;
             }
 
-            let excess = self.excesses[u];
+            let excess = self.excesses[u].clone();
             if excess >= Flow::zero() {
                 // u -> root
-                let edge_id = self.add_directed_edge(u, root, Flow::zero(), excess, inf_cost).unwrap();
-                self.edges[edge_id].flow = excess;
-                artificial_edges.push(edge_id);
+                let edge_id = self.push_edge(u, root, Flow::zero(), excess.clone(), inf_cost.clone());
+                self.edges[edge_id].flow = excess.clone();
+                let role = if excess > Flow::zero() {
+                    ArtificialEdgeRole::SupplySlack { node: u }
+                } else {
+                    ArtificialEdgeRole::RootLink { node: u }
+                };
+                artificial_edges.push((edge_id, role));
             } else {
                 // root -> u
-                let edge_id = self.add_directed_edge(root, u, Flow::zero(), -excess, inf_cost).unwrap();
+                let edge_id = self.push_edge(root, u, Flow::zero(), -excess.clone(), inf_cost.clone());
                 self.edges[edge_id].flow = -excess;
-                artificial_edges.push(edge_id);
+                artificial_edges.push((edge_id, ArtificialEdgeRole::DemandSlack { node: u }));
             }
             self.excesses[u] = Flow::zero();
         }
 
-        (root, vec![root], artificial_edges)
+        Ok((root, ArtificialStructure { nodes: vec![(root, ArtificialNodeRole::Root)], edges: artificial_edges }))
+    }
+}
+
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + ToPrimitive,
+{
+    // minimum_cost accumulated in i128, so that it is right even if the cost * flow of an edge does not fit in Flow
+    pub fn minimum_cost_i128(&self) -> i128 {
+        (0..self.num_edges).fold(0, |cost, edge_id| {
+            let edge = self.get_edge(edge_id).unwrap();
+            cost + to_i128(edge.cost) * to_i128(edge.flow)
+        })
+    }
+}
+
+pub(crate) fn to_i128<Flow: ToPrimitive>(value: Flow) -> i128 {
+    value.to_i128().expect("the costs and the flows fit in i128")
+}
+
+pub struct DotOptions {
+    pub show_zero_flow_edges: bool,
+    // the potentials are those of the last solve
+    pub show_potentials: bool,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self { show_zero_flow_edges: true, show_potentials: false }
     }
+}
 
-    pub(crate) fn remove_artificial_sub_graph(&mut self, artificial_nodes: &[usize], artificial_edges: &[usize]) {
-        self.edges.truncate(self.num_edges - artificial_edges.len());
-        self.b.truncate(self.num_nodes - artificial_nodes.len());
-        self.lowers.truncate(self.num_edges - artificial_edges.len());
-        self.excesses.truncate(self.num_nodes - artificial_nodes.len());
-        self.is_reversed.truncate(self.num_edges - artificial_edges.len());
+impl<Flow> Graph<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Display,
+{
+    // graphviz dot with the edges as given to add_directed_edge, labeled "flow/upper @ cost" ("flow/lower..upper @ cost" with a lower bound).
+    // saturated edges are bold, and the supply and demand nodes are labeled with b
+    pub fn to_dot(&self) -> String {
+        self.to_dot_with_options(&DotOptions::default())
+    }
 
-        self.num_nodes -= artificial_nodes.len();
-        self.num_edges -= artificial_edges.len();
+    pub fn to_dot_with_options(&self, options: &DotOptions) -> String {
+        self.to_dot_with_artificial_structure(options, &ArtificialStructure::default())
+    }
+
+    // the artificial nodes and edges of an extended network are labeled with their roles
+    pub fn to_dot_with_artificial_structure(&self, options: &DotOptions, structure: &ArtificialStructure) -> String {
+        let mut dot = String::from("digraph {\n");
+        for u in 0..self.num_nodes {
+            let mut label = String::new();
+            if let Some(role) = structure.node_role(u) {
+                write!(label, "\\n{role}").unwrap();
+            }
+            if self.b[u] != Flow::zero() {
+                write!(label, "\\nb = {}", self.b[u]).unwrap();
+            }
+            if options.show_potentials {
+                write!(label, "\\np = {}", self.potentials[u]).unwrap();
+            }
+            if label.is_empty() {
+                writeln!(dot, "    {u};").unwrap();
+            } else {
+                writeln!(dot, "    {u} [label=\"{u}{label}\"];").unwrap();
+            }
+        }
+        for (edge_id, edge) in (0..self.num_edges).map(|edge_id| (edge_id, self.get_edge(edge_id).unwrap())) {
+            if !options.show_zero_flow_edges && edge.flow == Flow::zero() {
+                continue;
+            }
+            let capacity = if edge.lower == Flow::zero() {
+                edge.upper.to_string()
+            } else {
+                format!("{}..{}", edge.lower, edge.upper)
+            };
+            let role = structure.edge_role(edge_id).map_or(String::new(), |role| format!("\\n{role}"));
+            let style = if edge.flow == edge.upper && edge.upper > Flow::zero() { ", style=bold" } else { "" };
+            writeln!(dot, "    {} -> {} [label=\"{}/{capacity} @ {}{role}\"{style}];", edge.from, edge.to, edge.flow, edge.cost).unwrap();
+        }
+        dot.push_str("}\n");
+        dot
     }
 }
@@ -0,0 +1,244 @@
+use crate::minimum_cost_flow::graph::Graph;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Relabeling {
+    Found(Vec<usize>), // node u of a is node relabeling[u] of b
+    NonIsomorphic,     // proven
+    GaveUp,            // the effort limit was reached before the search ended
+}
+
+// (direction, lower, upper, cost) of an edge seen from one of its ends
+type Incidence<Flow> = (u8, Flow, Flow, Flow);
+// (the other end, incidence) of the edges of a node
+type Neighborhood<Flow> = Vec<(usize, Incidence<Flow>)>;
+
+const OUT: u8 = 0;
+const IN: u8 = 1;
+const LOOP: u8 = 2;
+
+// a relabeling of the nodes of a that maps its edges (with their bounds and costs) and its supplies onto those of b.
+// the nodes are colored from their supplies and incident edges and the colors are refined with those of the neighbors (1-dimensional weisfeiler-leman),
+// then a backtracking search matches the nodes of the same color. effort_limit bounds the number of nodes of the search tree
+pub fn find_relabeling<Flow>(a: &Graph<Flow>, b: &Graph<Flow>, effort_limit: usize) -> Relabeling
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    if a.num_nodes() != b.num_nodes() || a.num_edges() != b.num_edges() {
+        return Relabeling::NonIsomorphic;
+    }
+    let num_nodes = a.num_nodes();
+    let (adjacency_a, adjacency_b) = (adjacency(a), adjacency(b));
+
+    // the colors of the nodes of a and b, numbered together so that they are comparable
+    let initial_keys = |graph: &Graph<Flow>, adjacency: &[Neighborhood<Flow>]| -> Vec<(Flow, Vec<Incidence<Flow>>)> {
+        (0..num_nodes)
+            .map(|u| {
+                let mut incidences: Vec<Incidence<Flow>> = adjacency[u].iter().map(|&(_, incidence)| incidence).collect();
+                incidences.sort();
+                (graph.b[u], incidences)
+            })
+            .collect()
+    };
+    let (mut colors_a, mut colors_b, mut num_colors) = number(initial_keys(a, &adjacency_a), initial_keys(b, &adjacency_b));
+    loop {
+        if !have_same_color_counts(&colors_a, &colors_b, num_colors) {
+            return Relabeling::NonIsomorphic;
+        }
+        let refined_keys = |colors: &[usize], adjacency: &[Neighborhood<Flow>]| -> Vec<(usize, Neighborhood<Flow>)> {
+            (0..num_nodes)
+                .map(|u| {
+                    let mut neighbors: Neighborhood<Flow> = adjacency[u].iter().map(|&(v, incidence)| (colors[v], incidence)).collect();
+                    neighbors.sort();
+                    (colors[u], neighbors)
+                })
+                .collect()
+        };
+        let (refined_a, refined_b, num_refined) = number(refined_keys(&colors_a, &adjacency_a), refined_keys(&colors_b, &adjacency_b));
+        // a refinement only splits colors, so the same number means stable
+        let is_stable = num_refined == num_colors;
+        (colors_a, colors_b, num_colors) = (refined_a, refined_b, num_refined);
+        if is_stable {
+            break;
+        }
+    }
+    if !have_same_color_counts(&colors_a, &colors_b, num_colors) {
+        return Relabeling::NonIsomorphic;
+    }
+
+    let mut search = Search {
+        adjacency_a: &adjacency_a,
+        adjacency_b: &adjacency_b,
+        colors_a: &colors_a,
+        nodes_of_color_b: (0..num_colors).map(|_| Vec::new()).collect(),
+        order: search_order(&adjacency_a, &colors_a, num_colors),
+        relabeling: vec![usize::MAX; num_nodes],
+        inverse: vec![usize::MAX; num_nodes],
+        effort: 0,
+        effort_limit,
+    };
+    for (v, &color) in colors_b.iter().enumerate() {
+        search.nodes_of_color_b[color].push(v);
+    }
+    match search.extend(0, &|relabeling| is_relabeling(a, b, relabeling)) {
+        Some(true) => Relabeling::Found(search.relabeling),
+        Some(false) => Relabeling::NonIsomorphic,
+        None => Relabeling::GaveUp,
+    }
+}
+
+// whether relabeling maps the edges and the supplies of a exactly onto those of b
+pub fn is_relabeling<Flow>(a: &Graph<Flow>, b: &Graph<Flow>, relabeling: &[usize]) -> bool
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    let num_nodes = a.num_nodes();
+    if b.num_nodes() != num_nodes || a.num_edges() != b.num_edges() || relabeling.len() != num_nodes {
+        return false;
+    }
+    let mut is_used = vec![false; num_nodes];
+    for &v in relabeling.iter() {
+        if v >= num_nodes || is_used[v] {
+            return false;
+        }
+        is_used[v] = true;
+    }
+    if (0..num_nodes).any(|u| a.b[u] != b.b[relabeling[u]]) {
+        return false;
+    }
+
+    let edges = |graph: &Graph<Flow>, relabeling: &dyn Fn(usize) -> usize| {
+        let mut edges: Vec<(usize, usize, Flow, Flow, Flow)> = (0..graph.num_edges())
+            .map(|edge_id| {
+                let edge = graph.get_edge(edge_id).unwrap();
+                (relabeling(edge.from), relabeling(edge.to), edge.lower, edge.upper, edge.cost)
+            })
+            .collect();
+        edges.sort();
+        edges
+    };
+    edges(a, &|u| relabeling[u]) == edges(b, &|u| u)
+}
+
+// the incident edges of each node, a loop once
+fn adjacency<Flow>(graph: &Graph<Flow>) -> Vec<Neighborhood<Flow>>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    let mut adjacency = vec![Vec::new(); graph.num_nodes()];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        if edge.from == edge.to {
+            adjacency[edge.from].push((edge.to, (LOOP, edge.lower, edge.upper, edge.cost)));
+        } else {
+            adjacency[edge.from].push((edge.to, (OUT, edge.lower, edge.upper, edge.cost)));
+            adjacency[edge.to].push((edge.from, (IN, edge.lower, edge.upper, edge.cost)));
+        }
+    }
+    adjacency
+}
+
+// numbers the distinct keys of both lists in sorted order
+fn number<K: Ord + Clone>(keys_a: Vec<K>, keys_b: Vec<K>) -> (Vec<usize>, Vec<usize>, usize) {
+    let mut distinct: Vec<K> = keys_a.iter().chain(keys_b.iter()).cloned().collect();
+    distinct.sort();
+    distinct.dedup();
+    let color = |key: &K| distinct.binary_search(key).unwrap();
+    (keys_a.iter().map(color).collect(), keys_b.iter().map(color).collect(), distinct.len())
+}
+
+fn have_same_color_counts(colors_a: &[usize], colors_b: &[usize], num_colors: usize) -> bool {
+    let mut counts = vec![0i64; num_colors];
+    colors_a.iter().for_each(|&color| counts[color] += 1);
+    colors_b.iter().for_each(|&color| counts[color] -= 1);
+    counts.iter().all(|&count| count == 0)
+}
+
+// breadth first from the nodes of the rarest colors, so that most nodes have a matched neighbor when they are matched
+fn search_order<Flow>(adjacency: &[Neighborhood<Flow>], colors: &[usize], num_colors: usize) -> Vec<usize> {
+    let mut color_sizes = vec![0; num_colors];
+    colors.iter().for_each(|&color| color_sizes[color] += 1);
+    let mut roots: Vec<usize> = (0..colors.len()).collect();
+    roots.sort_by_key(|&u| (color_sizes[colors[u]], u));
+
+    let mut order = Vec::with_capacity(colors.len());
+    let mut is_visited = vec![false; colors.len()];
+    for root in roots {
+        if is_visited[root] {
+            continue;
+        }
+        is_visited[root] = true;
+        let mut head = order.len();
+        order.push(root);
+        while head < order.len() {
+            let u = order[head];
+            head += 1;
+            for &(v, _) in adjacency[u].iter() {
+                if !is_visited[v] {
+                    is_visited[v] = true;
+                    order.push(v);
+                }
+            }
+        }
+    }
+    order
+}
+
+struct Search<'a, Flow> {
+    adjacency_a: &'a [Neighborhood<Flow>],
+    adjacency_b: &'a [Neighborhood<Flow>],
+    colors_a: &'a [usize],
+    nodes_of_color_b: Vec<Vec<usize>>,
+    order: Vec<usize>,
+    relabeling: Vec<usize>,
+    inverse: Vec<usize>,
+    effort: usize,
+    effort_limit: usize,
+}
+
+impl<Flow> Search<'_, Flow>
+where
+    Flow: Ord + Copy,
+{
+    // Some(true) if the relabeling is completed from the first depth nodes of the order, Some(false) if it cannot be, and None if the effort limit is reached
+    fn extend(&mut self, depth: usize, verify: &dyn Fn(&[usize]) -> bool) -> Option<bool> {
+        if depth == self.order.len() {
+            return Some(verify(&self.relabeling));
+        }
+        let u = self.order[depth];
+        let color = self.colors_a[u];
+        for i in 0..self.nodes_of_color_b[color].len() {
+            let v = self.nodes_of_color_b[color][i];
+            if self.inverse[v] != usize::MAX || !self.is_consistent(u, v) {
+                continue;
+            }
+            self.effort += 1;
+            if self.effort > self.effort_limit {
+                return None;
+            }
+            (self.relabeling[u], self.inverse[v]) = (v, u);
+            if self.extend(depth + 1, verify)? {
+                return Some(true);
+            }
+            (self.relabeling[u], self.inverse[v]) = (usize::MAX, usize::MAX);
+        }
+        Some(false)
+    }
+
+    // the edges between u and the matched nodes are the same as those between v and their images
+    fn is_consistent(&self, u: usize, v: usize) -> bool {
+        let mut edges_a: Neighborhood<Flow> = self.adjacency_a[u]
+            .iter()
+            .filter(|&&(w, _)| self.relabeling[w] != usize::MAX)
+            .map(|&(w, incidence)| (self.relabeling[w], incidence))
+            .collect();
+        let mut edges_b: Neighborhood<Flow> = self.adjacency_b[v].iter().filter(|&&(x, _)| self.inverse[x] != usize::MAX).copied().collect();
+        if edges_a.len() != edges_b.len() {
+            return false;
+        }
+        edges_a.sort();
+        edges_b.sort();
+        edges_a == edges_b
+    }
+}
@@ -0,0 +1,46 @@
+use crate::ids::NodeId;
+use crate::maximum_flow;
+use crate::maximum_flow::dinic::Dinic;
+use crate::maximum_flow::solver::MaximumFlowSolver;
+use crate::minimum_cost_flow::exact_amount::TemporarySupply;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+// the value of the maximum flow (with the lower bounds) is found first, and then sent from source to sink at minimum cost.
+// without an s-t path the value is zero, and the cheapest circulation is left in graph
+pub(crate) fn solve<Flow, Solver>(solver: &mut Solver, source: usize, sink: usize, graph: &mut Graph<Flow>) -> (Flow, Status)
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Default,
+    Solver: MinimumCostFlowSolver<Flow> + ?Sized,
+{
+    if source >= graph.num_nodes() || sink >= graph.num_nodes() || source == sink || graph.b.iter().any(|b| *b != Flow::zero()) {
+        return (Flow::zero(), Status::BadInput);
+    }
+
+    let value = match maximum_flow_value(source, sink, graph) {
+        Ok(value) => value,
+        Err(status) => return (Flow::zero(), status),
+    };
+    (value.clone(), solver.solve(&mut TemporarySupply::new(graph, source, sink, value)))
+}
+
+// the value of a maximum flow from source to sink with the lower bounds, Err(Status::Infeasible) if they cannot be met
+pub(crate) fn maximum_flow_value<Flow>(source: usize, sink: usize, graph: &Graph<Flow>) -> Result<Flow, Status>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Default,
+{
+    let mut network = maximum_flow::graph::Graph::default();
+    network.add_nodes(graph.num_nodes());
+    for edge in (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()) {
+        let edge_id = network.push_edge(edge.from, edge.to, edge.upper);
+        network.lowers[edge_id] = edge.lower;
+    }
+    match Dinic::default().solve_with_lower_bounds(NodeId(source), NodeId(sink), &mut network) {
+        maximum_flow::status::Status::Optimal => Ok(network.maximum_flow(source)),
+        maximum_flow::status::Status::Infeasible => Err(Status::Infeasible),
+        _ => Err(Status::NotSolved),
+    }
+}
@@ -1,22 +1,73 @@
 use crate::minimum_cost_flow::spanning_tree_structure::{EdgeState, InternalEdge, SpanningTreeStructure};
-use num_traits::NumAssign;
+use num_traits::{NumAssign, ToPrimitive};
+use std::cmp::Reverse;
 use std::ops::Neg;
 
+// what a pivot of the primal network simplex did, for the rules that keep state per edge
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct PivotUpdate {
+    pub entering_edge_id: usize,
+    pub leaving_edge_id: usize,
+    pub num_cycle_nodes: usize, // the number of nodes (and edges) of the cycle closed by the entering edge
+}
+
 pub trait PivotRule<Flow> {
     fn new(num_edges: usize) -> Self;
+
+    // the network simplex variants call this at the start of every solve, so that a rule can be reused for another graph.
+    // a rule with a cursor or buffers sized for the edges overrides it
+    fn reset(&mut self, _num_edges: usize) {}
+
     fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize>;
+
+    // the network simplex variants call this one, with a violation that can be evaluated from several threads.
+    // a rule with a parallel scan overrides it, and the result must be the same as find_entering_edge
+    fn find_entering_edge_parallel<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow + Sync>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        self.find_entering_edge(st, calculate_violation)
+    }
+
+    // only a rule that returns true gets update_after_pivot after every pivot of the primal network simplex.
+    // otherwise the cycle is not even measured
+    fn needs_pivot_updates(&self) -> bool {
+        false
+    }
+
+    fn update_after_pivot(&mut self, _update: &PivotUpdate) {}
+
+    // a rule that returns true gets the blocking edge with the smallest id as the leaving edge of the primal network simplex,
+    // instead of the one that keeps the tree strongly feasible
+    fn needs_smallest_index_leaving_edge(&self) -> bool {
+        false
+    }
 }
 
 pub struct BestEligibleArcPivotRule<Flow> {
+    #[cfg(feature = "parallel")]
+    parallel: Option<ParallelScan<Flow>>,
     _maker: std::marker::PhantomData<fn() -> Flow>,
 }
 
+#[cfg(feature = "parallel")]
+impl<Flow> BestEligibleArcPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Send + Sync,
+{
+    // scan the edges with num_threads threads, each of which gets at least min_chunk_len edges
+    pub fn set_parallel_scan(&mut self, num_threads: usize, min_chunk_len: usize) {
+        self.parallel = ParallelScan::new(num_threads, min_chunk_len);
+    }
+}
+
 impl<Flow> PivotRule<Flow> for BestEligibleArcPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     fn new(_num_edges: usize) -> Self {
-        Self { _maker: std::marker::PhantomData }
+        Self {
+            #[cfg(feature = "parallel")]
+            parallel: None,
+            _maker: std::marker::PhantomData,
+        }
     }
 
     fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
@@ -33,6 +84,15 @@ where
 
         entering_edge_id
     }
+
+    #[cfg(feature = "parallel")]
+    fn find_entering_edge_parallel<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow + Sync>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        match self.parallel.as_ref().filter(|parallel| st.num_edges > parallel.chunk_len(1)) {
+            // all edges in one block, so that the first maximum is the one with the smallest edge id
+            Some(parallel) => (parallel.scan)(st, &calculate_violation, 0, 0..st.num_edges, st.num_edges, parallel.num_threads),
+            None => self.find_entering_edge(st, calculate_violation),
+        }
+    }
 }
 
 pub struct FirstEligibleArcPivotRule<Flow> {
@@ -42,12 +102,16 @@ pub struct FirstEligibleArcPivotRule<Flow> {
 
 impl<Flow> PivotRule<Flow> for FirstEligibleArcPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     fn new(_num_edges: usize) -> Self {
         Self { current_edge_id: 0, _maker: std::marker::PhantomData }
     }
 
+    fn reset(&mut self, _num_edges: usize) {
+        self.current_edge_id = 0;
+    }
+
     fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
         for _ in 0..st.num_edges {
             let edge = &st.edges[self.current_edge_id];
@@ -67,39 +131,65 @@ where
     }
 }
 
+// bland's rule: the eligible edge with the smallest id enters and the blocking edge with the smallest id leaves,
+// so that no basis is repeated even when most pivots are degenerate
+pub struct BlandPivotRule<Flow> {
+    _maker: std::marker::PhantomData<fn() -> Flow>,
+}
+
+impl<Flow> PivotRule<Flow> for BlandPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    fn new(_num_edges: usize) -> Self {
+        Self { _maker: std::marker::PhantomData }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        st.edges.iter().position(|edge| calculate_violation(edge, st) > Flow::zero())
+    }
+
+    fn needs_smallest_index_leaving_edge(&self) -> bool {
+        true
+    }
+}
+
 pub struct BlockSearchPivotRule<Flow> {
     current_edge_id: usize,
     block_size: usize,
+    min_block_size: usize,
+    block_size_factor: f64,
+    #[cfg(feature = "parallel")]
+    parallel: Option<ParallelScan<Flow>>,
     _maker: std::marker::PhantomData<fn() -> Flow>,
 }
 
 impl<Flow> BlockSearchPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     pub fn new_with_parameter(num_edges: usize, min_block_size: usize, block_size_factor: f64) -> Self {
         assert!(min_block_size > 0);
         assert!(block_size_factor >= 0.0);
-        Self { current_edge_id: 0, block_size: min_block_size.max((block_size_factor * (num_edges as f64).sqrt()) as usize), _maker: std::marker::PhantomData }
-    }
-}
-
-impl<Flow> PivotRule<Flow> for BlockSearchPivotRule<Flow>
-where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
-{
-    fn new(num_edges: usize) -> Self {
-        let min_block_size = 10;
-        let block_size_factor = 1.0; // between 0.5 and 2.0
-        Self::new_with_parameter(num_edges, min_block_size, block_size_factor)
+        Self {
+            current_edge_id: 0,
+            block_size: min_block_size.max((block_size_factor * (num_edges as f64).sqrt()) as usize),
+            min_block_size,
+            block_size_factor,
+            #[cfg(feature = "parallel")]
+            parallel: None,
+            _maker: std::marker::PhantomData,
+        }
     }
 
-    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+    // the serial block search over the first end edges from current_edge_id.
+    // end is num_edges, or a multiple of block_size at which the search stops with current_edge_id on the next edge
+    fn scan<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: &F, end: usize) -> Option<usize> {
         let mut maxi_violation = Flow::zero();
         let mut entering_edge_id = None;
         let mut count = self.block_size;
 
-        for _ in 0..st.num_edges {
+        for _ in 0..end {
             let edge = &st.edges[self.current_edge_id];
             let violation = calculate_violation(edge, st);
 
@@ -126,6 +216,163 @@ where
     }
 }
 
+#[cfg(feature = "parallel")]
+impl<Flow> BlockSearchPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Send + Sync,
+{
+    // after NUM_SERIAL_BLOCKS blocks without an eligible edge, scan the rest of the edges with num_threads threads,
+    // each of which gets at least min_chunk_len edges (rounded up to whole blocks)
+    pub fn set_parallel_scan(&mut self, num_threads: usize, min_chunk_len: usize) {
+        self.parallel = ParallelScan::new(num_threads, min_chunk_len);
+    }
+}
+
+impl<Flow> PivotRule<Flow> for BlockSearchPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    fn new(num_edges: usize) -> Self {
+        let min_block_size = 10;
+        let block_size_factor = 1.0; // between 0.5 and 2.0
+        Self::new_with_parameter(num_edges, min_block_size, block_size_factor)
+    }
+
+    fn reset(&mut self, num_edges: usize) {
+        self.current_edge_id = 0;
+        self.block_size = self.min_block_size.max((self.block_size_factor * (num_edges as f64).sqrt()) as usize);
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        self.scan(st, &calculate_violation, st.num_edges)
+    }
+
+    #[cfg(feature = "parallel")]
+    fn find_entering_edge_parallel<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow + Sync>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        let num_serial_edges = self.block_size.saturating_mul(NUM_SERIAL_BLOCKS);
+        let Some(parallel) = self
+            .parallel
+            .as_ref()
+            .filter(|parallel| st.num_edges.saturating_sub(num_serial_edges) > parallel.chunk_len(self.block_size))
+        else {
+            return self.scan(st, &calculate_violation, st.num_edges);
+        };
+        let (scan, num_threads, round_len) = (parallel.scan, parallel.num_threads, parallel.num_threads * parallel.chunk_len(self.block_size));
+
+        let start = self.current_edge_id;
+        if let Some(entering_edge_id) = self.scan(st, &calculate_violation, num_serial_edges) {
+            return Some(entering_edge_id);
+        }
+
+        // the rest in rounds of whole blocks, so that a block is never split between two rounds
+        let mut begin = num_serial_edges;
+        while begin < st.num_edges {
+            let end = st.num_edges.min(begin + round_len);
+            if let Some(position) = scan(st, &calculate_violation, start, begin..end, self.block_size, num_threads) {
+                // where the serial scan stops: on the last edge of a full block, or back at the start after the last block
+                let block_end = (position / self.block_size + 1) * self.block_size;
+                self.current_edge_id = if block_end <= st.num_edges { (start + block_end - 1) % st.num_edges } else { start };
+                return Some((start + position) % st.num_edges);
+            }
+            begin = end;
+        }
+
+        self.current_edge_id = start;
+        None
+    }
+}
+
+// the violations are read from several threads.
+// SpanningTreeStructure has no interior mutability, so a shared reference to it is Sync when Flow is
+#[cfg(feature = "parallel")]
+type Violation<'a, Flow> = dyn Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow + Sync + 'a;
+
+#[cfg(feature = "parallel")]
+type BlockScan<Flow> = fn(&SpanningTreeStructure<Flow>, &Violation<'_, Flow>, usize, std::ops::Range<usize>, usize, usize) -> Option<usize>;
+
+#[cfg(feature = "parallel")]
+const NUM_SERIAL_BLOCKS: usize = 4;
+
+// scan is instantiated where Flow is known to be Send + Sync, so the rules need no such bound in PivotRule
+#[cfg(feature = "parallel")]
+struct ParallelScan<Flow> {
+    num_threads: usize,
+    min_chunk_len: usize,
+    scan: BlockScan<Flow>,
+}
+
+#[cfg(feature = "parallel")]
+impl<Flow> ParallelScan<Flow>
+where
+    Flow: NumAssign + Ord + Clone + Send + Sync,
+{
+    fn new(num_threads: usize, min_chunk_len: usize) -> Option<Self> {
+        assert!(num_threads > 0 && min_chunk_len > 0);
+        (num_threads > 1).then_some(Self { num_threads, min_chunk_len, scan: parallel_block_scan::<Flow> })
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl<Flow> ParallelScan<Flow> {
+    fn chunk_len(&self, block_size: usize) -> usize {
+        self.min_chunk_len.div_ceil(block_size) * block_size
+    }
+}
+
+// the position of an edge is its distance from start in the cyclic order, and the blocks are [k * block_size, (k + 1) * block_size).
+// returns the position of the most violating edge of the first block in positions with an eligible edge,
+// the smallest position among the ties, which is what the serial scan returns
+#[cfg(feature = "parallel")]
+fn parallel_block_scan<Flow>(
+    st: &SpanningTreeStructure<Flow>,
+    calculate_violation: &Violation<'_, Flow>,
+    start: usize,
+    positions: std::ops::Range<usize>,
+    block_size: usize,
+    num_threads: usize,
+) -> Option<usize>
+where
+    Flow: NumAssign + Ord + Clone + Send + Sync,
+{
+    // (block, violation, position) of the first block with an eligible edge in [begin, end)
+    let scan_chunk = |begin: usize, end: usize| {
+        let mut best: Option<(usize, Flow, usize)> = None;
+        for position in begin..end {
+            let block = position / block_size;
+            if best.as_ref().is_some_and(|(best_block, _, _)| *best_block != block) {
+                break;
+            }
+            let edge_id = (start + position) % st.num_edges;
+            let violation = calculate_violation(&st.edges[edge_id], st);
+            if violation > best.as_ref().map_or(Flow::zero(), |(_, maxi_violation, _)| maxi_violation.clone()) {
+                best = Some((block, violation, position));
+            }
+        }
+        best
+    };
+
+    let chunk_len = positions.len().div_ceil(num_threads).max(1);
+    let results: Vec<Option<(usize, Flow, usize)>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = positions
+            .clone()
+            .step_by(chunk_len)
+            .map(|begin| scope.spawn(move || scan_chunk(begin, positions.end.min(begin + chunk_len))))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+
+    // the chunks are in order, so a block is shared only by consecutive chunks
+    let mut best: Option<(usize, Flow, usize)> = None;
+    for (block, violation, position) in results.into_iter().flatten() {
+        match &best {
+            Some((best_block, _, _)) if *best_block < block => break,
+            Some((_, maxi_violation, _)) if violation <= *maxi_violation => {}
+            _ => best = Some((block, violation, position)),
+        }
+    }
+    best.map(|(_, _, position)| position)
+}
+
 pub struct CandidateListPivotRule<Flow> {
     current_edge_id: usize,
     candidates: Box<[usize]>,
@@ -133,12 +380,13 @@ pub struct CandidateListPivotRule<Flow> {
     minor_count_limit: usize,
     minor_count: usize,
     current_size: usize,
+    parameter: (usize, f64, usize, f64), // the arguments of new_with_parameter after num_edges
     _maker: std::marker::PhantomData<fn() -> Flow>,
 }
 
 impl<Flow> CandidateListPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     pub fn new_with_parameter(num_edges: usize, min_candidate_list_size: usize, candidate_list_size_factor: f64, min_minor_limit: usize, minor_limit_factor: f64) -> Self {
         assert!(min_candidate_list_size > 0);
@@ -156,14 +404,20 @@ where
             current_size: 0,
             minor_count_limit: minor_limit,
             minor_count: 0,
+            parameter: (min_candidate_list_size, candidate_list_size_factor, min_minor_limit, minor_limit_factor),
             _maker: std::marker::PhantomData,
         }
     }
+
+    // the edge ids of the candidate list, the eligible edges found by the last rebuild that have not been removed since
+    pub fn candidates(&self) -> &[usize] {
+        &self.candidates[..self.current_size]
+    }
 }
 
 impl<Flow> PivotRule<Flow> for CandidateListPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     fn new(num_edges: usize) -> Self {
         let min_candidate_list_size = 10;
@@ -173,6 +427,11 @@ where
         Self::new_with_parameter(num_edges, min_candidate_list_size, candidate_list_size_factor, min_minor_limit, minor_limit_factor)
     }
 
+    fn reset(&mut self, num_edges: usize) {
+        let (min_candidate_list_size, candidate_list_size_factor, min_minor_limit, minor_limit_factor) = self.parameter;
+        *self = Self::new_with_parameter(num_edges, min_candidate_list_size, candidate_list_size_factor, min_minor_limit, minor_limit_factor);
+    }
+
     fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
         let mut maxi_violation = Flow::zero();
         let mut entering_edge_id = None;
@@ -209,14 +468,9 @@ where
         // build a candidate list
         self.current_size = 0;
         for _ in 0..st.num_edges {
-            let edge = &st.edges[self.current_edge_id];
-            let violation = match edge.state {
-                EdgeState::Upper => st.reduced_cost(edge),
-                _ => -st.reduced_cost(edge),
-            };
-
+            let violation = calculate_violation(&st.edges[self.current_edge_id], st);
             if violation > Flow::zero() {
-                self.candidates[self.current_size] = self.current_size;
+                self.candidates[self.current_size] = self.current_edge_id;
                 self.current_size += 1;
 
                 if violation > maxi_violation {
@@ -244,13 +498,14 @@ pub struct AlteringCandidateListPivotRule<Flow> {
     current_edge_id: usize,
     block_size: usize,
     head_length: usize,
-    candidates: Box<[(usize, Flow)]>,
-    current_size: usize,
+    candidates: Vec<(usize, Flow)>, // reserved for head_length + block_size, grown if a call needs more
+    is_candidate: Vec<bool>,
+    parameter: (usize, f64, usize, f64), // the arguments of new_with_parameter after num_edges
 }
 
 impl<Flow> AlteringCandidateListPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     pub fn new_with_parameter(num_edges: usize, min_block_size: usize, block_size_factor: f64, min_head_length: usize, head_length_factor: f64) -> Self {
         assert!(min_block_size > 0);
@@ -261,13 +516,20 @@ where
         let block_size = min_block_size.max((block_size_factor * (num_edges as f64).sqrt()) as usize);
         let head_length = min_head_length.max((head_length_factor * block_size as f64) as usize);
 
-        Self { current_edge_id: 0, block_size, head_length, candidates: vec![(usize::MAX, Flow::zero()); head_length + block_size].into_boxed_slice(), current_size: 0 }
+        Self {
+            current_edge_id: 0,
+            block_size,
+            head_length,
+            candidates: Vec::with_capacity(head_length + block_size),
+            is_candidate: vec![false; num_edges],
+            parameter: (min_block_size, block_size_factor, min_head_length, head_length_factor),
+        }
     }
 }
 
 impl<Flow> PivotRule<Flow> for AlteringCandidateListPivotRule<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     fn new(num_edges: usize) -> Self {
         let min_block_size = 10;
@@ -277,18 +539,28 @@ where
         Self::new_with_parameter(num_edges, min_block_size, block_size_factor, min_head_length, head_length_factor)
     }
 
+    fn reset(&mut self, num_edges: usize) {
+        let (min_block_size, block_size_factor, min_head_length, head_length_factor) = self.parameter;
+        *self = Self::new_with_parameter(num_edges, min_block_size, block_size_factor, min_head_length, head_length_factor);
+    }
+
     fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        // the spanning tree structure has artificial edges in addition to the edges of the graph
+        if self.is_candidate.len() < st.num_edges {
+            self.is_candidate.resize(st.num_edges, false);
+        }
+
         // update candidate cost
         let mut i = 0;
-        while i < self.current_size {
+        while i < self.candidates.len() {
             let (edge_id, _) = self.candidates[i];
             let edge = &st.edges[edge_id];
             let violation = calculate_violation(edge, st);
 
             if violation <= Flow::zero() {
                 // remove ineligible arc from the candidates
-                self.current_size -= 1;
-                self.candidates[i] = self.candidates[self.current_size];
+                self.is_candidate[edge_id] = false;
+                self.candidates.swap_remove(i);
             } else {
                 self.candidates[i].1 = violation;
                 i += 1;
@@ -296,25 +568,24 @@ where
         }
 
         // extend the candidate list
+        // at most head_length candidates are carried over, and at most block_size candidates are added before the size is checked,
+        // so the candidates stay within the reserved head_length + block_size
         let mut block_count = self.block_size;
         let mut limit = self.head_length;
 
         for _ in 0..st.num_edges {
-            let edge = &st.edges[self.current_edge_id];
-            let violation = match edge.state {
-                EdgeState::Upper => st.reduced_cost(edge),
-                _ => -st.reduced_cost(edge),
-            };
-
-            // add eligible arc to the candidates
-            if violation > Flow::zero() {
-                self.candidates[self.current_size] = (self.current_edge_id, violation);
-                self.current_size += 1;
+            // add eligible arc to the candidates (unless it is already there)
+            if !self.is_candidate[self.current_edge_id] {
+                let violation = calculate_violation(&st.edges[self.current_edge_id], st);
+                if violation > Flow::zero() {
+                    self.candidates.push((self.current_edge_id, violation));
+                    self.is_candidate[self.current_edge_id] = true;
+                }
             }
             block_count -= 1;
 
             if block_count == 0 {
-                if self.current_size > limit {
+                if self.candidates.len() > limit {
                     break;
                 }
                 limit = 0;
@@ -327,21 +598,385 @@ where
             }
         }
 
-        if self.current_size == 0 {
+        if self.candidates.is_empty() {
             return None;
         }
 
-        let new_length = self.current_size.min(self.head_length + 1);
-        if new_length == self.current_size {
-            self.candidates[..self.current_size].sort_unstable_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        } else {
-            self.candidates[..self.current_size].select_nth_unstable_by(new_length, |a, b| b.1.partial_cmp(&a.1).unwrap());
+        // keep the head_length + 1 most violating candidates, sorted in decreasing order of violation.
+        // select_nth_unstable_by puts the new_length most violating ones at [0, new_length)
+        let new_length = self.candidates.len().min(self.head_length + 1);
+        if new_length < self.candidates.len() {
+            self.candidates.select_nth_unstable_by(new_length - 1, |a, b| b.1.cmp(&a.1));
+            for &(edge_id, _) in self.candidates[new_length..].iter() {
+                self.is_candidate[edge_id] = false;
+            }
+            self.candidates.truncate(new_length);
         }
+        self.candidates.sort_unstable_by_key(|candidate| Reverse(candidate.1.clone()));
 
-        let entering_edge_id = Some(self.candidates[0].0);
-        self.candidates[0] = self.candidates[new_length - 1];
-        self.current_size = new_length - 1;
+        // the head maximum enters, and the rest of the head is carried over to the next call
+        let (entering_edge_id, _) = self.candidates.remove(0);
+        self.is_candidate[entering_edge_id] = false;
+        debug_assert!(self.candidates.len() <= self.head_length);
+
+        Some(entering_edge_id)
+    }
+}
+
+// maximizes violation^2 / weight, where the weight approximates the steepest edge norm of the edge.
+// the tableau column of a non-tree edge has 1 or -1 for each edge of its cycle, so the norm is the length of the cycle.
+// the goldfarb-reid recurrence gives the leaving edge the norm of the entering edge (the pivot element is 1 or -1),
+// which is exactly the length of the pivot cycle. the other edges need the pivot row to be updated, so they keep their weights
+pub struct ApproximateSteepestEdgePivotRule<Flow> {
+    weights: Vec<f64>, // the reference weight is 1
+    _maker: std::marker::PhantomData<fn() -> Flow>,
+}
 
+impl<Flow> ApproximateSteepestEdgePivotRule<Flow> {
+    pub fn weight(&self, edge_id: usize) -> f64 {
+        self.weights.get(edge_id).copied().unwrap_or(1.0)
+    }
+
+    fn resize(&mut self, num_edges: usize) {
+        if self.weights.len() < num_edges {
+            self.weights.resize(num_edges, 1.0);
+        }
+    }
+}
+
+impl<Flow> PivotRule<Flow> for ApproximateSteepestEdgePivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + ToPrimitive,
+{
+    fn new(num_edges: usize) -> Self {
+        Self { weights: vec![1.0; num_edges], _maker: std::marker::PhantomData }
+    }
+
+    fn reset(&mut self, num_edges: usize) {
+        self.weights.clear();
+        self.weights.resize(num_edges, 1.0);
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        // the spanning tree structure has artificial edges in addition to the edges of the graph
+        self.resize(st.num_edges);
+
+        let mut maxi_score = 0.0;
+        let mut entering_edge_id = None;
+        for (edge_id, edge) in st.edges.iter().enumerate() {
+            let violation = calculate_violation(edge, st);
+            if violation <= Flow::zero() {
+                continue;
+            }
+            let violation = violation.to_f64().unwrap();
+            let score = violation * violation / self.weights[edge_id];
+            if score > maxi_score {
+                maxi_score = score;
+                entering_edge_id = Some(edge_id);
+            }
+        }
+        entering_edge_id
+    }
+
+    fn needs_pivot_updates(&self) -> bool {
+        true
+    }
+
+    fn update_after_pivot(&mut self, update: &PivotUpdate) {
+        self.resize(update.entering_edge_id.max(update.leaving_edge_id) + 1);
+        // the entering edge is in the tree and is not priced until it leaves
+        self.weights[update.entering_edge_id] = 1.0;
+        self.weights[update.leaving_edge_id] = update.num_cycle_nodes as f64;
+    }
+}
+
+// the most violating of sample_size edges drawn at random (with replacement), and of all the edges if none of them is eligible.
+// the same seed gives the same sequence of pivots
+pub struct RandomSamplingPivotRule<Flow> {
+    sample_size: usize,
+    seed: u64,
+    state: u64, // xorshift
+    _maker: std::marker::PhantomData<fn() -> Flow>,
+}
+
+impl<Flow> RandomSamplingPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    pub fn new_with_seed(_num_edges: usize, sample_size: usize, seed: u64) -> Self {
+        assert!(sample_size > 0);
+        // xorshift never leaves 0
+        let seed = if seed == 0 { 88172645463325252 } else { seed };
+        Self { sample_size, seed, state: seed, _maker: std::marker::PhantomData }
+    }
+
+    fn next_index(&mut self, len: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        (self.state % len as u64) as usize
+    }
+}
+
+impl<Flow> PivotRule<Flow> for RandomSamplingPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    fn new(num_edges: usize) -> Self {
+        let sample_size = 10.max((num_edges as f64).sqrt() as usize);
+        Self::new_with_seed(num_edges, sample_size, 88172645463325252)
+    }
+
+    // every solve draws the same edges from the seed
+    fn reset(&mut self, _num_edges: usize) {
+        self.state = self.seed;
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        if st.num_edges == 0 {
+            return None;
+        }
+
+        let mut maxi_violation = Flow::zero();
+        let mut entering_edge_id = None;
+        for _ in 0..self.sample_size {
+            let edge_id = self.next_index(st.num_edges);
+            let violation = calculate_violation(&st.edges[edge_id], st);
+            if violation > maxi_violation {
+                maxi_violation = violation;
+                entering_edge_id = Some(edge_id);
+            }
+        }
+        if entering_edge_id.is_some() {
+            return entering_edge_id;
+        }
+
+        // None only after every edge has been checked, or the solve would stop before it is optimal
+        for (edge_id, edge) in st.edges.iter().enumerate().take(st.num_edges) {
+            let violation = calculate_violation(edge, st);
+            if violation > maxi_violation {
+                maxi_violation = violation;
+                entering_edge_id = Some(edge_id);
+            }
+        }
+        entering_edge_id
+    }
+}
+
+// the violation times an estimate of the flow the pivot sends around the cycle, which would be the cost improvement if it were exact.
+// the estimate is the residual capacity of the entering edge and of the tree edges at both of its ends, an upper bound of the bottleneck.
+// in the dual network simplex the selected edge is a tree edge, and its violation is what the pivot moves
+pub struct ScaledViolationPivotRule<Flow> {
+    _maker: std::marker::PhantomData<fn() -> Flow>,
+}
+
+impl<Flow> ScaledViolationPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    fn estimate_delta(st: &SpanningTreeStructure<Flow>, edge: &InternalEdge<Flow>, violation: Flow) -> Flow {
+        // the flow goes from -> to on the edge, and back from to to from on the tree
+        let (delta, from, to) = match edge.state {
+            EdgeState::Lower => (edge.residual_capacity(), edge.from, edge.to),
+            EdgeState::Upper => (edge.flow.clone(), edge.to, edge.from),
+            EdgeState::Tree => return violation,
+        };
+        let mut delta = delta;
+        if let Some(parent_edge) = st.edges.get(st.nodes[to].parent_edge_id) {
+            delta = delta.min(if to == parent_edge.from { parent_edge.residual_capacity() } else { parent_edge.flow.clone() });
+        }
+        if let Some(parent_edge) = st.edges.get(st.nodes[from].parent_edge_id) {
+            delta = delta.min(if from == parent_edge.to { parent_edge.residual_capacity() } else { parent_edge.flow.clone() });
+        }
+        delta
+    }
+}
+
+impl<Flow> PivotRule<Flow> for ScaledViolationPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + ToPrimitive,
+{
+    fn new(_num_edges: usize) -> Self {
+        Self { _maker: std::marker::PhantomData }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        let mut maxi_score = 0.0;
+        let mut entering_edge_id = None;
+        for (edge_id, edge) in st.edges.iter().enumerate() {
+            let violation = calculate_violation(edge, st);
+            if violation <= Flow::zero() {
+                continue;
+            }
+            // in f64, as the product can overflow Flow
+            let score = violation.to_f64().unwrap() * Self::estimate_delta(st, edge, violation).to_f64().unwrap();
+            // a degenerate pivot scores 0, and it is taken when no other edge is eligible
+            if score > maxi_score || entering_edge_id.is_none() {
+                maxi_score = score;
+                entering_edge_id = Some(edge_id);
+            }
+        }
         entering_edge_id
     }
 }
+
+// one of the pivot rules chosen at runtime (e.g. from a config file), which dispatches to the rule it holds
+pub enum DynPivotRule<Flow> {
+    BestEligibleArc(BestEligibleArcPivotRule<Flow>),
+    FirstEligibleArc(FirstEligibleArcPivotRule<Flow>),
+    Bland(BlandPivotRule<Flow>),
+    BlockSearch(BlockSearchPivotRule<Flow>),
+    CandidateList(CandidateListPivotRule<Flow>),
+    AlteringCandidateList(AlteringCandidateListPivotRule<Flow>),
+    ApproximateSteepestEdge(ApproximateSteepestEdgePivotRule<Flow>),
+    RandomSampling(RandomSamplingPivotRule<Flow>),
+    ScaledViolation(ScaledViolationPivotRule<Flow>),
+}
+
+impl<Flow> From<BestEligibleArcPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: BestEligibleArcPivotRule<Flow>) -> Self {
+        DynPivotRule::BestEligibleArc(rule)
+    }
+}
+
+impl<Flow> From<FirstEligibleArcPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: FirstEligibleArcPivotRule<Flow>) -> Self {
+        DynPivotRule::FirstEligibleArc(rule)
+    }
+}
+
+impl<Flow> From<BlandPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: BlandPivotRule<Flow>) -> Self {
+        DynPivotRule::Bland(rule)
+    }
+}
+
+impl<Flow> From<BlockSearchPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: BlockSearchPivotRule<Flow>) -> Self {
+        DynPivotRule::BlockSearch(rule)
+    }
+}
+
+impl<Flow> From<CandidateListPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: CandidateListPivotRule<Flow>) -> Self {
+        DynPivotRule::CandidateList(rule)
+    }
+}
+
+impl<Flow> From<AlteringCandidateListPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: AlteringCandidateListPivotRule<Flow>) -> Self {
+        DynPivotRule::AlteringCandidateList(rule)
+    }
+}
+
+impl<Flow> From<ApproximateSteepestEdgePivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: ApproximateSteepestEdgePivotRule<Flow>) -> Self {
+        DynPivotRule::ApproximateSteepestEdge(rule)
+    }
+}
+
+impl<Flow> From<RandomSamplingPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: RandomSamplingPivotRule<Flow>) -> Self {
+        DynPivotRule::RandomSampling(rule)
+    }
+}
+
+impl<Flow> From<ScaledViolationPivotRule<Flow>> for DynPivotRule<Flow> {
+    fn from(rule: ScaledViolationPivotRule<Flow>) -> Self {
+        DynPivotRule::ScaledViolation(rule)
+    }
+}
+
+impl<Flow> PivotRule<Flow> for DynPivotRule<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + ToPrimitive,
+{
+    // the default rule of the network simplex variants
+    fn new(num_edges: usize) -> Self {
+        DynPivotRule::BlockSearch(BlockSearchPivotRule::new(num_edges))
+    }
+
+    fn reset(&mut self, num_edges: usize) {
+        match self {
+            DynPivotRule::BestEligibleArc(rule) => rule.reset(num_edges),
+            DynPivotRule::FirstEligibleArc(rule) => rule.reset(num_edges),
+            DynPivotRule::Bland(rule) => rule.reset(num_edges),
+            DynPivotRule::BlockSearch(rule) => rule.reset(num_edges),
+            DynPivotRule::CandidateList(rule) => rule.reset(num_edges),
+            DynPivotRule::AlteringCandidateList(rule) => rule.reset(num_edges),
+            DynPivotRule::ApproximateSteepestEdge(rule) => rule.reset(num_edges),
+            DynPivotRule::RandomSampling(rule) => rule.reset(num_edges),
+            DynPivotRule::ScaledViolation(rule) => rule.reset(num_edges),
+        }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        match self {
+            DynPivotRule::BestEligibleArc(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::FirstEligibleArc(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::Bland(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::BlockSearch(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::CandidateList(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::AlteringCandidateList(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::ApproximateSteepestEdge(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::RandomSampling(rule) => rule.find_entering_edge(st, calculate_violation),
+            DynPivotRule::ScaledViolation(rule) => rule.find_entering_edge(st, calculate_violation),
+        }
+    }
+
+    fn find_entering_edge_parallel<F: Fn(&InternalEdge<Flow>, &SpanningTreeStructure<Flow>) -> Flow + Sync>(&mut self, st: &SpanningTreeStructure<Flow>, calculate_violation: F) -> Option<usize> {
+        match self {
+            DynPivotRule::BestEligibleArc(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::FirstEligibleArc(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::Bland(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::BlockSearch(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::CandidateList(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::AlteringCandidateList(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::ApproximateSteepestEdge(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::RandomSampling(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+            DynPivotRule::ScaledViolation(rule) => rule.find_entering_edge_parallel(st, calculate_violation),
+        }
+    }
+
+    fn needs_pivot_updates(&self) -> bool {
+        match self {
+            DynPivotRule::BestEligibleArc(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::FirstEligibleArc(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::Bland(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::BlockSearch(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::CandidateList(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::AlteringCandidateList(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::ApproximateSteepestEdge(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::RandomSampling(rule) => rule.needs_pivot_updates(),
+            DynPivotRule::ScaledViolation(rule) => rule.needs_pivot_updates(),
+        }
+    }
+
+    fn update_after_pivot(&mut self, update: &PivotUpdate) {
+        match self {
+            DynPivotRule::BestEligibleArc(rule) => rule.update_after_pivot(update),
+            DynPivotRule::FirstEligibleArc(rule) => rule.update_after_pivot(update),
+            DynPivotRule::Bland(rule) => rule.update_after_pivot(update),
+            DynPivotRule::BlockSearch(rule) => rule.update_after_pivot(update),
+            DynPivotRule::CandidateList(rule) => rule.update_after_pivot(update),
+            DynPivotRule::AlteringCandidateList(rule) => rule.update_after_pivot(update),
+            DynPivotRule::ApproximateSteepestEdge(rule) => rule.update_after_pivot(update),
+            DynPivotRule::RandomSampling(rule) => rule.update_after_pivot(update),
+            DynPivotRule::ScaledViolation(rule) => rule.update_after_pivot(update),
+        }
+    }
+
+    fn needs_smallest_index_leaving_edge(&self) -> bool {
+        match self {
+            DynPivotRule::BestEligibleArc(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::FirstEligibleArc(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::Bland(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::BlockSearch(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::CandidateList(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::AlteringCandidateList(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::ApproximateSteepestEdge(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::RandomSampling(rule) => rule.needs_smallest_index_leaving_edge(),
+            DynPivotRule::ScaledViolation(rule) => rule.needs_smallest_index_leaving_edge(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+// whether a node pays its own cost for the flow it supplies.
+// the flow ending at a node never pays the cost of the node
+#[derive(PartialEq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OriginatedFlow {
+    #[default]
+    Exempt, // the supply is attached to the out-copy
+    Charged, // the supply is attached to the in-copy
+}
+
+// solves graph with the node costs.
+// a node u with a non-zero cost is split into u_in -> u_out, and the cost is put on the split edge.
+// edges entering u enter u_in, edges leaving u leave u_out, and the demand of u is attached to u_in.
+// the flows are written back to the edges of graph. the potentials of graph are not updated.
+pub fn solve<Flow, Solver>(solver: &mut Solver, graph: &mut Graph<Flow>) -> Status
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+    Solver: MinimumCostFlowSolver<Flow> + ?Sized,
+{
+    let num_nodes = graph.num_nodes();
+    let is_split: Vec<bool> = (0..num_nodes).map(|u| graph.node_cost(u) != Flow::zero()).collect();
+
+    // out-copies are appended after the original nodes
+    let mut split = Graph::default();
+    split.add_nodes(num_nodes);
+    let mut out = (0..num_nodes).collect::<Vec<usize>>();
+    for u in (0..num_nodes).filter(|&u| is_split[u]) {
+        out[u] = split.add_node_raw();
+    }
+
+    // the original edges keep their ids
+    let mut capacities = vec![Flow::zero(); num_nodes];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        split.push_edge(out[edge.from], edge.to, edge.lower, edge.upper, edge.cost);
+        capacities[edge.to] += edge.upper;
+    }
+
+    for u in 0..num_nodes {
+        let b = graph.b[u];
+        if b > Flow::zero() && graph.originated_flow() == OriginatedFlow::Exempt {
+            split.add_supply(out[u], b);
+        } else {
+            split.add_supply(u, b);
+        }
+
+        // enough for all the flow entering u and the supply of u
+        if is_split[u] {
+            split.push_edge(u, out[u], Flow::zero(), capacities[u] + b.max(Flow::zero()), graph.node_cost(u));
+        }
+    }
+
+    let status = solver.solve(&mut split);
+    if status == Status::Optimal {
+        for edge_id in 0..graph.num_edges() {
+            graph.set_flow(edge_id, split.get_edge(edge_id).unwrap().flow);
+        }
+    }
+    status
+}
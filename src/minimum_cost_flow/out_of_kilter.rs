@@ -1,28 +1,69 @@
+use crate::checked::add_costs;
+use crate::flow_ext::FlowExt;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, AUGMENTATIONS_TOTAL};
 use crate::minimum_cost_flow::csr::CSR;
+use crate::minimum_cost_flow::flow_result::{commit, FlowResult};
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::ops::Neg;
+use std::sync::Arc;
 
 // O(nU * (m + n) log n)
 #[derive(Default)]
 pub struct OutOfKilter<Flow> {
     csr: CSR<Flow>,
+    num_iterations: usize,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> OutOfKilter<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
 {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
     pub fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        graph.solve_balanced(|graph| {
+            let result = self.solve_ref(graph);
+            commit(graph, &result);
+            result.status()
+        })
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit.
+    // the balance policy of graph is applied by solve, not here
+    pub fn solve_ref(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "out_of_kilter");
+        let result = self.solve_inner(graph);
+        self.stats = Stats::from_counters(&[(AUGMENTATIONS_TOTAL, self.num_iterations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_iterations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.num_iterations = 0;
+
         if graph.is_unbalance() {
-            return Status::Unbalanced;
+            return FlowResult::without_flows(Status::Unbalanced);
         }
 
-        let (_source, artificial_nodes, artificial_edges) = graph.construct_extend_network_feasible_solution();
-        self.csr.build(graph);
+        // the artificial sub graph is added to a copy, so that graph is only read
+        let mut network = graph.clone();
+        let (_source, artificial_structure) = match network.construct_extend_network_feasible_solution() {
+            Ok(extended) => extended,
+            Err(status) => return FlowResult::without_flows(status),
+        };
+        self.csr.build(&network);
 
         let mut out_of_kilter_edges = Vec::new();
         for (edge_id, edge) in self.csr.inside_edge_list.iter().enumerate() {
@@ -35,36 +76,44 @@ where
 
         'outer: for (p, q, edge_id) in out_of_kilter_edges {
             while self.kilter_number(p, edge_id) > Flow::zero() {
-                let (dist, prev) = self.shortest_path(q);
+                let (dist, prev) = match self.shortest_path(q) {
+                    Ok(shortest_path) => shortest_path,
+                    Err(status) => return FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges()),
+                };
                 if prev[p].is_none() {
                     break 'outer;
                 }
 
                 // update potentials
-                for u in 0..self.csr.num_nodes {
-                    if let Some(d) = dist[u] {
-                        self.csr.potentials[u] -= d;
-                    }
+                // the update is capped at the distance to p so that the edges in kilter stay in kilter
+                let dist_p = dist[p].unwrap();
+                for (u, d) in dist.iter().enumerate() {
+                    self.csr.potentials[u] -= match *d {
+                        Some(d) => d.min(dist_p),
+                        None => dist_p,
+                    };
                 }
 
                 // update flow
                 let edge = &self.csr.inside_edge_list[edge_id];
                 if self.csr.reduced_cost(p, edge) < Flow::zero() {
                     self.update_flow_in_cycle(q, edge_id, prev);
+                    self.num_iterations += 1;
                 }
             }
         }
 
-        self.csr.set_flow(graph);
-
-        let status = if artificial_edges.iter().all(|&edge_id| graph.edges[edge_id].flow == Flow::zero()) {
+        let status = if artificial_structure.edges.iter().all(|&(edge_id, _)| self.csr.flow(edge_id) == Flow::zero()) {
             Status::Optimal
         } else {
             Status::Infeasible
         };
-        graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
+        FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges())
+    }
 
-        status
+    pub fn solve_with_solution(&mut self, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(graph);
+        Solution::from_status(status, graph, self.num_iterations)
     }
 
     fn kilter_number(&self, u: usize, edge_id: usize) -> Flow {
@@ -76,7 +125,8 @@ where
         }
     }
 
-    fn shortest_path(&mut self, s: usize) -> (Vec<Option<Flow>>, Vec<Option<usize>>) {
+    #[allow(clippy::type_complexity)]
+    fn shortest_path(&mut self, s: usize) -> Result<(Vec<Option<Flow>>, Vec<Option<usize>>), Status> {
         let mut prev = vec![None; self.csr.num_nodes];
         let mut bh = BinaryHeap::new();
         let mut dist: Vec<Option<Flow>> = vec![None; self.csr.num_nodes];
@@ -97,7 +147,7 @@ where
                     continue;
                 }
 
-                let new_dist = d.0 + self.csr.reduced_cost(u, edge).max(Flow::zero());
+                let new_dist = add_costs(d.0, self.csr.reduced_cost(u, edge).max(Flow::zero()))?;
                 if dist[edge.to].is_none() || dist[edge.to].unwrap() > new_dist {
                     dist[edge.to] = Some(new_dist);
                     prev[edge.to] = Some(edge_id);
@@ -106,7 +156,7 @@ where
             }
         }
 
-        (dist, prev)
+        Ok((dist, prev))
     }
 
     fn update_flow_in_cycle(&mut self, q: usize, edge_id: usize, mut prev: Vec<Option<usize>>) {
@@ -136,3 +186,20 @@ where
         }
     }
 }
+
+impl<Flow> MinimumCostFlowSolver<Flow> for OutOfKilter<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "out_of_kilter"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
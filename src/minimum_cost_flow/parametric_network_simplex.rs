@@ -1,49 +1,144 @@
+use crate::flow_ext::FlowExt;
+use crate::ids::NodeId;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PIVOTS_TOTAL};
+use crate::minimum_cost_flow::artificial::ArtificialEdgeRole;
+use crate::minimum_cost_flow::exact_amount::TemporarySupply;
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::spanning_tree_structure::{EdgeState, SpanningTreeStructure};
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::collections::VecDeque;
 use std::ops::Neg;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct ParametricNetworkSimplex<Flow> {
     st: SpanningTreeStructure<Flow>,
     sink: usize,
+    num_iterations: usize,
+    segments: Option<Vec<(Flow, Flow)>>, // the amount and the cost per unit of each augmentation, while solve_with_breakpoints records them
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> ParametricNetworkSimplex<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
 {
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
     pub fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "parametric_network_simplex");
+        let status = graph.solve_balanced(|graph| self.solve_inner(graph));
+        self.stats = Stats::from_counters(&[(PIVOTS_TOTAL, self.num_iterations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(status, &[(PIVOTS_TOTAL, self.num_iterations)]);
+        }
+        status
+    }
+
+    fn solve_inner(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.num_iterations = 0;
+
         if graph.is_unbalance() {
             return Status::Unbalanced;
         }
 
-        let (source, sink, artificial_nodes, artificial_edges) = graph.construct_extend_network_one_supply_one_demand();
+        let (source, sink, artificial_structure) = graph.construct_extend_network_one_supply_one_demand();
         self.st.build(graph);
         (self.st.root, self.sink) = (source, sink);
 
-        if !self.make_initial_spanning_tree_structure() {
-            // there is no s-t path
-            let status = if self.st.satisfy_constraints() { Status::Optimal } else { Status::Infeasible };
-            graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
-            return status;
+        // without an s-t path, or with a distance that does not fit in Flow, nothing is sent
+        let initial = self.make_initial_spanning_tree_structure();
+        if initial == Ok(true) {
+            debug_assert!(self.st.satisfy_optimality_conditions());
+            self.run();
+            graph.potentials = self.st.nodes.iter().map(|node| node.potential).collect();
         }
-        debug_assert!(self.st.satisfy_optimality_conditions());
 
-        self.run();
-
-        let status = if self.st.satisfy_constraints() { Status::Optimal } else { Status::Infeasible };
+        let status = match initial {
+            Err(status) => status,
+            _ if self.st.satisfy_constraints() => Status::Optimal,
+            _ => Status::Infeasible,
+        };
         // copy
+        // the supply or demand that was not sent stays at its node, so that the excesses match the flows when infeasible
         graph.excesses = self.st.excesses.clone();
+        for &(edge_id, role) in artificial_structure.edges.iter() {
+            let residual_capacity = self.st.edges[edge_id].residual_capacity();
+            match role {
+                ArtificialEdgeRole::SupplySlack { node } => graph.excesses[node] += residual_capacity,
+                ArtificialEdgeRole::DemandSlack { node } => graph.excesses[node] -= residual_capacity,
+                ArtificialEdgeRole::RootLink { .. } => {}
+            }
+        }
         for edge_id in 0..graph.num_edges() {
             graph.edges[edge_id].flow = self.st.edges[edge_id].flow;
         }
-        graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
+        graph.remove_artificial_sub_graph(&artificial_structure);
         status
     }
 
+    // the cheapest flow of exactly k from source to sink, in a graph without supplies.
+    // run stops as soon as the super source has sent k, so every pivot before keeps the optimality conditions.
+    // Status::Infeasible is returned if the maximum flow is less than k
+    pub fn solve_flow_value(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, k: Flow, graph: &mut Graph<Flow>) -> Status {
+        let (source, sink) = (source.into().index(), sink.into().index());
+        if source >= graph.num_nodes() || sink >= graph.num_nodes() || source == sink || k < Flow::zero() || graph.b.iter().any(|&b| b != Flow::zero()) {
+            return Status::BadInput;
+        }
+
+        // as in max_flow_min_cost, the flow shows as the excesses of source and sink afterwards
+        self.solve(&mut TemporarySupply::new(graph, source, sink, k))
+    }
+
+    // the breakpoints (flow value, minimum cost) of the minimum cost as a function of the flow value from source to sink,
+    // from (0, 0) to (maximum flow, minimum cost of a maximum flow). the function is convex and linear between the breakpoints.
+    // a cheapest maximum flow is left in graph, which has no supplies, no lower bounds and no negative costs (Status::BadInput otherwise)
+    pub fn solve_with_breakpoints(&mut self, source: impl Into<NodeId>, sink: impl Into<NodeId>, graph: &mut Graph<Flow>) -> Result<Vec<(Flow, Flow)>, Status> {
+        let (source, sink) = (source.into().index(), sink.into().index());
+        if source >= graph.num_nodes() || sink >= graph.num_nodes() || source == sink || graph.b.iter().any(|&b| b != Flow::zero()) {
+            return Err(Status::BadInput);
+        }
+        if graph.lowers.iter().any(|&lower| lower != Flow::zero()) || graph.edges.iter().any(|edge| edge.cost < Flow::zero()) {
+            return Err(Status::BadInput);
+        }
+
+        // more than the maximum flow is offered, and the solve ends infeasible when no more can be sent
+        let offered = graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == source && edge.to != source)
+            .fold(Flow::zero(), |sum, edge| sum + edge.upper);
+        self.segments = Some(Vec::new());
+        let status = self.solve(&mut TemporarySupply::new(graph, source, sink, offered));
+        let segments = self.segments.take().unwrap();
+        if status != Status::Optimal && status != Status::Infeasible {
+            return Err(status);
+        }
+
+        let mut breakpoints = vec![(Flow::zero(), Flow::zero())];
+        let mut last_unit_cost = None;
+        for (delta, unit_cost) in segments.into_iter().filter(|&(delta, _)| delta != Flow::zero()) {
+            let &(value, cost) = breakpoints.last().unwrap();
+            if last_unit_cost == Some(unit_cost) {
+                breakpoints.pop();
+            }
+            breakpoints.push((value + delta, cost + delta * unit_cost));
+            last_unit_cost = Some(unit_cost);
+        }
+        Ok(breakpoints)
+    }
+
+    pub fn solve_with_solution(&mut self, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(graph);
+        Solution::from_status(status, graph, self.num_iterations)
+    }
+
     pub(crate) fn run(&mut self) {
         while let Some((leaving_edge_id, delta)) = self.select_leaving_edge() {
             let leaving_edge = &self.st.edges[leaving_edge_id];
@@ -54,12 +149,17 @@ where
             };
 
             self.st.update_flow_in_path(self.st.root, self.sink, delta);
+            if let Some(segments) = self.segments.as_mut() {
+                // the reduced costs of the tree edges are zero, so the cost of the path is the difference of the potentials
+                segments.push((delta, self.st.nodes[self.st.root].potential - self.st.nodes[self.sink].potential));
+            }
             if self.st.excesses[self.st.root] == Flow::zero() {
                 break;
             }
 
             if let Some((entering_edge_id, t2_new_root)) = self.select_entering_edge_id(leaving_edge_id, t2_now_root) {
                 self.dual_pivot(leaving_edge_id, entering_edge_id, t2_now_root, t2_new_root);
+                self.num_iterations += 1;
                 debug_assert!(self.st.satisfy_optimality_conditions());
             } else {
                 break;
@@ -70,21 +170,23 @@ where
     // T: shortest path
     // L: A \ T
     // U: empty
-    fn make_initial_spanning_tree_structure(&mut self) -> bool {
-        let (distances, prev_edge_id) = self.st.shortest_path(self.st.root);
+    fn make_initial_spanning_tree_structure(&mut self) -> Result<bool, Status> {
+        let (distances, prev_edge_id) = self.st.shortest_path(self.st.root)?;
 
         // there is no s-t path
         if prev_edge_id[self.sink].is_none() {
-            return false;
+            return Ok(false);
         }
 
         // make tree structure
         let mut children = vec![Vec::new(); self.st.num_nodes];
-        for edge_id in prev_edge_id.iter().filter_map(|&edge_id| edge_id) {
+        // a negative cost edge is at its upper bound and leads from its to to its from
+        for (v, edge_id) in prev_edge_id.iter().enumerate().filter_map(|(v, &edge_id)| Some((v, edge_id?))) {
             let edge = &mut self.st.edges[edge_id];
+            let u = edge.opposite_side(v);
             edge.state = EdgeState::Tree;
-            (self.st.nodes[edge.to].parent, self.st.nodes[edge.to].parent_edge_id) = (edge.from, edge_id);
-            children[edge.from].push(edge.to);
+            (self.st.nodes[v].parent, self.st.nodes[v].parent_edge_id) = (u, edge_id);
+            children[u].push(v);
         }
         (self.st.nodes[self.st.root].parent, self.st.nodes[self.st.root].parent_edge_id) = (usize::MAX, usize::MAX);
         self.st.last_descendent_dft = (0..self.st.num_nodes).collect();
@@ -120,7 +222,7 @@ where
             node.potential = -distances[u];
         }
 
-        true
+        Ok(true)
     }
 
     fn select_leaving_edge(&self) -> Option<(usize, Flow)> {
@@ -209,3 +311,20 @@ where
         self.st.attach_tree(self.st.root, attach_node, t2_new_root, entering_edge_id);
     }
 }
+
+impl<Flow> MinimumCostFlowSolver<Flow> for ParametricNetworkSimplex<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "parametric_network_simplex"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
@@ -1,55 +1,106 @@
+use crate::checked::add_costs;
+use crate::flow_ext::FlowExt;
+use crate::metrics::{MetricsSink, SolveMetrics, Stats, PHASES_TOTAL};
 use crate::minimum_cost_flow::csr::CSR;
+use crate::minimum_cost_flow::flow_result::{commit, FlowResult};
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::collections::{BinaryHeap, VecDeque};
 use std::ops::Neg;
+use std::sync::Arc;
 
 #[derive(Default)]
 pub struct PrimalDual<Flow> {
     csr: CSR<Flow>,
+    num_iterations: usize,
 
     // maximum flow(dinic)
     que: VecDeque<usize>,
     distances: Vec<usize>,
     current_edge: Vec<usize>,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
 }
 
 impl<Flow> PrimalDual<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
 {
+    // whether the last solve started from the potentials of the previous one
+    pub fn are_potentials_preserved(&self) -> bool {
+        self.csr.are_potentials_preserved
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
     pub fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        graph.solve_balanced(|graph| {
+            let result = self.solve_ref(graph);
+            commit(graph, &result);
+            result.status()
+        })
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit.
+    // the balance policy of graph is applied by solve, not here
+    pub fn solve_ref(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "primal_dual");
+        let result = self.solve_inner(graph);
+        self.stats = Stats::from_counters(&[(PHASES_TOTAL, self.num_iterations)]);
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(PHASES_TOTAL, self.num_iterations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.num_iterations = 0;
+
         if graph.is_unbalance() {
-            return Status::Unbalanced;
+            return FlowResult::without_flows(Status::Unbalanced);
         }
 
         // transforms the minimum cost flow problem into a problem with a single excess node and a single deficit node.
-        let (source, sink, artificial_nodes, artificial_edges) = graph.construct_extend_network_one_supply_one_demand();
-        self.csr.build(graph);
+        // the artificial sub graph is added to a copy, so that graph is only read
+        let mut network = graph.clone();
+        let (source, sink, _) = network.construct_extend_network_one_supply_one_demand();
+        if !self.csr.build_preserving_potentials(&network) {
+            return FlowResult::without_flows(Status::Unbounded);
+        }
 
         self.distances.resize(self.csr.num_nodes, 0);
         self.current_edge.resize(self.csr.num_nodes, 0);
 
         while self.csr.excesses[source] > Flow::zero() {
-            if !self.dual(source, sink) {
-                break;
+            match self.dual(source, sink) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(status) => return FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges()),
             }
             self.primal(source, sink);
+            self.num_iterations += 1;
         }
 
-        self.csr.set_flow(graph);
-
-        graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
-        if self.csr.excesses[source] != Flow::zero() || self.csr.excesses[sink] != Flow::zero() {
-            return Status::Infeasible;
-        }
+        let status = if self.csr.excesses[source] != Flow::zero() || self.csr.excesses[sink] != Flow::zero() {
+            Status::Infeasible
+        } else {
+            Status::Optimal
+        };
+        FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges())
+    }
 
-        Status::Optimal
+    pub fn solve_with_solution(&mut self, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(graph);
+        Solution::from_status(status, graph, self.num_iterations)
     }
 
-    // update potentials
-    fn dual(&mut self, source: usize, sink: usize) -> bool {
+    // update potentials. Err(Status::Overflow) if a distance does not fit in Flow
+    fn dual(&mut self, source: usize, sink: usize) -> Result<bool, Status> {
         assert!(self.csr.excesses[source] > Flow::zero());
 
         // calculate the shortest path
@@ -74,22 +125,35 @@ where
                     if e.residual_capacity() == Flow::zero() {
                         continue;
                     }
-                    if dist[e.to].is_none() || dist[e.to].unwrap() > d + self.csr.reduced_cost(u, e) {
-                        dist[e.to] = Some(d + self.csr.reduced_cost(u, e));
-                        bh.push((-dist[e.to].unwrap(), e.to));
+                    let new_dist = add_costs(d, self.csr.reduced_cost(u, e))?;
+                    if dist[e.to].is_none() || dist[e.to].unwrap() > new_dist {
+                        dist[e.to] = Some(new_dist);
+                        bh.push((-new_dist, e.to));
                     }
                 }
             }
         }
 
+        if !visited[sink] {
+            return Ok(false);
+        }
+
         // update potentials
-        for u in 0..self.csr.num_nodes {
-            if visited[u] {
-                self.csr.potentials[u] -= dist[u].unwrap();
-            }
+        // distances are capped at dist[sink] so that every residual edge keeps non-negative reduced cost
+        let dist_sink = dist[sink].unwrap();
+        for (u, d) in dist.iter().enumerate() {
+            self.csr.potentials[u] -= match d {
+                Some(d) if visited[u] => (*d).min(dist_sink),
+                _ => dist_sink,
+            };
         }
+        debug_assert!(self.satisfy_reduced_cost_optimality());
 
-        visited[sink]
+        Ok(true)
+    }
+
+    fn satisfy_reduced_cost_optimality(&self) -> bool {
+        (0..self.csr.num_nodes).all(|u| self.csr.neighbors(u).all(|e| e.residual_capacity() == Flow::zero() || self.csr.reduced_cost(u, e) >= Flow::zero()))
     }
 
     fn primal(&mut self, source: usize, sink: usize) {
@@ -136,38 +200,51 @@ where
         }
     }
 
+    // with an explicit stack not to overflow the thread stack on long paths.
+    // a frame is (node, the bound of the flow through it, the flow sent from it so far), and goes down along the current edge of the node
     fn dfs(&mut self, u: usize, sink: usize, upper: Flow) -> Option<Flow> {
-        if u == sink {
-            return Some(upper);
-        }
-
-        let mut res = Flow::zero();
-        for edge_index in self.current_edge[u]..self.csr.start[u + 1] {
-            self.current_edge[u] = edge_index;
-
-            if !self.is_admissible_edge(u, edge_index) || self.csr.reduced_cost(u, &self.csr.inside_edge_list[edge_index]) != Flow::zero() {
-                continue;
-            }
-
-            let v = self.csr.inside_edge_list[edge_index].to;
-            let residual_capacity = self.csr.inside_edge_list[edge_index].residual_capacity();
-            if let Some(d) = self.dfs(v, sink, residual_capacity.min(upper - res)) {
-                let rev = self.csr.inside_edge_list[edge_index].rev;
-
+        let mut stack = vec![(u, upper, Flow::zero())];
+        let mut returned: Option<Flow> = None;
+        while let Some(&(u, upper, mut res)) = stack.last() {
+            let mut next = self.current_edge[u];
+            if let Some(d) = returned.take() {
                 // update flow
-                self.csr.inside_edge_list[edge_index].flow += d;
-                self.csr.inside_edge_list[rev].flow -= d;
+                self.csr.move_flow(next, d);
 
                 res += d;
+                stack.last_mut().unwrap().2 = res;
                 if res == upper {
-                    return Some(res);
+                    stack.pop();
+                    returned = Some(res);
+                    continue;
+                }
+                next += 1;
+            }
+
+            let admissible_edge = (next..self.csr.start[u + 1]).find(|&edge_index| {
+                self.current_edge[u] = edge_index;
+                self.is_admissible_edge(u, edge_index) && self.csr.reduced_cost(u, &self.csr.inside_edge_list[edge_index]) == Flow::zero()
+            });
+            match admissible_edge {
+                Some(edge_index) => {
+                    let v = self.csr.inside_edge_list[edge_index].to;
+                    let bound = self.csr.inside_edge_list[edge_index].residual_capacity().min(upper - res);
+                    if v == sink {
+                        returned = Some(bound);
+                    } else {
+                        stack.push((v, bound, Flow::zero()));
+                    }
+                }
+                None => {
+                    self.current_edge[u] = self.csr.start[u + 1];
+                    self.distances[u] = self.csr.num_nodes;
+                    stack.pop();
+                    returned = Some(res);
                 }
             }
         }
-        self.current_edge[u] = self.csr.start[u + 1];
-        self.distances[u] = self.csr.num_nodes;
 
-        Some(res)
+        returned
     }
 
     #[inline]
@@ -175,3 +252,20 @@ where
         self.csr.inside_edge_list[i].residual_capacity() > Flow::zero() && self.distances[from] == self.distances[self.csr.inside_edge_list[i].to] + 1
     }
 }
+
+impl<Flow> MinimumCostFlowSolver<Flow> for PrimalDual<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "primal_dual"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
@@ -1,72 +1,564 @@
-use crate::minimum_cost_flow::graph::Graph;
-use crate::minimum_cost_flow::network_simplex_pivot_rules::PivotRule;
+use crate::approx::snap;
+use crate::clock::TimeLimit;
+use crate::cooperative::{ignore_progress, CancellationToken, Progress, WorkCounter, YieldHook};
+use crate::flow_ext::FlowExt;
+use crate::ids::NodeId;
+use crate::metrics::{Histogram, MetricsSink, SolveMetrics, Stats, StatsLevel, PIVOTS_TOTAL};
+use crate::minimum_cost_flow::analysis::CostRange;
+use crate::minimum_cost_flow::artificial::{ArtificialEdgeRole, ArtificialStructure};
+use crate::minimum_cost_flow::basis::{fingerprint, BasisBundle, BasisError};
+use crate::minimum_cost_flow::consistency::{ConsistencyChecker, ConsistencyChecks, InjectedFault, InvariantViolation};
+use crate::minimum_cost_flow::graph::{DotOptions, Graph};
+use crate::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule, PivotUpdate};
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::spanning_tree_structure::{EdgeState, InternalEdge, SpanningTreeStructure};
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
-use std::ops::Neg;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::ops::{ControlFlow, Neg};
+use std::sync::Arc;
+
+// pivots between two calls of the progress callback
+pub const PROGRESS_INTERVAL: usize = 1000;
+
+// the spanning tree of the last optimal solve on the extended network
+struct Basis<Flow> {
+    num_nodes: usize,
+    edges: Vec<(usize, usize, Flow, Flow)>, // from, to, upper and cost of the edges of the graph
+    states: Vec<EdgeState>,                 // including the artificial edges
+    potentials: Vec<Flow>,                  // of the nodes of the graph
+}
+
+// the artificial part of the extended network, removed from the graph when the solve finishes
+struct Extension {
+    num_edges: usize,
+    artificial_structure: ArtificialStructure,
+}
 
 #[derive(Default)]
 pub struct PrimalNetworkSimplex<Flow> {
     st: SpanningTreeStructure<Flow>,
+    time_limit: Option<TimeLimit>,
+    cancellation_token: Option<CancellationToken>,
+    is_stopped: bool, // by the progress callback
+    basis: Option<Basis<Flow>>,
+    is_warm_started: bool,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+    stats_level: StatsLevel,
+    cycle_lengths: Histogram,
+    work_counter: WorkCounter,
+    check_position: Option<usize>, // where the optimality check after a paused scan goes on
+    unmet_slacks: Vec<(ArtificialEdgeRole, Flow)>,
+    consistency_checker: ConsistencyChecker,
+    invariant_violation: Option<InvariantViolation>,
+}
+
+// a solve that returns Status::Paused when the hook of the work counter returns Control::Pause, and goes on with resume.
+// dropped while paused, it leaves the flow so far in the graph as Status::TimeLimit does
+pub struct PrimalNetworkSimplexSession<'a, Flow, Pivot>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    solver: &'a mut PrimalNetworkSimplex<Flow>,
+    pivot: &'a mut Pivot,
+    graph: &'a mut Graph<Flow>,
+    extension: Option<Extension>, // None once finished
+    status: Status,
 }
 
 impl<Flow> PrimalNetworkSimplex<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
 {
+    pub fn set_time_limit(&mut self, time_limit: TimeLimit) {
+        self.time_limit = Some(time_limit);
+    }
+
+    // looked at before each pivot. the flow so far is left in the graph with Status::Cancelled as with Status::TimeLimit
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation_token = Some(token);
+    }
+
+    pub fn num_pivots(&self) -> usize {
+        self.stats.num_pivots
+    }
+
+    pub fn set_stats_level(&mut self, stats_level: StatsLevel) {
+        self.stats_level = stats_level;
+    }
+
+    // the number of edges of the cycle of each pivot, entering edge included, recorded with StatsLevel::Histograms
+    pub fn cycle_length_histogram(&self) -> Vec<(u64, u64)> {
+        self.cycle_lengths.buckets()
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // hook is called every granularity units of work: the edges scanned by the pivot rule and the nodes walked on the cycles.
+    // the scans are serial while it is set. solve goes on after Control::Pause, which only stops a session
+    pub fn set_work_counter(&mut self, granularity: u64, hook: YieldHook) {
+        self.work_counter = WorkCounter::new(granularity, hook);
+    }
+
+    // the units of work of the last solve, counted while a work counter is set
+    pub fn work_count(&self) -> u64 {
+        self.work_counter.count()
+    }
+
+    // checks every interval pivots and once more when the pivots end, Status::InternalInvariant if one fails.
+    // ConsistencyChecks::NONE (the default) costs nothing
+    pub fn set_consistency_checks(&mut self, checks: ConsistencyChecks, interval: usize) {
+        self.consistency_checker = ConsistencyChecker::new(checks, interval);
+    }
+
+    // corrupts the state after at_pivot pivots (a round of checks must be due then), to test the checks
+    pub fn set_injected_fault(&mut self, fault: InjectedFault, at_pivot: usize) {
+        self.consistency_checker.set_injected_fault(Some((fault, at_pivot)));
+    }
+
+    // the failed check of the last solve that returned Status::InternalInvariant
+    pub fn invariant_violation(&self) -> Option<InvariantViolation> {
+        self.invariant_violation
+    }
+
+    // the rounds of consistency checks of the last solve
+    pub fn num_consistency_rounds(&self) -> usize {
+        self.consistency_checker.num_rounds()
+    }
+
+    // the balance policy of graph is not applied, and an unbalanced graph finishes with Status::Unbalanced
+    pub fn session<'a, Pivot: PivotRule<Flow>>(&'a mut self, pivot: &'a mut Pivot, graph: &'a mut Graph<Flow>) -> PrimalNetworkSimplexSession<'a, Flow, Pivot> {
+        pivot.reset(graph.num_edges());
+        let (extension, status) = match self.start(graph, false) {
+            Ok(extension) => (Some(extension), Status::Paused),
+            Err(status) => (None, status),
+        };
+        PrimalNetworkSimplexSession { solver: self, pivot, graph, extension, status }
+    }
+
+    // whether the last solve started from the basis of the previous solve
+    // the artificial edges still carrying flow when the last solve ended with Status::Infeasible,
+    // e.g. (SupplySlack { node: 17 }, 3) if 3 units of the supply of node 17 could not be sent
+    pub fn unmet_slacks(&self) -> &[(ArtificialEdgeRole, Flow)] {
+        &self.unmet_slacks
+    }
+
+    pub fn is_warm_started(&self) -> bool {
+        self.is_warm_started
+    }
+
+    pub fn clear_basis(&mut self) {
+        self.basis = None;
+    }
+
+    // the basis of the last optimal solve, None if there is none
+    pub fn export_basis(&self) -> Option<BasisBundle<Flow>>
+    where
+        Flow: Hash,
+    {
+        let basis = self.basis.as_ref()?;
+        let num_edges = basis.edges.len();
+        let edge_ids = |state: EdgeState| (0..num_edges).filter(|&edge_id| basis.states[edge_id] == state).collect();
+        let artificial_tree_nodes = (0..basis.num_nodes - 1).filter(|&u| basis.states[num_edges + u] == EdgeState::Tree).collect();
+        let fingerprint = fingerprint(basis.num_nodes - 1, basis.edges.iter().map(|&(from, to, _, _)| (from, to)));
+        Some(BasisBundle::new(fingerprint, edge_ids(EdgeState::Tree), artificial_tree_nodes, edge_ids(EdgeState::Upper), basis.potentials.clone()))
+    }
+
+    // the cost range of each edge of the graph for the basis of the last optimal solve, None if there is none.
+    // a change of the cost of a non-tree edge only changes its reduced cost. a change of the cost of a tree edge shifts the potentials
+    // of the subtree below it, which changes the reduced costs of the non-tree edges across it, i.e. those whose cycle has the tree edge
+    pub fn cost_ranging(&self) -> Option<Vec<CostRange<Flow>>> {
+        let basis = self.basis.as_ref()?;
+        let st = &self.st;
+
+        // a subtree is a range of the depth-first thread
+        let mut position = vec![0; st.num_nodes];
+        let mut now = st.root;
+        for i in 0..st.num_nodes {
+            position[now] = i;
+            now = st.next_node_dft[now];
+        }
+        let is_in_subtree = |sub_tree_root: usize, u: usize| position[sub_tree_root] <= position[u] && position[u] < position[sub_tree_root] + st.num_successors[sub_tree_root];
+
+        let cost_ranges = (0..basis.edges.len())
+            .map(|edge_id| {
+                let edge = &st.edges[edge_id];
+                let reduced_cost = st.reduced_cost(edge);
+                match edge.state {
+                    EdgeState::Lower => return CostRange { lower_delta: Some(-reduced_cost), upper_delta: None },
+                    EdgeState::Upper => return CostRange { lower_delta: None, upper_delta: Some(-reduced_cost) },
+                    EdgeState::Tree => {}
+                }
+
+                // increasing the cost by delta keeps the reduced cost zero by shifting the potentials of the subtree by -delta
+                // if the edge points into it, and by delta otherwise
+                let points_into_subtree = st.nodes[edge.to].parent_edge_id == edge_id;
+                let sub_tree_root = if points_into_subtree { edge.to } else { edge.from };
+                let mut cost_range = CostRange { lower_delta: None, upper_delta: None };
+                for other in st.edges.iter().filter(|other| other.state != EdgeState::Tree && other.upper != Flow::zero()) {
+                    // the reduced cost of other changes by delta (is_increasing) or by -delta
+                    let is_increasing = match (is_in_subtree(sub_tree_root, other.from), is_in_subtree(sub_tree_root, other.to)) {
+                        (true, false) => points_into_subtree,
+                        (false, true) => !points_into_subtree,
+                        _ => continue,
+                    };
+                    let reduced_cost = st.reduced_cost(other);
+                    // at the lower bound the reduced cost stays non-negative, and at the upper bound non-positive
+                    let (lower_delta, upper_delta) = match (other.state == EdgeState::Lower, is_increasing) {
+                        (true, true) => (Some(-reduced_cost), None),
+                        (true, false) => (None, Some(reduced_cost)),
+                        (false, true) => (None, Some(-reduced_cost)),
+                        (false, false) => (Some(reduced_cost), None),
+                    };
+                    cost_range.lower_delta = cost_range.lower_delta.max(lower_delta);
+                    if let Some(upper_delta) = upper_delta {
+                        cost_range.upper_delta = Some(match cost_range.upper_delta.take() {
+                            Some(delta) => delta.min(upper_delta),
+                            None => upper_delta,
+                        });
+                    }
+                }
+                cost_range
+            })
+            .collect();
+        Some(cost_ranges)
+    }
+
+    // the next solve_with_basis starts from bundle, exported on a graph with the same edges but possibly other supplies, bounds and costs.
+    // the tree flows out of bounds are repaired by the pivots as in solve_with_basis, and the potentials are computed again from the tree
+    pub fn import_basis(&mut self, graph: &Graph<Flow>, bundle: &BasisBundle<Flow>) -> Result<(), BasisError>
+    where
+        Flow: Hash,
+    {
+        bundle.validate(graph)?;
+        let num_edges = graph.num_edges();
+        let mut states = vec![EdgeState::Lower; num_edges + graph.num_nodes()];
+        bundle.tree_edge_ids.iter().for_each(|&edge_id| states[edge_id] = EdgeState::Tree);
+        bundle.upper_edge_ids.iter().for_each(|&edge_id| states[edge_id] = EdgeState::Upper);
+        bundle.artificial_tree_nodes.iter().for_each(|&u| states[num_edges + u] = EdgeState::Tree);
+        let edges = (0..num_edges)
+            .map(|edge_id| {
+                let edge = graph.get_edge(edge_id).unwrap();
+                (edge.from, edge.to, edge.upper - edge.lower, edge.cost)
+            })
+            .collect();
+        self.basis = Some(Basis { num_nodes: graph.num_nodes() + 1, edges, states, potentials: bundle.potentials.clone() });
+        Ok(())
+    }
+
     pub fn solve<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>) -> Status {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "primal_network_simplex");
+        let status = graph.solve_balanced(|graph| self.solve_inner(pivot, graph, false, &mut ignore_progress));
+        if let Some(metrics) = metrics {
+            metrics.finish(status, &[(PIVOTS_TOTAL, self.stats.num_pivots)]);
+        }
+        status
+    }
+
+    // starts from the optimal basis of the previous solve if the graph has the same edges (only the supplies or the lower bounds changed)
+    // otherwise the initial basis is built from the artificial edges as in solve
+    pub fn solve_with_basis<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>) -> Status {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "primal_network_simplex");
+        let status = graph.solve_balanced(|graph| self.solve_inner(pivot, graph, true, &mut ignore_progress));
+        if let Some(metrics) = metrics {
+            metrics.finish(status, &[(PIVOTS_TOTAL, self.stats.num_pivots)]);
+        }
+        status
+    }
+
+    // progress is called every PROGRESS_INTERVAL pivots, and ControlFlow::Break leaves the flow so far in the graph with Status::Cancelled
+    pub fn solve_with_progress<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>, mut progress: impl FnMut(Progress<Flow>) -> ControlFlow<()>) -> Status {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "primal_network_simplex");
+        let status = graph.solve_balanced(|graph| self.solve_inner(pivot, graph, false, &mut progress));
+        if let Some(metrics) = metrics {
+            metrics.finish(status, &[(PIVOTS_TOTAL, self.stats.num_pivots)]);
+        }
+        status
+    }
+
+    fn solve_inner<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>, warm_start: bool, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> Status {
+        pivot.reset(graph.num_edges());
+        let extension = match self.start(graph, warm_start) {
+            Ok(extension) => extension,
+            Err(status) => return status,
+        };
+        let mut finished = self.run(pivot, progress);
+        while !finished && self.work_counter.is_paused() {
+            self.work_counter.resume();
+            finished = self.run(pivot, progress);
+        }
+        self.finish(graph, extension, finished)
+    }
+
+    // build the initial spanning tree of the extended network
+    fn start(&mut self, graph: &mut Graph<Flow>, warm_start: bool) -> Result<Extension, Status> {
+        self.stats = Stats::default();
+        self.is_stopped = false;
+        self.is_warm_started = false;
+        self.cycle_lengths.clear();
+        self.work_counter.start();
+        self.check_position = None;
+        self.consistency_checker.start();
+        self.invariant_violation = None;
+
         if graph.is_unbalance() {
-            return Status::Unbalanced;
+            return Err(Status::Unbalanced);
         }
+        // longer than any path. the tree structure keeps the negative costs, so the absolute values are summed
+        let inf_cost = graph.inf_cost()?;
+        // the net outflow each node needs with every edge at its lower bound, independent of the current flow
+        let mut required = graph.excesses.clone();
+        for edge in graph.edges.iter() {
+            required[edge.from] += edge.flow.clone();
+            required[edge.to] -= edge.flow.clone();
+        }
+
+        // start from the bounds the edges are added at (a negative cost edge at its upper bound),
+        // since the flow of a previous solve is not at the bounds of the non-tree edges
+        graph.reset_flows();
 
-        let inf_cost = graph.edges.iter().map(|e| e.cost).fold(Flow::one(), |acc, cost| acc + cost); // all edge costs are non-negative
-        let (root, artificial_nodes, artificial_edges) = graph.construct_extend_network_feasible_solution();
+        let num_edges = graph.num_edges();
+        let (root, artificial_structure) = graph.construct_extend_network_feasible_solution()?;
         self.st.build(graph);
-        (self.st.root, self.st.nodes[root].parent, self.st.nodes[root].parent_edge_id) = (root, usize::MAX, usize::MAX);
+        (self.st.root, self.st.nodes[root].parent, self.st.nodes[root].parent_edge_id, self.st.nodes[root].potential) = (root, usize::MAX, usize::MAX, Flow::zero());
+        required.push(Flow::zero());
 
-        self.make_initial_spanning_tree_structure(graph, &artificial_edges, inf_cost);
+        let basis = self.basis.take();
+        self.is_warm_started = warm_start && basis.as_ref().is_some_and(|basis| self.restore_basis(basis, num_edges, &required));
+        if !self.is_warm_started {
+            self.make_initial_spanning_tree_structure(graph, &artificial_structure.edge_ids(), inf_cost);
+        }
         debug_assert!(self.st.validate_num_successors(self.st.root));
         debug_assert!(self.st.satisfy_constraints());
 
-        self.run(pivot, &artificial_edges);
+        if let Some(time_limit) = self.time_limit.as_mut() {
+            time_limit.start();
+        }
+        Ok(Extension { num_edges, artificial_structure })
+    }
 
-        let status = if self.st.satisfy_constraints() { Status::Optimal } else { Status::Infeasible };
+    // copy the result of run to graph
+    fn finish(&mut self, graph: &mut Graph<Flow>, extension: Extension, finished: bool) -> Status {
+        let Extension { num_edges, artificial_structure } = extension;
+        if finished && self.consistency_checker.is_due(0) {
+            self.invariant_violation = self.consistency_checker.check(&mut self.st, self.stats.num_pivots).err();
+        }
+
+        // if there is remaining flow on the artificial edge, revert it
+        self.unmet_slacks.clear();
+        for &(edge_id, role) in artificial_structure.edges.iter() {
+            let edge = &mut self.st.edges[edge_id];
+            if edge.flow > self.st.tolerance {
+                self.unmet_slacks.push((role, edge.flow.clone()));
+                self.st.excesses[edge.from] += edge.flow.clone();
+                self.st.excesses[edge.to] -= edge.flow.clone();
+                edge.flow = Flow::zero();
+            }
+        }
+
+        let status = if self.invariant_violation.is_some() {
+            Status::InternalInvariant
+        } else if !finished && self.is_cancelled() {
+            Status::Cancelled
+        } else if !finished {
+            Status::TimeLimit
+        } else if self.st.satisfy_constraints() {
+            Status::Optimal
+        } else {
+            Status::Infeasible
+        };
+        if status != Status::Infeasible {
+            self.unmet_slacks.clear();
+        }
+
+        if status == Status::Optimal {
+            self.save_basis(num_edges);
+        }
 
         // copy
         graph.excesses = self.st.excesses.clone();
+        graph.potentials = self.st.nodes.iter().map(|node| node.potential.clone()).collect();
         for edge_id in 0..graph.num_edges() {
-            graph.edges[edge_id].flow = self.st.edges[edge_id].flow;
+            graph.edges[edge_id].flow = self.st.edges[edge_id].flow.clone();
         }
-        graph.remove_artificial_sub_graph(&artificial_nodes, &artificial_edges);
+        graph.remove_artificial_sub_graph(&artificial_structure);
 
         status
     }
 
-    pub(crate) fn run<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, artificial_edges: &[usize]) {
-        while let Some(entering_edge_id) = pivot.find_entering_edge(&self.st, Self::calculate_violation) {
-            let (leaving_edge_id, apex, delta, t2_now_root, t2_new_root) = self.select_leaving_edge(entering_edge_id);
-            self.st.update_flow_in_cycle(entering_edge_id, delta, apex);
-            self.pivot(leaving_edge_id, entering_edge_id, t2_now_root, t2_new_root);
+    fn save_basis(&mut self, num_edges: usize) {
+        let edges = self.st.edges[..num_edges].iter().map(|edge| (edge.from, edge.to, edge.upper.clone(), edge.cost.clone())).collect();
+        let states = self.st.edges.iter().map(|edge| edge.state).collect();
+        let potentials = self.st.nodes[..self.st.num_nodes - 1].iter().map(|node| node.potential.clone()).collect();
+        self.basis = Some(Basis { num_nodes: self.st.num_nodes, edges, states, potentials });
+    }
+
+    // adds the edge to graph (its id is the one add_directed_edge returns) and goes on from the spanning tree of the last optimal solve,
+    // where the new edge starts at its lower bound with flow zero and enters by the usual pricing if its reduced cost is negative.
+    // it is solved from scratch if there is no such solve on graph, the supplies changed since, or the lower bound is not zero
+    pub fn add_edge_and_reoptimize(&mut self, graph: &mut Graph<Flow>, from: impl Into<NodeId>, to: impl Into<NodeId>, lower: Flow, upper: Flow, cost: Flow) -> Status {
+        let (from, to) = (from.into().index(), to.into().index());
+        let is_basis_valid = self.basis.as_ref().is_some_and(|basis| {
+            basis.num_nodes == graph.num_nodes() + 1
+                && basis.edges.len() == graph.num_edges()
+                && basis.edges.iter().enumerate().all(|(edge_id, (from, to, upper, cost))| {
+                    let edge = graph.get_edge(edge_id).unwrap();
+                    (edge.from, edge.to, edge.upper - edge.lower, edge.cost) == (*from, *to, upper.clone(), cost.clone())
+                })
+        });
+        let is_reoptimizable = is_basis_valid && lower == Flow::zero() && graph.excesses.iter().all(|excess| *excess == Flow::zero());
+        let Ok(edge_id) = graph.add_directed_edge_raw(from, to, lower, upper.clone(), cost.clone()) else {
+            return Status::BadInput;
+        };
+        if !is_reoptimizable {
+            return self.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph);
+        }
+
+        // the new edge goes before the artificial edges of the extended network, as in a solve
+        self.st.edges.insert(edge_id, InternalEdge { from, to, flow: Flow::zero(), upper, cost, state: EdgeState::Lower });
+        self.st.num_edges += 1;
+        for node in self.st.nodes.iter_mut().filter(|node| node.parent_edge_id != usize::MAX && node.parent_edge_id >= edge_id) {
+            node.parent_edge_id += 1;
+        }
+
+        self.stats = Stats::default();
+        self.is_stopped = false;
+        self.cycle_lengths.clear();
+        self.work_counter.start();
+        self.check_position = None;
+        self.consistency_checker.start();
+        (self.invariant_violation, self.basis) = (None, None);
+        if let Some(time_limit) = self.time_limit.as_mut() {
+            time_limit.start();
+        }
+        let mut pivot = BlockSearchPivotRule::new(self.st.num_edges);
+        let mut finished = self.run(&mut pivot, &mut ignore_progress);
+        while !finished && self.work_counter.is_paused() {
+            self.work_counter.resume();
+            finished = self.run(&mut pivot, &mut ignore_progress);
+        }
+
+        let status = if self.invariant_violation.is_some() {
+            Status::InternalInvariant
+        } else if !finished && self.is_cancelled() {
+            Status::Cancelled
+        } else if !finished {
+            Status::TimeLimit
+        } else {
+            // the old flow with the new edge at zero is feasible, and so is every pivot after it
+            Status::Optimal
+        };
+        if status == Status::Optimal {
+            self.save_basis(graph.num_edges());
+        }
+        for u in 0..graph.num_nodes() {
+            (graph.excesses[u], graph.potentials[u]) = (self.st.excesses[u].clone(), self.st.nodes[u].potential.clone());
+        }
+        for edge_id in 0..graph.num_edges() {
+            graph.edges[edge_id].flow = self.st.edges[edge_id].flow.clone();
+        }
+        status
+    }
+
+    pub fn solve_with_solution<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(pivot, graph);
+        Solution::from_status(status, graph, self.stats.num_pivots)
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.is_stopped || self.cancellation_token.as_ref().is_some_and(|token| token.is_cancelled())
+    }
+
+    // return false if the time limit is reached, the solve is cancelled or the work counter is paused
+    pub(crate) fn run<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> bool {
+        while let Some(entering_edge_id) = self.find_entering_edge(pivot) {
+            if self.time_limit.as_ref().is_some_and(|time_limit| time_limit.is_over()) || self.is_cancelled() {
+                return false;
+            }
+
+            let (leaving_edge_id, apex, delta, t2_now_root, t2_new_root, to_upper) = self.select_leaving_edge(entering_edge_id, pivot.needs_smallest_index_leaving_edge());
+            let num_cycle_nodes = (pivot.needs_pivot_updates() || self.stats_level == StatsLevel::Histograms).then(|| self.num_cycle_nodes(entering_edge_id, apex));
+            if self.stats_level == StatsLevel::Histograms {
+                // a cycle has as many edges as nodes
+                self.cycle_lengths.observe(num_cycle_nodes.unwrap() as u64);
+            }
+            let update = num_cycle_nodes
+                .filter(|_| pivot.needs_pivot_updates())
+                .map(|num_cycle_nodes| PivotUpdate { entering_edge_id, leaving_edge_id, num_cycle_nodes });
+            self.st.update_flow_in_cycle(entering_edge_id, delta.clone(), apex);
+            self.pivot(leaving_edge_id, entering_edge_id, t2_now_root, t2_new_root, to_upper);
+            self.stats.num_pivots += 1;
+            if delta == Flow::zero() {
+                self.stats.num_degenerate_pivots += 1;
+            }
+            if let Some(update) = update {
+                pivot.update_after_pivot(&update);
+            }
 
             debug_assert!(self.st.validate_num_successors(self.st.root));
             debug_assert!(self.st.satisfy_constraints());
+            if self.consistency_checker.is_due(self.stats.num_pivots) {
+                if let Err(violation) = self.consistency_checker.check(&mut self.st, self.stats.num_pivots) {
+                    self.invariant_violation = Some(violation);
+                    return false;
+                }
+            }
+            if self.stats.num_pivots.is_multiple_of(PROGRESS_INTERVAL) && progress(Progress::NetworkSimplex { num_pivots: self.stats.num_pivots }).is_break() {
+                self.is_stopped = true;
+                return false;
+            }
+            if self.work_counter.is_paused() {
+                return false;
+            }
         }
+        !self.work_counter.is_paused()
+    }
 
-        // if there is remaining flow on the artificial edge, revert it
-        for &edge_id in artificial_edges.iter() {
-            let edge = &mut self.st.edges[edge_id];
-            if edge.flow > Flow::zero() {
-                self.st.excesses[edge.from] += edge.flow;
-                self.st.excesses[edge.to] -= edge.flow;
-                edge.flow = Flow::zero();
+    fn find_entering_edge<Pivot: PivotRule<Flow>>(&mut self, pivot: &mut Pivot) -> Option<usize> {
+        self.stats.num_entering_edge_scans += 1;
+        if !self.work_counter.is_configured() {
+            return pivot.find_entering_edge_parallel(&self.st, Self::calculate_violation);
+        }
+
+        // a scan stopped by a pause has only seen some of the edges, so its None does not prove optimality.
+        // the check over all the edges goes on from where it paused, and its first eligible edge enters
+        if let Some(position) = self.check_position.take() {
+            for edge_id in position..self.st.num_edges {
+                if Self::calculate_violation(&self.st.edges[edge_id], &self.st) > Flow::zero() {
+                    return Some(edge_id);
+                }
+                if self.work_counter.tick(1) {
+                    self.check_position = Some(edge_id + 1);
+                    return None;
+                }
             }
+            return None;
         }
+
+        // after a pause the rest of the scan sees no violation, and the best edge seen so far enters
+        let work_counter = &self.work_counter;
+        let entering_edge_id = pivot.find_entering_edge(&self.st, |edge, st| if work_counter.tick(1) { Flow::zero() } else { Self::calculate_violation(edge, st) });
+        if entering_edge_id.is_none() && work_counter.is_paused() {
+            self.check_position = Some(0);
+        }
+        entering_edge_id
     }
 
+    // a violation within the tolerance is none
     fn calculate_violation(edge: &InternalEdge<Flow>, st: &SpanningTreeStructure<Flow>) -> Flow {
-        match edge.state {
+        let violation = match edge.state {
             EdgeState::Upper => st.reduced_cost(edge),
             _ => -st.reduced_cost(edge),
+        };
+        if violation > st.tolerance {
+            violation
+        } else {
+            Flow::zero()
         }
     }
 
@@ -77,9 +569,9 @@ where
             let u = if edge.from == self.st.root { edge.to } else { edge.from };
 
             if edge.from == u {
-                (self.st.nodes[u].potential, self.st.edges[edge_id].state) = (inf_cost, EdgeState::Tree);
+                (self.st.nodes[u].potential, self.st.edges[edge_id].state) = (inf_cost.clone(), EdgeState::Tree);
             } else {
-                (self.st.nodes[u].potential, self.st.edges[edge_id].state) = (-inf_cost, EdgeState::Tree);
+                (self.st.nodes[u].potential, self.st.edges[edge_id].state) = (-inf_cost.clone(), EdgeState::Tree);
             }
 
             (self.st.nodes[u].parent, self.st.nodes[u].parent_edge_id) = (self.st.root, edge_id);
@@ -97,8 +589,154 @@ where
         self.st.num_successors[self.st.root] = graph.num_nodes();
     }
 
-    // keep strongly feasible solution
-    fn select_leaving_edge(&self, entering_edge_id: usize) -> (usize, usize, Flow, usize, usize) {
+    // rebuilds the tree of basis on the current extended network and computes its flows and potentials.
+    // a tree edge whose flow is out of bounds is fixed at the violated bound and the subtree below it hangs from the root through its artificial edge,
+    // which the pivots drive out again because of the cost. returns false without changing the tree if the edges are different
+    fn restore_basis(&mut self, basis: &Basis<Flow>, num_edges: usize, required: &[Flow]) -> bool {
+        if basis.num_nodes != self.st.num_nodes || basis.states.len() != self.st.num_edges {
+            return false;
+        }
+        if self.st.edges[..num_edges]
+            .iter()
+            .zip(basis.edges.iter())
+            .any(|(edge, (from, to, upper, cost))| (edge.from, edge.to, &edge.upper, &edge.cost) != (*from, *to, upper, cost))
+        {
+            return false;
+        }
+
+        // a non-tree edge is at its lower or upper bound. the artificial ones are always at the lower bound
+        let mut states = basis.states.clone();
+        let mut flows = vec![Flow::zero(); self.st.num_edges];
+        let mut remaining = required.to_vec();
+        let mut tree_edges = vec![Vec::new(); self.st.num_nodes];
+        for (edge_id, edge) in self.st.edges.iter().enumerate() {
+            match states[edge_id] {
+                EdgeState::Tree => {
+                    tree_edges[edge.from].push(edge_id);
+                    tree_edges[edge.to].push(edge_id);
+                }
+                EdgeState::Upper if edge_id < num_edges => {
+                    flows[edge_id] = edge.upper.clone();
+                    remaining[edge.from] -= edge.upper.clone();
+                    remaining[edge.to] += edge.upper.clone();
+                }
+                _ => states[edge_id] = EdgeState::Lower,
+            }
+        }
+
+        let root = self.st.num_nodes - 1;
+        let (mut parent, mut parent_edge_id) = (vec![usize::MAX; self.st.num_nodes], vec![usize::MAX; self.st.num_nodes]);
+        let order = Self::preorder(root, &self.st.edges, &tree_edges, &mut parent, &mut parent_edge_id);
+        if order.len() != self.st.num_nodes {
+            return false;
+        }
+
+        // the tree flows are determined from the leaves.
+        // an artificial edge only has to carry its flow in some direction, so it is turned and widened as needed
+        let mut is_turned = vec![false; self.st.num_edges];
+        for &u in order.iter().rev().filter(|&&u| u != root) {
+            let edge_id = parent_edge_id[u];
+            let edge = &self.st.edges[edge_id];
+            let flow = if edge.from == u { remaining[u].clone() } else { -remaining[u].clone() };
+            if edge_id >= num_edges || (Flow::zero() <= flow && flow <= edge.upper) {
+                flows[edge_id] = flow;
+                let excess = remaining[u].clone();
+                remaining[parent[u]] += excess;
+                continue;
+            }
+
+            // fix the edge at the violated bound and route the rest to the root
+            let (bound, state) = if flow < Flow::zero() {
+                (Flow::zero(), EdgeState::Lower)
+            } else {
+                (edge.upper.clone(), EdgeState::Upper)
+            };
+            let outflow = if edge.from == u { bound.clone() } else { -bound.clone() };
+            (flows[edge_id], states[edge_id]) = (bound, state);
+            remaining[parent[u]] += outflow.clone();
+            remaining[u] -= outflow;
+
+            let artificial_edge_id = num_edges + u;
+            let artificial_edge = &self.st.edges[artificial_edge_id];
+            flows[artificial_edge_id] = if artificial_edge.from == u { remaining[u].clone() } else { -remaining[u].clone() };
+            states[artificial_edge_id] = EdgeState::Tree;
+            (parent[u], parent_edge_id[u]) = (root, artificial_edge_id);
+        }
+        for edge_id in num_edges..self.st.num_edges {
+            if flows[edge_id] < Flow::zero() {
+                (flows[edge_id], is_turned[edge_id]) = (-flows[edge_id].clone(), true);
+            }
+        }
+
+        // the tree may have changed, so the order is taken again
+        let mut tree_edges = vec![Vec::new(); self.st.num_nodes];
+        for u in (0..self.st.num_nodes).filter(|&u| u != root) {
+            tree_edges[parent[u]].push(parent_edge_id[u]);
+        }
+        let order = Self::preorder(root, &self.st.edges, &tree_edges, &mut parent, &mut parent_edge_id);
+
+        // replace the initial tree
+        for (edge_id, edge) in self.st.edges.iter_mut().enumerate() {
+            if is_turned[edge_id] {
+                (edge.from, edge.to) = (edge.to, edge.from);
+            }
+            if edge_id >= num_edges {
+                edge.upper = edge.upper.clone().max(flows[edge_id].clone());
+            }
+            (edge.flow, edge.state) = (flows[edge_id].clone(), states[edge_id]);
+        }
+        self.st.excesses.fill(Flow::zero());
+
+        let mut num_successors = vec![1; self.st.num_nodes];
+        for &u in order.iter().rev().filter(|&&u| u != root) {
+            num_successors[parent[u]] += num_successors[u];
+        }
+        for (i, &u) in order.iter().enumerate() {
+            (self.st.nodes[u].parent, self.st.nodes[u].parent_edge_id) = (parent[u], parent_edge_id[u]);
+            self.st.nodes[u].potential = if u == root {
+                Flow::zero()
+            } else {
+                // reduced cost of the tree edge is 0
+                let edge = &self.st.edges[parent_edge_id[u]];
+                let parent_potential = self.st.nodes[parent[u]].potential.clone();
+                if edge.from == u {
+                    parent_potential + edge.cost.clone()
+                } else {
+                    parent_potential - edge.cost.clone()
+                }
+            };
+            self.st.num_successors[u] = num_successors[u];
+            self.st.last_descendent_dft[u] = order[i + num_successors[u] - 1];
+            self.st.next_node_dft[u] = order[(i + 1) % order.len()];
+            self.st.prev_node_dft[order[(i + 1) % order.len()]] = u;
+        }
+        self.st.root = root;
+        true
+    }
+
+    // depth-first preorder of the nodes reachable from root through tree_edges
+    fn preorder(root: usize, edges: &[InternalEdge<Flow>], tree_edges: &[Vec<usize>], parent: &mut [usize], parent_edge_id: &mut [usize]) -> Vec<usize> {
+        let (mut order, mut stack) = (Vec::with_capacity(tree_edges.len()), vec![root]);
+        let mut visited = vec![false; tree_edges.len()];
+        visited[root] = true;
+        while let Some(u) = stack.pop() {
+            order.push(u);
+            for &edge_id in tree_edges[u].iter().rev() {
+                let v = edges[edge_id].opposite_side(u);
+                if !visited[v] {
+                    visited[v] = true;
+                    (parent[v], parent_edge_id[v]) = (u, edge_id);
+                    stack.push(v);
+                }
+            }
+        }
+        order
+    }
+
+    // keep strongly feasible solution, or take the blocking arc with the smallest id if smallest_index.
+    // a delta within the tolerance is zero, so that the ties among them are exact
+    // the last value is whether the leaving edge reaches its upper bound
+    fn select_leaving_edge(&self, entering_edge_id: usize, smallest_index: bool) -> (usize, usize, Flow, usize, usize, bool) {
         let entering_edge = &self.st.edges[entering_edge_id];
 
         let (from, to) = match entering_edge.state {
@@ -107,21 +745,24 @@ where
             EdgeState::Upper => (entering_edge.to, entering_edge.from),
         };
 
-        let (mut leaving_edge_id, mut mini_delta, mut t2_now_root, mut t2_new_root) = (entering_edge_id, entering_edge.upper, usize::MAX, usize::MAX);
+        let (mut leaving_edge_id, mut mini_delta, mut t2_now_root, mut t2_new_root) = (entering_edge_id, entering_edge.upper.clone(), usize::MAX, usize::MAX);
+        let mut to_upper = entering_edge.state == EdgeState::Lower;
 
+        let mut num_steps = 0;
         let apex = {
             let (mut u, mut v) = (from, to);
             while u != v {
+                num_steps += 1;
                 let (u_num, v_num) = (self.st.num_successors[u], self.st.num_successors[v]);
 
                 if u_num <= v_num {
                     let edge_id = self.st.nodes[u].parent_edge_id;
                     let edge = &self.st.edges[edge_id];
-                    let delta = if u == edge.to { edge.residual_capacity() } else { edge.flow };
+                    let delta = snap(if u == edge.to { edge.residual_capacity() } else { edge.flow.clone() }, &self.st.tolerance);
 
                     // search first blocking arc
-                    if delta < mini_delta {
-                        (leaving_edge_id, mini_delta, t2_now_root, t2_new_root) = (edge_id, delta, u, from);
+                    if delta < mini_delta || (smallest_index && delta == mini_delta && edge_id < leaving_edge_id) {
+                        (leaving_edge_id, mini_delta, t2_now_root, t2_new_root, to_upper) = (edge_id, delta, u, from, u == edge.to);
                     }
                     u = self.st.nodes[u].parent;
                 }
@@ -129,22 +770,36 @@ where
                 if v_num <= u_num {
                     let edge_id = self.st.nodes[v].parent_edge_id;
                     let edge = &self.st.edges[edge_id];
-                    let delta = if v == edge.from { edge.residual_capacity() } else { edge.flow };
+                    let delta = snap(if v == edge.from { edge.residual_capacity() } else { edge.flow.clone() }, &self.st.tolerance);
 
                     // search last blocking arc
-                    if delta <= mini_delta {
-                        (leaving_edge_id, mini_delta, t2_now_root, t2_new_root) = (edge_id, delta, v, to);
+                    if delta < mini_delta || (delta == mini_delta && (!smallest_index || edge_id < leaving_edge_id)) {
+                        (leaving_edge_id, mini_delta, t2_now_root, t2_new_root, to_upper) = (edge_id, delta, v, to, v == edge.from);
                     }
                     v = self.st.nodes[v].parent;
                 }
             }
             u
         };
+        self.work_counter.tick(num_steps);
 
-        (leaving_edge_id, apex, mini_delta, t2_now_root, t2_new_root)
+        (leaving_edge_id, apex, mini_delta, t2_now_root, t2_new_root, to_upper)
     }
 
-    fn pivot(&mut self, leaving_edge_id: usize, entering_edge_id: usize, t2_now_root: usize, t2_new_root: usize) {
+    // the nodes on the tree paths from both ends of the entering edge to apex
+    fn num_cycle_nodes(&self, entering_edge_id: usize, apex: usize) -> usize {
+        let entering_edge = &self.st.edges[entering_edge_id];
+        let mut num_nodes = 1;
+        for mut u in [entering_edge.from, entering_edge.to] {
+            while u != apex {
+                num_nodes += 1;
+                u = self.st.nodes[u].parent;
+            }
+        }
+        num_nodes
+    }
+
+    fn pivot(&mut self, leaving_edge_id: usize, entering_edge_id: usize, t2_now_root: usize, t2_new_root: usize, to_upper: bool) {
         if leaving_edge_id == entering_edge_id {
             self.st.edges[entering_edge_id].state = match self.st.edges[entering_edge_id].state {
                 EdgeState::Upper => EdgeState::Lower,
@@ -156,6 +811,8 @@ where
 
         // drop leaving edge and detach tree
         self.st.detach_tree(self.st.root, t2_now_root, leaving_edge_id);
+        // an edge with lower == upper leaves at the bound the cycle pushed it to, otherwise it could be eligible again at once
+        self.st.edges[leaving_edge_id].state = if to_upper { EdgeState::Upper } else { EdgeState::Lower };
 
         // if the size of subtree t2 is larger than that of subtree t1, swap t1 and t2.
         let (t1_new_root, t2_new_root, t2_now_root, new_attach_node) = if self.st.num_successors[t2_now_root] * 2 >= self.st.num_nodes {
@@ -171,3 +828,82 @@ where
         assert_eq!(self.st.nodes[self.st.root].parent, usize::MAX);
     }
 }
+
+impl<Flow, Pivot> PrimalNetworkSimplexSession<'_, Flow, Pivot>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+    Pivot: PivotRule<Flow>,
+{
+    // Status::Paused if the hook paused it again, and the final status otherwise
+    pub fn resume(&mut self) -> Status {
+        let Some(extension) = self.extension.take() else {
+            return self.status;
+        };
+        self.solver.work_counter.resume();
+        let finished = self.solver.run(self.pivot, &mut ignore_progress);
+        if !finished && self.solver.work_counter.is_paused() {
+            self.extension = Some(extension);
+            return Status::Paused;
+        }
+        self.status = self.solver.finish(self.graph, extension, finished);
+        self.status
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    // the artificial root and edges added to the graph while the session is paused, None once it is finished
+    pub fn artificial_structure(&self) -> Option<&ArtificialStructure> {
+        self.extension.as_ref().map(|extension| &extension.artificial_structure)
+    }
+}
+
+impl<Flow, Pivot> PrimalNetworkSimplexSession<'_, Flow, Pivot>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Display + FlowExt,
+{
+    // the graph with the flows so far, the artificial elements labeled with their roles while paused
+    pub fn to_dot(&self) -> String {
+        let Some(extension) = self.extension.as_ref() else {
+            return self.graph.to_dot();
+        };
+        let mut graph = self.graph.clone();
+        for edge_id in 0..graph.num_edges() {
+            graph.edges[edge_id].flow = self.solver.st.edges[edge_id].flow.clone();
+        }
+        graph.to_dot_with_artificial_structure(&DotOptions::default(), &extension.artificial_structure)
+    }
+}
+
+impl<Flow, Pivot> Drop for PrimalNetworkSimplexSession<'_, Flow, Pivot>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    fn drop(&mut self) {
+        if let Some(extension) = self.extension.take() {
+            self.solver.finish(self.graph, extension, false);
+        }
+    }
+}
+
+impl<Flow> MinimumCostFlowSolver<Flow> for PrimalNetworkSimplex<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "primal_network_simplex"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    fn potentials(&self) -> Option<&[Flow]> {
+        self.basis.as_ref().map(|basis| basis.potentials.as_slice())
+    }
+}
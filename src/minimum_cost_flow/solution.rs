@@ -0,0 +1,81 @@
+use crate::minimum_cost_flow::graph::{to_i128, Graph};
+use crate::minimum_cost_flow::status::Status;
+use num_traits::{NumAssign, ToPrimitive};
+use std::ops::Neg;
+
+// snapshot of an optimal solution, independent of the graph it was computed on.
+// flows are indexed by the edge ids returned by add_directed_edge.
+#[derive(PartialEq, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Solution<Flow> {
+    costs: Vec<Flow>,
+    flows: Vec<Flow>,
+    potentials: Vec<Flow>,
+    iterations: usize,
+}
+
+impl<Flow> Solution<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+{
+    pub(crate) fn from_graph(graph: &Graph<Flow>, iterations: usize) -> Self {
+        Self {
+            costs: (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().cost).collect(),
+            flows: (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect(),
+            potentials: (0..graph.num_nodes()).map(|u| graph.potential(u)).collect(),
+            iterations,
+        }
+    }
+
+    // Ok(solution) if status is Status::Optimal, and Err(status) otherwise
+    pub(crate) fn from_status(status: Status, graph: &Graph<Flow>, iterations: usize) -> Result<Self, Status> {
+        match status {
+            Status::Optimal => Ok(Self::from_graph(graph, iterations)),
+            status => Err(status),
+        }
+    }
+
+    pub fn objective(&self) -> Flow {
+        self.costs.iter().zip(self.flows.iter()).fold(Flow::zero(), |sum, (cost, flow)| sum + cost.clone() * flow.clone())
+    }
+
+    #[inline]
+    pub fn flow(&self, edge_id: usize) -> Flow {
+        self.flows[edge_id].clone()
+    }
+
+    #[inline]
+    pub fn flows(&self) -> &[Flow] {
+        &self.flows
+    }
+
+    // reduced cost: cost - potential(from) + potential(to)
+    #[inline]
+    pub fn potential(&self, u: usize) -> Flow {
+        self.potentials[u].clone()
+    }
+
+    #[inline]
+    pub fn potentials(&self) -> &[Flow] {
+        &self.potentials
+    }
+
+    // pivots for the network simplex variants, augmentations or phases for the others
+    #[inline]
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+}
+
+impl<Flow> Solution<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + ToPrimitive,
+{
+    // the objective accumulated in i128, see Graph::minimum_cost_i128
+    pub fn objective_i128(&self) -> i128 {
+        self.costs
+            .iter()
+            .zip(self.flows.iter())
+            .fold(0, |sum, (cost, flow)| sum + to_i128(cost.clone()) * to_i128(flow.clone()))
+    }
+}
@@ -0,0 +1,39 @@
+use crate::ids::NodeId;
+use crate::metrics::Stats;
+use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::max_flow_min_cost;
+use crate::minimum_cost_flow::status::Status;
+use num_traits::NumAssign;
+use std::ops::Neg;
+
+// common interface of the minimum cost flow algorithms, so that they can be swapped behind a trait object
+// (Vec<Box<dyn MinimumCostFlowSolver<Flow>>> for code that runs any of them). the network simplex variants use BlockSearchPivotRule
+pub trait MinimumCostFlowSolver<Flow> {
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status;
+
+    fn name(&self) -> &'static str;
+
+    fn stats(&self) -> &Stats;
+
+    // the cost of the flow in graph after solve
+    fn objective(&self, graph: &Graph<Flow>) -> Flow
+    where
+        Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+    {
+        graph.minimum_cost()
+    }
+
+    // the potentials of the nodes the solver keeps after an optimal solve, None if it keeps none (the graph has them in any case)
+    fn potentials(&self) -> Option<&[Flow]> {
+        None
+    }
+
+    // the value of a maximum flow from source to sink, with a cheapest maximum flow left in graph.
+    // the graph has no supplies, and Status::BadInput is returned otherwise or if source == sink
+    fn max_flow_min_cost(&mut self, source: NodeId, sink: NodeId, graph: &mut Graph<Flow>) -> (Flow, Status)
+    where
+        Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + Default,
+    {
+        max_flow_min_cost::solve(self, source.index(), sink.index(), graph)
+    }
+}
@@ -1,17 +1,22 @@
+use crate::approx::snap;
+use crate::checked::add_costs;
+use crate::flow_ext::FlowExt;
+use crate::index::{check_capacity, IndexType};
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
 use std::ops::Neg;
 
 #[derive(Default, Clone)]
-pub struct Node<Flow> {
-    pub parent: usize,
-    pub parent_edge_id: usize,
+pub struct Node<Flow, I: IndexType = usize> {
+    pub parent: I,
+    pub parent_edge_id: I,
     pub potential: Flow,
 }
 
-#[derive(Default, PartialEq, Debug)]
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
 pub enum EdgeState {
     #[default]
     Lower,
@@ -31,7 +36,7 @@ pub struct InternalEdge<Flow> {
 
 impl<Flow> InternalEdge<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
 {
     pub fn is_feasible(&self) -> bool {
         Flow::zero() <= self.flow && self.flow <= self.upper
@@ -46,68 +51,110 @@ where
     }
 
     pub fn residual_capacity(&self) -> Flow {
-        self.upper - self.flow
+        self.upper.clone() - self.flow.clone()
     }
 
     pub fn opposite_side(&self, u: usize) -> usize {
         debug_assert!(u == self.from || u == self.to);
         u ^ self.to ^ self.from
     }
+
+    // (from, to, cost) of the direction in which the flow of a non-tree edge can move
+    pub(crate) fn residual_direction(&self) -> (usize, usize, Flow) {
+        match self.state {
+            EdgeState::Upper => (self.to, self.from, -self.cost.clone()),
+            _ => (self.from, self.to, self.cost.clone()),
+        }
+    }
 }
 
+// the node arrays are of I, see IndexType. a parent of I::MAX is none
 #[derive(Default)]
-pub struct SpanningTreeStructure<Flow> {
+pub struct SpanningTreeStructure<Flow, I: IndexType = usize> {
     pub(crate) num_nodes: usize,
     pub(crate) num_edges: usize,
     pub(crate) excesses: Vec<Flow>,
+    pub(crate) tolerance: Flow, // of the optimality and feasibility checks, see FlowExt::tolerance
 
-    pub(crate) nodes: Vec<Node<Flow>>,
+    pub(crate) nodes: Vec<Node<Flow, I>>,
     pub(crate) edges: Vec<InternalEdge<Flow>>,
 
     pub(crate) root: usize,
-    pub(crate) next_node_dft: Vec<usize>,       // next nodes in depth-first thread
-    pub(crate) prev_node_dft: Vec<usize>,       // previous nodes in depth-first thread
-    pub(crate) last_descendent_dft: Vec<usize>, // last descendants in depth-first thread
-    pub(crate) num_successors: Vec<usize>,      // the number of successors of the node in the tree
+    pub(crate) next_node_dft: Vec<I>,       // next nodes in depth-first thread
+    pub(crate) prev_node_dft: Vec<I>,       // previous nodes in depth-first thread
+    pub(crate) last_descendent_dft: Vec<I>, // last descendants in depth-first thread
+    pub(crate) num_successors: Vec<I>,      // the number of successors of the node in the tree
 }
 
 #[allow(dead_code)]
-impl<Flow> SpanningTreeStructure<Flow>
+impl<Flow, I> SpanningTreeStructure<Flow, I>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Clone,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone,
+    I: IndexType,
 {
-    pub(crate) fn build(&mut self, graph: &mut Graph<Flow>) {
+    // read access for the pivot rules implemented outside the crate
+    #[inline]
+    pub fn num_edges(&self) -> usize {
+        self.num_edges
+    }
+
+    #[inline]
+    pub fn edge(&self, edge_id: usize) -> &InternalEdge<Flow> {
+        &self.edges[edge_id]
+    }
+
+    #[inline]
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    // the edges are at the bounds the graph starts them at (see Graph::reset_flows): a negative cost edge at Upper, the others at Lower.
+    // panics if the extended network does not fit in I
+    pub(crate) fn build(&mut self, graph: &mut Graph<Flow>)
+    where
+        Flow: FlowExt,
+    {
+        check_capacity::<I>(graph.num_nodes(), graph.num_edges()).unwrap();
         (self.num_nodes, self.num_edges) = (graph.num_nodes(), graph.num_edges());
         self.excesses = graph.excesses.clone();
+        self.tolerance = Flow::tolerance();
 
+        self.edges.clear();
         for edge in graph.edges.iter() {
-            assert!(edge.upper >= Flow::zero() && edge.cost >= Flow::zero());
+            assert!(edge.upper >= Flow::zero());
+            let state = if edge.cost < Flow::zero() { EdgeState::Upper } else { EdgeState::Lower };
             self.edges
-                .push(InternalEdge { from: edge.from, to: edge.to, flow: edge.flow, upper: edge.upper, cost: edge.cost, state: EdgeState::Lower });
+                .push(InternalEdge { from: edge.from, to: edge.to, flow: edge.flow.clone(), upper: edge.upper.clone(), cost: edge.cost.clone(), state });
         }
 
+        // nothing is kept from the last solve, as the dual network simplex only spans the reachable nodes
         self.root = usize::MAX;
-        self.nodes.resize(self.num_nodes, Node { parent: usize::MAX, parent_edge_id: usize::MAX, potential: Flow::zero() });
-        self.next_node_dft.resize(self.num_nodes, usize::MAX);
-        self.prev_node_dft.resize(self.num_nodes, usize::MAX);
-        self.last_descendent_dft.resize(self.num_nodes, usize::MAX);
-        self.num_successors.resize(self.num_nodes, 0);
+        self.nodes.clear();
+        self.next_node_dft.clear();
+        self.prev_node_dft.clear();
+        self.last_descendent_dft.clear();
+        self.num_successors.clear();
+        self.nodes.resize(self.num_nodes, Node { parent: I::none(), parent_edge_id: I::none(), potential: Flow::zero() });
+        self.next_node_dft.resize(self.num_nodes, I::none());
+        self.prev_node_dft.resize(self.num_nodes, I::none());
+        self.last_descendent_dft.resize(self.num_nodes, I::none());
+        self.num_successors.resize(self.num_nodes, I::new(0));
     }
 
     #[inline]
     pub(crate) fn reduced_cost(&self, edge: &InternalEdge<Flow>) -> Flow {
-        edge.cost - self.nodes[edge.from].potential + self.nodes[edge.to].potential
+        edge.cost.clone() - self.nodes[edge.from].potential.clone() + self.nodes[edge.to].potential.clone()
     }
 
     pub(crate) fn update_flow_in_path(&mut self, source: usize, sink: usize, delta: Flow) {
         let mut now = sink;
         while now != source {
-            let (parent, edge_id) = (self.nodes[now].parent, self.nodes[now].parent_edge_id);
+            let (parent, edge_id) = (self.nodes[now].parent.index(), self.nodes[now].parent_edge_id.index());
             let edge = &mut self.edges[edge_id];
-            edge.flow += if edge.from == parent { delta } else { -delta };
+            edge.flow += if edge.from == parent { delta.clone() } else { -delta.clone() };
             now = parent;
         }
-        self.excesses[source] -= delta;
+        self.excesses[source] -= delta.clone();
         self.excesses[sink] += delta;
     }
 
@@ -116,20 +163,20 @@ where
             EdgeState::Upper => -delta,
             _ => delta,
         };
-        self.edges[entering_edge_id].flow += delta;
+        self.edges[entering_edge_id].flow += delta.clone();
 
         let mut now = self.edges[entering_edge_id].from;
         while now != apex {
-            let edge = &mut self.edges[self.nodes[now].parent_edge_id];
-            edge.flow += if now == edge.from { -delta } else { delta };
-            now = self.nodes[now].parent;
+            let edge = &mut self.edges[self.nodes[now].parent_edge_id.index()];
+            edge.flow += if now == edge.from { -delta.clone() } else { delta.clone() };
+            now = self.nodes[now].parent.index();
         }
 
         let mut now = self.edges[entering_edge_id].to;
         while now != apex {
-            let edge = &mut self.edges[self.nodes[now].parent_edge_id];
-            edge.flow += if now == edge.from { delta } else { -delta };
-            now = self.nodes[now].parent;
+            let edge = &mut self.edges[self.nodes[now].parent_edge_id.index()];
+            edge.flow += if now == edge.from { delta.clone() } else { -delta.clone() };
+            now = self.nodes[now].parent.index();
         }
     }
 
@@ -138,42 +185,42 @@ where
     pub(crate) fn re_rooting(&mut self, _now_root: usize, new_root: usize, entering_edge_id: usize) {
         let mut ancestors = Vec::new();
         let mut now = new_root;
-        while now != usize::MAX {
+        while now != I::MAX {
             ancestors.push(now);
-            now = self.nodes[now].parent;
+            now = self.nodes[now].parent.index();
         }
         ancestors.reverse();
 
         for pair in ancestors.windows(2) {
             let (p, q) = (pair[0], pair[1]);
-            let size_p = self.num_successors[p];
-            let last_q = self.last_descendent_dft[q];
+            let size_p = self.num_successors[p].index();
+            let last_q = self.last_descendent_dft[q].index();
 
-            self.nodes[p].parent = q;
-            self.nodes[q].parent = usize::MAX;
+            self.nodes[p].parent = I::new(q);
+            self.nodes[q].parent = I::none();
             self.nodes[p].parent_edge_id = self.nodes[q].parent_edge_id;
-            self.nodes[q].parent_edge_id = usize::MAX;
-            self.num_successors[p] = size_p - self.num_successors[q];
-            self.num_successors[q] = size_p;
-
-            let prev_q = self.prev_node_dft[q];
-            let next_last_q = self.next_node_dft[last_q];
-            self.next_node_dft[prev_q] = next_last_q;
-            self.prev_node_dft[next_last_q] = prev_q;
-            self.next_node_dft[last_q] = q;
-            self.prev_node_dft[q] = last_q;
-
-            let mut last_p = self.last_descendent_dft[p];
+            self.nodes[q].parent_edge_id = I::none();
+            self.num_successors[p] = I::new(size_p - self.num_successors[q].index());
+            self.num_successors[q] = I::new(size_p);
+
+            let prev_q = self.prev_node_dft[q].index();
+            let next_last_q = self.next_node_dft[last_q].index();
+            self.next_node_dft[prev_q] = I::new(next_last_q);
+            self.prev_node_dft[next_last_q] = I::new(prev_q);
+            self.next_node_dft[last_q] = I::new(q);
+            self.prev_node_dft[q] = I::new(last_q);
+
+            let mut last_p = self.last_descendent_dft[p].index();
             if last_p == last_q {
-                self.last_descendent_dft[p] = prev_q;
+                self.last_descendent_dft[p] = I::new(prev_q);
                 last_p = prev_q;
             }
 
-            self.prev_node_dft[p] = last_q;
-            self.next_node_dft[last_q] = p;
-            self.next_node_dft[last_p] = q;
-            self.prev_node_dft[q] = last_p;
-            self.last_descendent_dft[q] = last_p;
+            self.prev_node_dft[p] = I::new(last_q);
+            self.next_node_dft[last_q] = I::new(p);
+            self.next_node_dft[last_p] = I::new(q);
+            self.prev_node_dft[q] = I::new(last_p);
+            self.last_descendent_dft[q] = I::new(last_p);
         }
 
         // update potential
@@ -185,12 +232,12 @@ where
         };
 
         let mut now = new_root;
-        while now != usize::MAX {
-            self.nodes[now].potential += delta;
-            if now == self.last_descendent_dft[new_root] {
+        while now != I::MAX {
+            self.nodes[now].potential += delta.clone();
+            if now == self.last_descendent_dft[new_root].index() {
                 break;
             }
-            now = self.next_node_dft[now];
+            now = self.next_node_dft[now].index();
         }
     }
 
@@ -200,25 +247,25 @@ where
         leaving_edge.state = if leaving_edge.is_lower() { EdgeState::Lower } else { EdgeState::Upper };
 
         // detach sub tree
-        self.nodes[sub_tree_root].parent = usize::MAX;
-        self.nodes[sub_tree_root].parent_edge_id = usize::MAX;
-
-        let prev_t = self.prev_node_dft[sub_tree_root];
-        let last_t = self.last_descendent_dft[sub_tree_root];
-        let next_last_t = self.next_node_dft[last_t];
-        self.next_node_dft[prev_t] = next_last_t;
-        self.prev_node_dft[next_last_t] = prev_t;
-        self.next_node_dft[last_t] = sub_tree_root;
-        self.prev_node_dft[sub_tree_root] = last_t;
-
-        let sub_tree_size = self.num_successors[sub_tree_root];
+        self.nodes[sub_tree_root].parent = I::none();
+        self.nodes[sub_tree_root].parent_edge_id = I::none();
+
+        let prev_t = self.prev_node_dft[sub_tree_root].index();
+        let last_t = self.last_descendent_dft[sub_tree_root].index();
+        let next_last_t = self.next_node_dft[last_t].index();
+        self.next_node_dft[prev_t] = I::new(next_last_t);
+        self.prev_node_dft[next_last_t] = I::new(prev_t);
+        self.next_node_dft[last_t] = I::new(sub_tree_root);
+        self.prev_node_dft[sub_tree_root] = I::new(last_t);
+
+        let sub_tree_size = self.num_successors[sub_tree_root].index();
         let mut now = leaving_edge.opposite_side(sub_tree_root);
-        while now != usize::MAX {
-            self.num_successors[now] -= sub_tree_size;
-            if self.last_descendent_dft[now] == last_t {
-                self.last_descendent_dft[now] = prev_t;
+        while now != I::MAX {
+            self.num_successors[now] = I::new(self.num_successors[now].index() - sub_tree_size);
+            if self.last_descendent_dft[now].index() == last_t {
+                self.last_descendent_dft[now] = I::new(prev_t);
             }
-            now = self.nodes[now].parent;
+            now = self.nodes[now].parent.index();
         }
     }
 
@@ -231,39 +278,44 @@ where
         let (p, q) = (attach_node, sub_tree_root); // p -> q
 
         // attach tree
-        self.nodes[q].parent = p;
-        self.nodes[q].parent_edge_id = entering_edge_id;
-
-        let last_p = self.last_descendent_dft[attach_node];
-        let next_last_p = self.next_node_dft[last_p];
-        let last_q = self.last_descendent_dft[q];
-        self.next_node_dft[last_p] = q;
-        self.prev_node_dft[q] = last_p;
-        self.prev_node_dft[next_last_p] = last_q;
-        self.next_node_dft[last_q] = next_last_p;
-
-        let sub_tree_size = self.num_successors[q];
+        self.nodes[q].parent = I::new(p);
+        self.nodes[q].parent_edge_id = I::new(entering_edge_id);
+
+        let last_p = self.last_descendent_dft[attach_node].index();
+        let next_last_p = self.next_node_dft[last_p].index();
+        let last_q = self.last_descendent_dft[q].index();
+        self.next_node_dft[last_p] = I::new(q);
+        self.prev_node_dft[q] = I::new(last_p);
+        self.prev_node_dft[next_last_p] = I::new(last_q);
+        self.next_node_dft[last_q] = I::new(next_last_p);
+
+        let sub_tree_size = self.num_successors[q].index();
         let mut now = attach_node;
-        while now != usize::MAX {
-            self.num_successors[now] += sub_tree_size;
-            if self.last_descendent_dft[now] == last_p {
-                self.last_descendent_dft[now] = last_q
+        while now != I::MAX {
+            self.num_successors[now] = I::new(self.num_successors[now].index() + sub_tree_size);
+            if self.last_descendent_dft[now].index() == last_p {
+                self.last_descendent_dft[now] = I::new(last_q)
             }
-            now = self.nodes[now].parent;
+            now = self.nodes[now].parent.index();
         }
     }
 
-    // dijkstra
-    pub(crate) fn shortest_path(&self, source: usize) -> (Vec<Flow>, Vec<Option<usize>>) {
+    // dijkstra. Err(Status::Overflow) if the sum of the costs does not fit in Flow.
+    // an edge at Upper (one with a negative cost) is passed from to to from with the cost -cost, so prev_edge_id[u] may leave u at its from
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn shortest_path(&self, source: usize) -> Result<(Vec<Flow>, Vec<Option<usize>>), Status>
+    where
+        Flow: FlowExt,
+    {
         let mut graph = vec![Vec::new(); self.num_nodes];
         let mut total_cost = Flow::zero();
         for (edge_id, edge) in self.edges.iter().enumerate() {
-            graph[edge.from].push(edge_id);
-            assert!(edge.cost >= Flow::zero());
-            total_cost += edge.cost;
+            let (from, _, cost) = edge.residual_direction();
+            graph[from].push(edge_id);
+            total_cost = add_costs(total_cost, cost)?;
         }
 
-        let mut distances = vec![total_cost + Flow::one(); self.num_nodes];
+        let mut distances = vec![add_costs(total_cost, Flow::one())?; self.num_nodes];
         let mut prev_edge_id = vec![None; self.num_nodes];
         let mut seen = vec![false; self.num_nodes];
         let mut bh = BinaryHeap::from([(Reverse(Flow::zero()), source)]);
@@ -276,29 +328,36 @@ where
             seen[u] = true;
 
             for &edge_id in graph[u].iter() {
-                let edge = &self.edges[edge_id];
-                let new_dist = now_dist.0 + edge.cost;
+                let (_, to, cost) = self.edges[edge_id].residual_direction();
+                let new_dist = now_dist.0.clone() + cost;
 
-                if new_dist < distances[edge.to] {
-                    prev_edge_id[edge.to] = Some(edge_id);
-                    distances[edge.to] = new_dist;
-                    bh.push((Reverse(new_dist), edge.to));
+                if new_dist < distances[to] {
+                    prev_edge_id[to] = Some(edge_id);
+                    distances[to] = new_dist.clone();
+                    bh.push((Reverse(new_dist), to));
                 }
             }
         }
 
-        (distances, prev_edge_id)
+        Ok((distances, prev_edge_id))
     }
 
+    // within the tolerance
     pub fn satisfy_constraints(&self) -> bool {
-        self.edges.iter().all(|edge| edge.is_feasible()) && self.excesses.iter().all(|&excess| excess == Flow::zero())
+        let tolerance = &self.tolerance;
+        self.edges.iter().all(|edge| -tolerance.clone() <= edge.flow && edge.flow <= edge.upper.clone() + tolerance.clone())
+            && self.excesses.iter().all(|excess| snap(excess.clone(), tolerance) == Flow::zero())
     }
 
+    // within the tolerance
     pub fn satisfy_optimality_conditions(&self) -> bool {
-        self.edges.iter().all(|edge| match edge.state {
-            EdgeState::Tree => self.reduced_cost(edge) == Flow::zero(),
-            EdgeState::Lower => edge.upper == Flow::zero() || self.reduced_cost(edge) >= Flow::zero(),
-            EdgeState::Upper => edge.upper == Flow::zero() || self.reduced_cost(edge) <= Flow::zero(),
+        self.edges.iter().all(|edge| {
+            let reduced_cost = snap(self.reduced_cost(edge), &self.tolerance);
+            match edge.state {
+                EdgeState::Tree => reduced_cost == Flow::zero(),
+                EdgeState::Lower => edge.upper == Flow::zero() || reduced_cost >= Flow::zero(),
+                EdgeState::Upper => edge.upper == Flow::zero() || reduced_cost <= Flow::zero(),
+            }
         })
     }
 
@@ -307,7 +366,7 @@ where
         let mut now = root;
         loop {
             order.push(now);
-            now = self.next_node_dft[now];
+            now = self.next_node_dft[now].index();
             if now == root {
                 break;
             }
@@ -315,11 +374,12 @@ where
 
         let mut num_successors = vec![1; self.num_nodes];
         for &u in order.iter().rev() {
-            if num_successors[u] != self.num_successors[u] {
+            if num_successors[u] != self.num_successors[u].index() {
                 return false;
             }
-            if self.nodes[u].parent != usize::MAX {
-                num_successors[self.nodes[u].parent] += num_successors[u];
+            let parent = self.nodes[u].parent.index();
+            if parent != I::MAX {
+                num_successors[parent] += num_successors[u];
             }
         }
 
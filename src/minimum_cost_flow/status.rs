@@ -1,9 +1,16 @@
-#[derive(Default, PartialEq, Debug)]
+#[derive(Default, PartialEq, Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Status {
     #[default]
     NotSolved,
     BadInput,
     Unbalanced,
     Infeasible,
+    Unbounded, // the residual network has a negative cycle, which the solver cannot cancel
     Optimal,
+    TimeLimit,
+    Paused,            // a session stopped by Control::Pause, resume goes on
+    Cancelled,         // the cancellation token was cancelled
+    InternalInvariant, // a consistency check failed, see invariant_violation of the solver
+    Overflow,          // the scaled costs or an objective do not fit in Flow
 }
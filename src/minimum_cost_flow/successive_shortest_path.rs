@@ -1,95 +1,501 @@
+use crate::approx::snap;
+use crate::cooperative::{ignore_progress, Progress, WorkCounter, YieldHook};
+use crate::flow_ext::FlowExt;
+use crate::metrics::{Histogram, MetricsSink, SolveMetrics, Stats, StatsLevel, AUGMENTATIONS_TOTAL};
+use crate::minimum_cost_flow::basis::{BasisError, PotentialBundle};
 use crate::minimum_cost_flow::csr::CSR;
+use crate::minimum_cost_flow::flow_result::{commit, FlowResult};
 use crate::minimum_cost_flow::graph::Graph;
+use crate::minimum_cost_flow::solution::Solution;
+use crate::minimum_cost_flow::solver::MinimumCostFlowSolver;
 use crate::minimum_cost_flow::status::Status;
 use num_traits::NumAssign;
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::ops::Neg;
+use std::hash::Hash;
+use std::ops::{ControlFlow, Neg};
+use std::sync::Arc;
+
+// fall back to a full recompute when more than 1 / TREE_REPAIR_FALLBACK_RATIO of the nodes are invalidated
+const TREE_REPAIR_FALLBACK_RATIO: usize = 4;
 
 #[derive(Default)]
 pub struct SuccessiveShortestPath<Flow> {
     csr: CSR<Flow>,
+    tree_repair: bool,
+    tree: ShortestPathTree<Flow>,
+    imported_potentials: Option<Vec<Flow>>,
+    num_heap_operations: usize,
+    num_edge_scans: usize,
+    num_iterations: usize,
+    metrics_sink: Option<Arc<dyn MetricsSink>>,
+    stats: Stats,
+    augmented_units: Flow,
+    stats_level: StatsLevel,
+    path_lengths: Histogram,
+    work_counter: WorkCounter,
+    source: usize,                    // the node whose excess is being sent
+    dijkstra: Option<Dijkstra<Flow>>, // paused in the middle of a search
+    is_tree_started: bool,
+    remaining_excess: Flow, // the positive excesses not sent yet
+    is_stopped: bool,       // by the progress callback
+}
+
+// a solve that returns Status::Paused when the hook of the work counter returns Control::Pause, and goes on with resume.
+// the flows are written to the graph when it finishes
+pub struct SuccessiveShortestPathSession<'a, Flow> {
+    solver: &'a mut SuccessiveShortestPath<Flow>,
+    graph: &'a mut Graph<Flow>,
+    status: Status,
+}
+
+struct Dijkstra<Flow> {
+    prev: Vec<Option<usize>>,
+    dist: Vec<Option<Flow>>,
+    visited: Vec<bool>,
+    heap: BinaryHeap<(Reverse<Flow>, usize)>,
+}
+
+enum Search {
+    Sink(usize),
+    Exhausted,
+    Paused,
+}
+
+// shortest path tree kept between augmentations from the same source.
+// label[u] is the distance from the source plus a shift that grows with each augmentation,
+// so the entries left in the heap stay valid after the potentials are updated.
+#[derive(Default)]
+struct ShortestPathTree<Flow> {
+    label: Vec<Option<Flow>>,
+    prev: Vec<Option<usize>>,
+    settled: Vec<bool>,
+    invalid: Vec<bool>,
+    settled_nodes: Vec<usize>, // in the order of settlement, so parents come before children
+    reached_nodes: Vec<usize>,
+    heap: BinaryHeap<(Reverse<Flow>, usize)>,
 }
 
 impl<Flow> SuccessiveShortestPath<Flow>
 where
-    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
 {
+    // reuse the shortest path tree of the previous augmentation and repair it locally
+    pub fn set_tree_repair(&mut self, tree_repair: bool) {
+        self.tree_repair = tree_repair;
+    }
+
+    pub fn num_heap_operations(&self) -> usize {
+        self.num_heap_operations
+    }
+
+    // the edges examined by the dijkstra searches of the last solve
+    pub fn num_edge_scans(&self) -> usize {
+        self.num_edge_scans
+    }
+
+    // on by default, the searches skip the edges without residual capacity 64 at a time instead of examining each of them
+    pub fn set_skip_empty_edges(&mut self, enabled: bool) {
+        self.csr.scan_empty_edges = !enabled;
+    }
+
+    pub fn num_augmentations(&self) -> usize {
+        self.num_iterations
+    }
+
+    pub fn set_stats_level(&mut self, stats_level: StatsLevel) {
+        self.stats_level = stats_level;
+    }
+
+    // the flow sent by the augmentations of the last solve
+    pub fn augmented_units(&self) -> Flow {
+        self.augmented_units.clone()
+    }
+
+    // the number of edges of each augmenting path, recorded with StatsLevel::Histograms
+    pub fn path_length_histogram(&self) -> Vec<(u64, u64)> {
+        self.path_lengths.buckets()
+    }
+
+    // the next solve starts from the potentials of bundle instead of those of the previous solve.
+    // the residual edges of negative reduced cost under them are saturated first, and the excesses this leaves are sent as supplies
+    pub fn import_potentials(&mut self, graph: &Graph<Flow>, bundle: &PotentialBundle<Flow>) -> Result<(), BasisError>
+    where
+        Flow: Hash,
+    {
+        bundle.validate(graph)?;
+        self.imported_potentials = Some(bundle.potentials.clone());
+        Ok(())
+    }
+
+    // whether the last solve started from the potentials of the previous one or the imported ones
+    pub fn are_potentials_preserved(&self) -> bool {
+        self.csr.are_potentials_preserved
+    }
+
+    pub fn set_metrics_sink(&mut self, sink: Arc<dyn MetricsSink>) {
+        self.metrics_sink = Some(sink);
+    }
+
+    // hook is called every granularity heap pops. solve goes on after Control::Pause, which only stops a session
+    pub fn set_work_counter(&mut self, granularity: u64, hook: YieldHook) {
+        self.work_counter = WorkCounter::new(granularity, hook);
+    }
+
+    // the units of work of the last solve, counted while a work counter is set
+    pub fn work_count(&self) -> u64 {
+        self.work_counter.count()
+    }
+
+    // the balance policy of graph is not applied, and an unbalanced graph finishes with Status::Unbalanced
+    pub fn session<'a>(&'a mut self, graph: &'a mut Graph<Flow>) -> SuccessiveShortestPathSession<'a, Flow> {
+        let status = match self.start(graph) {
+            Ok(()) => Status::Paused,
+            Err(result) => result.status(),
+        };
+        SuccessiveShortestPathSession { solver: self, graph, status }
+    }
+
     pub fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
-        if graph.is_unbalance() {
-            return Status::Unbalanced;
-        }
-        self.csr.build(graph);
-
-        for s in 0..self.csr.num_nodes {
-            while self.csr.excesses[s] > Flow::zero() {
-                match self.calculate_distance(s) {
-                    Some((t, visited, dist, prev)) => {
-                        // update potentials
-                        for u in 0..self.csr.num_nodes {
-                            if visited[u] {
-                                self.csr.potentials[u] = self.csr.potentials[u] - dist[u].unwrap() + dist[t].unwrap();
-                            }
-                        }
-                        // update flow
-                        self.update_flow(s, t, prev);
-                    }
-                    None => break,
-                }
+        self.solve_with_progress(graph, ignore_progress)
+    }
+
+    // progress is called after each augmentation, and ControlFlow::Break writes the flow so far (the excesses not sent stay on their nodes)
+    // to the graph with Status::Cancelled
+    pub fn solve_with_progress(&mut self, graph: &mut Graph<Flow>, mut progress: impl FnMut(Progress<Flow>) -> ControlFlow<()>) -> Status {
+        graph.solve_balanced(|graph| {
+            let result = self.solve_ref_with_progress(graph, &mut progress);
+            commit(graph, &result);
+            result.status()
+        })
+    }
+
+    // the graph is only read, and the flows are written by flow_result::commit.
+    // the balance policy of graph is applied by solve, not here
+    pub fn solve_ref(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        self.solve_ref_with_progress(graph, &mut ignore_progress)
+    }
+
+    fn solve_ref_with_progress(&mut self, graph: &Graph<Flow>, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> FlowResult<Flow> {
+        let metrics = SolveMetrics::start(self.metrics_sink.as_ref(), "successive_shortest_path");
+        self.stats = Stats::default();
+        let result = self.solve_inner(graph, progress);
+        self.stats.num_augmentations = self.num_iterations;
+        if let Some(metrics) = metrics {
+            metrics.finish(result.status(), &[(AUGMENTATIONS_TOTAL, self.num_iterations)]);
+        }
+        result
+    }
+
+    fn solve_inner(&mut self, graph: &Graph<Flow>, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> FlowResult<Flow> {
+        if let Err(result) = self.start(graph) {
+            return result;
+        }
+        while !self.run(progress) {
+            if self.is_stopped {
+                return FlowResult::from_csr(Status::Cancelled, &self.csr, graph.num_nodes(), graph.num_edges());
             }
+            self.work_counter.resume();
+        }
+        self.finish(graph)
+    }
+
+    fn start(&mut self, graph: &Graph<Flow>) -> Result<(), FlowResult<Flow>> {
+        (self.num_heap_operations, self.num_edge_scans, self.num_iterations) = (0, 0, 0);
+        self.augmented_units = Flow::zero();
+        self.path_lengths.clear();
+        self.work_counter.start();
+        (self.source, self.dijkstra, self.is_tree_started, self.is_stopped) = (0, None, false, false);
+
+        if graph.is_unbalance() {
+            return Err(FlowResult::without_flows(Status::Unbalanced));
+        }
+        // dijkstra needs potentials under which no residual edge has a negative reduced cost.
+        // they are computed by bellman-ford if the flow of graph leaves negative cost residual edges
+        let imported_potentials = self.imported_potentials.take().filter(|potentials| potentials.len() == graph.num_nodes());
+        if let Some(potentials) = imported_potentials {
+            self.csr.build_with_potentials(graph, potentials);
+        } else if !self.csr.build_preserving_potentials(graph) {
+            return Err(FlowResult::without_flows(Status::Unbounded));
         }
+        self.remaining_excess = self.csr.excesses.iter().filter(|excess| **excess > Flow::zero()).fold(Flow::zero(), |sum, excess| sum + excess.clone());
+        Ok(())
+    }
 
-        self.csr.set_flow(graph);
+    // return false if the work counter is paused or the progress callback stopped the solve
+    fn run(&mut self, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> bool {
+        while self.source < self.csr.num_nodes {
+            let s = self.source;
+            let finished = if self.tree_repair {
+                self.solve_with_tree_repair(s, progress)
+            } else {
+                self.solve_from(s, progress)
+            };
+            if !finished {
+                return false;
+            }
+            self.source += 1;
+        }
+        true
+    }
 
-        if self.csr.excesses.iter().all(|&e| e == Flow::zero()) {
+    fn finish(&mut self, graph: &Graph<Flow>) -> FlowResult<Flow> {
+        let status = if self.csr.excesses.iter().all(|e| snap(e.clone(), &self.csr.tolerance) == Flow::zero()) {
             Status::Optimal
         } else {
             Status::Infeasible
+        };
+        FlowResult::from_csr(status, &self.csr, graph.num_nodes(), graph.num_edges())
+    }
+
+    // send the excess of s along shortest paths
+    fn solve_from(&mut self, s: usize, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> bool {
+        while self.csr.excesses[s] > Flow::zero() {
+            let mut dijkstra = self.dijkstra.take().unwrap_or_else(|| {
+                self.num_heap_operations += 1;
+                self.stats.num_dijkstra_runs += 1;
+                Dijkstra::new(self.csr.num_nodes, s)
+            });
+            match dijkstra.search(&self.csr, &mut self.num_heap_operations, &mut self.num_edge_scans, &self.work_counter) {
+                Search::Sink(t) => {
+                    // update potentials
+                    let dist_t = dijkstra.dist[t].clone().unwrap();
+                    for u in 0..self.csr.num_nodes {
+                        if dijkstra.visited[u] {
+                            self.csr.potentials[u] = self.csr.potentials[u].clone() - dijkstra.dist[u].clone().unwrap() + dist_t.clone();
+                        }
+                    }
+                    // update flow
+                    self.update_flow(s, t, &dijkstra.prev);
+                    if self.report(progress) {
+                        return false;
+                    }
+                }
+                Search::Exhausted => break,
+                Search::Paused => {
+                    self.dijkstra = Some(dijkstra);
+                    return false;
+                }
+            }
         }
+        true
+    }
+
+    pub fn solve_with_solution(&mut self, graph: &mut Graph<Flow>) -> Result<Solution<Flow>, Status> {
+        let status = self.solve(graph);
+        Solution::from_status(status, graph, self.num_iterations)
     }
 
+    #[allow(clippy::type_complexity)]
     pub fn calculate_distance(&mut self, s: usize) -> Option<(usize, Vec<bool>, Vec<Option<Flow>>, Vec<Option<usize>>)> {
-        let mut prev = vec![None; self.csr.num_nodes];
-        let mut bh = BinaryHeap::new();
-        let mut dist: Vec<Option<Flow>> = vec![None; self.csr.num_nodes];
-        let mut visited = vec![false; self.csr.num_nodes];
+        let mut dijkstra = Dijkstra::new(self.csr.num_nodes, s);
+        self.num_heap_operations += 1;
+        match dijkstra.search(&self.csr, &mut self.num_heap_operations, &mut self.num_edge_scans, &WorkCounter::default()) {
+            Search::Sink(t) => Some((t, dijkstra.visited, dijkstra.dist, dijkstra.prev)),
+            _ => None,
+        }
+    }
 
-        bh.push((Reverse(Flow::zero()), s));
-        dist[s] = Some(Flow::zero());
+    // return true if the progress callback stops the solve
+    fn report(&mut self, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> bool {
+        self.is_stopped = progress(Progress::SuccessiveShortestPath { remaining_excess: self.remaining_excess.clone(), num_augmentations: self.num_iterations }).is_break();
+        self.is_stopped
+    }
+
+    fn solve_with_tree_repair(&mut self, s: usize, progress: &mut dyn FnMut(Progress<Flow>) -> ControlFlow<()>) -> bool {
+        if self.csr.excesses[s] <= Flow::zero() {
+            return true;
+        }
+        if !self.is_tree_started {
+            self.reset_tree(s);
+            self.is_tree_started = true;
+        }
+        while self.csr.excesses[s] > Flow::zero() {
+            let t = match self.find_sink_with_tree() {
+                Search::Sink(t) => Some(t),
+                Search::Exhausted => None,
+                Search::Paused => return false,
+            };
+            debug_assert!(self.is_same_distance_as_plain(s, t));
+            let Some(t) = t else {
+                break;
+            };
+
+            // update potentials
+            let label_t = self.tree.label[t].clone().unwrap();
+            for &u in self.tree.settled_nodes.iter() {
+                self.csr.potentials[u] = self.csr.potentials[u].clone() - self.tree.label[u].clone().unwrap() + label_t.clone();
+                self.tree.label[u] = Some(label_t.clone());
+            }
 
-        while let Some((d, u)) = bh.pop() {
-            if visited[u] {
+            // update flow
+            let prev = std::mem::take(&mut self.tree.prev);
+            self.update_flow(s, t, &prev);
+            self.tree.prev = prev;
+            if self.report(progress) {
+                return false;
+            }
+
+            if self.csr.excesses[s] > Flow::zero() {
+                self.repair_tree(s);
+            }
+        }
+        self.is_tree_started = false;
+        true
+    }
+
+    fn reset_tree(&mut self, s: usize) {
+        let n = self.csr.num_nodes;
+        let tree = &mut self.tree;
+        tree.label = vec![None; n];
+        tree.prev = vec![None; n];
+        tree.settled = vec![false; n];
+        tree.invalid = vec![false; n];
+        tree.settled_nodes.clear();
+        tree.reached_nodes.clear();
+        tree.heap.clear();
+
+        tree.label[s] = Some(Flow::zero());
+        tree.reached_nodes.push(s);
+        tree.heap.push((Reverse(Flow::zero()), s));
+        self.num_heap_operations += 1;
+        self.stats.num_dijkstra_runs += 1;
+    }
+
+    // continue dijkstra from the state of the heap
+    fn find_sink_with_tree(&mut self) -> Search {
+        while let Some((Reverse(d), u)) = self.tree.heap.pop() {
+            if self.work_counter.tick(1) {
+                self.tree.heap.push((Reverse(d), u));
+                return Search::Paused;
+            }
+            self.num_heap_operations += 1;
+            if self.tree.settled[u] || self.tree.label[u].as_ref() != Some(&d) {
                 continue;
             }
-            visited[u] = true;
 
+            // the sink stays in the heap, so that it is settled and scanned once its deficit is gone
             if self.csr.excesses[u] < Flow::zero() {
-                return Some((u, visited, dist, prev));
+                self.tree.heap.push((Reverse(d), u));
+                self.num_heap_operations += 1;
+                return Search::Sink(u);
             }
 
-            for edge_id in self.csr.start[u]..self.csr.start[u + 1] {
+            self.tree.settled[u] = true;
+            self.tree.settled_nodes.push(u);
+
+            let mut next = self.csr.start[u];
+            while let Some(edge_id) = self.csr.next_residual_edge(next, self.csr.start[u + 1]) {
+                next = edge_id + 1;
+                self.num_edge_scans += 1;
                 let edge = &self.csr.inside_edge_list[edge_id];
-                if edge.residual_capacity() == Flow::zero() {
+                if edge.residual_capacity() == Flow::zero() || self.tree.settled[edge.to] {
+                    continue;
+                }
+
+                let new_label = d.clone() + self.csr.dijkstra_reduced_cost(u, edge);
+                if self.tree.label[edge.to].as_ref().is_none_or(|label| *label > new_label) {
+                    if self.tree.label[edge.to].is_none() {
+                        self.tree.reached_nodes.push(edge.to);
+                    }
+                    self.tree.label[edge.to] = Some(new_label.clone());
+                    self.tree.prev[edge.to] = Some(edge_id);
+                    self.tree.heap.push((Reverse(new_label), edge.to));
+                    self.num_heap_operations += 1;
+                }
+            }
+        }
+
+        Search::Exhausted
+    }
+
+    // after an augmentation, the tree edges are still tight except the saturated ones.
+    // the subtrees below the saturated edges are invalidated and their boundary is pushed to the heap again.
+    fn repair_tree(&mut self, s: usize) {
+        let csr = &self.csr;
+        let tree = &mut self.tree;
+        let is_broken = |invalid: &[bool], edge_id: usize| csr.inside_edge_list[edge_id].residual_capacity() == Flow::zero() || invalid[csr.inside_edge_list[csr.inside_edge_list[edge_id].rev].to];
+
+        let mut num_invalid = 0;
+        for &v in tree.settled_nodes.iter() {
+            if tree.prev[v].is_some_and(|edge_id| is_broken(&tree.invalid, edge_id)) {
+                tree.invalid[v] = true;
+                num_invalid += 1;
+            }
+        }
+
+        if num_invalid * TREE_REPAIR_FALLBACK_RATIO > csr.num_nodes {
+            self.reset_tree(s);
+            return;
+        }
+
+        self.stats.num_dijkstra_runs += 1;
+
+        // invalidated nodes and the nodes labeled through them (including the sink)
+        let mut detached = Vec::new();
+        for &v in tree.reached_nodes.iter() {
+            if tree.invalid[v] || (!tree.settled[v] && tree.prev[v].is_some_and(|edge_id| is_broken(&tree.invalid, edge_id))) {
+                detached.push(v);
+            }
+        }
+        for &v in detached.iter() {
+            tree.label[v] = None;
+            tree.prev[v] = None;
+            tree.settled[v] = false;
+        }
+        tree.settled_nodes.retain(|&v| !tree.invalid[v]);
+        tree.reached_nodes.retain(|&v| tree.label[v].is_some());
+        for &v in detached.iter() {
+            tree.invalid[v] = false;
+        }
+
+        // best label from the remaining settled nodes
+        for &v in detached.iter() {
+            for edge_id in csr.start[v]..csr.start[v + 1] {
+                let (w, rev) = (csr.inside_edge_list[edge_id].to, csr.inside_edge_list[edge_id].rev);
+                let edge = &csr.inside_edge_list[rev];
+                if !tree.settled[w] || edge.residual_capacity() == Flow::zero() {
                     continue;
                 }
 
-                let new_dist = d.0 + self.csr.reduced_cost(u, edge);
-                if dist[edge.to].is_none() || dist[edge.to].unwrap() > new_dist {
-                    dist[edge.to] = Some(new_dist);
-                    prev[edge.to] = Some(edge_id);
-                    bh.push((Reverse(new_dist), edge.to));
+                let new_label = tree.label[w].clone().unwrap() + csr.dijkstra_reduced_cost(w, edge);
+                if tree.label[v].as_ref().is_none_or(|label| *label > new_label) {
+                    tree.label[v] = Some(new_label);
+                    tree.prev[v] = Some(rev);
                 }
             }
+            if let Some(label) = tree.label[v].clone() {
+                tree.reached_nodes.push(v);
+                tree.heap.push((Reverse(label), v));
+                self.num_heap_operations += 1;
+            }
         }
+    }
 
-        None
+    // validate the repaired tree against the plain dijkstra
+    #[cfg(debug_assertions)]
+    fn is_same_distance_as_plain(&mut self, s: usize, t: Option<usize>) -> bool {
+        let num_heap_operations = self.num_heap_operations;
+        let plain = self.calculate_distance(s).map(|(t, _, dist, _)| dist[t].clone().unwrap());
+        self.num_heap_operations = num_heap_operations;
+        // the labels are summed in another order, so they agree within the tolerance
+        match (plain, t.map(|t| self.tree.label[t].clone().unwrap() - self.tree.label[s].clone().unwrap())) {
+            (Some(plain), Some(label)) => snap(plain - label, &self.csr.tolerance) == Flow::zero(),
+            (plain, label) => plain.is_none() && label.is_none(),
+        }
     }
 
-    fn update_flow(&mut self, s: usize, t: usize, prev: Vec<Option<usize>>) {
+    #[cfg(not(debug_assertions))]
+    fn is_same_distance_as_plain(&mut self, _s: usize, _t: Option<usize>) -> bool {
+        true
+    }
+
+    fn update_flow(&mut self, s: usize, t: usize, prev: &[Option<usize>]) {
+        self.num_iterations += 1;
         debug_assert!(self.csr.excesses[s] > Flow::zero() && self.csr.excesses[t] < Flow::zero());
 
         // calculate delta
-        let mut delta = self.csr.excesses[s].min(-self.csr.excesses[t]);
+        let mut delta = self.csr.excesses[s].clone().min(-self.csr.excesses[t].clone());
         {
             let mut v = t;
             while let Some(edge_idx) = prev[v] {
@@ -97,7 +503,7 @@ where
                 let rev = self.csr.inside_edge_list[edge_idx].rev;
                 v = self.csr.inside_edge_list[rev].to;
             }
-            delta = delta.min(self.csr.excesses[v]);
+            delta = delta.min(self.csr.excesses[v].clone());
             debug_assert_eq!(s, v);
             debug_assert!(delta > Flow::zero());
         }
@@ -105,17 +511,114 @@ where
         // update flow
         {
             let mut v = t;
+            let mut path_length = 0;
             while let Some(edge_idx) = prev[v] {
                 // push
                 let rev = self.csr.inside_edge_list[edge_idx].rev;
-                self.csr.inside_edge_list[edge_idx].flow += delta;
-                self.csr.inside_edge_list[rev].flow -= delta;
+                self.csr.move_flow(edge_idx, delta.clone());
                 v = self.csr.inside_edge_list[rev].to;
+                path_length += 1;
             }
             debug_assert_eq!(s, v);
+            if self.stats_level == StatsLevel::Histograms {
+                self.path_lengths.observe(path_length);
+            }
         }
 
-        self.csr.excesses[t] += delta;
-        self.csr.excesses[s] -= delta;
+        self.csr.excesses[t] += delta.clone();
+        self.csr.excesses[s] -= delta.clone();
+        self.remaining_excess -= delta.clone();
+        self.augmented_units += delta;
+    }
+}
+
+impl<Flow> Dijkstra<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    fn new(num_nodes: usize, s: usize) -> Self {
+        let mut dist = vec![None; num_nodes];
+        dist[s] = Some(Flow::zero());
+        Self { prev: vec![None; num_nodes], dist, visited: vec![false; num_nodes], heap: BinaryHeap::from([(Reverse(Flow::zero()), s)]) }
+    }
+
+    // until a node with a deficit is visited. a pause puts the popped entry back, so that the search goes on as if it had not stopped
+    fn search(&mut self, csr: &CSR<Flow>, num_heap_operations: &mut usize, num_edge_scans: &mut usize, work_counter: &WorkCounter) -> Search {
+        while let Some((d, u)) = self.heap.pop() {
+            if work_counter.tick(1) {
+                self.heap.push((d, u));
+                return Search::Paused;
+            }
+            *num_heap_operations += 1;
+            if self.visited[u] {
+                continue;
+            }
+            self.visited[u] = true;
+
+            if csr.excesses[u] < Flow::zero() {
+                return Search::Sink(u);
+            }
+
+            let mut next = csr.start[u];
+            while let Some(edge_id) = csr.next_residual_edge(next, csr.start[u + 1]) {
+                next = edge_id + 1;
+                *num_edge_scans += 1;
+                let edge = &csr.inside_edge_list[edge_id];
+                if edge.residual_capacity() == Flow::zero() {
+                    continue;
+                }
+
+                let new_dist = d.0.clone() + csr.dijkstra_reduced_cost(u, edge);
+                if self.dist[edge.to].as_ref().is_none_or(|dist| *dist > new_dist) {
+                    self.dist[edge.to] = Some(new_dist.clone());
+                    self.prev[edge.to] = Some(edge_id);
+                    self.heap.push((Reverse(new_dist), edge.to));
+                    *num_heap_operations += 1;
+                }
+            }
+        }
+
+        Search::Exhausted
+    }
+}
+
+impl<Flow> SuccessiveShortestPathSession<'_, Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    // Status::Paused if the hook paused it again, and the final status otherwise
+    pub fn resume(&mut self) -> Status {
+        if self.status != Status::Paused {
+            return self.status;
+        }
+        self.solver.work_counter.resume();
+        if !self.solver.run(&mut ignore_progress) {
+            return Status::Paused;
+        }
+        let result = self.solver.finish(self.graph);
+        commit(self.graph, &result);
+        self.status = result.status();
+        self.status
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+}
+
+impl<Flow> MinimumCostFlowSolver<Flow> for SuccessiveShortestPath<Flow>
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Clone + FlowExt,
+{
+    fn solve(&mut self, graph: &mut Graph<Flow>) -> Status {
+        self.solve(graph)
+    }
+
+    fn name(&self) -> &'static str {
+        "successive_shortest_path"
+    }
+
+    fn stats(&self) -> &Stats {
+        &self.stats
     }
 }
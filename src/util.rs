@@ -0,0 +1 @@
+pub mod union_find;
@@ -0,0 +1,173 @@
+// disjoint sets with path halving and union by size, almost constant amortized time per operation
+#[derive(Default, Clone, Debug)]
+pub struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>, // of the set of a root
+    num_components: usize,
+}
+
+impl UnionFind {
+    pub fn new(num_elements: usize) -> Self {
+        Self { parent: (0..num_elements).collect(), size: vec![1; num_elements], num_components: num_elements }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    #[inline]
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    pub fn find(&mut self, mut u: usize) -> usize {
+        while self.parent[u] != u {
+            self.parent[u] = self.parent[self.parent[u]];
+            u = self.parent[u];
+        }
+        u
+    }
+
+    // false if u and v are already in the same set
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        let (mut root_u, mut root_v) = (self.find(u), self.find(v));
+        if root_u == root_v {
+            return false;
+        }
+        if self.size[root_u] < self.size[root_v] {
+            (root_u, root_v) = (root_v, root_u);
+        }
+        self.parent[root_v] = root_u;
+        self.size[root_u] += self.size[root_v];
+        self.num_components -= 1;
+        true
+    }
+
+    pub fn same(&mut self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    pub fn component_size(&mut self, u: usize) -> usize {
+        let root = self.find(u);
+        self.size[root]
+    }
+
+    // the sets in the order of their smallest elements, each in increasing order
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let roots: Vec<usize> = (0..self.len()).map(|u| self.find(u)).collect();
+        group(&roots)
+    }
+}
+
+// the state of a RollbackUnionFind that rollback_to returns to
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Snapshot(usize);
+
+// union by size without path compression, so that the unions can be undone in reverse order. O(log n) per find
+#[derive(Default, Clone, Debug)]
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    num_components: usize,
+    history: Vec<(usize, usize)>, // (the root attached, the root it is attached to) of each union that merged two sets
+}
+
+impl RollbackUnionFind {
+    pub fn new(num_elements: usize) -> Self {
+        Self { parent: (0..num_elements).collect(), size: vec![1; num_elements], num_components: num_elements, history: Vec::new() }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    #[inline]
+    pub fn num_components(&self) -> usize {
+        self.num_components
+    }
+
+    pub fn find(&self, mut u: usize) -> usize {
+        while self.parent[u] != u {
+            u = self.parent[u];
+        }
+        u
+    }
+
+    // false if u and v are already in the same set, in which case nothing is recorded
+    pub fn union(&mut self, u: usize, v: usize) -> bool {
+        let (mut root_u, mut root_v) = (self.find(u), self.find(v));
+        if root_u == root_v {
+            return false;
+        }
+        if self.size[root_u] < self.size[root_v] {
+            (root_u, root_v) = (root_v, root_u);
+        }
+        self.parent[root_v] = root_u;
+        self.size[root_u] += self.size[root_v];
+        self.num_components -= 1;
+        self.history.push((root_v, root_u));
+        true
+    }
+
+    // undo the last union that merged two sets, false if there is none
+    pub fn undo(&mut self) -> bool {
+        let Some((root_v, root_u)) = self.history.pop() else {
+            return false;
+        };
+        self.parent[root_v] = root_v;
+        self.size[root_u] -= self.size[root_v];
+        self.num_components += 1;
+        true
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot(self.history.len())
+    }
+
+    // undo the unions made since snapshot was taken. a snapshot taken after a rollback to an earlier one is invalid
+    pub fn rollback_to(&mut self, snapshot: Snapshot) {
+        assert!(snapshot.0 <= self.history.len(), "the snapshot is older than a rollback");
+        while self.history.len() > snapshot.0 {
+            self.undo();
+        }
+    }
+
+    pub fn same(&self, u: usize, v: usize) -> bool {
+        self.find(u) == self.find(v)
+    }
+
+    pub fn component_size(&self, u: usize) -> usize {
+        self.size[self.find(u)]
+    }
+
+    // the sets in the order of their smallest elements, each in increasing order
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let roots: Vec<usize> = (0..self.len()).map(|u| self.find(u)).collect();
+        group(&roots)
+    }
+}
+
+fn group(roots: &[usize]) -> Vec<Vec<usize>> {
+    let mut index = vec![usize::MAX; roots.len()];
+    let mut components: Vec<Vec<usize>> = Vec::new();
+    for (u, &root) in roots.iter().enumerate() {
+        if index[root] == usize::MAX {
+            index[root] = components.len();
+            components.push(Vec::new());
+        }
+        components[index[root]].push(u);
+    }
+    components
+}
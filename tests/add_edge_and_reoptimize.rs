@@ -0,0 +1,117 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+// (from, to, lower, upper, cost) and the supplies
+fn build(edges: &[(usize, usize, i64, i64, i64)], supplies: &[i64]) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(supplies.len());
+    for (u, &supply) in supplies.iter().enumerate() {
+        graph.add_supply(u, supply);
+    }
+    for &(from, to, lower, upper, cost) in edges.iter() {
+        graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+    }
+    graph
+}
+
+fn solve(graph: &mut Graph<i64>) -> (PrimalNetworkSimplex<i64>, Status) {
+    let mut solver = PrimalNetworkSimplex::default();
+    let status = solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph);
+    (solver, status)
+}
+
+fn assert_feasible(graph: &Graph<i64>) {
+    let mut net_outflows = vec![0; graph.num_nodes()];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+        net_outflows[edge.from] += edge.flow;
+        net_outflows[edge.to] -= edge.flow;
+    }
+    assert!((0..graph.num_nodes()).all(|u| graph.excess(u) == 0 && net_outflows[u] == graph.supply(u)));
+}
+
+#[test]
+fn shortcut() {
+    // 3 units from 0 to 2 through 1 for 2 each, then a shortcut for 1 that takes 2 of them
+    let mut graph = build(&[(0, 1, 0, 5, 1), (1, 2, 0, 5, 1)], &[3, 0, -3]);
+    let (mut solver, status) = solve(&mut graph);
+    assert_eq!((status, graph.minimum_cost()), (Status::Optimal, 6));
+
+    assert_eq!(solver.add_edge_and_reoptimize(&mut graph, 0, 2, 0, 2, 1), Status::Optimal);
+    assert_eq!(graph.num_edges(), 3);
+    assert_eq!(graph.get_edge(2).unwrap().flow, 2);
+    assert_eq!(graph.minimum_cost(), 2 + 2);
+    assert_eq!(solver.num_pivots(), 1);
+    assert_feasible(&graph);
+
+    // a negative cycle back through 1
+    assert_eq!(solver.add_edge_and_reoptimize(&mut graph, 2, 0, 0, 4, -3), Status::Optimal);
+    assert_eq!(graph.get_edge(3).unwrap().flow, 4);
+    assert_eq!(graph.minimum_cost(), 5 + 5 + 2 - 12);
+    assert_feasible(&graph);
+}
+
+#[test]
+fn bad_input_and_solves_from_scratch() {
+    let mut graph = build(&[(0, 1, 0, 5, 1)], &[3, -3]);
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.add_edge_and_reoptimize(&mut graph, 0, 2, 0, 2, 1), Status::BadInput);
+    assert_eq!(graph.num_edges(), 1);
+
+    // not solved yet
+    assert_eq!(solver.add_edge_and_reoptimize(&mut graph, 0, 1, 0, 2, 0), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 1);
+
+    // a lower bound, and a supply changed after the solve
+    assert_eq!(solver.add_edge_and_reoptimize(&mut graph, 0, 1, 1, 2, 5), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 5);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    assert_eq!(solver.add_edge_and_reoptimize(&mut graph, 1, 0, 0, 2, 0), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 5 + 1);
+    assert_feasible(&graph);
+}
+
+#[test]
+fn random_edges_match_solves_from_scratch() {
+    let mut rng = XorShift::new(779);
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 15) as usize, rng.gen_range(1, 40) as usize);
+        let random_edge = |rng: &mut XorShift| {
+            let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, 3) } else { 0 };
+            (rng.gen_index(num_nodes), rng.gen_index(num_nodes), lower, lower + rng.gen_range(0, 10), rng.gen_range(-10, 10))
+        };
+        let mut edges: Vec<_> = (0..num_edges).map(|_| random_edge(&mut rng)).collect();
+        // a cycle through all the nodes keeps the supplies feasible
+        edges.extend((0..num_nodes).map(|u| (u, (u + 1) % num_nodes, 0, 100, 20)));
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..num_nodes {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+
+        let mut graph = build(&edges, &supplies);
+        let (mut solver, status) = solve(&mut graph);
+        assert_eq!(status, Status::Optimal);
+        for _ in 0..5 {
+            let (from, to, lower, upper, cost) = random_edge(&mut rng);
+            let edge_id = graph.clone().add_directed_edge(from, to, lower, upper, cost).unwrap();
+            assert_eq!(solver.add_edge_and_reoptimize(&mut graph, from, to, lower, upper, cost), Status::Optimal);
+            assert_eq!(graph.num_edges(), edge_id.index() + 1);
+            edges.push((from, to, lower, upper, cost));
+
+            let mut expected = build(&edges, &supplies);
+            assert_eq!(SuccessiveShortestPath::default().solve(&mut expected), Status::Optimal);
+            assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+            assert_feasible(&graph);
+        }
+    }
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::analysis::*;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+struct Instance {
+    supplies: Vec<i64>,
+    edges: Vec<(usize, usize, i64, i64, i64)>, // from, to, lower, upper, cost
+}
+
+impl Instance {
+    fn random(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> Self {
+        let edges = (0..num_edges)
+            .map(|_| {
+                let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(-3, 3) } else { 0 };
+                (rng.gen_index(num_nodes), rng.gen_index(num_nodes), lower, lower + rng.gen_range(0, 10), rng.gen_range(-5, 10))
+            })
+            .collect();
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..num_nodes {
+            let amount = rng.gen_range(0, 8);
+            supplies[rng.gen_index(num_nodes)] += amount;
+            supplies[rng.gen_index(num_nodes)] -= amount;
+        }
+        Self { supplies, edges }
+    }
+
+    // the edge perturbed_edge_id gets the bounds given
+    fn graph(&self, perturbed: Option<(usize, i64, i64)>) -> Graph<i64> {
+        let mut graph = Graph::default();
+        graph.add_nodes(self.supplies.len());
+        for (u, &supply) in self.supplies.iter().enumerate() {
+            graph.add_supply(u, supply);
+        }
+        for (edge_id, &(from, to, lower, upper, cost)) in self.edges.iter().enumerate() {
+            let (lower, upper) = match perturbed {
+                Some((perturbed_edge_id, lower, upper)) if perturbed_edge_id == edge_id => (lower, upper),
+                _ => (lower, upper),
+            };
+            graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+        }
+        graph
+    }
+}
+
+fn solve(graph: &mut Graph<i64>) -> Status {
+    PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+}
+
+fn state(graph: &Graph<i64>) -> String {
+    format!("{:?} {:?} {:?}", graph.to_dot(), (0..graph.num_nodes()).map(|u| graph.potential(u)).collect::<Vec<_>>(), (0..graph.num_nodes()).map(|u| graph.excess(u)).collect::<Vec<_>>())
+}
+
+#[test]
+fn cold_solves() {
+    let mut rng = XorShift::new(765);
+    let mut num_checked = 0;
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 8) as usize, rng.gen_range(1, 20) as usize);
+        let instance = Instance::random(&mut rng, num_nodes, num_edges);
+
+        let mut expected_graph = instance.graph(None);
+        let expected_status = solve(&mut expected_graph);
+        let candidates: Vec<usize> = (0..num_edges).filter(|_| rng.gen_range(0, 3) != 0).collect();
+        for perturbation in [Perturbation::Remove, Perturbation::HalveCapacity] {
+            let mut graph = instance.graph(None);
+            let report = edge_criticality(&mut graph, &candidates, perturbation);
+            if expected_status != Status::Optimal {
+                assert_eq!(report, Err(expected_status));
+                continue;
+            }
+            let report = report.unwrap();
+            num_checked += 1;
+
+            // the same as the plain solve, and the same report again
+            assert_eq!(state(&graph), state(&expected_graph));
+            assert_eq!(report.baseline, expected_graph.minimum_cost());
+            assert_eq!(edge_criticality(&mut graph, &candidates, perturbation), Ok(report.clone()));
+            assert_eq!(state(&graph), state(&expected_graph));
+
+            let mut num_solves = 0;
+            for (&edge_id, &criticality) in candidates.iter().zip(report.criticalities.iter()) {
+                let (_, _, lower, upper, _) = instance.edges[edge_id];
+                let (lower, upper) = match perturbation {
+                    Perturbation::Remove => (0, 0),
+                    Perturbation::HalveCapacity => (lower, lower + (upper - lower) / 2),
+                };
+                let flow = graph.get_edge(edge_id).unwrap().flow;
+                num_solves += !(lower <= flow && flow <= upper) as usize;
+
+                let mut perturbed = instance.graph(Some((edge_id, lower, upper)));
+                let expected = match solve(&mut perturbed) {
+                    Status::Optimal => Criticality::Delta(perturbed.minimum_cost() - report.baseline),
+                    Status::Infeasible => Criticality::Infeasible,
+                    status => unreachable!("{status:?}"),
+                };
+                assert_eq!(criticality, expected);
+            }
+            assert_eq!(report.num_solves, num_solves);
+        }
+    }
+    assert!(num_checked > 100);
+}
+
+#[test]
+fn shortcut() {
+    // the cheap path 0 -> 1 -> 3 is full, the expensive path 0 -> 2 -> 3 carries the rest, and 1 -> 2 carries nothing
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 5, 3).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    graph.add_supply(0, 3);
+    graph.add_demand(3, 3);
+
+    let report = edge_criticality(&mut graph, &[4, 0, 2], Perturbation::Remove).unwrap();
+    assert_eq!(report.baseline, 10);
+    assert_eq!(report.criticalities, vec![Criticality::Delta(0), Criticality::Delta(8), Criticality::Infeasible]);
+    assert_eq!(report.num_solves, 2);
+
+    // 1 -> 3 keeps 1 unit and the other goes 0 -> 1 -> 2 -> 3, and 2 -> 3 keeps the 2 units it needs
+    let report = edge_criticality(&mut graph, &[4, 1, 3], Perturbation::HalveCapacity).unwrap();
+    assert_eq!(report.criticalities, vec![Criticality::Delta(0), Criticality::Delta(3), Criticality::Delta(0)]);
+    assert_eq!(report.num_solves, 1);
+}
+
+#[test]
+fn disconnecting_removal() {
+    // a path, so that every edge disconnects the demand
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    for u in 0..3 {
+        graph.add_directed_edge(u, u + 1, 0, 10, 1).unwrap();
+    }
+    graph.add_supply(0, 4);
+    graph.add_demand(3, 4);
+
+    let report = edge_criticality(&mut graph, &[0, 1, 2], Perturbation::Remove).unwrap();
+    assert_eq!(report.baseline, 12);
+    assert_eq!(report.criticalities, vec![Criticality::Infeasible; 3]);
+    // halving keeps 5 units
+    let report = edge_criticality(&mut graph, &[0, 1, 2], Perturbation::HalveCapacity).unwrap();
+    assert_eq!(report.criticalities, vec![Criticality::Delta(0); 3]);
+    assert_eq!(report.num_solves, 0);
+
+    graph.add_demand(3, 1);
+    assert_eq!(edge_criticality(&mut graph, &[0], Perturbation::Remove), Err(Status::Unbalanced));
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::apply as maximum_flow_apply;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph;
+use network_algorithms::minimum_cost_flow::apply::{apply_path, unapply_path, ApplyError, Direction};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+// 0 -> 1 -> 2 -> 3
+fn path_graph() -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    graph.add_directed_edge(2, 3, 0, 2, 1).unwrap();
+    graph
+}
+
+fn flows(graph: &Graph<i64>) -> Vec<i64> {
+    (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect()
+}
+
+// decompose the flow of a dag into paths from source
+fn decompose(num_nodes: usize, source: usize, edges: &[(usize, usize, i64)]) -> Vec<(Vec<(usize, Direction)>, i64)> {
+    let mut remaining: Vec<i64> = edges.iter().map(|e| e.2).collect();
+    let mut paths = Vec::new();
+    loop {
+        let (mut now, mut path) = (source, Vec::new());
+        while let Some(edge_id) = (0..edges.len()).find(|&edge_id| edges[edge_id].0 == now && remaining[edge_id] > 0) {
+            path.push(edge_id);
+            now = edges[edge_id].1;
+            assert!(path.len() <= num_nodes);
+        }
+        if path.is_empty() {
+            return paths;
+        }
+        let amount = path.iter().map(|&edge_id| remaining[edge_id]).min().unwrap();
+        path.iter().for_each(|&edge_id| remaining[edge_id] -= amount);
+        paths.push((path.into_iter().map(|edge_id| (edge_id, Direction::Forward)).collect(), amount));
+    }
+}
+
+#[test]
+fn apply_and_unapply() {
+    let mut graph = path_graph();
+    let path = [(0, Direction::Forward), (1, Direction::Forward), (2, Direction::Forward)];
+    assert_eq!(apply_path(&mut graph, &path, 2), Ok(()));
+    assert_eq!(flows(&graph), vec![2, 2, 2]);
+    assert_eq!((graph.excess(0), graph.excess(3)), (-2, 2));
+
+    assert_eq!(unapply_path(&mut graph, &path, 1), Ok(()));
+    assert_eq!(flows(&graph), vec![1, 1, 1]);
+    assert_eq!((graph.excess(0), graph.excess(3)), (-1, 1));
+}
+
+#[test]
+fn atomicity() {
+    let mut graph = path_graph();
+    let path = [(0, Direction::Forward), (1, Direction::Forward), (2, Direction::Forward)];
+    assert_eq!(apply_path(&mut graph, &path, 3), Err(ApplyError::InsufficientCapacity { position: 2, edge_id: 2 }));
+    assert_eq!(flows(&graph), vec![0, 0, 0]);
+    assert_eq!((graph.excess(0), graph.excess(3)), (0, 0));
+
+    assert_eq!(apply_path(&mut graph, &[(0, Direction::Forward), (2, Direction::Forward)], 1), Err(ApplyError::Disconnected { position: 1 }));
+    assert_eq!(apply_path(&mut graph, &[(0, Direction::Forward), (3, Direction::Forward)], 1), Err(ApplyError::EdgeOutOfRange { position: 1, edge_id: 3 }));
+    assert_eq!(apply_path(&mut graph, &[], 1), Err(ApplyError::EmptyPath));
+    assert_eq!(flows(&graph), vec![0, 0, 0]);
+}
+
+#[test]
+fn backward_respects_lower_bound() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    let edge_id = graph.add_directed_edge(0, 1, 2, 5, 1).unwrap().index();
+    let negative_edge_id = graph.add_directed_edge(0, 1, 1, 4, -1).unwrap().index();
+    assert_eq!(flows(&graph), vec![2, 4]);
+
+    // the flow cannot go below the lower bound
+    assert_eq!(apply_path(&mut graph, &[(edge_id, Direction::Backward)], 1), Err(ApplyError::InsufficientCapacity { position: 0, edge_id }));
+    assert_eq!(apply_path(&mut graph, &[(negative_edge_id, Direction::Backward)], 3), Ok(()));
+    assert_eq!(flows(&graph), vec![2, 1]);
+    assert_eq!(apply_path(&mut graph, &[(negative_edge_id, Direction::Backward)], 1), Err(ApplyError::InsufficientCapacity { position: 0, edge_id: negative_edge_id }));
+
+    assert_eq!((graph.excess(0), graph.excess(1)), (-3, 3));
+
+    // 0 -> 1 -> 0
+    let cycle = [(negative_edge_id, Direction::Forward), (edge_id, Direction::Backward)];
+    assert_eq!(apply_path(&mut graph, &cycle, 1), Err(ApplyError::InsufficientCapacity { position: 1, edge_id }));
+    let cycle = [(edge_id, Direction::Forward), (negative_edge_id, Direction::Backward)];
+    assert_eq!(apply_path(&mut graph, &cycle, 1), Err(ApplyError::InsufficientCapacity { position: 1, edge_id: negative_edge_id }));
+    assert_eq!(apply_path(&mut graph, &[(edge_id, Direction::Forward), (edge_id, Direction::Backward)], 1), Ok(()));
+    assert_eq!(flows(&graph), vec![2, 1]);
+}
+
+#[test]
+fn replay_minimum_cost_flow() {
+    let mut rng = XorShift::new(748);
+    for _ in 0..50 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..3 * num_nodes {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            if u < v {
+                graph.add_directed_edge(u, v, 0, rng.gen_range(0, 10), rng.gen_range(0, 10)).unwrap();
+            }
+        }
+        let mut solved = graph.clone();
+        solved.add_supply(0, 5);
+        solved.add_demand(num_nodes - 1, 5);
+        if SuccessiveShortestPath::default().solve(&mut solved) != Status::Optimal {
+            continue;
+        }
+
+        let edges: Vec<(usize, usize, i64)> = (0..solved.num_edges()).map(|edge_id| solved.get_edge(edge_id).unwrap()).map(|e| (e.from, e.to, e.flow)).collect();
+        for (path, amount) in decompose(num_nodes, 0, &edges) {
+            assert_eq!(apply_path(&mut graph, &path, amount), Ok(()));
+        }
+        assert_eq!(flows(&graph), flows(&solved));
+        assert_eq!((graph.excess(0), graph.excess(num_nodes - 1)), (-5, 5));
+    }
+}
+
+#[test]
+fn replay_maximum_flow() {
+    let mut rng = XorShift::new(749);
+    for _ in 0..50 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let mut graph = graph::Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..3 * num_nodes {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            if u < v {
+                graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+            }
+        }
+        let mut solved = graph.clone();
+        Dinic::default().solve(0, num_nodes - 1, &mut solved);
+
+        let edges: Vec<(usize, usize, i64)> = (0..solved.num_edges()).map(|edge_id| solved.get_edge(edge_id).unwrap()).map(|e| (e.from, e.to, e.flow)).collect();
+        for (path, amount) in decompose(num_nodes, 0, &edges) {
+            let path: Vec<(usize, maximum_flow_apply::Direction)> = path.into_iter().map(|(edge_id, _)| (edge_id, maximum_flow_apply::Direction::Forward)).collect();
+            assert_eq!(maximum_flow_apply::apply_path(&mut graph, &path, amount), Ok(()));
+        }
+        assert_eq!(graph.maximum_flow(0), solved.maximum_flow(0));
+        for edge_id in 0..graph.num_edges() {
+            assert_eq!(graph.get_edge(edge_id), solved.get_edge(edge_id));
+        }
+
+        // the flow of the last edge cannot be negative
+        let last = [(graph.num_edges().saturating_sub(1), maximum_flow_apply::Direction::Backward)];
+        if graph.num_edges() > 0 && graph.get_edge(last[0].0).unwrap().flow == 0 {
+            assert!(maximum_flow_apply::apply_path(&mut graph, &last, 1).is_err());
+        }
+    }
+}
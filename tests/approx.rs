@@ -0,0 +1,124 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::approx::{Approx, TOLERANCE};
+use network_algorithms::checked::Checked;
+use network_algorithms::flow_ext::FlowExt;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{AlteringCandidateListPivotRule, BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+// the costs are k / 1000. the reference solves the same instance with the costs k in i64, so its objective is exact
+struct Instance {
+    num_nodes: usize,
+    edges: Vec<(usize, usize, i64, i64, i64)>,
+    supplies: Vec<(usize, usize, i64)>,
+}
+
+impl Instance {
+    fn random(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> Self {
+        let edges = (0..num_edges)
+            .map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), 0, rng.gen_range(0, 10), rng.gen_range(0, 10_000)))
+            .collect();
+        let supplies = (0..num_nodes).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10))).collect();
+        Self { num_nodes, edges, supplies }
+    }
+
+    fn scaled(&self) -> Graph<i64> {
+        let mut graph = Graph::default();
+        graph.add_nodes(self.num_nodes);
+        for &(from, to, lower, upper, cost) in self.edges.iter() {
+            graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+        }
+        for &(u, v, amount) in self.supplies.iter() {
+            graph.add_supply(u, amount);
+            graph.add_demand(v, amount);
+        }
+        graph
+    }
+
+    fn real(&self) -> Graph<Approx<f64>> {
+        let to_real = |x: i64| Approx(x as f64);
+        let mut graph = Graph::default();
+        graph.add_nodes(self.num_nodes);
+        for &(from, to, lower, upper, cost) in self.edges.iter() {
+            graph.add_directed_edge(from, to, to_real(lower), to_real(upper), Approx(cost as f64 / 1000.0)).unwrap();
+        }
+        for &(u, v, amount) in self.supplies.iter() {
+            graph.add_supply(u, to_real(amount));
+            graph.add_demand(v, to_real(amount));
+        }
+        graph
+    }
+}
+
+fn assert_objective(actual: f64, scaled: i64) {
+    let expected = scaled as f64 / 1000.0;
+    assert!((actual - expected).abs() <= 1e-9 * expected.abs().max(1.0), "{actual} != {expected}");
+}
+
+#[test]
+fn comparison() {
+    // exact, the solvers apply the tolerance
+    assert_ne!(Approx(0.1) + Approx(0.2), Approx(0.3));
+    assert!(Approx(1e-12) > Approx(0.0));
+    assert_eq!(Approx(-0.0), Approx(0.0));
+    assert!(Approx(f64::NAN) > Approx(f64::INFINITY));
+    assert_eq!(Approx(f64::NAN), Approx(f64::NAN));
+
+    // the tolerance is a property of the Flow type: TOLERANCE for Approx, zero for the exact types
+    assert_eq!(Approx::<f64>::tolerance(), Approx(TOLERANCE));
+    assert_eq!((i64::tolerance(), Checked::<i64>::tolerance()), (0, Checked::Value(0)));
+
+    // a total order: values closer than the tolerance are still ordered, so sorting by them cannot panic
+    let mut values: Vec<Approx<f64>> = (0..1000).map(|i| Approx((i * 7919 % 1000) as f64 * 1e-10)).collect();
+    values.sort_unstable();
+    assert!(values.windows(2).all(|pair| pair[0].value() < pair[1].value()));
+}
+
+#[test]
+fn rounded_paths() {
+    // 0 -> 1 -> 2 costs 0.1 + 0.2, which rounds above 0.3 = 0 -> 2
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, Approx(4.0));
+    graph.add_demand(2, Approx(4.0));
+    graph.add_directed_edge(0, 1, Approx(0.0), Approx(2.0), Approx(0.1)).unwrap();
+    graph.add_directed_edge(1, 2, Approx(0.0), Approx(2.0), Approx(0.2)).unwrap();
+    graph.add_directed_edge(0, 2, Approx(0.0), Approx(2.0), Approx(0.3)).unwrap();
+
+    let mut ssp = graph.clone();
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut ssp), Status::Optimal);
+    assert_objective(ssp.minimum_cost().value(), 1200);
+
+    let mut pivot = BlockSearchPivotRule::new(graph.num_edges());
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut pivot, &mut graph), Status::Optimal);
+    assert_objective(graph.minimum_cost().value(), 1200);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(816);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 40) as usize);
+        let instance = Instance::random(&mut rng, num_nodes, num_edges);
+
+        let mut scaled = instance.scaled();
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut scaled);
+
+        let mut ssp = instance.real();
+        assert_eq!(SuccessiveShortestPath::default().solve(&mut ssp), expected);
+        let mut primal = instance.real();
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut primal), expected);
+        // sorts its candidates by violation
+        let mut altering = instance.real();
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut AlteringCandidateListPivotRule::new(num_edges), &mut altering), expected);
+        if expected == Status::Optimal {
+            assert_objective(ssp.minimum_cost().value(), scaled.minimum_cost());
+            assert_objective(primal.minimum_cost().value(), scaled.minimum_cost());
+            assert_objective(altering.minimum_cost().value(), scaled.minimum_cost());
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use network_algorithms::cooperative::Control;
+use network_algorithms::minimum_cost_flow::artificial::{ArtificialEdgeRole, ArtificialNodeRole, ArtificialStructure};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+#[test]
+fn roles_of_the_extended_network() {
+    // a supply, a node without supply nor demand and a demand
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_work_counter(1, Box::new(|_| Control::Pause));
+    let mut pivot = BlockSearchPivotRule::new(graph.num_edges());
+    let mut session = solver.session(&mut pivot, &mut graph);
+    let expected = ArtificialStructure {
+        nodes: vec![(3, ArtificialNodeRole::Root)],
+        edges: vec![
+            (2, ArtificialEdgeRole::SupplySlack { node: 0 }),
+            (3, ArtificialEdgeRole::RootLink { node: 1 }),
+            (4, ArtificialEdgeRole::DemandSlack { node: 2 }),
+        ],
+    };
+    assert_eq!(session.artificial_structure(), Some(&expected));
+    assert_eq!(expected.node_role(3), Some(ArtificialNodeRole::Root));
+    assert_eq!(expected.node_role(0), None);
+    assert_eq!(expected.edge_role(4).map(|role| role.node()), Some(2));
+
+    let dot = session.to_dot();
+    assert!(dot.contains("3 [label=\"3\\nroot\"];"), "{dot}");
+    assert!(dot.contains("0 -> 3 [label=\"3/3 @ 3\\nsupply slack of node 0\""), "{dot}");
+    assert!(dot.contains("1 -> 3 [label=\"0/0 @ 3\\nroot link of node 1\"];"), "{dot}");
+    assert!(dot.contains("3 -> 2 [label=\"3/3 @ 3\\ndemand slack of node 2\""), "{dot}");
+
+    while session.resume() == Status::Paused {}
+    assert_eq!(session.status(), Status::Optimal);
+    assert_eq!(session.artificial_structure(), None);
+    assert!(!session.to_dot().contains("slack"));
+    drop(session);
+    assert_eq!(graph.num_nodes(), 3);
+    assert_eq!(graph.num_edges(), 2);
+}
+
+#[test]
+fn unmet_slacks_of_an_infeasible_solve() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    graph.add_supply(0, 3);
+    graph.add_demand(1, 2);
+    graph.add_demand(2, 1);
+
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Infeasible);
+    let mut unmet = solver.unmet_slacks().to_vec();
+    unmet.sort_by_key(|&(role, _)| role.node());
+    assert_eq!(
+        unmet,
+        vec![
+            (ArtificialEdgeRole::SupplySlack { node: 0 }, 2),
+            (ArtificialEdgeRole::DemandSlack { node: 1 }, 1),
+            (ArtificialEdgeRole::DemandSlack { node: 2 }, 1)
+        ]
+    );
+    assert_eq!(ArtificialEdgeRole::SupplySlack { node: 0 }.to_string(), "supply slack of node 0");
+
+    graph.add_directed_edge(0, 2, 0, 5, 1).unwrap();
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(solver.unmet_slacks().is_empty());
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::assignment::*;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+// the minimum cost of assigning num_assignments of the rows from row on, with the columns in used taken
+fn brute_force(cost_matrix: &[Vec<Option<i64>>], row: usize, used: u32, num_assignments: usize) -> Option<i64> {
+    if num_assignments == 0 {
+        return Some(0);
+    }
+    if cost_matrix.len() - row < num_assignments {
+        return None;
+    }
+    // row is not assigned
+    let mut best = brute_force(cost_matrix, row + 1, used, num_assignments);
+    for (column, &cost) in cost_matrix[row].iter().enumerate().filter(|&(column, _)| used >> column & 1 == 0) {
+        let (Some(cost), Some(rest)) = (cost, brute_force(cost_matrix, row + 1, used | 1 << column, num_assignments - 1)) else {
+            continue;
+        };
+        best = Some(best.map_or(cost + rest, |best: i64| best.min(cost + rest)));
+    }
+    best
+}
+
+fn check(cost_matrix: &[Vec<Option<i64>>], total_cost: i64, columns: &[Option<usize>]) {
+    let num_columns = cost_matrix.first().map_or(0, |row| row.len());
+    assert_eq!(columns.len(), cost_matrix.len());
+    assert_eq!(columns.iter().flatten().count(), cost_matrix.len().min(num_columns));
+
+    let mut used = vec![false; num_columns];
+    let mut cost = 0;
+    for (row, &column) in columns.iter().enumerate() {
+        if let Some(column) = column {
+            assert!(!used[column]);
+            used[column] = true;
+            cost += cost_matrix[row][column].unwrap();
+        }
+    }
+    assert_eq!(cost, total_cost);
+}
+
+#[test]
+fn square() {
+    let cost_matrix = vec![vec![Some(4), Some(1), Some(3)], vec![Some(2), Some(0), Some(5)], vec![Some(3), Some(2), Some(2)]];
+    assert_eq!(minimum_cost_assignment(&cost_matrix), Ok((5, vec![Some(1), Some(0), Some(2)])));
+}
+
+#[test]
+fn rectangular_and_forbidden() {
+    // more workers than jobs, and worker 0 cannot do job 0
+    let cost_matrix = vec![vec![None, Some(1)], vec![Some(8), Some(3)], vec![Some(4), Some(-2)]];
+    assert_eq!(minimum_cost_assignment(&cost_matrix), Ok((5, vec![Some(1), None, Some(0)])));
+
+    // more jobs than workers
+    let cost_matrix = vec![vec![Some(5), Some(1), Some(2)], vec![Some(1), Some(1), Some(9)]];
+    assert_eq!(minimum_cost_assignment(&cost_matrix), Ok((2, vec![Some(1), Some(0)])));
+
+    // nobody can do job 1
+    let cost_matrix = vec![vec![Some(1), None], vec![Some(2), None]];
+    assert_eq!(minimum_cost_assignment(&cost_matrix), Err(Status::Infeasible));
+
+    assert_eq!(minimum_cost_assignment::<i64>(&[]), Ok((0, vec![])));
+    assert_eq!(minimum_cost_assignment::<i64>(&[vec![], vec![]]), Ok((0, vec![None, None])));
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(767);
+    for _ in 0..300 {
+        let (num_rows, num_columns) = (rng.gen_range(1, 9) as usize, rng.gen_range(1, 9) as usize);
+        let forbidden = rng.gen_range(0, 4);
+        let cost_matrix: Vec<Vec<Option<i64>>> = (0..num_rows)
+            .map(|_| (0..num_columns).map(|_| (rng.gen_range(0, 10) >= forbidden).then(|| rng.gen_range(-20, 50))).collect())
+            .collect();
+
+        for solve in [minimum_cost_assignment, minimum_cost_assignment_auction] {
+            match (solve(&cost_matrix), brute_force(&cost_matrix, 0, 0, num_rows.min(num_columns))) {
+                (Ok((total_cost, columns)), Some(expected)) => {
+                    assert_eq!(total_cost, expected);
+                    check(&cost_matrix, total_cost, &columns);
+                }
+                (Err(status), None) => assert_eq!(status, Status::Infeasible),
+                (actual, expected) => panic!("{actual:?} {expected:?}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn auction() {
+    let cost_matrix = vec![vec![Some(4), Some(1), Some(3)], vec![Some(2), Some(0), Some(5)], vec![Some(3), Some(2), Some(2)]];
+    assert_eq!(minimum_cost_assignment_auction(&cost_matrix), Ok((5, vec![Some(1), Some(0), Some(2)])));
+    assert_eq!(maximum_weight_assignment_auction(&cost_matrix), Ok((11, vec![Some(0), Some(2), Some(1)])));
+
+    // more workers than jobs, and worker 0 cannot do job 0
+    let cost_matrix = vec![vec![None, Some(1)], vec![Some(8), Some(3)], vec![Some(4), Some(-2)]];
+    assert_eq!(minimum_cost_assignment_auction(&cost_matrix), Ok((5, vec![Some(1), None, Some(0)])));
+    assert_eq!(maximum_weight_assignment_auction(&cost_matrix), Ok((9, vec![Some(1), Some(0), None])));
+
+    // worker 1 can do nothing
+    let cost_matrix = vec![vec![Some(1), Some(2)], vec![None, None]];
+    assert_eq!(minimum_cost_assignment_auction(&cost_matrix), Err(Status::Infeasible));
+    // workers 1 and 2 can only do job 0
+    let cost_matrix = vec![vec![Some(1), Some(2), Some(3)], vec![Some(1), None, None], vec![Some(1), None, None]];
+    assert_eq!(minimum_cost_assignment_auction(&cost_matrix), Err(Status::Infeasible));
+
+    assert_eq!(minimum_cost_assignment_auction::<i64>(&[]), Ok((0, vec![])));
+    assert_eq!(minimum_cost_assignment_auction::<i64>(&[vec![], vec![]]), Ok((0, vec![None, None])));
+}
+
+#[test]
+fn auction_dense() {
+    let mut rng = XorShift::new(768);
+    for _ in 0..5 {
+        let (num_rows, num_columns) = (rng.gen_range(20, 60) as usize, rng.gen_range(20, 60) as usize);
+        let weight_matrix: Vec<Vec<Option<i64>>> = (0..num_rows).map(|_| (0..num_columns).map(|_| Some(rng.gen_range(0, 1_000_000))).collect()).collect();
+        let cost_matrix: Vec<Vec<Option<i64>>> = weight_matrix.iter().map(|weights| weights.iter().map(|weight| weight.map(|weight| -weight)).collect()).collect();
+
+        let (expected, _) = minimum_cost_assignment(&cost_matrix).unwrap();
+        let (total_cost, columns) = minimum_cost_assignment_auction(&cost_matrix).unwrap();
+        assert_eq!(total_cost, expected);
+        check(&cost_matrix, total_cost, &columns);
+        assert_eq!(maximum_weight_assignment_auction(&weight_matrix).map(|(total_weight, _)| total_weight), Ok(-expected));
+    }
+}
@@ -0,0 +1,146 @@
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use network_algorithms::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use network_algorithms::minimum_cost_flow::graph::{BalancePolicy, Graph};
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::out_of_kilter::OutOfKilter;
+use network_algorithms::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use network_algorithms::minimum_cost_flow::primal_dual::PrimalDual;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+fn solvers() -> Vec<fn(&mut Graph<i64>) -> Status> {
+    vec![
+        |graph| SuccessiveShortestPath::default().solve(graph),
+        |graph| PrimalDual::default().solve(graph),
+        |graph| OutOfKilter::default().solve(graph),
+        |graph| CycleCanceling::default().solve(graph),
+        |graph| CostScalingPushRelabel::default().solve(graph),
+        |graph| PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph),
+        |graph| PrimalNetworkSimplex::default().solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), graph),
+        |graph| DualNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph),
+        |graph| ParametricNetworkSimplex::default().solve(graph),
+    ]
+}
+
+// 0 -> 1 -> 2 costs 2 up to 4 units, 0 -> 2 costs 3
+fn triangle(supply: i64, demand: i64) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, supply);
+    graph.add_demand(2, demand);
+    graph.add_directed_edge(0, 1, 0, 10, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 4, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 10, 3).unwrap();
+    graph
+}
+
+fn excesses(graph: &Graph<i64>) -> Vec<i64> {
+    (0..graph.num_nodes()).map(|u| graph.excess(u)).collect()
+}
+
+#[test]
+fn reject_by_default() {
+    for solve in solvers() {
+        let mut graph = triangle(5, 3);
+        assert_eq!(graph.balance_policy(), BalancePolicy::Reject);
+        assert_eq!(solve(&mut graph), Status::Unbalanced);
+        assert_eq!(graph.balance_adjustment(), None);
+    }
+}
+
+#[test]
+fn slack_node_added() {
+    for solve in solvers() {
+        // 2 units of supply stay at 0 through the slack edge of cost 1
+        let mut graph = triangle(5, 3);
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: None, cost_per_unit: 1, max_imbalance: 2 });
+        assert_eq!(solve(&mut graph), Status::Optimal);
+        assert_eq!((graph.num_nodes(), graph.num_edges()), (3, 3));
+        assert_eq!(graph.get_edge(3), None);
+        assert_eq!(graph.minimum_cost(), 6);
+        assert_eq!(graph.balance_adjustment(), Some((2, 2)));
+        assert_eq!(excesses(&graph), vec![2, 0, 0]);
+
+        // 2 units of demand are not met
+        let mut graph = triangle(3, 5);
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: None, cost_per_unit: 4, max_imbalance: 2 });
+        assert_eq!(solve(&mut graph), Status::Optimal);
+        assert_eq!((graph.num_nodes(), graph.num_edges()), (3, 3));
+        assert_eq!(graph.minimum_cost(), 6);
+        assert_eq!(graph.balance_adjustment(), Some((-2, 8)));
+        assert_eq!(excesses(&graph), vec![0, 0, -2]);
+
+        // balanced, nothing is absorbed
+        let mut graph = triangle(5, 5);
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: None, cost_per_unit: 4, max_imbalance: 2 });
+        assert_eq!(solve(&mut graph), Status::Optimal);
+        assert_eq!(graph.minimum_cost(), 11);
+        assert_eq!(graph.balance_adjustment(), None);
+    }
+}
+
+#[test]
+fn designated_slack_node() {
+    for solve in solvers() {
+        // node 2 takes all 5 units, 2 of which it keeps
+        let mut graph = triangle(5, 3);
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: Some(2), cost_per_unit: 3, max_imbalance: 10 });
+        assert_eq!(solve(&mut graph), Status::Optimal);
+        assert_eq!((graph.num_nodes(), graph.num_edges()), (3, 3));
+        assert_eq!(graph.minimum_cost(), 11);
+        assert_eq!(graph.balance_adjustment(), Some((2, 6)));
+        assert_eq!(excesses(&graph), vec![0, 0, 2]);
+
+        // node 0 supplies the missing 2 units
+        let mut graph = triangle(3, 5);
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: Some(0), cost_per_unit: 3, max_imbalance: 10 });
+        assert_eq!(solve(&mut graph), Status::Optimal);
+        assert_eq!(graph.minimum_cost(), 11);
+        assert_eq!(graph.balance_adjustment(), Some((-2, 6)));
+        assert_eq!(excesses(&graph), vec![-2, 0, 0]);
+    }
+}
+
+#[test]
+fn threshold() {
+    for solve in solvers() {
+        for node in [None, Some(1)] {
+            let mut graph = triangle(5, 2);
+            graph.set_balance_policy(BalancePolicy::SlackNode { node, cost_per_unit: 1, max_imbalance: 2 });
+            assert_eq!(solve(&mut graph), Status::Unbalanced);
+            assert_eq!(graph.balance_adjustment(), None);
+            assert_eq!((graph.num_nodes(), graph.num_edges()), (3, 3));
+            assert_eq!(excesses(&graph), vec![5, 0, -2]);
+        }
+
+        // out of range
+        let mut graph = triangle(5, 4);
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: Some(3), cost_per_unit: 1, max_imbalance: 2 });
+        assert_eq!(solve(&mut graph), Status::Unbalanced);
+    }
+}
+
+#[test]
+fn lower_bounds() {
+    for solve in solvers() {
+        // the lower bound of 0 -> 1 shifts the excesses, but not the supplies that are balanced
+        let mut graph = Graph::default();
+        graph.add_nodes(3);
+        graph.add_supply(0, 5);
+        graph.add_demand(2, 4);
+        graph.add_directed_edge(0, 1, 2, 10, 1).unwrap();
+        graph.add_directed_edge(1, 2, 0, 10, 1).unwrap();
+        graph.add_directed_edge(0, 2, 0, 10, 5).unwrap();
+        graph.add_directed_edge(2, 0, 1, 3, -1).unwrap();
+        graph.set_balance_policy(BalancePolicy::SlackNode { node: None, cost_per_unit: 0, max_imbalance: 1 });
+        assert_eq!(solve(&mut graph), Status::Optimal);
+        assert_eq!((graph.num_nodes(), graph.num_edges()), (3, 4));
+        assert_eq!(graph.balance_adjustment(), Some((1, 0)));
+        assert_eq!(excesses(&graph), vec![1, 0, 0]);
+        // the cycle 0 -> 1 -> 2 -> 0 costs 1, so 2 -> 0 carries its lower bound only
+        assert_eq!((0..4).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect::<Vec<_>>(), vec![5, 5, 0, 1]);
+        assert_eq!(graph.minimum_cost(), 9);
+    }
+}
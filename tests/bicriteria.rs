@@ -0,0 +1,170 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::bicriteria::{efficient_frontier, FrontierPoint};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+// send 2 units from 0 to 1 over three parallel edges
+fn three_edges() -> (Graph<i64>, Vec<i64>) {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 2);
+    graph.add_demand(1, 2);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 1, 0, 2, 3).unwrap();
+    graph.add_directed_edge(0, 1, 0, 2, 6).unwrap();
+    (graph, vec![5, 2, 1])
+}
+
+fn minimum_cost(graph: &Graph<i64>, costs: &[i64]) -> Option<i64> {
+    // excess = supply - outflow + inflow
+    let mut supplies: Vec<i64> = (0..graph.num_nodes()).map(|u| graph.excess(u)).collect();
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        supplies[edge.from] += edge.flow;
+        supplies[edge.to] -= edge.flow;
+    }
+
+    let mut copied = Graph::default();
+    copied.add_nodes(graph.num_nodes());
+    for (u, &supply) in supplies.iter().enumerate() {
+        copied.add_supply(u, supply);
+    }
+    for (edge_id, &cost) in costs.iter().enumerate() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        copied.add_directed_edge(edge.from, edge.to, edge.lower, edge.upper, cost).unwrap();
+    }
+    match PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(copied.num_edges()), &mut copied) {
+        Status::Optimal => Some(copied.minimum_cost()),
+        _ => None,
+    }
+}
+
+#[test]
+fn hand_computed_frontier() {
+    let (mut graph, cost2) = three_edges();
+    let frontier = efficient_frontier(&mut graph, &cost2, 10, true);
+    assert_eq!(frontier.status, Status::Optimal);
+    assert!(!frontier.truncated);
+    assert_eq!(
+        frontier.points,
+        vec![
+            FrontierPoint { objective1: 2, objective2: 10, lambda_lower: (0, 1), lambda_upper: Some((2, 3)), flows: Some(vec![2, 0, 0]) },
+            FrontierPoint { objective1: 6, objective2: 4, lambda_lower: (2, 3), lambda_upper: Some((3, 1)), flows: Some(vec![0, 2, 0]) },
+            FrontierPoint { objective1: 12, objective2: 2, lambda_lower: (3, 1), lambda_upper: None, flows: Some(vec![0, 0, 2]) },
+        ]
+    );
+    assert_eq!(graph.minimum_cost(), 2);
+}
+
+#[test]
+fn identical_costs() {
+    let (mut graph, _) = three_edges();
+    let frontier = efficient_frontier(&mut graph, &[1, 3, 6], 10, false);
+    assert_eq!(frontier.points, vec![FrontierPoint { objective1: 2, objective2: 2, lambda_lower: (0, 1), lambda_upper: None, flows: None }]);
+}
+
+#[test]
+fn truncated() {
+    // unit costs on a convex curve, so that every edge is a breakpoint
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    let mut cost2 = Vec::new();
+    for i in 0..10 {
+        graph.add_directed_edge(0, 1, 0, 1, i).unwrap();
+        cost2.push((10 - i) * (10 - i));
+    }
+
+    let frontier = efficient_frontier(&mut graph.clone(), &cost2, 100, false);
+    assert_eq!(frontier.points.len(), 10);
+    assert!(!frontier.truncated);
+
+    let frontier = efficient_frontier(&mut graph, &cost2, 4, false);
+    assert_eq!(frontier.points.len(), 4);
+    assert!(frontier.truncated);
+    assert_eq!((frontier.points[0].objective1, frontier.points[3].objective1), (0, 9));
+}
+
+#[test]
+fn infeasible() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 3);
+    graph.add_demand(1, 3);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    let frontier = efficient_frontier(&mut graph, &[1], 10, false);
+    assert_eq!(frontier.status, Status::Infeasible);
+    assert!(frontier.points.is_empty());
+}
+
+#[test]
+fn overflow() {
+    // each objective can be solved alone, but the combined costs overflow
+    let (mut graph, _) = three_edges();
+    let frontier = efficient_frontier(&mut graph, &[i64::MAX / 8, 2, 1], 10, false);
+    assert_eq!(frontier.status, Status::BadInput);
+}
+
+#[test]
+fn objective_overflow() {
+    // the costs fit, but 2 units on the edge cost more than i64::MAX in objective2
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 2);
+    graph.add_demand(1, 2);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    let frontier = efficient_frontier(&mut graph, &[i64::MAX / 2 + 1], 10, false);
+    assert_eq!(frontier.status, Status::Overflow);
+    assert!(frontier.points.is_empty());
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(752);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 10) as usize, rng.gen_range(1, 30) as usize);
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+        let cost1: Vec<i64> = (0..num_edges).map(|edge_id| graph.get_edge(edge_id).unwrap().cost).collect();
+        let cost2: Vec<i64> = (0..num_edges).map(|_| rng.gen_range(-10, 10)).collect();
+
+        let Some(minimum1) = minimum_cost(&graph, &cost1) else {
+            continue;
+        };
+        let minimum2 = minimum_cost(&graph, &cost2).unwrap();
+
+        let frontier = efficient_frontier(&mut graph, &cost2, 100, true);
+        assert_eq!(frontier.status, Status::Optimal);
+        let points = &frontier.points;
+
+        // endpoints
+        assert_eq!(points[0].objective1, minimum1);
+        assert_eq!(points[points.len() - 1].objective2, minimum2);
+        assert_eq!(graph.minimum_cost(), minimum1);
+
+        for w in points.windows(2) {
+            // non-dominated
+            assert!(w[0].objective1 < w[1].objective1 && w[0].objective2 > w[1].objective2);
+            assert_eq!(w[0].lambda_upper, Some(w[1].lambda_lower));
+        }
+        for w in points.windows(3) {
+            // convex: the slopes are increasing
+            let (dx1, dy1) = (w[1].objective1 - w[0].objective1, w[1].objective2 - w[0].objective2);
+            let (dx2, dy2) = (w[2].objective1 - w[1].objective1, w[2].objective2 - w[1].objective2);
+            assert!(dy1 * dx2 < dy2 * dx1);
+        }
+        for point in points.iter() {
+            // each point is optimal for the combined cost at its lambda
+            let (numerator, denominator) = point.lambda_lower;
+            let combined: Vec<i64> = (0..num_edges).map(|edge_id| denominator * cost1[edge_id] + numerator * cost2[edge_id]).collect();
+            assert_eq!(minimum_cost(&graph, &combined), Some(denominator * point.objective1 + numerator * point.objective2));
+
+            let flows = point.flows.as_ref().unwrap();
+            assert_eq!(point.objective1, (0..num_edges).map(|edge_id| cost1[edge_id] * flows[edge_id]).sum::<i64>());
+        }
+    }
+}
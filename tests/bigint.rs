@@ -0,0 +1,110 @@
+#![cfg(all(feature = "bigint", feature = "rational"))]
+
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use num_bigint::BigInt;
+use num_rational::Ratio;
+
+fn big(x: i64) -> BigInt {
+    BigInt::from(x)
+}
+
+fn pow2(exponent: u32) -> BigInt {
+    BigInt::from(1) << exponent
+}
+
+fn bigint_graph(graph: &Graph<i64>) -> Graph<BigInt> {
+    let mut converted = Graph::default();
+    converted.add_nodes(graph.num_nodes());
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        converted.add_directed_edge(edge.from, edge.to, big(edge.lower), big(edge.upper), big(edge.cost)).unwrap();
+    }
+    for u in 0..graph.num_nodes() {
+        converted.add_supply(u, big(graph.supply(u)));
+    }
+    converted
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(817);
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 40) as usize);
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 20, 50);
+        let mut ssp = bigint_graph(&graph);
+        let mut primal = bigint_graph(&graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut graph);
+
+        assert_eq!(SuccessiveShortestPath::default().solve(&mut ssp), expected);
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut primal), expected);
+        if expected == Status::Optimal {
+            assert_eq!(ssp.minimum_cost(), big(graph.minimum_cost()));
+            assert_eq!(primal.minimum_cost(), big(graph.minimum_cost()));
+        }
+    }
+}
+
+#[test]
+fn cost_beyond_u128() {
+    // 2^41 units from 0 to 3, half on each path: the optimal cost is 2^40 * (2^100 + 1) + 2^40 * (2^100 + 2^90) > 2^128
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_supply(0, pow2(41));
+    graph.add_demand(3, pow2(41));
+    graph.add_directed_edge(0, 1, big(0), pow2(40), pow2(100)).unwrap();
+    graph.add_directed_edge(1, 3, big(0), pow2(40), big(1)).unwrap();
+    graph.add_directed_edge(0, 2, big(0), pow2(40), pow2(100)).unwrap();
+    graph.add_directed_edge(2, 3, big(0), pow2(45), pow2(90)).unwrap();
+    graph.add_directed_edge(0, 3, big(0), pow2(45), pow2(101)).unwrap();
+    let expected = pow2(40) * (pow2(100) + big(1)) + pow2(40) * (pow2(100) + pow2(90));
+    assert!(expected > BigInt::from(u128::MAX));
+
+    let mut ssp = graph.clone();
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut ssp), Status::Optimal);
+    assert_eq!(ssp.minimum_cost(), expected);
+
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), expected);
+}
+
+#[test]
+fn dinic_beyond_u128() {
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, pow2(200)).unwrap();
+    graph.add_directed_edge(0, 2, pow2(150)).unwrap();
+    graph.add_directed_edge(1, 3, pow2(199)).unwrap();
+    graph.add_directed_edge(2, 3, pow2(200)).unwrap();
+    graph.add_directed_edge(1, 2, pow2(199)).unwrap();
+    assert_eq!(Dinic::default().solve(0, 3, &mut graph), maximum_flow::status::Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), pow2(200) + pow2(150));
+}
+
+#[test]
+fn ratio() {
+    // 0 -> 1 -> 2 costs 1/10 + 1/5 = 3/10, the same as 0 -> 2 but exactly
+    let r = |numer: i64, denom: i64| Ratio::new(numer, denom);
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, r(7, 2));
+    graph.add_demand(2, r(7, 2));
+    graph.add_directed_edge(0, 1, r(0, 1), r(3, 2), r(1, 10)).unwrap();
+    graph.add_directed_edge(1, 2, r(0, 1), r(3, 2), r(1, 5)).unwrap();
+    graph.add_directed_edge(0, 2, r(0, 1), r(2, 1), r(3, 10)).unwrap();
+
+    let mut ssp = graph.clone();
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut ssp), Status::Optimal);
+    assert_eq!(ssp.minimum_cost(), r(21, 20));
+
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), r(21, 20));
+}
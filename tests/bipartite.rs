@@ -0,0 +1,76 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::bipartite::maximum_bipartite_matching;
+use std::collections::HashSet;
+
+// the pairs are given, and no node is matched twice
+fn check_matching(num_left: usize, num_right: usize, pairs: &[(usize, usize)], matching: &[(usize, usize)]) {
+    let given: HashSet<(usize, usize)> = pairs.iter().copied().collect();
+    let (mut is_left_matched, mut is_right_matched) = (vec![false; num_left], vec![false; num_right]);
+    for &(left, right) in matching.iter() {
+        assert!(given.contains(&(left, right)));
+        assert!(!is_left_matched[left] && !is_right_matched[right]);
+        (is_left_matched[left], is_right_matched[right]) = (true, true);
+    }
+    // maximal as well
+    assert!(pairs.iter().all(|&(left, right)| is_left_matched[left] || is_right_matched[right]));
+}
+
+// the maximum matching of the left nodes from left on, with the right nodes in used taken
+fn brute_force(left: usize, used: u32, adjacency: &[Vec<usize>]) -> usize {
+    if left == adjacency.len() {
+        return 0;
+    }
+    let mut best = brute_force(left + 1, used, adjacency);
+    for &right in adjacency[left].iter().filter(|&&right| used >> right & 1 == 0) {
+        best = best.max(1 + brute_force(left + 1, used | 1 << right, adjacency));
+    }
+    best
+}
+
+#[test]
+fn small() {
+    // the greedy choice 0 - 0 blocks 1, so 0 has to take 1
+    let pairs = [(0, 0), (0, 1), (1, 0)];
+    let matching = maximum_bipartite_matching(2, 2, &pairs);
+    check_matching(2, 2, &pairs, &matching);
+    assert_eq!(matching, vec![(0, 1), (1, 0)]);
+
+    assert!(maximum_bipartite_matching(3, 0, &[]).is_empty());
+    assert!(maximum_bipartite_matching(0, 0, &[]).is_empty());
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(766);
+    for _ in 0..500 {
+        let (num_left, num_right) = (rng.gen_range(1, 8) as usize, rng.gen_range(1, 8) as usize);
+        let pairs: Vec<(usize, usize)> = (0..rng.gen_range(0, 20)).map(|_| (rng.gen_index(num_left), rng.gen_index(num_right))).collect();
+
+        let matching = maximum_bipartite_matching(num_left, num_right, &pairs);
+        check_matching(num_left, num_right, &pairs, &matching);
+
+        let mut adjacency = vec![Vec::new(); num_left];
+        for &(left, right) in pairs.iter() {
+            adjacency[left].push(right);
+        }
+        assert_eq!(matching.len(), brute_force(0, 0, &adjacency));
+    }
+}
+
+#[test]
+fn large_sparse() {
+    let mut rng = XorShift::new(7660);
+    let (num_left, num_right) = (50_000, 50_000);
+    // a perfect matching hidden among random pairs
+    let mut pairs: Vec<(usize, usize)> = (0..num_left).map(|left| (left, (left * 7919) % num_right)).collect();
+    pairs.extend((0..150_000).map(|_| (rng.gen_index(num_left), rng.gen_index(num_right))));
+    for i in (1..pairs.len()).rev() {
+        pairs.swap(i, rng.gen_index(i + 1));
+    }
+
+    let matching = maximum_bipartite_matching(num_left, num_right, &pairs);
+    check_matching(num_left, num_right, &pairs, &matching);
+    assert_eq!(matching.len(), num_left);
+}
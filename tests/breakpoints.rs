@@ -0,0 +1,97 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+// the minimum cost of the value on the piecewise linear function
+fn interpolate(breakpoints: &[(i64, i64)], value: i64) -> i64 {
+    let i = breakpoints.iter().position(|&(v, _)| value <= v).unwrap();
+    if i == 0 {
+        return breakpoints[0].1;
+    }
+    let ((v0, c0), (v1, c1)) = (breakpoints[i - 1], breakpoints[i]);
+    c0 + (value - v0) * (c1 - c0) / (v1 - v0)
+}
+
+#[test]
+fn two_paths() {
+    // 0 -> 1 -> 3 costs 2 for up to 2 units, 0 -> 2 -> 3 costs 6 for up to 3 units
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 3, 3).unwrap();
+
+    let breakpoints = ParametricNetworkSimplex::default().solve_with_breakpoints(0, 3, &mut graph).unwrap();
+    assert_eq!(breakpoints, vec![(0, 0), (2, 4), (5, 22)]);
+    assert_eq!(graph.minimum_cost(), 22);
+    assert_eq!((graph.excess(0), graph.excess(3)), (-5, 5));
+    assert_eq!(interpolate(&breakpoints, 3), 10);
+}
+
+#[test]
+fn bad_input_and_no_path() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 4, 1).unwrap();
+    graph.add_directed_edge(2, 1, 0, 4, 1).unwrap();
+    assert_eq!(ParametricNetworkSimplex::default().solve_with_breakpoints(0, 2, &mut graph), Ok(vec![(0, 0)]));
+    assert_eq!((graph.excess(0), graph.excess(2)), (0, 0));
+    assert_eq!(graph.minimum_cost(), 0);
+
+    assert_eq!(ParametricNetworkSimplex::default().solve_with_breakpoints(1, 1, &mut graph), Err(Status::BadInput));
+    assert_eq!(ParametricNetworkSimplex::default().solve_with_breakpoints(0, 3, &mut graph), Err(Status::BadInput));
+    let mut negative = graph.clone();
+    negative.add_directed_edge(0, 2, 0, 1, -1).unwrap();
+    assert_eq!(ParametricNetworkSimplex::default().solve_with_breakpoints(0, 2, &mut negative), Err(Status::BadInput));
+    let mut lower = graph.clone();
+    lower.add_directed_edge(0, 2, 1, 1, 1).unwrap();
+    assert_eq!(ParametricNetworkSimplex::default().solve_with_breakpoints(0, 2, &mut lower), Err(Status::BadInput));
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    assert_eq!(ParametricNetworkSimplex::default().solve_with_breakpoints(0, 1, &mut graph), Err(Status::BadInput));
+}
+
+#[test]
+fn random_against_fixed_values() {
+    let mut rng = XorShift::new(776);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let (source, sink) = (0, num_nodes - 1);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        let mut network = maximum_flow::graph::Graph::default();
+        network.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(1, 30) {
+            let (from, to, upper) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            graph.add_directed_edge(from, to, 0, upper, rng.gen_range(0, 10)).unwrap();
+            network.add_directed_edge(from, to, upper).unwrap();
+        }
+        Dinic::default().solve(source, sink, &mut network);
+        let maximum_flow = network.maximum_flow(source);
+
+        let mut solved = graph.clone();
+        let breakpoints = ParametricNetworkSimplex::default().solve_with_breakpoints(source, sink, &mut solved).unwrap();
+        assert_eq!(breakpoints[0], (0, 0));
+        assert_eq!(breakpoints.last().unwrap(), &(maximum_flow, solved.minimum_cost()));
+        assert_eq!((solved.excess(source), solved.excess(sink)), (-maximum_flow, maximum_flow));
+        // strictly increasing slopes
+        for window in breakpoints.windows(3) {
+            let ((v0, c0), (v1, c1), (v2, c2)) = (window[0], window[1], window[2]);
+            assert!(v0 < v1 && v1 < v2);
+            assert!((c1 - c0) * (v2 - v1) < (c2 - c1) * (v1 - v0));
+        }
+
+        for _ in 0..3 {
+            let k = rng.gen_range(0, maximum_flow);
+            let mut fixed = graph.clone();
+            assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(source, sink, k, &mut fixed), Status::Optimal);
+            assert_eq!(interpolate(&breakpoints, k), fixed.minimum_cost());
+        }
+    }
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::cooperative::{CancellationToken, Control};
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+// the flows are within their bounds and the excesses are what they leave of the supplies
+fn check_consistent(graph: &Graph<i64>) {
+    let mut balance: Vec<i64> = (0..graph.num_nodes()).map(|u| graph.supply(u)).collect();
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for (u, &balance) in balance.iter().enumerate() {
+        assert_eq!(balance, graph.excess(u));
+    }
+}
+
+fn check_maximum_flow_consistent(graph: &maximum_flow::graph::Graph<i64>, source: usize, sink: usize) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(0 <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for u in (0..graph.num_nodes()).filter(|&u| u != source && u != sink) {
+        assert_eq!(balance[u], 0);
+    }
+}
+
+fn large_graph() -> Graph<i64> {
+    let mut rng = XorShift::new(783);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 300, 3000, 50, 100);
+    // a path through all the nodes keeps the supplies feasible
+    for u in 0..graph.num_nodes() - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 1_000_000, 1_000).unwrap();
+        graph.add_directed_edge(u + 1, u, 0, 1_000_000, 1_000).unwrap();
+    }
+    graph
+}
+
+fn large_network() -> maximum_flow::graph::Graph<i64> {
+    let mut rng = XorShift::new(783);
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(300);
+    for _ in 0..3000 {
+        graph.add_directed_edge(rng.gen_index(300), rng.gen_index(300), rng.gen_range(0, 50)).unwrap();
+    }
+    graph
+}
+
+#[test]
+fn primal_network_simplex() {
+    let token = CancellationToken::new();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_cancellation_token(token.clone());
+    let mut graph = large_graph();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    let optimal_cost = graph.minimum_cost();
+
+    token.cancel();
+    let mut graph = large_graph();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Cancelled);
+    assert_eq!(solver.num_pivots(), 0);
+    check_consistent(&graph);
+
+    // cancelled in the middle of the pivots
+    let token = CancellationToken::new();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_cancellation_token(token.clone());
+    let canceller = token.clone();
+    solver.set_work_counter(
+        1000,
+        Box::new(move |count| {
+            if count >= 20_000 {
+                canceller.cancel();
+            }
+            Control::Continue
+        }),
+    );
+    let mut graph = large_graph();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Cancelled);
+    assert!(solver.num_pivots() > 0);
+    check_consistent(&graph);
+
+    // a fresh token lets the same solver finish
+    solver.set_cancellation_token(CancellationToken::new());
+    let mut graph = large_graph();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), optimal_cost);
+}
+
+#[test]
+fn cost_scaling_push_relabel() {
+    let is_cancelled = Arc::new(AtomicBool::new(true));
+    let mut solver = CostScalingPushRelabel::default();
+    solver.set_cancellation_token(CancellationToken::from(is_cancelled.clone()));
+    let mut graph = large_graph();
+    let before = graph.clone();
+    assert_eq!(solver.solve(&mut graph), Status::Cancelled);
+    // left as it was before the solve
+    for edge_id in 0..graph.num_edges() {
+        assert_eq!(graph.get_edge(edge_id), before.get_edge(edge_id));
+    }
+    for u in 0..graph.num_nodes() {
+        assert_eq!(graph.excess(u), before.excess(u));
+    }
+
+    is_cancelled.store(false, std::sync::atomic::Ordering::Relaxed);
+    assert_eq!(solver.solve(&mut graph), Status::Optimal);
+    let mut expected = large_graph();
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(expected.num_edges()), &mut expected), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+}
+
+#[test]
+fn dinic() {
+    let (source, sink) = (0, 299);
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut solver = Dinic::default();
+    solver.set_cancellation_token(token);
+    let mut graph = large_network();
+    assert_eq!(solver.solve(source, sink, &mut graph), maximum_flow::status::Status::Cancelled);
+    assert_eq!(graph.maximum_flow(source), 0);
+
+    // cancelled after the first phase, the flow so far is feasible
+    let token = CancellationToken::new();
+    let mut solver = Dinic::default();
+    solver.set_cancellation_token(token.clone());
+    solver.set_work_counter(
+        1,
+        Box::new(move |_| {
+            token.cancel();
+            Control::Continue
+        }),
+    );
+    let mut graph = large_network();
+    assert_eq!(solver.solve(source, sink, &mut graph), maximum_flow::status::Status::Cancelled);
+    assert_eq!(solver.num_phases(), 1);
+    check_maximum_flow_consistent(&graph, source, sink);
+
+    let mut expected = large_network();
+    assert_eq!(Dinic::default().solve(source, sink, &mut expected), maximum_flow::status::Status::Optimal);
+    assert!(graph.maximum_flow(source) <= expected.maximum_flow(source));
+}
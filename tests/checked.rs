@@ -0,0 +1,114 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::approx::Approx;
+use network_algorithms::checked::Checked;
+use network_algorithms::flow_ext::FlowExt;
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use network_algorithms::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::out_of_kilter::OutOfKilter;
+use network_algorithms::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use network_algorithms::minimum_cost_flow::primal_dual::PrimalDual;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+fn solvers() -> Vec<Box<dyn MinimumCostFlowSolver<Checked<i64>>>> {
+    vec![
+        Box::new(SuccessiveShortestPath::default()),
+        Box::new(PrimalDual::default()),
+        Box::new(OutOfKilter::default()),
+        Box::new(CycleCanceling::default()),
+        Box::new(CostScalingPushRelabel::default()),
+        Box::new(PrimalNetworkSimplex::default()),
+        Box::new(DualNetworkSimplex::default()),
+        Box::new(ParametricNetworkSimplex::default()),
+    ]
+}
+
+fn checked_graph(graph: &Graph<i64>) -> Graph<Checked<i64>> {
+    let mut checked = Graph::default();
+    checked.add_nodes(graph.num_nodes());
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        checked
+            .add_directed_edge(edge.from, edge.to, Checked::Value(edge.lower), Checked::Value(edge.upper), Checked::Value(edge.cost))
+            .unwrap();
+    }
+    for u in 0..graph.num_nodes() {
+        checked.add_supply(u, Checked::Value(graph.supply(u)));
+    }
+    checked
+}
+
+#[test]
+fn arithmetic() {
+    let max = Checked::Value(i64::MAX);
+    assert_eq!(max + Checked::Value(1), Checked::Overflow);
+    assert_eq!(max - Checked::Value(1), Checked::Value(i64::MAX - 1));
+    assert_eq!(-Checked::Value(i64::MIN), Checked::Overflow);
+    assert_eq!(Checked::Value(3) / Checked::Value(0), Checked::Overflow);
+    assert_eq!(Checked::Overflow - Checked::Overflow, Checked::<i64>::Overflow);
+    assert!(Checked::Overflow > max);
+    assert_eq!((max * Checked::Value(2) + Checked::Value(-1)).value(), None);
+}
+
+#[test]
+fn flow_ext() {
+    // only Checked is checked: a NaN or an infinity of Approx is not an overflow
+    assert_eq!((Checked::<i64>::CAN_OVERFLOW, i64::CAN_OVERFLOW, Approx::<f64>::CAN_OVERFLOW), (true, false, false));
+    assert!(Checked::<i64>::Overflow.is_overflow() && !FlowExt::is_overflow(&Checked::Value(i64::MAX)));
+    assert!(!Approx(f64::NAN).is_overflow() && !Approx(f64::INFINITY).is_overflow());
+}
+
+#[test]
+fn objective_overflow() {
+    // every cost fits, but 2 units on the edge do not
+    let cost = i64::MAX / 2 + 1;
+    for mut solver in solvers() {
+        let mut graph = Graph::default();
+        graph.add_nodes(2);
+        graph.add_supply(0, Checked::Value(2));
+        graph.add_demand(1, Checked::Value(2));
+        graph.add_directed_edge(0, 1, Checked::Value(0), Checked::Value(2), Checked::Value(cost)).unwrap();
+        assert_eq!(solver.solve(&mut graph), Status::Overflow, "{}", solver.name());
+    }
+}
+
+#[test]
+fn distance_overflow() {
+    // the length of the path 0 -> 1 -> 2 does not fit
+    let cost = i64::MAX / 2 + 1;
+    for mut solver in solvers() {
+        let mut graph = Graph::default();
+        graph.add_nodes(3);
+        graph.add_supply(0, Checked::Value(1));
+        graph.add_demand(2, Checked::Value(1));
+        graph.add_directed_edge(0, 1, Checked::Value(0), Checked::Value(1), Checked::Value(cost)).unwrap();
+        graph.add_directed_edge(1, 2, Checked::Value(0), Checked::Value(1), Checked::Value(cost)).unwrap();
+        assert_eq!(solver.solve(&mut graph), Status::Overflow, "{}", solver.name());
+    }
+}
+
+#[test]
+fn without_overflow_random() {
+    let mut rng = XorShift::new(8140);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 10) as usize, rng.gen_range(1, 30) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let mut expected_graph = graph.clone();
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut expected_graph);
+        for mut solver in solvers() {
+            let mut graph = checked_graph(&graph);
+            assert_eq!(solver.solve(&mut graph), expected, "{}", solver.name());
+            if expected == Status::Optimal {
+                assert_eq!(graph.minimum_cost(), Checked::Value(expected_graph.minimum_cost()), "{}", solver.name());
+            }
+        }
+    }
+}
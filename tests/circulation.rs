@@ -0,0 +1,86 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::circulation::minimum_cost_circulation;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+fn check_circulation(graph: &Graph<i64>) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge in (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()) {
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    assert!(balance.iter().all(|&b| b == 0));
+}
+
+#[test]
+fn zero_circulation_is_optimal() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 5, 2).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, -1).unwrap();
+    graph.add_directed_edge(2, 0, 0, 5, 0).unwrap();
+    assert_eq!(minimum_cost_circulation(&mut graph), Status::Optimal);
+    check_circulation(&graph);
+    assert_eq!(graph.minimum_cost(), 0);
+    assert!((0..graph.num_edges()).all(|edge_id| graph.get_edge(edge_id).unwrap().flow == 0));
+}
+
+#[test]
+fn negative_cycle_is_saturated() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 3, -5).unwrap();
+    graph.add_directed_edge(1, 0, 0, 10, 1).unwrap();
+    // a lower bound forces a cycle that costs
+    graph.add_directed_edge(1, 2, 2, 4, 1).unwrap();
+    graph.add_directed_edge(2, 1, 0, 4, 1).unwrap();
+    assert_eq!(minimum_cost_circulation(&mut graph), Status::Optimal);
+    check_circulation(&graph);
+    assert_eq!(graph.minimum_cost(), 3 * -4 + 2 * 2);
+}
+
+#[test]
+fn bad_input_and_infeasible() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 0, 3, -1).unwrap();
+    graph.add_directed_edge(1, 0, 0, 3, 1).unwrap();
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    assert_eq!(minimum_cost_circulation(&mut graph), Status::BadInput);
+
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 2, 3, 1).unwrap();
+    graph.add_directed_edge(1, 0, 0, 1, 1).unwrap();
+    assert_eq!(minimum_cost_circulation(&mut graph), Status::Infeasible);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(7732);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(1, 12) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 40) {
+            let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, 3) } else { 0 };
+            graph
+                .add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), lower, lower + rng.gen_range(0, 10), rng.gen_range(-10, 10))
+                .unwrap();
+        }
+        let mut expected = graph.clone();
+        let expected_status = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(expected.num_edges()), &mut expected);
+
+        assert_eq!(minimum_cost_circulation(&mut graph), expected_status);
+        if expected_status == Status::Optimal {
+            check_circulation(&graph);
+            assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+        }
+    }
+}
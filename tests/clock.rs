@@ -0,0 +1,102 @@
+use network_algorithms::clock::{Clock, TimeLimit};
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::{graph, status};
+use network_algorithms::metrics::{labeled, set_clock, InMemoryMetrics, MetricValue, SOLVE_SECONDS};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(Default)]
+struct StepClock {
+    now: Cell<u64>,
+}
+
+impl Clock for StepClock {
+    fn now_millis(&self) -> u64 {
+        self.now.set(self.now.get() + 1);
+        self.now.get()
+    }
+}
+
+// StepClock for set_clock, which needs Sync
+#[derive(Default)]
+struct AtomicStepClock {
+    now: AtomicU64,
+}
+
+impl Clock for AtomicStepClock {
+    fn now_millis(&self) -> u64 {
+        self.now.fetch_add(250, Ordering::Relaxed) + 250
+    }
+}
+
+fn minimum_cost_flow_graph() -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 1, 2).unwrap();
+    graph.add_directed_edge(1, 2, 0, 1, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 1, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 2, 1).unwrap();
+    graph.add_supply(0, 2);
+    graph.add_demand(3, 2);
+    graph
+}
+
+fn maximum_flow_graph() -> graph::Graph<i64> {
+    let mut graph = graph::Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 2).unwrap();
+    graph.add_directed_edge(0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 1).unwrap();
+    graph.add_directed_edge(2, 3, 2).unwrap();
+    graph
+}
+
+#[test]
+fn primal_network_simplex_time_limit() {
+    let mut graph = minimum_cost_flow_graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_time_limit(TimeLimit::new(0, Box::new(StepClock::default())));
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::TimeLimit);
+
+    let mut graph = minimum_cost_flow_graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_time_limit(TimeLimit::new(1000, Box::new(StepClock::default())));
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 6);
+}
+
+#[test]
+fn dinic_time_limit() {
+    let mut graph = maximum_flow_graph();
+    let mut solver = Dinic::default();
+    solver.set_time_limit(TimeLimit::new(0, Box::new(StepClock::default())));
+    assert_eq!(solver.solve(0, 3, &mut graph), status::Status::TimeLimit);
+
+    let mut graph = maximum_flow_graph();
+    let mut solver = Dinic::default();
+    solver.set_time_limit(TimeLimit::with_std_clock(60_000));
+    assert_eq!(solver.solve(0, 3, &mut graph), status::Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 3);
+}
+
+#[test]
+fn solve_seconds_from_clock() {
+    // the other tests have no metrics sink, so they do not read the clock
+    set_clock(Some(Arc::new(AtomicStepClock::default())));
+    let metrics = Arc::new(InMemoryMetrics::default());
+    let mut graph = minimum_cost_flow_graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_metrics_sink(metrics.clone());
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    set_clock(None);
+
+    // read at the start and at the end of the solve
+    assert_eq!(metrics.get(&labeled(SOLVE_SECONDS, &[("algorithm", "primal_network_simplex")])), Some(MetricValue::Histogram { count: 1, sum: 0.25 }));
+}
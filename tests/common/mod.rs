@@ -0,0 +1,49 @@
+#![allow(dead_code)]
+use network_algorithms::minimum_cost_flow::graph::Graph;
+
+// small deterministic generator so that the tests do not need an extra dependency
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1 }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // [lower, upper]
+    pub fn gen_range(&mut self, lower: i64, upper: i64) -> i64 {
+        lower + (self.next_u64() % (upper - lower + 1) as u64) as i64
+    }
+
+    pub fn gen_index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+// balanced supplies, non-negative lower bounds, costs in [-max_cost, max_cost]
+pub fn random_minimum_cost_flow_graph(rng: &mut XorShift, num_nodes: usize, num_edges: usize, max_capacity: i64, max_cost: i64) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, max_capacity / 4) } else { 0 };
+        let upper = lower + rng.gen_range(0, max_capacity);
+        let cost = rng.gen_range(-max_cost, max_cost);
+        graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+    }
+    for _ in 0..num_nodes {
+        let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        let amount = rng.gen_range(0, max_capacity);
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+    }
+    graph
+}
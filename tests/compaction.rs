@@ -0,0 +1,174 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::compaction::CompactionMap;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+fn solve(graph: &mut minimum_cost_flow::graph::Graph<i64>) -> Status {
+    PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+}
+
+// isolated nodes and edges that can carry nothing, between the others
+fn add_holes(rng: &mut XorShift, graph: &mut minimum_cost_flow::graph::Graph<i64>) {
+    let num_nodes = graph.num_nodes();
+    for _ in 0..rng.gen_range(0, 5) {
+        graph.add_node();
+    }
+    for _ in 0..rng.gen_range(0, 5) {
+        graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), 0, 0, rng.gen_range(-10, 10)).unwrap();
+    }
+}
+
+// everything the compacted graph has is the same as in the original under the map
+fn check_mapped(original: &minimum_cost_flow::graph::Graph<i64>, compacted: &minimum_cost_flow::graph::Graph<i64>, map: &CompactionMap) {
+    for u in 0..compacted.num_nodes() {
+        let original_u = map.original_node(u).unwrap();
+        assert_eq!(map.translate_node(original_u), Some(u));
+        assert_eq!((compacted.excess(u), compacted.potential(u), compacted.node_cost(u)), (original.excess(original_u), original.potential(original_u), original.node_cost(original_u)));
+    }
+    for edge_id in 0..compacted.num_edges() {
+        let original_edge_id = map.original_edge(edge_id).unwrap();
+        assert_eq!(map.translate_edge(original_edge_id), Some(edge_id));
+        let (edge, original_edge) = (compacted.get_edge(edge_id).unwrap(), original.get_edge(original_edge_id).unwrap());
+        assert_eq!((map.original_node(edge.from), map.original_node(edge.to)), (Some(original_edge.from), Some(original_edge.to)));
+        assert_eq!((edge.flow, edge.lower, edge.upper, edge.cost), (original_edge.flow, original_edge.lower, original_edge.upper, original_edge.cost));
+    }
+    // what is dropped is an edge that carries nothing, or an isolated node
+    for edge_id in (0..original.num_edges()).filter(|&edge_id| map.translate_edge(edge_id).is_none()) {
+        let edge = original.get_edge(edge_id).unwrap();
+        assert_eq!((edge.lower, edge.upper, edge.flow), (0, 0, 0));
+    }
+    assert_eq!(compacted.minimum_cost(), original.minimum_cost());
+}
+
+#[test]
+fn minimum_cost_flow_solve_compact_solve() {
+    let mut rng = XorShift::new(767);
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(1, 10) as usize, rng.gen_range(0, 30) as usize);
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+        add_holes(&mut rng, &mut graph);
+        for u in 0..graph.num_nodes() {
+            if rng.gen_range(0, 3) == 0 {
+                graph.set_node_cost(u, rng.gen_range(0, 5));
+            }
+        }
+        let status = solve(&mut graph);
+
+        let mut compacted = graph.clone();
+        let map = compacted.compact();
+        check_mapped(&graph, &compacted, &map);
+
+        // the same optimum after solving again
+        let mut resolved = compacted.clone();
+        assert_eq!(solve(&mut resolved), status);
+        if status == Status::Optimal {
+            assert_eq!(resolved.minimum_cost(), graph.minimum_cost());
+        }
+
+        // compacting again changes nothing
+        let mut again = compacted.clone();
+        let identity = again.compact();
+        assert!(identity.is_identity());
+        check_mapped(&compacted, &again, &identity);
+        assert!((0..again.num_nodes()).all(|u| identity.translate_node(u) == Some(u)));
+        assert!((0..again.num_edges()).all(|edge_id| identity.translate_edge(edge_id) == Some(edge_id)));
+    }
+}
+
+#[test]
+fn composition() {
+    let mut rng = XorShift::new(7670);
+    for _ in 0..100 {
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, 8, 20, 10, 10);
+        add_holes(&mut rng, &mut graph);
+        let original = graph.clone();
+        let first = graph.compact();
+        let (first_num_nodes, first_num_edges) = (graph.num_nodes(), graph.num_edges());
+
+        // new holes, and a former one is added to the next compaction
+        add_holes(&mut rng, &mut graph);
+        let intermediate = graph.clone();
+        let second = graph.compact();
+        let composed = first.then(&second);
+
+        for u in 0..original.num_nodes() {
+            assert_eq!(composed.translate_node(u), first.translate_node(u).and_then(|u| second.translate_node(u)));
+        }
+        for edge_id in 0..original.num_edges() {
+            assert_eq!(composed.translate_edge(edge_id), first.translate_edge(edge_id).and_then(|edge_id| second.translate_edge(edge_id)));
+        }
+        // the nodes and edges added after the first compaction have no original
+        for u in 0..graph.num_nodes() {
+            let intermediate_u = second.original_node(u).unwrap();
+            assert_eq!(composed.original_node(u), (intermediate_u < first_num_nodes).then(|| first.original_node(intermediate_u).unwrap()));
+        }
+        for edge_id in 0..graph.num_edges() {
+            let intermediate_edge_id = second.original_edge(edge_id).unwrap();
+            assert_eq!(composed.original_edge(edge_id), (intermediate_edge_id < first_num_edges).then(|| first.original_edge(intermediate_edge_id).unwrap()));
+        }
+        check_mapped(&intermediate, &graph, &second);
+    }
+}
+
+#[test]
+fn slack_node_is_kept() {
+    let mut graph = minimum_cost_flow::graph::Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(1, 3, 0, 5, 1).unwrap();
+    graph.add_supply(1, 4);
+    graph.add_demand(3, 3);
+    // node 2 takes the extra unit, which it cannot get
+    graph.set_balance_policy(minimum_cost_flow::graph::BalancePolicy::SlackNode { node: Some(2), cost_per_unit: 1, max_imbalance: 1 });
+    assert_eq!(solve(&mut graph.clone()), Status::Infeasible);
+
+    let map = graph.compact();
+    assert_eq!((0..4).map(|u| map.translate_node(u)).collect::<Vec<_>>(), vec![None, Some(0), Some(1), Some(2)]);
+    assert_eq!(graph.balance_policy(), minimum_cost_flow::graph::BalancePolicy::SlackNode { node: Some(1), cost_per_unit: 1, max_imbalance: 1 });
+    assert_eq!(solve(&mut graph), Status::Infeasible);
+
+    // the old node 3 takes it instead
+    graph.set_balance_policy(minimum_cost_flow::graph::BalancePolicy::SlackNode { node: Some(2), cost_per_unit: 1, max_imbalance: 1 });
+    assert_eq!(solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 4);
+}
+
+#[test]
+fn maximum_flow_solve_compact_solve() {
+    let mut rng = XorShift::new(7671);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let mut graph = maximum_flow::graph::Graph::default();
+        graph.add_nodes(num_nodes + rng.gen_range(0, 4) as usize);
+        for _ in 0..rng.gen_range(1, 30) {
+            let upper = if rng.gen_range(0, 3) == 0 { 0 } else { rng.gen_range(1, 10) };
+            graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), upper).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+        Dinic::default().solve(source, sink, &mut graph);
+
+        let mut compacted = graph.clone();
+        let map = compacted.compact();
+        for edge_id in 0..compacted.num_edges() {
+            let (edge, original_edge) = (compacted.get_edge(edge_id).unwrap(), graph.get_edge(map.original_edge(edge_id).unwrap()).unwrap());
+            assert_eq!((map.original_node(edge.from), map.original_node(edge.to), edge.flow, edge.upper), (Some(original_edge.from), Some(original_edge.to), original_edge.flow, original_edge.upper));
+        }
+        assert!((0..graph.num_edges()).all(|edge_id| map.translate_edge(edge_id).is_some() || graph.get_edge(edge_id).unwrap().upper == 0));
+
+        let expected = graph.maximum_flow(source);
+        match (map.translate_node(source), map.translate_node(sink)) {
+            (Some(source), Some(sink)) if source != sink => {
+                assert_eq!(compacted.maximum_flow(source), expected);
+                Dinic::default().solve(source, sink, &mut compacted);
+                assert_eq!(compacted.maximum_flow(source), expected);
+            }
+            _ => assert_eq!(expected, 0),
+        }
+        assert!(compacted.clone().compact().is_identity());
+    }
+}
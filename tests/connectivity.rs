@@ -0,0 +1,156 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::connectivity::*;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::status::Status;
+
+fn unit_graph(num_nodes: usize, edges: &[(usize, usize)]) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for &(u, v) in edges.iter() {
+        graph.add_directed_edge(u, v, 1).unwrap();
+    }
+    graph
+}
+
+fn cycle(num_nodes: usize) -> Vec<(usize, usize)> {
+    (0..num_nodes).map(|u| (u, (u + 1) % num_nodes)).collect()
+}
+
+fn complete(num_nodes: usize) -> Vec<(usize, usize)> {
+    (0..num_nodes).flat_map(|u| (u + 1..num_nodes).map(move |v| (u, v))).collect()
+}
+
+// the minimum number of edges whose removal disconnects v from u
+fn brute_force_edge_connectivity(num_nodes: usize, edges: &[(usize, usize)], u: usize, v: usize, orientation: Orientation) -> i64 {
+    (0..1u32 << edges.len())
+        .filter(|&removed| {
+            let mut visited = vec![false; num_nodes];
+            let mut stack = vec![u];
+            visited[u] = true;
+            while let Some(x) = stack.pop() {
+                for (_, &(a, b)) in edges.iter().enumerate().filter(|&(i, _)| removed >> i & 1 == 0) {
+                    let next = if a == x {
+                        Some(b)
+                    } else if b == x && orientation == Orientation::Undirected {
+                        Some(a)
+                    } else {
+                        None
+                    };
+                    if let Some(y) = next.filter(|&y| !visited[y]) {
+                        visited[y] = true;
+                        stack.push(y);
+                    }
+                }
+            }
+            !visited[v]
+        })
+        .map(|removed| removed.count_ones() as i64)
+        .min()
+        .unwrap()
+}
+
+#[test]
+fn standard_graphs() {
+    // cycles
+    let graph = unit_graph(6, &cycle(6));
+    assert_eq!(edge_connectivity(0, 3, &graph, Orientation::Directed), Ok(1));
+    assert_eq!(edge_connectivity(0, 3, &graph, Orientation::Undirected), Ok(2));
+    assert_eq!(global_edge_connectivity(&graph, Orientation::Directed), Ok(1));
+    assert_eq!(global_edge_connectivity(&graph, Orientation::Undirected), Ok(2));
+    assert_eq!(is_k_connected(&[0, 2, 4], 2, &graph, Orientation::Undirected), Ok(true));
+    assert_eq!(is_k_connected(&[0, 2, 4], 2, &graph, Orientation::Directed), Ok(false));
+
+    // complete graphs
+    for num_nodes in 2..7 {
+        let graph = unit_graph(num_nodes, &complete(num_nodes));
+        assert_eq!(global_edge_connectivity(&graph, Orientation::Undirected), Ok(num_nodes as i64 - 1));
+        // every edge is in one direction only, so node 0 is not reachable
+        assert_eq!(global_edge_connectivity(&graph, Orientation::Directed), Ok(0));
+        let terminals: Vec<usize> = (0..num_nodes).collect();
+        assert_eq!(is_k_connected(&terminals, num_nodes as i64 - 1, &graph, Orientation::Undirected), Ok(true));
+        assert_eq!(is_k_connected(&terminals, num_nodes as i64, &graph, Orientation::Undirected), Ok(false));
+    }
+
+    // two triangles joined by a bridge
+    let graph = unit_graph(6, &[(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)]);
+    assert_eq!(global_edge_connectivity(&graph, Orientation::Undirected), Ok(1));
+    assert_eq!(edge_connectivity(0, 1, &graph, Orientation::Undirected), Ok(2));
+    assert_eq!(edge_connectivity(0, 5, &graph, Orientation::Undirected), Ok(1));
+    assert_eq!(is_k_connected(&[0, 1, 2], 2, &graph, Orientation::Undirected), Ok(true));
+    assert_eq!(is_k_connected(&[0, 1, 5], 2, &graph, Orientation::Undirected), Ok(false));
+
+    // weighted
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    graph.add_directed_edge(1, 2, 3).unwrap();
+    graph.add_directed_edge(0, 2, 4).unwrap();
+    assert_eq!(edge_connectivity(0, 2, &graph, Orientation::Directed), Ok(7));
+    assert_eq!(global_edge_connectivity(&graph, Orientation::Undirected), Ok(7));
+}
+
+#[test]
+fn brute_force() {
+    let mut rng = XorShift::new(759);
+    for orientation in [Orientation::Directed, Orientation::Undirected] {
+        for _ in 0..100 {
+            let num_nodes = rng.gen_range(2, 5) as usize;
+            let edges: Vec<(usize, usize)> = (0..rng.gen_range(1, 9)).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes))).collect();
+            let graph = unit_graph(num_nodes, &edges);
+
+            let mut expected_global = i64::MAX;
+            for u in 0..num_nodes {
+                for v in (0..num_nodes).filter(|&v| v != u) {
+                    let expected = brute_force_edge_connectivity(num_nodes, &edges, u, v, orientation);
+                    assert_eq!(edge_connectivity(u, v, &graph, orientation), Ok(expected));
+                    expected_global = expected_global.min(expected);
+                }
+            }
+            assert_eq!(global_edge_connectivity(&graph, orientation), Ok(expected_global));
+
+            let terminals: Vec<usize> = (0..num_nodes).collect();
+            for k in 0..4 {
+                assert_eq!(is_k_connected(&terminals, k, &graph, orientation), Ok(k <= expected_global));
+            }
+        }
+    }
+}
+
+#[test]
+fn early_cutoff() {
+    // a complete graph on the nodes other than 1, and the leaf 1 found by the first flow
+    let num_nodes = 8;
+    let mut edges: Vec<(usize, usize)> = complete(num_nodes).into_iter().map(|(u, v)| (u + (u > 0) as usize, v + 1)).collect();
+    edges.push((2, 1));
+    let graph = unit_graph(num_nodes + 1, &edges);
+
+    // the flows stop at the value of the leaf found first
+    let mut connectivity = Connectivity::new(Orientation::Undirected);
+    let mut num_full_augmentations = 0;
+    for u in 1..=num_nodes {
+        connectivity.edge_connectivity(0, u, &graph).unwrap();
+        num_full_augmentations += connectivity.num_augmentations();
+    }
+    assert_eq!(connectivity.global_edge_connectivity(&graph), Ok(1));
+    assert!(connectivity.num_augmentations() * 4 < num_full_augmentations, "{} {}", connectivity.num_augmentations(), num_full_augmentations);
+
+    // the flows stop at k
+    let terminals: Vec<usize> = (0..=num_nodes).filter(|&u| u != 1).collect();
+    assert_eq!(connectivity.is_k_connected(&terminals, 2, &graph), Ok(true));
+    assert_eq!(connectivity.num_augmentations(), 2 * (num_nodes - 1));
+    assert_eq!(connectivity.is_k_connected(&terminals, 7, &graph), Ok(true));
+    assert_eq!(connectivity.is_k_connected(&terminals, 8, &graph), Ok(false));
+    assert_eq!(connectivity.num_augmentations(), 7);
+}
+
+#[test]
+fn bad_input() {
+    let graph = unit_graph(3, &cycle(3));
+    assert_eq!(edge_connectivity(0, 0, &graph, Orientation::Directed), Err(Status::BadInput));
+    assert_eq!(edge_connectivity(0, 3, &graph, Orientation::Directed), Err(Status::BadInput));
+    assert_eq!(is_k_connected(&[0, 3], 1, &graph, Orientation::Directed), Err(Status::BadInput));
+    assert_eq!(is_k_connected(&[], 1, &graph, Orientation::Directed), Ok(true));
+    assert_eq!(global_edge_connectivity(&unit_graph(1, &[]), Orientation::Directed), Err(Status::BadInput));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::consistency::{ConsistencyChecks, InjectedFault, InvariantViolation};
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+#[test]
+fn all_checks_pass_on_correct_solves() {
+    let mut rng = XorShift::new(776);
+    for _ in 0..50 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 100) as usize, rng.gen_range(1, 500) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 30, 50);
+        let mut expected = graph.clone();
+        let expected_status = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut expected);
+
+        let interval = rng.gen_range(1, 10) as usize;
+        let mut checked = graph.clone();
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_consistency_checks(ConsistencyChecks::ALL, interval);
+        assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(num_edges), &mut checked), expected_status);
+        assert_eq!(solver.invariant_violation(), None);
+        // a round every interval pivots and one at the end
+        assert_eq!(solver.num_consistency_rounds(), solver.num_pivots() / interval + 1);
+        if expected_status == Status::Optimal {
+            assert_eq!(checked.minimum_cost(), expected.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn none_runs_no_rounds() {
+    let mut rng = XorShift::new(7761);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 50, 300, 30, 50);
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_consistency_checks(ConsistencyChecks::NONE, 1);
+    solver.set_injected_fault(InjectedFault::Excess, 1);
+    solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph);
+    assert_eq!(solver.num_consistency_rounds(), 0);
+    assert_eq!(solver.invariant_violation(), None);
+}
+
+#[test]
+fn each_check_catches_its_fault() {
+    let mut rng = XorShift::new(7762);
+    let graph = random_minimum_cost_flow_graph(&mut rng, 30, 200, 30, 50);
+    let mut solved = graph.clone();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut solved);
+    let num_pivots = solver.num_pivots();
+    assert!(num_pivots >= 4);
+
+    for (check, fault) in [
+        (ConsistencyChecks::PERIODIC_EXCESS_SUM, InjectedFault::Excess),
+        (ConsistencyChecks::TREE_STRUCTURE, InjectedFault::Parent),
+        (ConsistencyChecks::REDUCED_COST_SAMPLE, InjectedFault::Potential),
+        (ConsistencyChecks::FLOW_BOUNDS_FULL, InjectedFault::Flow),
+    ] {
+        // at a pivot in the middle, and at the round when the pivots end
+        for at_pivot in [2, num_pivots] {
+            let mut corrupted = graph.clone();
+            let mut solver = PrimalNetworkSimplex::default();
+            solver.set_consistency_checks(check, 2);
+            solver.set_injected_fault(fault, at_pivot);
+            let status = solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut corrupted);
+            assert_eq!(status, Status::InternalInvariant, "{check}");
+            assert_eq!(solver.invariant_violation(), Some(InvariantViolation { check, num_pivots: at_pivot }));
+            // the artificial nodes and edges are removed as after any solve
+            assert_eq!((corrupted.num_nodes(), corrupted.num_edges()), (graph.num_nodes(), graph.num_edges()));
+        }
+
+        // with all the checks on, the fault is caught in the same round
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_consistency_checks(ConsistencyChecks::ALL, 2);
+        solver.set_injected_fault(fault, 2);
+        assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph.clone()), Status::InternalInvariant);
+        assert_eq!(solver.invariant_violation().unwrap().num_pivots, 2);
+    }
+}
+
+#[test]
+fn display() {
+    assert_eq!(ConsistencyChecks::NONE.to_string(), "NONE");
+    assert_eq!((ConsistencyChecks::TREE_STRUCTURE | ConsistencyChecks::PERIODIC_EXCESS_SUM).to_string(), "PERIODIC_EXCESS_SUM | TREE_STRUCTURE");
+    let violation = InvariantViolation { check: ConsistencyChecks::FLOW_BOUNDS_FULL, num_pivots: 7 };
+    assert_eq!(violation.to_string(), "FLOW_BOUNDS_FULL failed after 7 pivots");
+    assert_eq!(ConsistencyChecks::from_bits_truncate(0xff), ConsistencyChecks::ALL);
+}
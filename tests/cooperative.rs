@@ -0,0 +1,211 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::cooperative::{Control, YieldHook};
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BestEligibleArcPivotRule, BlockSearchPivotRule, CandidateListPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn recording_hook(control: Control) -> (YieldHook, Rc<RefCell<Vec<u64>>>) {
+    let counts = Rc::new(RefCell::new(Vec::new()));
+    let recorded = counts.clone();
+    let hook = Box::new(move |count| {
+        recorded.borrow_mut().push(count);
+        control
+    });
+    (hook, counts)
+}
+
+fn flows(graph: &Graph<i64>) -> Vec<i64> {
+    (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect()
+}
+
+#[test]
+fn hook_is_called_within_granularity_during_full_scans() {
+    let mut rng = XorShift::new(770);
+    let (num_nodes, granularity) = (100, 10_000);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, 30_000, 100, 100);
+    let (hook, counts) = recording_hook(Control::Continue);
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_work_counter(granularity, hook);
+
+    // every pivot scans all the edges
+    let status = solver.solve(&mut BestEligibleArcPivotRule::new(graph.num_edges()), &mut graph);
+    assert_ne!(status, Status::Paused);
+    let counts = counts.borrow();
+    assert!(counts.len() as u64 >= solver.num_pivots() as u64 * 30_000 / granularity);
+    // a scan ticks each edge, and a cycle at most all the nodes at once
+    let mut previous = 0;
+    for &count in counts.iter() {
+        assert!(granularity <= count - previous && count - previous <= granularity + num_nodes as u64, "{previous} {count}");
+        previous = count;
+    }
+    assert!(solver.work_count() - previous < granularity + num_nodes as u64);
+}
+
+#[test]
+fn no_hook_no_pause() {
+    let mut rng = XorShift::new(7701);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 30, 100, 20, 20);
+    let mut expected = graph.clone();
+    let expected_status = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(expected.num_edges()), &mut expected);
+
+    let mut solver = PrimalNetworkSimplex::default();
+    let mut pivot = BlockSearchPivotRule::new(graph.num_edges());
+    let mut session = solver.session(&mut pivot, &mut graph);
+    assert_eq!(session.resume(), expected_status);
+    drop(session);
+    assert_eq!(flows(&graph), flows(&expected));
+}
+
+fn primal_network_simplex_session<Pivot: PivotRule<i64>>(seed: u64) {
+    let mut rng = XorShift::new(seed);
+    for _ in 0..30 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 40) as usize, rng.gen_range(1, 200) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 30, 50);
+        let granularity = rng.gen_range(1, 50) as u64;
+
+        let mut expected = graph.clone();
+        let expected_status = PrimalNetworkSimplex::default().solve(&mut Pivot::new(num_edges), &mut expected);
+
+        // the same pivots as without the hook
+        let mut continued = graph.clone();
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_work_counter(granularity, recording_hook(Control::Continue).0);
+        assert_eq!(solver.solve(&mut Pivot::new(num_edges), &mut continued), expected_status);
+        assert_eq!(flows(&continued), flows(&expected));
+
+        // solve does not stop for the pauses
+        let mut paused = graph.clone();
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_work_counter(granularity, recording_hook(Control::Pause).0);
+        assert_eq!(solver.solve(&mut Pivot::new(num_edges), &mut paused), expected_status);
+
+        let mut resumed = graph.clone();
+        let mut pivot = Pivot::new(num_edges);
+        let (hook, counts) = recording_hook(Control::Pause);
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_work_counter(granularity, hook);
+        let mut session = solver.session(&mut pivot, &mut resumed);
+        let mut num_pauses = 0;
+        while session.resume() == Status::Paused {
+            num_pauses += 1;
+        }
+        assert_eq!(session.status(), expected_status);
+        assert_eq!(session.resume(), expected_status);
+        drop(session);
+        assert!(num_pauses <= counts.borrow().len() && counts.borrow().len() <= num_pauses + 1);
+        assert_eq!(resumed.num_nodes(), num_nodes);
+        assert_eq!(resumed.num_edges(), num_edges);
+        if expected_status == Status::Optimal {
+            assert_eq!(resumed.minimum_cost(), expected.minimum_cost());
+            assert_eq!(paused.minimum_cost(), expected.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn primal_network_simplex_session_best_eligible_arc() {
+    primal_network_simplex_session::<BestEligibleArcPivotRule<i64>>(7702);
+}
+
+#[test]
+fn primal_network_simplex_session_block_search() {
+    primal_network_simplex_session::<BlockSearchPivotRule<i64>>(7703);
+}
+
+#[test]
+fn primal_network_simplex_session_candidate_list() {
+    primal_network_simplex_session::<CandidateListPivotRule<i64>>(7704);
+}
+
+#[test]
+fn dropped_session_removes_artificial_nodes() {
+    let mut rng = XorShift::new(7705);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 20, 80, 30, 50);
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_work_counter(1, recording_hook(Control::Pause).0);
+    let mut pivot = BlockSearchPivotRule::new(graph.num_edges());
+    let mut session = solver.session(&mut pivot, &mut graph);
+    assert_eq!(session.resume(), Status::Paused);
+    drop(session);
+    assert_eq!((graph.num_nodes(), graph.num_edges()), (20, 80));
+}
+
+#[test]
+fn session_rejects_unbalanced_graph() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 0, 10, 1).unwrap();
+    graph.add_supply(0, 5);
+    let mut solver = PrimalNetworkSimplex::default();
+    let mut pivot = BlockSearchPivotRule::new(graph.num_edges());
+    assert_eq!(solver.session(&mut pivot, &mut graph).resume(), Status::Unbalanced);
+    assert_eq!(SuccessiveShortestPath::default().session(&mut graph).resume(), Status::Unbalanced);
+}
+
+#[test]
+fn successive_shortest_path_session() {
+    let mut rng = XorShift::new(7706);
+    for tree_repair in [false, true] {
+        for _ in 0..30 {
+            let (num_nodes, num_edges) = (rng.gen_range(2, 40) as usize, rng.gen_range(1, 200) as usize);
+            let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 30, 50);
+
+            let mut expected = graph.clone();
+            let mut solver = SuccessiveShortestPath::default();
+            solver.set_tree_repair(tree_repair);
+            let expected_status = solver.solve(&mut expected);
+
+            // a paused search goes on from the same heap, so the paths are the same
+            let mut resumed = graph.clone();
+            let mut solver = SuccessiveShortestPath::default();
+            solver.set_tree_repair(tree_repair);
+            solver.set_work_counter(rng.gen_range(1, 20) as u64, recording_hook(Control::Pause).0);
+            let mut session = solver.session(&mut resumed);
+            while session.resume() == Status::Paused {}
+            assert_eq!(session.status(), expected_status);
+            assert_eq!(flows(&resumed), flows(&expected));
+        }
+    }
+}
+
+#[test]
+fn dinic_session() {
+    let mut rng = XorShift::new(7707);
+    for _ in 0..50 {
+        let num_nodes = rng.gen_range(2, 40) as usize;
+        let mut graph = maximum_flow::graph::Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(1, 200) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 30)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), maximum_flow::status::Status::Optimal);
+
+        let mut resumed = graph.clone();
+        let mut solver = Dinic::default();
+        let (hook, counts) = recording_hook(Control::Pause);
+        solver.set_work_counter(rng.gen_range(1, 20) as u64, hook);
+        let mut session = solver.session(source, sink, &mut resumed);
+        let mut num_pauses = 0;
+        while session.resume() == maximum_flow::status::Status::Paused {
+            num_pauses += 1;
+        }
+        assert_eq!(session.status(), maximum_flow::status::Status::Optimal);
+        // the last pause may come when nothing is left to do
+        assert!(num_pauses <= counts.borrow().len() && counts.borrow().len() <= num_pauses + 1);
+        assert_eq!(resumed.maximum_flow(source), expected.maximum_flow(source));
+        let flows = |graph: &maximum_flow::graph::Graph<i64>| (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect::<Vec<_>>();
+        assert_eq!(flows(&resumed), flows(&expected));
+    }
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::corridor::CorridorDirection;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+#[test]
+fn parallel_edges_both_ways() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 7);
+    graph.add_demand(1, 7);
+    // two tariff bands, and an edge back with a negative cost
+    let cheap = graph.add_directed_edge(0, 1, 0, 4, 1).unwrap().index();
+    let expensive = graph.add_directed_edge(0, 1, 0, 10, 3).unwrap().index();
+    let back = graph.add_directed_edge(1, 0, 0, 2, -4).unwrap().index();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    // 4 + 5 units go forward, and the negative cost edge sends 2 of them back
+    assert_eq!((0..3).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect::<Vec<_>>(), vec![4, 5, 2]);
+
+    let forward = graph.corridor_between(0, 1).unwrap();
+    assert_eq!(forward.edge_ids, vec![cheap, expensive]);
+    assert_eq!((forward.net_flow, forward.gross_flow, forward.capacity, forward.cost, forward.is_saturated), (9, 9, 14, 4 + 15, true));
+    assert_eq!(forward.average_cost(), Some(19.0 / 9.0));
+    assert_eq!(graph.corridor_between(1, 0).unwrap().edge_ids, vec![back]);
+    assert_eq!(graph.corridor_between(2, 1), None);
+    assert_eq!(graph.corridors().count(), 3);
+
+    graph.set_corridor_direction(CorridorDirection::Unordered);
+    let both = graph.corridor_between(1, 0).unwrap();
+    assert_eq!((both.from, both.to), (0, 1));
+    assert_eq!(both.edge_ids, vec![cheap, expensive, back]);
+    assert_eq!((both.net_flow, both.gross_flow, both.capacity, both.cost), (7, 11, 16, 19 - 8));
+    assert_eq!(graph.corridor_between(0, 1), Some(both));
+    assert_eq!(graph.corridor_between(2, 1).unwrap().edge_ids, vec![3]);
+    assert_eq!(graph.corridors().count(), 2);
+
+    // the index follows the edges added after a query
+    let added = graph.add_directed_edge(1, 0, 0, 1, 0).unwrap().index();
+    assert_eq!(graph.corridor_between(0, 1).unwrap().edge_ids, vec![cheap, expensive, back, added]);
+}
+
+#[test]
+fn totals_reconcile_with_the_edges() {
+    let mut rng = XorShift::new(774);
+    for direction in [CorridorDirection::Ordered, CorridorDirection::Unordered] {
+        for _ in 0..50 {
+            let (num_nodes, num_edges) = (rng.gen_range(2, 8) as usize, rng.gen_range(1, 40) as usize);
+            let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 20, 20);
+            graph.set_corridor_direction(direction);
+            PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph);
+
+            let corridors: Vec<_> = graph.corridors().collect();
+            let mut edge_ids: Vec<usize> = corridors.iter().flat_map(|corridor| corridor.edge_ids.clone()).collect();
+            edge_ids.sort();
+            assert_eq!(edge_ids, (0..graph.num_edges()).collect::<Vec<_>>());
+
+            let edges: Vec<_> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).collect();
+            assert_eq!(corridors.iter().map(|corridor| corridor.cost).sum::<i64>(), graph.minimum_cost());
+            assert_eq!(corridors.iter().map(|corridor| corridor.gross_flow).sum::<i64>(), edges.iter().map(|edge| edge.flow).sum::<i64>());
+            assert_eq!(corridors.iter().map(|corridor| corridor.capacity).sum::<i64>(), edges.iter().map(|edge| edge.upper).sum::<i64>());
+            for corridor in corridors.iter() {
+                let net: i64 = corridor
+                    .edge_ids
+                    .iter()
+                    .map(|&edge_id| if edges[edge_id].from == corridor.from { edges[edge_id].flow } else { -edges[edge_id].flow })
+                    .sum();
+                assert_eq!(corridor.net_flow, net);
+                assert!(corridor
+                    .edge_ids
+                    .iter()
+                    .all(|&edge_id| graph.corridor_between(edges[edge_id].from, edges[edge_id].to).as_ref() == Some(corridor)));
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::analysis::CostRange;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+// (from, to, lower, upper, cost) and the supplies
+fn build(edges: &[(usize, usize, i64, i64, i64)], supplies: &[i64]) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(supplies.len());
+    for (u, &supply) in supplies.iter().enumerate() {
+        graph.add_supply(u, supply);
+    }
+    for &(from, to, lower, upper, cost) in edges.iter() {
+        graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+    }
+    graph
+}
+
+fn solve(graph: &mut Graph<i64>) -> (PrimalNetworkSimplex<i64>, Status) {
+    let mut solver = PrimalNetworkSimplex::default();
+    let status = solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph);
+    (solver, status)
+}
+
+#[test]
+fn two_paths() {
+    // 0 -> 1 -> 3 costs 2 for up to 2 units and 0 -> 2 -> 3 costs 6, and 3 units are sent
+    let edges = [(0, 1, 0, 2, 1), (1, 3, 0, 5, 1), (0, 2, 0, 5, 3), (2, 3, 0, 5, 3)];
+    let mut graph = build(&edges, &[3, 0, 0, -3]);
+    let (solver, status) = solve(&mut graph);
+    assert_eq!(status, Status::Optimal);
+    let cost_ranges = solver.cost_ranging().unwrap();
+    // the first path stays the cheaper one while it costs less than 6, and the second one while it costs more than 2
+    assert_eq!(cost_ranges[0], CostRange { lower_delta: None, upper_delta: Some(4) });
+    assert_eq!(cost_ranges[1].upper_delta, Some(4));
+    assert_eq!(cost_ranges[2].lower_delta, Some(-4));
+    assert_eq!(cost_ranges[3].lower_delta, Some(-4));
+
+    assert_eq!(PrimalNetworkSimplex::<i64>::default().cost_ranging(), None);
+}
+
+#[test]
+fn random_perturbations_keep_the_flows_optimal() {
+    let mut rng = XorShift::new(777);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(1, 60) as usize);
+        let edges: Vec<_> = (0..num_edges)
+            .map(|_| {
+                let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, 3) } else { 0 };
+                (rng.gen_index(num_nodes), rng.gen_index(num_nodes), lower, lower + rng.gen_range(0, 10), rng.gen_range(-10, 10))
+            })
+            .collect();
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..num_nodes {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+
+        let mut graph = build(&edges, &supplies);
+        let (solver, status) = solve(&mut graph);
+        if status != Status::Optimal {
+            assert_eq!(solver.cost_ranging(), None);
+            continue;
+        }
+        let flows: Vec<i64> = (0..num_edges).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect();
+        let cost_ranges = solver.cost_ranging().unwrap();
+
+        for (edge_id, &CostRange { lower_delta, upper_delta }) in cost_ranges.iter().enumerate() {
+            assert!(lower_delta.is_none_or(|delta| delta <= 0) && upper_delta.is_none_or(|delta| delta >= 0));
+            // the ends of the range and a point in it
+            let lower = lower_delta.unwrap_or(-rng.gen_range(0, 100));
+            let upper = upper_delta.unwrap_or(rng.gen_range(0, 100));
+            for delta in [lower, upper, rng.gen_range(lower, upper)] {
+                let mut perturbed_edges = edges.clone();
+                perturbed_edges[edge_id].4 += delta;
+                let mut perturbed = build(&perturbed_edges, &supplies);
+                assert_eq!(solve(&mut perturbed).1, Status::Optimal);
+                // the flows of the basis are still optimal
+                let cost = perturbed_edges.iter().zip(flows.iter()).map(|(edge, &flow)| edge.4 * flow).sum::<i64>();
+                assert_eq!(perturbed.minimum_cost(), cost, "edge {edge_id} delta {delta}");
+            }
+        }
+    }
+}
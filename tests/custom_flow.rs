@@ -0,0 +1,148 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::flow_ext::FlowExt;
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::status::Status;
+use num_traits::{CheckedMul, Num, NumAssign, One, Zero};
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+// a units wrapper that implements the arithmetic traits, but not FromPrimitive
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default)]
+struct Units(i64);
+
+macro_rules! impl_op {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident) => {
+        impl $trait for Units {
+            type Output = Units;
+            fn $method(self, rhs: Units) -> Units {
+                Units(self.0.$method(rhs.0))
+            }
+        }
+
+        impl $assign_trait for Units {
+            fn $assign_method(&mut self, rhs: Units) {
+                self.0.$assign_method(rhs.0);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign);
+impl_op!(Sub, sub, SubAssign, sub_assign);
+impl_op!(Mul, mul, MulAssign, mul_assign);
+impl_op!(Div, div, DivAssign, div_assign);
+impl_op!(Rem, rem, RemAssign, rem_assign);
+
+impl Neg for Units {
+    type Output = Units;
+    fn neg(self) -> Units {
+        Units(-self.0)
+    }
+}
+
+impl Zero for Units {
+    fn zero() -> Self {
+        Units(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl One for Units {
+    fn one() -> Self {
+        Units(1)
+    }
+}
+
+impl CheckedMul for Units {
+    fn checked_mul(&self, rhs: &Units) -> Option<Units> {
+        self.0.checked_mul(rhs.0).map(Units)
+    }
+}
+
+impl Num for Units {
+    type FromStrRadixErr = std::num::ParseIntError;
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        i64::from_str_radix(str, radix).map(Units)
+    }
+}
+
+// exact and never an overflow, as the defaults of FlowExt assume
+impl FlowExt for Units {}
+
+struct Instance {
+    num_nodes: usize,
+    edges: Vec<(usize, usize, i64, i64, i64)>,
+    supplies: Vec<(usize, usize, i64)>,
+}
+
+impl Instance {
+    // the same distribution as random_minimum_cost_flow_graph
+    fn random(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> Self {
+        let edges = (0..num_edges)
+            .map(|_| {
+                let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+                let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, 2) } else { 0 };
+                (from, to, lower, lower + rng.gen_range(0, 10), rng.gen_range(-10, 10))
+            })
+            .collect();
+        let supplies = (0..num_nodes).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10))).collect();
+        Self { num_nodes, edges, supplies }
+    }
+
+    fn graph<Flow>(&self, to_flow: impl Fn(i64) -> Flow) -> Graph<Flow>
+    where
+        Flow: NumAssign + Neg<Output = Flow> + Ord + Copy + Default + Debug,
+    {
+        let mut graph = Graph::default();
+        graph.add_nodes(self.num_nodes);
+        for &(from, to, lower, upper, cost) in self.edges.iter() {
+            graph.add_directed_edge(from, to, to_flow(lower), to_flow(upper), to_flow(cost)).unwrap();
+        }
+        for &(u, v, amount) in self.supplies.iter() {
+            graph.add_supply(u, to_flow(amount));
+            graph.add_demand(v, to_flow(amount));
+        }
+        graph
+    }
+}
+
+#[test]
+fn cost_scaling_push_relabel_without_from_primitive() {
+    let mut rng = XorShift::new(756);
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 40) as usize);
+        let instance = Instance::random(&mut rng, num_nodes, num_edges);
+        let (mut graph, mut units) = (instance.graph(|x| x), instance.graph(Units));
+
+        let expected = CostScalingPushRelabel::default().solve(&mut graph);
+        assert_eq!(CostScalingPushRelabel::default().solve(&mut units), expected);
+        if expected == Status::Optimal {
+            assert_eq!(units.minimum_cost(), Units(graph.minimum_cost()));
+        }
+    }
+}
+
+#[test]
+fn cost_scaling_push_relabel_scaling_factors() {
+    let mut rng = XorShift::new(7560);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 40) as usize);
+        let instance = Instance::random(&mut rng, num_nodes, num_edges);
+
+        let mut expected_graph = instance.graph(|x| x);
+        let expected = CostScalingPushRelabel::default().solve(&mut expected_graph);
+        for scaling_factor in [2, 3, 7, 16, 24] {
+            let mut units = instance.graph(Units);
+            assert_eq!(CostScalingPushRelabel::new(scaling_factor).solve(&mut units), expected);
+            if expected == Status::Optimal {
+                assert_eq!(units.minimum_cost(), Units(expected_graph.minimum_cost()));
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::decomposition::{decompose_flow, FlowComponent};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+fn solve(graph: &mut Graph<i64>) -> Status {
+    PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+}
+
+// the node the edge is left from and the node it enters, in the direction of its flow
+fn traverse(graph: &Graph<i64>, edge_id: usize) -> (usize, usize) {
+    let edge = graph.get_edge(edge_id).unwrap();
+    if edge.flow >= 0 {
+        (edge.from, edge.to)
+    } else {
+        (edge.to, edge.from)
+    }
+}
+
+fn check(graph: &Graph<i64>, components: &[FlowComponent<i64>]) {
+    assert!(components.len() <= graph.num_edges());
+
+    // the net outflow of every node
+    let mut balances = vec![0; graph.num_nodes()];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        balances[edge.from] += edge.flow;
+        balances[edge.to] -= edge.flow;
+    }
+
+    let mut flows = vec![0; graph.num_edges()];
+    for component in components.iter() {
+        let (edges, amount, is_cycle) = match component {
+            FlowComponent::Path { edges, amount } => (edges, *amount, false),
+            FlowComponent::Cycle { edges, amount } => (edges, *amount, true),
+        };
+        assert!(amount > 0);
+        assert!(!edges.is_empty());
+        for window in edges.windows(2) {
+            assert_eq!(traverse(graph, window[0]).1, traverse(graph, window[1]).0);
+        }
+
+        let (first, last) = (traverse(graph, edges[0]).0, traverse(graph, *edges.last().unwrap()).1);
+        if is_cycle {
+            assert_eq!(first, last);
+        } else {
+            assert!(balances[first] > 0 && balances[last] < 0);
+        }
+        for &edge_id in edges.iter() {
+            flows[edge_id] += if graph.get_edge(edge_id).unwrap().flow >= 0 { amount } else { -amount };
+        }
+    }
+
+    assert_eq!(flows, (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect::<Vec<_>>());
+}
+
+#[test]
+fn paths_and_cycles() {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 5, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 5, 3).unwrap();
+    // a negative cycle 1 -> 2 -> 1, and the lower bound of 2 -> 1 pushes more flow around it
+    graph.add_directed_edge(1, 2, 0, 3, -2).unwrap();
+    graph.add_directed_edge(2, 1, 2, 4, 1).unwrap();
+    graph.add_supply(0, 7);
+    graph.add_demand(3, 7);
+    assert_eq!(solve(&mut graph), Status::Optimal);
+
+    let components = decompose_flow(&graph);
+    check(&graph, &components);
+    assert_eq!(
+        components,
+        vec![
+            FlowComponent::Path { edges: vec![0, 1], amount: 5 },
+            FlowComponent::Path { edges: vec![2, 3], amount: 2 },
+            FlowComponent::Cycle { edges: vec![4, 5], amount: 3 },
+        ]
+    );
+}
+
+#[test]
+fn negative_flow() {
+    // the flow goes against a lower bound of -4
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(1, 0, -4, 4, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 4, 1).unwrap();
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    assert_eq!(solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.get_edge(0).unwrap().flow, -3);
+
+    let components = decompose_flow(&graph);
+    check(&graph, &components);
+    assert_eq!(components, vec![FlowComponent::Path { edges: vec![0, 1], amount: 3 }]);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(7642);
+    for _ in 0..500 {
+        let (num_nodes, num_edges) = (rng.gen_range(1, 12) as usize, rng.gen_range(0, 60) as usize);
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 20, 10);
+        // the flow of an unsolved graph is decomposed as well
+        if rng.gen_range(0, 4) != 0 && solve(&mut graph) != Status::Optimal {
+            continue;
+        }
+        check(&graph, &decompose_flow(&graph));
+    }
+}
@@ -0,0 +1,119 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::decremental::decrease_capacity;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::status::Status;
+
+fn check_flow(graph: &Graph<i64>, source: usize, sink: usize) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(0 <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for u in (0..graph.num_nodes()).filter(|&u| u != source && u != sink) {
+        assert_eq!(balance[u], 0);
+    }
+}
+
+fn cold_maximum_flow(graph: &Graph<i64>, source: usize, sink: usize) -> i64 {
+    let mut cold = Graph::default();
+    cold.add_nodes(graph.num_nodes());
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        cold.add_directed_edge(edge.from, edge.to, edge.upper).unwrap();
+    }
+    assert_eq!(Dinic::default().solve(source, sink, &mut cold), Status::Optimal);
+    cold.maximum_flow(source)
+}
+
+#[test]
+fn bottleneck_on_every_path() {
+    // 0 -> {1, 2, 3} -> 4 -> 5 with 4 -> 5 on every path
+    let mut graph = Graph::default();
+    graph.add_nodes(6);
+    for u in 1..=3 {
+        graph.add_directed_edge(0, u, 10).unwrap();
+        graph.add_directed_edge(u, 4, 10).unwrap();
+    }
+    let bottleneck = graph.add_directed_edge(4, 5, 25).unwrap();
+    assert_eq!(Dinic::default().solve(0, 5, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 25);
+
+    assert_eq!(decrease_capacity(0, 5, bottleneck, 3, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 3);
+    check_flow(&graph, 0, 5);
+
+    assert_eq!(decrease_capacity(0, 5, bottleneck, 0, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 0);
+    check_flow(&graph, 0, 5);
+}
+
+#[test]
+fn rerouted_around_the_edge() {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    let edge_id = graph.add_directed_edge(1, 2, 5).unwrap();
+    graph.add_directed_edge(1, 3, 5).unwrap();
+    graph.add_directed_edge(3, 2, 5).unwrap();
+    let mut solver = Dinic::default();
+    assert_eq!(solver.solve(0, 2, &mut graph), Status::Optimal);
+    assert_eq!(graph.get_edge(edge_id).unwrap().flow, 5);
+
+    assert_eq!(decrease_capacity(0, 2, edge_id, 1, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 5);
+    assert_eq!(graph.get_edge(edge_id).unwrap().flow, 1);
+    check_flow(&graph, 0, 2);
+}
+
+#[test]
+fn bad_input() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    let edge_id = graph.add_directed_edge(0, 1, 5).unwrap();
+    assert_eq!(Dinic::default().solve(0, 1, &mut graph), Status::Optimal);
+
+    assert_eq!(decrease_capacity(0, 1, 1, 3, &mut graph), Status::BadInput);
+    assert_eq!(decrease_capacity(0, 0, edge_id, 3, &mut graph), Status::BadInput);
+    assert_eq!(decrease_capacity(0, 2, edge_id, 3, &mut graph), Status::BadInput);
+    assert_eq!(decrease_capacity(0, 1, edge_id, 6, &mut graph), Status::BadInput);
+    assert_eq!(decrease_capacity(0, 1, edge_id, -1, &mut graph), Status::BadInput);
+    assert_eq!(graph.get_edge(edge_id).unwrap().upper, 5);
+    assert_eq!(graph.maximum_flow(0), 5);
+}
+
+#[test]
+fn random_decreases_match_cold_solves() {
+    let mut rng = XorShift::new(781);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 12) as usize;
+        let num_edges = rng.gen_range(1, 40) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..num_edges {
+            let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(from, to, rng.gen_range(0, 20)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+        assert_eq!(Dinic::default().solve(source, sink, &mut graph), Status::Optimal);
+
+        for _ in 0..5 {
+            let edge_id = rng.gen_index(num_edges);
+            let edge = graph.get_edge(edge_id).unwrap();
+            // mostly cut into the flow on the edge
+            let new_upper = if edge.flow > 0 && rng.gen_range(0, 3) > 0 {
+                rng.gen_range(0, edge.flow - 1)
+            } else {
+                rng.gen_range(0, edge.upper)
+            };
+            assert_eq!(decrease_capacity(source, sink, edge_id, new_upper, &mut graph), Status::Optimal);
+            assert_eq!(graph.get_edge(edge_id).unwrap().upper, new_upper);
+            check_flow(&graph, source, sink);
+            assert_eq!(graph.maximum_flow(source), cold_maximum_flow(&graph, source, sink));
+        }
+    }
+}
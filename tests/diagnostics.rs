@@ -0,0 +1,124 @@
+use network_algorithms::diagnostics::*;
+use network_algorithms::minimum_cost_flow::consistency::ConsistencyChecks;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::fs::{read_to_string, remove_dir_all, write};
+use std::path::PathBuf;
+
+// see tests/minimum_cost_flow.rs for the format
+fn load_graph(path: &str) -> (Graph<i64>, i64) {
+    let text = read_to_string(path).unwrap();
+    let mut lines = text.lines();
+    let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    let (num_nodes, num_edges) = (header[0].parse().unwrap(), header[1].parse().unwrap());
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes {
+        graph.add_supply(u, lines.next().unwrap().trim().parse().unwrap());
+    }
+    for _ in 0..num_edges {
+        let e: Vec<i64> = lines.next().unwrap().split_whitespace().map(|x| x.parse().unwrap()).collect();
+        graph.add_directed_edge(e[0] as usize, e[1] as usize, e[2], e[3], e[4]).unwrap();
+    }
+    (graph, header[2].parse().unwrap())
+}
+
+fn bundle_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("network_algorithms_{}_{}", name, std::process::id()));
+    let _ = remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn bundle_and_replay() {
+    let (mut graph, expected) = load_graph("tests/minimum_cost_flow/AOJ_GRL_6_B/03_random_02.txt");
+    let dir = bundle_dir("bundle_and_replay");
+    let solver = SolverKind::PrimalNetworkSimplex(PivotRuleKind::BlockSearch);
+    let (status, manifest) = run_and_bundle(solver, &SolverOptions::default(), &mut graph, &dir).unwrap();
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(graph.minimum_cost(), expected);
+    assert_eq!(manifest.format_version, BUNDLE_FORMAT_VERSION);
+    assert_eq!(manifest.stats.as_ref().unwrap().objective, expected);
+
+    let report = replay_bundle(&dir).unwrap();
+    assert!(report.is_clean(), "{:?}", report.divergences);
+    assert_eq!(report.manifest.solver, solver);
+    assert_eq!(report.manifest.stats.as_ref().unwrap().num_operations, manifest.stats.as_ref().unwrap().num_operations);
+    assert_eq!(report.replayed.objective, expected);
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn options_round_trip() {
+    let (graph, _) = load_graph("tests/minimum_cost_flow/AOJ_GRL_6_B/00_sample_00.txt");
+    let dir = bundle_dir("options_round_trip");
+    let options = SolverOptions {
+        scaling_factor: Some(8),
+        time_limit_millis: Some(1000),
+        tree_repair: true,
+        consistency_checks: Some((ConsistencyChecks::TREE_STRUCTURE | ConsistencyChecks::FLOW_BOUNDS_FULL, 10)),
+    };
+    for solver in [
+        SolverKind::SuccessiveShortestPath,
+        SolverKind::CostScalingPushRelabel,
+        SolverKind::DualNetworkSimplex(PivotRuleKind::CandidateList),
+        SolverKind::PrimalNetworkSimplex(PivotRuleKind::ApproximateSteepestEdge),
+    ] {
+        let (_, manifest) = run_and_bundle(solver, &options, &mut graph.clone(), &dir).unwrap();
+        let report = replay_bundle(&dir).unwrap();
+        assert!(report.is_clean(), "{:?}", report.divergences);
+        assert_eq!(report.manifest, manifest);
+    }
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrupted_objective() {
+    let (mut graph, expected) = load_graph("tests/minimum_cost_flow/AOJ_GRL_6_B/03_random_02.txt");
+    let dir = bundle_dir("corrupted_objective");
+    run_and_bundle(SolverKind::SuccessiveShortestPath, &SolverOptions::default(), &mut graph, &dir).unwrap();
+
+    let stats = read_to_string(dir.join("stats.txt")).unwrap();
+    write(dir.join("stats.txt"), stats.replace(&format!("objective = {expected}"), &format!("objective = {}", expected + 1))).unwrap();
+
+    let report = replay_bundle(&dir).unwrap();
+    assert_eq!(report.divergences, vec![Divergence { field: "objective", recorded: (expected + 1).to_string(), replayed: expected.to_string() }]);
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn repro_bundle_without_stats() {
+    let (graph, _) = load_graph("tests/minimum_cost_flow/AOJ_GRL_6_B/00_sample_00.txt");
+    let dir = bundle_dir("repro_bundle_without_stats");
+    repro_bundle(SolverKind::OutOfKilter, &SolverOptions::default(), &graph, &dir).unwrap();
+
+    let report = replay_bundle(&dir).unwrap();
+    assert!(report.is_clean());
+    assert!(report.manifest.stats.is_none());
+    assert_eq!(report.replayed.status, Status::Optimal);
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn unsupported_version() {
+    let (graph, _) = load_graph("tests/minimum_cost_flow/AOJ_GRL_6_B/00_sample_00.txt");
+    let dir = bundle_dir("unsupported_version");
+    repro_bundle(SolverKind::PrimalDual, &SolverOptions::default(), &graph, &dir).unwrap();
+
+    let manifest = read_to_string(dir.join("manifest.txt")).unwrap();
+    write(dir.join("manifest.txt"), manifest.replace(&format!("format_version = {BUNDLE_FORMAT_VERSION}"), "format_version = 999")).unwrap();
+    assert!(matches!(replay_bundle(&dir), Err(BundleError::UnsupportedVersion(999))));
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn no_bundle_without_violation() {
+    let (mut graph, expected) = load_graph("tests/minimum_cost_flow/AOJ_GRL_6_B/03_random_02.txt");
+    let dir = bundle_dir("no_bundle_without_violation");
+    let options = SolverOptions { consistency_checks: Some((ConsistencyChecks::ALL, 1)), ..SolverOptions::default() };
+    let (status, manifest) = run_with_repro_on_violation(SolverKind::PrimalNetworkSimplex(PivotRuleKind::BlockSearch), &options, &mut graph, &dir).unwrap();
+    assert_eq!((status, manifest), (Status::Optimal, None));
+    assert_eq!(graph.minimum_cost(), expected);
+    assert!(!dir.exists());
+}
@@ -0,0 +1,62 @@
+use network_algorithms::generalized_maximum_flow;
+use network_algorithms::ids::EdgeId;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+#[test]
+fn minimum_cost_flow_edges() {
+    let mut graph = Graph::<i64>::default();
+    graph.add_nodes(3);
+    let forward = graph.add_directed_edge(0, 1, 0, 4, 1).unwrap();
+    let negative = graph.add_directed_edge(1, 2, 1, 3, -2).unwrap();
+    let unused = graph.add_directed_edge(0, 2, 0, 5, 10).unwrap();
+    let lower = graph.add_directed_edge(2, 0, 1, 2, 1).unwrap();
+    graph.add_supply(0, 2);
+    graph.add_demand(2, 2);
+
+    // before a solve the negative cost edge is at its upper bound and the others at their lower bound
+    let ends: Vec<_> = graph.edges().map(|(edge_id, edge)| (edge_id, edge.from, edge.to, edge.flow)).collect();
+    assert_eq!(ends, vec![(forward, 0, 1, 0), (negative, 1, 2, 3), (unused, 0, 2, 0), (lower, 2, 0, 1)]);
+
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut graph.clone()), Status::Optimal);
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    for (edge_id, edge) in graph.edges() {
+        assert_eq!(Some(edge.clone()), graph.get_edge(edge_id));
+        assert_eq!(Some(edge.flow), graph.flow(edge_id));
+    }
+    let edge = graph.edges().nth(negative.index()).unwrap().1;
+    assert_eq!((edge.from, edge.to, edge.lower, edge.upper, edge.cost, edge.flow), (1, 2, 1, 3, -2, 3));
+    assert_eq!(graph.edges_with_flow().map(|(edge_id, _)| edge_id).collect::<Vec<_>>(), vec![forward, negative, lower]);
+    assert_eq!((graph.flow(forward), graph.flow(unused), graph.flow(EdgeId(4))), (Some(3), Some(0), None));
+}
+
+#[test]
+fn maximum_flow_edges() {
+    let mut graph = maximum_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(3);
+    let first = graph.add_directed_edge(0, 1, 2).unwrap();
+    let dead_end = graph.add_directed_edge(2, 0, 4).unwrap();
+    let second = graph.add_directed_edge(1, 2, 3).unwrap();
+    assert_eq!(Dinic::default().solve(0, 2, &mut graph), maximum_flow::status::Status::Optimal);
+
+    assert_eq!(graph.edges().map(|(edge_id, edge)| (edge_id, edge.from, edge.to, edge.flow)).collect::<Vec<_>>(), vec![(first, 0, 1, 2), (dead_end, 2, 0, 0), (second, 1, 2, 2)]);
+    assert_eq!(graph.edges_with_flow().map(|(edge_id, _)| edge_id).collect::<Vec<_>>(), vec![first, second]);
+    assert_eq!((graph.flow(second), graph.flow(dead_end), graph.flow(3)), (Some(2), Some(0), None));
+}
+
+#[test]
+fn generalized_maximum_flow_edges() {
+    let mut graph = generalized_maximum_flow::graph::Graph::<f64>::default();
+    graph.add_nodes(3);
+    let first = graph.add_directed_edge(0, 1, 2.0, 0.5).unwrap();
+    let second = graph.add_directed_edge(1, 2, 1.0, 2.0).unwrap();
+
+    assert_eq!(graph.edges().map(|(edge_id, edge)| (edge_id, edge.from, edge.to, edge.gain)).collect::<Vec<_>>(), vec![(first, 0, 1, 0.5), (second, 1, 2, 2.0)]);
+    assert_eq!(graph.edges_with_flow().count(), 0);
+    assert_eq!((graph.flow(first), graph.flow(2)), (Some(0.0), None));
+}
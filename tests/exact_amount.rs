@@ -0,0 +1,94 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::exact_amount::{solve_at_most_amount, solve_exact_amount, solve_exact_amount_reporting_achievable};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn supply_hash(graph: &Graph<i64>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (0..graph.num_nodes()).map(|u| graph.supply(u)).collect::<Vec<_>>().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn random_graph(rng: &mut XorShift, num_nodes: usize) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..rng.gen_range(1, 30) {
+        let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        graph.add_directed_edge(from, to, 0, rng.gen_range(0, 10), rng.gen_range(-5, 10)).unwrap();
+    }
+    graph
+}
+
+#[test]
+fn repeated_queries_leave_b_untouched() {
+    let mut rng = XorShift::new(7772);
+    for _ in 0..100 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let (source, sink) = (0, num_nodes - 1);
+        let mut graph = random_graph(&mut rng, num_nodes);
+        let hash = supply_hash(&graph);
+
+        let (maximum_flow, status) = PrimalNetworkSimplex::default().max_flow_min_cost(source.into(), sink.into(), &mut graph.clone());
+        assert_eq!(status, Status::Optimal);
+        for _ in 0..5 {
+            let amount = rng.gen_range(0, maximum_flow + 2);
+            let mut manual = graph.clone();
+            manual.add_supply(source, amount);
+            manual.add_demand(sink, amount);
+            let expected_status = SuccessiveShortestPath::default().solve(&mut manual);
+
+            let status = solve_exact_amount(source, sink, amount, &mut graph, &mut PrimalNetworkSimplex::default());
+            assert_eq!(status, expected_status);
+            assert_eq!(supply_hash(&graph), hash);
+            if status == Status::Optimal {
+                assert_eq!(graph.minimum_cost(), manual.minimum_cost());
+                assert_eq!((graph.excess(source), graph.excess(sink)), (-amount, amount));
+            } else {
+                assert!(amount > maximum_flow);
+            }
+        }
+    }
+}
+
+#[test]
+fn infeasible_reports_the_achievable_amount() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 4, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 3, 1).unwrap();
+    let mut solver = SuccessiveShortestPath::default();
+    assert_eq!(solve_exact_amount_reporting_achievable(0, 2, 5, &mut graph, &mut solver), (Status::Infeasible, Some(3)));
+    assert_eq!(solve_exact_amount_reporting_achievable(0, 2, 2, &mut graph, &mut solver), (Status::Optimal, None));
+    assert_eq!(graph.minimum_cost(), 4);
+
+    // at most 5 is the maximum flow 3
+    assert_eq!(solve_at_most_amount(0, 2, 5, &mut graph, &mut solver), (3, Status::Optimal));
+    assert_eq!(graph.minimum_cost(), 6);
+    assert_eq!(solve_at_most_amount(0, 2, 1, &mut graph, &mut solver), (1, Status::Optimal));
+    assert_eq!(graph.minimum_cost(), 2);
+    assert!((0..3).all(|u| graph.supply(u) == 0));
+}
+
+#[test]
+fn bad_input() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 4, 1).unwrap();
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solve_exact_amount(0, 0, 1, &mut graph, &mut solver), Status::BadInput);
+    assert_eq!(solve_exact_amount(0, 3, 1, &mut graph, &mut solver), Status::BadInput);
+    assert_eq!(solve_exact_amount(0, 1, -1, &mut graph, &mut solver), Status::BadInput);
+    assert_eq!(solve_at_most_amount(0, 0, 1, &mut graph, &mut solver), (0, Status::BadInput));
+    // leftover b from an earlier experiment
+    graph.add_supply(2, 1);
+    graph.add_demand(1, 1);
+    assert_eq!(solve_exact_amount(0, 1, 1, &mut graph, &mut solver), Status::BadInput);
+    assert_eq!((graph.supply(1), graph.supply(2)), (-1, 1));
+}
@@ -0,0 +1,159 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::fixed_charge::add_drop_heuristic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+
+struct Instance {
+    num_nodes: usize,
+    supplies: Vec<i64>,
+    edges: Vec<(usize, usize, i64, i64)>, // from, to, upper, cost
+    fixed_costs: Vec<i64>,
+}
+
+impl Instance {
+    fn random(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> Self {
+        let edges = (0..num_edges)
+            .map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(1, 10), rng.gen_range(0, 10)))
+            .collect();
+        let fixed_costs = (0..num_edges).map(|_| rng.gen_range(0, 30)).collect();
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..2 {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(1, 8));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+        Self { num_nodes, supplies, edges, fixed_costs }
+    }
+
+    fn graph(&self, open: &[bool]) -> Graph<i64> {
+        let mut graph = Graph::default();
+        graph.add_nodes(self.num_nodes);
+        for (u, &supply) in self.supplies.iter().enumerate() {
+            graph.add_supply(u, supply);
+        }
+        for (edge_id, &(from, to, upper, cost)) in self.edges.iter().enumerate() {
+            graph.add_directed_edge(from, to, 0, if open[edge_id] { upper } else { 0 }, cost).unwrap();
+        }
+        graph
+    }
+
+    // enumerate all open sets
+    fn brute_force(&self) -> Option<i64> {
+        let num_edges = self.edges.len();
+        assert!(num_edges <= 12);
+        let mut best = None;
+        for mask in 0..1_usize << num_edges {
+            let open: Vec<bool> = (0..num_edges).map(|edge_id| mask >> edge_id & 1 == 1).collect();
+            let mut graph = self.graph(&open);
+            if PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph) != Status::Optimal {
+                continue;
+            }
+            let fixed_cost: i64 = (0..num_edges).filter(|&edge_id| open[edge_id]).map(|edge_id| self.fixed_costs[edge_id]).sum();
+            let total = graph.minimum_cost() + fixed_cost;
+            best = Some(best.map_or(total, |b: i64| b.min(total)));
+        }
+        best
+    }
+}
+
+#[test]
+fn compare_with_brute_force() {
+    let mut rng = XorShift::new(749);
+    let (mut num_instances, mut num_optimal, mut max_gap) = (0, 0, 0.0_f64);
+    for _ in 0..200 {
+        let instance = Instance::random(&mut rng, 5, 10);
+        let mut graph = instance.graph(&[true; 10]);
+        let result = add_drop_heuristic(&mut graph, &instance.fixed_costs, 100);
+        let Some(optimal) = instance.brute_force() else {
+            assert_eq!(result.status, Status::Infeasible);
+            continue;
+        };
+        assert_eq!(result.status, Status::Optimal);
+        assert_eq!(result.total_cost, result.flow_cost + result.fixed_cost);
+        assert_eq!(result.fixed_cost, (0..10).filter(|&edge_id| result.open[edge_id]).map(|edge_id| instance.fixed_costs[edge_id]).sum::<i64>());
+        assert_eq!(graph.minimum_cost(), result.flow_cost);
+        for edge_id in 0..10 {
+            assert_eq!(graph.get_edge(edge_id).unwrap().flow, result.flows[edge_id]);
+            assert!(result.open[edge_id] || result.flows[edge_id] == 0);
+        }
+        assert!(result.total_cost >= optimal);
+
+        num_instances += 1;
+        if result.total_cost == optimal {
+            num_optimal += 1;
+        }
+        if optimal > 0 {
+            max_gap = max_gap.max((result.total_cost - optimal) as f64 / optimal as f64);
+        }
+    }
+    // the heuristic is optimal on 51 of the 57 feasible instances and at most 28% worse on the rest
+    assert_eq!((num_instances, num_optimal), (57, 51));
+    assert!(max_gap < 0.28);
+}
+
+#[test]
+fn deterministic() {
+    let mut rng = XorShift::new(1);
+    for _ in 0..20 {
+        let instance = Instance::random(&mut rng, 6, 12);
+        let (mut graph1, mut graph2) = (instance.graph(&[true; 12]), instance.graph(&[true; 12]));
+        let result1 = add_drop_heuristic(&mut graph1, &instance.fixed_costs, 100);
+        let result2 = add_drop_heuristic(&mut graph2, &instance.fixed_costs, 100);
+        assert_eq!(result1.status, result2.status);
+        assert_eq!(result1.open, result2.open);
+        assert_eq!(result1.flows, result2.flows);
+        assert_eq!(result1.total_cost, result2.total_cost);
+        assert_eq!(result1.iterations, result2.iterations);
+    }
+}
+
+#[test]
+fn required_edge() {
+    // 0 -> 1 is the only way to reach the demand, and it has the worst ratio.
+    // after closing the idle edge 2, closing 0 or 1 disconnects the demand, so both become required.
+    // (opening 0 and 2 instead costs 109, this is where the heuristic misses the optimum)
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 2);
+    graph.add_demand(2, 2);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 3).unwrap();
+    let result = add_drop_heuristic(&mut graph, &[100, 10, 1], 100);
+
+    assert_eq!(result.status, Status::Optimal);
+    assert_eq!(result.required, vec![true, true, false]);
+    assert_eq!(result.open, vec![true, true, false]);
+    assert_eq!(result.flows, vec![2, 2, 0]);
+    assert_eq!((result.flow_cost, result.fixed_cost, result.total_cost), (4, 110, 114));
+}
+
+#[test]
+fn infeasible() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 2);
+    graph.add_demand(1, 2);
+    graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    let result = add_drop_heuristic(&mut graph, &[1], 100);
+    assert_eq!(result.status, Status::Infeasible);
+    assert_eq!(result.iterations, 1);
+}
+
+#[test]
+fn max_iters() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    let result = add_drop_heuristic(&mut graph, &[0, 5], 1);
+    assert_eq!(result.iterations, 1);
+    assert_eq!(result.open, vec![true, true]);
+    assert_eq!(result.total_cost, 6);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+fn check_flow(graph: &Graph<i64>, source: usize, sink: usize, value: i64) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge in (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()) {
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for (u, &balance) in balance.iter().enumerate() {
+        let expected = if u == source {
+            -value
+        } else if u == sink {
+            value
+        } else {
+            0
+        };
+        assert_eq!(balance, expected);
+    }
+}
+
+#[test]
+fn less_than_the_maximum_flow() {
+    // 0 -> 1 -> 3 is cheap but narrow, 0 -> 2 -> 3 is expensive
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 3, 3).unwrap();
+
+    for (k, expected_cost) in [(0, 0), (1, 2), (2, 4), (4, 16)] {
+        let mut graph = graph.clone();
+        assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(0, 3, k, &mut graph), Status::Optimal);
+        check_flow(&graph, 0, 3, k);
+        assert_eq!(graph.minimum_cost(), expected_cost);
+        // the supplies of the solve are not left in the graph
+        assert_eq!((graph.excess(0), graph.excess(3)), (-k, k));
+    }
+
+    let mut over = graph.clone();
+    assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(0, 3, 6, &mut over), Status::Infeasible);
+    assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(0, 0, 1, &mut graph), Status::BadInput);
+    assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(0, 4, 1, &mut graph), Status::BadInput);
+    assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(0, 3, -1, &mut graph), Status::BadInput);
+    graph.add_supply(1, 1);
+    graph.add_demand(2, 1);
+    assert_eq!(ParametricNetworkSimplex::default().solve_flow_value(0, 3, 1, &mut graph), Status::BadInput);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(7752);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let (source, sink) = (0, num_nodes - 1);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        let mut network = maximum_flow::graph::Graph::default();
+        network.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(1, 30) {
+            let (from, to, upper) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            graph.add_directed_edge(from, to, 0, upper, rng.gen_range(-5, 10)).unwrap();
+            network.add_directed_edge(from, to, upper).unwrap();
+        }
+        Dinic::default().solve(source, sink, &mut network);
+        let maximum_flow = network.maximum_flow(source);
+
+        let k = rng.gen_range(0, maximum_flow + 1);
+        let mut expected = graph.clone();
+        expected.add_supply(source, k);
+        expected.add_demand(sink, k);
+        let expected_status = SuccessiveShortestPath::default().solve(&mut expected);
+        assert_eq!(expected_status, if k <= maximum_flow { Status::Optimal } else { Status::Infeasible });
+
+        let status = ParametricNetworkSimplex::default().solve_flow_value(source, sink, k, &mut graph);
+        assert_eq!(status, expected_status);
+        if status == Status::Optimal {
+            check_flow(&graph, source, sink, k);
+            assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+        }
+    }
+}
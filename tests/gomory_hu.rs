@@ -0,0 +1,76 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::gomory_hu::gomory_hu;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::status::Status;
+
+fn graph(num_nodes: usize, edges: &[(usize, usize, i64)]) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for &(u, v, capacity) in edges.iter() {
+        graph.add_directed_edge(u, v, capacity).unwrap();
+    }
+    graph
+}
+
+// the maximum flow between u and v with every edge in both directions
+fn maximum_flow(num_nodes: usize, edges: &[(usize, usize, i64)], u: usize, v: usize) -> i64 {
+    let undirected: Vec<(usize, usize, i64)> = edges.iter().flat_map(|&(a, b, capacity)| [(a, b, capacity), (b, a, capacity)]).collect();
+    let mut graph = graph(num_nodes, &undirected);
+    assert_eq!(Dinic::default().solve(u, v, &mut graph), Status::Optimal);
+    graph.maximum_flow(u)
+}
+
+fn cut_value(edges: &[(usize, usize, i64)], side: &[usize]) -> i64 {
+    edges.iter().filter(|&&(a, b, _)| side.contains(&a) != side.contains(&b)).map(|&(_, _, capacity)| capacity).sum()
+}
+
+#[test]
+fn small() {
+    // two triangles joined by an edge of capacity 2
+    let edges = vec![(0, 1, 3), (1, 2, 4), (2, 0, 5), (3, 4, 3), (4, 5, 3), (5, 3, 3), (2, 3, 2)];
+    let tree = gomory_hu(&graph(6, &edges));
+    assert_eq!(tree.edges().len(), 5);
+    assert_eq!(tree.min_cut_value(0, 5), Ok(2));
+    assert_eq!(tree.min_cut_value(0, 1), Ok(7));
+    assert_eq!(tree.min_cut_value(1, 2), Ok(7));
+    assert_eq!(tree.min_cut_value(3, 4), Ok(6));
+
+    let mut side = tree.minimum_cut(0, 5).unwrap();
+    side.sort();
+    assert_eq!(side, vec![0, 1, 2]);
+    let mut side = tree.minimum_cut(5, 0).unwrap();
+    side.sort();
+    assert_eq!(side, vec![3, 4, 5]);
+
+    assert_eq!(tree.min_cut_value(1, 1), Err(Status::BadInput));
+    assert_eq!(tree.min_cut_value(1, 6), Err(Status::BadInput));
+
+    // disconnected
+    let tree = gomory_hu(&graph(3, &[(0, 1, 4)]));
+    assert_eq!(tree.min_cut_value(0, 1), Ok(4));
+    assert_eq!(tree.min_cut_value(0, 2), Ok(0));
+    assert_eq!(tree.minimum_cut(2, 1), Ok(vec![2]));
+}
+
+#[test]
+fn all_pairs() {
+    let mut rng = XorShift::new(769);
+    for _ in 0..5 {
+        let num_nodes = 20;
+        let edges: Vec<(usize, usize, i64)> = (0..rng.gen_range(20, 60)).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(1, 10))).collect();
+        let tree = gomory_hu(&graph(num_nodes, &edges));
+        for u in 0..num_nodes {
+            for v in (0..num_nodes).filter(|&v| v != u) {
+                let expected = maximum_flow(num_nodes, &edges, u, v);
+                assert_eq!(tree.min_cut_value(u, v), Ok(expected));
+
+                let side = tree.minimum_cut(u, v).unwrap();
+                assert!(side.contains(&u) && !side.contains(&v));
+                assert_eq!(cut_value(&edges, &side), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use network_algorithms::generalized_maximum_flow;
+use network_algorithms::graph_error::GraphError;
+use network_algorithms::ids::EdgeId;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::node_capacity::NodeCapacityGraph;
+use network_algorithms::minimum_cost_flow;
+
+#[test]
+fn maximum_flow_errors() {
+    let mut graph = maximum_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(2);
+    assert_eq!(graph.add_directed_edge(0, 2, 1), Err(GraphError::NodeOutOfRange { node: 2, num_nodes: 2 }));
+    assert_eq!(graph.add_directed_edge(3, 0, 1), Err(GraphError::NodeOutOfRange { node: 3, num_nodes: 2 }));
+    assert_eq!(graph.add_directed_edge(0, 1, -1), Err(GraphError::NegativeCapacity { upper: -1 }));
+    assert_eq!(graph.add_directed_edge_with_lower(0, 1, 5, 3), Err(GraphError::InvalidBounds { lower: 5, upper: 3 }));
+    assert_eq!(graph.add_directed_edge_with_lower(0, 1, -1, 3), Err(GraphError::NegativeLower { lower: -1 }));
+    assert_eq!(graph.add_directed_edge_with_lower(0, 5, 1, 3), Err(GraphError::NodeOutOfRange { node: 5, num_nodes: 2 }));
+    assert_eq!(graph.num_edges(), 0);
+
+    assert_eq!(graph.add_directed_edge(0, 1, 0), Ok(EdgeId(0)));
+    assert_eq!(graph.add_directed_edge_with_lower(1, 0, 3, 3), Ok(EdgeId(1)));
+
+    let mut graph = NodeCapacityGraph::<i64>::default();
+    graph.add_node();
+    graph.add_node_with_capacity(2);
+    assert_eq!(graph.add_directed_edge(1, 2, 1), Err(GraphError::NodeOutOfRange { node: 2, num_nodes: 2 }));
+    assert_eq!(graph.add_directed_edge(1, 0, -2), Err(GraphError::NegativeCapacity { upper: -2 }));
+    assert_eq!((graph.num_edges(), graph.add_directed_edge(1, 0, 2)), (0, Ok(EdgeId(0))));
+}
+
+#[test]
+fn minimum_cost_flow_errors() {
+    let mut graph = minimum_cost_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(12);
+    assert_eq!(graph.add_directed_edge(10, 11, 5, 3, 1), Err(GraphError::InvalidBounds { lower: 5, upper: 3 }));
+    assert_eq!(graph.add_directed_edge(10, 12, 0, 3, 1), Err(GraphError::NodeOutOfRange { node: 12, num_nodes: 12 }));
+    assert_eq!(graph.add_directed_edge_raw(12, 10, 0, 3, -1), Err(GraphError::NodeOutOfRange { node: 12, num_nodes: 12 }));
+    assert_eq!((graph.num_edges(), graph.excess(10), graph.excess(11)), (0, 0, 0));
+
+    // negative bounds are allowed in a minimum cost flow
+    assert_eq!(graph.add_directed_edge(10, 11, -3, -1, 1), Ok(EdgeId(0)));
+}
+
+#[test]
+fn generalized_maximum_flow_errors() {
+    let mut graph = generalized_maximum_flow::graph::Graph::<f64>::default();
+    graph.add_nodes(2);
+    assert_eq!(graph.add_directed_edge(0, 2, 1.0, 1.0), Err(GraphError::NodeOutOfRange { node: 2, num_nodes: 2 }));
+    assert_eq!(graph.add_directed_edge(0, 1, 0.0, 1.0), Err(GraphError::NonPositiveCapacity { upper: 0.0 }));
+    assert_eq!(graph.add_directed_edge(0, 1, 1.0, 0.0), Err(GraphError::NonPositiveGain { gain: 0.0 }));
+    assert_eq!(graph.add_directed_edge(0, 1, 1.0, -0.5), Err(GraphError::NonPositiveGain { gain: -0.5 }));
+    assert_eq!(graph.num_edges(), 0);
+    assert_eq!(graph.add_directed_edge(0, 1, 1.0, 0.5), Ok(EdgeId(0)));
+}
+
+#[test]
+fn display() {
+    let error: Box<dyn std::error::Error> = Box::new(GraphError::InvalidBounds { lower: 5, upper: 3 });
+    assert_eq!(format!("edge (10, 11) has {error}"), "edge (10, 11) has lower 5 > upper 3");
+    assert_eq!(GraphError::<i64>::NodeOutOfRange { node: 12, num_nodes: 12 }.to_string(), "node 12 is out of range of 12 nodes");
+    assert_eq!(GraphError::NegativeLower { lower: -1 }.to_string(), "negative lower -1");
+    assert_eq!(GraphError::NegativeCapacity { upper: -2 }.to_string(), "negative capacity -2");
+    assert_eq!(GraphError::NonPositiveCapacity { upper: 0.0 }.to_string(), "capacity 0 is not positive");
+    assert_eq!(GraphError::NonPositiveGain { gain: -0.5 }.to_string(), "gain -0.5 is not positive");
+}
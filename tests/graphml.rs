@@ -0,0 +1,90 @@
+use network_algorithms::io::graphml::{escape_xml, write_graphml, GraphmlOptions, GraphmlSource, LabelMap};
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::fs::read_to_string;
+
+fn annotated() -> Graph<i64> {
+    // 0 -> 1 -> 3 is cheap but narrow, 0 -> 2 -> 3 is expensive, and 2 -> 1 is unused
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 1, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 3, -1).unwrap();
+    graph.add_directed_edge(2, 1, 0, 4, 2).unwrap();
+    graph.add_supply(0, 3);
+    graph.add_demand(3, 3);
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    graph
+}
+
+fn write<G: GraphmlSource<i64>>(graph: &G, options: &GraphmlOptions<i64>) -> String {
+    let mut buffer = Vec::new();
+    write_graphml(graph, &mut buffer, options).unwrap();
+    String::from_utf8(buffer).unwrap()
+}
+
+#[test]
+fn golden_minimum_cost_flow() {
+    let graph = annotated();
+    let labels = LabelMap::from([(0, "source".to_string()), (3, "sink".to_string())]);
+    let potentials: Vec<i64> = (0..graph.num_nodes()).map(|u| graph.potential(u)).collect();
+    let options = GraphmlOptions { labels: Some(&labels), potentials: Some(&potentials), support_only: false };
+    assert_eq!(write(&graph, &options), read_to_string("tests/graphml/annotated.graphml").unwrap());
+}
+
+#[test]
+fn golden_maximum_flow() {
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 3).unwrap();
+    graph.add_directed_edge(1, 2, 2).unwrap();
+    graph.add_directed_edge(0, 2, 1).unwrap();
+    Dinic::default().solve(0, 2, &mut graph);
+    assert_eq!(write(&graph, &GraphmlOptions::default()), read_to_string("tests/graphml/maximum_flow.graphml").unwrap());
+}
+
+#[test]
+fn hostile_labels_are_escaped() {
+    assert_eq!(escape_xml(r#"<a href="x">&'</a>"#), "&lt;a href=&quot;x&quot;&gt;&amp;&apos;&lt;/a&gt;");
+
+    let graph = annotated();
+    let labels = LabelMap::from([(1, "</data></node><node id=\"evil\">".to_string()), (2, "&amp; ]]> \u{e9}".to_string())]);
+    let text = write(&graph, &GraphmlOptions { labels: Some(&labels), ..GraphmlOptions::default() });
+    assert!(text.contains(r#"<data key="label">&lt;/data&gt;&lt;/node&gt;&lt;node id=&quot;evil&quot;&gt;</data>"#));
+    assert!(text.contains("<data key=\"label\">&amp;amp; ]]&gt; \u{e9}</data>"));
+    assert!(!text.contains("evil\""));
+    assert_eq!(text.matches("<node ").count(), graph.num_nodes());
+}
+
+#[test]
+fn support_only_drops_zero_flow_edges() {
+    // a path carrying the flow and many edges that carry none
+    let (num_nodes, num_idle_edges) = (100, 10_000);
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 1, 1).unwrap();
+    }
+    for i in 0..num_idle_edges {
+        graph.add_directed_edge(i % num_nodes, (i * 7 + 3) % num_nodes, 0, 1, 1_000).unwrap();
+    }
+    graph.add_supply(0, 1);
+    graph.add_demand(num_nodes - 1, 1);
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+
+    let full = write(&graph, &GraphmlOptions::default());
+    let support = write(&graph, &GraphmlOptions { support_only: true, ..GraphmlOptions::default() });
+    assert_eq!(full.matches("<edge ").count(), num_nodes - 1 + num_idle_edges);
+    assert_eq!(support.matches("<edge ").count(), num_nodes - 1);
+    assert_eq!(support.matches("<node ").count(), num_nodes);
+    // a few hundred bytes per node and path edge, while the idle edges dominate the full export
+    assert!(support.len() < 500 * num_nodes);
+    assert!(20 * support.len() < full.len());
+    // the ids of the graph are kept
+    assert!(support.contains(r#"<edge id="e98" source="n98" target="n99">"#));
+}
@@ -0,0 +1,74 @@
+use network_algorithms::generalized_maximum_flow;
+use network_algorithms::ids::{EdgeId, NodeId};
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::solver::MaximumFlowSolver;
+use network_algorithms::maximum_flow::status::Status;
+use network_algorithms::minimum_cost_flow;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+#[test]
+fn ids_in_insertion_order() {
+    let mut graph = Graph::<i64>::default();
+    assert_eq!(graph.add_node(), NodeId(0));
+    assert_eq!(graph.add_nodes(2), vec![NodeId(1), NodeId(2)]);
+    assert_eq!(graph.add_node_raw(), 3);
+    assert_eq!(graph.add_directed_edge(NodeId(0), NodeId(1), 1), Ok(EdgeId(0)));
+    assert_eq!(graph.add_directed_edge_raw(1, 2, 1), Ok(1));
+    assert_eq!(graph.add_directed_edge_with_lower(1, 3, 1, 2), Ok(EdgeId(2)));
+    assert!(graph.add_directed_edge(NodeId(0), NodeId(4), 1).is_err());
+
+    assert_eq!(usize::from(EdgeId(2)), 2);
+    assert_eq!(NodeId::from(3).index(), 3);
+    assert_eq!(format!("{} {}", NodeId(1), EdgeId(2)), "1 2");
+}
+
+#[test]
+fn maximum_flow_with_ids() {
+    let mut graph = Graph::<i64>::default();
+    let (source, middle, sink) = (graph.add_node(), graph.add_node(), graph.add_node());
+    let first = graph.add_directed_edge(source, middle, 3).unwrap();
+    let second = graph.add_directed_edge(middle, sink, 2).unwrap();
+
+    let mut raw = graph.clone();
+    assert_eq!(Dinic::default().solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(Dinic::default().solve_raw(0, 2, &mut raw), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), 2);
+    assert_eq!(graph.flow_value(source, sink), 2);
+    assert_eq!(graph.get_edge(first).unwrap().flow, 2);
+    assert_eq!(graph.get_edge(second), raw.get_edge(1));
+
+    let mut solver: Box<dyn MaximumFlowSolver<i64>> = Box::new(Dinic::default());
+    assert_eq!(solver.solve(sink, source, &mut raw), Status::Optimal);
+    assert_eq!(solver.objective(sink, &raw), 0);
+}
+
+#[test]
+fn minimum_cost_flow_with_ids() {
+    let mut graph = minimum_cost_flow::graph::Graph::<i64>::default();
+    let nodes = graph.add_nodes(3);
+    let cheap = graph.add_directed_edge(nodes[0], nodes[2], 0, 1, 1).unwrap();
+    let negative = graph.add_directed_edge(nodes[0], nodes[1], 0, 2, -1).unwrap();
+    graph.add_directed_edge(nodes[1], nodes[2], 0, 2, 3).unwrap();
+    graph.add_supply(nodes[0], 2);
+    graph.add_demand(nodes[2], 2);
+
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut graph), minimum_cost_flow::status::Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 3);
+    assert_eq!(graph.get_edge(cheap).unwrap().flow, 1);
+    let edge = graph.get_edge(negative).unwrap();
+    assert_eq!((edge.from, edge.to, edge.flow), (0, 1, 1));
+    assert_eq!(graph.excess(nodes[2]), 0);
+    assert_eq!(graph.supply(nodes[2]), -2);
+}
+
+#[test]
+fn generalized_maximum_flow_with_ids() {
+    let mut graph = generalized_maximum_flow::graph::Graph::<f64>::default();
+    let (source, sink) = (graph.add_node(), graph.add_node());
+    assert!(graph.add_directed_edge(source, sink, 1.0, 0.0).is_err());
+    let edge_id = graph.add_directed_edge(source, sink, 1.0, 0.5).unwrap();
+    assert_eq!(edge_id, EdgeId(0));
+    assert_eq!(graph.get_edge(edge_id).unwrap().gain, 0.5);
+    assert_eq!(graph.maximum_flow(sink), 0.0);
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::index::{IndexError, IndexType};
+use network_algorithms::maximum_flow;
+use network_algorithms::minimum_cost_flow::csr::CSR;
+
+fn random_maximum_flow_graph(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> maximum_flow::graph::Graph<i64> {
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10)).unwrap();
+    }
+    graph
+}
+
+fn indices<I: IndexType>(values: &[I]) -> Vec<usize> {
+    values.iter().map(|value| value.index()).collect()
+}
+
+#[test]
+fn u32_same_as_usize() {
+    let mut rng = XorShift::new(818);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(0, 60) as usize);
+
+        let graph = random_maximum_flow_graph(&mut rng, num_nodes, num_edges);
+        let (mut wide, mut narrow) = (maximum_flow::csr::CSR::<i64>::default(), maximum_flow::csr::CSR::<i64, u32>::default());
+        wide.build(&graph);
+        assert_eq!(narrow.try_build(&graph), Ok(()));
+        assert_eq!(indices(&narrow.start), wide.start);
+        assert_eq!(indices(&narrow.edge_index_to_inside_edge_index), wide.edge_index_to_inside_edge_index);
+        for (narrow_edge, wide_edge) in narrow.inside_edge_list.iter().zip(wide.inside_edge_list.iter()) {
+            assert_eq!((narrow_edge.to.index(), narrow_edge.rev.index(), narrow_edge.upper), (wide_edge.to, wide_edge.rev, wide_edge.upper));
+        }
+        assert_eq!(narrow.reachable_nodes(0), wide.reachable_nodes(0));
+        assert_eq!(narrow.min_cut_edges(0), wide.min_cut_edges(0));
+
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+        let (mut wide, mut narrow) = (CSR::<i64>::default(), CSR::<i64, u32>::default());
+        assert_eq!(narrow.build_preserving_potentials(&graph), wide.build_preserving_potentials(&graph));
+        assert_eq!(narrow.potentials, wide.potentials);
+        assert_eq!(narrow.calculate_distance_from_source(0), wide.calculate_distance_from_source(0));
+    }
+}
+
+#[test]
+fn too_many_nodes() {
+    let mut graph = maximum_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(u16::MAX as usize);
+    assert_eq!(maximum_flow::csr::CSR::<i64, u16>::default().try_build(&graph), Ok(()));
+
+    graph.add_node();
+    let error = IndexError::TooManyNodes { num_nodes: 1 << 16, max: u16::MAX as usize };
+    assert_eq!(maximum_flow::csr::CSR::<i64, u16>::default().try_build(&graph), Err(error));
+    assert_eq!(maximum_flow::csr::CSR::<i64, u32>::default().try_build(&graph), Ok(()));
+
+    let mut graph = network_algorithms::minimum_cost_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(1 << 16);
+    assert_eq!(CSR::<i64, u16>::default().try_build(&graph), Err(error));
+    assert_eq!(error.to_string(), "65536 nodes do not fit in an index type of at most 65535 nodes");
+}
+
+#[test]
+fn too_many_edges() {
+    let mut graph = maximum_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(2);
+    for _ in 0..u16::MAX / 2 {
+        graph.add_directed_edge(0, 1, 1).unwrap();
+    }
+    assert_eq!(maximum_flow::csr::CSR::<i64, u16>::default().try_build(&graph), Ok(()));
+
+    graph.add_directed_edge(1, 0, 1).unwrap();
+    let error = IndexError::TooManyEdges { num_edges: 1 << 15, max: (u16::MAX / 2) as usize };
+    assert_eq!(maximum_flow::csr::CSR::<i64, u16>::default().try_build(&graph), Err(error));
+}
+
+// 4 GiB of excesses: cargo test --release --test index_type -- --ignored
+#[test]
+#[ignore]
+fn too_many_nodes_u32() {
+    let mut graph = maximum_flow::graph::Graph::<u8>::default();
+    for _ in 0..(1 << 12) {
+        graph.add_nodes(1 << 20);
+    }
+    graph.add_directed_edge(0, u32::MAX as usize, 1).unwrap();
+    let error = IndexError::TooManyNodes { num_nodes: 1 << 32, max: u32::MAX as usize };
+    assert_eq!(maximum_flow::csr::CSR::<u8, u32>::default().try_build(&graph), Err(error));
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::isomorphism::{find_relabeling, is_relabeling, Relabeling};
+
+// the nodes of graph renamed by permutation, and the edges in another order
+fn relabeled(rng: &mut XorShift, graph: &Graph<i64>, permutation: &[usize], supplies: &[i64]) -> Graph<i64> {
+    let mut edge_ids: Vec<usize> = (0..graph.num_edges()).collect();
+    for i in (1..edge_ids.len()).rev() {
+        edge_ids.swap(i, rng.gen_index(i + 1));
+    }
+    let mut result = Graph::default();
+    result.add_nodes(graph.num_nodes());
+    for edge_id in edge_ids {
+        let edge = graph.get_edge(edge_id).unwrap();
+        result.add_directed_edge(permutation[edge.from], permutation[edge.to], edge.lower, edge.upper, edge.cost).unwrap();
+    }
+    for (u, &supply) in supplies.iter().enumerate() {
+        result.add_supply(permutation[u], supply);
+    }
+    result
+}
+
+fn random_permutation(rng: &mut XorShift, n: usize) -> Vec<usize> {
+    let mut permutation: Vec<usize> = (0..n).collect();
+    for i in (1..n).rev() {
+        permutation.swap(i, rng.gen_index(i + 1));
+    }
+    permutation
+}
+
+// small bounds and costs, so that many nodes look alike
+fn random_instance(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> (Graph<i64>, Vec<i64>) {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        let lower = rng.gen_range(0, 2);
+        graph.add_directed_edge(from, to, lower, lower + rng.gen_range(0, 3), rng.gen_range(-2, 3)).unwrap();
+    }
+    let supplies: Vec<i64> = (0..num_nodes).map(|_| rng.gen_range(-1, 2)).collect();
+    for (u, &supply) in supplies.iter().enumerate() {
+        graph.add_supply(u, supply);
+    }
+    (graph, supplies)
+}
+
+#[test]
+fn recovers_permutation() {
+    let mut rng = XorShift::new(771);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(1, 30) as usize, rng.gen_range(0, 80) as usize);
+        let (graph, supplies) = random_instance(&mut rng, num_nodes, num_edges);
+        let permutation = random_permutation(&mut rng, num_nodes);
+        let other = relabeled(&mut rng, &graph, &permutation, &supplies);
+        assert!(is_relabeling(&graph, &other, &permutation));
+
+        // an automorphism may give another relabeling
+        match find_relabeling(&graph, &other, 100_000) {
+            Relabeling::Found(relabeling) => assert!(is_relabeling(&graph, &other, &relabeling)),
+            result => panic!("{result:?}"),
+        }
+    }
+}
+
+#[test]
+fn near_miss_is_rejected_by_signatures() {
+    let mut rng = XorShift::new(7711);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 30) as usize, rng.gen_range(1, 80) as usize);
+        let (graph, supplies) = random_instance(&mut rng, num_nodes, num_edges);
+        let permutation = random_permutation(&mut rng, num_nodes);
+
+        // one cost changed
+        let mut changed = Graph::default();
+        changed.add_nodes(num_nodes);
+        let target = rng.gen_index(num_edges);
+        for edge_id in 0..num_edges {
+            let edge = graph.get_edge(edge_id).unwrap();
+            let cost = if edge_id == target { edge.cost + 100 } else { edge.cost };
+            changed.add_directed_edge(edge.from, edge.to, edge.lower, edge.upper, cost).unwrap();
+        }
+        let changed = relabeled(&mut rng, &changed, &permutation, &supplies);
+
+        // no search node is needed
+        assert_eq!(find_relabeling(&graph, &changed, 0), Relabeling::NonIsomorphic);
+    }
+}
+
+#[test]
+fn supplies_and_sizes_matter() {
+    let mut a = Graph::default();
+    a.add_nodes(2);
+    a.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    a.add_supply(0, 3);
+    a.add_demand(1, 3);
+
+    let mut b = Graph::default();
+    b.add_nodes(2);
+    b.add_directed_edge(1, 0, 0, 5, 1).unwrap();
+    b.add_supply(1, 3);
+    b.add_demand(0, 3);
+    assert_eq!(find_relabeling(&a, &b, 2), Relabeling::Found(vec![1, 0]));
+
+    b.add_supply(1, 1);
+    b.add_demand(0, 1);
+    assert_eq!(find_relabeling(&a, &b, 100), Relabeling::NonIsomorphic);
+
+    b.add_node();
+    assert_eq!(find_relabeling(&a, &b, 100), Relabeling::NonIsomorphic);
+}
+
+#[test]
+fn effort_limit() {
+    // a cycle of 60 nodes against two cycles of 30, which have the same colors
+    let (mut a, mut b) = (Graph::default(), Graph::default());
+    a.add_nodes(60);
+    b.add_nodes(60);
+    for u in 0..60 {
+        a.add_directed_edge(u, (u + 1) % 60, 0, 1, 1).unwrap();
+        b.add_directed_edge(u, u / 30 * 30 + (u + 1) % 30, 0, 1, 1).unwrap();
+    }
+    assert_eq!(find_relabeling(&a, &b, 100), Relabeling::GaveUp);
+    assert_eq!(find_relabeling(&a, &b, 1_000_000), Relabeling::NonIsomorphic);
+
+    // the same graph is found along the first choice
+    let c = a.clone();
+    assert!(matches!(find_relabeling(&a, &c, 100), Relabeling::Found(_)));
+}
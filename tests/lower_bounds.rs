@@ -0,0 +1,109 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::graph_error::GraphError;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::push_relabel_fifo::PushRelabelFIFO;
+use network_algorithms::maximum_flow::solver::MaximumFlowSolver;
+use network_algorithms::maximum_flow::status::Status;
+use network_algorithms::minimum_cost_flow;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+
+fn check_bounds(graph: &Graph<i64>, source: usize, sink: usize) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(graph.get_lower(edge_id).unwrap() <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for u in (0..graph.num_nodes()).filter(|&u| u != source && u != sink) {
+        assert_eq!(balance[u], 0);
+    }
+}
+
+#[test]
+fn infeasible() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 3).unwrap();
+    graph.add_directed_edge_with_lower(1, 2, 5, 10).unwrap();
+    assert_eq!(graph.add_directed_edge_with_lower(1, 2, 5, 4), Err(GraphError::InvalidBounds { lower: 5, upper: 4 }));
+    assert_eq!(graph.add_directed_edge_with_lower(1, 2, -1, 4), Err(GraphError::NegativeLower { lower: -1 }));
+
+    assert_eq!(Dinic::default().solve_with_lower_bounds(0.into(), 2.into(), &mut graph), Status::Infeasible);
+    assert!((0..graph.num_edges()).all(|edge_id| graph.get_edge(edge_id).unwrap().flow == 0));
+    // without the lower bounds it is an ordinary maximum flow
+    assert_eq!(Dinic::default().solve(0, 2, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 3);
+}
+
+#[test]
+fn lower_bounds_force_flow_on_unused_edges() {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 10).unwrap();
+    graph.add_directed_edge(1, 2, 10).unwrap();
+    // a cycle off the way from source to sink
+    let forced = graph.add_directed_edge_with_lower(1, 3, 4, 4).unwrap();
+    let back = graph.add_directed_edge(3, 1, 5).unwrap();
+    // the sink has to send some flow back
+    let returned = graph.add_directed_edge_with_lower(2, 0, 2, 6).unwrap();
+
+    assert_eq!(PushRelabelFIFO::default().solve_with_lower_bounds(0.into(), 2.into(), &mut graph), Status::Optimal);
+    check_bounds(&graph, 0, 2);
+    assert_eq!(graph.get_edge(forced).unwrap().flow, 4);
+    assert_eq!(graph.get_edge(back).unwrap().flow, 4);
+    assert_eq!(graph.get_edge(returned).unwrap().flow, 2);
+    assert_eq!(graph.maximum_flow(0), 8);
+}
+
+#[test]
+fn negative_value() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 2).unwrap();
+    graph.add_directed_edge_with_lower(1, 0, 5, 5).unwrap();
+    assert_eq!(Dinic::default().solve_with_lower_bounds(0.into(), 1.into(), &mut graph), Status::Optimal);
+    check_bounds(&graph, 0, 1);
+    assert_eq!(graph.maximum_flow(0), -3);
+    assert_eq!(Dinic::default().solve_with_lower_bounds(0.into(), 0.into(), &mut graph), Status::BadInput);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(772);
+    let mut num_infeasible = 0;
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let (source, sink) = (0, num_nodes - 1);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        // the reference is a circulation of minimum cost where the flow from sink back to source costs -1
+        let mut reference = minimum_cost_flow::graph::Graph::default();
+        reference.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(1, 25) {
+            let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            let lower = if rng.gen_range(0, 3) == 0 { rng.gen_range(0, 5) } else { 0 };
+            let upper = lower + rng.gen_range(0, 10);
+            graph.add_directed_edge_with_lower(from, to, lower, upper).unwrap();
+            reference.add_directed_edge(from, to, lower, upper, 0).unwrap();
+        }
+        reference.add_directed_edge(sink, source, 0, 1000, -1).unwrap();
+        reference.add_directed_edge(source, sink, 0, 1000, 1).unwrap();
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(reference.num_edges()), &mut reference);
+
+        let status = Dinic::default().solve_with_lower_bounds(source.into(), sink.into(), &mut graph);
+        if expected == minimum_cost_flow::status::Status::Infeasible {
+            assert_eq!(status, Status::Infeasible);
+            num_infeasible += 1;
+            continue;
+        }
+        assert_eq!(status, Status::Optimal);
+        check_bounds(&graph, source, sink);
+        assert_eq!(graph.maximum_flow(source), -reference.minimum_cost());
+    }
+    assert!(num_infeasible > 0);
+}
@@ -0,0 +1,97 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+fn check_flow(graph: &Graph<i64>, source: usize, sink: usize, value: i64) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge in (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()) {
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for (u, &balance) in balance.iter().enumerate() {
+        let expected = if u == source {
+            -value
+        } else if u == sink {
+            value
+        } else {
+            0
+        };
+        assert_eq!(balance, expected);
+    }
+}
+
+#[test]
+fn cheapest_among_the_maximum_flows() {
+    // 0 -> 1 -> 3 is cheap but narrow, 0 -> 2 -> 3 is expensive
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 3, 3).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 0).unwrap();
+
+    let (value, status) = PrimalNetworkSimplex::default().max_flow_min_cost(0.into(), 3.into(), &mut graph);
+    assert_eq!((value, status), (5, Status::Optimal));
+    check_flow(&graph, 0, 3, 5);
+    assert_eq!(graph.minimum_cost(), 2 * 2 + 3 * 6);
+    // the supplies of the solve are not left in the graph
+    assert_eq!((graph.excess(0), graph.excess(3)), (-5, 5));
+    assert_eq!(SuccessiveShortestPath::default().max_flow_min_cost(0.into(), 3.into(), &mut graph), (5, Status::Optimal));
+    assert_eq!(graph.minimum_cost(), 2 * 2 + 3 * 6);
+}
+
+#[test]
+fn degenerate_cases() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 4, 1).unwrap();
+    graph.add_directed_edge(2, 1, 0, 4, 1).unwrap();
+    let (value, status) = PrimalNetworkSimplex::default().max_flow_min_cost(0.into(), 2.into(), &mut graph);
+    assert_eq!((value, status), (0, Status::Optimal));
+    check_flow(&graph, 0, 2, 0);
+
+    assert_eq!(PrimalNetworkSimplex::default().max_flow_min_cost(1.into(), 1.into(), &mut graph).1, Status::BadInput);
+    assert_eq!(PrimalNetworkSimplex::default().max_flow_min_cost(0.into(), 3.into(), &mut graph).1, Status::BadInput);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    assert_eq!(PrimalNetworkSimplex::default().max_flow_min_cost(0.into(), 1.into(), &mut graph).1, Status::BadInput);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(7742);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let (source, sink) = (0, num_nodes - 1);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        let mut network = maximum_flow::graph::Graph::default();
+        network.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(1, 30) {
+            let (from, to, upper) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            graph.add_directed_edge(from, to, 0, upper, rng.gen_range(-5, 10)).unwrap();
+            network.add_directed_edge(from, to, upper).unwrap();
+        }
+        Dinic::default().solve(source, sink, &mut network);
+        let expected_value = network.maximum_flow(source);
+
+        let mut expected = graph.clone();
+        expected.add_supply(source, expected_value);
+        expected.add_demand(sink, expected_value);
+        assert_eq!(SuccessiveShortestPath::default().solve(&mut expected), Status::Optimal);
+
+        let (value, status) = PrimalNetworkSimplex::default().max_flow_min_cost(source.into(), sink.into(), &mut graph);
+        assert_eq!((value, status), (expected_value, Status::Optimal));
+        check_flow(&graph, source, sink, value);
+        assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+    }
+}
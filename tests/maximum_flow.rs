@@ -0,0 +1,1306 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::boykov_kolmogorov::BoykovKolmogorov;
+use network_algorithms::maximum_flow::capacity_scaling::CapacityScaling;
+use network_algorithms::maximum_flow::decomposition::decompose;
+use network_algorithms::maximum_flow::dimacs::DimacsError;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::edmonds_karp::EdmondsKarp;
+use network_algorithms::maximum_flow::estimate::{quick_bounds, Effort};
+use network_algorithms::maximum_flow::excess_scaling::ExcessScaling;
+use network_algorithms::maximum_flow::ford_fulkerson::FordFulkerson;
+use network_algorithms::maximum_flow::graph::{DotOptions, Edge, Graph};
+use network_algorithms::maximum_flow::isap::Isap;
+use network_algorithms::maximum_flow::mpm::Mpm;
+use network_algorithms::maximum_flow::push_relabel_fifo::PushRelabelFIFO;
+use network_algorithms::maximum_flow::shortest_augmenting_path::ShortestAugmentingPath;
+use network_algorithms::maximum_flow::solver::MaximumFlowSolver;
+use network_algorithms::maximum_flow::status::Status;
+use network_algorithms::metrics::Stats;
+use rstest::rstest;
+use std::fs::read_to_string;
+use std::path::PathBuf;
+
+// first line: num_nodes num_edges source sink expected
+// next num_edges lines: from to upper
+fn load_graph(path: &PathBuf) -> (Graph<i64>, usize, usize, i64) {
+    let binding = read_to_string(path).unwrap();
+    let mut lines = binding.lines();
+    let first: Vec<usize> = lines.next().unwrap().split_whitespace().map(|x| x.parse().unwrap()).collect();
+    let (num_nodes, source, sink, expected) = (first[0], first[2], first[3], first[4] as i64);
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for line in lines {
+        let edge: Vec<i64> = line.split_whitespace().map(|x| x.parse().unwrap()).collect();
+        graph.add_directed_edge(edge[0] as usize, edge[1] as usize, edge[2]).unwrap();
+    }
+    (graph, source, sink, expected)
+}
+
+// the total capacity of the edges leaving the source side
+fn cut_capacity(graph: &Graph<i64>, cut: &[usize]) -> i64 {
+    let mut is_source_side = vec![false; graph.num_nodes()];
+    cut.iter().for_each(|&u| is_source_side[u] = true);
+    (0..graph.num_edges())
+        .map(|edge_id| graph.get_edge(edge_id).unwrap())
+        .filter(|e| is_source_side[e.from] && !is_source_side[e.to])
+        .map(|e| e.upper)
+        .sum()
+}
+
+fn check_minimum_cut(graph: &Graph<i64>, source: usize, sink: usize, cut: &[usize]) {
+    assert!(cut.contains(&source));
+    assert!(!cut.contains(&sink));
+    assert_eq!(cut_capacity(graph, cut), graph.maximum_flow(source));
+}
+
+// each edge crosses from the source side to the sink side, and all such edges are listed once
+fn check_min_cut_edges(graph: &Graph<i64>, source: usize, cut: &[usize], cut_edges: &[usize]) {
+    let mut is_source_side = vec![false; graph.num_nodes()];
+    cut.iter().for_each(|&u| is_source_side[u] = true);
+    let expected: Vec<usize> = (0..graph.num_edges())
+        .filter(|&edge_id| {
+            let edge = graph.get_edge(edge_id).unwrap();
+            is_source_side[edge.from] && !is_source_side[edge.to]
+        })
+        .collect();
+    assert_eq!(cut_edges, expected);
+    assert_eq!(cut_edges.iter().map(|&edge_id| graph.get_edge(edge_id).unwrap().upper).sum::<i64>(), graph.maximum_flow(source));
+}
+
+#[allow(clippy::type_complexity)]
+// solve returns (status, flow_value of the solver, minimum cut, its edges)
+fn check(path: &PathBuf, solve: impl Fn(usize, usize, &mut Graph<i64>) -> (Status, i64, Vec<usize>, Vec<usize>)) {
+    let (mut graph, source, sink, expected) = load_graph(path);
+    let (status, flow_value, cut, cut_edges) = solve(source, sink, &mut graph);
+    assert_eq!(status, Status::Optimal, "{path:?}");
+    assert_eq!(graph.maximum_flow(source), expected, "{path:?}");
+    assert_eq!(flow_value, expected, "{path:?}");
+    check_minimum_cut(&graph, source, sink, &cut);
+    check_min_cut_edges(&graph, source, &cut, &cut_edges);
+}
+
+#[rstest]
+fn dinic(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = Dinic::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn dinic_capacity_scaling(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = Dinic::default();
+        solver.set_capacity_scaling(true);
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[test]
+fn dinic_capacity_scaling_random() {
+    let mut rng = XorShift::new(800);
+    for _ in 0..500 {
+        let num_nodes = rng.gen_range(2, 20) as usize;
+        let max_capacity = 10i64.pow(rng.gen_range(0, 9) as u32);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 60) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, max_capacity)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        let mut solver = Dinic::default();
+        solver.set_capacity_scaling(true);
+        assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+        check_minimum_cut(&graph, source, sink, &solver.minimum_cut(source));
+    }
+}
+
+#[test]
+fn dinic_capacity_scaling_fewer_phases() {
+    // source -> a and b -> sink of a huge capacity, and from a to b the unit paths of 2, 3, ..., 31 edges and a huge path of 32 edges.
+    // dinic sends a unit along one more length every phase before the huge path, and the scaling sends the huge flow at once
+    let huge = 1 << 20;
+    let mut graph = Graph::default();
+    let (source, a, b, sink) = (0, 1, 2, 3);
+    graph.add_nodes(4);
+    graph.add_directed_edge(source, a, huge).unwrap();
+    graph.add_directed_edge(b, sink, huge).unwrap();
+    for (length, upper) in (2..32).map(|length| (length, 1)).chain([(32, huge)]) {
+        let mut u = a;
+        for _ in 0..length - 1 {
+            let v = graph.add_node_raw();
+            graph.add_directed_edge(u, v, upper).unwrap();
+            u = v;
+        }
+        graph.add_directed_edge(u, b, upper).unwrap();
+    }
+
+    let mut expected = graph.clone();
+    let mut dinic = Dinic::default();
+    assert_eq!(dinic.solve(source, sink, &mut expected), Status::Optimal);
+
+    let mut solver = Dinic::default();
+    solver.set_capacity_scaling(true);
+    assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+    assert_eq!(graph.maximum_flow(source), huge);
+    assert_eq!(dinic.num_phases(), 31);
+    assert_eq!(solver.num_phases(), 1);
+}
+
+#[test]
+fn long_path_graph() {
+    // deeper than the default thread stack allows a recursive dfs
+    let num_nodes = 500_000;
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 3 + (u % 5) as i64).unwrap();
+    }
+    let (source, sink) = (0, num_nodes - 1);
+
+    let mut solved = graph.clone();
+    assert_eq!(Dinic::default().solve(source, sink, &mut solved), Status::Optimal);
+    assert_eq!(solved.maximum_flow(source), 3);
+
+    let mut solved = graph.clone();
+    let mut solver = Dinic::default();
+    solver.set_capacity_scaling(true);
+    assert_eq!(solver.solve(source, sink, &mut solved), Status::Optimal);
+    assert_eq!(solved.maximum_flow(source), 3);
+
+    let mut solved = graph.clone();
+    assert_eq!(CapacityScaling::default().solve(source, sink, &mut solved), Status::Optimal);
+    assert_eq!(solved.maximum_flow(source), 3);
+
+    // one augmentation and the search that finds no path, with the same visited stamps
+    let mut solved = graph.clone();
+    let mut solver = FordFulkerson::default();
+    assert_eq!(solver.solve(source, sink, &mut solved), Status::Optimal);
+    assert_eq!(solved.maximum_flow(source), 3);
+    assert_eq!((solver.stats().num_augmentations, solver.num_searches()), (1, 2));
+}
+
+#[test]
+fn ford_fulkerson_random() {
+    let mut rng = XorShift::new(802);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 40) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        // the solver is reused, and the stamps of the previous solve are not mistaken for visits
+        let mut solver = FordFulkerson::default();
+        for _ in 0..2 {
+            let mut actual = graph.clone();
+            assert_eq!(solver.solve(source, sink, &mut actual), Status::Optimal);
+            assert_eq!(actual.maximum_flow(source), expected.maximum_flow(source));
+            assert_eq!(solver.num_searches(), solver.stats().num_augmentations + 1);
+        }
+    }
+}
+
+#[rstest]
+fn push_relabel_fifo(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = PushRelabelFIFO::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn capacity_scaling(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = CapacityScaling::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn capacity_scaling_with_parameters(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf, #[values(3, 4, 8)] base: i64) {
+    check(&path, |source, sink, graph| {
+        let mut solver = CapacityScaling::with_parameters(base, None);
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[test]
+fn capacity_scaling_with_parameters_random() {
+    let mut rng = XorShift::new(808);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 40) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 1000)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+        for (base, initial_delta) in [(2, None), (3, None), (4, None), (8, None), (10, None), (4, Some(1)), (4, Some(10)), (2, Some(1 << 20))] {
+            let mut solved = graph.clone();
+            let mut solver = CapacityScaling::with_parameters(base, initial_delta);
+            assert_eq!(solver.solve(source, sink, &mut solved), Status::Optimal);
+            assert_eq!(solved.maximum_flow(source), expected.maximum_flow(source), "{base} {initial_delta:?}");
+        }
+    }
+}
+
+#[test]
+fn capacity_scaling_larger_base_takes_fewer_phases() {
+    // capacities of 10^9 on a few arcs, and unit ones elsewhere
+    let mut graph = Graph::default();
+    graph.add_nodes(6);
+    for (u, v, upper) in [(0, 1, 1_000_000_000), (1, 2, 1_000_000_000), (2, 5, 999_999_999), (0, 3, 1), (3, 4, 1), (4, 5, 1), (1, 4, 1)] {
+        graph.add_directed_edge(u, v, upper).unwrap();
+    }
+
+    let mut num_phases = Vec::new();
+    for base in [2, 4, 8] {
+        let mut solved = graph.clone();
+        let mut solver = CapacityScaling::with_parameters(base, None);
+        assert_eq!(solver.solve(0, 5, &mut solved), Status::Optimal);
+        assert_eq!(solver.flow_value(), 1_000_000_000);
+        num_phases.push(solver.stats().num_phases);
+    }
+    assert!(num_phases[0] > num_phases[1] && num_phases[1] > num_phases[2], "{num_phases:?}");
+}
+
+#[test]
+fn capacity_scaling_near_overflow() {
+    // the powers of 2 above 2^30 do not fit in i32
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, i32::MAX).unwrap();
+    graph.add_directed_edge(1, 3, i32::MAX - 1).unwrap();
+    graph.add_directed_edge(1, 2, 1 << 30).unwrap();
+    graph.add_directed_edge(2, 3, 5).unwrap();
+
+    for base in [2, 3, 7, i32::MAX] {
+        let mut solved = graph.clone();
+        let mut solver = CapacityScaling::with_parameters(base, None);
+        assert_eq!(solver.solve(0, 3, &mut solved), Status::Optimal);
+        assert_eq!(solved.maximum_flow(0), i32::MAX);
+        assert_eq!(solver.flow_value(), i32::MAX);
+    }
+    let mut solved = graph.clone();
+    assert_eq!(CapacityScaling::default().solve(0, 3, &mut solved), Status::Optimal);
+    assert_eq!(solved.maximum_flow(0), i32::MAX);
+}
+
+#[test]
+#[should_panic]
+fn capacity_scaling_base_below_two() {
+    CapacityScaling::<i64>::with_parameters(1, None);
+}
+
+#[rstest]
+fn excess_scaling(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = ExcessScaling::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn mpm(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = Mpm::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn boykov_kolmogorov(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = BoykovKolmogorov::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+// LibreOJ_101 is too large for the algorithms that augment one path at a time
+#[rstest]
+fn edmonds_karp(#[files("tests/maximum_flow/AOJ_GRL_6_A/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = EdmondsKarp::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[test]
+fn edmonds_karp_with_limit() {
+    let mut rng = XorShift::new(806);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 40) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+        let maximum_flow = expected.maximum_flow(source);
+
+        let mut solver = EdmondsKarp::default();
+        let mut unlimited = graph.clone();
+        assert_eq!(solver.solve(source, sink, &mut unlimited), Status::Optimal);
+        assert_eq!((solver.flow_value(), unlimited.maximum_flow(source)), (maximum_flow, maximum_flow));
+
+        for limit in [0, 1, rng.gen_range(0, 30), maximum_flow, maximum_flow + 1] {
+            let mut limited = graph.clone();
+            let value = solver.solve_with_limit(source, sink, limit, &mut limited);
+            assert_eq!(value, limit.min(maximum_flow));
+            assert_eq!(limited.maximum_flow(source), value);
+            // a feasible flow
+            let mut balance = vec![0; num_nodes];
+            for edge in (0..limited.num_edges()).map(|edge_id| limited.get_edge(edge_id).unwrap()) {
+                assert!(0 <= edge.flow && edge.flow <= edge.upper);
+                balance[edge.from] -= edge.flow;
+                balance[edge.to] += edge.flow;
+            }
+            assert!((0..num_nodes).filter(|&u| u != source && u != sink).all(|u| balance[u] == 0));
+        }
+    }
+
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    assert_eq!(EdmondsKarp::default().solve_with_limit(1, 1, 3, &mut graph), 0);
+    assert_eq!(graph.get_edge(0).unwrap().flow, 0);
+}
+
+// LibreOJ_101 is too large for the algorithms that augment one path at a time
+#[rstest]
+fn ford_fulkerson(#[files("tests/maximum_flow/AOJ_GRL_6_A/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = FordFulkerson::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn shortest_augmenting_path(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = ShortestAugmentingPath::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[rstest]
+fn isap(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |source, sink, graph| {
+        let mut solver = Isap::default();
+        let status = solver.solve(source, sink, graph);
+        (status, solver.flow_value(), solver.minimum_cut(source), solver.min_cut_edges(source))
+    });
+}
+
+#[test]
+fn isap_random() {
+    let mut rng = XorShift::new(799);
+    for _ in 0..500 {
+        let num_nodes = rng.gen_range(2, 20) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 60) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        if source == sink {
+            continue;
+        }
+
+        let mut expected = graph.clone();
+        assert_eq!(ShortestAugmentingPath::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        let mut solver = Isap::default();
+        assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+        check_minimum_cut(&graph, source, sink, &solver.minimum_cut(source));
+        check_min_cut_edges(&graph, source, &solver.minimum_cut(source), &solver.min_cut_edges(source));
+    }
+}
+
+#[test]
+fn isap_layered_graph() {
+    // source -> a long chain -> hub -> many unit edges -> sink.
+    // ShortestAugmentingPath walks the chain and the saturated edges of hub again for every augmentation
+    let (chain_length, width) = (200, 200);
+    let mut graph = Graph::default();
+    graph.add_nodes(chain_length + width + 2);
+    let (source, sink) = (0, chain_length + width + 1);
+    for u in 0..chain_length {
+        graph.add_directed_edge(u, u + 1, width as i64).unwrap();
+    }
+    for k in 0..width {
+        let v = chain_length + 1 + k;
+        graph.add_directed_edge(chain_length, v, 1).unwrap();
+        graph.add_directed_edge(v, sink, 1).unwrap();
+    }
+
+    let mut expected = graph.clone();
+    let mut shortest_augmenting_path = ShortestAugmentingPath::default();
+    assert_eq!(shortest_augmenting_path.solve(source, sink, &mut expected), Status::Optimal);
+    let mut solver = Isap::default();
+    assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), width as i64);
+    assert_eq!(expected.maximum_flow(source), width as i64);
+    assert!(solver.num_edge_scans() * 10 < shortest_augmenting_path.num_edge_scans());
+}
+
+#[test]
+fn shortest_augmenting_path_gap_relabeling() {
+    // source -> a path -> sink through a single unit of capacity, with a long dead end chain hanging off each node of the path.
+    // once the unit is sent, the labels of the path climb to n two at a time without the gap heuristic
+    let (path_length, chain_length) = (100, 20);
+    let mut graph = Graph::default();
+    let source = graph.add_node();
+    let path = graph.add_nodes(path_length);
+    let sink = graph.add_node();
+    graph.add_directed_edge(source, path[0], 10).unwrap();
+    for (i, &u) in path.iter().enumerate() {
+        let next = if i + 1 < path_length { path[i + 1] } else { sink };
+        graph.add_directed_edge(u, next, if next == sink { 1 } else { 10 }).unwrap();
+        let mut tail = u;
+        for v in graph.add_nodes(chain_length) {
+            graph.add_directed_edge(tail, v, 10).unwrap();
+            tail = v;
+        }
+    }
+
+    let mut without_gap = ShortestAugmentingPath::default();
+    without_gap.set_gap_relabeling(false);
+    let mut expected = graph.clone();
+    assert_eq!(without_gap.solve(source, sink, &mut expected), Status::Optimal);
+    let mut solver = ShortestAugmentingPath::default();
+    assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), 1);
+    assert_eq!(expected.maximum_flow(source), 1);
+    assert_eq!(solver.flow_value(), 1);
+    assert!(solver.num_edge_scans() * 10 < without_gap.num_edge_scans(), "{} {}", solver.num_edge_scans(), without_gap.num_edge_scans());
+}
+
+#[test]
+fn shortest_augmenting_path_gap_relabeling_random() {
+    let mut rng = XorShift::new(807);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 40) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        let mut without_gap = ShortestAugmentingPath::default();
+        without_gap.set_gap_relabeling(false);
+        assert_eq!(without_gap.solve(source, sink, &mut expected), Status::Optimal);
+        let mut solver = ShortestAugmentingPath::default();
+        assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+        assert!(solver.num_edge_scans() <= without_gap.num_edge_scans());
+    }
+}
+
+#[test]
+fn minimum_cut_parallel_and_zero_capacity_edges() {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 2).unwrap();
+    graph.add_directed_edge(0, 1, 3).unwrap();
+    graph.add_directed_edge(0, 2, 0).unwrap();
+    graph.add_directed_edge(1, 2, 4).unwrap();
+    graph.add_directed_edge(1, 3, 0).unwrap();
+    graph.add_directed_edge(2, 3, 10).unwrap();
+    graph.add_directed_edge(2, 3, 0).unwrap();
+
+    let mut solver = Dinic::default();
+    assert_eq!(solver.solve(0, 3, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 4);
+    let mut cut = solver.minimum_cut(0);
+    cut.sort();
+    assert_eq!(cut, vec![0, 1]);
+    check_minimum_cut(&graph, 0, 3, &cut);
+    // parallel edges are listed separately, and zero capacity edges cross the cut as well
+    assert_eq!(solver.min_cut_edges(0), vec![2, 3, 4]);
+}
+
+#[test]
+fn minimum_cut_no_path() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    graph.add_directed_edge(2, 1, 5).unwrap();
+
+    let mut solver = PushRelabelFIFO::default();
+    assert_eq!(solver.solve(0, 2, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 0);
+    let mut cut = solver.minimum_cut(0);
+    cut.sort();
+    assert_eq!(cut, vec![0, 1]);
+}
+
+#[test]
+fn minimum_cut_random() {
+    let mut rng = XorShift::new(753);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 30) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut dinic_graph = graph.clone();
+        let mut dinic = Dinic::default();
+        assert_eq!(dinic.solve(source, sink, &mut dinic_graph), Status::Optimal);
+        check_minimum_cut(&dinic_graph, source, sink, &dinic.minimum_cut(source));
+        check_min_cut_edges(&dinic_graph, source, &dinic.minimum_cut(source), &dinic.min_cut_edges(source));
+
+        let mut push_relabel = PushRelabelFIFO::default();
+        assert_eq!(push_relabel.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), dinic_graph.maximum_flow(source));
+        check_minimum_cut(&graph, source, sink, &push_relabel.minimum_cut(source));
+        check_min_cut_edges(&graph, source, &push_relabel.minimum_cut(source), &push_relabel.min_cut_edges(source));
+    }
+}
+
+#[test]
+fn excess_scaling_random() {
+    let mut rng = XorShift::new(796);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 30) as usize;
+        // the capacities vary from 0 to 10^12
+        let max_capacity = 10i64.pow(rng.gen_range(0, 12) as u32);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 150) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, max_capacity)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        let mut solver = ExcessScaling::default();
+        assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+        check_minimum_cut(&graph, source, sink, &solver.minimum_cut(source));
+        check_min_cut_edges(&graph, source, &solver.minimum_cut(source), &solver.min_cut_edges(source));
+    }
+}
+
+#[test]
+fn mpm_random() {
+    let mut rng = XorShift::new(797);
+    for _ in 0..500 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        // many zero capacities, and dense enough for the nodes to be deleted in the middle of a phase
+        for _ in 0..rng.gen_range(0, 80) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 4).max(rng.gen_range(-4, 0))).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        let mut solver = Mpm::default();
+        assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+        check_minimum_cut(&graph, source, sink, &solver.minimum_cut(source));
+        check_min_cut_edges(&graph, source, &solver.minimum_cut(source), &solver.min_cut_edges(source));
+    }
+}
+
+fn check_boykov_kolmogorov(graph: &Graph<i64>, source: usize, sink: usize) {
+    let mut expected = graph.clone();
+    assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+    let mut solver = BoykovKolmogorov::default();
+    let mut actual = graph.clone();
+    assert_eq!(solver.solve(source, sink, &mut actual), Status::Optimal);
+    assert_eq!(actual.maximum_flow(source), expected.maximum_flow(source));
+    // the source tree is the minimum cut
+    let source_tree: Vec<usize> = (0..graph.num_nodes()).filter(|&u| solver.is_source_side(u)).collect();
+    assert_eq!(source_tree, solver.minimum_cut(source));
+    check_minimum_cut(&actual, source, sink, &source_tree);
+    check_min_cut_edges(&actual, source, &source_tree, &solver.min_cut_edges(source));
+}
+
+#[test]
+fn boykov_kolmogorov_random() {
+    let mut rng = XorShift::new(798);
+    for _ in 0..500 {
+        let num_nodes = rng.gen_range(2, 20) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 60) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        if source != sink {
+            check_boykov_kolmogorov(&graph, source, sink);
+        }
+    }
+}
+
+#[test]
+fn boykov_kolmogorov_grid() {
+    // 100 x 100 pixels with the terminal edges from source and to sink, and the 4 neighbors in both directions
+    let mut rng = XorShift::new(7980);
+    let (height, width) = (100, 100);
+    let mut graph = Graph::default();
+    graph.add_nodes(height * width + 2);
+    let (source, sink) = (height * width, height * width + 1);
+    for y in 0..height {
+        for x in 0..width {
+            let u = y * width + x;
+            graph.add_directed_edge(source, u, rng.gen_range(0, 100)).unwrap();
+            graph.add_directed_edge(u, sink, rng.gen_range(0, 100)).unwrap();
+            if x + 1 < width {
+                graph.add_directed_edge(u, u + 1, rng.gen_range(0, 30)).unwrap();
+                graph.add_directed_edge(u + 1, u, rng.gen_range(0, 30)).unwrap();
+            }
+            if y + 1 < height {
+                graph.add_directed_edge(u, u + width, rng.gen_range(0, 30)).unwrap();
+                graph.add_directed_edge(u + width, u, rng.gen_range(0, 30)).unwrap();
+            }
+        }
+    }
+    check_boykov_kolmogorov(&graph, source, sink);
+}
+
+#[test]
+fn excess_scaling_large_capacities() {
+    let mut rng = XorShift::new(7960);
+    let large = i64::MAX / 2;
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(3, 12) as usize;
+        let (source, sink) = (0, num_nodes - 1);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        // at most 2 edges at source, so that the excesses and the value fit in i64
+        for _ in 0..rng.gen_range(1, 2) {
+            graph.add_directed_edge(source, rng.gen_range(1, num_nodes as i64 - 1) as usize, large - rng.gen_range(0, 3)).unwrap();
+        }
+        for _ in 0..rng.gen_range(0, 40) {
+            let (u, v) = (rng.gen_range(1, num_nodes as i64 - 1) as usize, rng.gen_range(1, num_nodes as i64 - 1) as usize);
+            let upper = if rng.gen_range(0, 1) == 0 { large - rng.gen_range(0, 1000) } else { rng.gen_range(0, 1000) };
+            graph.add_directed_edge(u, v, upper).unwrap();
+        }
+
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        let mut solver = ExcessScaling::default();
+        assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+        check_minimum_cut(&graph, source, sink, &solver.minimum_cut(source));
+    }
+}
+
+#[test]
+fn min_cut_edges_multiple_minimum_cuts() {
+    // 0 -> 1 -> 2 -> 3 with capacities 3, 3, 3: any of the three edges is a minimum cut
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 3).unwrap();
+    graph.add_directed_edge(1, 2, 3).unwrap();
+    graph.add_directed_edge(2, 3, 3).unwrap();
+    graph.add_directed_edge(0, 2, 0).unwrap();
+
+    let mut solver = Dinic::default();
+    assert_eq!(solver.solve(0, 3, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 3);
+    let cut_edges = solver.min_cut_edges(0);
+    check_min_cut_edges(&graph, 0, &solver.minimum_cut(0), &cut_edges);
+
+    // removing the edges disconnects the sink from the source
+    let mut removed = Graph::default();
+    removed.add_nodes(4);
+    for edge_id in (0..graph.num_edges()).filter(|edge_id| !cut_edges.contains(edge_id)) {
+        let edge = graph.get_edge(edge_id).unwrap();
+        removed.add_directed_edge(edge.from, edge.to, edge.upper).unwrap();
+    }
+    assert_eq!(Dinic::default().solve(0, 3, &mut removed), Status::Optimal);
+    assert_eq!(removed.maximum_flow(0), 0);
+}
+
+// (status, source-minimal cut, sink-minimal cut, uniqueness), each cut as (source side, crossing edges)
+type ExtremeCuts = (Status, (Vec<usize>, Vec<usize>), (Vec<usize>, Vec<usize>), bool);
+
+#[allow(clippy::type_complexity)]
+fn extreme_cut_solvers() -> Vec<(&'static str, fn(usize, usize, &mut Graph<i64>) -> ExtremeCuts)> {
+    vec![
+        ("dinic", |source, sink, graph| {
+            let mut solver = Dinic::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("push_relabel_fifo", |source, sink, graph| {
+            let mut solver = PushRelabelFIFO::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("capacity_scaling", |source, sink, graph| {
+            let mut solver = CapacityScaling::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("excess_scaling", |source, sink, graph| {
+            let mut solver = ExcessScaling::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("mpm", |source, sink, graph| {
+            let mut solver = Mpm::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("boykov_kolmogorov", |source, sink, graph| {
+            let mut solver = BoykovKolmogorov::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("isap", |source, sink, graph| {
+            let mut solver = Isap::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("edmonds_karp", |source, sink, graph| {
+            let mut solver = EdmondsKarp::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("ford_fulkerson", |source, sink, graph| {
+            let mut solver = FordFulkerson::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+        ("shortest_augmenting_path", |source, sink, graph| {
+            let mut solver = ShortestAugmentingPath::default();
+            (solver.solve(source, sink, graph), solver.min_cut_source_minimal(source, sink), solver.min_cut_sink_minimal(source, sink), solver.cut_is_unique(source, sink))
+        }),
+    ]
+}
+
+#[allow(clippy::type_complexity)]
+fn flow_value_solvers() -> Vec<(&'static str, fn(usize, usize, &mut Graph<i64>) -> (Status, i64))> {
+    vec![
+        ("dinic", |source, sink, graph| {
+            let mut solver = Dinic::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("push_relabel_fifo", |source, sink, graph| {
+            let mut solver = PushRelabelFIFO::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("capacity_scaling", |source, sink, graph| {
+            let mut solver = CapacityScaling::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("excess_scaling", |source, sink, graph| {
+            let mut solver = ExcessScaling::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("mpm", |source, sink, graph| {
+            let mut solver = Mpm::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("boykov_kolmogorov", |source, sink, graph| {
+            let mut solver = BoykovKolmogorov::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("isap", |source, sink, graph| {
+            let mut solver = Isap::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("edmonds_karp", |source, sink, graph| {
+            let mut solver = EdmondsKarp::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("ford_fulkerson", |source, sink, graph| {
+            let mut solver = FordFulkerson::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+        ("shortest_augmenting_path", |source, sink, graph| {
+            let mut solver = ShortestAugmentingPath::default();
+            (solver.solve(source, sink, graph), solver.flow_value())
+        }),
+    ]
+}
+
+#[test]
+fn flow_value_random() {
+    let mut rng = XorShift::new(805);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        // with self loops, parallel edges and edges into source and out of sink
+        for _ in 0..rng.gen_range(0, 50) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+        if source == sink {
+            continue;
+        }
+        for (name, solve) in flow_value_solvers() {
+            let mut solved = graph.clone();
+            let (status, flow_value) = solve(source, sink, &mut solved);
+            assert_eq!(status, Status::Optimal, "{name}");
+            assert_eq!(flow_value, solved.maximum_flow(source), "{name}");
+        }
+    }
+
+    // 0 for bad input
+    for (name, solve) in flow_value_solvers() {
+        let mut graph = Graph::default();
+        graph.add_nodes(2);
+        graph.add_directed_edge(0, 1, 5).unwrap();
+        assert_eq!(solve(0, 0, &mut graph), (Status::BadInput, 0), "{name}");
+    }
+}
+
+#[test]
+fn extreme_cuts_differ() {
+    // 0 -> 1 -> 2 -> 3 with capacities 1, 5, 1 and the isolated node 4.
+    // the source-minimal cut is {0}, and the sink-minimal one adds {1, 2, 4}
+    let mut graph = Graph::default();
+    graph.add_nodes(5);
+    graph.add_directed_edge(0, 1, 1).unwrap();
+    graph.add_directed_edge(1, 2, 5).unwrap();
+    graph.add_directed_edge(2, 3, 1).unwrap();
+    for (name, solve) in extreme_cut_solvers() {
+        let (status, source_minimal, sink_minimal, is_unique) = solve(0, 3, &mut graph.clone());
+        assert_eq!(status, Status::Optimal, "{name}");
+        assert_eq!(source_minimal, (vec![0], vec![0]), "{name}");
+        assert_eq!(sink_minimal, (vec![0, 1, 2, 4], vec![2]), "{name}");
+        assert!(!is_unique, "{name}");
+    }
+
+    // zero flow: the nodes reachable from the source
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 4).unwrap();
+    graph.add_directed_edge(2, 1, 4).unwrap();
+    let mut solver = Dinic::default();
+    assert_eq!(solver.solve(0, 2, &mut graph), Status::Optimal);
+    assert_eq!(solver.min_cut_source_minimal(0, 2), (vec![0, 1], vec![]));
+    assert_eq!(solver.min_cut_sink_minimal(0, 2), (vec![0, 1], vec![]));
+    assert!(solver.cut_is_unique(0, 2));
+}
+
+#[test]
+fn extreme_cuts_brute_force() {
+    let mut rng = XorShift::new(769);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 7) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(1, 15) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 5)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        // the source sides of all minimum cuts. the source-minimal one is their intersection, and the sink-minimal one their union
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+        let value = expected.maximum_flow(source);
+        let minimum_cuts: Vec<u32> = (0..1u32 << num_nodes)
+            .filter(|&side| side >> source & 1 == 1 && side >> sink & 1 == 0)
+            .filter(|&side| cut_capacity(&graph, &(0..num_nodes).filter(|&u| side >> u & 1 == 1).collect::<Vec<_>>()) == value)
+            .collect();
+        let nodes = |side: u32| (0..num_nodes).filter(|&u| side >> u & 1 == 1).collect::<Vec<_>>();
+        let intersection = nodes(minimum_cuts.iter().fold(u32::MAX, |acc, &side| acc & side));
+        let union = nodes(minimum_cuts.iter().fold(0, |acc, &side| acc | side));
+
+        for (name, solve) in extreme_cut_solvers() {
+            let mut solved = graph.clone();
+            let (status, (source_side, source_edges), (sink_side, sink_edges), is_unique) = solve(source, sink, &mut solved);
+            assert_eq!(status, Status::Optimal, "{name}");
+            assert_eq!(source_side, intersection, "{name}");
+            assert_eq!(sink_side, union, "{name}");
+            assert_eq!(is_unique, minimum_cuts.len() == 1, "{name}");
+            check_min_cut_edges(&solved, source, &source_side, &source_edges);
+            check_min_cut_edges(&solved, source, &sink_side, &sink_edges);
+        }
+    }
+}
+
+fn solvers() -> Vec<Box<dyn MaximumFlowSolver<i64>>> {
+    vec![
+        Box::new(Dinic::default()),
+        Box::new(PushRelabelFIFO::default()),
+        Box::new(CapacityScaling::default()),
+        Box::new(ExcessScaling::default()),
+        Box::new(Mpm::default()),
+        Box::new(BoykovKolmogorov::default()),
+        Box::new(EdmondsKarp::default()),
+        Box::new(FordFulkerson::default()),
+        Box::new(ShortestAugmentingPath::default()),
+        Box::new(Isap::default()),
+    ]
+}
+
+#[rstest]
+fn trait_objects(#[files("tests/maximum_flow/AOJ_GRL_6_A/*.txt")] path: PathBuf) {
+    for mut solver in solvers() {
+        let (mut graph, source, sink, expected) = load_graph(&path);
+        assert_eq!(solver.solve(source.into(), sink.into(), &mut graph), Status::Optimal, "{}", solver.name());
+        assert_eq!(graph.maximum_flow(source), expected, "{}", solver.name());
+        assert_eq!(solver.objective(source.into(), &graph), expected, "{}", solver.name());
+    }
+}
+
+#[test]
+fn trait_objects_stats() {
+    // 2 disjoint paths
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    for (from, to) in [(0, 1), (1, 3), (0, 2), (2, 3)] {
+        graph.add_directed_edge(from, to, 3).unwrap();
+    }
+
+    let mut solvers = solvers();
+    for solver in solvers.iter_mut() {
+        assert_eq!(*solver.stats(), Stats::default(), "{}", solver.name());
+        let mut graph = graph.clone();
+        assert_eq!(solver.solve(0.into(), 3.into(), &mut graph), Status::Optimal, "{}", solver.name());
+        assert_eq!(solver.objective(0.into(), &graph), 6, "{}", solver.name());
+    }
+    let stats: Vec<(&str, Stats)> = solvers.iter().map(|solver| (solver.name(), solver.stats().clone())).collect();
+    for (name, stats) in stats {
+        match name {
+            // counts its pushes and relabels, not the phases or augmentations of the sink
+            "push_relabel_fifo" => assert!(stats.num_pushes > 0 && stats.num_phases == 0 && stats.num_augmentations == 0, "{name}"),
+            "dinic" | "capacity_scaling" | "excess_scaling" | "mpm" => assert!(stats.num_phases > 0 && stats.num_augmentations == 0, "{name}"),
+            _ => assert_eq!(stats, Stats { num_augmentations: 2, ..Stats::default() }, "{name}"),
+        }
+    }
+}
+
+#[test]
+fn trait_objects_bad_input() {
+    let names: Vec<&str> = solvers().iter().map(|solver| solver.name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "dinic",
+            "push_relabel_fifo",
+            "capacity_scaling",
+            "excess_scaling",
+            "mpm",
+            "boykov_kolmogorov",
+            "edmonds_karp",
+            "ford_fulkerson",
+            "shortest_augmenting_path",
+            "isap"
+        ]
+    );
+
+    for (source, sink) in [(0, 0), (0, 3), (3, 0), (5, 5)] {
+        for mut solver in solvers() {
+            let mut graph = Graph::default();
+            graph.add_nodes(3);
+            graph.add_directed_edge(0, 1, 1).unwrap();
+            graph.add_directed_edge(1, 2, 1).unwrap();
+            assert_eq!(solver.solve(source.into(), sink.into(), &mut graph), Status::BadInput, "{} {source} {sink}", solver.name());
+        }
+    }
+}
+
+#[test]
+fn bad_input_matrix() {
+    let edges = |graph: &Graph<i64>| (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).collect::<Vec<_>>();
+
+    // (nodes, has the path 0 -> 1 -> 2, source, sink)
+    let cases = [
+        (0, false, 0, 0),
+        (0, false, 0, 1),
+        (1, false, 0, 0),
+        (3, false, 1, 1),
+        (3, false, 3, 0),
+        (3, true, 0, 3),
+        (3, true, usize::MAX, 2),
+        (3, true, 2, 2),
+    ];
+    for (num_nodes, has_path, source, sink) in cases {
+        for mut solver in solvers() {
+            let mut graph = Graph::default();
+            graph.add_nodes(num_nodes);
+            if has_path {
+                graph.add_directed_edge(0, 1, 1).unwrap();
+                graph.add_directed_edge(1, 2, 1).unwrap();
+            }
+            // the flows of a previous solve are kept
+            if num_nodes > 1 {
+                assert_eq!(solver.solve(0.into(), (num_nodes - 1).into(), &mut graph), Status::Optimal);
+            }
+            let before = edges(&graph);
+
+            assert_eq!(solver.solve(source.into(), sink.into(), &mut graph), Status::BadInput, "{} {num_nodes} {source} {sink}", solver.name());
+            assert_eq!(edges(&graph), before, "{}", solver.name());
+            assert_eq!(graph.num_nodes(), num_nodes);
+            assert!(!graph.is_preflow());
+        }
+    }
+
+    // a graph without edges is not bad input
+    for mut solver in solvers() {
+        let mut graph = Graph::<i64>::default();
+        graph.add_nodes(2);
+        assert_eq!(solver.solve(0.into(), 1.into(), &mut graph), Status::Optimal, "{}", solver.name());
+        assert_eq!(graph.maximum_flow(0), 0);
+    }
+}
+
+#[rstest]
+fn quick_bounds_files(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf, #[values(Effort::Cheap, Effort::Moderate)] effort: Effort) {
+    let (graph, source, sink, expected) = load_graph(&path);
+    let bounds = quick_bounds(source, sink, &graph, effort).unwrap();
+    assert!(bounds.lower <= expected && expected <= bounds.upper, "{path:?}");
+    assert!(bounds.verify(source, sink, &graph));
+    assert!((0.0..=1.0).contains(&bounds.gap()));
+
+    // linear in the size of the graph
+    if effort == Effort::Cheap {
+        assert!(bounds.num_operations() <= 20 * (graph.num_nodes() + graph.num_edges()), "{path:?}");
+    }
+}
+
+#[test]
+fn quick_bounds_random() {
+    let mut rng = XorShift::new(755);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 12) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for _ in 0..rng.gen_range(0, 40) {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_directed_edge(u, v, rng.gen_range(0, 10)).unwrap();
+        }
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut solved = graph.clone();
+        Dinic::default().solve(source, sink, &mut solved);
+        let expected = solved.maximum_flow(source);
+
+        for effort in [Effort::Cheap, Effort::Moderate] {
+            let bounds = quick_bounds(source, sink, &graph, effort).unwrap();
+            assert!(bounds.lower <= expected && expected <= bounds.upper);
+            assert!(bounds.verify(source, sink, &graph));
+        }
+    }
+}
+
+#[test]
+fn quick_bounds_tight() {
+    // a single path, found by the first augmentation
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    graph.add_directed_edge(1, 2, 3).unwrap();
+    graph.add_directed_edge(2, 3, 4).unwrap();
+    for effort in [Effort::Cheap, Effort::Moderate] {
+        let bounds = quick_bounds(0, 3, &graph, effort).unwrap();
+        assert_eq!((bounds.lower, bounds.upper), (3, 3));
+        assert_eq!(bounds.gap(), 0.0);
+        assert_eq!(bounds.flows(), &[3, 3, 3]);
+        assert_eq!(bounds.cut(), &[0, 1]);
+    }
+
+    // 20 unit edges in the middle are the bottleneck, more than the augmentations of Cheap
+    let mut graph = Graph::default();
+    graph.add_nodes(23);
+    let (source, middle, sink) = (0, 21, 22);
+    for u in 1..=20 {
+        graph.add_directed_edge(source, u, 10).unwrap();
+        graph.add_directed_edge(u, middle, 1).unwrap();
+    }
+    graph.add_directed_edge(middle, sink, 100).unwrap();
+
+    let cheap = quick_bounds(source, sink, &graph, Effort::Cheap).unwrap();
+    assert_eq!((cheap.lower, cheap.upper), (8, 100));
+    assert!(cheap.verify(source, sink, &graph));
+
+    let moderate = quick_bounds(source, sink, &graph, Effort::Moderate).unwrap();
+    assert_eq!((moderate.lower, moderate.upper), (20, 20));
+    assert!(moderate.verify(source, sink, &graph));
+}
+
+#[test]
+fn quick_bounds_bad_input() {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 1).unwrap();
+    assert_eq!(quick_bounds(0, 0, &graph, Effort::Cheap), Err(Status::BadInput));
+    assert_eq!(quick_bounds(0, 2, &graph, Effort::Moderate), Err(Status::BadInput));
+}
+
+#[rstest]
+fn dimacs_round_trip(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    let (graph, source, sink, expected) = load_graph(&path);
+    let (mut graph, source, sink) = Graph::<i64>::from_dimacs(&graph.to_dimacs(source, sink)).unwrap();
+    assert_eq!(Dinic::default().solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), expected);
+}
+
+#[test]
+fn dimacs_format() {
+    let text = "c a small instance\n\
+                c\n\
+                p  max\t4   5\n\
+                \n\
+                n 1 s\n\
+                n   4 t   \n\
+                c arcs\n\
+                a 1 2 3\n\
+                a 1 3 2\n\
+                \ta 2 3 5\n\
+                a 2 4 2\n\
+                a 3 4 3\n";
+    let (mut graph, source, sink) = Graph::<i64>::from_dimacs(text).unwrap();
+    assert_eq!((graph.num_nodes(), graph.num_edges(), source, sink), (4, 5, 0, 3));
+    assert_eq!(graph.get_edge(2).unwrap(), Edge { from: 1, to: 2, flow: 0, upper: 5 });
+    assert_eq!(Dinic::default().solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), 5);
+    assert_eq!(graph.to_dimacs(source, sink), "p max 4 5\nn 1 s\nn 4 t\na 1 2 3\na 1 3 2\na 2 3 5\na 2 4 2\na 3 4 3\n");
+}
+
+#[test]
+fn dimacs_errors() {
+    let parse = |text: &str| Graph::<i64>::from_dimacs(text).map(|_| ());
+    assert_eq!(parse(""), Err(DimacsError::MissingProblemLine));
+    assert_eq!(parse("n 1 s\np max 2 0\n"), Err(DimacsError::MissingProblemLine));
+    assert_eq!(parse("p max 2 0\np max 2 0\n"), Err(DimacsError::DuplicateProblemLine { line: 2 }));
+    assert_eq!(parse("p min 2 0\n"), Err(DimacsError::UnknownLine { line: 1 }));
+    assert_eq!(parse("p max 2 1\nn 1 s\nn 2 t\na 1 2 x\n"), Err(DimacsError::InvalidNumber { line: 4 }));
+    assert_eq!(parse("p max 2 1\nn 1 s\nn 2 t\na 1 3 1\n"), Err(DimacsError::NodeOutOfRange { line: 4 }));
+    assert_eq!(parse("p max 2 1\nn 0 s\n"), Err(DimacsError::NodeOutOfRange { line: 2 }));
+    assert_eq!(parse("p max 2 1\nn 1 s\nn 2 t\na 1 2 -1\n"), Err(DimacsError::NegativeCapacity { line: 4 }));
+    assert_eq!(parse("p max 2 0\nn 1 s\nn 2 s\n"), Err(DimacsError::DuplicateTerminal { line: 3 }));
+    assert_eq!(parse("p max 2 0\nn 2 t\n"), Err(DimacsError::MissingSource));
+    assert_eq!(parse("p max 2 0\nn 1 s\n"), Err(DimacsError::MissingSink));
+    assert_eq!(parse("p max 2 2\nn 1 s\nn 2 t\na 1 2 1\n"), Err(DimacsError::ArcCountMismatch { expected: 2, actual: 1 }));
+    assert_eq!(parse("p max 2 0\nn 1 x\n"), Err(DimacsError::UnknownLine { line: 2 }));
+}
+
+#[test]
+fn dot() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 2).unwrap();
+    graph.add_directed_edge(1, 2, 3).unwrap();
+    graph.add_directed_edge(0, 2, 4).unwrap();
+    graph.add_directed_edge(2, 0, 1).unwrap();
+    assert_eq!(Dinic::default().solve(0, 2, &mut graph), Status::Optimal);
+
+    assert_eq!(
+        graph.to_dot(),
+        "digraph {\n    0;\n    1;\n    2;\n    0 -> 1 [label=\"2/2\", style=bold];\n    1 -> 2 [label=\"2/3\"];\n    0 -> 2 [label=\"4/4\", style=bold];\n    2 -> 0 [label=\"0/1\"];\n}\n"
+    );
+    assert_eq!(
+        graph.to_dot_with_options(&DotOptions { show_zero_flow_edges: false }),
+        "digraph {\n    0;\n    1;\n    2;\n    0 -> 1 [label=\"2/2\", style=bold];\n    1 -> 2 [label=\"2/3\"];\n    0 -> 2 [label=\"4/4\", style=bold];\n}\n"
+    );
+}
+
+// simple source-sink walks, and what is left of the flow is conserved at every node (the canceled cycles)
+fn check_decomposition(graph: &Graph<i64>, source: usize, sink: usize, paths: &[(Vec<usize>, i64)]) {
+    assert!(paths.len() <= graph.num_edges());
+    let mut left: Vec<i64> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect();
+    for (path, amount) in paths.iter() {
+        assert!(*amount > 0);
+        let mut visited = vec![false; graph.num_nodes()];
+        let mut u = source;
+        visited[u] = true;
+        for &edge_id in path.iter() {
+            let edge = graph.get_edge(edge_id).unwrap();
+            assert_eq!(edge.from, u);
+            u = edge.to;
+            assert!(!visited[u]);
+            visited[u] = true;
+            left[edge_id] -= amount;
+        }
+        assert_eq!(u, sink);
+    }
+
+    assert!(left.iter().all(|&flow| flow >= 0));
+    let mut balances = vec![0; graph.num_nodes()];
+    for (edge_id, &flow) in left.iter().enumerate() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        balances[edge.from] += flow;
+        balances[edge.to] -= flow;
+    }
+    assert!(balances.iter().all(|&balance| balance == 0));
+    assert_eq!(paths.iter().map(|(_, amount)| amount).sum::<i64>(), graph.maximum_flow(source));
+}
+
+#[test]
+fn decompose_three_paths() {
+    // each edge leaving the source has capacity 2, and the middle edge can take only one of the three units arriving at node 2
+    let mut graph = Graph::default();
+    graph.add_nodes(5);
+    graph.add_directed_edge(0, 1, 2).unwrap();
+    graph.add_directed_edge(0, 2, 3).unwrap();
+    graph.add_directed_edge(1, 4, 2).unwrap();
+    graph.add_directed_edge(2, 4, 2).unwrap();
+    graph.add_directed_edge(2, 3, 1).unwrap();
+    graph.add_directed_edge(3, 4, 1).unwrap();
+    assert_eq!(Dinic::default().solve(0, 4, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 5);
+
+    let mut paths = decompose(0, 4, &graph);
+    check_decomposition(&graph, 0, 4, &paths);
+    paths.sort();
+    assert_eq!(paths, vec![(vec![0, 2], 2), (vec![1, 3], 2), (vec![1, 4, 5], 1)]);
+}
+
+#[rstest]
+fn decompose_files(#[files("tests/maximum_flow/*/*.txt")] path: PathBuf) {
+    let (graph, source, sink, expected) = load_graph(&path);
+    let solvers: Vec<Box<dyn MaximumFlowSolver<i64>>> = vec![Box::new(Dinic::default()), Box::new(PushRelabelFIFO::default())];
+    for mut solver in solvers {
+        let mut graph = graph.clone();
+        assert_eq!(solver.solve(source.into(), sink.into(), &mut graph), Status::Optimal);
+        assert_eq!(graph.maximum_flow(source), expected);
+        check_decomposition(&graph, source, sink, &decompose(source, sink, &graph));
+    }
+}
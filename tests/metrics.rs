@@ -0,0 +1,278 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::edmonds_karp::EdmondsKarp;
+use network_algorithms::maximum_flow::graph;
+use network_algorithms::maximum_flow::push_relabel_fifo::PushRelabelFIFO;
+use network_algorithms::metrics::*;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use std::sync::Arc;
+use std::thread;
+
+fn solves(metrics: &InMemoryMetrics, algorithm: &str, status: &str) -> u64 {
+    metrics.counter(&labeled(SOLVES_TOTAL, &[("algorithm", algorithm), ("status", status)]))
+}
+
+fn solve_seconds_count(metrics: &InMemoryMetrics, algorithm: &str) -> u64 {
+    match metrics.get(&labeled(SOLVE_SECONDS, &[("algorithm", algorithm)])) {
+        Some(MetricValue::Histogram { count, sum }) => {
+            assert!(sum >= 0.0);
+            count
+        }
+        _ => 0,
+    }
+}
+
+#[test]
+fn scripted_solves() {
+    let metrics = Arc::new(InMemoryMetrics::default());
+    let solver = || {
+        let mut solver = PrimalNetworkSimplex::default();
+        solver.set_metrics_sink(metrics.clone());
+        solver
+    };
+
+    let mut rng = XorShift::new(754);
+    let (mut num_optimal, mut num_infeasible, mut num_pivots) = (0, 0, 0);
+    for _ in 0..50 {
+        let mut solver = solver();
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, 6, 12, 10, 10);
+        match solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph) {
+            Status::Optimal => num_optimal += 1,
+            Status::Infeasible => num_infeasible += 1,
+            status => panic!("{status:?}"),
+        }
+        num_pivots += solver.num_pivots() as u64;
+    }
+
+    // unbalanced supplies
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 1);
+    assert_eq!(solver().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Unbalanced);
+
+    assert!(num_optimal > 0 && num_infeasible > 0);
+    assert_eq!(solves(&metrics, "primal_network_simplex", "Optimal"), num_optimal);
+    assert_eq!(solves(&metrics, "primal_network_simplex", "Infeasible"), num_infeasible);
+    assert_eq!(solves(&metrics, "primal_network_simplex", "Unbalanced"), 1);
+    assert_eq!(metrics.counter(&labeled(PIVOTS_TOTAL, &[("algorithm", "primal_network_simplex")])), num_pivots);
+    assert_eq!(solve_seconds_count(&metrics, "primal_network_simplex"), 51);
+}
+
+#[test]
+fn sub_millisecond_solve_seconds() {
+    // the StdClock times a solve to the nanosecond, so a solve far shorter than a millisecond is not recorded as 0
+    let metrics = Arc::new(InMemoryMetrics::default());
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_metrics_sink(metrics.clone());
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    match metrics.get(&labeled(SOLVE_SECONDS, &[("algorithm", "primal_network_simplex")])) {
+        Some(MetricValue::Histogram { count, sum }) => assert!(count == 1 && sum > 0.0, "{sum}"),
+        value => panic!("{value:?}"),
+    }
+}
+
+#[test]
+fn shared_sink_across_solvers() {
+    let metrics = Arc::new(InMemoryMetrics::default());
+
+    let mut ssp = SuccessiveShortestPath::default();
+    ssp.set_metrics_sink(metrics.clone());
+    let mut dinic = Dinic::default();
+    dinic.set_metrics_sink(metrics.clone());
+
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 4);
+    graph.add_demand(2, 4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 2, 5).unwrap();
+    let solution = ssp.solve_with_solution(&mut graph).unwrap();
+
+    let mut max_flow_graph = graph::Graph::default();
+    max_flow_graph.add_nodes(2);
+    max_flow_graph.add_directed_edge(0, 1, 3).unwrap();
+    dinic.solve(0, 1, &mut max_flow_graph);
+
+    assert_eq!(solves(&metrics, "successive_shortest_path", "Optimal"), 1);
+    assert_eq!(metrics.counter(&labeled(AUGMENTATIONS_TOTAL, &[("algorithm", "successive_shortest_path")])), solution.iterations() as u64);
+    assert_eq!(solves(&metrics, "dinic", "Optimal"), 1);
+    assert!(metrics.counter(&labeled(PHASES_TOTAL, &[("algorithm", "dinic")])) > 0);
+
+    // sorted by name
+    let snapshot = metrics.snapshot();
+    assert!(snapshot.windows(2).all(|w| w[0].0 < w[1].0));
+    assert_eq!(snapshot.iter().filter(|(name, _)| name.starts_with(SOLVES_TOTAL)).count(), 2);
+}
+
+#[test]
+fn concurrent_solves() {
+    let metrics = Arc::new(InMemoryMetrics::default());
+    let handles: Vec<_> = (0..4)
+        .map(|seed| {
+            let metrics = metrics.clone();
+            thread::spawn(move || {
+                let mut rng = XorShift::new(seed + 1);
+                let mut num_augmentations = 0;
+                for _ in 0..25 {
+                    let mut solver = SuccessiveShortestPath::default();
+                    solver.set_metrics_sink(metrics.clone());
+                    let mut graph = random_minimum_cost_flow_graph(&mut rng, 6, 12, 10, 10);
+                    num_augmentations += match solver.solve_with_solution(&mut graph) {
+                        Ok(solution) => solution.iterations() as u64,
+                        Err(_) => 0,
+                    };
+                }
+                num_augmentations
+            })
+        })
+        .collect();
+    let num_augmentations: u64 = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+
+    let num_solves = solves(&metrics, "successive_shortest_path", "Optimal") + solves(&metrics, "successive_shortest_path", "Infeasible");
+    assert_eq!(num_solves, 100);
+    assert_eq!(solve_seconds_count(&metrics, "successive_shortest_path"), 100);
+    // infeasible solves are counted as well, so this is a lower bound
+    assert!(metrics.counter(&labeled(AUGMENTATIONS_TOTAL, &[("algorithm", "successive_shortest_path")])) >= num_augmentations);
+}
+
+#[test]
+fn default_sink() {
+    let metrics = Arc::new(InMemoryMetrics::default());
+    set_default_sink(Some(metrics.clone()));
+
+    // the solver has no sink of its own
+    let mut graph = graph::Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 3).unwrap();
+    Dinic::default().solve(0, 1, &mut graph);
+    set_default_sink(None);
+    Dinic::default().solve(0, 1, &mut graph);
+
+    assert_eq!(solves(&metrics, "dinic", "Optimal"), 1);
+}
+
+#[test]
+fn labeled_name() {
+    assert_eq!(labeled("solves_total", &[("algorithm", "dinic"), ("status", "Optimal")]), "solves_total{algorithm=\"dinic\",status=\"Optimal\"}");
+}
+
+#[test]
+fn histogram_buckets() {
+    assert_eq!((0..4).map(Histogram::bucket_upper_bound).collect::<Vec<_>>(), vec![1, 2, 4, 8]);
+    assert_eq!(Histogram::bucket_upper_bound(NUM_BUCKETS - 2), 1 << 31);
+    assert_eq!(Histogram::bucket_upper_bound(NUM_BUCKETS - 1), u64::MAX);
+
+    let mut histogram = Histogram::default();
+    for value in [0, 1, 2, 3, 4, 5, 8, 9, 1 << 31, (1 << 31) + 1, u64::MAX] {
+        histogram.observe(value);
+    }
+    assert_eq!(histogram.buckets(), vec![(1, 2), (2, 1), (4, 2), (8, 2), (16, 1), (1 << 31, 1), (u64::MAX, 2)]);
+    assert_eq!(histogram.count(), 11);
+    histogram.clear();
+    assert_eq!(histogram.buckets(), vec![]);
+
+    // flows fall in the same buckets
+    let (mut by_value, mut by_flow) = (Histogram::default(), Histogram::default());
+    for value in (0..1000).chain([(1 << 31) - 1, 1 << 31, (1 << 31) + 1, 1 << 40, i64::MAX]) {
+        by_value.observe(value as u64);
+        by_flow.observe_flow(value);
+    }
+    assert_eq!(by_value, by_flow);
+}
+
+#[test]
+fn histograms_on_path_graph() {
+    let num_nodes = 9;
+    let mut graph = graph::Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 1).unwrap();
+    }
+
+    // one path of 8 edges in a single phase, which saturates the edge of the source
+    let mut dinic = Dinic::default();
+    dinic.set_stats_level(StatsLevel::Histograms);
+    dinic.solve(0, num_nodes - 1, &mut graph.clone());
+    assert_eq!(dinic.path_length_histogram(), vec![(8, 1)]);
+    assert_eq!(dinic.phase_size_histogram(), vec![(1, 1)]);
+
+    let mut edmonds_karp = EdmondsKarp::default();
+    edmonds_karp.set_stats_level(StatsLevel::Histograms);
+    edmonds_karp.solve(0, num_nodes - 1, &mut graph.clone());
+    assert_eq!(edmonds_karp.path_length_histogram(), vec![(8, 1)]);
+
+    // the unit moves down the labels 7, 6, ..., 1 after the source saturates its edge
+    let mut push_relabel = PushRelabelFIFO::default();
+    push_relabel.set_stats_level(StatsLevel::Histograms);
+    push_relabel.solve(0, num_nodes - 1, &mut graph.clone());
+    assert_eq!(push_relabel.push_distance_histogram(), vec![(1, 1), (2, 1), (4, 2), (8, 3)]);
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    graph.add_supply(0, 1);
+    graph.add_demand(num_nodes - 1, 1);
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 1, 1).unwrap();
+    }
+    let mut ssp = SuccessiveShortestPath::default();
+    ssp.set_stats_level(StatsLevel::Histograms);
+    assert_eq!(ssp.solve(&mut graph.clone()), Status::Optimal);
+    assert_eq!(ssp.path_length_histogram(), vec![(8, 1)]);
+
+    // one pivot per path edge, each through the root of the artificial edges
+    let mut simplex = PrimalNetworkSimplex::default();
+    simplex.set_stats_level(StatsLevel::Histograms);
+    assert_eq!(simplex.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph.clone()), Status::Optimal);
+    assert_eq!(simplex.num_pivots(), 8);
+    assert_eq!(simplex.cycle_length_histogram(), vec![(4, 2), (8, 4), (16, 2)]);
+}
+
+#[test]
+fn histogram_totals_equal_counters() {
+    let mut rng = XorShift::new(762);
+    for stats_level in [StatsLevel::Off, StatsLevel::Counters, StatsLevel::Histograms] {
+        let total = |histogram: Vec<(u64, u64)>| histogram.iter().map(|&(_, count)| count).sum::<u64>() as usize;
+        let expected = |counter: usize| if stats_level == StatsLevel::Histograms { counter } else { 0 };
+        for _ in 0..30 {
+            let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(1, 60) as usize);
+            let mut graph = graph::Graph::default();
+            graph.add_nodes(num_nodes);
+            for _ in 0..num_edges {
+                graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 20)).unwrap();
+            }
+
+            let mut dinic = Dinic::default();
+            dinic.set_stats_level(stats_level);
+            dinic.solve(0, num_nodes - 1, &mut graph.clone());
+            assert_eq!(total(dinic.phase_size_histogram()), expected(dinic.num_phases()));
+
+            let mut edmonds_karp = EdmondsKarp::default();
+            edmonds_karp.set_stats_level(stats_level);
+            edmonds_karp.solve(0, num_nodes - 1, &mut graph.clone());
+            assert_eq!(total(edmonds_karp.path_length_histogram()), expected(edmonds_karp.num_augmentations()));
+
+            let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+            let mut ssp = SuccessiveShortestPath::default();
+            ssp.set_stats_level(stats_level);
+            ssp.solve(&mut graph.clone());
+            assert_eq!(total(ssp.path_length_histogram()), expected(ssp.num_augmentations()));
+
+            let mut simplex = PrimalNetworkSimplex::default();
+            simplex.set_stats_level(stats_level);
+            simplex.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph.clone());
+            assert_eq!(total(simplex.cycle_length_histogram()), expected(simplex.num_pivots()));
+        }
+    }
+}
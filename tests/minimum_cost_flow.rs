@@ -0,0 +1,756 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::metrics::Stats;
+use network_algorithms::minimum_cost_flow::apply::{apply_path, Direction};
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::{ActiveNodeOrder, CostScalingPushRelabel};
+use network_algorithms::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use network_algorithms::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use network_algorithms::minimum_cost_flow::graph::{DotOptions, Graph};
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::*;
+use network_algorithms::minimum_cost_flow::out_of_kilter::OutOfKilter;
+use network_algorithms::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use network_algorithms::minimum_cost_flow::primal_dual::PrimalDual;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use num_traits::NumAssign;
+use rstest::rstest;
+use std::fs::read_to_string;
+use std::ops::Neg;
+use std::path::PathBuf;
+
+// first line: num_nodes num_edges expected(or infeasible)
+// next num_nodes lines: b
+// next num_edges lines: from to lower upper cost
+fn load_graph(path: &PathBuf) -> (Graph<i128>, Option<i128>) {
+    let text = read_to_string(path).unwrap();
+    let mut lines = text.lines();
+    let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+    let (num_nodes, num_edges) = (header[0].parse().unwrap(), header[1].parse().unwrap());
+    let expected = header[2].parse().ok();
+
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes {
+        graph.add_supply(u, lines.next().unwrap().trim().parse().unwrap());
+    }
+    for _ in 0..num_edges {
+        let e: Vec<i128> = lines.next().unwrap().split_whitespace().map(|x| x.parse().unwrap()).collect();
+        graph.add_directed_edge(e[0] as usize, e[1] as usize, e[2], e[3], e[4]).unwrap();
+    }
+    (graph, expected)
+}
+
+// complementary slackness
+fn satisfy_optimality_conditions<Flow>(graph: &Graph<Flow>) -> bool
+where
+    Flow: NumAssign + Neg<Output = Flow> + Ord + Copy,
+{
+    (0..graph.num_edges()).all(|edge_id| {
+        let edge = graph.get_edge(edge_id).unwrap();
+        let reduced_cost = edge.cost - graph.potential(edge.from) + graph.potential(edge.to);
+        (reduced_cost <= Flow::zero() || edge.flow == edge.lower) && (reduced_cost >= Flow::zero() || edge.flow == edge.upper)
+    })
+}
+
+fn check(path: &PathBuf, solve: impl Fn(&mut Graph<i128>) -> Status) {
+    let (mut graph, expected) = load_graph(path);
+    let status = solve(&mut graph);
+    match expected {
+        Some(expected) => {
+            assert_eq!(status, Status::Optimal, "{path:?}");
+            assert_eq!(graph.minimum_cost(), expected, "{path:?}");
+            assert!(satisfy_optimality_conditions(&graph), "{path:?}");
+        }
+        None => assert!(status == Status::Infeasible || status == Status::Unbalanced, "{path:?} {status:?}"),
+    }
+}
+
+// anti_ssp is exponential for augmenting path based algorithms
+#[rstest]
+fn successive_shortest_path(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp")]
+    path: PathBuf,
+) {
+    check(&path, |graph| SuccessiveShortestPath::default().solve(graph));
+}
+
+#[rstest]
+fn successive_shortest_path_tree_repair(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp")]
+    path: PathBuf,
+) {
+    check(&path, |graph| {
+        let mut solver = SuccessiveShortestPath::default();
+        solver.set_tree_repair(true);
+        solver.solve(graph)
+    });
+}
+
+#[rstest]
+fn primal_dual(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp")]
+    path: PathBuf,
+) {
+    check(&path, |graph| PrimalDual::default().solve(graph));
+}
+
+#[rstest]
+fn out_of_kilter(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp")]
+    path: PathBuf,
+) {
+    check(&path, |graph| OutOfKilter::default().solve(graph));
+}
+
+#[rstest]
+fn cycle_canceling(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp|goto|large|near")]
+    path: PathBuf,
+) {
+    check(&path, |graph| CycleCanceling::default().solve(graph));
+}
+
+#[rstest]
+fn cost_scaling_push_relabel(#[files("tests/minimum_cost_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |graph| CostScalingPushRelabel::default().solve(graph));
+}
+
+#[rstest]
+fn cost_scaling_push_relabel_active_node_order(
+    #[files("tests/minimum_cost_flow/*/*.txt")] path: PathBuf,
+    #[values(ActiveNodeOrder::Fifo, ActiveNodeOrder::Lifo, ActiveNodeOrder::HighestPotential)] order: ActiveNodeOrder,
+) {
+    check(&path, |graph| {
+        let mut solver = CostScalingPushRelabel::default();
+        solver.set_active_node_order(order);
+        solver.solve(graph)
+    });
+}
+
+#[test]
+fn cost_scaling_push_relabel_active_node_order_random() {
+    let mut rng = XorShift::new(8120);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 15) as usize, rng.gen_range(1, 50) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let mut expected_graph = graph.clone();
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut expected_graph);
+        for order in [ActiveNodeOrder::Fifo, ActiveNodeOrder::Lifo, ActiveNodeOrder::HighestPotential] {
+            let mut graph = graph.clone();
+            let mut solver = CostScalingPushRelabel::default();
+            solver.set_active_node_order(order);
+            assert_eq!(solver.solve(&mut graph), expected, "{order:?}");
+            if expected == Status::Optimal {
+                assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost(), "{order:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn cost_scaling_push_relabel_overflow() {
+    // a path 0 -> 1 -> ... -> n - 1 and an edge 0 -> n - 1 of a larger cost
+    let build = |num_nodes: usize, cost: i64| {
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        graph.add_supply(0, 2);
+        graph.add_demand(num_nodes - 1, 2);
+        for u in 0..num_nodes - 1 {
+            graph.add_directed_edge(u, u + 1, 0, 1, 1).unwrap();
+        }
+        graph.add_directed_edge(0, num_nodes - 1, 0, 2, cost).unwrap();
+        graph
+    };
+
+    // cost * 16 * n does not fit in i64
+    let num_nodes = 10;
+    let mut graph = build(num_nodes, i64::MAX / num_nodes as i64);
+    assert_eq!(CostScalingPushRelabel::default().solve(&mut graph), Status::Overflow);
+    assert!((0..graph.num_edges()).all(|edge_id| graph.get_edge(edge_id).unwrap().flow == 0));
+
+    // alpha * n does not fit in i32
+    let mut graph = Graph::<i32>::default();
+    graph.add_nodes(4);
+    graph.add_supply(0, 1);
+    graph.add_demand(3, 1);
+    graph.add_directed_edge(0, 3, 0, 1, 1).unwrap();
+    assert_eq!(CostScalingPushRelabel::new(1 << 30).solve(&mut graph), Status::Overflow);
+
+    // a large cost whose scaled potentials still fit
+    let cost = i64::MAX / 16 / num_nodes as i64 / 4;
+    let mut graph = build(num_nodes, cost);
+    assert_eq!(CostScalingPushRelabel::default().solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), num_nodes as i64 - 1 + cost);
+}
+
+#[test]
+fn cost_scaling_push_relabel_active_node_order_counts() {
+    let mut rng = XorShift::new(8121);
+    let num_nodes = 200;
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, 2000, 50, 100);
+    // the ring makes every supply reach every demand
+    for u in 0..num_nodes {
+        graph.add_directed_edge(u, (u + 1) % num_nodes, 0, 10_000, 1_000).unwrap();
+    }
+
+    let mut counts = Vec::new();
+    let mut costs = Vec::new();
+    for order in [ActiveNodeOrder::Fifo, ActiveNodeOrder::Lifo, ActiveNodeOrder::HighestPotential] {
+        let mut graph = graph.clone();
+        let mut solver = CostScalingPushRelabel::default();
+        solver.set_active_node_order(order);
+        assert_eq!(solver.solve(&mut graph), Status::Optimal, "{order:?}");
+        assert!(solver.stats().num_pushes > 0);
+        counts.push((solver.stats().num_pushes, solver.stats().num_relabels));
+        costs.push(graph.minimum_cost());
+    }
+    assert!(costs.iter().all(|&cost| cost == costs[0]));
+    assert_ne!(counts[0], counts[1]);
+    assert_ne!(counts[1], counts[2]);
+    assert_ne!(counts[0], counts[2]);
+}
+
+#[rstest]
+fn primal_network_simplex(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp")]
+    path: PathBuf,
+) {
+    check(&path, |graph| PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(graph.num_edges()), graph));
+    check(&path, |graph| PrimalNetworkSimplex::default().solve(&mut FirstEligibleArcPivotRule::new(graph.num_edges()), graph));
+    check(&path, |graph| PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph));
+    check(&path, |graph| PrimalNetworkSimplex::default().solve(&mut CandidateListPivotRule::new(graph.num_edges()), graph));
+    check(&path, |graph| PrimalNetworkSimplex::default().solve(&mut AlteringCandidateListPivotRule::new(graph.num_edges()), graph));
+}
+
+#[rstest]
+fn dual_network_simplex(#[files("tests/minimum_cost_flow/*/*.txt")] path: PathBuf) {
+    check(&path, |graph| DualNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph));
+}
+
+#[rstest]
+fn parametric_network_simplex(
+    #[files("tests/minimum_cost_flow/*/*.txt")]
+    #[exclude("anti_ssp")]
+    path: PathBuf,
+) {
+    check(&path, |graph| ParametricNetworkSimplex::default().solve(graph));
+}
+
+#[test]
+fn primal_dual_random() {
+    let mut rng = XorShift::new(747);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 40) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(expected_graph.num_edges()), &mut expected_graph);
+        let actual = PrimalDual::default().solve(&mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn primal_dual_long_path_graph() {
+    // deeper than the default thread stack allows a recursive dfs
+    let num_nodes = 500_000;
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for u in 0..num_nodes - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 3, 1).unwrap();
+    }
+    graph.add_supply(0, 3);
+    graph.add_demand(num_nodes - 1, 3);
+    assert_eq!(PrimalDual::default().solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 3 * (num_nodes as i128 - 1));
+}
+
+// in debug builds, every repaired tree is also validated against the plain dijkstra
+#[test]
+fn successive_shortest_path_tree_repair_random() {
+    let mut rng = XorShift::new(750);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 40) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = SuccessiveShortestPath::default().solve(&mut expected_graph);
+        let mut solver = SuccessiveShortestPath::default();
+        solver.set_tree_repair(true);
+        let actual = solver.solve(&mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn successive_shortest_path_tree_repair_heap_operations() {
+    // one source, one sink and many unit capacity paths with different costs
+    let num_paths = 300;
+    let mut graph = Graph::default();
+    graph.add_nodes(num_paths + 2);
+    let (source, sink) = (0, num_paths + 1);
+    graph.add_supply(source, num_paths as i64);
+    graph.add_demand(sink, num_paths as i64);
+    for i in 1..=num_paths {
+        graph.add_directed_edge(source, i, 0, 1, i as i64).unwrap();
+        graph.add_directed_edge(i, sink, 0, 1, 0).unwrap();
+    }
+
+    let (mut plain_graph, mut repair_graph) = (graph.clone(), graph);
+    let mut plain = SuccessiveShortestPath::default();
+    assert_eq!(plain.solve(&mut plain_graph), Status::Optimal);
+    let mut repair = SuccessiveShortestPath::default();
+    repair.set_tree_repair(true);
+    assert_eq!(repair.solve(&mut repair_graph), Status::Optimal);
+
+    assert_eq!(repair_graph.minimum_cost(), plain_graph.minimum_cost());
+    assert!(repair.num_heap_operations() * 10 < plain.num_heap_operations(), "{} {}", repair.num_heap_operations(), plain.num_heap_operations());
+}
+
+#[test]
+fn successive_shortest_path_skips_empty_edges() {
+    // a large sparse instance, where the reverse edges start without residual capacity and most of them keep it
+    let mut rng = XorShift::new(809);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 1000, 3000, 100, 100);
+    // a ring through every node makes it feasible
+    for u in 0..1000 {
+        graph.add_directed_edge(u, (u + 1) % 1000, 0, 1_000_000, 100).unwrap();
+    }
+    let edges = |graph: &Graph<i64>| (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect::<Vec<_>>();
+
+    let (mut scanning_graph, mut skipping_graph) = (graph.clone(), graph);
+    let mut scanning = SuccessiveShortestPath::default();
+    scanning.set_skip_empty_edges(false);
+    assert_eq!(scanning.solve(&mut scanning_graph), Status::Optimal);
+    let mut skipping = SuccessiveShortestPath::default();
+    assert_eq!(skipping.solve(&mut skipping_graph), Status::Optimal);
+
+    // the same edges are examined in the same order, so the flows are the same
+    assert_eq!(edges(&skipping_graph), edges(&scanning_graph));
+    assert_eq!(skipping.num_heap_operations(), scanning.num_heap_operations());
+    assert!(skipping.num_edge_scans() * 3 < scanning.num_edge_scans() * 2, "{} {}", skipping.num_edge_scans(), scanning.num_edge_scans());
+}
+
+#[test]
+fn potentials_random() {
+    let mut rng = XorShift::new(751);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 10) as usize, rng.gen_range(1, 30) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+        for mut solver in solvers() {
+            let mut graph = graph.clone();
+            if solver.solve(&mut graph) == Status::Optimal {
+                assert!(satisfy_optimality_conditions(&graph), "{}", solver.name());
+                // the potentials a solver keeps are those of the graph
+                if let Some(potentials) = solver.potentials() {
+                    assert!((0..graph.num_nodes()).all(|u| potentials[u] == graph.potential(u)), "{}", solver.name());
+                }
+            }
+        }
+    }
+}
+
+fn solvers() -> Vec<Box<dyn MinimumCostFlowSolver<i64>>> {
+    vec![
+        Box::new(SuccessiveShortestPath::default()),
+        Box::new(PrimalDual::default()),
+        Box::new(OutOfKilter::default()),
+        Box::new(CycleCanceling::default()),
+        Box::new(CostScalingPushRelabel::default()),
+        Box::new(PrimalNetworkSimplex::default()),
+        Box::new(DualNetworkSimplex::default()),
+        Box::new(ParametricNetworkSimplex::default()),
+    ]
+}
+
+#[test]
+fn trait_objects_random() {
+    let names: Vec<&str> = solvers().iter().map(|solver| solver.name()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "successive_shortest_path",
+            "primal_dual",
+            "out_of_kilter",
+            "cycle_canceling",
+            "cost_scaling_push_relabel",
+            "primal_network_simplex",
+            "dual_network_simplex",
+            "parametric_network_simplex"
+        ]
+    );
+
+    let mut rng = XorShift::new(7562);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 10) as usize, rng.gen_range(1, 30) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let mut expected_graph = graph.clone();
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(num_edges), &mut expected_graph);
+        for mut solver in solvers() {
+            let mut graph = graph.clone();
+            assert_eq!(solver.solve(&mut graph), expected, "{}", solver.name());
+            if expected == Status::Optimal {
+                assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost(), "{}", solver.name());
+            }
+        }
+    }
+}
+
+#[test]
+fn trait_objects_stats_and_objective() {
+    // 2 paths of 2 units from 0 to 3
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_supply(0, 4);
+    graph.add_demand(3, 4);
+    for (from, to, cost) in [(0, 1, 1), (1, 3, 1), (0, 2, 2), (2, 3, 2)] {
+        graph.add_directed_edge(from, to, 0, 2, cost).unwrap();
+    }
+
+    let mut solvers = solvers();
+    for solver in solvers.iter_mut() {
+        assert_eq!(*solver.stats(), Stats::default(), "{}", solver.name());
+        let mut graph = graph.clone();
+        assert_eq!(solver.solve(&mut graph), Status::Optimal, "{}", solver.name());
+        assert_eq!(solver.objective(&graph), 2 * 2 + 2 * 4, "{}", solver.name());
+        let stats = solver.stats();
+        assert!(stats.num_pivots + stats.num_augmentations + stats.num_phases > 0, "{}", solver.name());
+    }
+    // an augmentation per path, each found by a dijkstra run
+    assert_eq!(solvers[0].stats(), &Stats { num_augmentations: 2, num_dijkstra_runs: 2, ..Stats::default() });
+    assert!(solvers.iter().filter(|solver| solver.name().ends_with("network_simplex")).all(|solver| solver.stats().num_pivots > 0));
+}
+
+#[test]
+fn trait_objects_select_by_density() {
+    // a complete graph, dense enough for DualNetworkSimplex
+    let mut graph = Graph::default();
+    graph.add_nodes(5);
+    graph.add_supply(0, 6);
+    graph.add_demand(4, 6);
+    for u in 0..5 {
+        for v in 0..5 {
+            if u != v {
+                graph.add_directed_edge(u, v, 0, 3, ((u * 7 + v * 3) % 5) as i64).unwrap();
+            }
+        }
+    }
+
+    let density = graph.num_edges() as f64 / graph.num_nodes() as f64;
+    let mut solver: Box<dyn MinimumCostFlowSolver<i64>> = if density > 2.0 {
+        Box::new(DualNetworkSimplex::default())
+    } else {
+        Box::new(CostScalingPushRelabel::default())
+    };
+    assert_eq!(solver.name(), "dual_network_simplex");
+
+    let mut expected_graph = graph.clone();
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut expected_graph), Status::Optimal);
+    assert_eq!(solver.solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost());
+}
+
+// the minimum cost over every integral flow within the bounds, None if there is no feasible flow
+fn brute_force_minimum_cost(graph: &Graph<i64>, b: &[i64]) -> Option<i64> {
+    let edges: Vec<_> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).collect();
+    let mut flows: Vec<i64> = edges.iter().map(|edge| edge.lower).collect();
+    let mut best = None;
+    loop {
+        let mut excesses = b.to_vec();
+        for (edge, &flow) in edges.iter().zip(flows.iter()) {
+            excesses[edge.from] -= flow;
+            excesses[edge.to] += flow;
+        }
+        if excesses.iter().all(|&excess| excess == 0) {
+            let cost = edges.iter().zip(flows.iter()).map(|(edge, &flow)| edge.cost * flow).sum();
+            best = Some(best.map_or(cost, |best: i64| best.min(cost)));
+        }
+
+        // next combination
+        let Some(i) = (0..edges.len()).find(|&i| flows[i] < edges[i].upper) else {
+            return best;
+        };
+        flows[i] += 1;
+        for j in 0..i {
+            flows[j] = edges[j].lower;
+        }
+    }
+}
+
+#[test]
+fn negative_costs_brute_force() {
+    let mut rng = XorShift::new(758);
+    let mut num_optimal = 0;
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 4) as usize, rng.gen_range(1, 5) as usize);
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        let mut b = vec![0; num_nodes];
+        for _ in 0..num_edges {
+            let (from, to) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            let lower = if rng.gen_range(0, 3) == 0 { 1 } else { 0 };
+            graph.add_directed_edge(from, to, lower, lower + rng.gen_range(0, 3), rng.gen_range(-10, 5)).unwrap();
+        }
+        let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 3));
+        b[u] += amount;
+        b[v] -= amount;
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+
+        let expected = brute_force_minimum_cost(&graph, &b);
+        // the dual and parametric network simplex start their shortest path trees with the negative cost edges at the upper bounds
+        let (mut dual_graph, mut parametric_graph) = (graph.clone(), graph.clone());
+        let status = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph);
+        let dual_status = DualNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(dual_graph.num_edges()), &mut dual_graph);
+        let parametric_status = ParametricNetworkSimplex::default().solve(&mut parametric_graph);
+        match expected {
+            Some(expected) => {
+                assert_eq!(status, Status::Optimal);
+                assert_eq!(graph.minimum_cost(), expected);
+                assert!(satisfy_optimality_conditions(&graph));
+                assert!((0..graph.num_nodes()).all(|u| graph.excess(u) == 0));
+                assert_eq!((dual_status, dual_graph.minimum_cost()), (Status::Optimal, expected));
+                assert_eq!((parametric_status, parametric_graph.minimum_cost()), (Status::Optimal, expected));
+                num_optimal += 1;
+            }
+            None => assert_eq!((status, dual_status, parametric_status), (Status::Infeasible, Status::Infeasible, Status::Infeasible)),
+        }
+    }
+    assert!(num_optimal > 50);
+}
+
+#[test]
+fn negative_cost_cycle() {
+    // every edge of the cycle has a negative cost, so it is saturated even without any supply
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 3, -2).unwrap();
+    graph.add_directed_edge(1, 2, 1, 5, -1).unwrap();
+    graph.add_directed_edge(2, 0, 0, 4, -3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 2, 0).unwrap();
+    graph.add_directed_edge(3, 0, 0, 2, -4).unwrap();
+
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    let flows: Vec<i64> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect();
+    assert_eq!(flows, vec![3, 3, 1, 2, 2]);
+    assert_eq!(graph.minimum_cost(), -6 - 3 - 3 - 8);
+    assert!(satisfy_optimality_conditions(&graph));
+
+    // the warm start keeps the negative costs as well
+    graph.add_supply(1, 2);
+    graph.add_demand(0, 2);
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(solver.is_warm_started());
+    assert!(satisfy_optimality_conditions(&graph));
+    let mut expected_graph = graph.clone();
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut expected_graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost());
+}
+
+#[test]
+fn negative_cost_with_lower_bound() {
+    // 2 -> 1 has a negative cost and a lower bound, so it starts at its upper bound and 1 -> 2 limits how much of it is used
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    graph.add_directed_edge(0, 1, 0, 4, 2).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    let negative = graph.add_directed_edge(2, 1, 1, 3, -2).unwrap();
+    graph.add_directed_edge(0, 2, 0, 2, 4).unwrap();
+
+    let solvers: Vec<fn(&mut Graph<i64>) -> Status> = vec![
+        |graph| SuccessiveShortestPath::default().solve(graph),
+        |graph| CostScalingPushRelabel::default().solve(graph),
+        |graph| OutOfKilter::default().solve(graph),
+    ];
+    for (i, solve) in solvers.iter().enumerate() {
+        let mut graph = graph.clone();
+        assert_eq!(solve(&mut graph), Status::Optimal, "solver {i}");
+        assert_eq!(graph.minimum_cost(), 7, "solver {i}");
+        let edge = graph.get_edge(negative).unwrap();
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper, "solver {i}");
+        assert!((0..graph.num_nodes()).all(|u| graph.excess(u) == 0), "solver {i}");
+        assert!(satisfy_optimality_conditions(&graph), "solver {i}");
+    }
+}
+
+// a solver object reused on a different instance with the same number of nodes
+#[test]
+fn reuse_solver_on_another_instance() {
+    let new_solver = |name: &str| solvers().into_iter().find(|solver| solver.name() == name).unwrap();
+
+    let mut rng = XorShift::new(7582);
+    for name in ["successive_shortest_path", "primal_dual", "out_of_kilter"] {
+        for _ in 0..50 {
+            let mut solver = new_solver(name);
+            let num_nodes = rng.gen_range(3, 8) as usize;
+            for _ in 0..5 {
+                let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, 3 * num_nodes, 10, 100);
+                let mut expected_graph = graph.clone();
+                let expected = new_solver(name).solve(&mut expected_graph);
+
+                let mut graph = graph.clone();
+                assert_eq!(solver.solve(&mut graph), expected, "{}", solver.name());
+                if expected == Status::Optimal {
+                    assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost(), "{}", solver.name());
+                    assert!(satisfy_optimality_conditions(&graph), "{}", solver.name());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn preserved_potentials() {
+    let mut rng = XorShift::new(7583);
+    let (mut num_preserved, mut num_solves) = (0, 0);
+    for _ in 0..50 {
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, 8, 24, 10, 100);
+        let mut solver = SuccessiveShortestPath::default();
+        if solver.solve(&mut graph) != Status::Optimal {
+            continue;
+        }
+
+        // the same graph with more supply. a fresh solver starts from the flow of graph as well
+        let (u, v) = (rng.gen_index(8), rng.gen_index(8));
+        graph.add_supply(u, 1);
+        graph.add_demand(v, 1);
+        let mut expected_graph = graph.clone();
+        let expected = SuccessiveShortestPath::default().solve(&mut expected_graph);
+        assert_eq!(solver.solve(&mut graph), expected);
+        num_preserved += solver.are_potentials_preserved() as usize;
+        num_solves += 1;
+        if expected == Status::Optimal {
+            assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost());
+            assert!(satisfy_optimality_conditions(&graph));
+        }
+    }
+    assert!(num_solves > 0);
+    assert_eq!(num_preserved, num_solves);
+
+    // the potentials of another instance are not valid
+    let mut solver = PrimalDual::default();
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    graph.add_directed_edge(0, 1, 0, 1, 10).unwrap();
+    assert_eq!(solver.solve(&mut graph), Status::Optimal);
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(1, 1);
+    graph.add_demand(0, 1);
+    graph.add_directed_edge(1, 0, 0, 1, 3).unwrap();
+    graph.add_directed_edge(0, 1, 0, 1, 0).unwrap();
+    assert_eq!(solver.solve(&mut graph), Status::Optimal);
+    assert!(!solver.are_potentials_preserved());
+    assert_eq!(graph.minimum_cost(), 3);
+}
+
+#[test]
+fn successive_shortest_path_negative_costs() {
+    let mut rng = XorShift::new(759);
+    let mut num_optimal = 0;
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let num_edges = rng.gen_range(num_nodes as i64, 4 * num_nodes as i64) as usize;
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 100);
+
+        let mut expected_graph = graph.clone();
+        let expected = CycleCanceling::default().solve(&mut expected_graph);
+        let mut graph = graph.clone();
+        assert_eq!(SuccessiveShortestPath::default().solve(&mut graph), expected);
+        if expected == Status::Optimal {
+            assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost());
+            assert!(satisfy_optimality_conditions(&graph));
+            num_optimal += 1;
+        }
+
+        // a fresh solver on the solved graph starts from its flow, whose residual edges can have negative costs
+        if expected == Status::Optimal {
+            let (u, v) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes));
+            graph.add_supply(u, 1);
+            graph.add_demand(v, 1);
+            expected_graph.add_supply(u, 1);
+            expected_graph.add_demand(v, 1);
+            let expected = CycleCanceling::default().solve(&mut expected_graph);
+            assert_eq!(SuccessiveShortestPath::default().solve(&mut graph), expected);
+            if expected == Status::Optimal {
+                assert_eq!(graph.minimum_cost(), expected_graph.minimum_cost());
+            }
+        }
+    }
+    assert!(num_optimal > 20);
+}
+
+#[test]
+fn successive_shortest_path_negative_cycle() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 2, 1).unwrap();
+    graph.add_directed_edge(2, 0, 0, 2, 1).unwrap();
+
+    // the flow on the positive cost cycle leaves a negative cycle in the residual network
+    let cycle = [(0, Direction::Forward), (1, Direction::Forward), (2, Direction::Forward)];
+    apply_path(&mut graph, &cycle, 2).unwrap();
+    assert_eq!(graph.minimum_cost(), 6);
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut graph), Status::Unbounded);
+    assert_eq!(PrimalDual::default().solve(&mut graph), Status::Unbounded);
+    assert_eq!(graph.minimum_cost(), 6);
+    assert_eq!(graph.num_nodes(), 3);
+
+    // the solvers that cancel cycles remove it
+    assert_eq!(CycleCanceling::default().solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 0);
+}
+
+#[test]
+fn dot() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 2, 1, 3, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 4, 3).unwrap();
+    graph.add_directed_edge(2, 1, 0, 1, -1).unwrap();
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+
+    assert_eq!(
+        graph.to_dot(),
+        "digraph {\n    0 [label=\"0\\nb = 3\"];\n    1;\n    2 [label=\"2\\nb = -3\"];\n    0 -> 1 [label=\"2/2 @ 1\", style=bold];\n    1 -> 2 [label=\"3/1..3 @ 1\", style=bold];\n    0 -> 2 [label=\"1/4 @ 3\"];\n    2 -> 1 [label=\"1/1 @ -1\", style=bold];\n}\n"
+    );
+
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_supply(0, 1);
+    graph.add_demand(1, 1);
+    graph.add_directed_edge(0, 1, 0, 1, 2).unwrap();
+    graph.add_directed_edge(1, 0, 0, 1, 5).unwrap();
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    let potentials = (graph.potential(0), graph.potential(1));
+    assert_eq!(
+        graph.to_dot_with_options(&DotOptions { show_zero_flow_edges: false, show_potentials: true }),
+        format!("digraph {{\n    0 [label=\"0\\nb = 1\\np = {}\"];\n    1 [label=\"1\\nb = -1\\np = {}\"];\n    0 -> 1 [label=\"1/1 @ 2\", style=bold];\n}}\n", potentials.0, potentials.1)
+    );
+}
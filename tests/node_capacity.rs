@@ -0,0 +1,116 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::graph_error::GraphError;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::node_capacity::NodeCapacityGraph;
+use network_algorithms::maximum_flow::push_relabel_fifo::PushRelabelFIFO;
+use network_algorithms::maximum_flow::status::Status;
+
+#[test]
+fn node_is_the_bottleneck() {
+    let mut graph = NodeCapacityGraph::default();
+    graph.add_node();
+    let router = graph.add_node_with_capacity(3);
+    graph.add_node();
+    graph.add_directed_edge(0, router, 10).unwrap();
+    graph.add_directed_edge(router, 2, 10).unwrap();
+    graph.add_directed_edge(0, 2, 1).unwrap();
+    assert_eq!(graph.add_directed_edge(0, 3, 1), Err(GraphError::NodeOutOfRange { node: 3, num_nodes: 3 }));
+
+    assert_eq!(graph.solve(&mut Dinic::default(), 0, 2), Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 4);
+    assert_eq!(graph.node_flow(router), 3);
+    assert_eq!(graph.node_flow(0), 4);
+    assert_eq!(graph.node_capacity(router), Some(3));
+    assert_eq!(graph.node_capacity(0), None);
+
+    let edge = graph.get_edge(1).unwrap();
+    assert_eq!((edge.from, edge.to, edge.flow, edge.upper), (router.index(), 2, 3, 10));
+    assert_eq!(graph.network().num_nodes(), 4);
+    assert_eq!(graph.get_edge(3), None);
+}
+
+#[test]
+fn capped_source_and_sink() {
+    let mut graph = NodeCapacityGraph::default();
+    let source = graph.add_node_with_capacity(5);
+    graph.add_nodes(2);
+    let sink = graph.add_node_with_capacity(4);
+    graph.add_directed_edge(source, 1, 10).unwrap();
+    graph.add_directed_edge(source, 2, 10).unwrap();
+    graph.add_directed_edge(1, sink, 10).unwrap();
+    graph.add_directed_edge(2, sink, 10).unwrap();
+
+    assert_eq!(graph.solve(&mut PushRelabelFIFO::default(), source, sink), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), 4);
+    assert_eq!(graph.node_flow(source), 4);
+    assert_eq!(graph.node_flow(sink), 4);
+    assert_eq!(graph.solve(&mut Dinic::default(), source, source), Status::BadInput);
+    assert_eq!(graph.solve(&mut Dinic::default(), source, 4), Status::BadInput);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(7712);
+    for _ in 0..200 {
+        let num_nodes = rng.gen_range(2, 15) as usize;
+        let capacities: Vec<Option<i64>> = (0..num_nodes).map(|_| (rng.gen_range(0, 2) == 0).then(|| rng.gen_range(0, 20))).collect();
+        let edges: Vec<(usize, usize, i64)> = (0..rng.gen_range(1, 40)).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10))).collect();
+        let (source, sink) = (0, num_nodes - 1);
+
+        let mut graph = NodeCapacityGraph::default();
+        for &capacity in capacities.iter() {
+            match capacity {
+                Some(capacity) => graph.add_node_with_capacity(capacity),
+                None => graph.add_node(),
+            };
+        }
+        for &(u, v, upper) in edges.iter() {
+            graph.add_directed_edge(u, v, upper).unwrap();
+        }
+        assert_eq!(graph.solve(&mut Dinic::default(), source, sink), Status::Optimal);
+
+        // the in sides first and the out sides after them
+        let mut expected = Graph::default();
+        expected.add_nodes(2 * num_nodes);
+        for (u, &capacity) in capacities.iter().enumerate() {
+            expected.add_directed_edge(u, num_nodes + u, capacity.unwrap_or(i64::MAX / 4)).unwrap();
+        }
+        for &(u, v, upper) in edges.iter() {
+            expected.add_directed_edge(num_nodes + u, v, upper).unwrap();
+        }
+        assert_eq!(Dinic::default().solve(source, num_nodes + sink, &mut expected), Status::Optimal);
+        let value = (0..expected.num_edges())
+            .map(|edge_id| expected.get_edge(edge_id).unwrap())
+            .filter(|edge| edge.to == num_nodes + sink)
+            .map(|edge| edge.flow)
+            .sum::<i64>();
+        let value = value
+            - (0..expected.num_edges())
+                .map(|edge_id| expected.get_edge(edge_id).unwrap())
+                .filter(|edge| edge.from == num_nodes + sink)
+                .map(|edge| edge.flow)
+                .sum::<i64>();
+        assert_eq!(graph.maximum_flow(source), value);
+
+        // conservation and capacities in the ids of the user
+        let mut balance = vec![0; num_nodes];
+        for (edge_id, &expected_edge) in edges.iter().enumerate() {
+            let edge = graph.get_edge(edge_id).unwrap();
+            assert_eq!((edge.from, edge.to, edge.upper), expected_edge);
+            assert!(0 <= edge.flow && edge.flow <= edge.upper);
+            balance[edge.from] -= edge.flow;
+            balance[edge.to] += edge.flow;
+        }
+        for u in (0..num_nodes).filter(|&u| u != source && u != sink) {
+            assert_eq!(balance[u], 0);
+        }
+        for (u, &capacity) in capacities.iter().enumerate() {
+            if let Some(capacity) = capacity {
+                assert!(graph.node_flow(u) <= capacity);
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::node_cost::{self, OriginatedFlow};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+fn total_cost(graph: &Graph<i64>) -> i64 {
+    graph.minimum_cost() + (0..graph.num_nodes()).map(|u| graph.node_throughput_cost(u)).sum::<i64>()
+}
+
+#[test]
+fn expensive_node_loses_to_longer_route() {
+    // 0 -> 1 -> 3 is cheaper by the edge costs, but node 1 is expensive
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_supply(0, 2);
+    graph.add_demand(3, 2);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 5, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 5, 3).unwrap();
+    graph.set_node_cost(1, 10);
+
+    assert_eq!(node_cost::solve(&mut SuccessiveShortestPath::default(), &mut graph), Status::Optimal);
+    let flows: Vec<i64> = (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect();
+    assert_eq!(flows, vec![0, 0, 2, 2]);
+    assert_eq!(graph.node_throughput_cost(1), 0);
+    assert_eq!(total_cost(&graph), 12);
+
+    // cheaper than the detour again
+    graph.set_node_cost(1, 3);
+    assert_eq!(node_cost::solve(&mut SuccessiveShortestPath::default(), &mut graph), Status::Optimal);
+    assert_eq!(graph.node_throughput_cost(1), 6);
+    assert_eq!(total_cost(&graph), 10);
+}
+
+#[test]
+fn originated_flow() {
+    // 0 supplies 3 units to 2 through 1, every node has a cost
+    let build = |originated_flow: OriginatedFlow| {
+        let mut graph = Graph::default();
+        graph.add_nodes(3);
+        graph.add_supply(0, 3);
+        graph.add_demand(2, 3);
+        graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+        graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+        (0..3).for_each(|u| graph.set_node_cost(u, 10 * (u as i64 + 1)));
+        graph.set_originated_flow(originated_flow);
+        graph
+    };
+
+    // only the transit node pays
+    let mut exempt = build(OriginatedFlow::default());
+    assert_eq!(exempt.originated_flow(), OriginatedFlow::Exempt);
+    assert_eq!(node_cost::solve(&mut PrimalNetworkSimplex::default(), &mut exempt), Status::Optimal);
+    assert_eq!((0..3).map(|u| exempt.node_throughput_cost(u)).collect::<Vec<_>>(), vec![0, 60, 0]);
+    assert_eq!(total_cost(&exempt), 66);
+
+    // the supplying node pays as well, the demanding node does not
+    let mut charged = build(OriginatedFlow::Charged);
+    assert_eq!(node_cost::solve(&mut PrimalNetworkSimplex::default(), &mut charged), Status::Optimal);
+    assert_eq!((0..3).map(|u| charged.node_throughput_cost(u)).collect::<Vec<_>>(), vec![30, 60, 0]);
+    assert_eq!(total_cost(&charged), 96);
+}
+
+// every node is split by hand: u_in = u, u_out = num_nodes + u
+fn manually_split(num_nodes: usize, supplies: &[i64], edges: &[(usize, usize, i64, i64, i64)], node_costs: &[i64], originated_flow: OriginatedFlow) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(2 * num_nodes);
+    for &(from, to, lower, upper, cost) in edges.iter() {
+        graph.add_directed_edge(num_nodes + from, to, lower, upper, cost).unwrap();
+    }
+    let capacity: i64 = edges.iter().map(|e| e.3).sum::<i64>() + supplies.iter().filter(|&&b| b > 0).sum::<i64>();
+    for u in 0..num_nodes {
+        graph.add_directed_edge(u, num_nodes + u, 0, capacity, node_costs[u]).unwrap();
+        if supplies[u] > 0 && originated_flow == OriginatedFlow::Exempt {
+            graph.add_supply(num_nodes + u, supplies[u]);
+        } else {
+            graph.add_supply(u, supplies[u]);
+        }
+    }
+    graph
+}
+
+#[test]
+fn same_as_manually_split_model() {
+    let mut rng = XorShift::new(757);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 8) as usize, rng.gen_range(1, 20) as usize);
+        let edges: Vec<(usize, usize, i64, i64, i64)> = (0..num_edges)
+            .map(|_| {
+                let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, 2) } else { 0 };
+                (rng.gen_index(num_nodes), rng.gen_index(num_nodes), lower, lower + rng.gen_range(0, 10), rng.gen_range(-10, 10))
+            })
+            .collect();
+        let node_costs: Vec<i64> = (0..num_nodes).map(|_| if rng.gen_range(0, 2) == 0 { 0 } else { rng.gen_range(-5, 10) }).collect();
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..2 {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+
+        for originated_flow in [OriginatedFlow::Exempt, OriginatedFlow::Charged] {
+            let mut expected_graph = manually_split(num_nodes, &supplies, &edges, &node_costs, originated_flow);
+            let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(expected_graph.num_edges()), &mut expected_graph);
+
+            let mut graph = Graph::default();
+            graph.add_nodes(num_nodes);
+            for &(from, to, lower, upper, cost) in edges.iter() {
+                graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+            }
+            for u in 0..num_nodes {
+                graph.add_supply(u, supplies[u]);
+                graph.set_node_cost(u, node_costs[u]);
+            }
+            graph.set_originated_flow(originated_flow);
+
+            let solvers: Vec<Box<dyn MinimumCostFlowSolver<i64>>> = vec![Box::new(SuccessiveShortestPath::default()), Box::new(PrimalNetworkSimplex::default())];
+            for mut solver in solvers {
+                let mut graph = graph.clone();
+                assert_eq!(node_cost::solve(solver.as_mut(), &mut graph), expected);
+                if expected == Status::Optimal {
+                    assert_eq!(total_cost(&graph), expected_graph.minimum_cost());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "parallel")]
+
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::*;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::spanning_tree_structure::{InternalEdge, SpanningTreeStructure};
+
+type Callback = Box<dyn FnMut(&SpanningTreeStructure<i64>)>;
+
+// stops the solver at the first pivot and hands the spanning tree structure to the callback
+struct Probe {
+    callback: Option<Callback>,
+}
+
+impl PivotRule<i64> for Probe {
+    fn new(_num_edges: usize) -> Self {
+        Self { callback: None }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<i64>, &SpanningTreeStructure<i64>) -> i64>(&mut self, st: &SpanningTreeStructure<i64>, _calculate_violation: F) -> Option<usize> {
+        if let Some(mut callback) = self.callback.take() {
+            callback(st);
+        }
+        None
+    }
+}
+
+fn with_spanning_tree_structure(num_edges: usize, callback: impl FnMut(&SpanningTreeStructure<i64>) + 'static) {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    for _ in 0..num_edges {
+        graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    }
+    let mut probe = Probe { callback: Some(Box::new(callback)) };
+    PrimalNetworkSimplex::default().solve(&mut probe, &mut graph);
+    assert!(probe.callback.is_none());
+}
+
+// the edges are stored in one slice, so the edge id is the offset from the first edge
+fn edge_id(st: &SpanningTreeStructure<i64>, edge: &InternalEdge<i64>) -> usize {
+    (edge as *const InternalEdge<i64> as usize - st.edge(0) as *const InternalEdge<i64> as usize) / std::mem::size_of::<InternalEdge<i64>>()
+}
+
+// the eligible edges are in a random cyclic window, so that the serial blocks before the parallel scan are often empty.
+// few distinct values, so that the maximum is often tied
+fn random_violations(rng: &mut XorShift, num_edges: usize) -> Vec<i64> {
+    let (first, len, density) = (rng.gen_index(num_edges), rng.gen_index(num_edges), rng.gen_range(0, 100));
+    (0..num_edges)
+        .map(|edge_id| {
+            if (edge_id + num_edges - first) % num_edges < len && rng.gen_range(0, 100) < density {
+                rng.gen_range(1, 4)
+            } else {
+                rng.gen_range(-3, 1)
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn block_search_serial_vs_parallel() {
+    with_spanning_tree_structure(300, |st| {
+        let mut rng = XorShift::new(764);
+        for _ in 0..200 {
+            let min_block_size = rng.gen_range(1, 80) as usize;
+            let mut serial = BlockSearchPivotRule::new_with_parameter(st.num_edges(), min_block_size, 0.0);
+            let mut parallel = BlockSearchPivotRule::new_with_parameter(st.num_edges(), min_block_size, 0.0);
+            parallel.set_parallel_scan(rng.gen_range(2, 6) as usize, rng.gen_range(1, 50) as usize);
+
+            // the cursor is carried over, so a wrong position shows up in the following calls
+            for _ in 0..20 {
+                let violations = random_violations(&mut rng, st.num_edges());
+                let violation = |edge: &InternalEdge<i64>, st: &SpanningTreeStructure<i64>| violations[edge_id(st, edge)];
+                assert_eq!(parallel.find_entering_edge_parallel(st, violation), serial.find_entering_edge(st, violation));
+            }
+        }
+    });
+}
+
+#[test]
+fn best_eligible_arc_serial_vs_parallel() {
+    with_spanning_tree_structure(300, |st| {
+        let mut rng = XorShift::new(7640);
+        for _ in 0..200 {
+            let mut serial = <BestEligibleArcPivotRule<i64> as PivotRule<i64>>::new(st.num_edges());
+            let mut parallel = <BestEligibleArcPivotRule<i64> as PivotRule<i64>>::new(st.num_edges());
+            parallel.set_parallel_scan(rng.gen_range(2, 6) as usize, rng.gen_range(1, 50) as usize);
+
+            let violations = random_violations(&mut rng, st.num_edges());
+            let violation = |edge: &InternalEdge<i64>, st: &SpanningTreeStructure<i64>| violations[edge_id(st, edge)];
+            let expected = serial.find_entering_edge(st, violation);
+            assert_eq!(parallel.find_entering_edge_parallel(st, violation), expected);
+            // the smallest edge id among the ties
+            if let Some(entering_edge_id) = expected {
+                assert!(violations[..entering_edge_id].iter().all(|&v| v < violations[entering_edge_id]));
+            }
+        }
+    });
+}
+
+#[test]
+fn network_simplex_serial_vs_parallel() {
+    let mut rng = XorShift::new(7641);
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 15) as usize, rng.gen_range(1, 120) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+        let (num_threads, min_chunk_len) = (rng.gen_range(2, 5) as usize, rng.gen_range(1, 10) as usize);
+        let flows = |graph: &Graph<i64>| (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap().flow).collect::<Vec<_>>();
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let mut parallel = BlockSearchPivotRule::new_with_parameter(num_edges, 2, 0.5);
+        parallel.set_parallel_scan(num_threads, min_chunk_len);
+        let (mut serial_solver, mut parallel_solver) = (PrimalNetworkSimplex::default(), PrimalNetworkSimplex::default());
+        let expected = serial_solver.solve(&mut BlockSearchPivotRule::new_with_parameter(num_edges, 2, 0.5), &mut expected_graph);
+        assert_eq!(parallel_solver.solve(&mut parallel, &mut actual_graph), expected);
+        assert_eq!(parallel_solver.num_pivots(), serial_solver.num_pivots());
+        assert_eq!(flows(&actual_graph), flows(&expected_graph));
+    }
+
+    // a complete graph, so that every node is reachable for DualNetworkSimplex
+    for _ in 0..100 {
+        let num_nodes = rng.gen_range(2, 10) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for u in 0..num_nodes {
+            for v in (0..num_nodes).filter(|&v| v != u) {
+                graph.add_directed_edge(u, v, 0, rng.gen_range(1, 10), rng.gen_range(0, 10)).unwrap();
+            }
+        }
+        for _ in 0..num_nodes {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(1, 5));
+            graph.add_supply(u, amount);
+            graph.add_demand(v, amount);
+        }
+
+        let num_edges = graph.num_edges();
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let mut parallel = BlockSearchPivotRule::new_with_parameter(num_edges, 2, 0.5);
+        parallel.set_parallel_scan(rng.gen_range(2, 5) as usize, rng.gen_range(1, 10) as usize);
+        let expected = DualNetworkSimplex::default().solve_with_solution(&mut BlockSearchPivotRule::new_with_parameter(num_edges, 2, 0.5), &mut expected_graph);
+        assert_eq!(DualNetworkSimplex::default().solve_with_solution(&mut parallel, &mut actual_graph), expected);
+    }
+}
@@ -0,0 +1,556 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::*;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::spanning_tree_structure::{InternalEdge, SpanningTreeStructure};
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+type Callback = Box<dyn FnMut(&SpanningTreeStructure<i64>)>;
+
+// hands the spanning tree structure of the first pivot to a callback, and then stops the solver
+struct Probe {
+    callback: Option<Callback>,
+}
+
+impl PivotRule<i64> for Probe {
+    fn new(_num_edges: usize) -> Self {
+        Self { callback: None }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<i64>, &SpanningTreeStructure<i64>) -> i64>(&mut self, st: &SpanningTreeStructure<i64>, _calculate_violation: F) -> Option<usize> {
+        if let Some(mut callback) = self.callback.take() {
+            callback(st);
+        }
+        None
+    }
+}
+
+fn with_spanning_tree_structure(num_edges: usize, callback: impl FnMut(&SpanningTreeStructure<i64>) + 'static) {
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    for _ in 0..num_edges {
+        graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    }
+    let mut probe = Probe { callback: Some(Box::new(callback)) };
+    PrimalNetworkSimplex::default().solve(&mut probe, &mut graph);
+    assert!(probe.callback.is_none());
+}
+
+// calls the rule with violations[call][edge_id] and checks that the entering edge has the maximum violation among the edges evaluated in the call.
+// the head keeps the most violating candidates, so its maximum is the maximum of everything evaluated in the call.
+fn run_script(rule: &mut AlteringCandidateListPivotRule<i64>, st: &SpanningTreeStructure<i64>, violations: &[Vec<i64>]) -> Vec<Option<usize>> {
+    let ids: HashMap<*const InternalEdge<i64>, usize> = (0..st.num_edges()).map(|edge_id| (st.edge(edge_id) as *const _, edge_id)).collect();
+
+    let mut entering_edges = Vec::new();
+    for violation in violations.iter() {
+        let evaluated = RefCell::new(Vec::new());
+        let entering_edge_id = rule.find_entering_edge(st, |edge, _| {
+            let edge_id = ids[&(edge as *const _)];
+            evaluated.borrow_mut().push(edge_id);
+            violation[edge_id]
+        });
+
+        let evaluated = evaluated.into_inner();
+        // a candidate carried over from the previous call is not added again
+        let mut unique: Vec<usize> = evaluated.iter().copied().filter(|&edge_id| violation[edge_id] > 0).collect();
+        let num_eligible = unique.len();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), num_eligible);
+
+        let maxi_violation = evaluated.iter().map(|&edge_id| violation[edge_id]).max().unwrap_or(0);
+        match entering_edge_id {
+            Some(edge_id) => {
+                assert!(violation[edge_id] > 0);
+                assert_eq!(violation[edge_id], maxi_violation);
+            }
+            None => {
+                assert!(maxi_violation <= 0);
+                assert!((0..st.num_edges()).all(|edge_id| evaluated.contains(&edge_id)));
+            }
+        }
+        entering_edges.push(entering_edge_id);
+    }
+    entering_edges
+}
+
+#[test]
+fn altering_candidate_list_returns_head_maximum() {
+    with_spanning_tree_structure(10, |st| {
+        // 10 edges of the graph and 2 artificial edges, block size 4, head length 2
+        assert_eq!(st.num_edges(), 12);
+        let mut rule = AlteringCandidateListPivotRule::new_with_parameter(st.num_edges(), 4, 0.01, 2, 0.0);
+        let violations = vec![
+            vec![1, 2, 3, 4, 5, 0, 0, 0, 0, 0, 0, 0],
+            vec![1, 2, 3, 9, 0, 0, 0, 0, 0, 0, 0, 0],
+            vec![0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0],
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+            vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8],
+        ];
+        let entering_edges = run_script(&mut rule, st, &violations);
+        assert_eq!(entering_edges, vec![Some(3), Some(3), Some(2), Some(9), None, Some(11)]);
+    });
+}
+
+#[test]
+fn altering_candidate_list_no_duplicate_candidates() {
+    with_spanning_tree_structure(3, |st| {
+        // every edge stays eligible, so the edges carried over in the head are scanned again when the cursor wraps around
+        let mut rule = AlteringCandidateListPivotRule::new_with_parameter(st.num_edges(), 2, 0.01, 3, 0.0);
+        let violations = vec![vec![5, 4, 3, 2, 1]; 20];
+        let entering_edges = run_script(&mut rule, st, &violations);
+        assert!(entering_edges.iter().all(|edge_id| edge_id.is_some()));
+    });
+}
+
+#[test]
+fn altering_candidate_list_fuzz() {
+    with_spanning_tree_structure(60, |st| {
+        let mut rng = XorShift::new(753);
+        for _ in 0..50 {
+            let (min_block_size, min_head_length) = (rng.gen_range(1, 10) as usize, rng.gen_range(1, 10) as usize);
+            let mut rule = AlteringCandidateListPivotRule::new_with_parameter(st.num_edges(), min_block_size, 0.01, min_head_length, 0.0);
+
+            // mostly eligible edges, so that the candidate list is always full
+            let density = rng.gen_range(1, 10);
+            let violations: Vec<Vec<i64>> = (0..200)
+                .map(|_| {
+                    (0..st.num_edges())
+                        .map(|_| if rng.gen_range(0, 9) < density { rng.gen_range(1, 20) } else { rng.gen_range(-5, 0) })
+                        .collect()
+                })
+                .collect();
+            run_script(&mut rule, st, &violations);
+        }
+    });
+}
+
+#[test]
+fn altering_candidate_list_random() {
+    let mut rng = XorShift::new(7530);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 60) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let actual = PrimalNetworkSimplex::default().solve(&mut AlteringCandidateListPivotRule::new_with_parameter(num_edges, 2, 0.5, 1, 0.5), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn altering_candidate_list_all_eligible() {
+    with_spanning_tree_structure(200, |st| {
+        let mut rng = XorShift::new(787);
+        for (min_block_size, min_head_length, head_length_factor) in [(1, 1, 0.0), (1, 20, 0.0), (20, 1, 0.0), (7, 7, 5.0), (1, 1, 100.0), (300, 300, 0.0)] {
+            let mut rule = AlteringCandidateListPivotRule::new_with_parameter(st.num_edges(), min_block_size, 0.01, min_head_length, head_length_factor);
+            // every edge is eligible in every call, also the candidates carried over
+            let violations: Vec<Vec<i64>> = (0..100).map(|_| (0..st.num_edges()).map(|_| rng.gen_range(1, 1000)).collect()).collect();
+            let entering_edges = run_script(&mut rule, st, &violations);
+            assert!(entering_edges.iter().all(|edge_id| edge_id.is_some()));
+        }
+    });
+}
+
+#[test]
+fn altering_candidate_list_dense_random() {
+    let mut rng = XorShift::new(7870);
+    for _ in 0..100 {
+        let num_nodes = rng.gen_range(5, 15) as usize;
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_nodes * num_nodes * 2, 10, 10);
+        let num_edges = graph.num_edges();
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let (min_block_size, min_head_length) = (rng.gen_range(1, 5) as usize, rng.gen_range(1, 5) as usize);
+        let actual = PrimalNetworkSimplex::default().solve(&mut AlteringCandidateListPivotRule::new_with_parameter(num_edges, min_block_size, 0.01, min_head_length, 0.0), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn candidate_list_holds_edge_ids() {
+    with_spanning_tree_structure(10, |st| {
+        // 10 edges of the graph and 2 artificial edges, a candidate list of 3
+        let mut rule = CandidateListPivotRule::new_with_parameter(st.num_edges(), 3, 0.01, 1, 0.0);
+        let ids: HashMap<*const InternalEdge<i64>, usize> = (0..st.num_edges()).map(|edge_id| (st.edge(edge_id) as *const _, edge_id)).collect();
+        let violation = [0, 0, 0, 0, 0, 4, 0, 6, 0, 5, 0, 9];
+        let entering_edge_id = rule.find_entering_edge(st, |edge, _| violation[ids[&(edge as *const _)]]);
+
+        // the rebuild stops when the list is full, before edge 11
+        assert_eq!(entering_edge_id, Some(7));
+        assert_eq!(rule.candidates(), &[5, 7, 9]);
+        assert!(rule.candidates().iter().all(|&edge_id| edge_id < st.num_edges() && violation[edge_id] > 0));
+    });
+}
+
+#[test]
+fn candidate_list_random() {
+    let mut rng = XorShift::new(786);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 30) as usize, rng.gen_range(1, 200) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let actual = PrimalNetworkSimplex::default().solve(&mut CandidateListPivotRule::new_with_parameter(num_edges, 2, 0.25, 2, 0.5), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn approximate_steepest_edge_weight_updates() {
+    let mut rule = <ApproximateSteepestEdgePivotRule<i64> as PivotRule<i64>>::new(4);
+    assert!((0..6).all(|edge_id| rule.weight(edge_id) == 1.0));
+
+    // the leaving edge gets the length of the pivot cycle, the entering edge goes back to the reference weight
+    let script = [
+        (PivotUpdate { entering_edge_id: 0, leaving_edge_id: 2, num_cycle_nodes: 3 }, [1.0, 1.0, 3.0, 1.0, 1.0, 1.0]),
+        (PivotUpdate { entering_edge_id: 1, leaving_edge_id: 0, num_cycle_nodes: 5 }, [5.0, 1.0, 3.0, 1.0, 1.0, 1.0]),
+        (PivotUpdate { entering_edge_id: 2, leaving_edge_id: 5, num_cycle_nodes: 4 }, [5.0, 1.0, 1.0, 1.0, 1.0, 4.0]),
+        // a bound flip keeps the edge out of the tree with its own cycle
+        (PivotUpdate { entering_edge_id: 3, leaving_edge_id: 3, num_cycle_nodes: 2 }, [5.0, 1.0, 1.0, 2.0, 1.0, 4.0]),
+    ];
+    for (update, weights) in script.iter() {
+        rule.update_after_pivot(update);
+        assert_eq!((0..6).map(|edge_id| rule.weight(edge_id)).collect::<Vec<_>>(), weights.to_vec());
+    }
+}
+
+#[test]
+fn approximate_steepest_edge_selects_by_weighted_violation() {
+    with_spanning_tree_structure(3, |st| {
+        let mut rule = <ApproximateSteepestEdgePivotRule<i64> as PivotRule<i64>>::new(st.num_edges());
+        let ids: HashMap<*const InternalEdge<i64>, usize> = (0..st.num_edges()).map(|edge_id| (st.edge(edge_id) as *const _, edge_id)).collect();
+        let violations = [4, 3, 0, -1, 0];
+        assert_eq!(rule.find_entering_edge(st, |edge, _| violations[ids[&(edge as *const _)]]), Some(0));
+
+        // 4^2 / 4 < 3^2 / 1
+        rule.update_after_pivot(&PivotUpdate { entering_edge_id: 2, leaving_edge_id: 0, num_cycle_nodes: 4 });
+        assert_eq!(rule.find_entering_edge(st, |edge, _| violations[ids[&(edge as *const _)]]), Some(1));
+        assert_eq!(rule.find_entering_edge(st, |_, _| 0), None);
+    });
+}
+
+// sources on one side, sinks on the other and an edge between every pair
+fn transportation(rng: &mut XorShift, num_sources: usize, num_sinks: usize) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_sources + num_sinks);
+    for u in 0..num_sources {
+        for v in num_sources..num_sources + num_sinks {
+            graph.add_directed_edge(u, v, 0, 100, rng.gen_range(1, 100)).unwrap();
+        }
+    }
+    for _ in 0..num_sources * 5 {
+        let (u, v, amount) = (rng.gen_index(num_sources), num_sources + rng.gen_index(num_sinks), rng.gen_range(1, 10));
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+    }
+    graph
+}
+
+// edges in both directions between the neighbors of a grid, supply at the corners
+fn grid(rng: &mut XorShift, size: usize) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(size * size);
+    for i in 0..size {
+        for j in 0..size {
+            let u = i * size + j;
+            for v in [(i + 1 < size).then(|| u + size), (j + 1 < size).then(|| u + 1)].into_iter().flatten() {
+                graph.add_directed_edge(u, v, 0, rng.gen_range(5, 30), rng.gen_range(1, 20)).unwrap();
+                graph.add_directed_edge(v, u, 0, rng.gen_range(5, 30), rng.gen_range(1, 20)).unwrap();
+            }
+        }
+    }
+    graph.add_supply(0, 10);
+    graph.add_supply(size - 1, 10);
+    graph.add_demand(size * size - 1, 10);
+    graph.add_demand(size * (size - 1), 10);
+    graph
+}
+
+#[test]
+fn approximate_steepest_edge_families() {
+    let mut rng = XorShift::new(760);
+    let families: Vec<(&str, Vec<Graph<i64>>)> = vec![
+        ("transportation", (0..5).map(|_| transportation(&mut rng, 15, 20)).collect()),
+        ("grid", (0..5).map(|_| grid(&mut rng, 12)).collect()),
+    ];
+
+    let mut num_wins = 0;
+    for (name, graphs) in families.iter() {
+        let (mut num_block_search_pivots, mut num_steepest_edge_pivots) = (0, 0);
+        for graph in graphs.iter() {
+            let (mut expected_graph, mut actual_graph) = (graph.clone(), graph.clone());
+
+            let mut solver = PrimalNetworkSimplex::default();
+            assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut expected_graph), Status::Optimal, "{name}");
+            num_block_search_pivots += solver.num_pivots();
+
+            let mut solver = PrimalNetworkSimplex::default();
+            assert_eq!(solver.solve(&mut ApproximateSteepestEdgePivotRule::new(graph.num_edges()), &mut actual_graph), Status::Optimal, "{name}");
+            num_steepest_edge_pivots += solver.num_pivots();
+
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost(), "{name}");
+        }
+        num_wins += (num_steepest_edge_pivots < num_block_search_pivots) as usize;
+    }
+    assert!(num_wins >= 1);
+}
+
+#[test]
+fn approximate_steepest_edge_random() {
+    let mut rng = XorShift::new(7600);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 12) as usize, rng.gen_range(1, 60) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let actual = PrimalNetworkSimplex::default().solve(&mut ApproximateSteepestEdgePivotRule::new(num_edges), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn bland_degenerate_assignment() {
+    // unit supplies and equal costs: almost every pivot is degenerate and every eligible edge ties with the others
+    let n = 30;
+    let mut graph = Graph::default();
+    graph.add_nodes(2 * n);
+    for u in 0..n {
+        for v in n..2 * n {
+            graph.add_directed_edge(u, v, 0, 1, 1).unwrap();
+        }
+        graph.add_supply(u, 1);
+        graph.add_demand(n + u, 1);
+    }
+
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.solve(&mut BlandPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), n as i64);
+    assert!(solver.num_pivots() < graph.num_edges());
+}
+
+#[test]
+fn bland_random() {
+    let mut rng = XorShift::new(788);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(1, 100) as usize);
+        // small costs make ties in the entering and the leaving edge common
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 3, 2);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let actual = PrimalNetworkSimplex::default().solve(&mut BlandPivotRule::new(num_edges), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+// records the entering edges of the rule it wraps
+struct Recording<P> {
+    rule: P,
+    entering_edge_ids: Vec<usize>,
+}
+
+impl<P: PivotRule<i64>> PivotRule<i64> for Recording<P> {
+    fn new(num_edges: usize) -> Self {
+        Self { rule: P::new(num_edges), entering_edge_ids: Vec::new() }
+    }
+
+    fn reset(&mut self, num_edges: usize) {
+        self.rule.reset(num_edges);
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<i64>, &SpanningTreeStructure<i64>) -> i64>(&mut self, st: &SpanningTreeStructure<i64>, calculate_violation: F) -> Option<usize> {
+        let entering_edge_id = self.rule.find_entering_edge(st, calculate_violation);
+        self.entering_edge_ids.extend(entering_edge_id);
+        entering_edge_id
+    }
+}
+
+#[test]
+fn random_sampling_same_seed_same_pivots() {
+    let mut rng = XorShift::new(789);
+    let graph = transportation(&mut rng, 15, 20);
+    let solve = |seed: u64| {
+        let mut rule = Recording { rule: RandomSamplingPivotRule::new_with_seed(graph.num_edges(), 5, seed), entering_edge_ids: Vec::new() };
+        let mut graph = graph.clone();
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut rule, &mut graph), Status::Optimal);
+        (rule.entering_edge_ids, graph.minimum_cost())
+    };
+
+    let (entering_edge_ids, cost) = solve(1);
+    assert!(!entering_edge_ids.is_empty());
+    assert_eq!(solve(1), (entering_edge_ids.clone(), cost));
+    let (other_entering_edge_ids, other_cost) = solve(2);
+    assert_ne!(other_entering_edge_ids, entering_edge_ids);
+    assert_eq!(other_cost, cost);
+}
+
+#[test]
+fn random_sampling_random() {
+    let mut rng = XorShift::new(7890);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(1, 100) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+        // a sample of one edge is mostly not eligible near the optimum, which needs the full scan
+        let sample_size = rng.gen_range(1, 4) as usize;
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let actual = PrimalNetworkSimplex::default().solve(&mut RandomSamplingPivotRule::new_with_seed(num_edges, sample_size, rng.gen_range(0, 1000) as u64), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn scaled_violation_prefers_wide_edges() {
+    // both edges are eligible at first, the narrow one with the larger violation
+    let mut graph = Graph::default();
+    graph.add_nodes(2);
+    graph.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    let wide = graph.add_directed_edge(0, 1, 0, 10, 2).unwrap();
+    graph.add_supply(0, 10);
+    graph.add_demand(1, 10);
+
+    let mut rule = Recording::<BestEligibleArcPivotRule<i64>>::new(graph.num_edges());
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut rule, &mut graph.clone()), Status::Optimal);
+    assert_eq!(rule.entering_edge_ids[0], 0);
+
+    let mut rule = Recording::<ScaledViolationPivotRule<i64>>::new(graph.num_edges());
+    assert_eq!(PrimalNetworkSimplex::default().solve(&mut rule, &mut graph), Status::Optimal);
+    assert_eq!(rule.entering_edge_ids[0], wide.index());
+    assert_eq!(graph.minimum_cost(), 19);
+}
+
+#[test]
+fn scaled_violation_random() {
+    let mut rng = XorShift::new(790);
+    for _ in 0..300 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(1, 100) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        let expected = PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(num_edges), &mut expected_graph);
+        let actual = PrimalNetworkSimplex::default().solve(&mut ScaledViolationPivotRule::new(num_edges), &mut actual_graph);
+        assert_eq!(actual, expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn scaled_violation_dual_network_simplex() {
+    let mut rng = XorShift::new(7900);
+    for _ in 0..100 {
+        // a complete graph, so that every node is reachable for DualNetworkSimplex
+        let num_nodes = rng.gen_range(2, 8) as usize;
+        let mut graph = Graph::default();
+        graph.add_nodes(num_nodes);
+        for u in 0..num_nodes {
+            for v in (0..num_nodes).filter(|&v| v != u) {
+                graph.add_directed_edge(u, v, 0, rng.gen_range(1, 10), rng.gen_range(0, 10)).unwrap();
+            }
+        }
+        let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 5));
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph);
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut BestEligibleArcPivotRule::new(expected_graph.num_edges()), &mut expected_graph), Status::Optimal);
+        assert_eq!(DualNetworkSimplex::default().solve(&mut ScaledViolationPivotRule::new(actual_graph.num_edges()), &mut actual_graph), Status::Optimal);
+        assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+    }
+}
+
+fn all_rules(num_edges: usize) -> Vec<DynPivotRule<i64>> {
+    vec![
+        BestEligibleArcPivotRule::new(num_edges).into(),
+        FirstEligibleArcPivotRule::new(num_edges).into(),
+        BlandPivotRule::new(num_edges).into(),
+        BlockSearchPivotRule::new(num_edges).into(),
+        CandidateListPivotRule::new(num_edges).into(),
+        AlteringCandidateListPivotRule::new(num_edges).into(),
+        ApproximateSteepestEdgePivotRule::new(num_edges).into(),
+        RandomSamplingPivotRule::new_with_seed(num_edges, 3, 791).into(),
+        ScaledViolationPivotRule::new(num_edges).into(),
+    ]
+}
+
+#[test]
+fn dyn_pivot_rule_all_variants() {
+    let mut rng = XorShift::new(791);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 20) as usize, rng.gen_range(1, 100) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let mut results = Vec::new();
+        for mut rule in all_rules(num_edges) {
+            let mut graph = graph.clone();
+            let status = PrimalNetworkSimplex::default().solve(&mut rule, &mut graph);
+            results.push((status, if status == Status::Optimal { graph.minimum_cost() } else { 0 }));
+        }
+        assert!(results.windows(2).all(|pair| pair[0] == pair[1]), "{results:?}");
+    }
+}
+
+#[test]
+fn block_search_reused_for_a_smaller_graph() {
+    let mut rng = XorShift::new(792);
+    let large = random_minimum_cost_flow_graph(&mut rng, 200, 2000, 10, 10);
+    let small = random_minimum_cost_flow_graph(&mut rng, 5, 10, 10, 10);
+
+    let mut rule = BlockSearchPivotRule::new(large.num_edges());
+    for graph in [large, small] {
+        let (mut expected_graph, mut actual_graph) = (graph.clone(), graph.clone());
+        let expected = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut expected_graph);
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut rule, &mut actual_graph), expected);
+        if expected == Status::Optimal {
+            assert_eq!(actual_graph.minimum_cost(), expected_graph.minimum_cost());
+        }
+    }
+}
+
+#[test]
+fn reused_rules_match_new_rules() {
+    let mut rng = XorShift::new(7920);
+    let graphs: Vec<_> = (1..=5).map(|i| random_minimum_cost_flow_graph(&mut rng, 60 / i, 600 / i, 10, 10)).collect();
+    for (i, rule) in all_rules(graphs[0].num_edges()).into_iter().enumerate() {
+        // a rule reused for smaller and smaller graphs pivots as a new one would
+        let mut actual = Recording { rule, entering_edge_ids: Vec::new() };
+        for graph in graphs.iter() {
+            let mut expected = Recording { rule: all_rules(graph.num_edges()).swap_remove(i), entering_edge_ids: Vec::new() };
+            let expected_status = PrimalNetworkSimplex::default().solve(&mut expected, &mut graph.clone());
+            actual.entering_edge_ids.clear();
+            assert_eq!(PrimalNetworkSimplex::default().solve(&mut actual, &mut graph.clone()), expected_status);
+            assert_eq!(actual.entering_edge_ids, expected.entering_edge_ids);
+        }
+    }
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::cooperative::Progress;
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::{PrimalNetworkSimplex, PROGRESS_INTERVAL};
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use std::ops::ControlFlow;
+
+// the flows are within their bounds and the excesses are what they leave of the supplies
+fn check_consistent(graph: &Graph<i64>) {
+    let mut balance: Vec<i64> = (0..graph.num_nodes()).map(|u| graph.supply(u)).collect();
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    for (u, &balance) in balance.iter().enumerate() {
+        assert_eq!(balance, graph.excess(u));
+    }
+}
+
+fn large_graph() -> Graph<i64> {
+    let mut rng = XorShift::new(784);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 500, 5000, 50, 100);
+    // a path through all the nodes keeps the supplies feasible
+    for u in 0..graph.num_nodes() - 1 {
+        graph.add_directed_edge(u, u + 1, 0, 1_000_000, 1_000).unwrap();
+        graph.add_directed_edge(u + 1, u, 0, 1_000_000, 1_000).unwrap();
+    }
+    graph
+}
+
+fn optimal_cost() -> i64 {
+    let mut graph = large_graph();
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut graph), Status::Optimal);
+    graph.minimum_cost()
+}
+
+#[test]
+fn primal_network_simplex() {
+    let mut solver = PrimalNetworkSimplex::default();
+    let mut graph = large_graph();
+    let mut reported = Vec::new();
+    let status = solver.solve_with_progress(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph, |progress| {
+        reported.push(progress);
+        ControlFlow::Continue(())
+    });
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(graph.minimum_cost(), optimal_cost());
+    assert!(reported.len() >= 2);
+    assert_eq!(reported.len(), solver.num_pivots() / PROGRESS_INTERVAL);
+    for (i, &progress) in reported.iter().enumerate() {
+        assert_eq!(progress, Progress::NetworkSimplex { num_pivots: (i + 1) * PROGRESS_INTERVAL });
+    }
+
+    let mut graph = large_graph();
+    let mut num_calls = 0;
+    let status = solver.solve_with_progress(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph, |_| {
+        num_calls += 1;
+        if num_calls == 2 {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(status, Status::Cancelled);
+    assert_eq!(solver.num_pivots(), 2 * PROGRESS_INTERVAL);
+    check_consistent(&graph);
+}
+
+#[test]
+fn cost_scaling_push_relabel() {
+    let mut solver = CostScalingPushRelabel::default();
+    let mut graph = large_graph();
+    let mut epsilons = Vec::new();
+    let status = solver.solve_with_progress(&mut graph, |progress| {
+        let Progress::CostScaling { epsilon, num_phases } = progress else {
+            panic!("{progress:?}");
+        };
+        epsilons.push(epsilon);
+        assert_eq!(num_phases, epsilons.len());
+        ControlFlow::Continue(())
+    });
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(graph.minimum_cost(), optimal_cost());
+    assert_eq!(epsilons.len(), solver.stats().num_phases);
+    assert!(epsilons.windows(2).all(|pair| pair[0] > pair[1]));
+    assert_eq!(epsilons.last(), Some(&1));
+
+    // stopped after the first refine, the graph is left as it was
+    let mut graph = large_graph();
+    let before = graph.clone();
+    let status = solver.solve_with_progress(&mut graph, |_| ControlFlow::Break(()));
+    assert_eq!(status, Status::Cancelled);
+    assert!((0..graph.num_edges()).all(|edge_id| graph.get_edge(edge_id) == before.get_edge(edge_id)));
+}
+
+#[test]
+fn successive_shortest_path() {
+    for tree_repair in [false, true] {
+        let mut solver = SuccessiveShortestPath::default();
+        solver.set_tree_repair(tree_repair);
+        let mut graph = large_graph();
+        let mut remaining_excesses = Vec::new();
+        let status = solver.solve_with_progress(&mut graph, |progress| {
+            let Progress::SuccessiveShortestPath { remaining_excess, num_augmentations } = progress else {
+                panic!("{progress:?}");
+            };
+            remaining_excesses.push(remaining_excess);
+            assert_eq!(num_augmentations, remaining_excesses.len());
+            ControlFlow::Continue(())
+        });
+        assert_eq!(status, Status::Optimal);
+        assert_eq!(graph.minimum_cost(), optimal_cost());
+        assert_eq!(remaining_excesses.len(), solver.num_augmentations());
+        assert!(remaining_excesses.windows(2).all(|pair| pair[0] > pair[1]));
+        assert_eq!(remaining_excesses.last(), Some(&0));
+
+        let mut graph = large_graph();
+        let status = solver.solve_with_progress(&mut graph, |progress| match progress {
+            Progress::SuccessiveShortestPath { num_augmentations: 3, .. } => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        });
+        assert_eq!(status, Status::Cancelled);
+        assert_eq!(solver.num_augmentations(), 3);
+        check_consistent(&graph);
+    }
+}
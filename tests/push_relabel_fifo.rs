@@ -0,0 +1,117 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::push_relabel_fifo::{PushRelabelFIFO, ReturnExcessPolicy, DEFAULT_GLOBAL_UPDATE_FACTOR};
+use network_algorithms::maximum_flow::solver::MaximumFlowSolver;
+use network_algorithms::maximum_flow::status::Status;
+
+fn random_graph(rng: &mut XorShift) -> Graph<i64> {
+    let num_nodes = rng.gen_range(2, 40) as usize;
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..rng.gen_range(1, 200) {
+        graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 20)).unwrap();
+    }
+    graph
+}
+
+fn check_flow(graph: &Graph<i64>, source: usize, sink: usize) {
+    let mut balance = vec![0; graph.num_nodes()];
+    for edge in (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()) {
+        assert!(0 <= edge.flow && edge.flow <= edge.upper);
+        balance[edge.from] -= edge.flow;
+        balance[edge.to] += edge.flow;
+    }
+    assert!((0..graph.num_nodes()).filter(|&u| u != source && u != sink).all(|u| balance[u] == 0));
+}
+
+#[test]
+fn alpha_zero_disables_global_relabeling() {
+    let mut rng = XorShift::new(793);
+    for _ in 0..100 {
+        let graph = random_graph(&mut rng);
+        let (source, sink) = (0, graph.num_nodes() - 1);
+        for mut solver in [PushRelabelFIFO::with_global_relabel_frequency(0), PushRelabelFIFO::builder().global_update_factor(0.0).build()] {
+            assert_eq!(solver.solve(source, sink, &mut graph.clone()), Status::Optimal);
+            assert_eq!(solver.stats().num_global_relabelings, 0);
+        }
+    }
+}
+
+#[test]
+fn global_relabeling_gives_the_same_value() {
+    let mut rng = XorShift::new(7930);
+    let mut num_global_relabelings = 0;
+    for _ in 0..300 {
+        let graph = random_graph(&mut rng);
+        let (source, sink) = (0, graph.num_nodes() - 1);
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        for alpha in [1, 2, 6] {
+            let mut solver = PushRelabelFIFO::with_global_relabel_frequency(alpha);
+            let mut actual = graph.clone();
+            assert_eq!(solver.solve(source, sink, &mut actual), Status::Optimal);
+            assert_eq!(actual.maximum_flow(source), expected.maximum_flow(source));
+            check_flow(&actual, source, sink);
+            num_global_relabelings += solver.stats().num_global_relabelings;
+        }
+    }
+    assert!(num_global_relabelings > 0);
+}
+
+#[test]
+fn work_triggered_global_relabeling() {
+    let mut rng = XorShift::new(795);
+    let mut num_global_relabelings = 0;
+    for _ in 0..300 {
+        let graph = random_graph(&mut rng);
+        let (source, sink) = (0, graph.num_nodes() - 1);
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+
+        // frequent, default, and disabled
+        for factor in [0.01, 0.1, DEFAULT_GLOBAL_UPDATE_FACTOR, 0.0] {
+            let mut solver = PushRelabelFIFO::builder().global_update_factor(factor).build();
+            let mut actual = graph.clone();
+            assert_eq!(solver.solve(source, sink, &mut actual), Status::Optimal);
+            assert_eq!(actual.maximum_flow(source), expected.maximum_flow(source));
+            check_flow(&actual, source, sink);
+            if factor == 0.0 {
+                assert_eq!(solver.stats().num_global_relabelings, 0);
+            }
+            num_global_relabelings += solver.stats().num_global_relabelings;
+        }
+    }
+    assert!(num_global_relabelings > 0);
+}
+
+#[test]
+fn builder_toggles() {
+    let mut rng = XorShift::new(7931);
+    let mut num_gap_relabelings = 0;
+    for _ in 0..200 {
+        let graph = random_graph(&mut rng);
+        let (source, sink) = (0, graph.num_nodes() - 1);
+        let mut expected = graph.clone();
+        let mut solver = PushRelabelFIFO::default();
+        assert_eq!(solver.solve(source, sink, &mut expected), Status::Optimal);
+        num_gap_relabelings += solver.stats().num_gap_relabelings;
+
+        let mut solver = PushRelabelFIFO::builder().global_relabel_frequency(1).gap_relabeling(false).build();
+        let mut actual = graph.clone();
+        assert_eq!(solver.solve(source, sink, &mut actual), Status::Optimal);
+        assert_eq!(solver.stats().num_gap_relabelings, 0);
+        assert_eq!(actual.maximum_flow(source), expected.maximum_flow(source));
+        check_flow(&actual, source, sink);
+
+        let mut solver = PushRelabelFIFO::builder().gap_relabeling(false).return_excess_policy(ReturnExcessPolicy::ValueOnly).build();
+        assert_eq!(solver.solve(source, sink, &mut graph.clone()), Status::Optimal);
+        assert_eq!(solver.stats().num_gap_relabelings, 0);
+        assert_eq!(solver.flow_value(), expected.maximum_flow(source));
+    }
+    // the default solver does use the gap relabeling that the others turned off
+    assert!(num_gap_relabelings > 0);
+}
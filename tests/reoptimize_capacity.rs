@@ -0,0 +1,119 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::dual_network_simplex::DualNetworkSimplex;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+// (from, to, lower, upper, cost) and the supplies
+fn build(edges: &[(usize, usize, i64, i64, i64)], supplies: &[i64]) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(supplies.len());
+    for (u, &supply) in supplies.iter().enumerate() {
+        graph.add_supply(u, supply);
+    }
+    for &(from, to, lower, upper, cost) in edges.iter() {
+        graph.add_directed_edge(from, to, lower, upper, cost).unwrap();
+    }
+    graph
+}
+
+fn solve_dual(graph: &mut Graph<i64>) -> (DualNetworkSimplex<i64>, Status) {
+    let mut solver = DualNetworkSimplex::default();
+    let status = solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph);
+    (solver, status)
+}
+
+fn solve_cold(edges: &[(usize, usize, i64, i64, i64)], supplies: &[i64]) -> (Status, i64) {
+    let mut graph = build(edges, supplies);
+    let status = SuccessiveShortestPath::default().solve(&mut graph);
+    (status, graph.minimum_cost())
+}
+
+fn assert_feasible(graph: &Graph<i64>) {
+    for edge_id in 0..graph.num_edges() {
+        let edge = graph.get_edge(edge_id).unwrap();
+        assert!(edge.lower <= edge.flow && edge.flow <= edge.upper);
+    }
+    assert!((0..graph.num_nodes()).all(|u| graph.excess(u) == 0));
+}
+
+#[test]
+fn bottleneck() {
+    // 0 -> 1 -> 3 costs 2 and 0 -> 2 -> 3 costs 6, and 3 units are sent
+    let edges = [(0, 1, 0, 5, 1), (1, 3, 0, 5, 1), (0, 2, 0, 5, 3), (2, 3, 0, 5, 3)];
+    let mut graph = build(&edges, &[3, 0, 0, -3]);
+    let (mut solver, status) = solve_dual(&mut graph);
+    assert_eq!((status, graph.minimum_cost()), (Status::Optimal, 6));
+
+    assert_eq!(solver.reoptimize_capacity(0, 1, &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 2 + 2 * 6);
+    assert_eq!(graph.get_edge(0).unwrap().flow, 1);
+    assert_eq!(graph.get_edge(2).unwrap().flow, 2);
+    assert_feasible(&graph);
+
+    assert_eq!(solver.reoptimize_capacity(3, 1, &mut graph), Status::Infeasible);
+    assert_eq!(solver.reoptimize_capacity(0, 5, &mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 6);
+    assert_feasible(&graph);
+}
+
+#[test]
+fn bad_input() {
+    let edges = [(0, 1, 1, 5, 1)];
+    let mut graph = build(&edges, &[3, -3]);
+    // not solved yet
+    assert_eq!(DualNetworkSimplex::default().reoptimize_capacity(0, 4, &mut graph), Status::BadInput);
+
+    let (mut solver, status) = solve_dual(&mut graph);
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(solver.reoptimize_capacity(1, 4, &mut graph), Status::BadInput);
+    assert_eq!(solver.reoptimize_capacity(0, 0, &mut graph), Status::BadInput);
+
+    // another graph
+    let mut other = build(&[(1, 0, 0, 5, 1)], &[-3, 3]);
+    assert_eq!(solver.reoptimize_capacity(0, 4, &mut other), Status::BadInput);
+}
+
+#[test]
+fn random_capacity_changes_match_cold_solves() {
+    let mut rng = XorShift::new(778);
+    for _ in 0..200 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 15) as usize, rng.gen_range(1, 50) as usize);
+        let mut edges: Vec<_> = (0..num_edges)
+            .map(|_| {
+                let lower = if rng.gen_range(0, 4) == 0 { rng.gen_range(0, 3) } else { 0 };
+                (rng.gen_index(num_nodes), rng.gen_index(num_nodes), lower, lower + rng.gen_range(0, 10), rng.gen_range(-10, 10))
+            })
+            .collect();
+        // a cycle through all the nodes, as the dual network simplex only spans the nodes reachable from the supplies
+        edges.extend((0..num_nodes).map(|u| (u, (u + 1) % num_nodes, 0, 20, 10)));
+        let num_edges = edges.len();
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..num_nodes {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+
+        let mut graph = build(&edges, &supplies);
+        let (mut solver, status) = solve_dual(&mut graph);
+        if status != Status::Optimal || (0..num_nodes).all(|u| graph.supply(u) == 0) {
+            continue;
+        }
+        // decreases, increases and changes after an infeasible one
+        for _ in 0..10 {
+            let edge_id = rng.gen_index(num_edges);
+            edges[edge_id].3 = edges[edge_id].2 + rng.gen_range(0, 10);
+            let status = solver.reoptimize_capacity(edge_id, edges[edge_id].3, &mut graph);
+            let (expected_status, expected_cost) = solve_cold(&edges, &supplies);
+            assert_eq!(status, expected_status);
+            if status == Status::Optimal {
+                assert_eq!(graph.minimum_cost(), expected_cost);
+                assert_feasible(&graph);
+            }
+        }
+    }
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::push_relabel_fifo::{PushRelabelFIFO, ReturnExcessPolicy, DEFAULT_GLOBAL_UPDATE_FACTOR};
+use network_algorithms::maximum_flow::status::Status;
+
+fn random_graph(rng: &mut XorShift) -> Graph<i64> {
+    let num_nodes = rng.gen_range(2, 30) as usize;
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..rng.gen_range(1, 120) {
+        graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 20)).unwrap();
+    }
+    graph
+}
+
+fn excesses(graph: &Graph<i64>) -> Vec<i64> {
+    let mut excesses = vec![0; graph.num_nodes()];
+    for edge in (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()) {
+        excesses[edge.from] -= edge.flow;
+        excesses[edge.to] += edge.flow;
+    }
+    excesses
+}
+
+#[test]
+fn policies_agree_on_the_value() {
+    let mut rng = XorShift::new(773);
+    for _ in 0..200 {
+        let graph = random_graph(&mut rng);
+        let (source, sink) = (0, graph.num_nodes() - 1);
+        let mut expected = graph.clone();
+        assert_eq!(Dinic::default().solve(source, sink, &mut expected), Status::Optimal);
+        let value = expected.maximum_flow(source);
+
+        let mut solver = PushRelabelFIFO::default();
+        let mut full = graph.clone();
+        assert_eq!(solver.solve(source, sink, &mut full), Status::Optimal);
+        assert_eq!(solver.flow_value(), value);
+        assert!(!full.is_preflow());
+        assert_eq!(full.checked_maximum_flow(source), Some(value));
+        let excesses_of_full = excesses(&full);
+        assert!((0..full.num_nodes()).filter(|&u| u != source && u != sink).all(|u| excesses_of_full[u] == 0));
+
+        solver.set_return_excess_policy(ReturnExcessPolicy::SkipKeepPreflow);
+        let mut preflow = graph.clone();
+        assert_eq!(solver.solve(source, sink, &mut preflow), Status::Optimal);
+        assert_eq!(solver.flow_value(), value);
+        assert!(preflow.is_preflow());
+        assert_eq!(preflow.checked_maximum_flow(source), None);
+        assert_eq!(preflow.flow_value(source, sink), value);
+        assert!(excesses(&preflow).iter().enumerate().all(|(u, &excess)| u == source || excess >= 0));
+        for edge in (0..preflow.num_edges()).map(|edge_id| preflow.get_edge(edge_id).unwrap()) {
+            assert!(0 <= edge.flow && edge.flow <= edge.upper);
+        }
+
+        solver.set_return_excess_policy(ReturnExcessPolicy::ValueOnly);
+        let mut value_only = graph.clone();
+        assert_eq!(solver.solve(source, sink, &mut value_only), Status::Optimal);
+        assert_eq!(solver.flow_value(), value);
+        assert!((0..value_only.num_edges()).all(|edge_id| value_only.get_edge(edge_id).unwrap().flow == 0));
+        assert!(!value_only.is_preflow());
+
+        // a full solve on the same graph clears the mark
+        solver.set_return_excess_policy(ReturnExcessPolicy::Full);
+        assert_eq!(solver.solve(source, sink, &mut preflow), Status::Optimal);
+        assert!(!preflow.is_preflow());
+        assert_eq!(preflow.flow_value(source, sink), value);
+    }
+}
+
+#[test]
+fn trapped_excess_is_drained_in_linear_work() {
+    // the source fills a long chain that reaches sink through a single unit of capacity
+    let num_chain_nodes = 3000;
+    let mut graph = Graph::default();
+    let source = graph.add_node();
+    let chain = graph.add_nodes(num_chain_nodes);
+    let sink = graph.add_node();
+    for (i, &u) in chain.iter().enumerate() {
+        graph.add_directed_edge(source, u, 10).unwrap();
+        if i + 1 < num_chain_nodes {
+            graph.add_directed_edge(u, chain[i + 1], 10).unwrap();
+            graph.add_directed_edge(chain[i + 1], u, 10).unwrap();
+        }
+    }
+    graph.add_directed_edge(chain[num_chain_nodes - 1], sink, 1).unwrap();
+
+    let mut solver = PushRelabelFIFO::default();
+    assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(graph.maximum_flow(source), 1);
+    let excesses = excesses(&graph);
+    assert!((0..graph.num_nodes()).filter(|&u| u != source.index() && u != sink.index()).all(|u| excesses[u] == 0));
+    // one search per unit of excess restarting all the current edges takes about num_chain_nodes^2 operations
+    assert!(solver.num_drain_operations() < 20 * graph.num_edges(), "{}", solver.num_drain_operations());
+
+    solver.set_return_excess_policy(ReturnExcessPolicy::SkipKeepPreflow);
+    assert_eq!(solver.solve(source, sink, &mut graph), Status::Optimal);
+    assert_eq!(solver.num_drain_operations(), 0);
+    assert_eq!(graph.flow_value(source, sink), 1);
+}
+
+#[test]
+fn large_excess_stranded_deep_is_returned() {
+    // a wide chain carries a huge preflow far from source, with edges in both directions and self loops that the preflow may cycle through,
+    // and only a few units reach sink
+    let (num_chain_nodes, huge) = (2000, 1_000_000_000_000_i64);
+    let mut graph = Graph::default();
+    let source = graph.add_node();
+    let chain = graph.add_nodes(num_chain_nodes);
+    let sink = graph.add_node();
+    graph.add_directed_edge(source, chain[0], huge).unwrap();
+    for i in 0..num_chain_nodes - 1 {
+        graph.add_directed_edge(chain[i], chain[i + 1], huge).unwrap();
+        graph.add_directed_edge(chain[i + 1], chain[i], huge).unwrap();
+        graph.add_directed_edge(chain[i], chain[i], huge).unwrap();
+    }
+    graph.add_directed_edge(chain[num_chain_nodes / 2], sink, 2).unwrap();
+    graph.add_directed_edge(chain[num_chain_nodes - 1], sink, 3).unwrap();
+
+    for global_update_factor in [0.0, DEFAULT_GLOBAL_UPDATE_FACTOR] {
+        let mut solved = graph.clone();
+        let mut solver = PushRelabelFIFO::builder().global_update_factor(global_update_factor).build();
+        assert_eq!(solver.solve(source, sink, &mut solved), Status::Optimal);
+        assert_eq!(solved.maximum_flow(source), 5);
+        assert_eq!(solved.flow_value(source, sink), 5);
+        let excesses = excesses(&solved);
+        assert!((0..solved.num_nodes()).filter(|&u| u != source.index() && u != sink.index()).all(|u| excesses[u] == 0));
+        assert!(solver.num_drain_operations() < 20 * solved.num_edges(), "{}", solver.num_drain_operations());
+    }
+}
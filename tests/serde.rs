@@ -0,0 +1,155 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::generalized_maximum_flow;
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::minimum_cost_flow;
+use network_algorithms::minimum_cost_flow::graph::BalancePolicy;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn through_json<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+fn through_bincode<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    bincode::deserialize(&bincode::serialize(value).unwrap()).unwrap()
+}
+
+fn minimum_cost_flow_edges(graph: &minimum_cost_flow::graph::Graph<i64>) -> Vec<minimum_cost_flow::graph::Edge<i64>> {
+    (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).collect()
+}
+
+fn solve(graph: &mut minimum_cost_flow::graph::Graph<i64>) -> minimum_cost_flow::status::Status {
+    PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), graph)
+}
+
+#[test]
+fn minimum_cost_flow_round_trip() {
+    let mut rng = XorShift::new(763);
+    let mut num_optimal = 0;
+    for _ in 0..50 {
+        // negative costs and lower bounds, so that the edges start at their bounds and the excesses are shifted
+        let original = random_minimum_cost_flow_graph(&mut rng, 6, 30, 20, 10);
+        let mut expected = original.clone();
+        let status = solve(&mut expected);
+        num_optimal += (status == minimum_cost_flow::status::Status::Optimal) as usize;
+
+        for round_trip in [through_json, through_bincode] {
+            let mut graph = round_trip(&original);
+            assert_eq!(minimum_cost_flow_edges(&graph), minimum_cost_flow_edges(&original));
+            assert_eq!(solve(&mut graph), status);
+            assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+
+            // the solved flows and potentials
+            let solved = round_trip(&expected);
+            assert_eq!(minimum_cost_flow_edges(&solved), minimum_cost_flow_edges(&expected));
+            assert!((0..solved.num_nodes()).all(|u| solved.potential(u) == expected.potential(u) && solved.excess(u) == expected.excess(u)));
+        }
+    }
+    assert!(num_optimal > 10, "{num_optimal}");
+}
+
+#[test]
+fn minimum_cost_flow_settings_round_trip() {
+    let mut graph = minimum_cost_flow::graph::Graph::<i64>::default();
+    graph.add_nodes(2);
+    graph.set_node_cost(1, 3);
+    graph.set_balance_policy(BalancePolicy::SlackNode { node: None, cost_per_unit: 2, max_imbalance: 5 });
+    for round_trip in [through_json, through_bincode] {
+        let graph = round_trip(&graph);
+        assert_eq!(graph.node_cost(1), 3);
+        assert_eq!(graph.balance_policy(), BalancePolicy::SlackNode { node: None, cost_per_unit: 2, max_imbalance: 5 });
+    }
+
+    let solution = PrimalNetworkSimplex::default().solve_with_solution(&mut BlockSearchPivotRule::new(0), &mut graph).unwrap();
+    assert_eq!(through_json(&solution), solution);
+    assert_eq!(through_bincode(&solution), solution);
+}
+
+#[test]
+fn maximum_flow_round_trip() {
+    let mut rng = XorShift::new(7630);
+    for _ in 0..50 {
+        let mut graph = maximum_flow::graph::Graph::default();
+        graph.add_nodes(10);
+        for _ in 0..30 {
+            graph.add_directed_edge(rng.gen_index(10), rng.gen_index(10), rng.gen_range(0, 20)).unwrap();
+        }
+        let mut expected = graph.clone();
+        let status = Dinic::default().solve(0, 9, &mut expected);
+
+        for round_trip in [through_json, through_bincode] {
+            let mut graph = round_trip(&graph);
+            assert_eq!(Dinic::default().solve(0, 9, &mut graph), status);
+            assert_eq!(graph.maximum_flow(0), expected.maximum_flow(0));
+
+            let solved = round_trip(&expected);
+            assert!((0..solved.num_edges()).all(|edge_id| solved.get_edge(edge_id) == expected.get_edge(edge_id)));
+        }
+
+        let result = Dinic::default().solve_ref(0, 9, &graph);
+        assert_eq!(through_json(&result), result);
+        assert_eq!(through_bincode(&result), result);
+    }
+}
+
+#[test]
+fn generalized_maximum_flow_round_trip() {
+    let mut graph = generalized_maximum_flow::graph::Graph::<f64>::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 2.5);
+    graph.add_directed_edge(0, 1, 2.0, 0.5).unwrap();
+    graph.add_directed_edge(1, 2, 1.5, 1.25).unwrap();
+    for round_trip in [through_json, through_bincode] {
+        let graph = round_trip(&graph);
+        assert_eq!((graph.num_nodes(), graph.num_edges()), (3, 2));
+        assert_eq!(graph.get_edge(0), Some(generalized_maximum_flow::graph::Edge { from: 0, to: 1, flow: 0.0, upper: 2.0, gain: 0.5 }));
+        assert_eq!(graph.get_edge(1), Some(generalized_maximum_flow::graph::Edge { from: 1, to: 2, flow: 0.0, upper: 1.5, gain: 1.25 }));
+    }
+}
+
+#[test]
+fn basis_bundle_round_trip() {
+    let mut rng = XorShift::new(768);
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 8, 40, 20, 10);
+    let mut solver = PrimalNetworkSimplex::default();
+    while solve(&mut graph) != minimum_cost_flow::status::Status::Optimal {
+        graph = random_minimum_cost_flow_graph(&mut rng, 8, 40, 20, 10);
+    }
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), minimum_cost_flow::status::Status::Optimal);
+    let bundle = solver.export_basis().unwrap();
+    let potential_bundle = minimum_cost_flow::basis::PotentialBundle::from_graph(&graph);
+
+    for round_trip in [through_json, through_bincode] {
+        let mut solver = PrimalNetworkSimplex::default();
+        assert_eq!(solver.import_basis(&graph, &round_trip(&bundle)), Ok(()));
+        assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), minimum_cost_flow::status::Status::Optimal);
+        assert!(solver.is_warm_started());
+        assert_eq!(solver.num_pivots(), 0);
+    }
+    assert_eq!(through_json(&potential_bundle), potential_bundle);
+    assert_eq!(through_bincode(&potential_bundle), potential_bundle);
+}
+
+#[test]
+fn status_round_trip() {
+    for status in [
+        minimum_cost_flow::status::Status::Optimal,
+        minimum_cost_flow::status::Status::Unbalanced,
+        minimum_cost_flow::status::Status::Unbounded,
+    ] {
+        assert_eq!(through_json(&status), status);
+        assert_eq!(through_bincode(&status), status);
+    }
+    for status in [maximum_flow::status::Status::Optimal, maximum_flow::status::Status::BadInput] {
+        assert_eq!(through_json(&status), status);
+        assert_eq!(through_bincode(&status), status);
+    }
+    assert_eq!(through_json(&generalized_maximum_flow::status::Status::default()), generalized_maximum_flow::status::Status::default());
+}
@@ -0,0 +1,249 @@
+// long runs of incremental changes against one solver and one graph, checked against cold solves.
+// cargo test --release --test soak -- --ignored (SOAK_OPERATIONS and SOAK_SEED override the defaults)
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::spanning_tree_structure::{InternalEdge, SpanningTreeStructure};
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::fmt::Write as _;
+
+const CHECK_INTERVAL: usize = 1000;
+const NUM_SCENARIOS: usize = 4;
+const MAX_EXTRA_EDGES: usize = 200;
+
+#[derive(Clone, Debug)]
+struct Instance {
+    num_nodes: usize,
+    edges: Vec<(usize, usize, i64, i64)>, // from, to, upper, cost
+    supplies: Vec<i64>,
+    num_initial_edges: usize,
+}
+
+impl Instance {
+    // always feasible thanks to the expensive cycle through all nodes
+    fn random(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> Self {
+        let mut edges: Vec<(usize, usize, i64, i64)> = (0..num_nodes).map(|u| (u, (u + 1) % num_nodes, 1_000_000, 1000)).collect();
+        edges.extend((0..num_edges).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(1, 20), rng.gen_range(-20, 100))));
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..num_nodes {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+        Self { num_nodes, num_initial_edges: edges.len(), edges, supplies }
+    }
+
+    fn graph(&self) -> Graph<i64> {
+        let mut graph = Graph::default();
+        graph.add_nodes(self.num_nodes);
+        for &(from, to, upper, cost) in self.edges.iter() {
+            graph.add_directed_edge(from, to, 0, upper, cost).unwrap();
+        }
+        for (u, &supply) in self.supplies.iter().enumerate() {
+            graph.add_supply(u, supply);
+        }
+        graph
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Operation {
+    ChangeSupply { from: usize, to: usize, amount: i64 },
+    ChangeCost { edge_id: usize, cost: i64 },
+    InsertEdge { from: usize, to: usize, upper: i64, cost: i64 },
+    SwapScenario { index: usize },
+}
+
+impl Operation {
+    fn random(rng: &mut XorShift, instance: &Instance) -> Self {
+        let n = instance.num_nodes;
+        match rng.gen_range(0, 20) {
+            0..=13 => Operation::ChangeSupply { from: rng.gen_index(n), to: rng.gen_index(n), amount: rng.gen_range(-5, 6) },
+            14..=16 => Operation::ChangeCost { edge_id: n + rng.gen_index(instance.edges.len() - n), cost: rng.gen_range(-20, 100) },
+            17..=18 => Operation::InsertEdge { from: rng.gen_index(n), to: rng.gen_index(n), upper: rng.gen_range(1, 20), cost: rng.gen_range(-20, 100) },
+            _ => Operation::SwapScenario { index: rng.gen_index(NUM_SCENARIOS) },
+        }
+    }
+}
+
+// checks the spanning tree structure when the solver finds no entering edge, which is the final tree
+struct Checked {
+    rule: BlockSearchPivotRule<i64>,
+    error: Option<String>,
+}
+
+impl PivotRule<i64> for Checked {
+    fn new(num_edges: usize) -> Self {
+        Self { rule: BlockSearchPivotRule::new(num_edges), error: None }
+    }
+
+    fn find_entering_edge<F: Fn(&InternalEdge<i64>, &SpanningTreeStructure<i64>) -> i64>(&mut self, st: &SpanningTreeStructure<i64>, calculate_violation: F) -> Option<usize> {
+        let entering_edge_id = self.rule.find_entering_edge(st, calculate_violation);
+        if entering_edge_id.is_none() {
+            if !st.validate_num_successors(st.root()) {
+                self.error = Some("num_successors does not match the tree".to_string());
+            } else if !st.satisfy_optimality_conditions() {
+                self.error = Some("the final tree does not satisfy the optimality conditions".to_string());
+            }
+        }
+        entering_edge_id
+    }
+}
+
+struct Soak {
+    seed: u64,
+    scenarios: Vec<Instance>,
+    current: usize,
+    graph: Graph<i64>,
+    solver: PrimalNetworkSimplex<i64>,
+    checkpoint: (usize, Vec<Instance>, usize), // the operation index, the scenarios and the current scenario at the last cross check
+    log: Vec<Operation>,                       // the operations since the checkpoint
+}
+
+impl Soak {
+    fn new(seed: u64) -> Self {
+        let mut rng = XorShift::new(seed);
+        let scenarios: Vec<Instance> = (0..NUM_SCENARIOS).map(|_| Instance::random(&mut rng, 100, 500)).collect();
+        let graph = scenarios[0].graph();
+        Self { seed, checkpoint: (0, scenarios.clone(), 0), scenarios, current: 0, graph, solver: PrimalNetworkSimplex::default(), log: Vec::new() }
+    }
+
+    fn apply(&mut self, operation: &Operation) {
+        let instance = &mut self.scenarios[self.current];
+        match *operation {
+            Operation::ChangeSupply { from, to, amount } => {
+                instance.supplies[from] += amount;
+                instance.supplies[to] -= amount;
+                self.graph.add_supply(from, amount);
+                self.graph.add_demand(to, amount);
+            }
+            Operation::ChangeCost { edge_id, cost } => {
+                instance.edges[edge_id].3 = cost;
+                self.graph = instance.graph();
+            }
+            Operation::InsertEdge { from, to, upper, cost } => {
+                // the scenario goes back to its initial edges instead of growing forever
+                if instance.edges.len() == instance.num_initial_edges + MAX_EXTRA_EDGES {
+                    instance.edges.truncate(instance.num_initial_edges);
+                    self.graph = instance.graph();
+                } else {
+                    instance.edges.push((from, to, upper, cost));
+                    self.graph.add_directed_edge(from, to, 0, upper, cost).unwrap();
+                }
+            }
+            Operation::SwapScenario { index } => {
+                self.current = index;
+                self.graph = self.scenarios[index].graph();
+            }
+        }
+    }
+
+    // the warm solve after an operation
+    fn solve(&mut self, operation: &Operation) -> Result<(), String> {
+        let mut rule = Checked::new(self.graph.num_edges());
+        let status = self.solver.solve_with_basis(&mut rule, &mut self.graph);
+        if status != Status::Optimal {
+            return Err(format!("status {status:?}"));
+        }
+        if let Some(error) = rule.error {
+            return Err(error);
+        }
+
+        // the artificial nodes and edges are removed every time
+        let instance = &self.scenarios[self.current];
+        if (self.graph.num_nodes(), self.graph.num_edges()) != (instance.num_nodes, instance.edges.len()) {
+            return Err(format!("the graph has {} nodes and {} edges", self.graph.num_nodes(), self.graph.num_edges()));
+        }
+        // only the supplies changed since the last optimal solve
+        if matches!(self.log.iter().rev().nth(1), Some(Operation::ChangeSupply { .. })) && matches!(operation, Operation::ChangeSupply { .. }) && !self.solver.is_warm_started() {
+            return Err("not warm started after a supply change".to_string());
+        }
+        Ok(())
+    }
+
+    fn cross_check(&self) -> Result<(), String> {
+        let instance = &self.scenarios[self.current];
+
+        // the flows send the supplies
+        let mut balances = instance.supplies.clone();
+        for edge_id in 0..self.graph.num_edges() {
+            let edge = self.graph.get_edge(edge_id).unwrap();
+            if !(edge.lower <= edge.flow && edge.flow <= edge.upper) {
+                return Err(format!("the flow of edge {edge_id} is out of its bounds"));
+            }
+            balances[edge.from] -= edge.flow;
+            balances[edge.to] += edge.flow;
+        }
+        if let Some(u) = (0..instance.num_nodes).find(|&u| balances[u] != 0 || self.graph.excess(u) != 0) {
+            return Err(format!("node {u} has the excess {} (recomputed {})", self.graph.excess(u), balances[u]));
+        }
+
+        let mut graph = instance.graph();
+        let status = PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph);
+        if status != Status::Optimal || graph.minimum_cost() != self.graph.minimum_cost() {
+            return Err(format!("the cold solve returned {status:?} with the cost {}, the incremental solve has {}", graph.minimum_cost(), self.graph.minimum_cost()));
+        }
+        Ok(())
+    }
+
+    // the scenarios at the checkpoint and the operations after it reproduce the failure
+    fn dump(&self, index: usize, error: &str) -> String {
+        let (checkpoint, scenarios, current) = &self.checkpoint;
+        let mut bundle = String::new();
+        writeln!(bundle, "seed {}\nfailed at operation {index}: {error}\ncheckpoint at operation {checkpoint}, scenario {current}", self.seed).unwrap();
+        for (i, scenario) in scenarios.iter().enumerate() {
+            writeln!(bundle, "scenario {i}: {scenario:?}").unwrap();
+        }
+        for operation in self.log.iter() {
+            writeln!(bundle, "{operation:?}").unwrap();
+        }
+
+        let path = std::env::temp_dir().join(format!("soak-{}-{index}.txt", self.seed));
+        std::fs::write(&path, bundle).unwrap();
+        format!("operation {index}: {error} (repro bundle in {})", path.display())
+    }
+
+    fn run(&mut self, num_operations: usize) {
+        let mut rng = XorShift::new(self.seed + 1);
+        if let Err(error) = self.solve(&Operation::SwapScenario { index: 0 }) {
+            panic!("{}", self.dump(0, &error));
+        }
+
+        for index in 1..=num_operations {
+            let operation = Operation::random(&mut rng, &self.scenarios[self.current]);
+            self.log.push(operation.clone());
+            self.apply(&operation);
+
+            let mut result = self.solve(&operation);
+            if result.is_ok() && index % CHECK_INTERVAL == 0 {
+                result = self.cross_check();
+            }
+            if let Err(error) = result {
+                panic!("{}", self.dump(index, &error));
+            }
+
+            if index % CHECK_INTERVAL == 0 {
+                self.checkpoint = (index, self.scenarios.clone(), self.current);
+                self.log.clear();
+            }
+        }
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name).map_or(default, |value| value.parse().unwrap())
+}
+
+#[test]
+fn soak_smoke() {
+    Soak::new(766).run(CHECK_INTERVAL);
+}
+
+#[test]
+#[ignore]
+fn soak() {
+    Soak::new(env_or("SOAK_SEED", 7661)).run(env_or("SOAK_OPERATIONS", 100_000) as usize);
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::out_of_kilter::OutOfKilter;
+use network_algorithms::minimum_cost_flow::parametric_network_simplex::ParametricNetworkSimplex;
+use network_algorithms::minimum_cost_flow::primal_dual::PrimalDual;
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solution::Solution;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+type Solve = fn(&mut Graph<i64>) -> Result<Solution<i64>, Status>;
+
+// 0 -> 1 -> 2 and 0 -> 2
+fn triangle(amount: i64) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, amount);
+    graph.add_demand(2, amount);
+    graph.add_directed_edge(0, 1, 0, 3, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 3, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 5, 3).unwrap();
+    graph
+}
+
+fn assert_same_as_graph(solution: &Solution<i64>, graph: &Graph<i64>) {
+    assert_eq!(solution.objective(), graph.minimum_cost());
+    assert_eq!(solution.flows().len(), graph.num_edges());
+    for edge_id in 0..graph.num_edges() {
+        assert_eq!(solution.flow(edge_id), graph.get_edge(edge_id).unwrap().flow);
+    }
+    assert_eq!(solution.potentials().len(), graph.num_nodes());
+    for u in 0..graph.num_nodes() {
+        assert_eq!(solution.potential(u), graph.potential(u));
+    }
+}
+
+#[test]
+fn keep_solutions_for_different_supplies() {
+    let solutions: Vec<Solution<i64>> = (1..=6)
+        .map(|amount| {
+            let mut graph = triangle(amount);
+            PrimalNetworkSimplex::default()
+                .solve_with_solution(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph)
+                .unwrap()
+        })
+        .collect();
+
+    assert_eq!(solutions.iter().map(|solution| solution.objective()).collect::<Vec<_>>(), vec![2, 4, 6, 9, 12, 15]);
+    assert_eq!(solutions[1].flows(), &[2, 2, 0]);
+    assert_eq!(solutions[5].flows(), &[3, 3, 3]);
+    for solution in solutions.iter() {
+        for (edge_id, &(from, to, cost)) in [(0, 1, 1), (1, 2, 1), (0, 2, 3)].iter().enumerate() {
+            let reduced_cost = cost - solution.potential(from) + solution.potential(to);
+            let (lower, upper) = (0, if edge_id == 2 { 5 } else { 3 });
+            assert!(reduced_cost <= 0 || solution.flow(edge_id) == lower);
+            assert!(reduced_cost >= 0 || solution.flow(edge_id) == upper);
+        }
+    }
+}
+
+#[test]
+fn infeasible() {
+    let mut graph = triangle(9);
+    assert_eq!(SuccessiveShortestPath::default().solve_with_solution(&mut graph), Err(Status::Infeasible));
+
+    let mut graph = triangle(1);
+    graph.add_supply(1, 1);
+    assert_eq!(PrimalDual::default().solve_with_solution(&mut graph), Err(Status::Unbalanced));
+}
+
+#[test]
+fn iterations() {
+    let mut graph = triangle(6);
+    let mut solver = SuccessiveShortestPath::default();
+    // one augmentation along each path
+    assert_eq!(solver.solve_with_solution(&mut graph).unwrap().iterations(), 2);
+
+    let mut graph = triangle(6);
+    let mut solver = PrimalNetworkSimplex::default();
+    let mut pivot = BlockSearchPivotRule::new(graph.num_edges());
+    let solution = solver.solve_with_solution(&mut pivot, &mut graph).unwrap();
+    assert_eq!(solution.iterations(), solver.num_pivots());
+}
+
+#[test]
+fn objective_i128() {
+    // every value fits in i32, but the cost * flow of each edge does not
+    let mut graph = Graph::<i32>::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 100_000);
+    graph.add_demand(2, 100_000);
+    graph.add_directed_edge(0, 1, 0, 100_000, 100_000).unwrap();
+    graph.add_directed_edge(1, 2, 0, 100_000, 30_000).unwrap();
+    graph.add_directed_edge(0, 2, 0, 100_000, 200_000).unwrap();
+
+    let solution = SuccessiveShortestPath::default().solve_with_solution(&mut graph).unwrap();
+    assert_eq!(graph.minimum_cost_i128(), 13_000_000_000);
+    assert_eq!(solution.objective_i128(), 13_000_000_000);
+    assert_eq!(solution.flows(), &[100_000, 100_000, 0]);
+}
+
+#[test]
+fn random() {
+    let mut rng = XorShift::new(752);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (2 + rng.gen_index(8), rng.gen_index(20));
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        let mut expected_graph = graph.clone();
+        let expected = PrimalNetworkSimplex::default().solve_with_solution(&mut BlockSearchPivotRule::new(num_edges), &mut expected_graph);
+        if let Ok(solution) = expected.as_ref() {
+            assert_same_as_graph(solution, &expected_graph);
+        }
+
+        let solvers: Vec<Solve> = vec![
+            |graph| SuccessiveShortestPath::default().solve_with_solution(graph),
+            |graph| PrimalDual::default().solve_with_solution(graph),
+            |graph| OutOfKilter::default().solve_with_solution(graph),
+            |graph| CycleCanceling::default().solve_with_solution(graph),
+            |graph| CostScalingPushRelabel::default().solve_with_solution(graph),
+            |graph| ParametricNetworkSimplex::default().solve_with_solution(graph),
+        ];
+        for solve in solvers {
+            let mut graph = graph.clone();
+            let actual = solve(&mut graph);
+            assert_eq!(actual.as_ref().map(|solution| solution.objective()), expected.as_ref().map(|solution| solution.objective()));
+            if let Ok(solution) = actual.as_ref() {
+                assert_same_as_graph(solution, &graph);
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::capacity_scaling::CapacityScaling;
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::edmonds_karp::EdmondsKarp;
+use network_algorithms::maximum_flow::ford_fulkerson::FordFulkerson;
+use network_algorithms::maximum_flow::push_relabel_fifo::PushRelabelFIFO;
+use network_algorithms::maximum_flow::shortest_augmenting_path::ShortestAugmentingPath;
+use network_algorithms::minimum_cost_flow;
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::cycle_canceling::CycleCanceling;
+use network_algorithms::minimum_cost_flow::out_of_kilter::OutOfKilter;
+use network_algorithms::minimum_cost_flow::primal_dual::PrimalDual;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+use std::thread;
+
+fn random_maximum_flow_graph(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> maximum_flow::graph::Graph<i64> {
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(num_nodes);
+    for _ in 0..num_edges {
+        graph.add_directed_edge(rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 20)).unwrap();
+    }
+    graph
+}
+
+fn maximum_flow_edges(graph: &maximum_flow::graph::Graph<i64>) -> Vec<maximum_flow::graph::Edge<i64>> {
+    (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).collect()
+}
+
+fn minimum_cost_flow_state(graph: &minimum_cost_flow::graph::Graph<i64>) -> (Vec<minimum_cost_flow::graph::Edge<i64>>, Vec<i64>, Vec<i64>) {
+    (
+        (0..graph.num_edges()).map(|edge_id| graph.get_edge(edge_id).unwrap()).collect(),
+        (0..graph.num_nodes()).map(|u| graph.excess(u)).collect(),
+        (0..graph.num_nodes()).map(|u| graph.potential(u)).collect(),
+    )
+}
+
+// solve_ref leaves the graph untouched, and commit makes it the same as solve does
+fn check_maximum_flow(
+    graph: &maximum_flow::graph::Graph<i64>,
+    solve_ref: impl Fn(&maximum_flow::graph::Graph<i64>) -> maximum_flow::flow_result::FlowResult<i64>,
+    solve: impl Fn(&mut maximum_flow::graph::Graph<i64>) -> maximum_flow::status::Status,
+) {
+    let result = solve_ref(graph);
+    let mut committed = graph.clone();
+    maximum_flow::flow_result::commit(&mut committed, &result);
+
+    let mut solved = graph.clone();
+    assert_eq!(solve(&mut solved), result.status());
+    assert_eq!(maximum_flow_edges(&committed), maximum_flow_edges(&solved));
+    assert_eq!(result.flows().map(|flows| flows.to_vec()), Some(maximum_flow_edges(&solved).iter().map(|edge| edge.flow).collect()));
+}
+
+fn check_minimum_cost_flow(
+    graph: &minimum_cost_flow::graph::Graph<i64>,
+    solve_ref: impl Fn(&minimum_cost_flow::graph::Graph<i64>) -> minimum_cost_flow::flow_result::FlowResult<i64>,
+    solve: impl Fn(&mut minimum_cost_flow::graph::Graph<i64>) -> minimum_cost_flow::status::Status,
+) {
+    let result = solve_ref(graph);
+    let mut committed = graph.clone();
+    minimum_cost_flow::flow_result::commit(&mut committed, &result);
+
+    let mut solved = graph.clone();
+    assert_eq!(solve(&mut solved), result.status());
+    assert_eq!(minimum_cost_flow_state(&committed), minimum_cost_flow_state(&solved));
+}
+
+#[test]
+fn maximum_flow_wrapper_equivalence() {
+    let mut rng = XorShift::new(761);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 10) as usize, rng.gen_range(1, 30) as usize);
+        let graph = random_maximum_flow_graph(&mut rng, num_nodes, num_edges);
+        let (source, sink) = (0, num_nodes - 1);
+
+        check_maximum_flow(&graph, |graph| Dinic::default().solve_ref(source, sink, graph), |graph| Dinic::default().solve(source, sink, graph));
+        check_maximum_flow(&graph, |graph| EdmondsKarp::default().solve_ref(source, sink, graph), |graph| EdmondsKarp::default().solve(source, sink, graph));
+        check_maximum_flow(&graph, |graph| FordFulkerson::default().solve_ref(source, sink, graph), |graph| FordFulkerson::default().solve(source, sink, graph));
+        check_maximum_flow(&graph, |graph| CapacityScaling::default().solve_ref(source, sink, graph), |graph| CapacityScaling::default().solve(source, sink, graph));
+        check_maximum_flow(&graph, |graph| ShortestAugmentingPath::default().solve_ref(source, sink, graph), |graph| ShortestAugmentingPath::default().solve(source, sink, graph));
+        check_maximum_flow(&graph, |graph| PushRelabelFIFO::default().solve_ref(source, sink, graph), |graph| PushRelabelFIFO::default().solve(source, sink, graph));
+    }
+}
+
+#[test]
+fn maximum_flow_bad_input() {
+    let mut graph = random_maximum_flow_graph(&mut XorShift::new(7610), 3, 5);
+    let result = Dinic::default().solve_ref(0, 0, &graph);
+    assert_eq!(result.status(), maximum_flow::status::Status::BadInput);
+    assert_eq!(result.flows(), None);
+
+    let edges = maximum_flow_edges(&graph);
+    maximum_flow::flow_result::commit(&mut graph, &result);
+    assert_eq!(maximum_flow_edges(&graph), edges);
+}
+
+#[test]
+fn minimum_cost_flow_wrapper_equivalence() {
+    let mut rng = XorShift::new(7611);
+    for _ in 0..100 {
+        let (num_nodes, num_edges) = (rng.gen_range(2, 10) as usize, rng.gen_range(1, 30) as usize);
+        let graph = random_minimum_cost_flow_graph(&mut rng, num_nodes, num_edges, 10, 10);
+
+        check_minimum_cost_flow(&graph, |graph| SuccessiveShortestPath::default().solve_ref(graph), |graph| SuccessiveShortestPath::default().solve(graph));
+        check_minimum_cost_flow(&graph, |graph| PrimalDual::default().solve_ref(graph), |graph| PrimalDual::default().solve(graph));
+        check_minimum_cost_flow(&graph, |graph| OutOfKilter::default().solve_ref(graph), |graph| OutOfKilter::default().solve(graph));
+        check_minimum_cost_flow(&graph, |graph| CycleCanceling::default().solve_ref(graph), |graph| CycleCanceling::default().solve(graph));
+        check_minimum_cost_flow(&graph, |graph| CostScalingPushRelabel::default().solve_ref(graph), |graph| CostScalingPushRelabel::default().solve(graph));
+    }
+}
+
+#[test]
+fn concurrent_solve_ref() {
+    let mut rng = XorShift::new(7612);
+
+    let mut graph = random_maximum_flow_graph(&mut rng, 200, 2000);
+    let (source, sink) = (0, 199);
+    let (dinic, push_relabel) = thread::scope(|s| {
+        let dinic = s.spawn(|| Dinic::default().solve_ref(source, sink, &graph));
+        let push_relabel = s.spawn(|| PushRelabelFIFO::default().solve_ref(source, sink, &graph));
+        (dinic.join().unwrap(), push_relabel.join().unwrap())
+    });
+    let mut expected = graph.clone();
+    Dinic::default().solve(source, sink, &mut expected);
+    for result in [dinic, push_relabel] {
+        assert_eq!(result.status(), maximum_flow::status::Status::Optimal);
+        maximum_flow::flow_result::commit(&mut graph, &result);
+        assert_eq!(graph.maximum_flow(source), expected.maximum_flow(source));
+    }
+
+    let mut graph = random_minimum_cost_flow_graph(&mut rng, 100, 1000, 10, 10);
+    let (successive_shortest_path, cost_scaling) = thread::scope(|s| {
+        let successive_shortest_path = s.spawn(|| SuccessiveShortestPath::default().solve_ref(&graph));
+        let cost_scaling = s.spawn(|| CostScalingPushRelabel::default().solve_ref(&graph));
+        (successive_shortest_path.join().unwrap(), cost_scaling.join().unwrap())
+    });
+    let mut expected = graph.clone();
+    let status = SuccessiveShortestPath::default().solve(&mut expected);
+    for result in [successive_shortest_path, cost_scaling] {
+        assert_eq!(result.status(), status);
+        minimum_cost_flow::flow_result::commit(&mut graph, &result);
+        assert_eq!(graph.minimum_cost(), expected.minimum_cost());
+    }
+}
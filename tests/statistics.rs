@@ -0,0 +1,98 @@
+mod common;
+
+use common::{random_minimum_cost_flow_graph, XorShift};
+use network_algorithms::maximum_flow;
+use network_algorithms::maximum_flow::push_relabel_fifo::PushRelabelFIFO;
+use network_algorithms::maximum_flow::solver::MaximumFlowSolver;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::solver::MinimumCostFlowSolver;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+#[test]
+fn successive_shortest_path_on_a_path() {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    for u in 0..3 {
+        graph.add_directed_edge(u, u + 1, 0, 10, 1).unwrap();
+    }
+    graph.add_supply(0, 5);
+    graph.add_demand(3, 5);
+
+    for tree_repair in [false, true] {
+        let mut solver = SuccessiveShortestPath::default();
+        solver.set_tree_repair(tree_repair);
+        assert_eq!(solver.solve(&mut graph.clone()), Status::Optimal);
+        let statistics = solver.stats();
+        assert_eq!(statistics.num_augmentations, 1);
+        assert_eq!(solver.augmented_units(), 5);
+        assert_eq!(statistics.num_dijkstra_runs, 1);
+        assert_eq!(statistics.num_pivots, 0);
+    }
+}
+
+#[test]
+fn successive_shortest_path_random() {
+    let mut rng = XorShift::new(785);
+    for _ in 0..50 {
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, 20, 80, 20, 30);
+        let mut solver = SuccessiveShortestPath::default();
+        if solver.solve(&mut graph) != Status::Optimal {
+            continue;
+        }
+        let statistics = solver.stats();
+        assert_eq!(statistics.num_augmentations, solver.num_augmentations());
+        // each augmentation is found by a run of its own
+        assert!(statistics.num_dijkstra_runs >= statistics.num_augmentations);
+        assert!(solver.augmented_units() >= statistics.num_augmentations as i64);
+    }
+}
+
+#[test]
+fn primal_network_simplex() {
+    let mut rng = XorShift::new(785);
+    let mut num_degenerate_pivots = 0;
+    for _ in 0..50 {
+        let mut graph = random_minimum_cost_flow_graph(&mut rng, 20, 80, 20, 30);
+        let mut solver = PrimalNetworkSimplex::default();
+        if solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph) != Status::Optimal {
+            continue;
+        }
+        let statistics = solver.stats();
+        assert_eq!(statistics.num_pivots, solver.num_pivots());
+        assert!(statistics.num_degenerate_pivots <= statistics.num_pivots);
+        num_degenerate_pivots += statistics.num_degenerate_pivots;
+        // one search for each pivot and the last one that finds no entering edge
+        assert_eq!(statistics.num_entering_edge_scans, statistics.num_pivots + 1);
+        assert_eq!(statistics.num_augmentations, 0);
+    }
+    // the artificial edges of the initial tree carry no flow at the nodes without a supply
+    assert!(num_degenerate_pivots > 0);
+}
+
+#[test]
+fn push_relabel_fifo() {
+    // source 0 -> 1 -> 2 sink: the preflow saturates 0 -> 1, and node 1 pushes once
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    graph.add_directed_edge(1, 2, 10).unwrap();
+    let mut solver = PushRelabelFIFO::default();
+    assert_eq!(solver.solve(0, 2, &mut graph), maximum_flow::status::Status::Optimal);
+    let statistics = solver.stats();
+    assert_eq!((statistics.num_pushes, statistics.num_relabels, statistics.num_gap_relabelings), (1, 0, 0));
+
+    // node 1 cannot reach sink with all its excess, and it is the only node at its distance
+    let mut graph = maximum_flow::graph::Graph::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 5).unwrap();
+    graph.add_directed_edge(1, 2, 3).unwrap();
+    // the distance counts of the previous solve are not carried over
+    assert_eq!(solver.solve(0, 2, &mut graph), maximum_flow::status::Status::Optimal);
+    assert_eq!(graph.maximum_flow(0), 3);
+    let statistics = solver.stats();
+    assert_eq!(statistics.num_pushes, 1);
+    assert_eq!(statistics.num_gap_relabelings, 1);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::maximum_flow::graph::Graph;
+use network_algorithms::maximum_flow::stoer_wagner::global_min_cut;
+
+fn graph(num_nodes: usize, edges: &[(usize, usize, i64)]) -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(num_nodes);
+    for &(u, v, capacity) in edges.iter() {
+        graph.add_directed_edge(u, v, capacity).unwrap();
+    }
+    graph
+}
+
+fn cut_value(edges: &[(usize, usize, i64)], side: &[usize]) -> i64 {
+    edges.iter().filter(|&&(a, b, _)| side.contains(&a) != side.contains(&b)).map(|&(_, _, capacity)| capacity).sum()
+}
+
+#[test]
+fn small() {
+    // two triangles joined by an edge of capacity 2
+    let edges = vec![(0, 1, 3), (1, 2, 4), (2, 0, 5), (3, 4, 3), (4, 5, 3), (5, 3, 3), (2, 3, 2)];
+    let (value, side) = global_min_cut(&graph(6, &edges));
+    assert_eq!(value, 2);
+    assert!(side == vec![0, 1, 2] || side == vec![3, 4, 5]);
+
+    // parallel edges add up, and the direction does not matter
+    let (value, side) = global_min_cut(&graph(3, &[(0, 1, 2), (1, 0, 2), (1, 2, 3), (2, 2, 1)]));
+    assert_eq!(value, 3);
+    assert!(side == vec![2] || side == vec![0, 1]);
+
+    // disconnected
+    assert_eq!(global_min_cut(&graph(4, &[(0, 1, 4), (2, 3, 1)])), (0, vec![0, 1]));
+    assert_eq!(global_min_cut(&graph(3, &[(1, 2, 4)])), (0, vec![0]));
+}
+
+#[test]
+fn brute_force() {
+    let mut rng = XorShift::new(7702);
+    for _ in 0..300 {
+        let num_nodes = rng.gen_range(2, 13) as usize;
+        let edges: Vec<(usize, usize, i64)> = (0..rng.gen_range(0, 40)).map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10))).collect();
+        let (value, side) = global_min_cut(&graph(num_nodes, &edges));
+
+        // every partition with node 0 on the first side
+        let expected = (0..1u32 << (num_nodes - 1))
+            .map(|mask| (0..num_nodes).filter(|&u| u == 0 || mask >> (u - 1) & 1 == 0).collect::<Vec<_>>())
+            .filter(|side| side.len() < num_nodes)
+            .map(|side| cut_value(&edges, &side))
+            .min()
+            .unwrap();
+        assert_eq!(value, expected);
+        assert!(!side.is_empty() && side.len() < num_nodes);
+        assert!(side.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(cut_value(&edges, &side), value);
+    }
+}
@@ -0,0 +1,65 @@
+use network_algorithms::generalized_maximum_flow;
+use network_algorithms::graph_error::GraphError;
+use network_algorithms::ids::NodeId;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+#[test]
+fn try_add_supply_out_of_range() {
+    let mut graph = Graph::<i64>::default();
+    graph.add_nodes(2);
+    assert_eq!(graph.try_add_supply(0, 3), Ok(()));
+    assert_eq!(graph.try_add_demand(NodeId(1), 3), Ok(()));
+    assert_eq!(graph.try_add_supply(2, 1), Err(GraphError::NodeOutOfRange { node: 2, num_nodes: 2 }));
+    assert_eq!(graph.try_add_demand(usize::MAX, 1), Err(GraphError::NodeOutOfRange { node: usize::MAX, num_nodes: 2 }));
+    assert_eq!(graph.supplies(), &[3, -3]);
+    assert_eq!((graph.supply(0), graph.excess(0), graph.supply(1), graph.excess(1)), (3, 3, -3, -3));
+
+    let mut graph = generalized_maximum_flow::graph::Graph::<f64>::default();
+    graph.add_node();
+    assert_eq!(graph.try_add_supply(0, 1.5), Ok(()));
+    assert_eq!(graph.try_add_demand(1, 1.5), Err(GraphError::NodeOutOfRange { node: 1, num_nodes: 1 }));
+    assert_eq!((graph.supply(0), graph.supplies()), (1.5, &[1.5][..]));
+}
+
+#[test]
+fn set_supplies() {
+    let mut graph = Graph::<i64>::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 1, 0, 10, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 10, 1).unwrap();
+    graph.add_supply(1, 4);
+
+    // the pairs replace b, and the last pair of a node wins
+    assert_eq!(graph.set_supplies(&[(0, 1), (2, -5), (0, 5), (1, 0)]), Ok(()));
+    assert_eq!(graph.supplies(), &[5, 0, -5]);
+    assert_eq!(SuccessiveShortestPath::default().solve(&mut graph), Status::Optimal);
+    assert_eq!(graph.minimum_cost(), 10);
+
+    let nodes = [NodeId(0), NodeId(2)];
+    assert_eq!(graph.set_supplies(&[(nodes[0], 0), (nodes[1], 0)]), Ok(()));
+    assert_eq!(graph.supplies(), &[0, 0, 0]);
+}
+
+#[test]
+fn set_supplies_all_or_nothing() {
+    let mut graph = Graph::<i64>::default();
+    graph.add_nodes(3);
+    graph.add_directed_edge(0, 2, 1, 3, 1).unwrap();
+    graph.add_supply(0, 2);
+    graph.add_demand(2, 2);
+    let (supplies, excesses) = (graph.supplies().to_vec(), (0..3).map(|u| graph.excess(u)).collect::<Vec<_>>());
+
+    assert_eq!(graph.set_supplies(&[(1, 7), (0, 1), (3, 4), (2, -8)]), Err(GraphError::NodeOutOfRange { node: 3, num_nodes: 3 }));
+    assert_eq!(graph.supplies(), supplies);
+    assert_eq!((0..3).map(|u| graph.excess(u)).collect::<Vec<_>>(), excesses);
+
+    let mut graph = generalized_maximum_flow::graph::Graph::<f64>::default();
+    graph.add_nodes(2);
+    graph.add_supply(1, 2.0);
+    assert_eq!(graph.set_supplies(&[(0, 1.0), (2, 1.0)]), Err(GraphError::NodeOutOfRange { node: 2, num_nodes: 2 }));
+    assert_eq!(graph.supplies(), &[0.0, 2.0]);
+    assert_eq!(graph.set_supplies(&[(0, 1.0), (1, -1.0)]), Ok(()));
+    assert_eq!(graph.supplies(), &[1.0, -1.0]);
+}
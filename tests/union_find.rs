@@ -0,0 +1,158 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::util::union_find::{RollbackUnionFind, UnionFind};
+
+// the label of the set of each element, relabeled on every union
+struct Naive {
+    labels: Vec<usize>,
+}
+
+impl Naive {
+    fn new(num_elements: usize) -> Self {
+        Self { labels: (0..num_elements).collect() }
+    }
+
+    fn union(&mut self, u: usize, v: usize) -> bool {
+        let (label_u, label_v) = (self.labels[u], self.labels[v]);
+        if label_u == label_v {
+            return false;
+        }
+        self.labels.iter_mut().filter(|label| **label == label_v).for_each(|label| *label = label_u);
+        true
+    }
+
+    fn same(&self, u: usize, v: usize) -> bool {
+        self.labels[u] == self.labels[v]
+    }
+
+    fn component_size(&self, u: usize) -> usize {
+        self.labels.iter().filter(|&&label| label == self.labels[u]).count()
+    }
+
+    fn components(&self) -> Vec<Vec<usize>> {
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for u in 0..self.labels.len() {
+            match components.iter_mut().find(|component| self.labels[component[0]] == self.labels[u]) {
+                Some(component) => component.push(u),
+                None => components.push(vec![u]),
+            }
+        }
+        components
+    }
+}
+
+#[test]
+fn adversarial_orders() {
+    let n = 1000;
+    // a chain from one end, a chain from the other end, and a star, all of which give deep trees without union by size
+    let orders: Vec<Vec<(usize, usize)>> = vec![
+        (0..n - 1).map(|u| (u, u + 1)).collect(),
+        (0..n - 1).rev().map(|u| (u + 1, u)).collect(),
+        (1..n).map(|u| (u, 0)).collect(),
+        (1..n).map(|u| (0, u)).collect(),
+    ];
+    for order in orders {
+        let mut union_find = UnionFind::new(n);
+        let mut rollback = RollbackUnionFind::new(n);
+        for (i, &(u, v)) in order.iter().enumerate() {
+            assert!(union_find.union(u, v));
+            assert!(rollback.union(u, v));
+            assert!(!union_find.union(v, u));
+            assert!(!rollback.union(u, v));
+            assert_eq!(union_find.component_size(u), i + 2);
+            assert_eq!(rollback.component_size(v), i + 2);
+        }
+        assert_eq!(union_find.num_components(), 1);
+        assert_eq!(union_find.components(), vec![(0..n).collect::<Vec<_>>()]);
+
+        // union by size keeps the trees of the rollback version within log n
+        let depth = |mut u: usize| {
+            let mut depth = 0;
+            while rollback.find(u) != u {
+                u = rollback.find(u);
+                depth += 1;
+            }
+            depth
+        };
+        assert!((0..n).all(|u| depth(u) <= 1));
+    }
+}
+
+#[test]
+fn rollback_interleaved_with_queries() {
+    let mut union_find = RollbackUnionFind::new(6);
+    union_find.union(0, 1);
+    let first = union_find.snapshot();
+    union_find.union(2, 3);
+    union_find.union(1, 2);
+    assert!(union_find.same(0, 3));
+    assert_eq!(union_find.component_size(3), 4);
+
+    let second = union_find.snapshot();
+    assert!(!union_find.union(0, 2));
+    assert_eq!(union_find.snapshot(), second);
+    union_find.union(4, 5);
+    union_find.union(5, 0);
+    assert_eq!(union_find.num_components(), 1);
+
+    union_find.rollback_to(second);
+    assert_eq!(union_find.components(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+    assert!(union_find.undo());
+    assert_eq!(union_find.components(), vec![vec![0, 1], vec![2, 3], vec![4], vec![5]]);
+    union_find.union(3, 4);
+    assert_eq!(union_find.component_size(4), 3);
+
+    union_find.rollback_to(first);
+    assert_eq!(union_find.components(), vec![vec![0, 1], vec![2], vec![3], vec![4], vec![5]]);
+    assert!(union_find.undo());
+    assert!(!union_find.undo());
+    assert_eq!(union_find.num_components(), 6);
+}
+
+#[test]
+fn random_against_naive() {
+    let mut rng = XorShift::new(775);
+    for _ in 0..100 {
+        let n = rng.gen_range(1, 40) as usize;
+        let mut union_find = UnionFind::new(n);
+        let mut rollback = RollbackUnionFind::new(n);
+        // the naive state at each snapshot
+        let mut naive = Naive::new(n);
+        let mut saved: Vec<(_, Vec<usize>)> = Vec::new();
+        for _ in 0..200 {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            match rng.gen_range(0, 9) {
+                0..=3 => {
+                    let expected = naive.union(u, v);
+                    assert_eq!(rollback.union(u, v), expected);
+                }
+                4 => saved.push((rollback.snapshot(), naive.labels.clone())),
+                5 => {
+                    if let Some((snapshot, labels)) = saved.pop() {
+                        rollback.rollback_to(snapshot);
+                        naive.labels = labels;
+                    }
+                }
+                _ => {
+                    assert_eq!(rollback.same(u, v), naive.same(u, v));
+                    assert_eq!(rollback.component_size(u), naive.component_size(u));
+                }
+            }
+            assert_eq!(rollback.num_components(), naive.components().len());
+        }
+        assert_eq!(rollback.components(), naive.components());
+
+        // the same unions without rollbacks
+        let mut naive = Naive::new(n);
+        for _ in 0..2 * n {
+            let (u, v) = (rng.gen_index(n), rng.gen_index(n));
+            assert_eq!(union_find.union(u, v), naive.union(u, v));
+            let (x, y) = (rng.gen_index(n), rng.gen_index(n));
+            assert_eq!(union_find.same(x, y), naive.same(x, y));
+            assert_eq!(union_find.component_size(x), naive.component_size(x));
+        }
+        assert_eq!(union_find.components(), naive.components());
+        assert_eq!(union_find.num_components(), naive.components().len());
+    }
+}
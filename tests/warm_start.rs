@@ -0,0 +1,291 @@
+mod common;
+
+use common::XorShift;
+use network_algorithms::minimum_cost_flow::basis::{BasisError, PotentialBundle};
+use network_algorithms::minimum_cost_flow::cost_scaling_push_relabel::CostScalingPushRelabel;
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use network_algorithms::minimum_cost_flow::successive_shortest_path::SuccessiveShortestPath;
+
+struct Instance {
+    num_nodes: usize,
+    edges: Vec<(usize, usize, i64, i64)>, // from, to, upper, cost
+    supplies: Vec<i64>,
+}
+
+impl Instance {
+    // always feasible thanks to the expensive cycle through all nodes
+    fn random(rng: &mut XorShift, num_nodes: usize, num_edges: usize) -> Self {
+        let mut edges: Vec<(usize, usize, i64, i64)> = (0..num_edges)
+            .map(|_| (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(1, 20), rng.gen_range(-20, 100)))
+            .collect();
+        edges.extend((0..num_nodes).map(|u| (u, (u + 1) % num_nodes, 1_000_000, 1000)));
+        let mut supplies = vec![0; num_nodes];
+        for _ in 0..num_nodes {
+            let (u, v, amount) = (rng.gen_index(num_nodes), rng.gen_index(num_nodes), rng.gen_range(0, 10));
+            supplies[u] += amount;
+            supplies[v] -= amount;
+        }
+        Self { num_nodes, edges, supplies }
+    }
+
+    fn graph(&self) -> Graph<i64> {
+        let mut graph = Graph::default();
+        graph.add_nodes(self.num_nodes);
+        for &(from, to, upper, cost) in self.edges.iter() {
+            graph.add_directed_edge(from, to, 0, upper, cost).unwrap();
+        }
+        for (u, &supply) in self.supplies.iter().enumerate() {
+            graph.add_supply(u, supply);
+        }
+        graph
+    }
+}
+
+fn cold_solve(instance: &Instance) -> (Status, i64, usize) {
+    let mut graph = instance.graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    let status = solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph);
+    (status, graph.minimum_cost(), solver.num_pivots())
+}
+
+fn satisfy_optimality_conditions(graph: &Graph<i64>) -> bool {
+    (0..graph.num_edges()).all(|edge_id| {
+        let edge = graph.get_edge(edge_id).unwrap();
+        let reduced_cost = edge.cost - graph.potential(edge.from) + graph.potential(edge.to);
+        (reduced_cost <= 0 || edge.flow == edge.lower) && (reduced_cost >= 0 || edge.flow == edge.upper)
+    })
+}
+
+#[test]
+fn bump_one_supply() {
+    let mut rng = XorShift::new(757);
+    let mut instance = Instance::random(&mut rng, 300, 1500);
+    let mut graph = instance.graph();
+    let mut solver = PrimalNetworkSimplex::default();
+
+    // nothing to start from
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(!solver.is_warm_started());
+    let num_cold_pivots = solver.num_pivots();
+
+    let (u, v) = (10, 200);
+    graph.add_supply(u, 1);
+    graph.add_demand(v, 1);
+    instance.supplies[u] += 1;
+    instance.supplies[v] -= 1;
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(solver.is_warm_started());
+    assert!(satisfy_optimality_conditions(&graph));
+
+    let (status, objective, _) = cold_solve(&instance);
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(graph.minimum_cost(), objective);
+    assert!(solver.num_pivots() * 20 < num_cold_pivots, "{} {}", solver.num_pivots(), num_cold_pivots);
+}
+
+#[test]
+fn sequence_of_supply_changes() {
+    let mut rng = XorShift::new(7570);
+    let mut instance = Instance::random(&mut rng, 30, 100);
+    let mut graph = instance.graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    let mut num_warm_starts = 0;
+    for _ in 0..100 {
+        assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+        assert!(satisfy_optimality_conditions(&graph));
+        assert_eq!(graph.minimum_cost(), cold_solve(&instance).1);
+        num_warm_starts += solver.is_warm_started() as usize;
+
+        let (u, v, amount) = (rng.gen_index(instance.num_nodes), rng.gen_index(instance.num_nodes), rng.gen_range(1, 5));
+        graph.add_supply(u, amount);
+        graph.add_demand(v, amount);
+        instance.supplies[u] += amount;
+        instance.supplies[v] -= amount;
+    }
+    assert!(num_warm_starts > 50);
+}
+
+#[test]
+fn fall_back_to_cold_start() {
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+
+    // the tree flows become 6 > 5. the basis is repaired through the artificial edges, which keep a flow of 1
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Infeasible);
+    assert!(solver.is_warm_started());
+
+    // an infeasible solve does not keep its basis
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Infeasible);
+    assert!(!solver.is_warm_started());
+
+    // a different graph
+    let mut graph = Graph::default();
+    graph.add_nodes(3);
+    graph.add_supply(0, 3);
+    graph.add_demand(2, 3);
+    graph.add_directed_edge(0, 1, 0, 5, 1).unwrap();
+    graph.add_directed_edge(1, 2, 0, 5, 1).unwrap();
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    graph.add_directed_edge(0, 2, 0, 5, 1).unwrap();
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(!solver.is_warm_started());
+    assert_eq!(graph.minimum_cost(), 3);
+
+    // solve never starts from the basis
+    graph.add_supply(0, 1);
+    graph.add_demand(2, 1);
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(!solver.is_warm_started());
+    assert_eq!(graph.minimum_cost(), 4);
+}
+
+// the costs of instance times 100, moved by up to 1%
+fn perturb_costs(rng: &mut XorShift, instance: &Instance) -> (Instance, Instance) {
+    let scaled = Instance { num_nodes: instance.num_nodes, edges: instance.edges.iter().map(|&(from, to, upper, cost)| (from, to, upper, cost * 100)).collect(), supplies: instance.supplies.clone() };
+    let perturbed = Instance {
+        num_nodes: instance.num_nodes,
+        edges: instance
+            .edges
+            .iter()
+            .map(|&(from, to, upper, cost)| (from, to, upper, cost * 100 + rng.gen_range(-cost.abs(), cost.abs() + 1)))
+            .collect(),
+        supplies: instance.supplies.clone(),
+    };
+    (scaled, perturbed)
+}
+
+#[test]
+fn export_and_import_basis() {
+    let mut rng = XorShift::new(768);
+    let instance = Instance::random(&mut rng, 300, 1500);
+    let (instance, perturbed) = perturb_costs(&mut rng, &instance);
+
+    let mut solver = PrimalNetworkSimplex::default();
+    assert!(solver.export_basis().is_none());
+    let mut graph = instance.graph();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    let bundle = solver.export_basis().unwrap();
+    assert_eq!(bundle.fingerprint, graph.topology_fingerprint());
+    assert!((0..graph.num_nodes()).all(|u| bundle.potentials[u] == graph.potential(u)));
+
+    // another solver, as in another process
+    let mut graph = perturbed.graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.import_basis(&graph, &bundle), Ok(()));
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(solver.is_warm_started());
+    assert!(satisfy_optimality_conditions(&graph));
+
+    let (status, objective, num_cold_pivots) = cold_solve(&perturbed);
+    assert_eq!(status, Status::Optimal);
+    assert_eq!(graph.minimum_cost(), objective);
+    assert!(solver.num_pivots() * 2 < num_cold_pivots, "{} {}", solver.num_pivots(), num_cold_pivots);
+
+    // supplies changed as well
+    let mut changed = perturbed;
+    changed.supplies[3] += 5;
+    changed.supplies[200] -= 5;
+    let mut graph = changed.graph();
+    assert_eq!(solver.import_basis(&graph, &bundle), Ok(()));
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(solver.is_warm_started());
+    assert_eq!(graph.minimum_cost(), cold_solve(&changed).1);
+}
+
+#[test]
+fn reject_bundles() {
+    let mut rng = XorShift::new(7680);
+    let instance = Instance::random(&mut rng, 30, 100);
+    let mut graph = instance.graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    assert_eq!(solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    let bundle = solver.export_basis().unwrap();
+    let potential_bundle = PotentialBundle::from_graph(&graph);
+
+    // a different topology
+    let other = Instance::random(&mut rng, 30, 100).graph();
+    assert_eq!(solver.import_basis(&other, &bundle), Err(BasisError::TopologyMismatch));
+    assert_eq!(SuccessiveShortestPath::default().import_potentials(&other, &potential_bundle), Err(BasisError::TopologyMismatch));
+    let mut one_more_edge = instance.graph();
+    one_more_edge.add_directed_edge(0, 1, 0, 1, 1).unwrap();
+    assert_eq!(solver.import_basis(&one_more_edge, &bundle), Err(BasisError::TopologyMismatch));
+
+    // corrupted
+    let mut corrupted = bundle.clone();
+    corrupted.potentials[5] += 1;
+    assert_eq!(solver.import_basis(&graph, &corrupted), Err(BasisError::Corrupted));
+    let mut corrupted = potential_bundle.clone();
+    corrupted.potentials[5] += 1;
+    assert_eq!(CostScalingPushRelabel::default().import_potentials(&graph, &corrupted), Err(BasisError::Corrupted));
+    let mut corrupted = bundle.clone();
+    corrupted.checksum ^= 1;
+    assert_eq!(solver.import_basis(&graph, &corrupted), Err(BasisError::Corrupted));
+
+    // not a spanning tree
+    let mut corrupted = bundle.clone();
+    corrupted.tree_edge_ids.pop();
+    assert_eq!(solver.import_basis(&graph, &corrupted), Err(BasisError::InvalidBasis));
+    let mut corrupted = bundle.clone();
+    corrupted.tree_edge_ids[0] = graph.num_edges();
+    assert_eq!(solver.import_basis(&graph, &corrupted), Err(BasisError::InvalidBasis));
+    let mut corrupted = bundle.clone();
+    let tree_edge_id = corrupted.tree_edge_ids[0];
+    corrupted.upper_edge_ids.push(tree_edge_id);
+    assert_eq!(solver.import_basis(&graph, &corrupted), Err(BasisError::InvalidBasis));
+    let mut corrupted = bundle.clone();
+    let u = corrupted.artificial_tree_nodes.pop().unwrap_or(0);
+    corrupted.artificial_tree_nodes.push(u);
+    corrupted.artificial_tree_nodes.push(u);
+    corrupted.tree_edge_ids.pop();
+    assert_eq!(solver.import_basis(&graph, &corrupted), Err(BasisError::InvalidBasis));
+
+    // a rejected bundle leaves the solver as it was
+    let mut graph = instance.graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    assert!(solver.import_basis(&graph, &corrupted).is_err());
+    assert_eq!(solver.solve_with_basis(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+    assert!(!solver.is_warm_started());
+    assert_eq!(graph.minimum_cost(), cold_solve(&instance).1);
+}
+
+#[test]
+fn import_potentials() {
+    let mut rng = XorShift::new(7681);
+    for _ in 0..20 {
+        let instance = Instance::random(&mut rng, 50, 200);
+        let (instance, perturbed) = perturb_costs(&mut rng, &instance);
+        let mut graph = instance.graph();
+        assert_eq!(PrimalNetworkSimplex::default().solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph), Status::Optimal);
+        let bundle = PotentialBundle::from_graph(&graph);
+        let (_, objective, _) = cold_solve(&perturbed);
+
+        let mut graph = perturbed.graph();
+        let mut solver = SuccessiveShortestPath::default();
+        assert_eq!(solver.import_potentials(&graph, &bundle), Ok(()));
+        assert_eq!(solver.solve(&mut graph), Status::Optimal);
+        assert!(solver.are_potentials_preserved());
+        assert_eq!(graph.minimum_cost(), objective);
+        assert!(satisfy_optimality_conditions(&graph));
+
+        let mut graph = perturbed.graph();
+        let num_cold_phases = CostScalingPushRelabel::default().solve_with_solution(&mut graph).unwrap().iterations();
+        let mut graph = perturbed.graph();
+        let mut solver = CostScalingPushRelabel::default();
+        assert_eq!(solver.import_potentials(&graph, &bundle), Ok(()));
+        let solution = solver.solve_with_solution(&mut graph).unwrap();
+        assert_eq!(solution.objective(), objective);
+        assert!(solution.iterations() < num_cold_phases, "{} {}", solution.iterations(), num_cold_phases);
+    }
+}
@@ -0,0 +1,11 @@
+// cargo build --release --target wasm32-unknown-unknown && node run.mjs
+import { readFile } from "node:fs/promises";
+import assert from "node:assert/strict";
+
+const path = new URL("./target/wasm32-unknown-unknown/release/network_algorithms_wasm_test.wasm", import.meta.url);
+const { instance } = await WebAssembly.instantiate(await readFile(path), { env: { host_now_millis: () => Date.now() } });
+
+assert.equal(instance.exports.dinic_sample(), 3n);
+assert.equal(instance.exports.primal_network_simplex_sample(), 6n);
+assert.equal(instance.exports.primal_network_simplex_time_limit(), 1);
+console.log("ok");
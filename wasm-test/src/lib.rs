@@ -0,0 +1,87 @@
+use network_algorithms::clock::{Clock, TimeLimit};
+use network_algorithms::maximum_flow::dinic::Dinic;
+use network_algorithms::maximum_flow::{graph, status};
+use network_algorithms::minimum_cost_flow::graph::Graph;
+use network_algorithms::minimum_cost_flow::network_simplex_pivot_rules::{BlockSearchPivotRule, PivotRule};
+use network_algorithms::minimum_cost_flow::primal_network_simplex::PrimalNetworkSimplex;
+use network_algorithms::minimum_cost_flow::status::Status;
+use std::cell::Cell;
+
+extern "C" {
+    // provided by the host (e.g. Date.now() in javascript)
+    fn host_now_millis() -> f64;
+}
+
+struct HostClock;
+
+impl Clock for HostClock {
+    fn now_millis(&self) -> u64 {
+        unsafe { host_now_millis() as u64 }
+    }
+}
+
+// advances by one millisecond on every call
+#[derive(Default)]
+struct StepClock {
+    now: Cell<u64>,
+}
+
+impl Clock for StepClock {
+    fn now_millis(&self) -> u64 {
+        self.now.set(self.now.get() + 1);
+        self.now.get()
+    }
+}
+
+fn minimum_cost_flow_graph() -> Graph<i64> {
+    let mut graph = Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 0, 2, 1).unwrap();
+    graph.add_directed_edge(0, 2, 0, 1, 2).unwrap();
+    graph.add_directed_edge(1, 2, 0, 1, 1).unwrap();
+    graph.add_directed_edge(1, 3, 0, 1, 3).unwrap();
+    graph.add_directed_edge(2, 3, 0, 2, 1).unwrap();
+    graph.add_supply(0, 2);
+    graph.add_demand(3, 2);
+    graph
+}
+
+// expected: 3
+#[no_mangle]
+pub extern "C" fn dinic_sample() -> i64 {
+    let mut graph = graph::Graph::default();
+    graph.add_nodes(4);
+    graph.add_directed_edge(0, 1, 2).unwrap();
+    graph.add_directed_edge(0, 2, 1).unwrap();
+    graph.add_directed_edge(1, 2, 1).unwrap();
+    graph.add_directed_edge(1, 3, 1).unwrap();
+    graph.add_directed_edge(2, 3, 2).unwrap();
+
+    let mut solver = Dinic::default();
+    solver.set_time_limit(TimeLimit::new(1000, Box::new(HostClock)));
+    match solver.solve(0, 3, &mut graph) {
+        status::Status::Optimal => graph.maximum_flow(0),
+        _ => -1,
+    }
+}
+
+// expected: 6
+#[no_mangle]
+pub extern "C" fn primal_network_simplex_sample() -> i64 {
+    let mut graph = minimum_cost_flow_graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_time_limit(TimeLimit::new(1000, Box::new(HostClock)));
+    match solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph) {
+        Status::Optimal => graph.minimum_cost(),
+        _ => -1,
+    }
+}
+
+// expected: 1
+#[no_mangle]
+pub extern "C" fn primal_network_simplex_time_limit() -> i32 {
+    let mut graph = minimum_cost_flow_graph();
+    let mut solver = PrimalNetworkSimplex::default();
+    solver.set_time_limit(TimeLimit::new(0, Box::new(StepClock::default())));
+    (solver.solve(&mut BlockSearchPivotRule::new(graph.num_edges()), &mut graph) == Status::TimeLimit) as i32
+}